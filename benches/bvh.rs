@@ -0,0 +1,64 @@
+//! BVH construction and queries on the boxes of the `main.rs` benchmarks.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use session_rust::testing::random_boxes;
+use session_rust::{Point, Scalar, Vector, BVH};
+
+const WORLD_SIZE: Scalar = 100.0;
+
+/// Linear BVH from Morton codes against incremental insertion by surface area.
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bvh_build");
+    for count in [1_000, 10_000, 100_000] {
+        let boxes = random_boxes(42, count, WORLD_SIZE);
+        group.bench_with_input(BenchmarkId::new("lbvh", count), &boxes, |b, boxes| {
+            b.iter(|| BVH::from_boxes(black_box(boxes), WORLD_SIZE))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("lbvh_parallel", count),
+            &boxes,
+            |b, boxes| b.iter(|| BVH::from_boxes_parallel(black_box(boxes), WORLD_SIZE)),
+        );
+        if count <= 10_000 {
+            group.bench_with_input(BenchmarkId::new("sah_insert", count), &boxes, |b, boxes| {
+                b.iter(|| {
+                    let mut bvh = BVH::new();
+                    for (id, bbox) in boxes.iter().enumerate() {
+                        bvh.insert(bbox, id);
+                    }
+                    bvh
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bvh_collisions");
+    for count in [1_000, 10_000] {
+        let boxes = random_boxes(42, count, WORLD_SIZE);
+        let bvh = BVH::from_boxes(&boxes, WORLD_SIZE);
+        group.bench_with_input(BenchmarkId::new("serial", count), &boxes, |b, boxes| {
+            b.iter(|| bvh.check_all_collisions(black_box(boxes)))
+        });
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(BenchmarkId::new("parallel", count), &count, |b, &count| {
+            b.iter(|| bvh.check_all_collisions_parallel(black_box(count)))
+        });
+    }
+    group.finish();
+}
+
+fn ray_cast(c: &mut Criterion) {
+    let boxes = random_boxes(42, 10_000, WORLD_SIZE);
+    let bvh = BVH::from_boxes(&boxes, WORLD_SIZE);
+    let origin = Point::new(-60.0, 0.5, 0.5);
+    let direction = Vector::new(1.0, 0.1, 0.05);
+    c.bench_function("bvh_ray_cast_entries_10000", |b| {
+        b.iter(|| bvh.ray_cast_entries(black_box(&origin), black_box(&direction)))
+    });
+}
+
+criterion_group!(benches, build, collisions, ray_cast);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+//! Ray casts against meshes and OBJ parsing.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use session_rust::{read_obj, write_obj, Line, Mesh, Point, Scalar};
+
+/// Wavy height field of `n` by `n` quads.
+fn terrain(n: usize) -> Mesh {
+    let height = |x: Scalar, y: Scalar| (x * 0.3).sin() * (y * 0.2).cos();
+    let p = |x: Scalar, y: Scalar| Point::new(x, y, height(x, y));
+    let polygons = (0..n)
+        .flat_map(|i| {
+            (0..n).map(move |j| {
+                let (x, y) = (i as Scalar, j as Scalar);
+                vec![p(x, y), p(x + 1.0, y), p(x + 1.0, y + 1.0), p(x, y + 1.0)]
+            })
+        })
+        .collect();
+    Mesh::from_polygons(polygons, None)
+}
+
+/// Vertical rays on a grid over the mesh, all hitting it.
+fn rays(n: usize, count: usize) -> Vec<Line> {
+    (0..count)
+        .map(|k| {
+            let x = (k * 7 % n) as Scalar + 0.37;
+            let y = (k * 13 % n) as Scalar + 0.61;
+            Line::new(x, y, 10.0, x, y, -10.0)
+        })
+        .collect()
+}
+
+fn ray_mesh(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mesh_ray_cast");
+    for n in [32, 128] {
+        let mesh = terrain(n);
+        let rays = rays(n, 1_000);
+        group.bench_with_input(BenchmarkId::new("bvh", n * n * 2), &rays, |b, rays| {
+            b.iter(|| {
+                rays.iter()
+                    .filter(|ray| mesh.ray_cast_bvh(black_box(ray), 1e-9).is_some())
+                    .count()
+            })
+        });
+    }
+    group.finish();
+
+    let mut mesh = terrain(128);
+    c.bench_function("mesh_triangle_bvh_build_32768", |b| {
+        b.iter(|| {
+            mesh.invalidate_triangle_bvh();
+            mesh.ray_cast_bvh(&Line::new(5.5, 5.5, 10.0, 5.5, 5.5, -10.0), 1e-9)
+        })
+    });
+}
+
+fn obj(c: &mut Criterion) {
+    let path = std::env::temp_dir().join(format!("session_rust_bench_{}.obj", std::process::id()));
+    let path = path.to_str().unwrap().to_string();
+    write_obj(&terrain(128), &path).unwrap();
+    c.bench_function("obj_read_32768_triangles", |b| {
+        b.iter(|| read_obj(black_box(&path)).unwrap())
+    });
+    let _ = std::fs::remove_file(&path);
+}
+
+criterion_group!(benches, ray_mesh, obj);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+//! Session level queries on scenes of many objects.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use session_rust::testing::{random_boxes, Pcg32};
+use session_rust::{Point, Scalar, Session, TreeNode, Vector, Xform};
+
+const WORLD_SIZE: Scalar = 100.0;
+
+/// Points under groups of 100, each group and point with its own xform.
+fn grouped_points(count: usize) -> Session {
+    let mut session = Session::new("bench");
+    let mut rng = Pcg32::new(42);
+    let mut group = TreeNode::new("group");
+    for i in 0..count {
+        if i % 100 == 0 {
+            group = TreeNode::new(&format!("group_{i}"));
+            group.set_xform(Some(Xform::translation(0.0, 0.0, (i / 100) as Scalar)));
+            session.add(&group, None);
+        }
+        let half = WORLD_SIZE * 0.5;
+        let mut point = Point::new(
+            rng.range(-half, half),
+            rng.range(-half, half),
+            rng.range(-half, half),
+        );
+        point.xform = Xform::translation(1.0, 0.0, 0.0);
+        let node = session.add_point(point);
+        session.add(&node, &group);
+    }
+    session
+}
+
+fn get_geometry(c: &mut Criterion) {
+    let mut group = c.benchmark_group("session_get_geometry");
+    group.sample_size(20);
+    for count in [1_000, 50_000] {
+        let session = grouped_points(count);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &session,
+            |b, session| b.iter(|| black_box(session.get_geometry())),
+        );
+    }
+    group.finish();
+}
+
+fn ray_cast(c: &mut Criterion) {
+    let mut session = grouped_points(10_000);
+    let origin = Point::new(0.0, 0.0, 0.0);
+    let direction = Vector::new(1.0, 0.0, 0.0);
+    session.ray_cast(&origin, &direction, 1.0);
+    c.bench_function("session_ray_cast_10000_points", |b| {
+        b.iter(|| session.ray_cast(black_box(&origin), black_box(&direction), 1.0))
+    });
+}
+
+/// Boxes scattered with the same seed as the `bvh` collision benchmarks.
+fn box_scene(count: usize) -> Session {
+    let mut session = Session::new("bench");
+    for bbox in random_boxes(42, count, WORLD_SIZE) {
+        session.add_bbox(bbox);
+    }
+    session
+}
+
+fn collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("session_collisions");
+    group.sample_size(10);
+    let mut session = box_scene(10_000);
+    group.bench_function("10000_boxes", |b| b.iter(|| session.get_collisions()));
+
+    // Sessions are not Send, so the scene is built and timed on a thread of each pool
+    #[cfg(feature = "parallel")]
+    {
+        let mut thread_counts = vec![1, 2, 4, rayon::current_num_threads()];
+        thread_counts.sort_unstable();
+        thread_counts.dedup();
+        for threads in thread_counts {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            group.bench_function(BenchmarkId::new("10000_boxes_threads", threads), |b| {
+                b.iter_custom(|iters| {
+                    pool.install(|| {
+                        let mut session = box_scene(10_000);
+                        // The first call adds the collision edges to the graph
+                        session.get_collisions();
+                        let start = std::time::Instant::now();
+                        for _ in 0..iters {
+                            black_box(session.get_collisions());
+                        }
+                        start.elapsed()
+                    })
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, get_geometry, ray_cast, collisions);
+criterion_main!(benches);
@@ -0,0 +1,19 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    // Regenerate the C header for the `capi` module
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))
+            .expect("cbindgen.toml is valid");
+        cbindgen::Builder::new()
+            .with_src(format!("{crate_dir}/src/capi.rs"))
+            .with_config(config)
+            .generate()
+            .expect("C header generation failed")
+            .write_to_file(format!("{crate_dir}/include/session_rust.h"));
+    }
+}
@@ -1,11 +1,11 @@
 use serde::Deserialize;
-use session_rust::{BoundingBox, Point, Vector, BVH};
+use session_rust::{BoundingBox, Point, Scalar, Vector, BVH};
 use std::fs;
 
 #[derive(Deserialize)]
 struct BoxData {
-    center: [f64; 3],
-    half_size: [f64; 3],
+    center: [Scalar; 3],
+    half_size: [Scalar; 3],
 }
 
 fn main() {
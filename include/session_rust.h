@@ -0,0 +1,186 @@
+#ifndef SESSION_RUST_H
+#define SESSION_RUST_H
+
+/* Generated by cbindgen from src/capi.rs with `cargo build --features ffi`, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/* Opaque handle created by session_new, session_from_json and session_jsonload. */
+typedef struct Session Session;
+
+/**
+ * Length of a GUID including the terminating NUL.
+ */
+#define SESSION_GUID_LENGTH 37
+
+/**
+ * A NUL-terminated GUID.
+ */
+typedef struct SessionGuid {
+  char value[SESSION_GUID_LENGTH];
+} SessionGuid;
+
+/**
+ * An object hit by `session_ray_cast`.
+ */
+typedef struct SessionRayHit {
+  struct SessionGuid guid;
+  double x;
+  double y;
+  double z;
+  double distance;
+} SessionRayHit;
+
+/**
+ * A pair of objects reported by `session_get_collisions`.
+ */
+typedef struct SessionCollision {
+  struct SessionGuid first;
+  struct SessionGuid second;
+} SessionCollision;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * The message of the last failed call on this thread, or null.
+ *
+ * The pointer stays valid until the next failing call on the same thread.
+ */
+const char *session_last_error(void);
+
+/**
+ * Create an empty session.
+ *
+ * # Safety
+ * `name` must be null or a NUL-terminated string.
+ */
+Session *session_new(const char *name);
+
+/**
+ * Release a session created by this library. Null is ignored.
+ *
+ * # Safety
+ * `session` must be null or a handle that was not freed before.
+ */
+void session_free(Session *session);
+
+/**
+ * Load a session from a JSON file, or return null on failure.
+ *
+ * # Safety
+ * `filepath` must be null or a NUL-terminated string.
+ */
+Session *session_from_json(const char *filepath);
+
+/**
+ * Load a session from a JSON string, or return null on failure.
+ *
+ * # Safety
+ * `json` must be null or a NUL-terminated string.
+ */
+Session *session_jsonload(const char *json);
+
+/**
+ * Serialize a session to a JSON string, to be released with `session_string_free`.
+ *
+ * # Safety
+ * `session` must be null or a live handle.
+ */
+char *session_jsondump(Session *session);
+
+/**
+ * Save a session to a JSON file.
+ *
+ * # Safety
+ * `session` must be null or a live handle, `filepath` null or a NUL-terminated string.
+ */
+bool session_to_json(Session *session, const char *filepath);
+
+/**
+ * Release a string returned by `session_jsondump`. Null is ignored.
+ *
+ * # Safety
+ * `value` must be null or a string from this library that was not freed before.
+ */
+void session_string_free(char *value);
+
+/**
+ * Add a point and write its GUID to `out_guid` unless it is null.
+ *
+ * # Safety
+ * `session` must be null or a live handle, `out_guid` null or writable.
+ */
+bool session_add_point(Session *session,
+                       double x,
+                       double y,
+                       double z,
+                       struct SessionGuid *out_guid);
+
+/**
+ * Add a line from `x0 y0 z0 x1 y1 z1` and write its GUID to `out_guid` unless it is null.
+ *
+ * # Safety
+ * `session` must be null or a live handle, `coordinates` null or 6 readable
+ * doubles, `out_guid` null or writable.
+ */
+bool session_add_line(Session *session, const double *coordinates, struct SessionGuid *out_guid);
+
+/**
+ * Add a mesh from flat arrays and write its GUID to `out_guid` unless it is null.
+ *
+ * `vertices` holds `x y z` per vertex. Face `i` has `face_sizes[i]` vertices
+ * whose indices follow each other in `face_indices`.
+ *
+ * # Safety
+ * `session` must be null or a live handle, `vertices` null or
+ * `3 * vertex_count` readable doubles, `face_sizes` null or `face_count`
+ * readable values, `face_indices` null or as many values as the sum of the
+ * face sizes, and `out_guid` null or writable.
+ */
+bool session_add_mesh(Session *session,
+                      const double *vertices,
+                      size_t vertex_count,
+                      const uint32_t *face_sizes,
+                      size_t face_count,
+                      const uint32_t *face_indices,
+                      struct SessionGuid *out_guid);
+
+/**
+ * Cast a ray and write up to `capacity` hits, closest first, to `hits`.
+ *
+ * # Returns
+ * The total number of hits, which may exceed `capacity`.
+ *
+ * # Safety
+ * `session` must be null or a live handle, `origin` and `direction` null or
+ * 3 readable doubles, and `hits` null or `capacity` writable hits.
+ */
+size_t session_ray_cast(Session *session,
+                        const double *origin,
+                        const double *direction,
+                        double tolerance,
+                        struct SessionRayHit *hits,
+                        size_t capacity);
+
+/**
+ * Write up to `capacity` colliding object pairs to `pairs`.
+ *
+ * # Returns
+ * The total number of pairs, which may exceed `capacity`.
+ *
+ * # Safety
+ * `session` must be null or a live handle and `pairs` null or `capacity` writable pairs.
+ */
+size_t session_get_collisions(Session *session, struct SessionCollision *pairs, size_t capacity);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SESSION_RUST_H */
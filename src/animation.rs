@@ -0,0 +1,74 @@
+//! Keyframed motion of Session objects.
+//!
+//! A track stores absolute poses at points in time and interpolates between
+//! them with `Xform::lerp`. Tracks are stored per object in the Session JSON,
+//! so motion studies written by one implementation replay in the others.
+
+use crate::{Scalar, Xform};
+use serde::{Deserialize, Serialize};
+
+/// Poses of one object over time, see `Session::set_animation`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnimationTrack {
+    /// `(time, pose)` pairs sorted by time, without duplicate times
+    pub keyframes: Vec<(f64, Xform)>,
+}
+
+impl AnimationTrack {
+    /// Creates an empty track.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pose, replacing the one at the same time.
+    ///
+    /// # Arguments
+    /// * `time` - Time of the pose, in the units of the motion study
+    /// * `xform` - Absolute xform of the object at `time`
+    pub fn add_keyframe(&mut self, time: f64, xform: Xform) {
+        match self
+            .keyframes
+            .binary_search_by(|(other, _)| other.total_cmp(&time))
+        {
+            Ok(index) => self.keyframes[index].1 = xform,
+            Err(index) => self.keyframes.insert(index, (time, xform)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// Times of the first and last keyframe, None for an empty track.
+    pub fn time_range(&self) -> Option<(f64, f64)> {
+        Some((self.keyframes.first()?.0, self.keyframes.last()?.0))
+    }
+
+    /// The pose at a time.
+    ///
+    /// Times before the first or after the last keyframe hold that keyframe,
+    /// times in between interpolate the neighbouring keyframes with `Xform::lerp`.
+    ///
+    /// # Returns
+    /// None for an empty track.
+    pub fn evaluate(&self, time: f64) -> Option<Xform> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+        if time <= first.0 {
+            return Some(first.1.clone());
+        }
+        if time >= last.0 {
+            return Some(last.1.clone());
+        }
+        let next = self.keyframes.partition_point(|(other, _)| *other <= time);
+        let ((t0, a), (t1, b)) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        Some(Xform::lerp(a, b, ((time - t0) / (t1 - t0)) as Scalar))
+    }
+}
+
+#[cfg(test)]
+#[path = "animation_test.rs"]
+mod animation_test;
@@ -0,0 +1,37 @@
+use super::*;
+use crate::Point;
+
+#[test]
+fn test_animation_track_keyframes() {
+    let mut track = AnimationTrack::new();
+    assert!(track.is_empty());
+    assert_eq!(track.time_range(), None);
+    assert!(track.evaluate(0.0).is_none());
+
+    track.add_keyframe(2.0, Xform::translation(2.0, 0.0, 0.0));
+    track.add_keyframe(0.0, Xform::identity());
+    track.add_keyframe(2.0, Xform::translation(4.0, 0.0, 0.0));
+    assert_eq!(track.len(), 2);
+    assert_eq!(track.time_range(), Some((0.0, 2.0)));
+
+    let at = |time: f64| {
+        track
+            .evaluate(time)
+            .unwrap()
+            .transformed_point(&Point::new(0.0, 0.0, 0.0))
+            .x()
+    };
+    assert_eq!(at(-1.0), 0.0);
+    assert!((at(0.5) - 1.0).abs() < 1e-5);
+    assert_eq!(at(3.0), 4.0);
+}
+
+#[test]
+fn test_animation_track_json() {
+    let mut track = AnimationTrack::new();
+    track.add_keyframe(0.0, Xform::identity());
+    track.add_keyframe(1.5, Xform::rotation_z(1.0));
+    let json = serde_json::to_string(&track).unwrap();
+    let loaded: AnimationTrack = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, track);
+}
@@ -1,6 +1,6 @@
-use crate::{Line, Mesh, Point, Vector, Xform};
+use crate::error::SessionError;
+use crate::{Line, Mesh, Point, Scalar, Vector, Xform};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 /// An arrow geometry defined by a line and radius, the head is uniformly scaled.
 ///
@@ -11,7 +11,7 @@ use uuid::Uuid;
 pub struct Arrow {
     pub line: Line,
     pub mesh: Mesh,
-    pub radius: f64,
+    pub radius: Scalar,
     pub guid: String,
     pub name: String,
     #[serde(default = "Xform::identity")]
@@ -29,19 +29,19 @@ impl Arrow {
     /// # Returns
     ///
     /// A new `Arrow` with a cylinder body and cone head mesh
-    pub fn new(line: Line, radius: f64) -> Self {
+    pub fn new(line: Line, radius: Scalar) -> Self {
         let mesh = Self::create_arrow_mesh(&line, radius);
         Self {
             line,
             mesh,
             radius,
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_arrow".to_string(),
             xform: Xform::identity(),
         }
     }
 
-    fn create_arrow_mesh(line: &Line, radius: f64) -> Mesh {
+    fn create_arrow_mesh(line: &Line, radius: Scalar) -> Mesh {
         let start = line.start();
         let line_vec = line.to_vector();
         let length = line.length();
@@ -220,7 +220,7 @@ impl Arrow {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Serializes the Arrow to a JSON string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let data = serde_json::json!({
             "type": "Arrow",
             "guid": self.guid,
@@ -233,21 +233,21 @@ impl Arrow {
     }
 
     /// Deserializes an Arrow from a JSON string.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
     /// Serializes the Arrow to a JSON file.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
     }
 
     /// Deserializes an Arrow from a JSON file.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
     }
 }
 
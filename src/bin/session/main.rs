@@ -0,0 +1,243 @@
+//! Command line tool for inspecting and converting Session files.
+//!
+//! Files ending in `.json` are read and written as JSON, other files as MessagePack.
+//!
+//! ```text
+//! session info scene.json
+//! session convert scene.json scene.msgpack
+//! session merge a.json b.json --output merged.json
+//! session raycast scene.json --origin 0,0,10 --direction 0,0,-1
+//! session collide scene.json --narrow-phase
+//! ```
+
+use clap::{Parser, Subcommand};
+use session_rust::{
+    CollisionOptions, MergeStrategy, Point, RayCastMode, RayCastOptions, Scalar, Session, Vector,
+};
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+
+type CliResult = Result<String, Box<dyn Error>>;
+
+#[derive(Debug, Parser)]
+#[command(name = "session", version, about = "Inspect and convert Session files")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print object counts, bounds and validation issues
+    Info { file: String },
+    /// Convert between JSON and MessagePack
+    Convert { input: String, output: String },
+    /// Add the objects and edges of OTHER missing from BASE and write the result
+    Merge {
+        base: String,
+        other: String,
+        #[arg(short, long)]
+        output: String,
+    },
+    /// Cast a ray and print the hits, nearest first
+    Raycast {
+        file: String,
+        /// Ray origin as x,y,z
+        #[arg(long, value_parser = parse_vector, allow_hyphen_values = true)]
+        origin: [Scalar; 3],
+        /// Ray direction as x,y,z
+        #[arg(long, value_parser = parse_vector, allow_hyphen_values = true)]
+        direction: [Scalar; 3],
+        /// Distance within which hits count as equally close
+        #[arg(long, default_value_t = 1e-3)]
+        tolerance: Scalar,
+        /// Report every hit along the ray instead of the nearest
+        #[arg(long)]
+        all: bool,
+    },
+    /// Print the pairs of objects whose bounding boxes overlap
+    Collide {
+        file: String,
+        /// Confirm overlaps with exact geometry tests
+        #[arg(long)]
+        narrow_phase: bool,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(output) => print!("{output}"),
+        Err(error) => {
+            eprintln!("error: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(command: Command) -> CliResult {
+    match command {
+        Command::Info { file } => info(&file),
+        Command::Convert { input, output } => {
+            save(&load(&input)?, &output)?;
+            Ok(format!("Converted {input} to {output}\n"))
+        }
+        Command::Merge {
+            base,
+            other,
+            output,
+        } => merge(&base, &other, &output),
+        Command::Raycast {
+            file,
+            origin,
+            direction,
+            tolerance,
+            all,
+        } => raycast(&file, origin, direction, tolerance, all),
+        Command::Collide { file, narrow_phase } => collide(&file, narrow_phase),
+    }
+}
+
+fn is_json(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|e| e == "json")
+}
+
+fn load(path: &str) -> Result<Session, Box<dyn Error>> {
+    let session = if is_json(path) {
+        Session::from_json(path)?
+    } else {
+        Session::from_binary(path)?
+    };
+    Ok(session)
+}
+
+fn save(session: &Session, path: &str) -> Result<(), Box<dyn Error>> {
+    if is_json(path) {
+        session.to_json(path)?;
+    } else {
+        session.to_binary(path)?;
+    }
+    Ok(())
+}
+
+fn parse_vector(text: &str) -> Result<[Scalar; 3], String> {
+    let values: Vec<Scalar> = text
+        .split(',')
+        .map(|value| value.trim().parse::<Scalar>())
+        .collect::<Result<_, _>>()
+        .map_err(|error| format!("{text}: {error}"))?;
+    <[Scalar; 3]>::try_from(values).map_err(|_| format!("{text}: expected x,y,z"))
+}
+
+fn info(path: &str) -> CliResult {
+    let session = load(path)?;
+    let stats = session.stats();
+    let mut out = String::new();
+    writeln!(out, "name: {}", session.name)?;
+    writeln!(out, "guid: {}", session.guid)?;
+    writeln!(out, "objects: {}", stats.object_count)?;
+    for (kind, count) in &stats.object_counts {
+        writeln!(out, "  {kind}: {count}")?;
+    }
+    writeln!(out, "vertices: {}", stats.vertex_count)?;
+    writeln!(out, "faces: {}", stats.face_count)?;
+    writeln!(
+        out,
+        "graph: {} vertices, {} edges",
+        stats.graph_vertex_count, stats.graph_edge_count
+    )?;
+    writeln!(out, "tree: {} nodes", stats.tree_node_count)?;
+    if let Some(bbox) = session.bounding_box() {
+        let (min, max) = (bbox.min_point(), bbox.max_point());
+        writeln!(
+            out,
+            "bounds: ({}, {}, {}) - ({}, {}, {})",
+            min.x(),
+            min.y(),
+            min.z(),
+            max.x(),
+            max.y(),
+            max.z()
+        )?;
+    }
+    let issues = session.validate();
+    if issues.is_empty() {
+        writeln!(out, "valid")?;
+    } else {
+        writeln!(out, "{} issues:", issues.len())?;
+        for issue in &issues {
+            writeln!(out, "  {issue}")?;
+        }
+    }
+    Ok(out)
+}
+
+fn merge(base: &str, other: &str, output: &str) -> CliResult {
+    let mut session = load(base)?;
+    let before = session.stats().object_count;
+    let delta = session.diff(&load(other)?);
+    session.merge(&delta, MergeStrategy::Ours);
+    save(&session, output)?;
+    let added = session.stats().object_count - before;
+    Ok(format!(
+        "Merged {other} into {base}: {added} objects added, wrote {output}\n"
+    ))
+}
+
+fn raycast(
+    path: &str,
+    origin: [Scalar; 3],
+    direction: [Scalar; 3],
+    tolerance: Scalar,
+    all: bool,
+) -> CliResult {
+    let mut session = load(path)?;
+    let options = RayCastOptions {
+        mode: if all {
+            RayCastMode::All
+        } else {
+            RayCastMode::Nearest
+        },
+        ..Default::default()
+    };
+    let hits = session.ray_cast_with(
+        &Point::new(origin[0], origin[1], origin[2]),
+        &Vector::new(direction[0], direction[1], direction[2]),
+        tolerance,
+        &options,
+    );
+    let mut out = format!("{} hits\n", hits.len());
+    for hit in &hits {
+        let name = session.get_object(&hit.guid).map_or("", |g| g.name());
+        writeln!(
+            out,
+            "  {} {} distance {} at ({}, {}, {})",
+            hit.guid,
+            name,
+            hit.distance,
+            hit.point.x(),
+            hit.point.y(),
+            hit.point.z()
+        )?;
+    }
+    Ok(out)
+}
+
+fn collide(path: &str, narrow_phase: bool) -> CliResult {
+    let mut session = load(path)?;
+    let options = CollisionOptions {
+        narrow_phase,
+        ..Default::default()
+    };
+    let pairs = session.get_collisions_with(&options);
+    let mut out = format!("{} collisions\n", pairs.len());
+    for (a, b) in &pairs {
+        writeln!(out, "  {a} {b}")?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+#[path = "main_test.rs"]
+mod main_test;
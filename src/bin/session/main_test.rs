@@ -0,0 +1,134 @@
+use super::*;
+use session_rust::{Line, Mesh};
+
+fn temp_path(ext: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("session_cli_{}.{ext}", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Closed unit cube with its minimum corner at (x, 0, 0).
+fn cube(x: Scalar) -> Mesh {
+    let p = |i: usize| {
+        let [dx, dy, dz] = [i & 1, (i >> 1) & 1, (i >> 2) & 1].map(|bit| bit as Scalar);
+        Point::new(x + dx, dy, dz)
+    };
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+    Mesh::from_polygons(faces.iter().map(|f| f.map(p).to_vec()).collect(), None)
+}
+
+fn scene() -> Session {
+    let mut scene = Session::new("cli");
+    scene.add_mesh(cube(0.0));
+    scene.add_mesh(cube(0.5));
+    scene.add_line(Line::new(10.0, 0.0, 0.0, 10.0, 0.0, 1.0));
+    scene
+}
+
+fn parse(args: &[&str]) -> Command {
+    Cli::try_parse_from(std::iter::once("session").chain(args.iter().copied()))
+        .unwrap()
+        .command
+}
+
+#[test]
+fn test_parse_vector() {
+    assert_eq!(parse_vector("1, -2,3.5"), Ok([1.0, -2.0, 3.5]));
+    assert!(parse_vector("1,2").is_err());
+    assert!(parse_vector("1,x,3").is_err());
+
+    // Negative components are values, not flags
+    match parse(&[
+        "raycast",
+        "a.json",
+        "--origin",
+        "0,0,5",
+        "--direction",
+        "-1,0,0",
+    ]) {
+        Command::Raycast { direction, all, .. } => {
+            assert_eq!(direction, [-1.0, 0.0, 0.0]);
+            assert!(!all);
+        }
+        command => panic!("unexpected {command:?}"),
+    }
+}
+
+#[test]
+fn test_info_and_convert() {
+    let json = temp_path("json");
+    let binary = temp_path("msgpack");
+    scene().to_json(&json).unwrap();
+
+    let text = run(parse(&["convert", &json, &binary])).unwrap();
+    assert!(text.starts_with("Converted"));
+    assert_eq!(
+        Session::from_binary(&binary)
+            .unwrap()
+            .objects_of::<Mesh>()
+            .len(),
+        2
+    );
+
+    let text = run(parse(&["info", &binary])).unwrap();
+    assert!(text.contains("name: cli"));
+    assert!(text.contains("objects: 3"));
+    assert!(text.contains("  Mesh: 2"));
+    // Object boxes are inflated by the approximation tolerance
+    assert!(text.contains("bounds: (-0.000999"));
+    assert!(text.contains(") - (10.001, 1.001, 1.001)"));
+    assert!(text.ends_with("valid\n"));
+
+    assert!(run(parse(&["info", &temp_path("json")])).is_err());
+    for path in [json, binary] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn test_merge() {
+    let (base, other, output) = (temp_path("json"), temp_path("json"), temp_path("json"));
+    let mut first = Session::new("first");
+    first.add_line(Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+    first.to_json(&base).unwrap();
+    scene().to_json(&other).unwrap();
+
+    let text = run(parse(&["merge", &base, &other, "-o", &output])).unwrap();
+    assert!(text.contains("3 objects added"));
+    let merged = Session::from_json(&output).unwrap();
+    assert_eq!(merged.name, "first");
+    assert_eq!(merged.objects_of::<Line>().len(), 2);
+    assert_eq!(merged.objects_of::<Mesh>().len(), 2);
+    for path in [base, other, output] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn test_raycast_and_collide() {
+    let path = temp_path("json");
+    scene().to_json(&path).unwrap();
+    let ray = ["--origin", "0.25,0.5,5", "--direction", "0,0,-1"];
+
+    let args = [&["raycast", path.as_str()][..], &ray].concat();
+    let text = run(parse(&args)).unwrap();
+    assert!(text.starts_with("1 hits\n"));
+    assert!(text.contains("distance 4 at (0.25, 0.5, 1)"));
+
+    // The second box starts at x 0.5, a ray through both hits them twice each
+    let ray = ["--origin", "0.75,0.5,5", "--direction", "0,0,-1", "--all"];
+    let args = [&["raycast", path.as_str()][..], &ray].concat();
+    assert!(run(parse(&args)).unwrap().starts_with("4 hits\n"));
+
+    let text = run(parse(&["collide", &path, "--narrow-phase"])).unwrap();
+    assert!(text.starts_with("1 collisions\n"));
+    std::fs::remove_file(path).unwrap();
+}
@@ -1,6 +1,9 @@
-use crate::{Plane, Point, Vector, Xform};
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{AsVec3, Plane, Point, Scalar, Tolerance, Vec3, Vector, Xform};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename = "BoundingBox")]
@@ -30,57 +33,58 @@ impl BoundingBox {
             y_axis,
             z_axis,
             half_size,
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_boundingbox".to_string(),
             xform: Xform::identity(),
         }
     }
 
-    pub fn from_plane(plane: &Plane, dx: f64, dy: f64, dz: f64) -> Self {
+    pub fn from_plane(plane: &Plane, dx: Scalar, dy: Scalar, dz: Scalar) -> Self {
         BoundingBox {
             center: plane.origin(),
             x_axis: plane.x_axis(),
             y_axis: plane.y_axis(),
             z_axis: plane.z_axis(),
             half_size: Vector::new(dx * 0.5, dy * 0.5, dz * 0.5),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: String::new(),
             xform: Xform::identity(),
         }
     }
 
-    pub fn from_point(point: Point, inflate: f64) -> Self {
+    pub fn from_point(point: Point, inflate: Scalar) -> Self {
         BoundingBox {
             center: point,
             x_axis: Vector::new(1.0, 0.0, 0.0),
             y_axis: Vector::new(0.0, 1.0, 0.0),
             z_axis: Vector::new(0.0, 0.0, 1.0),
             half_size: Vector::new(inflate, inflate, inflate),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             xform: Xform::identity(),
             name: String::new(),
         }
     }
 
-    pub fn from_points(points: &[Point], inflate: f64) -> Self {
+    pub fn from_points<P: AsVec3>(points: &[P], inflate: Scalar) -> Self {
         if points.is_empty() {
             return BoundingBox::default();
         }
 
-        let mut min_x = f64::MAX;
-        let mut min_y = f64::MAX;
-        let mut min_z = f64::MAX;
-        let mut max_x = f64::MIN;
-        let mut max_y = f64::MIN;
-        let mut max_z = f64::MIN;
+        let mut min_x = Scalar::MAX;
+        let mut min_y = Scalar::MAX;
+        let mut min_z = Scalar::MAX;
+        let mut max_x = Scalar::MIN;
+        let mut max_y = Scalar::MIN;
+        let mut max_z = Scalar::MIN;
 
         for pt in points {
-            min_x = min_x.min(pt.x());
-            min_y = min_y.min(pt.y());
-            min_z = min_z.min(pt.z());
-            max_x = max_x.max(pt.x());
-            max_y = max_y.max(pt.y());
-            max_z = max_z.max(pt.z());
+            let pt = pt.as_vec3();
+            min_x = min_x.min(pt.x);
+            min_y = min_y.min(pt.y);
+            min_z = min_z.min(pt.z);
+            max_x = max_x.max(pt.x);
+            max_y = max_y.max(pt.y);
+            max_z = max_z.max(pt.z);
         }
 
         let center = Point::new(
@@ -100,22 +104,86 @@ impl BoundingBox {
             y_axis: Vector::new(0.0, 1.0, 0.0),
             z_axis: Vector::new(0.0, 0.0, 1.0),
             half_size,
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: String::new(),
             xform: Xform::identity(),
         }
     }
 
-    pub fn from_line(line: &crate::line::Line, inflate: f64) -> Self {
+    /// Tight oriented box around the points.
+    ///
+    /// The principal axes of the points seed the search. For each of them the
+    /// points are projected onto the perpendicular plane and the minimum-area
+    /// rectangle around their convex hull is found with rotating calipers.
+    /// The smallest of the resulting boxes is returned, which is the minimum
+    /// volume box whenever one of its axes is a principal axis.
+    ///
+    /// # Returns
+    /// The default box if there are no points.
+    pub fn fit_obb<P: AsVec3>(points: &[P]) -> Self {
+        let Some(frame) = crate::points::pca_frame(points) else {
+            return BoundingBox::default();
+        };
+        let points: Vec<Vec3> = points.iter().map(AsVec3::as_vec3).collect();
+        let axes = [frame.x_axis(), frame.y_axis(), frame.z_axis()].map(|axis| axis.as_vec3());
+
+        let candidates = (0..3).map(|i| {
+            let (normal, e1) = (axes[i], axes[(i + 1) % 3]);
+            let e2 = normal.cross(&e1);
+            let planar: Vec<[Scalar; 2]> =
+                points.iter().map(|p| [p.dot(&e1), p.dot(&e2)]).collect();
+            let (along, across) = min_area_rectangle(&convex_hull_2d(planar));
+            let x_axis = e1 * along[0] + e2 * along[1];
+            let y_axis = e1 * across[0] + e2 * across[1];
+            let frame_axes = [x_axis, y_axis, normal];
+            let (min, max) = points.iter().fold(
+                (
+                    Vec3::new(Scalar::MAX, Scalar::MAX, Scalar::MAX),
+                    Vec3::new(Scalar::MIN, Scalar::MIN, Scalar::MIN),
+                ),
+                |(min, max), p| {
+                    let [x, y, z] = frame_axes.map(|axis| p.dot(&axis));
+                    (
+                        Vec3::new(min.x.min(x), min.y.min(y), min.z.min(z)),
+                        Vec3::new(max.x.max(x), max.y.max(y), max.z.max(z)),
+                    )
+                },
+            );
+            // Flat point sets have zero volume for every frame, then the area decides
+            let size = max - min;
+            let key = (
+                size.x * size.y * size.z,
+                size.x * size.y + size.y * size.z + size.z * size.x,
+            );
+            (key, frame_axes, min, max)
+        });
+        let (_, [x_axis, y_axis, z_axis], min, max) = candidates
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(core::cmp::Ordering::Equal))
+            .unwrap_or_default();
+        let mid = (min + max) * 0.5;
+        let center = x_axis * mid.x + y_axis * mid.y + z_axis * mid.z;
+        BoundingBox {
+            center: center.into(),
+            x_axis: x_axis.into(),
+            y_axis: y_axis.into(),
+            z_axis: z_axis.into(),
+            half_size: ((max - min) * 0.5).into(),
+            guid: crate::guid::new_guid(),
+            name: String::new(),
+            xform: Xform::identity(),
+        }
+    }
+
+    pub fn from_line(line: &crate::line::Line, inflate: Scalar) -> Self {
         let points = vec![line.start(), line.end()];
         Self::from_points(&points, inflate)
     }
 
-    pub fn from_polyline(polyline: &crate::polyline::Polyline, inflate: f64) -> Self {
+    pub fn from_polyline(polyline: &crate::polyline::Polyline, inflate: Scalar) -> Self {
         Self::from_points(&polyline.points, inflate)
     }
 
-    pub fn point_at(&self, x: f64, y: f64, z: f64) -> Point {
+    pub fn point_at(&self, x: Scalar, y: Scalar, z: Scalar) -> Point {
         Point::new(
             self.center.x() + x * self.x_axis.x() + y * self.y_axis.x() + z * self.z_axis.x(),
             self.center.y() + x * self.x_axis.y() + y * self.y_axis.y() + z * self.z_axis.y(),
@@ -123,6 +191,18 @@ impl BoundingBox {
         )
     }
 
+    /// Check if the box axes coincide with the world X, Y and Z axes.
+    pub fn is_axis_aligned(&self) -> bool {
+        let aligned = |axis: &Vector, x: Scalar, y: Scalar, z: Scalar| {
+            (axis.x() - x).abs() < Tolerance::ZERO_TOLERANCE
+                && (axis.y() - y).abs() < Tolerance::ZERO_TOLERANCE
+                && (axis.z() - z).abs() < Tolerance::ZERO_TOLERANCE
+        };
+        aligned(&self.x_axis, 1.0, 0.0, 0.0)
+            && aligned(&self.y_axis, 0.0, 1.0, 0.0)
+            && aligned(&self.z_axis, 0.0, 0.0, 1.0)
+    }
+
     pub fn min_point(&self) -> Point {
         Point::new(
             self.center.x() - self.half_size.x(),
@@ -175,7 +255,7 @@ impl BoundingBox {
         ]
     }
 
-    pub fn inflate(&mut self, amount: f64) {
+    pub fn inflate(&mut self, amount: Scalar) {
         self.half_size = Vector::new(
             self.half_size.x() + amount,
             self.half_size.y() + amount,
@@ -183,6 +263,135 @@ impl BoundingBox {
         );
     }
 
+    /// A copy grown by `amount` on every side, see `inflate` for the in-place version.
+    ///
+    /// Negative amounts shrink the box, but not below zero size.
+    pub fn expand(&self, amount: Scalar) -> Self {
+        let mut result = self.clone();
+        result.half_size = Vector::new(
+            (self.half_size.x() + amount).max(0.0),
+            (self.half_size.y() + amount).max(0.0),
+            (self.half_size.z() + amount).max(0.0),
+        );
+        result
+    }
+
+    /// Volume of the box, also for axes that are scaled or not perpendicular.
+    pub fn volume(&self) -> Scalar {
+        let (x, y, z) = (
+            self.x_axis.as_vec3(),
+            self.y_axis.as_vec3(),
+            self.z_axis.as_vec3(),
+        );
+        8.0 * self.half_size.x()
+            * self.half_size.y()
+            * self.half_size.z()
+            * x.cross(&y).dot(&z).abs()
+    }
+
+    /// Check if a point is inside the box or on its boundary, within `Tolerance::ZERO_TOLERANCE`.
+    ///
+    /// Oriented boxes are tested in their own frame, the axes must be perpendicular.
+    pub fn contains_point(&self, point: &impl AsVec3) -> bool {
+        let d = point.as_vec3() - self.center.as_vec3();
+        [
+            (&self.x_axis, self.half_size.x()),
+            (&self.y_axis, self.half_size.y()),
+            (&self.z_axis, self.half_size.z()),
+        ]
+        .into_iter()
+        .all(|(axis, half)| {
+            let axis = axis.as_vec3();
+            let length_squared = axis.length_squared();
+            length_squared > 0.0
+                && (d.dot(&axis) / length_squared).abs() <= half + Tolerance::ZERO_TOLERANCE
+        })
+    }
+
+    /// Check if all corners of another box are inside this box, see `contains_point`.
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        other
+            .corners()
+            .iter()
+            .all(|corner| self.contains_point(corner))
+    }
+
+    /// Smallest axis-aligned box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> Self {
+        let (min_a, max_a) = self.extents();
+        let (min_b, max_b) = other.extents();
+        Self::from_points(
+            &[
+                Vec3::new(
+                    min_a.x.min(min_b.x),
+                    min_a.y.min(min_b.y),
+                    min_a.z.min(min_b.z),
+                ),
+                Vec3::new(
+                    max_a.x.max(max_b.x),
+                    max_a.y.max(max_b.y),
+                    max_a.z.max(max_b.z),
+                ),
+            ],
+            0.0,
+        )
+    }
+
+    /// Axis-aligned overlap of the axis-aligned extents of both boxes.
+    ///
+    /// Exact for axis-aligned boxes, oriented boxes are replaced by the
+    /// axis-aligned boxes around them first.
+    ///
+    /// # Returns
+    /// None if the boxes do not overlap, a flat box if they only touch.
+    pub fn intersection(&self, other: &BoundingBox) -> Option<Self> {
+        let (min_a, max_a) = self.extents();
+        let (min_b, max_b) = other.extents();
+        let min = Vec3::new(
+            min_a.x.max(min_b.x),
+            min_a.y.max(min_b.y),
+            min_a.z.max(min_b.z),
+        );
+        let max = Vec3::new(
+            max_a.x.min(max_b.x),
+            max_a.y.min(max_b.y),
+            max_a.z.min(max_b.z),
+        );
+        if max.x < min.x || max.y < min.y || max.z < min.z {
+            return None;
+        }
+        Some(Self::from_points(&[min, max], 0.0))
+    }
+
+    /// Axis-aligned box around the box after transforming it.
+    ///
+    /// All eight corners are transformed, so rotations and projective xforms
+    /// give a box that still contains the transformed box. The name is kept.
+    pub fn transformed_by(&self, xform: &Xform) -> Self {
+        let mut corners = self.corners().map(|corner| corner.as_vec3());
+        Vec3::transform_points(xform, &mut corners);
+        let mut result = Self::from_points(&corners, 0.0);
+        result.name = self.name.clone();
+        result
+    }
+
+    /// World minimum and maximum of the corners.
+    fn extents(&self) -> (Vec3, Vec3) {
+        self.corners().iter().fold(
+            (
+                Vec3::new(Scalar::MAX, Scalar::MAX, Scalar::MAX),
+                Vec3::new(Scalar::MIN, Scalar::MIN, Scalar::MIN),
+            ),
+            |(min, max), corner| {
+                let c = corner.as_vec3();
+                (
+                    Vec3::new(min.x.min(c.x), min.y.min(c.y), min.z.min(c.z)),
+                    Vec3::new(max.x.max(c.x), max.y.max(c.y), max.z.max(c.z)),
+                )
+            },
+        )
+    }
+
     fn separating_plane_exists(
         relative_position: &Vector,
         axis: &Vector,
@@ -286,7 +495,8 @@ impl BoundingBox {
         result
     }
 
-    pub fn jsondump(&self) -> Result<String, std::boxed::Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let data = serde_json::json!({
             "type": "BoundingBox",
             "center": serde_json::from_str::<serde_json::Value>(&self.center.jsondump()?)?,
@@ -300,7 +510,8 @@ impl BoundingBox {
         Ok(serde_json::to_string(&data)?)
     }
 
-    pub fn jsonload(json_data: &str) -> Result<Self, std::boxed::Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         let data: serde_json::Value = serde_json::from_str(json_data)?;
         let mut bbox = BoundingBox::new(
             Point::jsonload(&data["center"].to_string())?,
@@ -314,7 +525,8 @@ impl BoundingBox {
         Ok(bbox)
     }
 
-    pub fn to_json(&self, filepath: &str) -> Result<(), std::boxed::Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json_string = self.jsondump()?;
         let value: serde_json::Value = serde_json::from_str(&json_string)?;
         let pretty = serde_json::to_string_pretty(&value)?;
@@ -322,10 +534,88 @@ impl BoundingBox {
         Ok(())
     }
 
-    pub fn from_json(filepath: &str) -> Result<Self, std::boxed::Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json_string = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json_string)
+        Self::jsonload(&json_string).map_err(|e| e.with_path(filepath))
+    }
+}
+
+/// Counter-clockwise convex hull by Andrew's monotone chain, without collinear points.
+fn convex_hull_2d(mut points: Vec<[Scalar; 2]>) -> Vec<[Scalar; 2]> {
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let turn = |o: [Scalar; 2], a: [Scalar; 2], b: [Scalar; 2]| {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    };
+    let mut hull: Vec<[Scalar; 2]> = Vec::with_capacity(2 * points.len());
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2
+                && turn(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each chain starts the other one
+        hull.pop();
+    }
+    hull
+}
+
+/// Unit edge direction and its left normal of the minimum-area rectangle around a convex hull.
+///
+/// Rotating calipers: one side of the optimal rectangle lies on a hull edge,
+/// and the extreme points for consecutive edges only move forward.
+fn min_area_rectangle(hull: &[[Scalar; 2]]) -> ([Scalar; 2], [Scalar; 2]) {
+    let n = hull.len();
+    let dot = |p: [Scalar; 2], d: [Scalar; 2]| p[0] * d[0] + p[1] * d[1];
+    let edge = |i: usize| {
+        let (a, b) = (hull[i], hull[(i + 1) % n]);
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let length = (dx * dx + dy * dy).sqrt();
+        let d = [dx / length, dy / length];
+        (d, [-d[1], d[0]])
+    };
+    if n < 2 || (n == 2 && hull[0] == hull[1]) {
+        return ([1.0, 0.0], [0.0, 1.0]);
+    }
+    if n == 2 {
+        return edge(0);
+    }
+
+    let (mut best, mut best_area) = (edge(0), Scalar::MAX);
+    let (mut right, mut top, mut left) = (0, 0, 0);
+    for i in 0..n {
+        let (d, normal) = edge(i);
+        let advance = |mut k: usize, direction: [Scalar; 2]| {
+            while dot(hull[(k + 1) % n], direction) > dot(hull[k], direction) {
+                k = (k + 1) % n;
+            }
+            k
+        };
+        if i == 0 {
+            right = advance(0, d);
+            top = advance(right, normal);
+            left = advance(top, [-d[0], -d[1]]);
+        } else {
+            right = advance(right, d);
+            top = advance(top, normal);
+            left = advance(left, [-d[0], -d[1]]);
+        }
+        let width = dot(hull[right], d) - dot(hull[left], d);
+        let height = dot(hull[top], normal) - dot(hull[i], normal);
+        if width * height < best_area {
+            best_area = width * height;
+            best = (d, normal);
+        }
     }
+    best
 }
 
 impl Default for BoundingBox {
@@ -336,7 +626,7 @@ impl Default for BoundingBox {
             y_axis: Vector::new(0.0, 1.0, 0.0),
             z_axis: Vector::new(0.0, 0.0, 1.0),
             half_size: Vector::new(0.5, 0.5, 0.5),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: String::new(),
             xform: Xform::identity(),
         }
@@ -54,6 +54,21 @@ mod tests {
         assert_eq!(b.half_size.z(), 4.0);
     }
 
+    #[test]
+    fn test_box_is_axis_aligned() {
+        let b =
+            BoundingBox::from_points(&[Point::new(0.0, 0.0, 0.0), Point::new(1.0, 2.0, 3.0)], 0.0);
+        assert!(b.is_axis_aligned());
+
+        let plane = Plane::new(
+            Point::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 1.0, 0.0),
+            Vector::new(-1.0, 1.0, 0.0),
+        );
+        let rotated = BoundingBox::from_plane(&plane, 1.0, 1.0, 1.0);
+        assert!(!rotated.is_axis_aligned());
+    }
+
     #[test]
     fn test_box_corners() {
         let center = Point::new(0.0, 0.0, 0.0);
@@ -325,4 +340,95 @@ mod tests {
         assert_eq!(loaded.name, original.name);
         assert_eq!(loaded.guid, original.guid);
     }
+
+    #[test]
+    fn test_box_set_operations() {
+        let a =
+            BoundingBox::from_points(&[Point::new(0.0, 0.0, 0.0), Point::new(2.0, 2.0, 2.0)], 0.0);
+        let b =
+            BoundingBox::from_points(&[Point::new(1.0, 1.0, 1.0), Point::new(3.0, 4.0, 5.0)], 0.0);
+        let union = a.union(&b);
+        assert_eq!(union.min_point().z(), 0.0);
+        assert_eq!(union.max_point().z(), 5.0);
+        assert_eq!(union.volume(), 60.0);
+        assert!(union.contains_box(&a) && union.contains_box(&b));
+        assert!(!a.contains_box(&b));
+
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(overlap.center.x(), 1.5);
+        assert_eq!(overlap.volume(), 1.0);
+        let far = BoundingBox::from_point(Point::new(10.0, 0.0, 0.0), 1.0);
+        assert!(a.intersection(&far).is_none());
+
+        assert!(a.contains_point(&Point::new(2.0, 1.0, 0.0)));
+        assert!(!a.contains_point(&Point::new(2.1, 1.0, 0.0)));
+        assert_eq!(a.expand(1.0).volume(), 64.0);
+        assert_eq!(a.expand(-5.0).volume(), 0.0);
+        assert_eq!(a.volume(), 8.0);
+    }
+
+    #[test]
+    fn test_box_transformed_by() {
+        let unit = BoundingBox::from_point(Point::new(0.0, 0.0, 0.0), 1.0);
+        let rotated =
+            unit.transformed_by(&crate::Xform::rotation_z(crate::scalar::consts::FRAC_PI_4));
+        let diagonal = (2.0 as crate::Scalar).sqrt();
+        assert!((rotated.half_size.x() - diagonal).abs() < 1e-5);
+        assert!((rotated.half_size.z() - 1.0).abs() < 1e-5);
+        assert!(rotated.is_axis_aligned());
+        assert!(rotated.contains_box(&unit));
+
+        // Oriented boxes are tested in their own frame
+        let oriented = BoundingBox::new(
+            Point::new(0.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(-1.0, 0.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(3.0, 1.0, 1.0),
+        );
+        assert!(oriented.contains_point(&Point::new(0.0, 2.5, 0.0)));
+        assert!(!oriented.contains_point(&Point::new(2.5, 0.0, 0.0)));
+        assert_eq!(oriented.union(&unit).half_size.y(), 3.0);
+    }
+
+    #[test]
+    fn test_box_fit_obb() {
+        // A rotated 4 x 2 x 1 block sampled on its corners and faces
+        let rotation = crate::Xform::rotation(&Vector::new(1.0, 2.0, 3.0), 0.8);
+        let mut points = Vec::new();
+        for i in 0..=8 {
+            for j in 0..=4 {
+                for k in 0..=2 {
+                    let local = Point::new(
+                        i as crate::Scalar * 0.5,
+                        j as crate::Scalar * 0.5,
+                        k as crate::Scalar * 0.5,
+                    );
+                    points.push(rotation.transformed_point(&local));
+                }
+            }
+        }
+        let obb = BoundingBox::fit_obb(&points);
+        let mut half = [obb.half_size.x(), obb.half_size.y(), obb.half_size.z()];
+        half.sort_by(|a, b| a.total_cmp(b));
+        assert!((half[0] - 0.5).abs() < 1e-4);
+        assert!((half[1] - 1.0).abs() < 1e-4);
+        assert!((half[2] - 2.0).abs() < 1e-4);
+        assert!((obb.volume() - 8.0).abs() < 1e-3);
+        assert!(points.iter().all(|p| obb.expand(1e-4).contains_point(p)));
+        assert!(BoundingBox::from_points(&points, 0.0).volume() > obb.volume());
+
+        // A flat triangle, PCA alone does not find the best rectangle
+        let triangle = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(4.0, 0.0, 0.0),
+            Point::new(0.0, 3.0, 0.0),
+        ];
+        let flat = BoundingBox::fit_obb(&triangle);
+        let mut half = [flat.half_size.x(), flat.half_size.y(), flat.half_size.z()];
+        half.sort_by(|a, b| a.total_cmp(b));
+        assert!(half[0] < 1e-6);
+        assert!((4.0 * half[1] * half[2] - 12.0).abs() < 1e-4);
+        assert_eq!(BoundingBox::fit_obb::<Point>(&[]).volume(), 1.0);
+    }
 }
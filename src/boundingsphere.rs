@@ -0,0 +1,137 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{AsVec3, Point, Scalar, Tolerance, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Sphere enclosing a set of points, a rotation invariant collision proxy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "BoundingSphere")]
+pub struct BoundingSphere {
+    pub center: Point,
+    pub radius: Scalar,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Point, radius: Scalar) -> Self {
+        BoundingSphere { center, radius }
+    }
+
+    /// Smallest sphere enclosing the points, by Welzl's algorithm.
+    ///
+    /// The points are visited in a fixed pseudo-random order, which gives
+    /// expected linear time regardless of the input order.
+    ///
+    /// # Returns
+    /// None if there are no points.
+    pub fn from_points<P: AsVec3>(points: &[P]) -> Option<Self> {
+        let mut p: Vec<Vec3> = points.iter().map(AsVec3::as_vec3).collect();
+        let first = *p.first()?;
+        // Fisher-Yates with a xorshift generator, deterministic across runs
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        for i in (1..p.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            p.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+
+        let mut ball = (first, 0.0);
+        for i in 0..p.len() {
+            if contains(ball, p[i]) {
+                continue;
+            }
+            ball = (p[i], 0.0);
+            for j in 0..i {
+                if contains(ball, p[j]) {
+                    continue;
+                }
+                ball = ball_2(p[i], p[j]);
+                for k in 0..j {
+                    if contains(ball, p[k]) {
+                        continue;
+                    }
+                    ball = ball_3(p[i], p[j], p[k]);
+                    for l in 0..k {
+                        if !contains(ball, p[l]) {
+                            ball = ball_4(p[i], p[j], p[k], p[l]);
+                        }
+                    }
+                }
+            }
+        }
+        Some(BoundingSphere::new(ball.0.into(), ball.1))
+    }
+
+    /// Check if a point is inside the sphere or on its surface.
+    pub fn contains_point(&self, point: &Point) -> bool {
+        contains((self.center.as_vec3(), self.radius), point.as_vec3())
+    }
+
+    /// Check if two spheres overlap or touch.
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        self.center.as_vec3().distance(&other.center.as_vec3()) <= self.radius + other.radius
+    }
+
+    pub fn volume(&self) -> Scalar {
+        4.0 / 3.0 * crate::scalar::consts::PI * self.radius.powi(3)
+    }
+}
+
+type Ball = (Vec3, Scalar);
+
+/// Point in ball test with a relative tolerance, so rounding cannot reject boundary points.
+fn contains((center, radius): Ball, point: Vec3) -> bool {
+    let slack = (radius + center.length()) * 16.0 * Scalar::EPSILON + Tolerance::ZERO_TOLERANCE;
+    center.distance(&point) <= radius + slack
+}
+
+fn ball_2(a: Vec3, b: Vec3) -> Ball {
+    let center = (a + b) * 0.5;
+    (center, center.distance(&a))
+}
+
+/// Smallest ball with three points on its boundary, the circumcircle unless they are collinear.
+fn ball_3(a: Vec3, b: Vec3, c: Vec3) -> Ball {
+    let (u, v) = (b - a, c - a);
+    let w = u.cross(&v);
+    let w_squared = w.length_squared();
+    if w_squared <= Scalar::EPSILON * u.length_squared() * v.length_squared() {
+        // Collinear, the farthest pair spans the others
+        return [ball_2(a, b), ball_2(a, c), ball_2(b, c)].into_iter().fold(
+            (a, 0.0),
+            |best, ball| if ball.1 > best.1 { ball } else { best },
+        );
+    }
+    let offset =
+        (v.cross(&w) * u.length_squared() + w.cross(&u) * v.length_squared()) * (0.5 / w_squared);
+    (a + offset, offset.length())
+}
+
+/// Smallest ball with four points on its boundary, the circumsphere unless they are coplanar.
+fn ball_4(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> Ball {
+    let (u, v, w) = (b - a, c - a, d - a);
+    let det = u.dot(&v.cross(&w));
+    let scale = u.length() * v.length() * w.length();
+    if det.abs() <= Scalar::EPSILON * scale {
+        // Coplanar, the smallest boundary circle containing the fourth point
+        let points = [a, b, c, d];
+        return [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]]
+            .into_iter()
+            .map(|[i, j, k]| ball_3(points[i], points[j], points[k]))
+            .filter(|ball| points.iter().all(|p| contains(*ball, *p)))
+            .fold(None, |best: Option<Ball>, ball| match best {
+                Some(best) if best.1 <= ball.1 => Some(best),
+                _ => Some(ball),
+            })
+            .unwrap_or_else(|| ball_3(a, b, c));
+    }
+    let offset = (v.cross(&w) * u.length_squared()
+        + w.cross(&u) * v.length_squared()
+        + u.cross(&v) * w.length_squared())
+        * (0.5 / det);
+    (a + offset, offset.length())
+}
+
+#[cfg(test)]
+#[path = "boundingsphere_test.rs"]
+mod boundingsphere_test;
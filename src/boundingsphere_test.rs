@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn test_sphere_from_points() {
+    assert!(BoundingSphere::from_points::<Point>(&[]).is_none());
+    let single = BoundingSphere::from_points(&[Vec3::new(1.0, 2.0, 3.0)]).unwrap();
+    assert_eq!(single.radius, 0.0);
+    assert_eq!(single.center.y(), 2.0);
+
+    // Octahedron vertices with interior points, the sphere is the circumsphere
+    let mut points = vec![
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(0.0, 0.0, -1.0),
+    ];
+    for i in 0..50 {
+        let t = i as Scalar * 0.37;
+        points.push(Vec3::new(t.sin(), t.cos(), (t * 1.3).sin()) * 0.5);
+    }
+    let sphere = BoundingSphere::from_points(&points).unwrap();
+    assert!((sphere.radius - 1.0).abs() < 1e-5);
+    assert!(sphere.center.as_vec3().length() < 1e-5);
+    assert!(points
+        .iter()
+        .all(|p| sphere.contains_point(&Point::from(*p))));
+    assert!((sphere.volume() - 4.0 / 3.0 * crate::scalar::consts::PI).abs() < 1e-4);
+}
+
+#[test]
+fn test_sphere_degenerate_and_intersects() {
+    // Collinear and coplanar points
+    let line = [0.0, 3.0, 1.0, 2.0].map(|x| Vec3::new(x, 0.0, 0.0));
+    let sphere = BoundingSphere::from_points(&line).unwrap();
+    assert!((sphere.radius - 1.5).abs() < 1e-6);
+    let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (1.0, 1.0)]
+        .map(|(x, y)| Point::new(x, y, 0.0));
+    let sphere = BoundingSphere::from_points(&square).unwrap();
+    assert!((sphere.radius - (2.0 as Scalar).sqrt()).abs() < 1e-5);
+
+    let other = BoundingSphere::new(Point::new(4.0, 1.0, 0.0), 1.6);
+    assert!(sphere.intersects(&other));
+    assert!(!sphere.intersects(&BoundingSphere::new(Point::new(4.0, 1.0, 0.0), 1.5)));
+}
@@ -1,6 +1,10 @@
-use crate::{BoundingBox, Point, Vector};
+use crate::error::SessionError;
+use crate::intersection::{plane_aabb_classify_extents, PlaneSide};
+use crate::{BoundingBox, Plane, Point, Scalar, Tolerance, Vector};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BVHNode {
@@ -24,7 +28,7 @@ impl BVHNode {
 impl Default for BVHNode {
     fn default() -> Self {
         BVHNode {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             left: None,
             right: None,
             object_id: -1,
@@ -36,12 +40,12 @@ impl Default for BVHNode {
 // Lightweight AABB for arena nodes (6 doubles, no axes)
 #[derive(Clone, Copy, Default, Debug)]
 struct BvhAABB {
-    cx: f64,
-    cy: f64,
-    cz: f64,
-    hx: f64,
-    hy: f64,
-    hz: f64,
+    cx: Scalar,
+    cy: Scalar,
+    cz: Scalar,
+    hx: Scalar,
+    hy: Scalar,
+    hz: Scalar,
 }
 
 impl BvhAABB {
@@ -98,6 +102,86 @@ impl BvhAABB {
             && min1_z <= max2_z
             && max1_z >= min2_z
     }
+
+    #[inline(always)]
+    fn surface_area(&self) -> Scalar {
+        8.0 * (self.hx * self.hy + self.hy * self.hz + self.hz * self.hx)
+    }
+
+    #[inline(always)]
+    fn distance_squared(&self, p: &Point) -> Scalar {
+        self.distance_squared_xyz([p.x(), p.y(), p.z()])
+    }
+
+    #[inline(always)]
+    fn distance_squared_xyz(&self, p: [Scalar; 3]) -> Scalar {
+        let dx = ((p[0] - self.cx).abs() - self.hx).max(0.0);
+        let dy = ((p[1] - self.cy).abs() - self.hy).max(0.0);
+        let dz = ((p[2] - self.cz).abs() - self.hz).max(0.0);
+        dx * dx + dy * dy + dz * dz
+    }
+
+    // Distance to a segment is convex along it, so a golden-section search converges
+    fn segment_distance_squared(&self, a: [Scalar; 3], b: [Scalar; 3]) -> Scalar {
+        let at = |s: Scalar| {
+            self.distance_squared_xyz([
+                a[0] + (b[0] - a[0]) * s,
+                a[1] + (b[1] - a[1]) * s,
+                a[2] + (b[2] - a[2]) * s,
+            ])
+        };
+        if a == b {
+            return at(0.0);
+        }
+        let ratio = ((5.0 as Scalar).sqrt() - 1.0) * 0.5;
+        let (mut lo, mut hi) = (0.0, 1.0);
+        let mut s1 = hi - ratio * (hi - lo);
+        let mut s2 = lo + ratio * (hi - lo);
+        let (mut f1, mut f2) = (at(s1), at(s2));
+        for _ in 0..60 {
+            if f1 <= f2 {
+                hi = s2;
+                s2 = s1;
+                f2 = f1;
+                s1 = hi - ratio * (hi - lo);
+                f1 = at(s1);
+            } else {
+                lo = s1;
+                s1 = s2;
+                f1 = f2;
+                s2 = lo + ratio * (hi - lo);
+                f2 = at(s2);
+            }
+        }
+        at(0.0).min(at(1.0)).min(f1.min(f2))
+    }
+}
+
+// Priority queue entry for best-first traversal, ordered by distance or ray parameter
+#[derive(Clone, Copy, Debug)]
+struct QueueEntry {
+    priority: Scalar,
+    node: i32,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.total_cmp(&other.priority)
+    }
 }
 
 // Flat node for arena-based traversal (cache-friendly)
@@ -105,22 +189,166 @@ impl BvhAABB {
 struct FlatNode {
     left: i32,      // -1 if leaf
     right: i32,     // -1 if leaf
+    parent: i32,    // -1 for root
     object_id: i32, // >= 0 for leaf, -1 for internal
     aabb: BvhAABB,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "BvhData", try_from = "BvhData")]
 pub struct BVH {
     pub guid: String,
     pub name: String,
     pub root: Option<Box<BVHNode>>,
-    pub world_size: f64,
-    #[serde(skip)]
+    pub world_size: Scalar,
     pub object_guids: Vec<String>, // Parallel array to boxes - maps indices to GUIDs
-    #[serde(skip)]
-    arena: Vec<FlatNode>, // Flat node arena for fast queries
-    #[serde(skip)]
-    arena_root: i32, // Root index in arena (-1 if empty)
+    arena: Vec<FlatNode>,          // Flat node arena for fast queries
+    arena_root: i32,               // Root index in arena (-1 if empty)
+    leaf_nodes: Vec<i32>,          // Arena index of each object's leaf (-1 if absent)
+    free_nodes: Vec<i32>,          // Arena slots released by remove, reused by insert
+}
+
+/// Shape and quality metrics of a BVH, see `BVH::stats`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BvhStats {
+    /// Number of reachable nodes, leaves included
+    pub node_count: usize,
+    pub leaf_count: usize,
+    /// Depth of the deepest node, the root is at depth 0
+    pub max_depth: usize,
+    /// Number of nodes at each depth
+    pub depth_histogram: Vec<usize>,
+    /// Mean number of other leaves whose boxes overlap each leaf box
+    pub average_leaf_overlap: Scalar,
+    /// Surface area heuristic cost relative to the root box
+    pub sah_cost: Scalar,
+    /// Approximate heap and inline memory used by the BVH in bytes
+    pub memory_bytes: usize,
+}
+
+// Serialized form of a BVH: reachable arena nodes in depth-first order as flat arrays
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename = "BVH")]
+struct BvhData {
+    guid: String,
+    name: String,
+    #[serde(default)]
+    root: Option<Box<BVHNode>>,
+    world_size: Scalar,
+    #[serde(default)]
+    object_guids: Vec<String>,
+    #[serde(default = "BvhData::empty_root")]
+    arena_root: i32,
+    // left, right, parent, object_id per node
+    #[serde(default)]
+    links: Vec<i32>,
+    // cx, cy, cz, hx, hy, hz per node
+    #[serde(default)]
+    boxes: Vec<Scalar>,
+}
+
+impl BvhData {
+    fn empty_root() -> i32 {
+        -1
+    }
+}
+
+impl From<BVH> for BvhData {
+    fn from(bvh: BVH) -> Self {
+        // Renumber reachable nodes so slots freed by remove are not written
+        let mut order: Vec<i32> = Vec::new();
+        let mut new_index = vec![-1; bvh.arena.len()];
+        let mut stack: Vec<i32> = Vec::new();
+        if bvh.arena_root >= 0 {
+            stack.push(bvh.arena_root);
+        }
+        while let Some(idx) = stack.pop() {
+            new_index[idx as usize] = order.len() as i32;
+            order.push(idx);
+            let node = &bvh.arena[idx as usize];
+            if node.object_id < 0 {
+                stack.push(node.right);
+                stack.push(node.left);
+            }
+        }
+        let remap = |idx: i32| {
+            if idx >= 0 {
+                new_index[idx as usize]
+            } else {
+                -1
+            }
+        };
+
+        let mut links = Vec::with_capacity(order.len() * 4);
+        let mut boxes = Vec::with_capacity(order.len() * 6);
+        for &idx in &order {
+            let node = &bvh.arena[idx as usize];
+            links.extend([
+                remap(node.left),
+                remap(node.right),
+                remap(node.parent),
+                node.object_id,
+            ]);
+            let a = &node.aabb;
+            boxes.extend([a.cx, a.cy, a.cz, a.hx, a.hy, a.hz]);
+        }
+
+        BvhData {
+            guid: bvh.guid,
+            name: bvh.name,
+            root: bvh.root,
+            world_size: bvh.world_size,
+            object_guids: bvh.object_guids,
+            arena_root: remap(bvh.arena_root),
+            links,
+            boxes,
+        }
+    }
+}
+
+impl TryFrom<BvhData> for BVH {
+    type Error = String;
+
+    fn try_from(data: BvhData) -> Result<Self, Self::Error> {
+        let count = data.links.len() / 4;
+        if !data.links.len().is_multiple_of(4) || data.boxes.len() != count * 6 {
+            return Err("BVH node arrays have inconsistent lengths".to_string());
+        }
+        let arena: Vec<FlatNode> = data
+            .links
+            .chunks_exact(4)
+            .zip(data.boxes.chunks_exact(6))
+            .map(|(l, b)| FlatNode {
+                left: l[0],
+                right: l[1],
+                parent: l[2],
+                object_id: l[3],
+                aabb: BvhAABB {
+                    cx: b[0],
+                    cy: b[1],
+                    cz: b[2],
+                    hx: b[3],
+                    hy: b[4],
+                    hz: b[5],
+                },
+            })
+            .collect();
+
+        let mut bvh = BVH {
+            guid: data.guid,
+            name: data.name,
+            root: data.root,
+            world_size: data.world_size,
+            object_guids: data.object_guids,
+            arena,
+            arena_root: data.arena_root,
+            leaf_nodes: Vec::new(),
+            free_nodes: Vec::new(),
+        };
+        bvh.validate()?;
+        bvh.rebuild_leaf_links();
+        Ok(bvh)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -138,23 +366,25 @@ impl Default for BVH {
 impl BVH {
     pub fn new() -> Self {
         BVH {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_bvh".to_string(),
             root: None,
             world_size: 1000.0, // Default, will be computed from boxes
             object_guids: Vec::new(),
             arena: Vec::new(),
             arena_root: -1,
+            leaf_nodes: Vec::new(),
+            free_nodes: Vec::new(),
         }
     }
 
     /// Compute world size from bounding boxes
-    pub fn compute_world_size(bounding_boxes: &[BoundingBox]) -> f64 {
+    pub fn compute_world_size(bounding_boxes: &[BoundingBox]) -> Scalar {
         if bounding_boxes.is_empty() {
             return 1000.0;
         }
 
-        let mut max_extent = 0.0f64;
+        let mut max_extent: Scalar = 0.0;
         for bbox in bounding_boxes {
             // Find maximum absolute coordinate in any dimension
             let x_extent = (bbox.center.x() + bbox.half_size.x())
@@ -199,38 +429,72 @@ impl BVH {
         self.build(&bounding_boxes);
     }
 
-    pub fn from_boxes(bounding_boxes: &[BoundingBox], world_size: f64) -> Self {
+    pub fn from_boxes(bounding_boxes: &[BoundingBox], world_size: Scalar) -> Self {
         let mut bvh = Self::new();
         bvh.world_size = world_size;
         bvh.build(bounding_boxes);
         bvh
     }
 
+    /// Build BVH from bounding boxes using all available threads.
+    ///
+    /// Produces the same tree as `from_boxes`, with Morton coding, sorting and
+    /// topology construction spread over the rayon thread pool.
+    pub fn from_boxes_parallel(bounding_boxes: &[BoundingBox], world_size: Scalar) -> Self {
+        let mut bvh = Self::new();
+        bvh.world_size = world_size;
+        bvh.build_parallel(bounding_boxes);
+        bvh
+    }
+
     pub fn build(&mut self, bounding_boxes: &[BoundingBox]) {
+        self.build_impl(bounding_boxes, false);
+    }
+
+    /// Parallel version of `build`, see `from_boxes_parallel`.
+    pub fn build_parallel(&mut self, bounding_boxes: &[BoundingBox]) {
+        self.build_impl(bounding_boxes, true);
+    }
+
+    fn build_impl(&mut self, bounding_boxes: &[BoundingBox], parallel: bool) {
         if bounding_boxes.is_empty() {
             self.root = None;
             self.arena.clear();
             self.arena_root = -1;
+            self.leaf_nodes.clear();
+            self.free_nodes.clear();
             return;
         }
 
+        self.leaf_nodes = vec![-1; bounding_boxes.len()];
+        self.free_nodes.clear();
+
         // Create list of objects with their Morton codes (no bbox copies needed later)
-        let mut objects: Vec<ObjectInfo> = bounding_boxes
-            .iter()
-            .enumerate()
-            .map(|(i, bbox)| {
-                let morton_code = calculate_morton_code(
-                    bbox.center.x(),
-                    bbox.center.y(),
-                    bbox.center.z(),
-                    self.world_size,
-                );
-                ObjectInfo { id: i, morton_code }
-            })
-            .collect();
+        let world_size = self.world_size;
+        let object_info = |(i, bbox): (usize, &BoundingBox)| {
+            let morton_code = calculate_morton_code(
+                bbox.center.x(),
+                bbox.center.y(),
+                bbox.center.z(),
+                world_size,
+            );
+            ObjectInfo { id: i, morton_code }
+        };
+        let mut objects: Vec<ObjectInfo> = if parallel {
+            bounding_boxes
+                .par_iter()
+                .enumerate()
+                .map(object_info)
+                .collect()
+        } else {
+            bounding_boxes.iter().enumerate().map(object_info).collect()
+        };
 
-        // Radix sort 30-bit Morton codes: 3 passes of 10 bits (RADIX = 1024)
-        {
+        if parallel {
+            // Same order as the stable radix sort: ties keep ascending ids
+            objects.par_sort_unstable_by_key(|o| (o.morton_code, o.id));
+        } else {
+            // Radix sort 30-bit Morton codes: 3 passes of 10 bits (RADIX = 1024)
             const RADIX: usize = 1024;
             const PASSES: usize = 3;
             let mut tmp: Vec<ObjectInfo> = vec![objects[0].clone(); objects.len()];
@@ -270,10 +534,12 @@ impl BVH {
             self.arena.push(FlatNode {
                 left: -1,
                 right: -1,
+                parent: -1,
                 object_id: id as i32,
                 aabb,
             });
             self.arena_root = 0;
+            self.leaf_nodes[id] = 0;
             self.root = None;
             return;
         }
@@ -385,11 +651,22 @@ impl BVH {
             });
         }
 
-        // Build topology
-        let mut has_parent: Vec<bool> = vec![false; n - 1];
-        for i in 0..(n as i32 - 1) {
+        // Build topology: each internal node's range and split are independent
+        let node_split = |i: i32| {
             let (first, last) = determine_range(i);
-            let split = find_split(first, last);
+            (first, last, find_split(first, last))
+        };
+        let splits: Vec<(i32, i32, i32)> = if parallel {
+            (0..(n as i32 - 1))
+                .into_par_iter()
+                .map(node_split)
+                .collect()
+        } else {
+            (0..(n as i32 - 1)).map(node_split).collect()
+        };
+        let mut has_parent: Vec<bool> = vec![false; n - 1];
+        for (i, &(first, last, split)) in splits.iter().enumerate() {
+            let i = i as i32;
             if split == first {
                 internals[i as usize].left = Some(TempChild::Leaf(split as usize));
             } else {
@@ -444,6 +721,7 @@ impl BVH {
         // Helper to convert TempNode to FlatNode and add to arena
         fn build_arena_node(
             node_ref: TempChild,
+            parent: i32,
             internals: &[TempNode],
             leaves: &[TempNode],
             arena: &mut Vec<FlatNode>,
@@ -454,6 +732,7 @@ impl BVH {
                     arena.push(FlatNode {
                         left: -1,
                         right: -1,
+                        parent,
                         object_id: leaves[li].object_id,
                         aabb: leaves[li].aabb,
                     });
@@ -465,18 +744,21 @@ impl BVH {
                     arena.push(FlatNode {
                         left: -1,
                         right: -1,
+                        parent,
                         object_id: -1,
                         aabb: internals[ii].aabb,
                     });
                     // Build children
                     let left_idx = build_arena_node(
                         internals[ii].left.clone().expect("left child"),
+                        idx,
                         internals,
                         leaves,
                         arena,
                     );
                     let right_idx = build_arena_node(
                         internals[ii].right.clone().expect("right child"),
+                        idx,
                         internals,
                         leaves,
                         arena,
@@ -491,15 +773,431 @@ impl BVH {
 
         self.arena_root = build_arena_node(
             TempChild::Internal(root_idx),
+            -1,
             &internals,
             &leaves,
             &mut self.arena,
         );
+        for (idx, node) in self.arena.iter().enumerate() {
+            if node.object_id >= 0 {
+                self.leaf_nodes[node.object_id as usize] = idx as i32;
+            }
+        }
 
         // Leave self.root as None - arena is used for all queries now
         self.root = None;
     }
 
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Incremental Updates
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Insert a single object without rebuilding the tree.
+    ///
+    /// The leaf is paired with the sibling that least increases the surface area
+    /// of its ancestors, so the cost is proportional to the tree depth.
+    /// An object already present under the same id is replaced.
+    ///
+    /// # Arguments
+    /// * `bbox` - Bounding box of the object
+    /// * `object_id` - Index of the object, as used by `build` and the query methods
+    pub fn insert(&mut self, bbox: &BoundingBox, object_id: usize) {
+        self.remove(object_id);
+
+        let leaf_aabb = BvhAABB::from_bbox(bbox);
+        let leaf = self.allocate_node(FlatNode {
+            left: -1,
+            right: -1,
+            parent: -1,
+            object_id: object_id as i32,
+            aabb: leaf_aabb,
+        });
+        if self.leaf_nodes.len() <= object_id {
+            self.leaf_nodes.resize(object_id + 1, -1);
+        }
+        self.leaf_nodes[object_id] = leaf;
+
+        if self.arena_root < 0 {
+            self.arena_root = leaf;
+            return;
+        }
+
+        // Descend towards the cheapest sibling
+        let mut sibling = self.arena_root;
+        while self.arena[sibling as usize].object_id < 0 {
+            let node = self.arena[sibling as usize];
+            let combined = BvhAABB::merge(node.aabb, leaf_aabb).surface_area();
+            let cost_here = 2.0 * combined;
+            let inherited = 2.0 * (combined - node.aabb.surface_area());
+            let child_cost = |child: i32| {
+                let child = &self.arena[child as usize];
+                let merged = BvhAABB::merge(child.aabb, leaf_aabb).surface_area();
+                if child.object_id >= 0 {
+                    merged + inherited
+                } else {
+                    merged - child.aabb.surface_area() + inherited
+                }
+            };
+            let cost_left = child_cost(node.left);
+            let cost_right = child_cost(node.right);
+            if cost_here < cost_left && cost_here < cost_right {
+                break;
+            }
+            sibling = if cost_left < cost_right {
+                node.left
+            } else {
+                node.right
+            };
+        }
+
+        let old_parent = self.arena[sibling as usize].parent;
+        let new_parent = self.allocate_node(FlatNode {
+            left: sibling,
+            right: leaf,
+            parent: old_parent,
+            object_id: -1,
+            aabb: BvhAABB::merge(self.arena[sibling as usize].aabb, leaf_aabb),
+        });
+        self.arena[sibling as usize].parent = new_parent;
+        self.arena[leaf as usize].parent = new_parent;
+        if old_parent < 0 {
+            self.arena_root = new_parent;
+        } else {
+            self.replace_child(old_parent, sibling, new_parent);
+            self.refit_ancestors(old_parent);
+        }
+    }
+
+    /// Remove a single object without rebuilding the tree.
+    ///
+    /// # Returns
+    /// True if the object was present.
+    pub fn remove(&mut self, object_id: usize) -> bool {
+        let leaf = match self.leaf_nodes.get(object_id) {
+            Some(&leaf) if leaf >= 0 => leaf,
+            _ => return false,
+        };
+        self.leaf_nodes[object_id] = -1;
+        self.free_nodes.push(leaf);
+
+        let parent = self.arena[leaf as usize].parent;
+        if parent < 0 {
+            self.arena_root = -1;
+            return true;
+        }
+
+        let parent_node = self.arena[parent as usize];
+        let sibling = if parent_node.left == leaf {
+            parent_node.right
+        } else {
+            parent_node.left
+        };
+        let grandparent = parent_node.parent;
+        self.arena[sibling as usize].parent = grandparent;
+        self.free_nodes.push(parent);
+        if grandparent < 0 {
+            self.arena_root = sibling;
+        } else {
+            self.replace_child(grandparent, parent, sibling);
+            self.refit_ancestors(grandparent);
+        }
+        true
+    }
+
+    /// Update leaf boxes in place and recompute internal boxes, keeping the topology.
+    ///
+    /// Suited for objects that moved a little; after large motions a full
+    /// `build` gives a tighter tree.
+    ///
+    /// # Arguments
+    /// * `bounding_boxes` - Current boxes indexed by object id
+    pub fn refit(&mut self, bounding_boxes: &[BoundingBox]) {
+        for (object_id, &leaf) in self.leaf_nodes.iter().enumerate() {
+            if leaf >= 0 && object_id < bounding_boxes.len() {
+                self.arena[leaf as usize].aabb = BvhAABB::from_bbox(&bounding_boxes[object_id]);
+            }
+        }
+
+        fn refit_node(arena: &mut [FlatNode], idx: i32) -> BvhAABB {
+            let node = arena[idx as usize];
+            if node.object_id >= 0 {
+                return node.aabb;
+            }
+            let merged =
+                BvhAABB::merge(refit_node(arena, node.left), refit_node(arena, node.right));
+            arena[idx as usize].aabb = merged;
+            merged
+        }
+        if self.arena_root >= 0 {
+            refit_node(&mut self.arena, self.arena_root);
+        }
+    }
+
+    fn allocate_node(&mut self, node: FlatNode) -> i32 {
+        if let Some(idx) = self.free_nodes.pop() {
+            self.arena[idx as usize] = node;
+            idx
+        } else {
+            self.arena.push(node);
+            self.arena.len() as i32 - 1
+        }
+    }
+
+    fn replace_child(&mut self, parent: i32, old_child: i32, new_child: i32) {
+        let node = &mut self.arena[parent as usize];
+        if node.left == old_child {
+            node.left = new_child;
+        } else {
+            node.right = new_child;
+        }
+    }
+
+    fn refit_ancestors(&mut self, mut idx: i32) {
+        while idx >= 0 {
+            let node = self.arena[idx as usize];
+            self.arena[idx as usize].aabb = BvhAABB::merge(
+                self.arena[node.left as usize].aabb,
+                self.arena[node.right as usize].aabb,
+            );
+            idx = node.parent;
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Nearest Neighbours
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Find the object whose bounding box is closest to a point.
+    ///
+    /// # Returns
+    /// Object id and distance to its box (zero when the point is inside), or None if empty.
+    pub fn nearest(&self, point: &Point) -> Option<(usize, Scalar)> {
+        self.knn(point, 1).into_iter().next()
+    }
+
+    /// Find the `k` objects whose bounding boxes are closest to a point.
+    ///
+    /// Nodes are visited best-first by their box distance, so traversal stops as
+    /// soon as `k` leaves have been reached.
+    ///
+    /// # Returns
+    /// Object ids and distances to their boxes, sorted from nearest to farthest.
+    pub fn knn(&self, point: &Point, k: usize) -> Vec<(usize, Scalar)> {
+        let mut result = Vec::with_capacity(k);
+        if k == 0 || self.arena_root < 0 || self.arena.is_empty() {
+            return result;
+        }
+
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse(QueueEntry {
+            priority: self.arena[self.arena_root as usize]
+                .aabb
+                .distance_squared(point),
+            node: self.arena_root,
+        }));
+
+        while let Some(Reverse(entry)) = heap.pop() {
+            let node = &self.arena[entry.node as usize];
+            if node.object_id >= 0 {
+                result.push((node.object_id as usize, entry.priority.sqrt()));
+                if result.len() == k {
+                    break;
+                }
+                continue;
+            }
+            for child in [node.left, node.right] {
+                if child >= 0 {
+                    heap.push(Reverse(QueueEntry {
+                        priority: self.arena[child as usize].aabb.distance_squared(point),
+                        node: child,
+                    }));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Find the object with the smallest actual distance to a point.
+    ///
+    /// `distance` is called with a leaf's object id and returns the distance
+    /// from the point to the object, if any. Leaves are visited by the distance
+    /// to their boxes and traversal stops at boxes beyond the best distance.
+    ///
+    /// # Returns
+    /// Object id and distance of the closest object.
+    pub fn nearest_by<F>(&self, point: &Point, mut distance: F) -> Option<(usize, Scalar)>
+    where
+        F: FnMut(usize) -> Option<Scalar>,
+    {
+        if self.arena_root < 0 || self.arena.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(usize, Scalar)> = None;
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse(QueueEntry {
+            priority: self.arena[self.arena_root as usize]
+                .aabb
+                .distance_squared(point),
+            node: self.arena_root,
+        }));
+
+        while let Some(Reverse(entry)) = heap.pop() {
+            if best.is_some_and(|(_, d)| entry.priority > d * d) {
+                break;
+            }
+            let node = &self.arena[entry.node as usize];
+            if node.object_id >= 0 {
+                let id = node.object_id as usize;
+                if let Some(d) = distance(id) {
+                    if best.is_none_or(|(_, best_d)| d < best_d) {
+                        best = Some((id, d));
+                    }
+                }
+                continue;
+            }
+            for child in [node.left, node.right] {
+                if child >= 0 {
+                    heap.push(Reverse(QueueEntry {
+                        priority: self.arena[child as usize].aabb.distance_squared(point),
+                        node: child,
+                    }));
+                }
+            }
+        }
+
+        best
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Region Queries
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Find objects whose bounding boxes overlap a box, e.g. for rectangular selection.
+    ///
+    /// Oriented query boxes are replaced by their axis-aligned bounds.
+    pub fn query_box(&self, bbox: &BoundingBox) -> Vec<usize> {
+        let query = if bbox.is_axis_aligned() {
+            BvhAABB::from_bbox(bbox)
+        } else {
+            BvhAABB::from_bbox(&BoundingBox::from_points(&bbox.corners(), 0.0))
+        };
+
+        let mut result = Vec::new();
+        if self.arena_root < 0 || self.arena.is_empty() {
+            return result;
+        }
+        let mut stack: Vec<i32> = Vec::with_capacity(64);
+        stack.push(self.arena_root);
+        while let Some(node_idx) = stack.pop() {
+            let node = &self.arena[node_idx as usize];
+            if !query.intersects(&node.aabb) {
+                continue;
+            }
+            if node.object_id >= 0 {
+                result.push(node.object_id as usize);
+                continue;
+            }
+            stack.push(node.left);
+            stack.push(node.right);
+        }
+        result
+    }
+
+    /// Find objects whose bounding boxes are within a distance of a point.
+    ///
+    /// # Returns
+    /// Object ids and box distances sorted from nearest to farthest.
+    pub fn query_sphere(&self, center: &Point, radius: Scalar) -> Vec<(usize, Scalar)> {
+        let mut result = Vec::new();
+        if self.arena_root < 0 || self.arena.is_empty() || radius < 0.0 {
+            return result;
+        }
+        let p = [center.x(), center.y(), center.z()];
+        let radius_squared = radius * radius;
+        let mut stack: Vec<i32> = Vec::with_capacity(64);
+        stack.push(self.arena_root);
+        while let Some(node_idx) = stack.pop() {
+            let node = &self.arena[node_idx as usize];
+            let distance_squared = node.aabb.distance_squared_xyz(p);
+            if distance_squared > radius_squared {
+                continue;
+            }
+            if node.object_id >= 0 {
+                result.push((node.object_id as usize, distance_squared.sqrt()));
+                continue;
+            }
+            stack.push(node.left);
+            stack.push(node.right);
+        }
+        result.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        result
+    }
+
+    /// Find objects whose bounding boxes are inside or crossing a view frustum.
+    ///
+    /// Subtrees entirely inside the frustum are collected without further tests.
+    ///
+    /// # Arguments
+    /// * `planes` - Frustum planes with normals pointing into the frustum
+    pub fn query_frustum(&self, planes: &[Plane; 6]) -> Vec<usize> {
+        self.query_frustum_classified(planes)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Find objects whose bounding boxes touch a view frustum, flagging the ones fully inside.
+    ///
+    /// # Arguments
+    /// * `planes` - Frustum planes with normals pointing into the frustum
+    ///
+    /// # Returns
+    /// Object ids paired with true if the box is inside all planes, false if it crosses one.
+    pub fn query_frustum_classified(&self, planes: &[Plane; 6]) -> Vec<(usize, bool)> {
+        let mut result = Vec::new();
+        if self.arena_root < 0 || self.arena.is_empty() {
+            return result;
+        }
+
+        // Culled behind any plane, contained in front of all of them
+        let classify = |aabb: &BvhAABB| -> Option<bool> {
+            let center = [aabb.cx, aabb.cy, aabb.cz];
+            let half_size = [aabb.hx, aabb.hy, aabb.hz];
+            let mut inside = true;
+            for plane in planes {
+                match plane_aabb_classify_extents(plane, center, half_size) {
+                    PlaneSide::Back => return None,
+                    PlaneSide::Straddling => inside = false,
+                    PlaneSide::Front => {}
+                }
+            }
+            Some(inside)
+        };
+
+        let mut stack: Vec<(i32, bool)> = Vec::with_capacity(64);
+        stack.push((self.arena_root, false));
+        while let Some((node_idx, contained)) = stack.pop() {
+            let node = &self.arena[node_idx as usize];
+            let contained = if contained {
+                true
+            } else {
+                match classify(&node.aabb) {
+                    Some(inside) => inside,
+                    None => continue,
+                }
+            };
+            if node.object_id >= 0 {
+                result.push((node.object_id as usize, contained));
+                continue;
+            }
+            stack.push((node.left, contained));
+            stack.push((node.right, contained));
+        }
+        result
+    }
+
     pub fn merge_aabb(&self, aabb1: &BoundingBox, aabb2: &BoundingBox) -> BoundingBox {
         // Calculate min and max corners
         let min_x =
@@ -626,93 +1324,159 @@ impl BVH {
             return (all_collisions, Vec::new(), total_checks);
         }
 
-        // Track which object indices participate in any collision
-        let mut visited: Vec<bool> = vec![false; bounding_boxes.len()];
-
         // Stack of node index pairs for pairwise BVH traversal (cache-friendly)
         let mut stack: Vec<(i32, i32)> = Vec::with_capacity(256);
         stack.push((self.arena_root, self.arena_root));
 
         while let Some((a_idx, b_idx)) = stack.pop() {
-            let a = &self.arena[a_idx as usize];
-            let b = &self.arena[b_idx as usize];
-
-            // AABB overlap test (inline for speed)
-            if !a.aabb.intersects(&b.aabb) {
-                continue;
+            // Count only when node AABBs overlap (matches C++ metric)
+            if self.visit_node_pair(
+                a_idx,
+                b_idx,
+                bounding_boxes.len(),
+                &mut all_collisions,
+                &mut stack,
+            ) {
+                total_checks += 1;
             }
+        }
 
-            // Count only when node AABBs overlap (matches C++ metric)
-            total_checks += 1;
+        // Track which object indices participate in any collision
+        let mut visited: Vec<bool> = vec![false; bounding_boxes.len()];
+        for &(i, j) in &all_collisions {
+            visited[i] = true;
+            visited[j] = true;
+        }
+        let colliding_indices: Vec<usize> = visited
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, v)| if *v { Some(idx) } else { None })
+            .collect();
 
-            let a_leaf = a.object_id >= 0;
-            let b_leaf = b.object_id >= 0;
+        (all_collisions, colliding_indices, total_checks)
+    }
 
-            if a_leaf && b_leaf {
-                let i = a.object_id as usize;
-                let j = b.object_id as usize;
-                if i < j && i < visited.len() && j < visited.len() {
-                    all_collisions.push((i, j));
-                    visited[i] = true;
-                    visited[j] = true;
-                }
-                continue;
+    /// Collision pairs like `check_all_collisions`, with the pair traversal
+    /// spread over the rayon thread pool.
+    ///
+    /// The traversal is expanded breadth-first into independent node pairs that
+    /// are walked in parallel. The pairs come in the same order for any number
+    /// of threads.
+    ///
+    /// # Arguments
+    /// * `object_count` - Number of objects, leaves with larger ids are ignored
+    #[cfg(feature = "parallel")]
+    pub fn check_all_collisions_parallel(&self, object_count: usize) -> Vec<(usize, usize)> {
+        if self.arena_root < 0 || self.arena.is_empty() {
+            return Vec::new();
+        }
+
+        let mut collisions: Vec<(usize, usize)> = Vec::new();
+        let mut tasks = vec![(self.arena_root, self.arena_root)];
+        // A fixed task count keeps the result order independent of the thread count
+        while !tasks.is_empty() && tasks.len() < 256 {
+            let mut next = Vec::with_capacity(tasks.len() * 4);
+            for (a_idx, b_idx) in tasks {
+                self.visit_node_pair(a_idx, b_idx, object_count, &mut collisions, &mut next);
             }
+            tasks = next;
+        }
 
-            // Expand children (index-based, no pointer chasing)
-            if a_idx == b_idx {
-                // Same node: expand unique child pairs without symmetry duplicates
-                if a.left >= 0 {
-                    stack.push((a.left, a.left));
-                    if a.right >= 0 {
-                        stack.push((a.left, a.right));
-                        stack.push((a.right, a.right));
-                    }
+        let found: Vec<(usize, usize)> = tasks
+            .into_par_iter()
+            .flat_map_iter(|task| {
+                let mut found = Vec::new();
+                let mut stack = vec![task];
+                while let Some((a_idx, b_idx)) = stack.pop() {
+                    self.visit_node_pair(a_idx, b_idx, object_count, &mut found, &mut stack);
                 }
-                continue;
+                found
+            })
+            .collect();
+        collisions.extend(found);
+        collisions
+    }
+
+    /// One step of the pairwise traversal: record a colliding leaf pair or push
+    /// the child pairs to visit next.
+    ///
+    /// # Returns
+    /// `true` if the node boxes overlap.
+    #[inline]
+    fn visit_node_pair(
+        &self,
+        a_idx: i32,
+        b_idx: i32,
+        object_count: usize,
+        collisions: &mut Vec<(usize, usize)>,
+        stack: &mut Vec<(i32, i32)>,
+    ) -> bool {
+        let a = &self.arena[a_idx as usize];
+        let b = &self.arena[b_idx as usize];
+
+        // AABB overlap test (inline for speed)
+        if !a.aabb.intersects(&b.aabb) {
+            return false;
+        }
+
+        let a_leaf = a.object_id >= 0;
+        let b_leaf = b.object_id >= 0;
+
+        if a_leaf && b_leaf {
+            // Leaf order in the tree does not follow object ids
+            let i = (a.object_id as usize).min(b.object_id as usize);
+            let j = (a.object_id as usize).max(b.object_id as usize);
+            if i < j && j < object_count {
+                collisions.push((i, j));
             }
+            return true;
+        }
 
-            if !a_leaf && !b_leaf {
-                // Both internal
-                if a.left >= 0 && b.left >= 0 {
-                    stack.push((a.left, b.left));
-                }
-                if a.left >= 0 && b.right >= 0 {
-                    stack.push((a.left, b.right));
-                }
-                if a.right >= 0 && b.left >= 0 {
-                    stack.push((a.right, b.left));
-                }
-                if a.right >= 0 && b.right >= 0 {
-                    stack.push((a.right, b.right));
-                }
-            } else if a_leaf && !b_leaf {
-                // a is leaf, b is internal
-                if b.left >= 0 {
-                    stack.push((a_idx, b.left));
-                }
-                if b.right >= 0 {
-                    stack.push((a_idx, b.right));
-                }
-            } else if !a_leaf && b_leaf {
-                // a is internal, b is leaf
-                if a.left >= 0 {
-                    stack.push((a.left, b_idx));
-                }
+        // Expand children (index-based, no pointer chasing)
+        if a_idx == b_idx {
+            // Same node: expand unique child pairs without symmetry duplicates
+            if a.left >= 0 {
+                stack.push((a.left, a.left));
                 if a.right >= 0 {
-                    stack.push((a.right, b_idx));
+                    stack.push((a.left, a.right));
+                    stack.push((a.right, a.right));
                 }
             }
+            return true;
         }
 
-        let mut colliding_indices: Vec<usize> = visited
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, v)| if *v { Some(idx) } else { None })
-            .collect();
-        colliding_indices.sort_unstable();
-
-        (all_collisions, colliding_indices, total_checks)
+        if !a_leaf && !b_leaf {
+            // Both internal
+            if a.left >= 0 && b.left >= 0 {
+                stack.push((a.left, b.left));
+            }
+            if a.left >= 0 && b.right >= 0 {
+                stack.push((a.left, b.right));
+            }
+            if a.right >= 0 && b.left >= 0 {
+                stack.push((a.right, b.left));
+            }
+            if a.right >= 0 && b.right >= 0 {
+                stack.push((a.right, b.right));
+            }
+        } else if a_leaf && !b_leaf {
+            // a is leaf, b is internal
+            if b.left >= 0 {
+                stack.push((a_idx, b.left));
+            }
+            if b.right >= 0 {
+                stack.push((a_idx, b.right));
+            }
+        } else if !a_leaf && b_leaf {
+            // a is internal, b is leaf
+            if a.left >= 0 {
+                stack.push((a.left, b_idx));
+            }
+            if a.right >= 0 {
+                stack.push((a.right, b_idx));
+            }
+        }
+        true
     }
 
     /// Check for all collisions and return GUID pairs directly
@@ -721,6 +1485,9 @@ impl BVH {
         &self,
         bounding_boxes: &[BoundingBox],
     ) -> Vec<(String, String)> {
+        #[cfg(feature = "parallel")]
+        let collision_pairs = self.check_all_collisions_parallel(bounding_boxes.len());
+        #[cfg(not(feature = "parallel"))]
         let (collision_pairs, _, _) = self.check_all_collisions(bounding_boxes);
 
         // Convert indices to GUIDs
@@ -741,7 +1508,7 @@ impl BVH {
         origin: &Point,
         direction: &Vector,
         aabb: &BvhAABB,
-    ) -> Option<(f64, f64)> {
+    ) -> Option<(Scalar, Scalar)> {
         let min_x = aabb.cx - aabb.hx;
         let max_x = aabb.cx + aabb.hx;
         let min_y = aabb.cy - aabb.hy;
@@ -750,17 +1517,17 @@ impl BVH {
         let max_z = aabb.cz + aabb.hz;
 
         let invx = if direction.x() == 0.0 {
-            f64::INFINITY
+            Scalar::INFINITY
         } else {
             1.0 / direction.x()
         };
         let invy = if direction.y() == 0.0 {
-            f64::INFINITY
+            Scalar::INFINITY
         } else {
             1.0 / direction.y()
         };
         let invz = if direction.z() == 0.0 {
-            f64::INFINITY
+            Scalar::INFINITY
         } else {
             1.0 / direction.z()
         };
@@ -787,60 +1554,479 @@ impl BVH {
         }
     }
 
+    /// Collect leaves whose boxes are hit by a ray, ordered front to back.
+    ///
+    /// Children are visited nearest first. With `find_all` set every hit leaf is
+    /// returned sorted by the distance at which the ray enters its box; otherwise
+    /// only the leaf with the nearest box entry is returned and farther nodes are pruned.
     pub fn ray_cast(
         &self,
         origin: &Point,
         direction: &Vector,
         candidate_leaf_ids: &mut Vec<usize>,
-        _find_all: bool,
+        find_all: bool,
     ) -> bool {
         candidate_leaf_ids.clear();
+        candidate_leaf_ids.extend(
+            self.ray_box_entries(origin, direction, find_all)
+                .into_iter()
+                .map(|(id, _)| id),
+        );
+        !candidate_leaf_ids.is_empty()
+    }
+
+    /// Leaves whose boxes are hit by a ray with the ray parameter where each box
+    /// is entered (zero when the origin is inside), sorted nearest first.
+    ///
+    /// Since an object lies inside its box, the entry parameter is a lower bound
+    /// on the object hit, which lets callers stop at the first box beyond their best hit.
+    pub fn ray_cast_entries(&self, origin: &Point, direction: &Vector) -> Vec<(usize, Scalar)> {
+        self.ray_box_entries(origin, direction, true)
+    }
 
+    fn ray_box_entries(
+        &self,
+        origin: &Point,
+        direction: &Vector,
+        find_all: bool,
+    ) -> Vec<(usize, Scalar)> {
         // Use arena for fast index-based traversal
         if self.arena_root < 0 || self.arena.is_empty() {
-            return false;
+            return Vec::new();
         }
 
-        let root = &self.arena[self.arena_root as usize];
-        if let Some((_tmin, tmax)) = Self::ray_bvhaabb_intersect(origin, direction, &root.aabb) {
-            if tmax < 0.0 {
-                return false;
+        let entry = |node_idx: i32| -> Option<Scalar> {
+            let aabb = &self.arena[node_idx as usize].aabb;
+            match Self::ray_bvhaabb_intersect(origin, direction, aabb) {
+                Some((tmin, tmax)) if tmax >= 0.0 => Some(tmin.max(0.0)),
+                _ => None,
             }
-        } else {
-            return false;
+        };
+
+        let mut hits: Vec<(usize, Scalar)> = Vec::new();
+        let mut best = Scalar::INFINITY;
+        let mut stack: Vec<(i32, Scalar)> = Vec::with_capacity(64);
+        if let Some(t) = entry(self.arena_root) {
+            stack.push((self.arena_root, t));
         }
 
-        let mut stack: Vec<i32> = Vec::with_capacity(64);
-        stack.push(self.arena_root);
+        while let Some((node_idx, t)) = stack.pop() {
+            if !find_all && t > best {
+                continue;
+            }
+            let node = &self.arena[node_idx as usize];
 
-        while let Some(node_idx) = stack.pop() {
+            if node.object_id >= 0 {
+                // Leaf node
+                if find_all {
+                    hits.push((node.object_id as usize, t));
+                } else if t < best {
+                    best = t;
+                    hits.clear();
+                    hits.push((node.object_id as usize, t));
+                }
+                continue;
+            }
+
+            // Internal node: push the farther child first so the nearer one is popped next
+            let left = entry(node.left).map(|t| (node.left, t));
+            let right = entry(node.right).map(|t| (node.right, t));
+            match (left, right) {
+                (Some(l), Some(r)) if l.1 <= r.1 => stack.extend([r, l]),
+                (Some(l), Some(r)) => stack.extend([l, r]),
+                (Some(child), None) | (None, Some(child)) => stack.push(child),
+                (None, None) => {}
+            }
+        }
+
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits
+    }
+
+    /// Find the nearest actual hit along a ray, testing leaves front to back.
+    ///
+    /// `hit` is called with a leaf's object id and returns the ray parameter of
+    /// the object hit, if any. Nodes whose boxes are entered beyond the best hit
+    /// so far are skipped, so usually only a few leaves are tested.
+    ///
+    /// # Returns
+    /// Object id and ray parameter of the nearest hit.
+    pub fn ray_cast_nearest<F>(
+        &self,
+        origin: &Point,
+        direction: &Vector,
+        mut hit: F,
+    ) -> Option<(usize, Scalar)>
+    where
+        F: FnMut(usize) -> Option<Scalar>,
+    {
+        self.nearest_along_ray(origin, direction, [0.0; 3], |id, _| hit(id))
+    }
+
+    /// Best-first traversal along a ray against node boxes inflated by `expand`.
+    /// `hit` receives the object id and the entry parameter into its inflated box.
+    fn nearest_along_ray<F>(
+        &self,
+        origin: &Point,
+        direction: &Vector,
+        expand: [Scalar; 3],
+        mut hit: F,
+    ) -> Option<(usize, Scalar)>
+    where
+        F: FnMut(usize, Scalar) -> Option<Scalar>,
+    {
+        if self.arena_root < 0 || self.arena.is_empty() {
+            return None;
+        }
+
+        let entry = |node_idx: i32| -> Option<Scalar> {
+            let mut aabb = self.arena[node_idx as usize].aabb;
+            aabb.hx += expand[0];
+            aabb.hy += expand[1];
+            aabb.hz += expand[2];
+            match Self::ray_bvhaabb_intersect(origin, direction, &aabb) {
+                Some((tmin, tmax)) if tmax >= 0.0 => Some(tmin.max(0.0)),
+                _ => None,
+            }
+        };
+
+        let mut best: Option<(usize, Scalar)> = None;
+        let mut heap = BinaryHeap::new();
+        if let Some(t) = entry(self.arena_root) {
+            heap.push(Reverse(QueueEntry {
+                priority: t,
+                node: self.arena_root,
+            }));
+        }
+
+        while let Some(Reverse(QueueEntry {
+            priority: t,
+            node: node_idx,
+        })) = heap.pop()
+        {
+            if best.is_some_and(|(_, best_t)| t > best_t) {
+                break;
+            }
             let node = &self.arena[node_idx as usize];
+            if node.object_id >= 0 {
+                let id = node.object_id as usize;
+                if let Some(t_hit) = hit(id, t) {
+                    if t_hit >= 0.0 && best.is_none_or(|(_, best_t)| t_hit < best_t) {
+                        best = Some((id, t_hit));
+                    }
+                }
+                continue;
+            }
+            for child in [node.left, node.right] {
+                if let Some(t) = entry(child) {
+                    heap.push(Reverse(QueueEntry {
+                        priority: t,
+                        node: child,
+                    }));
+                }
+            }
+        }
 
-            if let Some((_tmin, tmax)) = Self::ray_bvhaabb_intersect(origin, direction, &node.aabb)
-            {
-                if tmax < 0.0 {
-                    continue;
+        best
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Sweep Queries
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Sweep a sphere along a straight path and find the first object box it touches.
+    ///
+    /// # Arguments
+    /// * `origin` - Sphere center at time 0
+    /// * `direction` - Translation of the center from time 0 to time 1
+    /// * `radius` - Sphere radius
+    ///
+    /// # Returns
+    /// Object id and time of impact in [0, 1], or None if the path is clear.
+    pub fn sweep_sphere(
+        &self,
+        origin: &Point,
+        direction: &Vector,
+        radius: Scalar,
+    ) -> Option<(usize, Scalar)> {
+        self.sweep_capsule(origin, origin, direction, radius)
+    }
+
+    /// Sweep a capsule along a straight path and find the first object box it touches.
+    ///
+    /// Leaves are visited in order of the time at which the capsule's bounds
+    /// reach them; the exact contact time is then found by conservative
+    /// advancement on the segment-to-box distance.
+    ///
+    /// # Arguments
+    /// * `start` - First endpoint of the capsule axis at time 0
+    /// * `end` - Second endpoint of the capsule axis at time 0
+    /// * `direction` - Translation of the capsule from time 0 to time 1
+    /// * `radius` - Capsule radius
+    ///
+    /// # Returns
+    /// Object id and time of impact in [0, 1], or None if the path is clear.
+    pub fn sweep_capsule(
+        &self,
+        start: &Point,
+        end: &Point,
+        direction: &Vector,
+        radius: Scalar,
+    ) -> Option<(usize, Scalar)> {
+        let speed = direction.compute_length();
+        let center = Point::new(
+            (start.x() + end.x()) * 0.5,
+            (start.y() + end.y()) * 0.5,
+            (start.z() + end.z()) * 0.5,
+        );
+        let expand = [
+            (end.x() - start.x()).abs() * 0.5 + radius,
+            (end.y() - start.y()).abs() * 0.5 + radius,
+            (end.z() - start.z()).abs() * 0.5 + radius,
+        ];
+        let a = [start.x(), start.y(), start.z()];
+        let b = [end.x(), end.y(), end.z()];
+        let d = [direction.x(), direction.y(), direction.z()];
+        let reach = a.iter().chain(&b).fold(0.0, |m: Scalar, v| m.max(v.abs())) + speed + radius;
+
+        let hit = self.nearest_along_ray(&center, direction, expand, |id, t_entry| {
+            let leaf = self.leaf_nodes.get(id).copied().filter(|&leaf| leaf >= 0)?;
+            let aabb = self.arena[leaf as usize].aabb;
+            // Contact within the rounding error of the coordinates involved
+            let scale = reach
+                .max(aabb.cx.abs() + aabb.hx)
+                .max(aabb.cy.abs() + aabb.hy)
+                .max(aabb.cz.abs() + aabb.hz);
+            let contact = Tolerance::ABSOLUTE.max(scale * 16.0 * Scalar::EPSILON);
+            let gap = |t: Scalar| {
+                let moved = |p: [Scalar; 3]| [p[0] + d[0] * t, p[1] + d[1] * t, p[2] + d[2] * t];
+                aabb.segment_distance_squared(moved(a), moved(b)).sqrt() - radius
+            };
+
+            // Every point moves at `speed`, so the gap closes at most that fast
+            let mut t = t_entry;
+            for _ in 0..100 {
+                if t > 1.0 {
+                    return None;
                 }
+                let g = gap(t);
+                if g <= contact {
+                    return Some(t);
+                }
+                if speed <= 0.0 {
+                    return None;
+                }
+                t += g / speed;
+            }
+            None
+        })?;
+        (hit.1 <= 1.0).then_some(hit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Statistics and Validation
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Approximate heap and inline memory used by the BVH in bytes.
+    pub fn memory_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.arena.capacity() * std::mem::size_of::<FlatNode>()
+            + (self.leaf_nodes.capacity() + self.free_nodes.capacity()) * std::mem::size_of::<i32>()
+            + self
+                .object_guids
+                .iter()
+                .map(|g| std::mem::size_of::<String>() + g.capacity())
+                .sum::<usize>()
+    }
+
+    /// Collect tree shape and quality metrics.
+    ///
+    /// The SAH cost uses unit traversal and intersection costs, so it equals the
+    /// expected number of node visits for a random ray hitting the root box.
+    /// Higher overlap and cost than a fresh build usually mean the tree should
+    /// be rebuilt after many incremental updates.
+    pub fn stats(&self) -> BvhStats {
+        let mut stats = BvhStats {
+            memory_bytes: self.memory_bytes(),
+            ..Default::default()
+        };
+        if self.arena_root < 0 || self.arena.is_empty() {
+            return stats;
+        }
+
+        let root_area = self.arena[self.arena_root as usize].aabb.surface_area();
+        let mut leaves: Vec<BvhAABB> = Vec::new();
+        let mut stack = vec![(self.arena_root, 0usize)];
+        while let Some((idx, depth)) = stack.pop() {
+            let node = &self.arena[idx as usize];
+            stats.node_count += 1;
+            if stats.depth_histogram.len() <= depth {
+                stats.depth_histogram.resize(depth + 1, 0);
+            }
+            stats.depth_histogram[depth] += 1;
+            if root_area > 0.0 {
+                stats.sah_cost += node.aabb.surface_area() / root_area;
+            }
+            if node.object_id >= 0 {
+                leaves.push(node.aabb);
             } else {
-                continue;
+                stack.push((node.left, depth + 1));
+                stack.push((node.right, depth + 1));
+            }
+        }
+        stats.leaf_count = leaves.len();
+        stats.max_depth = stats.depth_histogram.len() - 1;
+
+        // Overlapping leaf pairs found through the tree itself
+        let mut overlaps = 0usize;
+        for leaf in &leaves {
+            let mut stack = vec![self.arena_root];
+            while let Some(idx) = stack.pop() {
+                let node = &self.arena[idx as usize];
+                if !node.aabb.intersects(leaf) {
+                    continue;
+                }
+                if node.object_id >= 0 {
+                    overlaps += 1;
+                } else {
+                    stack.push(node.left);
+                    stack.push(node.right);
+                }
             }
+        }
+        // Each leaf overlaps itself
+        stats.average_leaf_overlap = (overlaps - leaves.len()) as Scalar / leaves.len() as Scalar;
+        stats
+    }
+
+    /// Check that the arena forms a single consistent tree.
+    ///
+    /// Verifies child and parent links, that every object appears in at most one
+    /// leaf, that leaf ids index `object_guids` when GUIDs are present and that
+    /// each internal box contains its children.
+    ///
+    /// # Returns
+    /// Ok, or a description of the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.arena_root < 0 {
+            return Ok(());
+        }
+        let n = self.arena.len() as i32;
+        if self.arena_root >= n {
+            return Err(format!("root index {} out of range", self.arena_root));
+        }
+        if self.arena[self.arena_root as usize].parent != -1 {
+            return Err("root node has a parent".to_string());
+        }
+
+        let mut visited = vec![false; self.arena.len()];
+        let mut seen_objects: Vec<usize> = Vec::new();
+        let mut stack = vec![self.arena_root];
+        while let Some(idx) = stack.pop() {
+            if visited[idx as usize] {
+                return Err(format!("node {idx} is reachable twice"));
+            }
+            visited[idx as usize] = true;
+            let node = &self.arena[idx as usize];
 
             if node.object_id >= 0 {
-                // Leaf node
-                candidate_leaf_ids.push(node.object_id as usize);
+                if node.left != -1 || node.right != -1 {
+                    return Err(format!("leaf node {idx} has children"));
+                }
+                let id = node.object_id as usize;
+                if !self.object_guids.is_empty() && id >= self.object_guids.len() {
+                    return Err(format!("object id {id} has no GUID"));
+                }
+                seen_objects.push(id);
                 continue;
             }
 
-            // Internal node: push children
-            if node.left >= 0 {
-                stack.push(node.left);
+            for child in [node.left, node.right] {
+                if child < 0 || child >= n {
+                    return Err(format!("node {idx} has invalid child {child}"));
+                }
+                let c = &self.arena[child as usize];
+                if c.parent != idx {
+                    return Err(format!("node {child} does not link back to parent {idx}"));
+                }
+                let (a, b) = (&node.aabb, &c.aabb);
+                // Boxes are stored as center and half size, so bounds carry
+                // rounding error relative to their magnitude
+                let scale = (a.cx.abs() + a.hx)
+                    .max(a.cy.abs() + a.hy)
+                    .max(a.cz.abs() + a.hz);
+                let tolerance = Tolerance::ABSOLUTE.max(scale * 16.0 * Scalar::EPSILON);
+                let contained = (a.cx - a.hx) <= (b.cx - b.hx) + tolerance
+                    && (a.cy - a.hy) <= (b.cy - b.hy) + tolerance
+                    && (a.cz - a.hz) <= (b.cz - b.hz) + tolerance
+                    && (a.cx + a.hx) >= (b.cx + b.hx) - tolerance
+                    && (a.cy + a.hy) >= (b.cy + b.hy) - tolerance
+                    && (a.cz + a.hz) >= (b.cz + b.hz) - tolerance;
+                if !contained {
+                    return Err(format!("node {child} is not inside its parent {idx}"));
+                }
+                stack.push(child);
             }
-            if node.right >= 0 {
+        }
+
+        seen_objects.sort_unstable();
+        if let Some(w) = seen_objects.windows(2).find(|w| w[0] == w[1]) {
+            return Err(format!("object id {} appears in several leaves", w[0]));
+        }
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // JSON
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    // Recompute object-to-leaf links and free slots from the arena
+    fn rebuild_leaf_links(&mut self) {
+        let object_count = self
+            .arena
+            .iter()
+            .map(|node| node.object_id + 1)
+            .max()
+            .unwrap_or(0)
+            .max(0) as usize;
+        self.leaf_nodes = vec![-1; object_count.max(self.object_guids.len())];
+        let mut reachable = vec![false; self.arena.len()];
+        let mut stack: Vec<i32> = if self.arena_root >= 0 {
+            vec![self.arena_root]
+        } else {
+            Vec::new()
+        };
+        while let Some(idx) = stack.pop() {
+            reachable[idx as usize] = true;
+            let node = &self.arena[idx as usize];
+            if node.object_id >= 0 {
+                self.leaf_nodes[node.object_id as usize] = idx;
+            } else {
+                stack.push(node.left);
                 stack.push(node.right);
             }
         }
+        self.free_nodes = (0..self.arena.len() as i32)
+            .filter(|&idx| !reachable[idx as usize])
+            .collect();
+    }
 
-        !candidate_leaf_ids.is_empty()
+    pub fn jsondump(&self) -> Result<String, SessionError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
+        Ok(serde_json::from_str(json_data)?)
+    }
+
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
+        std::fs::write(filepath, self.jsondump()?)?;
+        Ok(())
+    }
+
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
+        let json_data = std::fs::read_to_string(filepath)?;
+        Self::jsonload(&json_data).map_err(|e| e.with_path(filepath))
     }
 }
 
@@ -854,7 +2040,7 @@ pub fn expand_bits(v: u32) -> u32 {
     v
 }
 
-pub fn calculate_morton_code(x: f64, y: f64, z: f64, world_size: f64) -> u32 {
+pub fn calculate_morton_code(x: Scalar, y: Scalar, z: Scalar, world_size: Scalar) -> u32 {
     // Normalize coordinates to [0,1] range
     let nx = (x + world_size / 2.0) / world_size;
     let ny = (y + world_size / 2.0) / world_size;
@@ -3,6 +3,7 @@ use crate::boundingbox::BoundingBox;
 /// These tests match the Python test suite in bvh_test.py
 use crate::bvh::*;
 use crate::point::Point;
+use crate::scalar::{test_tolerance, Scalar};
 use crate::vector::Vector;
 use rand::prelude::*;
 use std::time::Instant;
@@ -281,6 +282,43 @@ mod tests {
         assert!(checks > 0);
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_bvh_check_all_collisions_parallel() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let bboxes: Vec<BoundingBox> = (0..2000)
+            .map(|_| {
+                BoundingBox::new(
+                    Point::new(
+                        rng.gen_range(-50.0..50.0),
+                        rng.gen_range(-50.0..50.0),
+                        rng.gen_range(-50.0..50.0),
+                    ),
+                    Vector::new(1.0, 0.0, 0.0),
+                    Vector::new(0.0, 1.0, 0.0),
+                    Vector::new(0.0, 0.0, 1.0),
+                    Vector::new(2.0, 2.0, 2.0),
+                )
+            })
+            .collect();
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+
+        let (mut sequential, _, _) = bvh.check_all_collisions(&bboxes);
+        let parallel = bvh.check_all_collisions_parallel(bboxes.len());
+        let mut sorted = parallel.clone();
+        sorted.sort_unstable();
+        sequential.sort_unstable();
+        assert_eq!(sorted, sequential);
+
+        // The order does not depend on the number of threads
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let single = pool.install(|| bvh.check_all_collisions_parallel(bboxes.len()));
+        assert_eq!(single, parallel);
+    }
+
     #[test]
     fn test_bvh_merge_aabb() {
         // Test AABB merging.
@@ -417,11 +455,429 @@ mod tests {
         println!("Naive would need: {naive_checks} checks");
         println!(
             "Check reduction: {:.1}%",
-            100.0 * (1.0 - checks as f64 / naive_checks as f64)
+            100.0 * (1.0 - checks as Scalar / naive_checks as Scalar)
         );
 
         // Should find some collisions
         assert!(!collisions.is_empty());
         assert!(!colliding_indices.is_empty());
     }
+
+    fn random_boxes(rng: &mut StdRng, count: usize) -> Vec<BoundingBox> {
+        (0..count)
+            .map(|_| {
+                BoundingBox::new(
+                    Point::new(
+                        rng.gen_range(-20.0..20.0),
+                        rng.gen_range(-20.0..20.0),
+                        rng.gen_range(-20.0..20.0),
+                    ),
+                    Vector::new(1.0, 0.0, 0.0),
+                    Vector::new(0.0, 1.0, 0.0),
+                    Vector::new(0.0, 0.0, 1.0),
+                    Vector::new(
+                        rng.gen_range(0.5..3.0),
+                        rng.gen_range(0.5..3.0),
+                        rng.gen_range(0.5..3.0),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn sorted_pairs(bvh: &BVH, bboxes: &[BoundingBox]) -> Vec<(usize, usize)> {
+        let (mut pairs, _, _) = bvh.check_all_collisions(bboxes);
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn test_bvh_insert_remove_matches_rebuild() {
+        // Incremental updates should find the same pairs as a full rebuild.
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut bboxes = random_boxes(&mut rng, 60);
+
+        let mut bvh = BVH::from_boxes(&bboxes[..40], 100.0);
+        for (i, bbox) in bboxes.iter().enumerate().skip(40) {
+            bvh.insert(bbox, i);
+        }
+        assert_eq!(
+            sorted_pairs(&bvh, &bboxes),
+            sorted_pairs(&BVH::from_boxes(&bboxes, 100.0), &bboxes)
+        );
+
+        // Removed objects are no longer reported; moved objects are reinserted
+        for i in (0..60).step_by(3) {
+            assert!(bvh.remove(i));
+        }
+        assert!(!bvh.remove(0));
+        for i in (1..60).step_by(3) {
+            bboxes[i].center = Point::new(
+                rng.gen_range(-20.0..20.0),
+                rng.gen_range(-20.0..20.0),
+                rng.gen_range(-20.0..20.0),
+            );
+            bvh.insert(&bboxes[i], i);
+        }
+
+        let mut expected = Vec::new();
+        for i in 0..60 {
+            for j in (i + 1)..60 {
+                if i % 3 != 0 && j % 3 != 0 && bvh.aabb_intersect(&bboxes[i], &bboxes[j]) {
+                    expected.push((i, j));
+                }
+            }
+        }
+        assert_eq!(sorted_pairs(&bvh, &bboxes), expected);
+
+        let mut candidates = Vec::new();
+        bvh.ray_cast(
+            &Point::new(-50.0, 0.0, 0.0),
+            &Vector::new(1.0, 0.0, 0.0),
+            &mut candidates,
+            true,
+        );
+        assert!(candidates.iter().all(|&id| id % 3 != 0));
+    }
+
+    #[test]
+    fn test_bvh_refit() {
+        // Refitting after moving every box should match a full rebuild.
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut bboxes = random_boxes(&mut rng, 50);
+        let mut bvh = BVH::from_boxes(&bboxes, 100.0);
+
+        for bbox in bboxes.iter_mut() {
+            bbox.center = Point::new(
+                bbox.center.x() + 1.5,
+                bbox.center.y() - 2.0,
+                bbox.center.z() * 0.5,
+            );
+        }
+        bvh.refit(&bboxes);
+
+        assert_eq!(
+            sorted_pairs(&bvh, &bboxes),
+            sorted_pairs(&BVH::from_boxes(&bboxes, 100.0), &bboxes)
+        );
+    }
+
+    #[test]
+    fn test_bvh_from_boxes_parallel_matches_sequential() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let bboxes = random_boxes(&mut rng, 5000);
+        let sequential = BVH::from_boxes(&bboxes, 100.0);
+        let parallel = BVH::from_boxes_parallel(&bboxes, 100.0);
+
+        assert_eq!(
+            sorted_pairs(&parallel, &bboxes),
+            sorted_pairs(&sequential, &bboxes)
+        );
+
+        let origin = Point::new(-50.0, 1.0, 2.0);
+        let direction = Vector::new(1.0, 0.1, -0.05);
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+        sequential.ray_cast(&origin, &direction, &mut a, true);
+        parallel.ray_cast(&origin, &direction, &mut b, true);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_bvh_knn_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let bboxes = random_boxes(&mut rng, 300);
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+
+        let box_distance = |b: &BoundingBox, p: &Point| {
+            let dx = ((p.x() - b.center.x()).abs() - b.half_size.x()).max(0.0);
+            let dy = ((p.y() - b.center.y()).abs() - b.half_size.y()).max(0.0);
+            let dz = ((p.z() - b.center.z()).abs() - b.half_size.z()).max(0.0);
+            (dx * dx + dy * dy + dz * dz).sqrt()
+        };
+
+        for _ in 0..20 {
+            let query = Point::new(
+                rng.gen_range(-30.0..30.0),
+                rng.gen_range(-30.0..30.0),
+                rng.gen_range(-30.0..30.0),
+            );
+            let mut expected: Vec<Scalar> =
+                bboxes.iter().map(|b| box_distance(b, &query)).collect();
+            expected.sort_by(|a, b| a.total_cmp(b));
+
+            let found = bvh.knn(&query, 5);
+            assert_eq!(found.len(), 5);
+            for ((id, distance), want) in found.iter().zip(expected.iter()) {
+                assert!((distance - want).abs() < 1e-9);
+                assert!((box_distance(&bboxes[*id], &query) - distance).abs() < 1e-9);
+            }
+
+            let (_, nearest) = bvh.nearest(&query).unwrap();
+            assert!((nearest - expected[0]).abs() < 1e-9);
+        }
+
+        assert!(BVH::new().nearest(&Point::new(0.0, 0.0, 0.0)).is_none());
+        assert_eq!(bvh.knn(&Point::new(0.0, 0.0, 0.0), 1000).len(), 300);
+    }
+
+    fn box_frustum(min: &Point, max: &Point) -> [crate::Plane; 6] {
+        // Axis-aligned region expressed as six inward-facing planes
+        let plane = |p: &Point, n: Vector| crate::Plane::from_point_normal(p.clone(), n);
+        [
+            plane(min, Vector::new(1.0, 0.0, 0.0)),
+            plane(min, Vector::new(0.0, 1.0, 0.0)),
+            plane(min, Vector::new(0.0, 0.0, 1.0)),
+            plane(max, Vector::new(-1.0, 0.0, 0.0)),
+            plane(max, Vector::new(0.0, -1.0, 0.0)),
+            plane(max, Vector::new(0.0, 0.0, -1.0)),
+        ]
+    }
+
+    #[test]
+    fn test_bvh_query_box_and_frustum() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let bboxes = random_boxes(&mut rng, 400);
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+
+        let min = Point::new(-5.0, -8.0, -3.0);
+        let max = Point::new(7.0, 4.0, 6.0);
+        let region = BoundingBox::from_points(&[min.clone(), max.clone()], 0.0);
+        let mut expected: Vec<usize> = (0..bboxes.len())
+            .filter(|&i| bvh.aabb_intersect(&region, &bboxes[i]))
+            .collect();
+        expected.sort_unstable();
+        assert!(!expected.is_empty());
+
+        let mut found = bvh.query_box(&region);
+        found.sort_unstable();
+        assert_eq!(found, expected);
+
+        let mut found = bvh.query_frustum(&box_frustum(&min, &max));
+        found.sort_unstable();
+        assert_eq!(found, expected);
+
+        // Boxes are flagged inside only when no plane cuts them
+        for (id, inside) in bvh.query_frustum_classified(&box_frustum(&min, &max)) {
+            let (lo, hi) = (bboxes[id].min_point(), bboxes[id].max_point());
+            let contained = lo.x() >= min.x()
+                && lo.y() >= min.y()
+                && lo.z() >= min.z()
+                && hi.x() <= max.x()
+                && hi.y() <= max.y()
+                && hi.z() <= max.z();
+            assert_eq!(inside, contained);
+        }
+
+        // A frustum with an empty intersection selects nothing
+        let far = box_frustum(
+            &Point::new(100.0, 100.0, 100.0),
+            &Point::new(101.0, 101.0, 101.0),
+        );
+        assert!(bvh.query_frustum(&far).is_empty());
+    }
+
+    #[test]
+    fn test_bvh_ray_cast_ordered_and_nearest() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let bboxes = random_boxes(&mut rng, 300);
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+        let origin = Point::new(-40.0, 0.5, -0.5);
+        let direction = Vector::new(1.0, 0.0, 0.0);
+
+        // All candidates come front to back by box entry
+        let entries = bvh.ray_cast_entries(&origin, &direction);
+        assert!(entries.len() > 1);
+        assert!(entries.windows(2).all(|w| w[0].1 <= w[1].1));
+        for (id, t) in &entries {
+            let entry = bboxes[*id].center.x() - bboxes[*id].half_size.x() - origin.x();
+            assert!((entry - t).abs() < 1e-9);
+        }
+
+        let mut all = Vec::new();
+        bvh.ray_cast(&origin, &direction, &mut all, true);
+        let mut first = Vec::new();
+        assert!(bvh.ray_cast(&origin, &direction, &mut first, false));
+        assert_eq!(first, vec![all[0]]);
+
+        // Only every other box counts as a hit, at its far side
+        let hit = |id: usize| {
+            id.is_multiple_of(2)
+                .then(|| bboxes[id].center.x() + bboxes[id].half_size.x() - origin.x())
+        };
+        let expected = all
+            .iter()
+            .filter_map(|&id| hit(id).map(|t| (id, t)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let mut tested = 0;
+        let nearest = bvh.ray_cast_nearest(&origin, &direction, |id| {
+            tested += 1;
+            hit(id)
+        });
+        assert_eq!(nearest.map(|(_, t)| t), expected.map(|(_, t)| t));
+        assert!(tested <= all.len());
+    }
+
+    fn unit_box(center: Point) -> BoundingBox {
+        BoundingBox::new(
+            center,
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(1.0, 1.0, 1.0),
+        )
+    }
+
+    #[test]
+    fn test_bvh_sweep_sphere() {
+        let bboxes = vec![
+            unit_box(Point::new(10.0, 0.0, 0.0)),
+            unit_box(Point::new(5.0, 0.0, 0.0)),
+            unit_box(Point::new(5.0, 10.0, 0.0)),
+        ];
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+
+        // Face contact: the sphere front reaches x = 4 when the center is at x = 3.5
+        let origin = Point::new(0.0, 0.0, 0.0);
+        let (id, t) = bvh
+            .sweep_sphere(&origin, &Vector::new(20.0, 0.0, 0.0), 0.5)
+            .unwrap();
+        assert_eq!(id, 1);
+        assert!((t - 3.5 / 20.0).abs() < test_tolerance(1e-6));
+
+        // Edge contact: passing one unit beside the box touches its edge at x = 4, y = 1
+        // only with a radius above one
+        let offset = Point::new(0.0, 2.0, 0.0);
+        let path = Vector::new(20.0, 0.0, 0.0);
+        assert!(bvh.sweep_sphere(&offset, &path, 0.9).is_none());
+        let (id, t) = bvh.sweep_sphere(&offset, &path, 1.5).unwrap();
+        assert_eq!(id, 1);
+        // Contact with the edge x = 4, y = 1: (4 - x)^2 + 1 = 1.5^2
+        let x = 4.0 - Scalar::sqrt(1.5 * 1.5 - 1.0);
+        assert!((t - x / 20.0).abs() < test_tolerance(1e-6));
+
+        // Too short a path, and a sphere starting in contact
+        assert!(bvh
+            .sweep_sphere(&origin, &Vector::new(2.0, 0.0, 0.0), 0.5)
+            .is_none());
+        let (id, t) = bvh
+            .sweep_sphere(&Point::new(5.0, 0.0, 0.0), &path, 0.5)
+            .unwrap();
+        assert_eq!((id, t), (1, 0.0));
+    }
+
+    #[test]
+    fn test_bvh_sweep_capsule() {
+        let bboxes = vec![unit_box(Point::new(5.0, 0.0, 0.0))];
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+
+        // Vertical capsule above the box: the sphere alone would pass by
+        let start = Point::new(0.0, 0.0, 3.0);
+        let end = Point::new(0.0, 0.0, 10.0);
+        let path = Vector::new(20.0, 0.0, -10.0);
+        assert!(bvh.sweep_sphere(&end, &path, 0.25).is_none());
+        let (id, t) = bvh.sweep_capsule(&start, &end, &path, 0.25).unwrap();
+        assert_eq!(id, 0);
+
+        // At impact the capsule is exactly `radius` away from the box
+        let moved_start = Point::new(start.x() + 20.0 * t, 0.0, start.z() - 10.0 * t);
+        let moved_end = Point::new(end.x() + 20.0 * t, 0.0, end.z() - 10.0 * t);
+        let axis = crate::Line::from_points(&moved_start, &moved_end);
+        let gap = (0..=1000)
+            .map(|i| crate::distance::distance(&axis.point_at(i as Scalar / 1000.0), &bboxes[0]))
+            .map(|d| d.distance)
+            .fold(Scalar::INFINITY, Scalar::min);
+        assert!((gap - 0.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bvh_json_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let bboxes = random_boxes(&mut rng, 50);
+        let boxes_with_guids: Vec<(BoundingBox, String)> = bboxes
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.clone(), format!("object_{i}")))
+            .collect();
+        let mut bvh = BVH::new();
+        bvh.build_with_guids(&boxes_with_guids);
+        assert!(bvh.remove(7));
+        assert!(bvh.validate().is_ok());
+
+        let json = bvh.jsondump().unwrap();
+        let mut loaded = BVH::jsonload(&json).unwrap();
+        assert!(loaded.validate().is_ok());
+        assert_eq!(loaded.guid, bvh.guid);
+        assert_eq!(loaded.object_guids, bvh.object_guids);
+        assert_eq!(
+            loaded.check_all_collisions_guids(&bboxes),
+            bvh.check_all_collisions_guids(&bboxes)
+        );
+        let query = Point::new(1.0, 2.0, 3.0);
+        assert_eq!(loaded.knn(&query, 5), bvh.knn(&query, 5));
+
+        // Incremental updates keep working after loading
+        loaded.insert(&bboxes[7], 7);
+        assert!(loaded.remove(3));
+        assert!(loaded.validate().is_ok());
+
+        // Broken links are rejected
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["links"][0] = serde_json::json!(1000);
+        assert!(BVH::jsonload(&value.to_string()).is_err());
+        value["links"] = serde_json::json!([-1, -1, -1]);
+        assert!(BVH::jsonload(&value.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_bvh_stats() {
+        let empty = BVH::new().stats();
+        assert_eq!(empty.node_count, 0);
+        assert!(empty.depth_histogram.is_empty());
+
+        let mut rng = StdRng::seed_from_u64(9);
+        let bboxes = random_boxes(&mut rng, 64);
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+        let stats = bvh.stats();
+
+        assert_eq!(stats.leaf_count, 64);
+        assert_eq!(stats.node_count, 2 * 64 - 1);
+        assert_eq!(stats.depth_histogram[0], 1);
+        assert_eq!(
+            stats.depth_histogram.iter().sum::<usize>(),
+            stats.node_count
+        );
+        assert_eq!(stats.max_depth + 1, stats.depth_histogram.len());
+        assert!(stats.max_depth >= 6);
+        assert!(stats.sah_cost >= 1.0);
+        assert!(stats.memory_bytes > stats.node_count * 4 * std::mem::size_of::<i32>());
+
+        // Average overlap matches the collision pairs found by the tree
+        let (pairs, _, _) = bvh.check_all_collisions(&bboxes);
+        assert!((stats.average_leaf_overlap - 2.0 * pairs.len() as Scalar / 64.0).abs() < 1e-12);
+        assert!(bvh.validate().is_ok());
+    }
+
+    #[test]
+    fn test_bvh_query_sphere() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let bboxes = random_boxes(&mut rng, 80);
+        let bvh = BVH::from_boxes(&bboxes, 100.0);
+        let center = Point::new(2.0, -3.0, 1.0);
+
+        let found = bvh.query_sphere(&center, 8.0);
+        assert!(found.windows(2).all(|w| w[0].1 <= w[1].1));
+        let mut ids: Vec<usize> = found.iter().map(|(id, _)| *id).collect();
+        ids.sort_unstable();
+
+        let expected: Vec<usize> = (0..bboxes.len())
+            .filter(|&i| {
+                let (lo, hi) = (bboxes[i].min_point(), bboxes[i].max_point());
+                let dx = (lo.x() - center.x()).max(center.x() - hi.x()).max(0.0);
+                let dy = (lo.y() - center.y()).max(center.y() - hi.y()).max(0.0);
+                let dz = (lo.z() - center.z()).max(center.z() - hi.z()).max(0.0);
+                (dx * dx + dy * dy + dz * dz).sqrt() <= 8.0
+            })
+            .collect();
+        assert_eq!(ids, expected);
+    }
 }
@@ -0,0 +1,406 @@
+//! C ABI for creating, querying and saving sessions from C and C++.
+//!
+//! Sessions are opaque pointers owned by the caller and released with
+//! `session_free`. Functions that can fail return `false` or null and leave
+//! a message for `session_last_error`. GUIDs are returned in fixed-size,
+//! NUL-terminated `SessionGuid` buffers so no Rust memory crosses the boundary,
+//! except for strings from `session_jsondump`, released with `session_string_free`.
+
+use crate::scalar::to_f64;
+use crate::{Line, Mesh, Point, Scalar, Session, Vector};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+
+/// Length of a GUID including the terminating NUL.
+pub const SESSION_GUID_LENGTH: usize = 37;
+
+/// A NUL-terminated GUID.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SessionGuid {
+    pub value: [c_char; SESSION_GUID_LENGTH],
+}
+
+/// An object hit by `session_ray_cast`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SessionRayHit {
+    pub guid: SessionGuid,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub distance: f64,
+}
+
+/// A pair of objects reported by `session_get_collisions`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SessionCollision {
+    pub first: SessionGuid,
+    pub second: SessionGuid,
+}
+
+impl SessionGuid {
+    fn new(guid: &str) -> Self {
+        let mut value = [0; SESSION_GUID_LENGTH];
+        for (slot, byte) in value
+            .iter_mut()
+            .zip(guid.bytes().take(SESSION_GUID_LENGTH - 1))
+        {
+            *slot = byte as c_char;
+        }
+        SessionGuid { value }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(message: impl ToString) {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+/// Read a C string argument, recording an error for null pointers and invalid UTF-8.
+unsafe fn str_arg<'a>(value: *const c_char, name: &str) -> Option<&'a str> {
+    if value.is_null() {
+        set_error(format!("{name} is null"));
+        return None;
+    }
+    match CStr::from_ptr(value).to_str() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            set_error(format!("{name} is not valid UTF-8"));
+            None
+        }
+    }
+}
+
+/// Borrow the session behind a handle, recording an error for null handles.
+unsafe fn session_arg<'a>(session: *mut Session) -> Option<&'a mut Session> {
+    if session.is_null() {
+        set_error("session is null");
+    }
+    session.as_mut()
+}
+
+unsafe fn write_guid(out: *mut SessionGuid, guid: &str) {
+    if !out.is_null() {
+        *out = SessionGuid::new(guid);
+    }
+}
+
+/// The message of the last failed call on this thread, or null.
+///
+/// The pointer stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn session_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Create an empty session.
+///
+/// # Safety
+/// `name` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn session_new(name: *const c_char) -> *mut Session {
+    match str_arg(name, "name") {
+        Some(name) => Box::into_raw(Box::new(Session::new(name))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Release a session created by this library. Null is ignored.
+///
+/// # Safety
+/// `session` must be null or a handle that was not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn session_free(session: *mut Session) {
+    if !session.is_null() {
+        drop(Box::from_raw(session));
+    }
+}
+
+/// Load a session from a JSON file, or return null on failure.
+///
+/// # Safety
+/// `filepath` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn session_from_json(filepath: *const c_char) -> *mut Session {
+    let Some(filepath) = str_arg(filepath, "filepath") else {
+        return std::ptr::null_mut();
+    };
+    match Session::from_json(filepath) {
+        Ok(session) => Box::into_raw(Box::new(session)),
+        Err(error) => {
+            set_error(error);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Load a session from a JSON string, or return null on failure.
+///
+/// # Safety
+/// `json` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn session_jsonload(json: *const c_char) -> *mut Session {
+    let Some(json) = str_arg(json, "json") else {
+        return std::ptr::null_mut();
+    };
+    match Session::jsonload(json) {
+        Ok(session) => Box::into_raw(Box::new(session)),
+        Err(error) => {
+            set_error(error);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Serialize a session to a JSON string, to be released with `session_string_free`.
+///
+/// # Safety
+/// `session` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn session_jsondump(session: *mut Session) -> *mut c_char {
+    let Some(session) = session_arg(session) else {
+        return std::ptr::null_mut();
+    };
+    match session.jsondump() {
+        Ok(json) => CString::new(json).map_or(std::ptr::null_mut(), CString::into_raw),
+        Err(error) => {
+            set_error(error);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Save a session to a JSON file.
+///
+/// # Safety
+/// `session` must be null or a live handle, `filepath` null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn session_to_json(session: *mut Session, filepath: *const c_char) -> bool {
+    let (Some(session), Some(filepath)) = (session_arg(session), str_arg(filepath, "filepath"))
+    else {
+        return false;
+    };
+    match session.to_json(filepath) {
+        Ok(()) => true,
+        Err(error) => {
+            set_error(error);
+            false
+        }
+    }
+}
+
+/// Release a string returned by `session_jsondump`. Null is ignored.
+///
+/// # Safety
+/// `value` must be null or a string from this library that was not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn session_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Add a point and write its GUID to `out_guid` unless it is null.
+///
+/// # Safety
+/// `session` must be null or a live handle, `out_guid` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn session_add_point(
+    session: *mut Session,
+    x: f64,
+    y: f64,
+    z: f64,
+    out_guid: *mut SessionGuid,
+) -> bool {
+    let Some(session) = session_arg(session) else {
+        return false;
+    };
+    let node = session.add_point(Point::new(x as Scalar, y as Scalar, z as Scalar));
+    write_guid(out_guid, &node.name());
+    true
+}
+
+/// Add a line from `x0 y0 z0 x1 y1 z1` and write its GUID to `out_guid` unless it is null.
+///
+/// # Safety
+/// `session` must be null or a live handle, `coordinates` null or 6 readable
+/// doubles, `out_guid` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn session_add_line(
+    session: *mut Session,
+    coordinates: *const f64,
+    out_guid: *mut SessionGuid,
+) -> bool {
+    let Some(session) = session_arg(session) else {
+        return false;
+    };
+    if coordinates.is_null() {
+        set_error("coordinates is null");
+        return false;
+    }
+    let c: Vec<Scalar> = std::slice::from_raw_parts(coordinates, 6)
+        .iter()
+        .map(|&value| value as Scalar)
+        .collect();
+    let node = session.add_line(Line::new(c[0], c[1], c[2], c[3], c[4], c[5]));
+    write_guid(out_guid, &node.name());
+    true
+}
+
+/// Add a mesh from flat arrays and write its GUID to `out_guid` unless it is null.
+///
+/// `vertices` holds `x y z` per vertex. Face `i` has `face_sizes[i]` vertices
+/// whose indices follow each other in `face_indices`.
+///
+/// # Safety
+/// `session` must be null or a live handle, `vertices` null or
+/// `3 * vertex_count` readable doubles, `face_sizes` null or `face_count`
+/// readable values, `face_indices` null or as many values as the sum of the
+/// face sizes, and `out_guid` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn session_add_mesh(
+    session: *mut Session,
+    vertices: *const f64,
+    vertex_count: usize,
+    face_sizes: *const u32,
+    face_count: usize,
+    face_indices: *const u32,
+    out_guid: *mut SessionGuid,
+) -> bool {
+    let Some(session) = session_arg(session) else {
+        return false;
+    };
+    if (vertices.is_null() && vertex_count > 0) || (face_sizes.is_null() && face_count > 0) {
+        set_error("mesh arrays are null");
+        return false;
+    }
+    let coordinates: Vec<Scalar> = slice_or_empty(vertices, 3 * vertex_count)
+        .iter()
+        .map(|&value| value as Scalar)
+        .collect();
+    let sizes = slice_or_empty(face_sizes, face_count);
+    let index_count = sizes.iter().map(|&size| size as usize).sum();
+    if face_indices.is_null() && index_count > 0 {
+        set_error("face_indices is null");
+        return false;
+    }
+    let indices = slice_or_empty(face_indices, index_count);
+    if let Some(index) = indices.iter().find(|&&i| i as usize >= vertex_count) {
+        set_error(format!(
+            "face index {index} is out of range for {vertex_count} vertices"
+        ));
+        return false;
+    }
+
+    let mut mesh = Mesh::new();
+    let keys: Vec<usize> = coordinates
+        .chunks_exact(3)
+        .map(|c| mesh.add_vertex(Point::new(c[0], c[1], c[2]), None))
+        .collect();
+    let mut start = 0;
+    for &size in sizes {
+        let face = &indices[start..start + size as usize];
+        start += size as usize;
+        mesh.add_face(face.iter().map(|&i| keys[i as usize]).collect(), None);
+    }
+    let node = session.add_mesh(mesh);
+    write_guid(out_guid, &node.name());
+    true
+}
+
+unsafe fn slice_or_empty<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if data.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, len)
+    }
+}
+
+/// Cast a ray and write up to `capacity` hits, closest first, to `hits`.
+///
+/// # Returns
+/// The total number of hits, which may exceed `capacity`.
+///
+/// # Safety
+/// `session` must be null or a live handle, `origin` and `direction` null or
+/// 3 readable doubles, and `hits` null or `capacity` writable hits.
+#[no_mangle]
+pub unsafe extern "C" fn session_ray_cast(
+    session: *mut Session,
+    origin: *const f64,
+    direction: *const f64,
+    tolerance: f64,
+    hits: *mut SessionRayHit,
+    capacity: usize,
+) -> usize {
+    let Some(session) = session_arg(session) else {
+        return 0;
+    };
+    if origin.is_null() || direction.is_null() {
+        set_error("ray origin or direction is null");
+        return 0;
+    }
+    let o = std::slice::from_raw_parts(origin, 3);
+    let d = std::slice::from_raw_parts(direction, 3);
+    let [ox, oy, oz, dx, dy, dz] = [o[0], o[1], o[2], d[0], d[1], d[2]].map(|v| v as Scalar);
+    let found = session.ray_cast(
+        &Point::new(ox, oy, oz),
+        &Vector::new(dx, dy, dz),
+        tolerance as Scalar,
+    );
+    if !hits.is_null() {
+        for (i, hit) in found.iter().take(capacity).enumerate() {
+            *hits.add(i) = SessionRayHit {
+                guid: SessionGuid::new(&hit.guid),
+                x: to_f64(hit.point.x()),
+                y: to_f64(hit.point.y()),
+                z: to_f64(hit.point.z()),
+                distance: to_f64(hit.distance),
+            };
+        }
+    }
+    found.len()
+}
+
+/// Write up to `capacity` colliding object pairs to `pairs`.
+///
+/// # Returns
+/// The total number of pairs, which may exceed `capacity`.
+///
+/// # Safety
+/// `session` must be null or a live handle and `pairs` null or `capacity` writable pairs.
+#[no_mangle]
+pub unsafe extern "C" fn session_get_collisions(
+    session: *mut Session,
+    pairs: *mut SessionCollision,
+    capacity: usize,
+) -> usize {
+    let Some(session) = session_arg(session) else {
+        return 0;
+    };
+    let collisions = session.get_collisions();
+    if !pairs.is_null() {
+        for (i, (first, second)) in collisions.iter().take(capacity).enumerate() {
+            *pairs.add(i) = SessionCollision {
+                first: SessionGuid::new(first),
+                second: SessionGuid::new(second),
+            };
+        }
+    }
+    collisions.len()
+}
+
+#[cfg(test)]
+#[path = "capi_test.rs"]
+mod capi_test;
@@ -0,0 +1,102 @@
+use crate::capi::*;
+use std::ffi::{CStr, CString};
+
+fn guid_str(guid: &SessionGuid) -> String {
+    unsafe { CStr::from_ptr(guid.value.as_ptr()) }
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_capi_session_roundtrip() {
+    unsafe {
+        let name = CString::new("ffi").unwrap();
+        let session = session_new(name.as_ptr());
+        assert!(!session.is_null());
+
+        let mut guid = SessionGuid {
+            value: [0; SESSION_GUID_LENGTH],
+        };
+        assert!(session_add_point(session, 1.0, 2.0, 3.0, &mut guid));
+        let point = guid_str(&guid);
+        assert_eq!(point.len(), 36);
+
+        let line = [0.0, 0.0, 0.0, 10.0, 0.0, 0.0];
+        assert!(session_add_line(
+            session,
+            line.as_ptr(),
+            std::ptr::null_mut()
+        ));
+
+        // A unit square crossing the line, as one quad
+        let vertices = [
+            5.0, -1.0, -1.0, 5.0, 1.0, -1.0, 5.0, 1.0, 1.0, 5.0, -1.0, 1.0,
+        ];
+        let sizes = [4u32];
+        let indices = [0u32, 1, 2, 3];
+        assert!(session_add_mesh(
+            session,
+            vertices.as_ptr(),
+            4,
+            sizes.as_ptr(),
+            1,
+            indices.as_ptr(),
+            &mut guid,
+        ));
+        let mesh = guid_str(&guid);
+
+        let bad = [0u32, 1, 9];
+        let triangle = [3u32];
+        assert!(!session_add_mesh(
+            session,
+            vertices.as_ptr(),
+            4,
+            triangle.as_ptr(),
+            1,
+            bad.as_ptr(),
+            std::ptr::null_mut(),
+        ));
+        let error = CStr::from_ptr(session_last_error()).to_str().unwrap();
+        assert!(error.contains("out of range"));
+
+        let origin = [-5.0, 0.0, 0.5];
+        let direction = [1.0, 0.0, 0.0];
+        let mut hits = [SessionRayHit {
+            guid: SessionGuid {
+                value: [0; SESSION_GUID_LENGTH],
+            },
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            distance: 0.0,
+        }; 4];
+        let count = session_ray_cast(
+            session,
+            origin.as_ptr(),
+            direction.as_ptr(),
+            1e-3,
+            hits.as_mut_ptr(),
+            hits.len(),
+        );
+        assert!(count >= 1);
+        assert!(hits[..count].iter().any(|hit| guid_str(&hit.guid) == mesh));
+
+        let total = session_get_collisions(session, std::ptr::null_mut(), 0);
+        assert!(total >= 1);
+
+        let json = session_jsondump(session);
+        assert!(!json.is_null());
+        let loaded = session_jsonload(json);
+        session_string_free(json);
+        assert!(!loaded.is_null());
+        assert!((*loaded).get_object(&point).is_some());
+        session_free(loaded);
+
+        let broken = CString::new("{").unwrap();
+        assert!(session_jsonload(broken.as_ptr()).is_null());
+        assert!(!session_last_error().is_null());
+
+        session_free(session);
+    }
+}
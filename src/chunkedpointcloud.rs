@@ -0,0 +1,541 @@
+use crate::error::SessionError;
+use crate::pointcloud::PointChunk;
+use crate::{AsVec3, BoundingBox, Color, Point, PointCloud, Scalar, Vec3, Vector, Xform, BVH};
+use once_cell::sync::OnceCell;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+
+/// First bytes of a chunked point cloud file.
+const MAGIC: &[u8; 4] = b"SRPC";
+
+/// Layout version written after the magic bytes.
+const VERSION: u32 = 1;
+
+/// A point cloud stored as blocks of `chunk_size` points, each with its own bounds.
+///
+/// Chunks are written to and read from a file one by one. A cloud opened from
+/// a file only reads its chunk table, `chunk` loads a chunk on first use, so
+/// clouds larger than memory can be queried by the chunks their bounds select.
+/// All chunks hold `chunk_size` points except the last, so point indices are
+/// `chunk * chunk_size + index in the chunk`.
+#[derive(Debug, Clone)]
+pub struct ChunkedPointCloud {
+    pub guid: String,
+    pub name: String,
+    pub xform: Xform,
+    chunk_size: usize,
+    slots: Vec<ChunkSlot>,
+    // File the chunks are loaded from, None for clouds built in memory
+    source: Option<String>,
+    // Cached BVH over the chunk bounds (not serialized)
+    bvh: OnceCell<BVH>,
+}
+
+/// Bounds, size and file location of a chunk, as stored in the chunk table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChunkEntry {
+    bbox: BoundingBox,
+    len: usize,
+    // Byte range of the chunk in the file, zero for chunks built in memory
+    offset: u64,
+    size: u64,
+}
+
+#[derive(Debug, Clone)]
+struct ChunkSlot {
+    entry: ChunkEntry,
+    chunk: OnceCell<PointChunk>,
+}
+
+/// A chunk as written to the file, with flat coordinate arrays like a serialized `PointCloud`.
+///
+/// The bounds are kept in the chunk table, so they are known without reading the chunk.
+#[derive(Serialize, Deserialize)]
+struct ChunkData {
+    points: Vec<Scalar>,
+    normals: Vec<Scalar>,
+    // RGBA
+    colors: Vec<u8>,
+    intensities: Vec<Scalar>,
+}
+
+impl ChunkData {
+    fn new(chunk: &PointChunk) -> Self {
+        let flat = |values: &[Vec3]| {
+            values
+                .iter()
+                .flat_map(|&v| <[Scalar; 3]>::from(v))
+                .collect()
+        };
+        Self {
+            points: flat(&chunk.points),
+            normals: flat(&chunk.normals),
+            colors: (chunk.colors.iter())
+                .flat_map(|c| [c.r, c.g, c.b, c.a])
+                .collect(),
+            intensities: chunk.intensities.clone(),
+        }
+    }
+
+    fn into_chunk(self, bbox: BoundingBox) -> PointChunk {
+        let vectors = |flat: Vec<Scalar>| {
+            flat.chunks_exact(3)
+                .map(|v| Vec3::new(v[0], v[1], v[2]))
+                .collect()
+        };
+        PointChunk {
+            bbox,
+            points: vectors(self.points),
+            normals: vectors(self.normals),
+            colors: (self.colors.chunks_exact(4))
+                .map(|c| Color::new(c[0], c[1], c[2], c[3]))
+                .collect(),
+            intensities: self.intensities,
+        }
+    }
+}
+
+/// Everything but the chunks, written after them at the end of the file.
+#[derive(Serialize, Deserialize)]
+struct ChunkTable {
+    guid: String,
+    name: String,
+    xform: Xform,
+    chunk_size: usize,
+    chunks: Vec<ChunkEntry>,
+}
+
+/// The values of a range of points, or none if the cloud does not have one per point.
+fn attribute<T: Clone>(values: &[T], count: usize, start: usize, end: usize) -> Vec<T> {
+    if values.len() == count {
+        values[start..end].to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Distance along a unit ray where it enters a box grown by `margin`, None if it misses.
+fn ray_entry(
+    bbox: &BoundingBox,
+    origin: &Point,
+    direction: &Vector,
+    margin: Scalar,
+) -> Option<Scalar> {
+    let center = <[Scalar; 3]>::from(bbox.center.as_vec3());
+    let half = <[Scalar; 3]>::from(bbox.half_size.as_vec3());
+    let origin = <[Scalar; 3]>::from(origin.as_vec3());
+    let direction = <[Scalar; 3]>::from(direction.as_vec3());
+    let (mut near, mut far) = (0.0, Scalar::INFINITY);
+    for axis in 0..3 {
+        let (min, max) = (
+            center[axis] - half[axis] - margin,
+            center[axis] + half[axis] + margin,
+        );
+        if direction[axis] == 0.0 {
+            if origin[axis] < min || origin[axis] > max {
+                return None;
+            }
+            continue;
+        }
+        let a = (min - origin[axis]) / direction[axis];
+        let b = (max - origin[axis]) / direction[axis];
+        near = Scalar::max(near, a.min(b));
+        far = Scalar::min(far, a.max(b));
+    }
+    (near <= far).then_some(near)
+}
+
+impl ChunkedPointCloud {
+    /// Split a cloud into chunks of `chunk_size` consecutive points.
+    ///
+    /// Normals, colors and intensities go with their points when the cloud has
+    /// one per point. Sort the cloud with `PointCloud::spatially_sorted` first
+    /// to keep the chunk bounds compact.
+    pub fn from_cloud(cloud: &PointCloud, chunk_size: usize) -> Self {
+        let chunk_size = chunk_size.max(1);
+        let count = cloud.points.len();
+        let chunk = |index: usize| {
+            let (start, end) = (index * chunk_size, ((index + 1) * chunk_size).min(count));
+            let points = cloud.points[start..end].to_vec();
+            PointChunk {
+                bbox: BoundingBox::from_points(&points, 0.0),
+                points,
+                normals: attribute(&cloud.normals, count, start, end),
+                colors: attribute(&cloud.colors, count, start, end),
+                intensities: attribute(&cloud.intensities, count, start, end),
+            }
+        };
+        #[cfg(feature = "parallel")]
+        let chunks: Vec<PointChunk> = (0..count.div_ceil(chunk_size))
+            .into_par_iter()
+            .map(chunk)
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let chunks: Vec<PointChunk> = (0..count.div_ceil(chunk_size)).map(chunk).collect();
+
+        let slots = chunks
+            .into_iter()
+            .map(|chunk| ChunkSlot {
+                entry: ChunkEntry {
+                    bbox: chunk.bbox.clone(),
+                    len: chunk.len(),
+                    offset: 0,
+                    size: 0,
+                },
+                chunk: OnceCell::with_value(chunk),
+            })
+            .collect();
+        Self {
+            guid: cloud.guid.clone(),
+            name: cloud.name.clone(),
+            xform: cloud.xform.clone(),
+            chunk_size,
+            slots,
+            source: None,
+            bvh: OnceCell::new(),
+        }
+    }
+
+    /// Join the chunks into one cloud with the same GUID, name and xform.
+    ///
+    /// Chunks that are not loaded are read for the call and not kept. Normals,
+    /// colors and intensities are kept if every point has one.
+    pub fn to_cloud(&self) -> Result<PointCloud, SessionError> {
+        let count = self.len();
+        let mut cloud = PointCloud {
+            guid: self.guid.clone(),
+            name: self.name.clone(),
+            xform: self.xform.clone(),
+            ..Default::default()
+        };
+        cloud.points.reserve(count);
+        for index in 0..self.slots.len() {
+            self.with_chunk(index, |chunk| {
+                cloud.points.extend_from_slice(&chunk.points);
+                cloud.normals.extend_from_slice(&chunk.normals);
+                cloud.colors.extend_from_slice(&chunk.colors);
+                cloud.intensities.extend_from_slice(&chunk.intensities);
+            })?;
+        }
+        if cloud.normals.len() != count {
+            cloud.normals.clear();
+        }
+        if cloud.colors.len() != count {
+            cloud.colors.clear();
+        }
+        if cloud.intensities.len() != count {
+            cloud.intensities.clear();
+        }
+        Ok(cloud)
+    }
+
+    /// Number of points in all chunks.
+    pub fn len(&self) -> usize {
+        self.slots.iter().map(|slot| slot.entry.len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Points per chunk, the last chunk may hold fewer.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Axis-aligned bounds of the points of a chunk, known without loading it.
+    pub fn chunk_bbox(&self, index: usize) -> Option<&BoundingBox> {
+        self.slots.get(index).map(|slot| &slot.entry.bbox)
+    }
+
+    /// Number of points of a chunk, known without loading it.
+    pub fn chunk_len(&self, index: usize) -> Option<usize> {
+        self.slots.get(index).map(|slot| slot.entry.len)
+    }
+
+    /// Whether a chunk is in memory.
+    pub fn is_loaded(&self, index: usize) -> bool {
+        self.slots
+            .get(index)
+            .is_some_and(|slot| slot.chunk.get().is_some())
+    }
+
+    /// A chunk, read from the file on first use and kept until `unload`.
+    ///
+    /// # Returns
+    /// The chunk, or an error for a missing chunk or an unreadable file.
+    pub fn chunk(&self, index: usize) -> Result<&PointChunk, SessionError> {
+        let slot = self.slot(index)?;
+        slot.chunk.get_or_try_init(|| self.read_chunk(index))
+    }
+
+    /// Release a chunk read from the file, it is read again when used.
+    ///
+    /// # Returns
+    /// `true` if the chunk was loaded and can be read again, chunks of clouds
+    /// built in memory are never released.
+    pub fn unload(&mut self, index: usize) -> bool {
+        if self.source.is_none() {
+            return false;
+        }
+        self.slots
+            .get_mut(index)
+            .is_some_and(|slot| slot.chunk.take().is_some())
+    }
+
+    /// A BVH with one leaf per chunk, query results are chunk indices.
+    pub fn bvh(&self) -> &BVH {
+        self.bvh.get_or_init(|| {
+            let boxes: Vec<BoundingBox> = (self.slots.iter())
+                .map(|slot| slot.entry.bbox.clone())
+                .collect();
+            BVH::from_boxes(&boxes, BVH::compute_world_size(&boxes))
+        })
+    }
+
+    /// Indices of the chunks whose bounds overlap a box, the chunks to load for a region.
+    pub fn chunks_in_box(&self, bbox: &BoundingBox) -> Vec<usize> {
+        let mut indices = self.bvh().query_box(bbox);
+        indices.sort_unstable();
+        indices
+    }
+
+    /// Find the first point along a ray, see `PointCloud::ray_cast`.
+    ///
+    /// Chunks are tested nearest first and only the chunks whose bounds pass
+    /// within `splat_radius` of the ray are loaded, until no closer point can follow.
+    ///
+    /// # Returns
+    /// Point index and distance from the origin to its projection onto the
+    /// ray, or an error if a chunk cannot be read.
+    pub fn ray_cast(
+        &self,
+        origin: &Point,
+        direction: &Vector,
+        splat_radius: Scalar,
+    ) -> Result<Option<(usize, Scalar)>, SessionError> {
+        let Some(direction) = direction.as_vec3().normalized() else {
+            return Ok(None);
+        };
+        let direction = Vector::from(direction);
+        let mut entries: Vec<(usize, Scalar)> = (self.slots.iter().enumerate())
+            .filter_map(|(index, slot)| {
+                ray_entry(&slot.entry.bbox, origin, &direction, splat_radius)
+                    .map(|entry| (index, entry))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut first: Option<(usize, Scalar)> = None;
+        for (index, entry) in entries {
+            // Points of later chunks project beyond where the ray enters their grown bounds
+            if first.is_some_and(|(_, t)| entry > t) {
+                break;
+            }
+            let hit = self
+                .chunk(index)?
+                .ray_cast(origin, &direction, splat_radius);
+            if let Some((point, t)) = hit {
+                if first.is_none_or(|(_, best)| t < best) {
+                    first = Some((index * self.chunk_size + point, t));
+                }
+            }
+        }
+        Ok(first)
+    }
+
+    /// Process every chunk on its own, on the rayon thread pool with the `parallel` feature.
+    ///
+    /// Chunks that are not loaded are read for the call and not kept.
+    ///
+    /// # Arguments
+    /// * `f` - Called with the index and the points of each chunk
+    ///
+    /// # Returns
+    /// The results in chunk order, or the first error reading a chunk.
+    pub fn map_chunks<R, F>(&self, f: F) -> Result<Vec<R>, SessionError>
+    where
+        R: Send,
+        F: Fn(usize, &PointChunk) -> R + Sync + Send,
+    {
+        let run = |index: usize| self.with_chunk(index, |chunk| f(index, chunk));
+        #[cfg(feature = "parallel")]
+        let results = (0..self.slots.len()).into_par_iter().map(run).collect();
+        #[cfg(not(feature = "parallel"))]
+        let results = (0..self.slots.len()).map(run).collect();
+        results
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Files
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Write the cloud chunk by chunk.
+    ///
+    /// The file holds the magic bytes `SRPC` and the version as a little endian
+    /// `u32`, then every chunk as MessagePack with flat coordinate and RGBA
+    /// arrays, then the chunk table with the
+    /// bounds, point count and byte range of each chunk as MessagePack, and
+    /// last the byte length of the table as a little endian `u64`. Chunks that
+    /// are not loaded are copied from the source file one at a time.
+    ///
+    /// # Arguments
+    /// * `filepath` - The path of the file to write
+    pub fn write(&self, filepath: &str) -> Result<(), SessionError> {
+        let mut out = BufWriter::new(File::create(filepath)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        let mut offset = (MAGIC.len() + 4) as u64;
+        let mut chunks = Vec::with_capacity(self.slots.len());
+        for (index, slot) in self.slots.iter().enumerate() {
+            let bytes =
+                self.with_chunk(index, |chunk| rmp_serde::to_vec(&ChunkData::new(chunk)))??;
+            out.write_all(&bytes)?;
+            chunks.push(ChunkEntry {
+                offset,
+                size: bytes.len() as u64,
+                ..slot.entry.clone()
+            });
+            offset += bytes.len() as u64;
+        }
+        let table = rmp_serde::to_vec_named(&ChunkTable {
+            guid: self.guid.clone(),
+            name: self.name.clone(),
+            xform: self.xform.clone(),
+            chunk_size: self.chunk_size,
+            chunks,
+        })?;
+        out.write_all(&table)?;
+        out.write_all(&(table.len() as u64).to_le_bytes())?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Open a file written by `write`, reading only its chunk table.
+    ///
+    /// # Returns
+    /// The cloud with no chunk loaded, or an error for unreadable files, other
+    /// formats and chunk tables that do not fit the file.
+    pub fn open(filepath: &str) -> Result<Self, SessionError> {
+        let invalid = |message: &str| SessionError::SchemaMismatch(message.to_string());
+        let mut file = File::open(filepath)?;
+        let file_len = file.metadata()?.len();
+        let data_start = (MAGIC.len() + 4) as u64;
+        if file_len < data_start + 8 {
+            return Err(invalid("chunked point cloud file is truncated"));
+        }
+        let mut head = [0u8; 8];
+        file.read_exact(&mut head)?;
+        if &head[..4] != MAGIC {
+            return Err(invalid("not a chunked point cloud file"));
+        }
+        let version = u32::from_le_bytes(head[4..].try_into().unwrap());
+        if version != VERSION {
+            return Err(SessionError::SchemaMismatch(format!(
+                "unsupported chunked point cloud version {version}"
+            )));
+        }
+
+        let mut trailer = [0u8; 8];
+        file.seek(SeekFrom::Start(file_len - 8))?;
+        file.read_exact(&mut trailer)?;
+        let table_len = u64::from_le_bytes(trailer);
+        let table_start = (file_len - 8)
+            .checked_sub(table_len)
+            .filter(|&start| start >= data_start)
+            .ok_or_else(|| invalid("chunk table is truncated"))?;
+        let mut table = vec![0u8; table_len as usize];
+        file.seek(SeekFrom::Start(table_start))?;
+        file.read_exact(&mut table)?;
+        let table: ChunkTable = rmp_serde::from_slice(&table)?;
+
+        // Every chunk but the last is full, so point indices follow from chunk indices
+        let last = table.chunks.len().saturating_sub(1);
+        for (index, entry) in table.chunks.iter().enumerate() {
+            let full = if index == last {
+                (1..=table.chunk_size).contains(&entry.len)
+            } else {
+                entry.len == table.chunk_size
+            };
+            let inside = entry.offset >= data_start
+                && entry
+                    .offset
+                    .checked_add(entry.size)
+                    .is_some_and(|end| end <= table_start);
+            if !full || !inside {
+                return Err(SessionError::SchemaMismatch(format!(
+                    "invalid entry of chunk {index} in the chunk table"
+                )));
+            }
+        }
+        Ok(Self {
+            guid: table.guid,
+            name: table.name,
+            xform: table.xform,
+            chunk_size: table.chunk_size,
+            slots: (table.chunks.into_iter())
+                .map(|entry| ChunkSlot {
+                    entry,
+                    chunk: OnceCell::new(),
+                })
+                .collect(),
+            source: Some(filepath.to_string()),
+            bvh: OnceCell::new(),
+        })
+    }
+
+    fn slot(&self, index: usize) -> Result<&ChunkSlot, SessionError> {
+        self.slots.get(index).ok_or_else(|| {
+            SessionError::InvalidGeometry(format!(
+                "no chunk {index} in a cloud of {} chunks",
+                self.slots.len()
+            ))
+        })
+    }
+
+    /// Call `f` with a chunk, reading it for the call if it is not loaded.
+    fn with_chunk<R>(
+        &self,
+        index: usize,
+        f: impl FnOnce(&PointChunk) -> R,
+    ) -> Result<R, SessionError> {
+        match self.slot(index)?.chunk.get() {
+            Some(chunk) => Ok(f(chunk)),
+            None => Ok(f(&self.read_chunk(index)?)),
+        }
+    }
+
+    fn read_chunk(&self, index: usize) -> Result<PointChunk, SessionError> {
+        let entry = &self.slot(index)?.entry;
+        let Some(filepath) = &self.source else {
+            return Err(SessionError::InvalidGeometry(format!(
+                "chunk {index} is not loaded and the cloud has no file"
+            )));
+        };
+        let mut file = File::open(filepath)?;
+        file.seek(SeekFrom::Start(entry.offset))?;
+        let mut bytes = vec![0u8; entry.size as usize];
+        file.read_exact(&mut bytes)?;
+        let data: ChunkData = rmp_serde::from_slice(&bytes)?;
+        let chunk = data.into_chunk(entry.bbox.clone());
+        if chunk.len() != entry.len {
+            return Err(SessionError::SchemaMismatch(format!(
+                "chunk {index} holds {} points, the chunk table {}",
+                chunk.len(),
+                entry.len
+            )));
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+#[path = "chunkedpointcloud_test.rs"]
+mod chunkedpointcloud_test;
@@ -0,0 +1,173 @@
+use crate::chunkedpointcloud::ChunkedPointCloud;
+use crate::{BoundingBox, Color, Point, PointCloud, Scalar, SessionError, Vec3, Vector};
+
+/// Points along the x axis, 0.1 apart, with a normal and an intensity each.
+fn line_cloud(count: usize) -> PointCloud {
+    let points: Vec<Vec3> = (0..count)
+        .map(|i| Vec3::new(i as Scalar * 0.1, 0.0, 0.0))
+        .collect();
+    let normals = vec![Vec3::new(0.0, 0.0, 1.0); count];
+    let mut cloud = PointCloud::from_coords(points, normals, vec![]);
+    cloud.intensities = (0..count).map(|i| i as Scalar).collect();
+    // Colors for only some points are dropped
+    cloud.colors = vec![Color::new(255, 0, 0, 255); 3];
+    cloud.name = "scan".to_string();
+    cloud
+}
+
+fn temp_path() -> String {
+    std::env::temp_dir()
+        .join(format!("scan_{}.srpc", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn region(min: [Scalar; 3], max: [Scalar; 3]) -> BoundingBox {
+    BoundingBox::from_points(
+        &[
+            Point::new(min[0], min[1], min[2]),
+            Point::new(max[0], max[1], max[2]),
+        ],
+        0.0,
+    )
+}
+
+#[test]
+fn test_chunked_pointcloud_from_cloud() {
+    let cloud = line_cloud(1000);
+    let chunked = ChunkedPointCloud::from_cloud(&cloud, 300);
+    assert_eq!(chunked.guid, cloud.guid);
+    assert_eq!(chunked.len(), 1000);
+    assert_eq!(chunked.chunk_count(), 4);
+    assert_eq!(chunked.chunk_size(), 300);
+    assert_eq!(chunked.chunk_len(3), Some(100));
+    assert!(chunked.chunk_len(4).is_none());
+
+    let chunk = chunked.chunk(1).unwrap();
+    assert_eq!(chunk.points[0], cloud.points[300]);
+    assert_eq!(chunk.normals.len(), 300);
+    assert_eq!(chunk.intensities[0], 300.0);
+    assert!(chunk.colors.is_empty());
+    let bbox = chunked.chunk_bbox(1).unwrap();
+    assert!(chunk.points.iter().all(|p| bbox.contains_point(p)));
+    assert!(!bbox.contains_point(&cloud.points[299]));
+
+    assert_eq!(
+        chunked.chunks_in_box(&region([35.0, -1.0, -1.0], [65.0, 1.0, 1.0])),
+        vec![1, 2]
+    );
+    let counts = chunked.map_chunks(|_, chunk| chunk.len()).unwrap();
+    assert_eq!(counts, vec![300, 300, 300, 100]);
+
+    let joined = chunked.to_cloud().unwrap();
+    assert_eq!(joined.guid, cloud.guid);
+    assert_eq!(joined.points, cloud.points);
+    assert_eq!(joined.normals, cloud.normals);
+    assert_eq!(joined.intensities, cloud.intensities);
+    assert!(joined.colors.is_empty());
+
+    // Chunks built in memory stay loaded
+    let mut chunked = chunked;
+    assert!(!chunked.unload(0));
+    assert!(chunked.is_loaded(0));
+    assert!(matches!(
+        chunked.chunk(4),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+    assert!(ChunkedPointCloud::from_cloud(&PointCloud::default(), 300).is_empty());
+}
+
+#[test]
+fn test_chunked_pointcloud_loads_chunks_lazily() {
+    let path = temp_path();
+    let cloud = line_cloud(1000);
+    ChunkedPointCloud::from_cloud(&cloud, 300)
+        .write(&path)
+        .unwrap();
+
+    let mut opened = ChunkedPointCloud::open(&path).unwrap();
+    assert_eq!(opened.guid, cloud.guid);
+    assert_eq!(opened.name, "scan");
+    assert_eq!(opened.len(), 1000);
+    assert!((0..4).all(|index| !opened.is_loaded(index)));
+    assert_eq!(opened.chunk_len(3), Some(100));
+
+    // A ray only reads the chunks it passes
+    let down = Vector::new(0.0, 0.0, -1.0);
+    let (index, t) = opened
+        .ray_cast(&Point::new(45.0, 0.0, 10.0), &down, 0.01)
+        .unwrap()
+        .unwrap();
+    assert_eq!(index, 450);
+    assert!((t - 10.0).abs() < 1e-6);
+    assert!(opened.is_loaded(1));
+    assert!(!opened.is_loaded(0) && !opened.is_loaded(2) && !opened.is_loaded(3));
+    assert!(opened
+        .ray_cast(&Point::new(45.0, 5.0, 10.0), &down, 0.01)
+        .unwrap()
+        .is_none());
+
+    // Loaded chunks can be released and read again
+    assert_eq!(opened.chunk(2).unwrap().points[0], cloud.points[600]);
+    assert!(opened.unload(2));
+    assert!(!opened.is_loaded(2));
+    assert!(!opened.unload(2));
+    assert_eq!(opened.chunk(2).unwrap().intensities[5], 605.0);
+
+    // Joining and copying read the chunks that are not loaded without keeping them
+    let joined = opened.to_cloud().unwrap();
+    assert_eq!(joined.points, cloud.points);
+    assert_eq!(joined.normals, cloud.normals);
+    assert!(!opened.is_loaded(0));
+    let copy = temp_path();
+    opened.write(&copy).unwrap();
+    let reopened = ChunkedPointCloud::open(&copy).unwrap();
+    assert_eq!(reopened.to_cloud().unwrap().points, cloud.points);
+    std::fs::remove_file(&copy).ok();
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_chunked_pointcloud_rejects_other_files() {
+    let path = temp_path();
+    ChunkedPointCloud::from_cloud(&line_cloud(100), 30)
+        .write(&path)
+        .unwrap();
+    let data = std::fs::read(&path).unwrap();
+    let open = |bytes: &[u8]| {
+        std::fs::write(&path, bytes).unwrap();
+        ChunkedPointCloud::open(&path)
+    };
+
+    assert!(matches!(
+        open(&data[..10]),
+        Err(SessionError::SchemaMismatch(_))
+    ));
+    let mut other = data.clone();
+    other[..4].copy_from_slice(b"LASF");
+    assert!(matches!(open(&other), Err(SessionError::SchemaMismatch(_))));
+    let mut version = data.clone();
+    version[4] = 9;
+    assert!(matches!(
+        open(&version),
+        Err(SessionError::SchemaMismatch(_))
+    ));
+    let mut table = data.clone();
+    let len = table.len();
+    table[len - 8..].copy_from_slice(&(len as u64).to_le_bytes());
+    assert!(matches!(open(&table), Err(SessionError::SchemaMismatch(_))));
+
+    // A chunk damaged after opening is reported when it is read
+    let opened = open(&data).unwrap();
+    let mut damaged = data.clone();
+    damaged[8..40].fill(0xc1);
+    std::fs::write(&path, &damaged).unwrap();
+    assert!(opened.chunk(0).is_err());
+    assert!(opened.chunk(1).is_ok());
+
+    std::fs::remove_file(&path).ok();
+    assert!(matches!(
+        ChunkedPointCloud::open(&path),
+        Err(SessionError::Io(_))
+    ));
+}
@@ -0,0 +1,278 @@
+use crate::distance::distance;
+use crate::gjk::{penetration, Support};
+use crate::{AsVec3, Geometry, Line, Scalar, Tolerance, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Collision group and mask bits of an object.
+///
+/// Two objects are tested against each other only if each one's group
+/// shares a bit with the other's mask. Group 0 excludes an object from all
+/// collision tests and ray casts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionFilter {
+    pub group: u32,
+    pub mask: u32,
+}
+
+impl Default for CollisionFilter {
+    /// Group 1 colliding with every group.
+    fn default() -> Self {
+        CollisionFilter {
+            group: 1,
+            mask: u32::MAX,
+        }
+    }
+}
+
+impl CollisionFilter {
+    pub fn new(group: u32, mask: u32) -> Self {
+        CollisionFilter { group, mask }
+    }
+
+    /// Whether objects with these two filters are tested against each other.
+    pub fn collides_with(&self, other: &CollisionFilter) -> bool {
+        self.group & other.mask != 0 && other.group & self.mask != 0
+    }
+}
+
+/// Options for `Session::get_collisions_with`.
+#[derive(Debug, Clone)]
+pub struct CollisionOptions {
+    /// Confirm box overlaps with exact tests where the pair of geometry types has one
+    pub narrow_phase: bool,
+    /// Gap below which two objects collide
+    pub tolerance: Scalar,
+    /// Pairs for which the filter returns false are skipped
+    pub filter: Option<fn(&Geometry, &Geometry) -> bool>,
+}
+
+impl Default for CollisionOptions {
+    fn default() -> Self {
+        CollisionOptions {
+            narrow_phase: false,
+            tolerance: Tolerance::APPROXIMATION,
+            filter: None,
+        }
+    }
+}
+
+impl CollisionOptions {
+    /// Options with the narrow phase enabled.
+    pub fn narrow_phase() -> Self {
+        CollisionOptions {
+            narrow_phase: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Segments with a radius in world coordinates for point and curve-like geometry.
+fn capsules(geometry: &Geometry) -> Option<Vec<(Line, Scalar)>> {
+    let placed;
+    let geometry = if geometry.xform().is_identity() {
+        geometry
+    } else {
+        placed = geometry.transformed();
+        &placed
+    };
+    let capsules = match geometry {
+        Geometry::Point(p) => vec![(Line::from_points(p, p), 0.0)],
+        Geometry::Line(l) => vec![(l.clone(), 0.0)],
+        Geometry::Polyline(pl) => pl
+            .points
+            .windows(2)
+            .map(|w| (Line::from_points(&w[0], &w[1]), 0.0))
+            .collect(),
+        Geometry::Cylinder(c) => vec![(c.line.clone(), c.radius)],
+        // The arrow head is 1.5 times wider than the body
+        Geometry::Arrow(a) => vec![(a.line.clone(), a.radius * 1.5)],
+        _ => return None,
+    };
+    Some(capsules)
+}
+
+/// Exact collision test for two objects whose bounding boxes overlap.
+///
+/// Points, lines, polylines, cylinders and arrows are tested as capsules and
+/// meshes against meshes with their triangle BVHs. Other pairs have no exact
+/// test and keep the box overlap result.
+///
+/// # Arguments
+/// * `a` - First object
+/// * `b` - Second object
+/// * `tolerance` - Gap below which the objects collide
+///
+/// # Returns
+/// `true` if the objects collide or the pair has no exact test.
+pub fn narrow_phase(a: &Geometry, b: &Geometry, tolerance: Scalar) -> bool {
+    if let (Geometry::Mesh(mesh_a), Geometry::Mesh(mesh_b)) = (a, b) {
+        return mesh_a.intersects_mesh(mesh_b, tolerance);
+    }
+    let (Some(capsules_a), Some(capsules_b)) = (capsules(a), capsules(b)) else {
+        return true;
+    };
+    capsules_a.iter().any(|(line_a, radius_a)| {
+        capsules_b.iter().any(|(line_b, radius_b)| {
+            distance(line_a, line_b).distance <= radius_a + radius_b + tolerance
+        })
+    })
+}
+
+/// Contact between two convex objects, see `contact`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "Contact")]
+pub struct Contact {
+    /// Point of the first object deepest inside the second
+    pub point_a: Vec3,
+    /// Point of the second object deepest inside the first
+    pub point_b: Vec3,
+    /// Unit direction from the first object to the second
+    pub normal: Vec3,
+    /// Overlap along the normal, negative for a gap within the tolerance
+    pub depth: Scalar,
+}
+
+impl Contact {
+    /// The same contact seen from the second object.
+    pub fn flipped(&self) -> Self {
+        Contact {
+            point_a: self.point_b,
+            point_b: self.point_a,
+            normal: -self.normal,
+            depth: self.depth,
+        }
+    }
+}
+
+/// Convex shape of an object in world coordinates.
+#[derive(Debug, Clone)]
+pub(crate) enum ConvexShape {
+    /// Center and the three half axes
+    Box(Vec3, [Vec3; 3]),
+    /// Axis start, axis end and radius
+    Cylinder(Vec3, Vec3, Scalar),
+    /// Convex hull of points
+    Hull(Vec<Vec3>),
+}
+
+impl ConvexShape {
+    /// Shape of boxes, cylinders and the convex hull of meshes, point clouds,
+    /// polylines, lines and points, None for other objects.
+    pub(crate) fn of(geometry: &Geometry) -> Option<Self> {
+        let xform = geometry.xform();
+        let mut points: Vec<Vec3> = match geometry {
+            Geometry::BoundingBox(b) => {
+                let b = b.transformed();
+                let half = b.half_size.as_vec3();
+                let axes = [b.x_axis, b.y_axis, b.z_axis].map(|axis| axis.as_vec3());
+                return Some(ConvexShape::Box(
+                    b.center.as_vec3(),
+                    [axes[0] * half.x, axes[1] * half.y, axes[2] * half.z],
+                ));
+            }
+            Geometry::Cylinder(c) => {
+                let [start, end] =
+                    [c.line.start(), c.line.end()].map(|p| xform.transformed_point(&p).as_vec3());
+                return Some(ConvexShape::Cylinder(start, end, c.radius));
+            }
+            Geometry::Mesh(m) => m.vertex.values().map(|v| v.coords()).collect(),
+            Geometry::PointCloud(pc) => pc.points.clone(),
+            Geometry::Polyline(pl) => pl.points.iter().map(AsVec3::as_vec3).collect(),
+            Geometry::Line(l) => vec![l.start().as_vec3(), l.end().as_vec3()],
+            Geometry::Point(p) => vec![p.as_vec3()],
+            _ => return None,
+        };
+        if points.is_empty() {
+            return None;
+        }
+        if !xform.is_identity() {
+            Vec3::transform_points(xform, &mut points);
+        }
+        Some(ConvexShape::Hull(points))
+    }
+}
+
+impl Support for ConvexShape {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        match self {
+            ConvexShape::Box(center, axes) => axes.iter().fold(*center, |point, axis| {
+                if axis.dot(&direction) >= 0.0 {
+                    point + *axis
+                } else {
+                    point - *axis
+                }
+            }),
+            ConvexShape::Cylinder(start, end, radius) => {
+                let axis = *end - *start;
+                let cap = if axis.dot(&direction) >= 0.0 {
+                    end
+                } else {
+                    start
+                };
+                let radial = match axis.normalized() {
+                    Some(unit) => direction - unit * unit.dot(&direction),
+                    None => direction,
+                };
+                *cap + radial.normalized().map_or(Vec3::default(), |r| r * *radius)
+            }
+            ConvexShape::Hull(points) => points.iter().fold(points[0], |best, p| {
+                if p.dot(&direction) > best.dot(&direction) {
+                    *p
+                } else {
+                    best
+                }
+            }),
+        }
+    }
+}
+
+/// A shape grown by a distance in all directions.
+struct Inflated<'a>(&'a ConvexShape, Scalar);
+
+impl Support for Inflated<'_> {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let grow = direction
+            .normalized()
+            .map_or(Vec3::default(), |d| d * self.1);
+        self.0.support(direction) + grow
+    }
+}
+
+/// Contact point, normal and penetration depth of two convex objects.
+///
+/// Boxes and cylinders are tested exactly, meshes, point clouds, polylines,
+/// lines and points by their convex hulls, see `gjk`. Objects closer than
+/// the tolerance are in contact with a negative depth, so touching faces
+/// get a contact as well.
+///
+/// # Arguments
+/// * `a` - First object
+/// * `b` - Second object
+/// * `tolerance` - Gap below which the objects are in contact
+///
+/// # Returns
+/// None if either object has no convex shape or the objects are apart.
+pub fn contact(a: &Geometry, b: &Geometry, tolerance: Scalar) -> Option<Contact> {
+    contact_between(&ConvexShape::of(a)?, &ConvexShape::of(b)?, tolerance)
+}
+
+/// `contact` for shapes built with `ConvexShape::of`.
+pub(crate) fn contact_between(
+    a: &ConvexShape,
+    b: &ConvexShape,
+    tolerance: Scalar,
+) -> Option<Contact> {
+    // Growing the first shape keeps the difference solid for flat or touching shapes
+    let margin = tolerance.max(Tolerance::ABSOLUTE);
+    let found = penetration(&Inflated(a, margin), b)?;
+    Some(Contact {
+        point_a: found.point_a - found.normal * margin,
+        point_b: found.point_b,
+        normal: found.normal,
+        depth: found.depth - margin,
+    })
+}
+
+#[cfg(test)]
+#[path = "collision_test.rs"]
+mod collision_test;
@@ -0,0 +1,139 @@
+use crate::collision::{contact, narrow_phase};
+use crate::Scalar;
+use crate::{BoundingBox, CollisionFilter, Cylinder, Geometry, Line, Mesh, Plane, Point, Xform};
+
+fn cube(size: Scalar) -> Mesh {
+    let p = |x: Scalar, y: Scalar, z: Scalar| Point::new(x * size, y * size, z * size);
+    Mesh::from_polygons(
+        vec![
+            vec![
+                p(0.0, 0.0, 0.0),
+                p(0.0, 1.0, 0.0),
+                p(1.0, 1.0, 0.0),
+                p(1.0, 0.0, 0.0),
+            ],
+            vec![
+                p(0.0, 0.0, 1.0),
+                p(1.0, 0.0, 1.0),
+                p(1.0, 1.0, 1.0),
+                p(0.0, 1.0, 1.0),
+            ],
+            vec![
+                p(0.0, 0.0, 0.0),
+                p(1.0, 0.0, 0.0),
+                p(1.0, 0.0, 1.0),
+                p(0.0, 0.0, 1.0),
+            ],
+            vec![
+                p(1.0, 0.0, 0.0),
+                p(1.0, 1.0, 0.0),
+                p(1.0, 1.0, 1.0),
+                p(1.0, 0.0, 1.0),
+            ],
+            vec![
+                p(1.0, 1.0, 0.0),
+                p(0.0, 1.0, 0.0),
+                p(0.0, 1.0, 1.0),
+                p(1.0, 1.0, 1.0),
+            ],
+            vec![
+                p(0.0, 1.0, 0.0),
+                p(0.0, 0.0, 0.0),
+                p(0.0, 0.0, 1.0),
+                p(0.0, 1.0, 1.0),
+            ],
+        ],
+        None,
+    )
+}
+
+#[test]
+fn test_narrow_phase_capsules() {
+    // Parallel diagonals share a bounding box but never touch
+    let a = Geometry::Line(Line::new(0.0, 0.0, 0.0, 10.0, 10.0, 0.0));
+    let b = Geometry::Line(Line::new(1.0, 0.0, 0.0, 11.0, 10.0, 0.0));
+    assert!(!narrow_phase(&a, &b, 1e-3));
+    assert!(narrow_phase(&a, &b, 1.0));
+
+    let cylinder = Cylinder::new(Line::new(1.0, 0.0, 0.0, 11.0, 10.0, 0.0), 0.8);
+    assert!(narrow_phase(&a, &Geometry::Cylinder(cylinder), 1e-3));
+
+    // Lines are compared in world coordinates
+    let mut moved = Line::new(1.0, 0.0, 0.0, 11.0, 10.0, 0.0);
+    moved.xform = Xform::translation(-1.0, 0.0, 0.0);
+    assert!(narrow_phase(&a, &Geometry::Line(moved), 1e-3));
+}
+
+#[test]
+fn test_narrow_phase_meshes() {
+    let a = Geometry::Mesh(cube(1.0));
+
+    let mut overlapping = cube(1.0);
+    overlapping.xform = Xform::translation(0.5, 0.5, 0.5);
+    assert!(narrow_phase(&a, &Geometry::Mesh(overlapping), 1e-3));
+
+    let mut apart = cube(1.0);
+    apart.xform = Xform::translation(1.5, 0.0, 0.0);
+    assert!(!narrow_phase(&a, &Geometry::Mesh(apart), 1e-3));
+
+    // A small cube inside a large one has no crossing triangles
+    let mut inner = cube(0.2);
+    inner.xform = Xform::translation(0.4, 0.4, 0.4);
+    assert!(narrow_phase(&a, &Geometry::Mesh(inner.clone()), 1e-3));
+    let inner = Geometry::Mesh(inner);
+    assert!(narrow_phase(&inner, &a, 1e-3));
+
+    // Pairs without an exact test keep the box overlap result
+    let point = Geometry::Point(Point::new(5.0, 5.0, 5.0));
+    assert!(narrow_phase(&a, &point, 1e-3));
+}
+
+#[test]
+fn test_collision_filter_groups_and_masks() {
+    let default = CollisionFilter::default();
+    assert!(default.collides_with(&default));
+
+    let scaffold = CollisionFilter::new(0, u32::MAX);
+    assert!(!scaffold.collides_with(&default));
+
+    // Both sides must accept the other's group
+    let walls = CollisionFilter::new(2, 1);
+    assert!(walls.collides_with(&default));
+    assert!(!walls.collides_with(&walls));
+    assert!(!walls.collides_with(&CollisionFilter::new(1, 4)));
+}
+
+#[test]
+fn test_contact_of_convex_shapes() {
+    let close = |a: Scalar, b: Scalar| (a - b).abs() < 1e-4;
+    // Unit cube hull from 0 to 1 and a box of half size 1 around (0.5, 0.5, 1.8)
+    let hull = Geometry::Mesh(cube(1.0));
+    let bbox = Geometry::BoundingBox(BoundingBox::from_point(Point::new(0.5, 0.5, 1.8), 1.0));
+    let found = contact(&hull, &bbox, 1e-3).unwrap();
+    assert!(close(found.depth, 0.2), "{found:?}");
+    assert!(close(found.normal.z, 1.0));
+    assert!(close(found.point_a.z, 1.0));
+    assert!(close(found.point_b.z, 0.8));
+    let flipped = contact(&bbox, &hull, 1e-3).unwrap();
+    assert!(close(flipped.normal.z, -1.0) && close(flipped.depth, 0.2));
+
+    // Gaps within the tolerance give negative depths, larger gaps no contact
+    let mut moved = cube(1.0);
+    moved.xform = Xform::translation(0.0, 0.0, 1.05);
+    let moved = Geometry::Mesh(moved);
+    let near = contact(&hull, &moved, 0.1).unwrap();
+    assert!(close(near.depth, -0.05));
+    assert!(contact(&hull, &moved, 1e-3).is_none());
+
+    // Cylinders keep their flat caps and round sides
+    let cylinder = Geometry::Cylinder(Cylinder::new(Line::new(-1.0, 0.5, 0.5, 3.0, 0.5, 0.5), 0.3));
+    let side = Geometry::BoundingBox(BoundingBox::from_point(Point::new(1.0, 0.5, 1.2), 0.5));
+    let found = contact(&cylinder, &side, 1e-3).unwrap();
+    assert!(close(found.depth, 0.1), "{found:?}");
+    let cap = Geometry::BoundingBox(BoundingBox::from_point(Point::new(3.4, 0.5, 0.5), 0.5));
+    let found = contact(&cylinder, &cap, 1e-3).unwrap();
+    assert!(close(found.depth, 0.1) && close(found.normal.x, 1.0));
+
+    let plane = Geometry::Plane(Plane::xy_plane());
+    assert!(contact(&hull, &plane, 1e-3).is_none());
+}
@@ -1,6 +1,11 @@
-use serde::{ser::Serialize as SerTrait, Deserialize, Serialize};
-use std::fmt;
-use uuid::Uuid;
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use core::fmt;
+#[cfg(feature = "std")]
+use serde::ser::Serialize as SerTrait;
+use serde::{Deserialize, Serialize};
 
 /// A color with RGBA values and JSON serialization support.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,7 +23,7 @@ impl Color {
     /// Create new color.
     pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
         Color {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "Color".to_string(),
             r,
             g,
@@ -209,12 +214,135 @@ impl Color {
         )
     }
 
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Conversions
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Parse a hex color `#RRGGBB` or `#RRGGBBAA`, the `#` is optional.
+    ///
+    /// # Returns
+    /// None if the text is not 6 or 8 hex digits.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+        let a = if digits.len() == 8 { channel(6)? } else { 255 };
+        Some(Color::new(channel(0)?, channel(2)?, channel(4)?, a))
+    }
+
+    /// Format as `#RRGGBB`, or `#RRGGBBAA` if the color is not opaque.
+    pub fn to_hex(&self) -> String {
+        if self.a == 255 {
+            format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02X}{:02X}{:02X}{:02X}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// Create an opaque color from hue in degrees, saturation and value in [0-1].
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Self {
+        let chroma = value * saturation;
+        let [r, g, b] = hue_to_rgb(hue, chroma);
+        let m = value - chroma;
+        Color::from_float(r + m, g + m, b + m, 1.0)
+    }
+
+    /// Convert to `[hue, saturation, value]`, hue in degrees [0-360).
+    pub fn to_hsv(&self) -> [f64; 3] {
+        let (hue, max, min) = self.hue_max_min();
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        [hue, saturation, max]
+    }
+
+    /// Create an opaque color from hue in degrees, saturation and lightness in [0-1].
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let [r, g, b] = hue_to_rgb(hue, chroma);
+        let m = lightness - chroma * 0.5;
+        Color::from_float(r + m, g + m, b + m, 1.0)
+    }
+
+    /// Convert to `[hue, saturation, lightness]`, hue in degrees [0-360).
+    pub fn to_hsl(&self) -> [f64; 3] {
+        let (hue, max, min) = self.hue_max_min();
+        let lightness = (max + min) * 0.5;
+        let saturation = if max > min {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        [hue, saturation, lightness]
+    }
+
+    /// Hue in degrees and the largest and smallest channel in [0-1].
+    fn hue_max_min(&self) -> (f64, f64, f64) {
+        let [r, g, b, _] = self.to_float_array();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let sector = if chroma == 0.0 {
+            0.0
+        } else if max == r {
+            (g - b) / chroma
+        } else if max == g {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        };
+        let hue = 60.0 * sector;
+        (if hue < 0.0 { hue + 360.0 } else { hue }, max, min)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Gradients
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Interpolate all four channels, `t = 0` gives `a` and `t = 1` gives `b`.
+    pub fn lerp(a: &Color, b: &Color, t: f64) -> Self {
+        let (a, b) = (a.to_float_array(), b.to_float_array());
+        let mix = |i: usize| (a[i] + (b[i] - a[i]) * t).clamp(0.0, 1.0);
+        Color::from_float(mix(0), mix(1), mix(2), mix(3))
+    }
+
+    /// Color of a value in a colormap, e.g. to show analysis results on mesh vertices.
+    ///
+    /// # Arguments
+    /// * `value` - The value to show, clamped to the range
+    /// * `min` - Value shown with the first color of the palette
+    /// * `max` - Value shown with the last color of the palette
+    /// * `palette` - The colormap
+    pub fn map(value: f64, min: f64, max: f64, palette: Colormap) -> Self {
+        let t = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        // NaN values and ranges get the first color
+        let t = if t.is_nan() { 0.0 } else { t };
+        match palette {
+            Colormap::Viridis => {
+                let scaled = t * (VIRIDIS.len() - 1) as f64;
+                let i = (scaled.floor() as usize).min(VIRIDIS.len() - 2);
+                let [a, b] = [VIRIDIS[i], VIRIDIS[i + 1]].map(|[r, g, b]| Color::new(r, g, b, 255));
+                Color::lerp(&a, &b, scaled - i as f64)
+            }
+            Colormap::Jet => {
+                let ramp = |center: f64| (1.5 - (4.0 * t - center).abs()).clamp(0.0, 1.0);
+                Color::from_float(ramp(3.0), ramp(2.0), ramp(1.0), 1.0)
+            }
+            Colormap::Grayscale => Color::from_float(t, t, t, 1.0),
+        }
+    }
+
     ///////////////////////////////////////////////////////////////////////////////////////////
     // JSON
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Serialize to JSON string (for cross-language compatibility)
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
@@ -223,21 +351,65 @@ impl Color {
     }
 
     /// Deserialize from JSON string (for cross-language compatibility)
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
     /// Serialize to JSON file
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
     }
 
     /// Deserialize from JSON file
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
+    }
+}
+
+/// Palettes of `Color::map`, from low to high values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Colormap {
+    /// Perceptually uniform dark blue to yellow, readable in grayscale
+    #[default]
+    Viridis,
+    /// Blue to cyan, yellow and red
+    Jet,
+    /// Black to white
+    Grayscale,
+}
+
+/// Viridis sampled at 0.0, 0.1, ..., 1.0.
+const VIRIDIS: [[u8; 3]; 11] = [
+    [68, 1, 84],
+    [72, 36, 117],
+    [65, 68, 135],
+    [53, 95, 141],
+    [42, 120, 142],
+    [33, 145, 140],
+    [34, 168, 132],
+    [68, 191, 112],
+    [122, 209, 81],
+    [189, 223, 38],
+    [253, 231, 37],
+];
+
+/// Red, green and blue of a fully saturated hue scaled to `chroma`, before adding the grey part.
+fn hue_to_rgb(hue: f64, chroma: f64) -> [f64; 3] {
+    let sector = (hue - 360.0 * (hue / 360.0).floor()) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    match sector as u8 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
     }
 }
 
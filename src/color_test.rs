@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::encoders::{json_dump, json_load};
-    use crate::Color;
+    use crate::{Color, Colormap};
 
     #[test]
     fn test_color_constructor() {
@@ -159,4 +159,60 @@ mod tests {
         assert_eq!(grey.b, 128);
         assert_eq!(grey.a, 255);
     }
+
+    #[test]
+    fn test_color_hex() {
+        let color = Color::from_hex("#FF8000").unwrap();
+        assert_eq!((color.r, color.g, color.b, color.a), (255, 128, 0, 255));
+        assert_eq!(color.to_hex(), "#FF8000");
+        let translucent = Color::from_hex("0a0B0c80").unwrap();
+        assert_eq!(translucent.a, 128);
+        assert_eq!(translucent.to_hex(), "#0A0B0C80");
+        assert!(Color::from_hex("#FF80").is_none());
+        assert!(Color::from_hex("#GG0000").is_none());
+        assert!(Color::from_hex("#ÿÿÿ").is_none());
+    }
+
+    #[test]
+    fn test_color_hsv_hsl() {
+        assert_eq!(Color::from_hsv(0.0, 1.0, 1.0).to_hex(), "#FF0000");
+        assert_eq!(Color::from_hsv(120.0, 1.0, 0.5).to_hex(), "#008000");
+        assert_eq!(Color::from_hsv(-120.0, 1.0, 1.0).to_hex(), "#0000FF");
+        assert_eq!(Color::from_hsl(60.0, 1.0, 0.5).to_hex(), "#FFFF00");
+        assert_eq!(Color::from_hsl(0.0, 0.0, 0.5).to_hex(), "#808080");
+
+        let orange = Color::orange();
+        let [h, s, v] = orange.to_hsv();
+        assert!((h - 30.1).abs() < 0.1 && s == 1.0 && v == 1.0);
+        assert_eq!(Color::from_hsv(h, s, v).to_hex(), orange.to_hex());
+        let [h, s, l] = Color::teal().to_hsl();
+        assert_eq!(Color::from_hsl(h, s, l).to_hex(), Color::teal().to_hex());
+        assert_eq!(Color::grey().to_hsv()[1], 0.0);
+    }
+
+    #[test]
+    fn test_color_lerp_and_map() {
+        let mid = Color::lerp(&Color::black(), &Color::white(), 0.5);
+        assert_eq!((mid.r, mid.a), (128, 255));
+        assert_eq!(Color::lerp(&Color::red(), &Color::blue(), 2.0).b, 255);
+
+        assert_eq!(
+            Color::map(0.0, 0.0, 1.0, Colormap::Viridis).to_hex(),
+            "#440154"
+        );
+        assert_eq!(
+            Color::map(5.0, 0.0, 1.0, Colormap::Viridis).to_hex(),
+            "#FDE725"
+        );
+        assert_eq!(
+            Color::map(0.5, 0.0, 1.0, Colormap::Viridis).to_hex(),
+            "#21918C"
+        );
+        assert_eq!(Color::map(0.0, 0.0, 1.0, Colormap::Jet).to_hex(), "#000080");
+        assert_eq!(Color::map(0.5, 0.0, 1.0, Colormap::Jet).to_hex(), "#80FF80");
+        assert_eq!(Color::map(1.0, 0.0, 1.0, Colormap::Jet).to_hex(), "#800000");
+        assert_eq!(Color::map(2.0, 0.0, 4.0, Colormap::Grayscale).r, 128);
+        assert_eq!(Color::map(1.0, 1.0, 1.0, Colormap::Grayscale).r, 0);
+        assert_eq!(Color::map(f64::NAN, 0.0, 1.0, Colormap::Grayscale).r, 0);
+    }
 }
@@ -0,0 +1,575 @@
+use crate::intersection::{line_line_parameters, Circle};
+use crate::{Line, NurbsCurve, Point, Polyline, Scalar, Tolerance, Vector};
+
+/// Result of filleting two curves: trimmed inputs and the connecting arc.
+///
+/// `curve_b` is a [`CircularArc`] when a line is filleted with an arc.
+#[derive(Debug, Clone)]
+pub struct FilletResult<B = Line> {
+    pub curve_a: Line,
+    pub curve_b: B,
+    pub arc: NurbsCurve,
+}
+
+/// Result of chamfering two curves: trimmed inputs and the connecting segment.
+///
+/// `curve_b` is a [`CircularArc`] when a line is chamfered with an arc.
+#[derive(Debug, Clone)]
+pub struct ChamferResult<B = Line> {
+    pub curve_a: Line,
+    pub curve_b: B,
+    pub segment: Line,
+}
+
+/// Circular arc running counterclockwise about the plane normal from
+/// `start_angle` to `end_angle`, with angles measured from the plane x-axis.
+#[derive(Debug, Clone)]
+pub struct CircularArc {
+    pub circle: Circle,
+    pub start_angle: Scalar,
+    pub end_angle: Scalar,
+}
+
+impl CircularArc {
+    pub fn new(circle: Circle, start_angle: Scalar, end_angle: Scalar) -> Self {
+        CircularArc {
+            circle,
+            start_angle,
+            end_angle,
+        }
+    }
+
+    /// Point on the circle at the given angle.
+    pub fn point_at_angle(&self, angle: Scalar) -> Point {
+        let plane = &self.circle.plane;
+        let direction = plane.x_axis() * angle.cos() + plane.y_axis() * angle.sin();
+        plane.origin() + direction * self.circle.radius
+    }
+
+    pub fn start(&self) -> Point {
+        self.point_at_angle(self.start_angle)
+    }
+
+    pub fn end(&self) -> Point {
+        self.point_at_angle(self.end_angle)
+    }
+
+    /// Angle of a point projected onto the plane of the arc, in (-pi, pi].
+    pub fn angle_of(&self, point: &Point) -> Scalar {
+        let plane = &self.circle.plane;
+        let v = point.clone() - plane.origin();
+        v.dot(&plane.y_axis()).atan2(v.dot(&plane.x_axis()))
+    }
+}
+
+/// Corner formed by two lines: the intersection point, the unit directions
+/// from the corner towards the far end of each line and the available leg lengths.
+struct Corner {
+    point: Point,
+    dir_a: Vector,
+    dir_b: Vector,
+    length_a: Scalar,
+    length_b: Scalar,
+}
+
+impl Corner {
+    fn from_lines(curve_a: &Line, curve_b: &Line) -> Option<Self> {
+        let (t0, t1) =
+            line_line_parameters(curve_a, curve_b, Tolerance::APPROXIMATION, false, false)?;
+        let point = curve_a.point_at(t0);
+        let point_b = curve_b.point_at(t1);
+        let point = Point::new(
+            (point.x() + point_b.x()) * 0.5,
+            (point.y() + point_b.y()) * 0.5,
+            (point.z() + point_b.z()) * 0.5,
+        );
+
+        let far_a = far_end(curve_a, &point);
+        let far_b = far_end(curve_b, &point);
+        let leg_a = far_a - point.clone();
+        let leg_b = far_b - point.clone();
+
+        Some(Corner {
+            point,
+            length_a: leg_a.compute_length(),
+            length_b: leg_b.compute_length(),
+            dir_a: leg_a.normalize(),
+            dir_b: leg_b.normalize(),
+        })
+    }
+
+    fn from_points(prev: &Point, point: &Point, next: &Point) -> Self {
+        let leg_a = prev.clone() - point.clone();
+        let leg_b = next.clone() - point.clone();
+        Corner {
+            point: point.clone(),
+            length_a: leg_a.compute_length(),
+            length_b: leg_b.compute_length(),
+            dir_a: leg_a.normalize(),
+            dir_b: leg_b.normalize(),
+        }
+    }
+
+    /// Interior angle at the corner in radians, None for degenerate or straight corners.
+    fn angle(&self) -> Option<Scalar> {
+        if self.length_a < Tolerance::ZERO_TOLERANCE || self.length_b < Tolerance::ZERO_TOLERANCE {
+            return None;
+        }
+        let cos_theta = self.dir_a.dot(&self.dir_b).clamp(-1.0, 1.0);
+        let theta = cos_theta.acos();
+        if !(Tolerance::ANGULAR..=crate::scalar::consts::PI - Tolerance::ANGULAR).contains(&theta) {
+            return None;
+        }
+        Some(theta)
+    }
+
+    fn point_on_a(&self, distance: Scalar) -> Point {
+        self.point.clone() + self.dir_a.clone() * distance
+    }
+
+    fn point_on_b(&self, distance: Scalar) -> Point {
+        self.point.clone() + self.dir_b.clone() * distance
+    }
+}
+
+/// Endpoint of the line farther from the given point.
+fn far_end(line: &Line, point: &Point) -> Point {
+    let start = line.start();
+    let end = line.end();
+    if start.distance(point) > end.distance(point) {
+        start
+    } else {
+        end
+    }
+}
+
+/// Trim a line so that the endpoint closest to `corner` is moved to `point`,
+/// keeping the original orientation.
+fn trim_line(line: &Line, corner: &Point, point: &Point) -> Line {
+    let start = line.start();
+    let end = line.end();
+    let mut trimmed = if start.distance(corner) > end.distance(corner) {
+        Line::from_points(&start, point)
+    } else {
+        Line::from_points(point, &end)
+    };
+    trimmed.name = line.name.clone();
+    trimmed.width = line.width;
+    trimmed.linecolor = line.linecolor.clone();
+    trimmed.xform = line.xform.clone();
+    trimmed
+}
+
+/// Corner formed by a line and an arc: the point where the line meets the
+/// circle of the arc, the unit direction from it towards the far end of the line
+/// and the turn (+1 counterclockwise) and sweep towards the far end of the arc.
+struct LineArcCorner {
+    point: Point,
+    angle: Scalar,
+    dir_line: Vector,
+    length_line: Scalar,
+    turn: Scalar,
+    sweep: Scalar,
+}
+
+impl LineArcCorner {
+    fn new(line: &Line, arc: &CircularArc) -> Option<Self> {
+        let plane = &arc.circle.plane;
+        let radius = arc.circle.radius;
+        let start = line.start();
+        let end = line.end();
+        if radius < Tolerance::ZERO_TOLERANCE
+            || plane.signed_distance(&start).abs() > Tolerance::APPROXIMATION
+            || plane.signed_distance(&end).abs() > Tolerance::APPROXIMATION
+        {
+            return None;
+        }
+        let direction = end.clone() - start.clone();
+        if direction.compute_length() < Tolerance::ZERO_TOLERANCE {
+            return None;
+        }
+        let direction = direction.normalize();
+
+        // Intersections of the infinite line with the circle
+        let center = plane.origin();
+        let closest =
+            start.clone() + direction.clone() * (center.clone() - start.clone()).dot(&direction);
+        let distance = closest.distance(&center);
+        if distance > radius + Tolerance::APPROXIMATION {
+            return None;
+        }
+        let half_chord = (radius * radius - distance * distance).max(0.0).sqrt();
+        let candidates = [
+            closest.clone() - direction.clone() * half_chord,
+            closest + direction * half_chord,
+        ];
+
+        // The corner is the intersection closest to the ends where the curves meet
+        let gap = |p: &Point| {
+            start.distance(p).min(end.distance(p))
+                + arc.start().distance(p).min(arc.end().distance(p))
+        };
+        let point = if gap(&candidates[0]) <= gap(&candidates[1]) {
+            candidates[0].clone()
+        } else {
+            candidates[1].clone()
+        };
+        let angle = arc.angle_of(&point);
+
+        let leg = far_end(line, &point) - point.clone();
+        let tau = 2.0 * crate::scalar::consts::PI;
+        let (turn, sweep) = if arc.start().distance(&point) > arc.end().distance(&point) {
+            (-1.0, (angle - arc.start_angle).rem_euclid(tau))
+        } else {
+            (1.0, (arc.end_angle - angle).rem_euclid(tau))
+        };
+
+        Some(LineArcCorner {
+            point,
+            angle,
+            length_line: leg.compute_length(),
+            dir_line: leg.normalize(),
+            turn,
+            sweep,
+        })
+    }
+
+    /// Unit tangent of the circle at the corner, towards the far end of the arc.
+    fn arc_tangent(&self, arc: &CircularArc) -> Vector {
+        let radial = (self.point.clone() - arc.circle.plane.origin()).normalize();
+        arc.circle.plane.z_axis().cross(&radial) * self.turn
+    }
+
+    /// Angle between the line and the arc tangent, None for degenerate or tangent corners.
+    fn angle(&self, arc: &CircularArc) -> Option<Scalar> {
+        if self.length_line < Tolerance::ZERO_TOLERANCE || self.sweep < Tolerance::ANGULAR {
+            return None;
+        }
+        let cos_theta = self.dir_line.dot(&self.arc_tangent(arc)).clamp(-1.0, 1.0);
+        let theta = cos_theta.acos();
+        if !(Tolerance::ANGULAR..=crate::scalar::consts::PI - Tolerance::ANGULAR).contains(&theta) {
+            return None;
+        }
+        Some(theta)
+    }
+
+    fn point_on_line(&self, distance: Scalar) -> Point {
+        self.point.clone() + self.dir_line.clone() * distance
+    }
+
+    /// Point on the arc `delta` radians from the corner towards its far end.
+    fn point_on_arc(&self, arc: &CircularArc, delta: Scalar) -> Point {
+        arc.point_at_angle(self.angle + self.turn * delta)
+    }
+
+    /// Trim the arc so that it starts `delta` radians from the corner, keeping
+    /// the original orientation.
+    fn trim_arc(&self, arc: &CircularArc, delta: Scalar) -> CircularArc {
+        let mut trimmed = arc.clone();
+        if self.turn > 0.0 {
+            trimmed.start_angle = arc.end_angle - (self.sweep - delta);
+        } else {
+            trimmed.end_angle = arc.start_angle + (self.sweep - delta);
+        }
+        trimmed
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Fillet
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Fillet two lines with a circular arc of the given radius.
+///
+/// The lines are extended or trimmed to their intersection, so they only need
+/// to be coplanar. Each line keeps the end farther from the corner.
+///
+/// # Arguments
+/// * `curve_a` - First line
+/// * `curve_b` - Second line
+/// * `radius` - Fillet radius
+///
+/// # Returns
+/// Trimmed lines and the arc as a rational quadratic NURBS curve, or None if
+/// the lines are parallel, skew or too short for the radius.
+pub fn fillet(curve_a: &Line, curve_b: &Line, radius: Scalar) -> Option<FilletResult> {
+    if radius <= 0.0 {
+        return None;
+    }
+    let corner = Corner::from_lines(curve_a, curve_b)?;
+    let theta = corner.angle()?;
+
+    let distance = radius / (theta * 0.5).tan();
+    if distance > corner.length_a || distance > corner.length_b {
+        return None;
+    }
+
+    let tangent_a = corner.point_on_a(distance);
+    let tangent_b = corner.point_on_b(distance);
+    let arc = NurbsCurve::create_arc(&tangent_a, &corner.point, &tangent_b)?;
+
+    Some(FilletResult {
+        curve_a: trim_line(curve_a, &corner.point, &tangent_a),
+        curve_b: trim_line(curve_b, &corner.point, &tangent_b),
+        arc,
+    })
+}
+
+/// Fillet a line and a circular arc with a circular arc of the given radius.
+///
+/// The line is extended or trimmed to the circle of the arc, so it only needs
+/// to lie in the plane of the arc. The line and the arc each keep the end
+/// farther from the corner.
+///
+/// # Arguments
+/// * `line` - Line
+/// * `arc` - Circular arc
+/// * `radius` - Fillet radius
+///
+/// # Returns
+/// Trimmed line and arc and the fillet as a rational quadratic NURBS curve, or
+/// None if the line misses or touches the circle or the curves are too short for the radius.
+pub fn fillet_line_arc(
+    line: &Line,
+    arc: &CircularArc,
+    radius: Scalar,
+) -> Option<FilletResult<CircularArc>> {
+    if radius <= 0.0 {
+        return None;
+    }
+    let corner = LineArcCorner::new(line, arc)?;
+    corner.angle(arc)?;
+    let center = arc.circle.plane.origin();
+    let tangent = corner.arc_tangent(arc);
+
+    // The fillet center is offset from the line towards the arc, and lies
+    // inside the circle when the line runs into it
+    let side = arc.circle.plane.z_axis().cross(&corner.dir_line);
+    let side = if side.dot(&tangent) < 0.0 {
+        -side
+    } else {
+        side
+    };
+    let inside = corner
+        .dir_line
+        .dot(&(center.clone() - corner.point.clone()))
+        > 0.0;
+    let distance = if inside {
+        arc.circle.radius - radius
+    } else {
+        arc.circle.radius + radius
+    };
+    if distance < Tolerance::ZERO_TOLERANCE {
+        return None;
+    }
+
+    // Solve |corner + side * r + dir * s - center| = distance for the nearest s > 0
+    let offset = corner.point.clone() + side * radius - center.clone();
+    let b = offset.dot(&corner.dir_line);
+    let c = offset.dot(&offset) - distance * distance;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let s = [-b - root, -b + root]
+        .into_iter()
+        .find(|s| *s > Tolerance::ZERO_TOLERANCE)?;
+    if s > corner.length_line {
+        return None;
+    }
+
+    let fillet_center = center.clone() + (offset + corner.dir_line.clone() * s);
+    let tangent_line = corner.point_on_line(s);
+    let tangent_arc =
+        center.clone() + (fillet_center.clone() - center) * (arc.circle.radius / distance);
+    let tau = 2.0 * crate::scalar::consts::PI;
+    let delta = (corner.turn * (arc.angle_of(&tangent_arc) - corner.angle)).rem_euclid(tau);
+    if delta > corner.sweep {
+        return None;
+    }
+
+    // Tangents at both ends of the fillet meet along the bisector
+    let bisector = ((tangent_line.clone() - fillet_center.clone())
+        + (tangent_arc.clone() - fillet_center.clone()))
+    .normalize();
+    let cos_half = bisector.dot(&(tangent_line.clone() - fillet_center.clone())) / radius;
+    if cos_half < Tolerance::ZERO_TOLERANCE {
+        return None;
+    }
+    let control = fillet_center + bisector * (radius / cos_half);
+    let fillet = NurbsCurve::create_arc(&tangent_line, &control, &tangent_arc)?;
+
+    Some(FilletResult {
+        curve_a: trim_line(line, &corner.point, &tangent_line),
+        curve_b: corner.trim_arc(arc, delta),
+        arc: fillet,
+    })
+}
+
+/// Fillet every interior corner of a polyline, sampling each arc into points.
+///
+/// Closed polylines are filleted at the seam as well. Corners whose tangent
+/// distance exceeds half of an adjacent segment are left sharp.
+///
+/// # Arguments
+/// * `polyline` - Input polyline
+/// * `radius` - Fillet radius
+/// * `segments` - Number of segments used to approximate each arc
+pub fn fillet_polyline(polyline: &Polyline, radius: Scalar, segments: usize) -> Polyline {
+    let segments = segments.max(1);
+    map_corners(polyline, |corner| {
+        let theta = corner.angle()?;
+        let distance = radius / (theta * 0.5).tan();
+        if radius <= 0.0 || distance > corner.length_a * 0.5 || distance > corner.length_b * 0.5 {
+            return None;
+        }
+        let arc = NurbsCurve::create_arc(
+            &corner.point_on_a(distance),
+            &corner.point,
+            &corner.point_on_b(distance),
+        )?;
+        let (t0, t1) = arc.domain();
+        Some(
+            (0..=segments)
+                .map(|i| arc.point_at(t0 + (t1 - t0) * i as Scalar / segments as Scalar))
+                .collect(),
+        )
+    })
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Chamfer
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Chamfer two lines with a straight segment.
+///
+/// # Arguments
+/// * `curve_a` - First line
+/// * `curve_b` - Second line
+/// * `d0` - Distance from the corner along `curve_a`
+/// * `d1` - Distance from the corner along `curve_b`
+///
+/// # Returns
+/// Trimmed lines and the chamfer segment from `curve_a` to `curve_b`, or None if
+/// the lines do not intersect or are too short for the distances.
+pub fn chamfer(curve_a: &Line, curve_b: &Line, d0: Scalar, d1: Scalar) -> Option<ChamferResult> {
+    if d0 <= 0.0 || d1 <= 0.0 {
+        return None;
+    }
+    let corner = Corner::from_lines(curve_a, curve_b)?;
+    corner.angle()?;
+    if d0 > corner.length_a || d1 > corner.length_b {
+        return None;
+    }
+
+    let point_a = corner.point_on_a(d0);
+    let point_b = corner.point_on_b(d1);
+
+    Some(ChamferResult {
+        curve_a: trim_line(curve_a, &corner.point, &point_a),
+        curve_b: trim_line(curve_b, &corner.point, &point_b),
+        segment: Line::from_points(&point_a, &point_b),
+    })
+}
+
+/// Chamfer a line and a circular arc with a straight segment.
+///
+/// # Arguments
+/// * `line` - Line
+/// * `arc` - Circular arc
+/// * `d0` - Distance from the corner along `line`
+/// * `d1` - Arc length from the corner along `arc`
+///
+/// # Returns
+/// Trimmed line and arc and the chamfer segment from `line` to `arc`, or None if
+/// the line misses or touches the circle or the curves are too short for the distances.
+pub fn chamfer_line_arc(
+    line: &Line,
+    arc: &CircularArc,
+    d0: Scalar,
+    d1: Scalar,
+) -> Option<ChamferResult<CircularArc>> {
+    if d0 <= 0.0 || d1 <= 0.0 {
+        return None;
+    }
+    let corner = LineArcCorner::new(line, arc)?;
+    corner.angle(arc)?;
+    let delta = d1 / arc.circle.radius;
+    if d0 > corner.length_line || delta > corner.sweep {
+        return None;
+    }
+
+    let point_a = corner.point_on_line(d0);
+    let point_b = corner.point_on_arc(arc, delta);
+
+    Some(ChamferResult {
+        curve_a: trim_line(line, &corner.point, &point_a),
+        curve_b: corner.trim_arc(arc, delta),
+        segment: Line::from_points(&point_a, &point_b),
+    })
+}
+
+/// Chamfer every interior corner of a polyline by the same distance on both sides.
+///
+/// Corners where the distance exceeds half of an adjacent segment are left sharp.
+pub fn chamfer_polyline(polyline: &Polyline, distance: Scalar) -> Polyline {
+    map_corners(polyline, |corner| {
+        corner.angle()?;
+        if distance <= 0.0 || distance > corner.length_a * 0.5 || distance > corner.length_b * 0.5 {
+            return None;
+        }
+        Some(vec![
+            corner.point_on_a(distance),
+            corner.point_on_b(distance),
+        ])
+    })
+}
+
+/// Replace each polyline corner with the points returned by `f`, keeping the
+/// corner point when `f` returns None.
+fn map_corners<F>(polyline: &Polyline, f: F) -> Polyline
+where
+    F: Fn(&Corner) -> Option<Vec<Point>>,
+{
+    let points = &polyline.points;
+    let closed = polyline.is_closed();
+    // A closed polyline repeats its first point at the end
+    let n = if closed {
+        points.len() - 1
+    } else {
+        points.len()
+    };
+    if n < 3 {
+        return polyline.clone();
+    }
+
+    let mut result = Vec::with_capacity(n);
+    for i in 0..n {
+        let is_end = !closed && (i == 0 || i == n - 1);
+        if is_end {
+            result.push(points[i].clone());
+            continue;
+        }
+        let prev = &points[(i + n - 1) % n];
+        let next = &points[(i + 1) % n];
+        let corner = Corner::from_points(prev, &points[i], next);
+        match f(&corner) {
+            Some(replacement) => result.extend(replacement),
+            None => result.push(points[i].clone()),
+        }
+    }
+    if closed {
+        if let Some(first) = result.first().cloned() {
+            result.push(first);
+        }
+    }
+
+    let mut filleted = polyline.clone();
+    filleted.points = result;
+    filleted
+}
+
+#[cfg(test)]
+#[path = "curve_test.rs"]
+mod curve_test;
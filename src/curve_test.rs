@@ -0,0 +1,204 @@
+use crate::curve::{
+    chamfer, chamfer_line_arc, chamfer_polyline, fillet, fillet_line_arc, fillet_polyline,
+    CircularArc,
+};
+use crate::intersection::Circle;
+use crate::scalar::test_tolerance;
+use crate::Scalar;
+use crate::{Line, NurbsCurve, Plane, Point, Polyline};
+
+/// Quarter arc of radius 10 around the origin, from (10, 0, 0) to (0, 10, 0).
+fn quarter_arc() -> CircularArc {
+    let circle = Circle {
+        plane: Plane::xy_plane(),
+        radius: 10.0,
+    };
+    CircularArc::new(circle, 0.0, crate::scalar::consts::FRAC_PI_2)
+}
+
+/// Check that the fillet runs from `start` on the line to the arc at radius `r` around `center`.
+fn assert_fillet_arc(arc: &NurbsCurve, start: &Point, center: &Point, r: Scalar) {
+    assert!(arc.point_at_start().distance(start) < test_tolerance(1e-9));
+    assert!(
+        (arc.point_at_end().distance(&Point::new(0.0, 0.0, 0.0)) - 10.0).abs()
+            < test_tolerance(1e-9)
+    );
+    let (t0, t1) = arc.domain();
+    for i in 0..=10 {
+        let p = arc.point_at(t0 + (t1 - t0) * i as Scalar / 10.0);
+        assert!((p.distance(center) - r).abs() < test_tolerance(1e-9));
+    }
+}
+
+#[test]
+fn test_fillet_right_angle() {
+    let a = Line::new(10.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let b = Line::new(0.0, 0.0, 0.0, 0.0, 10.0, 0.0);
+    let result = fillet(&a, &b, 2.0).unwrap();
+
+    // Trimmed lines keep their orientation and end at the tangent points
+    assert!(result.curve_a.start().distance(&Point::new(10.0, 0.0, 0.0)) < 1e-9);
+    assert!(result.curve_a.end().distance(&Point::new(2.0, 0.0, 0.0)) < 1e-9);
+    assert!(result.curve_b.start().distance(&Point::new(0.0, 2.0, 0.0)) < 1e-9);
+    assert!(result.curve_b.end().distance(&Point::new(0.0, 10.0, 0.0)) < 1e-9);
+
+    // Every arc point lies on the circle centered at (2, 2)
+    let center = Point::new(2.0, 2.0, 0.0);
+    let (t0, t1) = result.arc.domain();
+    for i in 0..=10 {
+        let p = result.arc.point_at(t0 + (t1 - t0) * i as Scalar / 10.0);
+        assert!((p.distance(&center) - 2.0).abs() < 1e-9);
+    }
+    assert!(
+        result
+            .arc
+            .point_at_start()
+            .distance(&Point::new(2.0, 0.0, 0.0))
+            < 1e-9
+    );
+    assert!(
+        result
+            .arc
+            .point_at_end()
+            .distance(&Point::new(0.0, 2.0, 0.0))
+            < 1e-9
+    );
+}
+
+#[test]
+fn test_fillet_non_touching_lines() {
+    // Lines are extended to meet at the origin
+    let a = Line::new(5.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    let b = Line::new(1.0, 1.0, 0.0, 5.0, 5.0, 0.0);
+    let result = fillet(&a, &b, 1.0).unwrap();
+    let tangent = 1.0 / (crate::scalar::consts::FRAC_PI_8).tan();
+    assert!((result.curve_a.end().x() - tangent).abs() < 1e-9);
+    assert!((result.curve_b.start().distance(&Point::new(0.0, 0.0, 0.0)) - tangent).abs() < 1e-9);
+}
+
+#[test]
+fn test_fillet_invalid() {
+    let a = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    let b = Line::new(0.0, 1.0, 0.0, 1.0, 1.0, 0.0);
+    assert!(fillet(&a, &b, 0.5).is_none());
+
+    let c = Line::new(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let d = Line::new(0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+    assert!(fillet(&c, &d, 5.0).is_none());
+}
+
+#[test]
+fn test_chamfer() {
+    let a = Line::new(10.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let b = Line::new(0.0, 0.0, 0.0, 0.0, 10.0, 0.0);
+    let result = chamfer(&a, &b, 1.0, 3.0).unwrap();
+    assert!(result.curve_a.end().distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+    assert!(result.curve_b.start().distance(&Point::new(0.0, 3.0, 0.0)) < 1e-9);
+    assert!(result.segment.start().distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+    assert!(result.segment.end().distance(&Point::new(0.0, 3.0, 0.0)) < 1e-9);
+    assert!(chamfer(&a, &b, 11.0, 1.0).is_none());
+}
+
+#[test]
+fn test_fillet_polyline() {
+    let polyline = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(10.0, 0.0, 0.0),
+        Point::new(10.0, 10.0, 0.0),
+    ]);
+    let filleted = fillet_polyline(&polyline, 1.0, 4);
+    assert_eq!(filleted.len(), 2 + 5);
+    assert!(filleted.points[1].distance(&Point::new(9.0, 0.0, 0.0)) < 1e-9);
+    assert!(filleted.points[5].distance(&Point::new(10.0, 1.0, 0.0)) < 1e-9);
+    assert!((filleted.length() - (18.0 + crate::scalar::consts::FRAC_PI_2)).abs() < 0.05);
+}
+
+#[test]
+fn test_chamfer_closed_polyline() {
+    let polyline = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(4.0, 0.0, 0.0),
+        Point::new(4.0, 4.0, 0.0),
+        Point::new(0.0, 4.0, 0.0),
+        Point::new(0.0, 0.0, 0.0),
+    ]);
+    let chamfered = chamfer_polyline(&polyline, 1.0);
+    assert_eq!(chamfered.len(), 9);
+    assert!(chamfered.is_closed());
+    assert!((chamfered.length() - (8.0 + 4.0 * Scalar::sqrt(2.0))).abs() < test_tolerance(1e-9));
+}
+
+#[test]
+fn test_create_arc() {
+    let arc = NurbsCurve::create_arc(
+        &Point::new(1.0, 0.0, 0.0),
+        &Point::new(1.0, 1.0, 0.0),
+        &Point::new(0.0, 1.0, 0.0),
+    )
+    .unwrap();
+    assert!(arc.is_rational());
+    let mid = arc.point_at(0.5);
+    assert!((mid.distance(&Point::new(0.0, 0.0, 0.0)) - 1.0).abs() < test_tolerance(1e-9));
+}
+
+#[test]
+fn test_fillet_line_arc_outside() {
+    // The line leaves the circle, so the fillet touches the arc from outside
+    let line = Line::new(10.0, 0.0, 0.0, 20.0, 0.0, 0.0);
+    let result = fillet_line_arc(&line, &quarter_arc(), 2.0).unwrap();
+    let x = Scalar::sqrt(140.0);
+    let center = Point::new(x, 2.0, 0.0);
+    assert_fillet_arc(&result.arc, &Point::new(x, 0.0, 0.0), &center, 2.0);
+
+    assert!(result.curve_a.start().distance(&Point::new(x, 0.0, 0.0)) < test_tolerance(1e-9));
+    assert!(result.curve_a.end().distance(&Point::new(20.0, 0.0, 0.0)) < test_tolerance(1e-9));
+    assert!((result.curve_b.start_angle - Scalar::atan2(2.0, x)).abs() < test_tolerance(1e-9));
+    assert!(
+        (result.curve_b.end_angle - crate::scalar::consts::FRAC_PI_2).abs() < test_tolerance(1e-9)
+    );
+    assert!(result.curve_b.start().distance(&result.arc.point_at_end()) < test_tolerance(1e-9));
+}
+
+#[test]
+fn test_fillet_line_arc_inside() {
+    // The line runs into the circle, so the fillet touches the arc from inside
+    let line = Line::new(0.0, 0.0, 0.0, 10.0, 0.0, 0.0);
+    let result = fillet_line_arc(&line, &quarter_arc(), 2.0).unwrap();
+    let x = Scalar::sqrt(60.0);
+    let center = Point::new(x, 2.0, 0.0);
+    assert_fillet_arc(&result.arc, &Point::new(x, 0.0, 0.0), &center, 2.0);
+
+    assert!(result.curve_a.start().distance(&Point::new(0.0, 0.0, 0.0)) < test_tolerance(1e-9));
+    assert!(result.curve_a.end().distance(&Point::new(x, 0.0, 0.0)) < test_tolerance(1e-9));
+    assert!(result.curve_b.start().distance(&result.arc.point_at_end()) < test_tolerance(1e-9));
+}
+
+#[test]
+fn test_fillet_line_arc_invalid() {
+    let arc = quarter_arc();
+    // The line misses the circle
+    let line = Line::new(0.0, 20.0, 0.0, 20.0, 20.0, 0.0);
+    assert!(fillet_line_arc(&line, &arc, 1.0).is_none());
+    // The line is too short for the radius
+    let line = Line::new(10.0, 0.0, 0.0, 11.0, 0.0, 0.0);
+    assert!(fillet_line_arc(&line, &arc, 2.0).is_none());
+    // The fillet does not fit inside the circle
+    let line = Line::new(0.0, 0.0, 0.0, 10.0, 0.0, 0.0);
+    assert!(fillet_line_arc(&line, &arc, 10.0).is_none());
+}
+
+#[test]
+fn test_chamfer_line_arc() {
+    let line = Line::new(20.0, 0.0, 0.0, 10.0, 0.0, 0.0);
+    let arc = quarter_arc();
+    let quarter = crate::scalar::consts::FRAC_PI_4;
+    let result = chamfer_line_arc(&line, &arc, 2.0, 10.0 * quarter).unwrap();
+    let on_arc = Point::new(10.0 * quarter.cos(), 10.0 * quarter.sin(), 0.0);
+
+    assert!(result.curve_a.start().distance(&Point::new(20.0, 0.0, 0.0)) < test_tolerance(1e-9));
+    assert!(result.curve_a.end().distance(&Point::new(12.0, 0.0, 0.0)) < test_tolerance(1e-9));
+    assert!((result.curve_b.start_angle - quarter).abs() < test_tolerance(1e-9));
+    assert!(result.segment.start().distance(&Point::new(12.0, 0.0, 0.0)) < test_tolerance(1e-9));
+    assert!(result.segment.end().distance(&on_arc) < test_tolerance(1e-9));
+    assert!(chamfer_line_arc(&line, &arc, 2.0, 20.0).is_none());
+}
@@ -1,6 +1,6 @@
-use crate::{Line, Mesh, Point, Vector, Xform};
+use crate::error::SessionError;
+use crate::{Line, Mesh, Point, Scalar, Vector, Xform};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 /// A cylinder geometry defined by a line and radius.
 ///
@@ -11,7 +11,7 @@ use uuid::Uuid;
 pub struct Cylinder {
     pub guid: String,
     pub name: String,
-    pub radius: f64,
+    pub radius: Scalar,
     pub line: Line,
     pub mesh: Mesh,
     #[serde(default = "Xform::identity")]
@@ -29,10 +29,10 @@ impl Cylinder {
     /// # Returns
     ///
     /// A new `Cylinder` with a generated 10-sided cylinder mesh
-    pub fn new(line: Line, radius: f64) -> Self {
+    pub fn new(line: Line, radius: Scalar) -> Self {
         let mesh = Self::create_cylinder_mesh(&line, radius);
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_cylinder".to_string(),
             radius,
             line,
@@ -41,7 +41,7 @@ impl Cylinder {
         }
     }
 
-    fn create_cylinder_mesh(line: &Line, radius: f64) -> Mesh {
+    fn create_cylinder_mesh(line: &Line, radius: Scalar) -> Mesh {
         let unit_cylinder = Self::unit_cylinder_geometry();
         let xform = Self::line_to_cylinder_transform(line, radius);
         Self::transform_geometry(&unit_cylinder, &xform)
@@ -97,7 +97,7 @@ impl Cylinder {
         (vertices, triangles)
     }
 
-    fn line_to_cylinder_transform(line: &Line, radius: f64) -> Xform {
+    fn line_to_cylinder_transform(line: &Line, radius: Scalar) -> Xform {
         let start = line.start();
         let end = line.end();
         let line_vec = line.to_vector();
@@ -167,7 +167,7 @@ impl Cylinder {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Serializes the Cylinder to a JSON string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let data = serde_json::json!({
             "type": "Cylinder",
             "guid": self.guid,
@@ -180,21 +180,21 @@ impl Cylinder {
     }
 
     /// Deserializes a Cylinder from a JSON string.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
     /// Serializes the Cylinder to a JSON file.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
     }
 
     /// Deserializes a Cylinder from a JSON file.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
     }
 }
 
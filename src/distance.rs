@@ -0,0 +1,252 @@
+use crate::intersection::{closest_point_on_triangle, line_line_closest_points, plane_plane};
+use crate::{BoundingBox, Line, Mesh, Plane, Point, Polyline, Scalar, Tolerance};
+
+/// Shortest distance between two geometries and the closest point on each.
+#[derive(Debug, Clone)]
+pub struct DistanceResult {
+    pub distance: Scalar,
+    /// Closest point on the first geometry
+    pub point_a: Point,
+    /// Closest point on the second geometry
+    pub point_b: Point,
+}
+
+impl DistanceResult {
+    fn new(point_a: Point, point_b: Point) -> Self {
+        DistanceResult {
+            distance: point_a.distance(&point_b),
+            point_a,
+            point_b,
+        }
+    }
+
+    /// Same result with the roles of the two geometries exchanged.
+    pub fn swapped(self) -> Self {
+        DistanceResult {
+            distance: self.distance,
+            point_a: self.point_b,
+            point_b: self.point_a,
+        }
+    }
+}
+
+/// Shortest distance query between `Self` and another geometry.
+///
+/// Lines and polylines are treated as finite, planes as infinite and meshes
+/// and boxes as surfaces bounding a solid, so points inside are at distance zero
+/// for boxes but measured to the nearest face for meshes.
+pub trait Distance<Rhs = Self> {
+    fn distance_to(&self, other: &Rhs) -> DistanceResult;
+}
+
+/// Shortest distance between two geometries.
+///
+/// # Arguments
+/// * `a` - First geometry
+/// * `b` - Second geometry
+///
+/// # Returns
+/// Distance with the closest point on `a` and on `b`.
+pub fn distance<A: Distance<B>, B>(a: &A, b: &B) -> DistanceResult {
+    a.distance_to(b)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Closest points
+///////////////////////////////////////////////////////////////////////////////////////////
+
+fn closest_point_on_line(point: &Point, line: &Line) -> Point {
+    let direction = line.to_vector();
+    let length_squared = direction.length_squared();
+    if length_squared < Tolerance::ZERO_TOLERANCE {
+        return line.start();
+    }
+    let t = (point.clone() - line.start()).dot(&direction) / length_squared;
+    line.point_at(t.clamp(0.0, 1.0))
+}
+
+fn closest_point_on_plane(point: &Point, plane: &Plane) -> Point {
+    let normal = plane.z_axis();
+    let offset = (point.clone() - plane.origin()).dot(&normal);
+    point.clone() - normal * offset
+}
+
+fn closest_point_on_box(point: &Point, bbox: &BoundingBox) -> Point {
+    let offset = point.clone() - bbox.center.clone();
+    let axes = [
+        bbox.x_axis.clone().normalize(),
+        bbox.y_axis.clone().normalize(),
+        bbox.z_axis.clone().normalize(),
+    ];
+    let half_size = [bbox.half_size.x(), bbox.half_size.y(), bbox.half_size.z()];
+    let mut closest = bbox.center.clone();
+    for (axis, half) in axes.into_iter().zip(half_size) {
+        let d = offset.dot(&axis).clamp(-half, half);
+        closest += axis * d;
+    }
+    closest
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Point
+///////////////////////////////////////////////////////////////////////////////////////////
+
+impl Distance for Point {
+    fn distance_to(&self, other: &Point) -> DistanceResult {
+        DistanceResult::new(self.clone(), other.clone())
+    }
+}
+
+impl Distance<Line> for Point {
+    fn distance_to(&self, other: &Line) -> DistanceResult {
+        DistanceResult::new(self.clone(), closest_point_on_line(self, other))
+    }
+}
+
+impl Distance<Plane> for Point {
+    fn distance_to(&self, other: &Plane) -> DistanceResult {
+        DistanceResult::new(self.clone(), closest_point_on_plane(self, other))
+    }
+}
+
+impl Distance<Polyline> for Point {
+    fn distance_to(&self, other: &Polyline) -> DistanceResult {
+        let closest = other
+            .points
+            .windows(2)
+            .map(|w| closest_point_on_line(self, &Line::from_points(&w[0], &w[1])))
+            .min_by(|a, b| self.distance(a).total_cmp(&self.distance(b)))
+            .or_else(|| other.points.first().cloned())
+            .unwrap_or_else(|| self.clone());
+        DistanceResult::new(self.clone(), closest)
+    }
+}
+
+impl Distance<Mesh> for Point {
+    fn distance_to(&self, other: &Mesh) -> DistanceResult {
+        let closest = other
+            .triangles()
+            .iter()
+            .map(|t| closest_point_on_triangle(self, &t[0], &t[1], &t[2]))
+            .min_by(|a, b| self.distance(a).total_cmp(&self.distance(b)))
+            .unwrap_or_else(|| self.clone());
+        DistanceResult::new(self.clone(), closest)
+    }
+}
+
+impl Distance<BoundingBox> for Point {
+    fn distance_to(&self, other: &BoundingBox) -> DistanceResult {
+        DistanceResult::new(self.clone(), closest_point_on_box(self, other))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Line
+///////////////////////////////////////////////////////////////////////////////////////////
+
+impl Distance<Point> for Line {
+    fn distance_to(&self, other: &Point) -> DistanceResult {
+        other.distance_to(self).swapped()
+    }
+}
+
+impl Distance for Line {
+    fn distance_to(&self, other: &Line) -> DistanceResult {
+        let (point_a, point_b, _) = line_line_closest_points(self, other, true, true);
+        DistanceResult::new(point_a, point_b)
+    }
+}
+
+impl Distance<Plane> for Line {
+    fn distance_to(&self, other: &Plane) -> DistanceResult {
+        let normal = other.z_axis();
+        let start = self.start();
+        let end = self.end();
+        let d0 = (start.clone() - other.origin()).dot(&normal);
+        let d1 = (end.clone() - other.origin()).dot(&normal);
+        if d0 * d1 <= 0.0 && d0 != d1 {
+            let crossing = self.point_at(d0 / (d0 - d1));
+            return DistanceResult::new(crossing.clone(), crossing);
+        }
+        let nearest = if d0.abs() <= d1.abs() { start } else { end };
+        let projected = closest_point_on_plane(&nearest, other);
+        DistanceResult::new(nearest, projected)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Plane
+///////////////////////////////////////////////////////////////////////////////////////////
+
+impl Distance<Point> for Plane {
+    fn distance_to(&self, other: &Point) -> DistanceResult {
+        other.distance_to(self).swapped()
+    }
+}
+
+impl Distance<Line> for Plane {
+    fn distance_to(&self, other: &Line) -> DistanceResult {
+        other.distance_to(self).swapped()
+    }
+}
+
+impl Distance for Plane {
+    fn distance_to(&self, other: &Plane) -> DistanceResult {
+        let parallel = self.z_axis().cross(&other.z_axis()).compute_length() <= Tolerance::ANGULAR;
+        if !parallel {
+            if let Some(line) = plane_plane(self, other) {
+                let point = line.start();
+                return DistanceResult::new(point.clone(), point);
+            }
+        }
+        let origin = self.origin();
+        let projected = closest_point_on_plane(&origin, other);
+        DistanceResult::new(origin, projected)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Polyline, Mesh and BoundingBox
+///////////////////////////////////////////////////////////////////////////////////////////
+
+impl Distance<Point> for Polyline {
+    fn distance_to(&self, other: &Point) -> DistanceResult {
+        other.distance_to(self).swapped()
+    }
+}
+
+impl Distance<Point> for Mesh {
+    fn distance_to(&self, other: &Point) -> DistanceResult {
+        other.distance_to(self).swapped()
+    }
+}
+
+impl Distance<Point> for BoundingBox {
+    fn distance_to(&self, other: &Point) -> DistanceResult {
+        other.distance_to(self).swapped()
+    }
+}
+
+impl Distance for BoundingBox {
+    /// Closest points between two oriented boxes by alternating projection,
+    /// which converges for convex solids. Overlapping boxes report zero.
+    fn distance_to(&self, other: &BoundingBox) -> DistanceResult {
+        let mut point_a = self.center.clone();
+        let mut point_b = closest_point_on_box(&point_a, other);
+        for _ in 0..64 {
+            let next_a = closest_point_on_box(&point_b, self);
+            let next_b = closest_point_on_box(&next_a, other);
+            let moved = next_a.distance(&point_a) + next_b.distance(&point_b);
+            point_a = next_a;
+            point_b = next_b;
+            if moved < Tolerance::ZERO_TOLERANCE {
+                break;
+            }
+        }
+        DistanceResult::new(point_a, point_b)
+    }
+}
+
+#[cfg(test)]
+#[path = "distance_test.rs"]
+mod distance_test;
@@ -0,0 +1,87 @@
+use crate::distance::{distance, Distance};
+use crate::Scalar;
+use crate::{BoundingBox, Line, Mesh, Plane, Point, Polyline, Vector};
+
+#[test]
+fn test_distance_point_line_and_plane() {
+    let p = Point::new(3.0, 2.0, 0.0);
+    let line = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    let result = distance(&p, &line);
+    assert!((result.distance - Scalar::sqrt(8.0)).abs() < 1e-9);
+    assert!(result.point_b.distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+
+    // Reversed arguments swap the closest points
+    let reversed = distance(&line, &p);
+    assert!(reversed.point_a.distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+
+    let plane = Plane::xy_plane();
+    let q = Point::new(1.0, 2.0, -4.0);
+    let result = q.distance_to(&plane);
+    assert!((result.distance - 4.0).abs() < 1e-9);
+    assert!(result.point_b.distance(&Point::new(1.0, 2.0, 0.0)) < 1e-9);
+}
+
+#[test]
+fn test_distance_point_polyline_and_mesh() {
+    let polyline = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(2.0, 0.0, 0.0),
+        Point::new(2.0, 2.0, 0.0),
+    ]);
+    let result = distance(&Point::new(3.0, 1.0, 0.0), &polyline);
+    assert!((result.distance - 1.0).abs() < 1e-9);
+
+    let mesh = Mesh::from_polygons(
+        vec![vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        ]],
+        None,
+    );
+    let result = distance(&Point::new(0.5, 0.5, 2.0), &mesh);
+    assert!((result.distance - 2.0).abs() < 1e-9);
+    assert!(result.point_b.distance(&Point::new(0.5, 0.5, 0.0)) < 1e-9);
+}
+
+#[test]
+fn test_distance_line_line_and_line_plane() {
+    let a = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    let b = Line::new(0.5, -1.0, 3.0, 0.5, 1.0, 3.0);
+    let result = distance(&a, &b);
+    assert!((result.distance - 3.0).abs() < 1e-9);
+
+    let plane = Plane::xy_plane();
+    let crossing = Line::new(0.0, 0.0, -1.0, 0.0, 0.0, 1.0);
+    assert!(distance(&crossing, &plane).distance < 1e-9);
+    let above = Line::new(0.0, 0.0, 2.0, 0.0, 0.0, 5.0);
+    let result = distance(&above, &plane);
+    assert!((result.distance - 2.0).abs() < 1e-9);
+    assert!(result.point_a.distance(&Point::new(0.0, 0.0, 2.0)) < 1e-9);
+}
+
+#[test]
+fn test_distance_plane_plane() {
+    let xy = Plane::xy_plane();
+    let shifted = Plane::from_point_normal(Point::new(0.0, 0.0, 5.0), Vector::new(0.0, 0.0, 1.0));
+    assert!((distance(&xy, &shifted).distance - 5.0).abs() < 1e-9);
+    assert!(distance(&xy, &Plane::yz_plane()).distance < 1e-9);
+}
+
+#[test]
+fn test_distance_box_box() {
+    let a = BoundingBox::from_points(&[Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0)], 0.0);
+    let b = BoundingBox::from_points(&[Point::new(3.0, 0.0, 0.0), Point::new(4.0, 1.0, 1.0)], 0.0);
+    let result = distance(&a, &b);
+    assert!((result.distance - 2.0).abs() < 1e-9);
+    assert!((result.point_a.x() - 1.0).abs() < 1e-9);
+    assert!((result.point_b.x() - 3.0).abs() < 1e-9);
+
+    let overlapping =
+        BoundingBox::from_points(&[Point::new(0.5, 0.5, 0.5), Point::new(2.0, 2.0, 2.0)], 0.0);
+    assert!(distance(&a, &overlapping).distance < 1e-9);
+
+    // Point inside a box is at distance zero
+    assert!(distance(&Point::new(0.5, 0.5, 0.5), &a).distance < 1e-9);
+}
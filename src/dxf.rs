@@ -0,0 +1,132 @@
+use crate::error::SessionError;
+use crate::{AttrValue, Line, Point, Polyline, Session, Xform};
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::io::{BufWriter, Write};
+
+/// Layer used for objects without a `layer` attribute.
+const DEFAULT_LAYER: &str = "0";
+
+/// Write the points, lines and polylines of a Session as an ASCII DXF (R12) drawing.
+///
+/// Coordinates are written in world space, with each object's xform applied.
+/// The layer of an object is its `layer` string attribute, or layer `0`.
+/// Polylines whose last point equals the first are written closed.
+///
+/// # Arguments
+/// * `session` - The Session to export
+/// * `filepath` - The path of the DXF file
+///
+/// # Returns
+/// A Result indicating success or failure of the file write operation.
+pub fn write_dxf(session: &Session, filepath: &str) -> Result<(), SessionError> {
+    let layer = |guid: &str| match session.get_attribute(guid, "layer") {
+        Some(AttrValue::String(name)) if !name.is_empty() => layer_name(name),
+        _ => DEFAULT_LAYER.to_string(),
+    };
+    let points = session.objects_of::<Point>();
+    let lines = session.objects_of::<Line>();
+    let polylines = session.objects_of::<Polyline>();
+    let guids = (points.iter().map(|p| &p.guid))
+        .chain(lines.iter().map(|l| &l.guid))
+        .chain(polylines.iter().map(|p| &p.guid));
+    let layers: BTreeSet<String> = std::iter::once(DEFAULT_LAYER.to_string())
+        .chain(guids.map(|guid| layer(guid)))
+        .collect();
+
+    let mut out = BufWriter::new(std::fs::File::create(filepath)?);
+    pair(&mut out, 0, "SECTION")?;
+    pair(&mut out, 2, "HEADER")?;
+    pair(&mut out, 9, "$ACADVER")?;
+    pair(&mut out, 1, "AC1009")?;
+    pair(&mut out, 0, "ENDSEC")?;
+
+    pair(&mut out, 0, "SECTION")?;
+    pair(&mut out, 2, "TABLES")?;
+    pair(&mut out, 0, "TABLE")?;
+    pair(&mut out, 2, "LAYER")?;
+    pair(&mut out, 70, layers.len())?;
+    for name in &layers {
+        pair(&mut out, 0, "LAYER")?;
+        pair(&mut out, 2, name)?;
+        pair(&mut out, 70, 0)?;
+        pair(&mut out, 62, 7)?;
+        pair(&mut out, 6, "CONTINUOUS")?;
+    }
+    pair(&mut out, 0, "ENDTAB")?;
+    pair(&mut out, 0, "ENDSEC")?;
+
+    pair(&mut out, 0, "SECTION")?;
+    pair(&mut out, 2, "ENTITIES")?;
+    for point in points {
+        pair(&mut out, 0, "POINT")?;
+        pair(&mut out, 8, layer(&point.guid))?;
+        coordinates(&mut out, 10, &point.xform.transformed_point(point))?;
+    }
+    for line in lines {
+        pair(&mut out, 0, "LINE")?;
+        pair(&mut out, 8, layer(&line.guid))?;
+        coordinates(&mut out, 10, &line.xform.transformed_point(&line.start()))?;
+        coordinates(&mut out, 11, &line.xform.transformed_point(&line.end()))?;
+    }
+    for polyline in polylines {
+        let points = world_points(&polyline.points, &polyline.xform);
+        let closed = points.len() > 2 && polyline.is_closed();
+        let points = if closed {
+            &points[..points.len() - 1]
+        } else {
+            &points[..]
+        };
+        let layer = layer(&polyline.guid);
+        pair(&mut out, 0, "POLYLINE")?;
+        pair(&mut out, 8, &layer)?;
+        pair(&mut out, 66, 1)?;
+        coordinates(&mut out, 10, &Point::new(0.0, 0.0, 0.0))?;
+        // 8 marks a 3D polyline, 1 a closed one
+        pair(&mut out, 70, if closed { 9 } else { 8 })?;
+        for point in points {
+            pair(&mut out, 0, "VERTEX")?;
+            pair(&mut out, 8, &layer)?;
+            coordinates(&mut out, 10, point)?;
+            pair(&mut out, 70, 32)?;
+        }
+        pair(&mut out, 0, "SEQEND")?;
+        pair(&mut out, 8, &layer)?;
+    }
+    pair(&mut out, 0, "ENDSEC")?;
+    pair(&mut out, 0, "EOF")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write a group code and its value on two lines.
+fn pair(out: &mut impl Write, code: u16, value: impl Display) -> Result<(), SessionError> {
+    writeln!(out, "{code:>3}\n{value}")?;
+    Ok(())
+}
+
+/// Write x, y and z with the group codes `code`, `code + 10` and `code + 20`.
+fn coordinates(out: &mut impl Write, code: u16, point: &Point) -> Result<(), SessionError> {
+    pair(out, code, point.x())?;
+    pair(out, code + 10, point.y())?;
+    pair(out, code + 20, point.z())
+}
+
+fn world_points(points: &[Point], xform: &Xform) -> Vec<Point> {
+    points.iter().map(|p| xform.transformed_point(p)).collect()
+}
+
+/// Replace the characters DXF does not allow in layer names.
+fn layer_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '<' | '>' | '/' | '\\' | '"' | ':' | ';' | '?' | '*' | '|' | '=' | '\'' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "dxf_test.rs"]
+mod dxf_test;
@@ -0,0 +1,69 @@
+use crate::dxf::write_dxf;
+use crate::{Line, Point, Polyline, Session, Xform};
+
+fn pairs(path: &str) -> Vec<(String, String)> {
+    let text = std::fs::read_to_string(path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    lines
+        .chunks(2)
+        .map(|pair| (pair[0].trim().to_string(), pair[1].to_string()))
+        .collect()
+}
+
+#[test]
+fn test_write_dxf_entities_and_layers() {
+    let mut session = Session::new("drawing");
+    let point = session.add_point(Point::new(1.0, 2.0, 0.0)).name();
+    let mut line = Line::new(0.0, 0.0, 0.0, 10.0, 0.0, 0.0);
+    line.xform = Xform::translation(0.0, 5.0, 0.0);
+    let line = session.add_line(line).name();
+    let square = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(1.0, 1.0, 0.0),
+        Point::new(0.0, 1.0, 0.0),
+        Point::new(0.0, 0.0, 0.0),
+    ]);
+    session.add_polyline(square);
+    session.set_attribute(&line, "layer", "cut".into());
+    session.set_attribute(&point, "layer", "drill:marks".into());
+
+    let path = std::env::temp_dir()
+        .join(format!("drawing_{}.dxf", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned();
+    write_dxf(&session, &path).unwrap();
+    let pairs = pairs(&path);
+    std::fs::remove_file(&path).ok();
+
+    let value = |code: &str, value: &str| (code.to_string(), value.to_string());
+    let count = |v: &(String, String)| pairs.iter().filter(|p| *p == v).count();
+    assert_eq!(pairs.last(), Some(&value("0", "EOF")));
+    assert_eq!(count(&value("0", "LAYER")), 3);
+    assert_eq!(count(&value("2", "drill_marks")), 1);
+    assert_eq!(count(&value("0", "POINT")), 1);
+    assert_eq!(count(&value("0", "LINE")), 1);
+    assert_eq!(count(&value("0", "POLYLINE")), 1);
+    assert_eq!(count(&value("0", "VERTEX")), 4);
+
+    // Lines are written in world coordinates on their layer
+    let start = pairs.iter().position(|p| *p == value("0", "LINE")).unwrap();
+    assert_eq!(
+        &pairs[start + 1..start + 8],
+        &[
+            value("8", "cut"),
+            value("10", "0"),
+            value("20", "5"),
+            value("30", "0"),
+            value("11", "10"),
+            value("21", "5"),
+            value("31", "0"),
+        ]
+    );
+    // The repeated end point closes the polyline
+    let start = pairs
+        .iter()
+        .position(|p| *p == value("0", "POLYLINE"))
+        .unwrap();
+    assert_eq!(pairs[start + 6], value("70", "9"));
+}
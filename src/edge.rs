@@ -1,6 +1,6 @@
+use crate::error::SessionError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use uuid::Uuid;
 
 impl fmt::Display for Edge {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -34,7 +34,7 @@ impl Default for Edge {
     fn default() -> Self {
         Self {
             name: "my_edge".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             v0: String::new(),
             v1: String::new(),
             attribute: String::new(),
@@ -65,12 +65,12 @@ impl Edge {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Convert the Edge to a JSON-serializable string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
 
     /// Create Edge from JSON string data.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
@@ -1,11 +1,9 @@
+use crate::error::SessionError;
 use serde::{Deserialize, Serialize};
 use std::fs;
 
 /// Serialize data to JSON string with pretty formatting.
-pub fn json_dumps<T: Serialize>(
-    data: &T,
-    pretty: bool,
-) -> Result<String, Box<dyn std::error::Error>> {
+pub fn json_dumps<T: Serialize>(data: &T, pretty: bool) -> Result<String, SessionError> {
     if pretty {
         Ok(serde_json::to_string_pretty(data)?)
     } else {
@@ -14,36 +12,26 @@ pub fn json_dumps<T: Serialize>(
 }
 
 /// Deserialize data from JSON string.
-pub fn json_loads<T: for<'de> Deserialize<'de>>(
-    json_str: &str,
-) -> Result<T, Box<dyn std::error::Error>> {
+pub fn json_loads<T: for<'de> Deserialize<'de>>(json_str: &str) -> Result<T, SessionError> {
     Ok(serde_json::from_str(json_str)?)
 }
 
 /// Write data to JSON file with pretty formatting.
-pub fn json_dump<T: Serialize>(
-    data: &T,
-    filepath: &str,
-    pretty: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+pub fn json_dump<T: Serialize>(data: &T, filepath: &str, pretty: bool) -> Result<(), SessionError> {
     let json_str = json_dumps(data, pretty)?;
     fs::write(filepath, json_str)?;
     Ok(())
 }
 
 /// Read data from JSON file.
-pub fn json_load<T: for<'de> Deserialize<'de>>(
-    filepath: &str,
-) -> Result<T, Box<dyn std::error::Error>> {
+pub fn json_load<T: for<'de> Deserialize<'de>>(filepath: &str) -> Result<T, SessionError> {
     let json_str = fs::read_to_string(filepath)?;
-    json_loads(&json_str)
+    json_loads(&json_str).map_err(|e| e.with_path(filepath))
 }
 
 /// Encode a value to JSON (wrapper for Serialize types).
 /// This function automatically calls the serde serialization.
-pub fn encode_value<T: Serialize>(
-    value: &T,
-) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+pub fn encode_value<T: Serialize>(value: &T) -> Result<serde_json::Value, SessionError> {
     Ok(serde_json::to_value(value)?)
 }
 
@@ -0,0 +1,94 @@
+use thiserror::Error;
+
+/// Errors returned when saving and loading geometry, graphs and sessions.
+#[derive(Debug, Error)]
+pub enum SessionError {
+    /// Reading or writing a file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Malformed JSON or JSON of the wrong shape, with the file it came from if any.
+    #[error(
+        "invalid JSON{} at line {line}: {message}",
+        .path.as_ref().map(|path| format!(" in {path}")).unwrap_or_default()
+    )]
+    Json {
+        path: Option<String>,
+        line: usize,
+        message: String,
+    },
+    /// Data written with an unsupported format version or layout.
+    #[error("schema mismatch: {0}")]
+    SchemaMismatch(String),
+    /// A required field is absent.
+    #[error("missing field `{0}`")]
+    MissingField(String),
+    /// Data that parses but does not describe valid geometry.
+    #[error("invalid geometry: {0}")]
+    InvalidGeometry(String),
+}
+
+impl SessionError {
+    /// Attach the file a JSON error came from.
+    ///
+    /// # Arguments
+    /// * `filepath` - The path of the file that was read
+    ///
+    /// # Returns
+    /// The error, with `path` set if it is a `Json` error.
+    pub fn with_path(self, filepath: &str) -> Self {
+        match self {
+            SessionError::Json { line, message, .. } => SessionError::Json {
+                path: Some(filepath.to_string()),
+                line,
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SessionError {
+    fn from(error: serde_json::Error) -> Self {
+        let message = error.to_string();
+        // serde reports "missing field `name`", keep the name for callers to match on
+        if let Some(field) = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next())
+        {
+            return SessionError::MissingField(field.to_string());
+        }
+        SessionError::Json {
+            path: None,
+            line: error.line(),
+            message,
+        }
+    }
+}
+
+impl From<std::string::FromUtf8Error> for SessionError {
+    fn from(error: std::string::FromUtf8Error) -> Self {
+        SessionError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl From<rmp_serde::encode::Error> for SessionError {
+    fn from(error: rmp_serde::encode::Error) -> Self {
+        SessionError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl From<zip::result::ZipError> for SessionError {
+    fn from(error: zip::result::ZipError) -> Self {
+        SessionError::Io(error.into())
+    }
+}
+
+impl From<rmp_serde::decode::Error> for SessionError {
+    fn from(error: rmp_serde::decode::Error) -> Self {
+        SessionError::SchemaMismatch(format!("invalid MessagePack: {error}"))
+    }
+}
+
+#[cfg(test)]
+#[path = "error_test.rs"]
+mod error_test;
@@ -0,0 +1,55 @@
+use crate::{Mesh, Point, Session, SessionError};
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("{}_{}.json", name, uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn test_json_errors_report_path_and_line() {
+    match Point::jsonload("{\n  \"x\": 1.0,\n  oops\n}").unwrap_err() {
+        SessionError::Json { path, line, .. } => {
+            assert_eq!(path, None);
+            assert_eq!(line, 3);
+        }
+        other => panic!("expected a JSON error, got {other:?}"),
+    }
+
+    let path = temp_path("error_json");
+    std::fs::write(&path, "{\n  \"type\": \"Session\",\n  \"objects\": [,]\n}").unwrap();
+    let error = Session::from_json(&path).unwrap_err();
+    assert!(matches!(
+        &error,
+        SessionError::Json { path: Some(p), line: 3, .. } if *p == path
+    ));
+    assert!(error.to_string().contains(&path));
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_error_variants() {
+    let missing = temp_path("error_missing");
+    assert!(matches!(
+        Session::from_json(&missing),
+        Err(SessionError::Io(ref e)) if e.kind() == std::io::ErrorKind::NotFound
+    ));
+
+    let error = Point::jsonload(r#"{"type": "Point", "x": 1.0}"#).unwrap_err();
+    assert!(matches!(error, SessionError::MissingField(ref field) if field == "guid"));
+
+    let newer = serde_json::json!({"type": "Session", "version": Session::SCHEMA_VERSION + 1});
+    assert!(matches!(
+        Session::jsonload(&newer.to_string()),
+        Err(SessionError::SchemaMismatch(_))
+    ));
+
+    let path = temp_path("error_mesh");
+    std::fs::write(&path, r#"{"type": "Mesh", "vertex": 5}"#).unwrap();
+    assert!(matches!(
+        Mesh::from_json(&path),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,345 @@
+//! Penetration of convex shapes by GJK and EPA.
+//!
+//! GJK (Gilbert-Johnson-Keerthi) searches the Minkowski difference `A - B` of
+//! two convex shapes for a tetrahedron enclosing the origin, which exists
+//! exactly when the shapes overlap. EPA (expanding polytope algorithm) then
+//! grows that tetrahedron until its face nearest to the origin lies on the
+//! boundary of the difference. The distance to that face is the penetration
+//! depth, the smallest translation that separates the shapes. Both algorithms
+//! only query support points, so any convex shape with a `Support` function
+//! works without building its geometry.
+
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{Scalar, Vec3};
+
+const MAX_ITERATIONS: usize = 128;
+
+/// Convex shape given by its support function.
+pub trait Support {
+    /// Point of the shape farthest along a direction, which need not be unit length.
+    fn support(&self, direction: Vec3) -> Vec3;
+}
+
+/// Deepest overlap of two convex shapes, see `penetration`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Penetration {
+    /// Point of the first shape deepest inside the second
+    pub point_a: Vec3,
+    /// Point of the second shape deepest inside the first
+    pub point_b: Vec3,
+    /// Unit direction from the first shape to the second
+    pub normal: Vec3,
+    /// Distance the second shape moves along the normal to separate the shapes
+    pub depth: Scalar,
+}
+
+/// Point of the Minkowski difference with the shape points it came from.
+#[derive(Debug, Clone, Copy)]
+struct Vertex {
+    w: Vec3,
+    a: Vec3,
+    b: Vec3,
+}
+
+fn support<A: Support + ?Sized, B: Support + ?Sized>(a: &A, b: &B, direction: Vec3) -> Vertex {
+    let (a, b) = (a.support(direction), b.support(-direction));
+    Vertex { w: a - b, a, b }
+}
+
+/// Penetration of two convex shapes, by GJK followed by EPA.
+///
+/// # Returns
+/// None if the shapes are apart, or if their Minkowski difference has no
+/// volume, as for two coplanar polygons.
+pub fn penetration<A: Support + ?Sized, B: Support + ?Sized>(a: &A, b: &B) -> Option<Penetration> {
+    let simplex = gjk(a, b)?;
+    let vertices = tetrahedron(a, b, simplex)?;
+    Some(epa(a, b, vertices))
+}
+
+/// Check whether two convex shapes overlap or touch.
+pub fn intersects<A: Support + ?Sized, B: Support + ?Sized>(a: &A, b: &B) -> bool {
+    gjk(a, b).is_some()
+}
+
+/// Simplex of the Minkowski difference containing the origin, newest point first.
+///
+/// Smaller simplices are returned when the origin lies on their boundary,
+/// that is when the shapes touch.
+fn gjk<A: Support + ?Sized, B: Support + ?Sized>(a: &A, b: &B) -> Option<Vec<Vertex>> {
+    let first = support(a, b, Vec3::new(1.0, 0.0, 0.0));
+    let scale = first.w.length().max(1.0);
+    let tiny = (scale * 64.0 * Scalar::EPSILON).powi(2);
+    let mut simplex = vec![first];
+    let mut direction = -first.w;
+    for _ in 0..MAX_ITERATIONS {
+        if direction.length_squared() <= tiny {
+            return Some(simplex);
+        }
+        let next = support(a, b, direction);
+        if next.w.dot(&direction) < 0.0 {
+            return None;
+        }
+        simplex.insert(0, next);
+        if enclose_origin(&mut simplex, &mut direction) {
+            return Some(simplex);
+        }
+    }
+    // Cycling between supports only happens when the origin is on the boundary
+    Some(simplex)
+}
+
+fn same_side(a: &Vec3, b: &Vec3) -> bool {
+    a.dot(b) > 0.0
+}
+
+/// Reduce the simplex to the feature nearest to the origin and point the search direction at it.
+///
+/// # Returns
+/// `true` if the tetrahedron contains the origin.
+fn enclose_origin(simplex: &mut Vec<Vertex>, direction: &mut Vec3) -> bool {
+    match simplex.len() {
+        2 => line_case(simplex, direction),
+        3 => triangle_case(simplex, direction),
+        _ => tetrahedron_case(simplex, direction),
+    }
+}
+
+fn line_case(simplex: &mut Vec<Vertex>, direction: &mut Vec3) -> bool {
+    let (a, b) = (simplex[0].w, simplex[1].w);
+    let (ab, ao) = (b - a, -a);
+    if same_side(&ab, &ao) {
+        *direction = ab.cross(&ao).cross(&ab);
+    } else {
+        simplex.truncate(1);
+        *direction = ao;
+    }
+    false
+}
+
+fn triangle_case(simplex: &mut Vec<Vertex>, direction: &mut Vec3) -> bool {
+    let (a, b, c) = (simplex[0].w, simplex[1].w, simplex[2].w);
+    let (ab, ac, ao) = (b - a, c - a, -a);
+    let abc = ab.cross(&ac);
+    if same_side(&abc.cross(&ac), &ao) {
+        if same_side(&ac, &ao) {
+            simplex.remove(1);
+            *direction = ac.cross(&ao).cross(&ac);
+            return false;
+        }
+        simplex.truncate(2);
+        return line_case(simplex, direction);
+    }
+    if same_side(&ab.cross(&abc), &ao) {
+        simplex.truncate(2);
+        return line_case(simplex, direction);
+    }
+    if same_side(&abc, &ao) {
+        *direction = abc;
+    } else {
+        simplex.swap(1, 2);
+        *direction = -abc;
+    }
+    false
+}
+
+fn tetrahedron_case(simplex: &mut Vec<Vertex>, direction: &mut Vec3) -> bool {
+    let (a, b, c, d) = (simplex[0].w, simplex[1].w, simplex[2].w, simplex[3].w);
+    let (ab, ac, ad, ao) = (b - a, c - a, d - a, -a);
+    // Faces abc, acd and adb, each given by the simplex indices of its last two points
+    for (normal, keep) in [
+        (ab.cross(&ac), [1, 2]),
+        (ac.cross(&ad), [2, 3]),
+        (ad.cross(&ab), [3, 1]),
+    ] {
+        if same_side(&normal, &ao) {
+            *simplex = vec![simplex[0], simplex[keep[0]], simplex[keep[1]]];
+            return triangle_case(simplex, direction);
+        }
+    }
+    true
+}
+
+/// Grow a GJK simplex containing the origin into a tetrahedron of the difference.
+///
+/// # Returns
+/// None if the difference is flat, so that no tetrahedron has volume.
+fn tetrahedron<A: Support + ?Sized, B: Support + ?Sized>(
+    a: &A,
+    b: &B,
+    mut simplex: Vec<Vertex>,
+) -> Option<Vec<Vertex>> {
+    let scale = simplex
+        .iter()
+        .fold(1.0, |scale: Scalar, v| scale.max(v.w.length()));
+    let tiny = scale * 64.0 * Scalar::EPSILON;
+    let axes = [
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    ];
+    // Distance of a new point from the affine hull of the simplex
+    let offset = |simplex: &[Vertex], w: Vec3| -> Scalar {
+        let o = simplex[0].w;
+        match simplex.len() {
+            1 => w.distance(&o),
+            2 => {
+                let line = simplex[1].w - o;
+                (w - o).cross(&line).length() / line.length().max(tiny)
+            }
+            _ => {
+                let normal = (simplex[1].w - o).cross(&(simplex[2].w - o));
+                (w - o).dot(&normal).abs() / normal.length().max(tiny)
+            }
+        }
+    };
+
+    while simplex.len() < 4 {
+        let o = simplex[0].w;
+        let directions: Vec<Vec3> = match simplex.len() {
+            1 => axes.iter().flat_map(|&axis| [axis, -axis]).collect(),
+            2 => {
+                let line = simplex[1].w - o;
+                let axis = axes
+                    .iter()
+                    .copied()
+                    .min_by(|p, q| line.dot(p).abs().total_cmp(&line.dot(q).abs()))?;
+                let (u, v) = (line.cross(&axis), line.cross(&line.cross(&axis)));
+                vec![u, -u, v, -v]
+            }
+            _ => {
+                let normal = (simplex[1].w - o).cross(&(simplex[2].w - o));
+                vec![normal, -normal]
+            }
+        };
+        let next = directions
+            .into_iter()
+            .map(|direction| support(a, b, direction))
+            .find(|vertex| offset(&simplex, vertex.w) > tiny)?;
+        simplex.push(next);
+    }
+    Some(simplex)
+}
+
+/// Triangle of the polytope with its outward unit normal and distance from the origin.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    vertices: [usize; 3],
+    normal: Vec3,
+    distance: Scalar,
+}
+
+fn face(points: &[Vertex], vertices: [usize; 3]) -> Face {
+    let [i, j, k] = vertices.map(|index| points[index].w);
+    match (j - i).cross(&(k - i)).normalized() {
+        Some(normal) => Face {
+            vertices,
+            normal,
+            distance: normal.dot(&i),
+        },
+        // Slivers are kept for the topology but never expanded
+        None => Face {
+            vertices,
+            normal: Vec3::default(),
+            distance: Scalar::MAX,
+        },
+    }
+}
+
+fn epa<A: Support + ?Sized, B: Support + ?Sized>(
+    a: &A,
+    b: &B,
+    mut points: Vec<Vertex>,
+) -> Penetration {
+    // Wind the faces of the tetrahedron outward
+    let center = points.iter().fold(Vec3::default(), |sum, v| sum + v.w) * 0.25;
+    let mut faces: Vec<Face> = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]
+        .into_iter()
+        .map(|[i, j, k]| {
+            let outward = (points[j].w - points[i].w)
+                .cross(&(points[k].w - points[i].w))
+                .dot(&(points[i].w - center))
+                >= 0.0;
+            face(&points, if outward { [i, j, k] } else { [i, k, j] })
+        })
+        .collect();
+    let scale = points
+        .iter()
+        .fold(1.0, |scale: Scalar, v| scale.max(v.w.length()));
+    let tolerance = scale * 64.0 * Scalar::EPSILON;
+
+    let mut nearest = nearest_face(&faces);
+    for _ in 0..MAX_ITERATIONS {
+        let next = support(a, b, nearest.normal);
+        if next.w.dot(&nearest.normal) - nearest.distance <= tolerance {
+            break;
+        }
+        // Remove the faces the new point sees, keeping the edges of the hole
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|face| {
+            let visible = face.distance < Scalar::MAX
+                && face.normal.dot(&(next.w - points[face.vertices[0]].w)) > 0.0;
+            if visible {
+                let [i, j, k] = face.vertices;
+                for (u, v) in [(i, j), (j, k), (k, i)] {
+                    match horizon.iter().position(|&edge| edge == (v, u)) {
+                        Some(shared) => {
+                            horizon.swap_remove(shared);
+                        }
+                        None => horizon.push((u, v)),
+                    }
+                }
+            }
+            !visible
+        });
+        if horizon.is_empty() {
+            break;
+        }
+        points.push(next);
+        let index = points.len() - 1;
+        faces.extend(
+            horizon
+                .into_iter()
+                .map(|(u, v)| face(&points, [u, v, index])),
+        );
+        nearest = nearest_face(&faces);
+    }
+
+    // Witness points from the barycentric coordinates of the nearest point on the face
+    let [p, q, r] = nearest.vertices.map(|index| points[index]);
+    let target = nearest.normal * nearest.distance;
+    let (e0, e1, e2) = (q.w - p.w, r.w - p.w, target - p.w);
+    let (d00, d01, d11) = (e0.dot(&e0), e0.dot(&e1), e1.dot(&e1));
+    let (d20, d21) = (e2.dot(&e0), e2.dot(&e1));
+    let denominator = d00 * d11 - d01 * d01;
+    let (v, w) = if denominator.abs() > Scalar::EPSILON * d00 * d11 {
+        (
+            (d11 * d20 - d01 * d21) / denominator,
+            (d00 * d21 - d01 * d20) / denominator,
+        )
+    } else {
+        (0.0, 0.0)
+    };
+    let u = 1.0 - v - w;
+    Penetration {
+        point_a: p.a * u + q.a * v + r.a * w,
+        point_b: p.b * u + q.b * v + r.b * w,
+        normal: nearest.normal,
+        depth: nearest.distance.max(0.0),
+    }
+}
+
+fn nearest_face(faces: &[Face]) -> Face {
+    faces.iter().copied().fold(faces[0], |nearest, face| {
+        if face.distance < nearest.distance {
+            face
+        } else {
+            nearest
+        }
+    })
+}
+
+#[cfg(test)]
+#[path = "gjk_test.rs"]
+mod gjk_test;
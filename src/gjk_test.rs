@@ -0,0 +1,98 @@
+use super::*;
+use crate::testing::Pcg32;
+
+struct Sphere(Vec3, Scalar);
+
+impl Support for Sphere {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        self.0 + direction.normalized().unwrap_or_default() * self.1
+    }
+}
+
+/// Axis-aligned box given by its center and half sizes.
+struct Cuboid(Vec3, Vec3);
+
+impl Support for Cuboid {
+    fn support(&self, d: Vec3) -> Vec3 {
+        let pick = |d: Scalar, h: Scalar| if d >= 0.0 { h } else { -h };
+        self.0
+            + Vec3::new(
+                pick(d.x, self.1.x),
+                pick(d.y, self.1.y),
+                pick(d.z, self.1.z),
+            )
+    }
+}
+
+fn close(a: Scalar, b: Scalar) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn test_penetration_of_spheres() {
+    let a = Sphere(Vec3::new(0.0, 0.0, 0.0), 1.0);
+    let b = Sphere(Vec3::new(1.5, 0.0, 0.0), 1.0);
+    let found = penetration(&a, &b).unwrap();
+    assert!(close(found.depth, 0.5));
+    assert!(close(found.normal.x, 1.0));
+    assert!(found.point_a.distance(&Vec3::new(1.0, 0.0, 0.0)) < 1e-2);
+    assert!(found.point_b.distance(&Vec3::new(0.5, 0.0, 0.0)) < 1e-2);
+    assert!(intersects(&a, &b));
+
+    let apart = Sphere(Vec3::new(0.0, 2.5, 0.0), 1.0);
+    assert!(penetration(&a, &apart).is_none());
+    assert!(!intersects(&a, &apart));
+}
+
+#[test]
+fn test_penetration_of_boxes() {
+    let a = Cuboid(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 1.0));
+    // Overlapping by 0.25 along z, more along x and y
+    let b = Cuboid(Vec3::new(0.5, 0.5, 1.75), Vec3::new(1.0, 1.0, 1.0));
+    let found = penetration(&a, &b).unwrap();
+    assert!(close(found.depth, 0.25));
+    assert!(close(found.normal.z, 1.0));
+    assert!(close(found.point_a.z, 1.0));
+    assert!(close(found.point_b.z, 0.75));
+    assert!(
+        found
+            .point_a
+            .distance(&(found.point_b + found.normal * found.depth))
+            < 1e-6
+    );
+
+    // A box inside another is pushed out through the nearest face
+    let inner = Cuboid(Vec3::new(0.0, -0.7, 0.0), Vec3::new(0.1, 0.1, 0.1));
+    let found = penetration(&a, &inner).unwrap();
+    assert!(close(found.depth, 0.4));
+    assert!(close(found.normal.y, -1.0));
+
+    // Coplanar squares have a flat difference
+    let square = Cuboid(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+    let shifted = Cuboid(Vec3::new(0.5, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+    assert!(intersects(&square, &shifted));
+    assert!(penetration(&square, &shifted).is_none());
+}
+
+#[test]
+fn test_penetration_matches_spheres_in_random_poses() {
+    let mut rng = Pcg32::new(7);
+    for _ in 0..200 {
+        let [x, y, z] = [0; 3].map(|_| rng.range(-2.0, 2.0));
+        let (ra, rb) = (rng.range(0.5, 1.5), rng.range(0.5, 1.5));
+        let a = Sphere(Vec3::new(0.0, 0.0, 0.0), ra);
+        let b = Sphere(Vec3::new(x, y, z), rb);
+        let gap = b.0.length() - ra - rb;
+        match penetration(&a, &b) {
+            Some(found) => {
+                assert!(gap <= 1e-6, "gap {gap}");
+                // Spheres are approximated by the polytope, which stays inside them
+                assert!(
+                    (found.depth + gap).abs() < 0.05 * (ra + rb),
+                    "{found:?} {gap}"
+                );
+            }
+            None => assert!(gap > -1e-6, "missed overlap {gap}"),
+        }
+    }
+}
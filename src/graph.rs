@@ -1,10 +1,10 @@
+use crate::error::SessionError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
-use uuid::Uuid;
 
 /// A graph vertex with a unique identifier and attribute string.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename = "Vertex")]
 pub struct Vertex {
     /// The unique identifier of the vertex.
@@ -21,7 +21,7 @@ impl Default for Vertex {
     fn default() -> Self {
         Self {
             name: "my_vertex".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             attribute: String::new(),
             index: -1,
         }
@@ -73,18 +73,21 @@ impl Vertex {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Convert the Vertex to a JSON-serializable string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
 
     /// Create Vertex from JSON string data.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 }
 
+/// A vertex and its edges by neighbor key, see `Graph::node_entry`.
+pub(crate) type NodeEntry = (Option<Vertex>, Option<HashMap<String, Edge>>);
+
 /// A graph edge with a unique identifier and attribute string.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename = "Edge")]
 pub struct Edge {
     /// The unique identifier of the edge.
@@ -105,7 +108,7 @@ impl Default for Edge {
     fn default() -> Self {
         Self {
             name: "my_edge".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             v0: String::new(),
             v1: String::new(),
             attribute: String::new(),
@@ -136,12 +139,12 @@ impl Edge {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Convert the Edge to a JSON-serializable string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
 
     /// Create Edge from JSON string data.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
@@ -183,7 +186,7 @@ pub struct Graph {
 impl Default for Graph {
     fn default() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_graph".to_string(),
             vertex_count: 0,
             edge_count: 0,
@@ -384,12 +387,174 @@ impl Graph {
         }
     }
 
+    /// Keys of all vertices and of all edge lists, a key may be listed twice.
+    pub(crate) fn node_keys(&self) -> impl Iterator<Item = &String> {
+        self.vertices.keys().chain(self.edges.keys())
+    }
+
+    /// Copy of a vertex and its edge list, for recording changes.
+    pub(crate) fn node_entry(&self, key: &str) -> NodeEntry {
+        (
+            self.vertices.get(key).cloned(),
+            self.edges.get(key).cloned(),
+        )
+    }
+
+    /// Replace a vertex and its edge list, returning the previous ones.
+    ///
+    /// The edge lists of the neighbors and the counters are not updated.
+    pub(crate) fn replace_node_entry(&mut self, key: &str, entry: NodeEntry) -> NodeEntry {
+        let vertex = match entry.0 {
+            Some(vertex) => self.vertices.insert(key.to_string(), vertex),
+            None => self.vertices.remove(key),
+        };
+        let edges = match entry.1 {
+            Some(edges) => self.edges.insert(key.to_string(), edges),
+            None => self.edges.remove(key),
+        };
+        (vertex, edges)
+    }
+
+    /// Set the GUID of a vertex, if there is one with the key.
+    pub(crate) fn set_node_guid(&mut self, key: &str, guid: &str) {
+        if let Some(vertex) = self.vertices.get_mut(key) {
+            vertex.guid = guid.to_string();
+        }
+    }
+
+    /// Set the GUID of an edge in the edge lists of both of its vertices.
+    pub(crate) fn set_edge_guid(&mut self, u: &str, v: &str, guid: &str) {
+        for (a, b) in [(u, v), (v, u)] {
+            if let Some(edge) = self.edges.get_mut(a).and_then(|edges| edges.get_mut(b)) {
+                edge.guid = guid.to_string();
+            }
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Algorithms
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Find a minimum spanning tree with Kruskal's algorithm.
+    ///
+    /// A disconnected graph gets one tree per connected component. Edges of
+    /// equal weight are taken in insertion order, so the result is repeatable.
+    ///
+    /// # Arguments
+    /// * `weight_fn` - Weight of an edge, e.g. the distance between the objects it connects
+    ///
+    /// # Returns
+    /// The tree edges as vertex name pairs, in increasing weight.
+    pub fn minimum_spanning_tree<F>(&self, weight_fn: F) -> Vec<(String, String)>
+    where
+        F: Fn(&Edge) -> f64,
+    {
+        let mut edges: Vec<(f64, &Edge)> = self
+            .unique_edges()
+            .into_iter()
+            .map(|edge| (weight_fn(edge), edge))
+            .collect();
+        edges.sort_by(|(wa, a), (wb, b)| {
+            wa.total_cmp(wb)
+                .then(a.index.cmp(&b.index))
+                .then_with(|| (&a.v0, &a.v1).cmp(&(&b.v0, &b.v1)))
+        });
+
+        let index: HashMap<&str, usize> = self
+            .vertices
+            .keys()
+            .enumerate()
+            .map(|(i, key)| (key.as_str(), i))
+            .collect();
+        let mut parent: Vec<usize> = (0..index.len()).collect();
+        let mut tree = Vec::new();
+        for (_, edge) in edges {
+            let (Some(&u), Some(&v)) = (index.get(edge.v0.as_str()), index.get(edge.v1.as_str()))
+            else {
+                continue;
+            };
+            let (ru, rv) = (find_root(&mut parent, u), find_root(&mut parent, v));
+            if ru != rv {
+                parent[ru] = rv;
+                tree.push((edge.v0.clone(), edge.v1.clone()));
+            }
+        }
+        tree
+    }
+
+    /// Group the vertices into clusters connected by the accepted edges.
+    ///
+    /// # Arguments
+    /// * `edge_filter` - Whether an edge joins its vertices, e.g. by its attribute
+    ///
+    /// # Returns
+    /// The vertex names of each cluster sorted by name, largest cluster first and
+    /// clusters of equal size by their first name. Vertices without accepted
+    /// edges form clusters of their own.
+    pub fn clusters<F>(&self, edge_filter: F) -> Vec<Vec<String>>
+    where
+        F: Fn(&Edge) -> bool,
+    {
+        let mut names: Vec<&String> = self.vertices.keys().collect();
+        names.sort();
+        let index: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+        let mut parent: Vec<usize> = (0..names.len()).collect();
+        for edge in self.unique_edges() {
+            if !edge_filter(edge) {
+                continue;
+            }
+            if let (Some(&u), Some(&v)) = (index.get(edge.v0.as_str()), index.get(edge.v1.as_str()))
+            {
+                let (ru, rv) = (find_root(&mut parent, u), find_root(&mut parent, v));
+                parent[ru.max(rv)] = ru.min(rv);
+            }
+        }
+
+        let mut clusters: HashMap<usize, Vec<String>> = HashMap::new();
+        for (i, name) in names.iter().enumerate() {
+            let root = find_root(&mut parent, i);
+            clusters.entry(root).or_default().push((*name).clone());
+        }
+        let mut clusters: Vec<Vec<String>> = clusters.into_values().collect();
+        clusters.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+        clusters
+    }
+
+    /// Label every vertex with the index of its cluster in `clusters`.
+    pub fn cluster_labels<F>(&self, edge_filter: F) -> HashMap<String, usize>
+    where
+        F: Fn(&Edge) -> bool,
+    {
+        self.clusters(edge_filter)
+            .into_iter()
+            .enumerate()
+            .flat_map(|(label, cluster)| cluster.into_iter().map(move |name| (name, label)))
+            .collect()
+    }
+
+    /// Every edge once, although it is stored for both of its vertices.
+    fn unique_edges(&self) -> Vec<&Edge> {
+        let mut edges = Vec::new();
+        for (u, neighbors) in &self.edges {
+            for (v, edge) in neighbors {
+                if u <= v {
+                    edges.push(edge);
+                }
+            }
+        }
+        edges
+    }
+
     ///////////////////////////////////////////////////////////////////////////////////////////
     // JSON
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Serializes the Graph to a JSON string.
-    pub fn jsondump(&self) -> Result<String, serde_json::Error> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         // Convert vertices to array, sorted by index to ensure consistent order
         let mut vertices: Vec<&Vertex> = self.vertices.values().collect();
         vertices.sort_by_key(|v| v.index);
@@ -417,11 +582,11 @@ impl Graph {
             "edge_count": self.edge_count
         });
 
-        serde_json::to_string_pretty(&json_obj)
+        Ok(serde_json::to_string_pretty(&json_obj)?)
     }
 
     /// Deserializes a Graph from a JSON string.
-    pub fn jsonload(json_data: &str) -> Result<Self, serde_json::Error> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         let json_obj: serde_json::Value = serde_json::from_str(json_data)?;
 
         let mut graph = Graph::new(json_obj["name"].as_str().unwrap_or("my_graph"));
@@ -461,16 +626,16 @@ impl Graph {
     }
 
     /// Serializes the Graph to a JSON file.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json_data = self.jsondump()?;
         std::fs::write(filepath, json_data)?;
         Ok(())
     }
 
     /// Deserializes a Graph from a JSON file.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json_data = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json_data).map_err(|e| e.into())
+        Self::jsonload(&json_data).map_err(|e| e.with_path(filepath))
     }
 
     /// Get or set edge attribute.
@@ -500,6 +665,15 @@ impl Graph {
     }
 }
 
+/// Root of a union-find set, halving the path on the way.
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
 #[cfg(test)]
 #[path = "graph_test.rs"]
 mod graph_test;
@@ -215,4 +215,55 @@ mod tests {
         );
         assert_eq!(graph.number_of_edges(), loaded_graph.number_of_edges());
     }
+
+    #[test]
+    fn test_graph_minimum_spanning_tree() {
+        let mut graph = Graph::new("my_graph");
+        graph.add_edge("A", "B", "1");
+        graph.add_edge("B", "C", "2");
+        graph.add_edge("A", "C", "3");
+        graph.add_edge("C", "D", "1");
+        graph.add_edge("E", "F", "5");
+        let weight = |edge: &crate::graph::Edge| edge.attribute.parse::<f64>().unwrap();
+
+        let tree = graph.minimum_spanning_tree(weight);
+        assert_eq!(
+            tree,
+            vec![
+                ("A".to_string(), "B".to_string()),
+                ("C".to_string(), "D".to_string()),
+                ("B".to_string(), "C".to_string()),
+                ("E".to_string(), "F".to_string()),
+            ]
+        );
+        assert!(Graph::new("empty").minimum_spanning_tree(weight).is_empty());
+    }
+
+    #[test]
+    fn test_graph_clusters() {
+        let mut graph = Graph::new("my_graph");
+        graph.add_edge("A", "B", "contact");
+        graph.add_edge("B", "C", "contact");
+        graph.add_edge("C", "D", "support");
+        graph.add_edge("E", "F", "contact");
+        graph.add_node("G", "");
+
+        let clusters = graph.clusters(|edge| edge.attribute == "contact");
+        assert_eq!(
+            clusters,
+            vec![
+                vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                vec!["E".to_string(), "F".to_string()],
+                vec!["D".to_string()],
+                vec!["G".to_string()],
+            ]
+        );
+        assert_eq!(graph.clusters(|_| true).len(), 3);
+
+        let labels = graph.cluster_labels(|edge| edge.attribute == "contact");
+        assert_eq!(labels.len(), 7);
+        assert_eq!(labels["A"], labels["C"]);
+        assert_eq!(labels["F"], 1);
+        assert_ne!(labels["D"], labels["C"]);
+    }
 }
@@ -0,0 +1,113 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use uuid::Uuid;
+
+/// How a Session generates GUIDs, see `Session::with_id_generator`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdGenerator {
+    /// Random version 4 UUIDs
+    #[default]
+    Random,
+    /// Counters in UUID format, starting at 00000000-0000-0000-0000-000000000001
+    Sequential,
+    /// FNV-1a hashes of the content an ID is created for in UUID format, e.g.
+    /// the serialized geometry of an object; repeated content is hashed with
+    /// the number of times it was seen before
+    Hashed,
+}
+
+/// GUIDs handed out by one Session with its generator.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IdSource {
+    generator: IdGenerator,
+    counter: u128,
+    /// Number of IDs created per content hash
+    seen: HashMap<u128, u128>,
+}
+
+#[cfg(feature = "std")]
+impl IdSource {
+    pub(crate) fn new(generator: IdGenerator) -> Self {
+        Self {
+            generator,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn generator(&self) -> IdGenerator {
+        self.generator
+    }
+
+    /// Create a GUID for the given content, which only the `Hashed` generator reads.
+    pub(crate) fn next(&mut self, content: &[u8]) -> String {
+        match self.generator {
+            IdGenerator::Random => new_guid(),
+            IdGenerator::Sequential => {
+                self.counter += 1;
+                hyphenated(self.counter)
+            }
+            IdGenerator::Hashed => {
+                let seen = self.seen.entry(fnv1a(content, 0)).or_insert(0);
+                let value = fnv1a(content, *seen);
+                *seen += 1;
+                hyphenated(value)
+            }
+        }
+    }
+}
+
+/// Create a random GUID.
+#[cfg(feature = "std")]
+pub fn new_guid() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// A GUID derived from another one, e.g. for the parts of an object.
+#[cfg(feature = "std")]
+pub(crate) fn derived_guid(guid: &str, index: u128) -> String {
+    hyphenated(fnv1a(guid.as_bytes(), index))
+}
+
+/// Create a sequential GUID from a global counter, as there is neither a
+/// random source nor thread local storage without `std`.
+#[cfg(not(feature = "std"))]
+pub fn new_guid() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    hyphenated(COUNTER.fetch_add(1, Ordering::Relaxed) as u128 + 1)
+}
+
+/// A 128-bit value in the hyphenated UUID format.
+fn hyphenated(value: u128) -> String {
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        value >> 96,
+        (value >> 80) & 0xffff,
+        (value >> 64) & 0xffff,
+        (value >> 48) & 0xffff,
+        value & 0xffff_ffff_ffff
+    )
+}
+
+/// 128-bit FNV-1a hash of bytes followed by a little endian counter.
+#[cfg(feature = "std")]
+fn fnv1a(bytes: &[u8], counter: u128) -> u128 {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    bytes
+        .iter()
+        .chain(counter.to_le_bytes().iter())
+        .fold(OFFSET, |hash, &byte| {
+            (hash ^ byte as u128).wrapping_mul(PRIME)
+        })
+}
+
+#[cfg(test)]
+#[path = "guid_test.rs"]
+mod guid_test;
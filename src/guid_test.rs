@@ -0,0 +1,25 @@
+use crate::guid::{derived_guid, new_guid, IdSource};
+use crate::IdGenerator;
+
+#[test]
+fn test_sequential_and_hashed_guids_repeat() {
+    let mut ids = IdSource::new(IdGenerator::Sequential);
+    assert_eq!(ids.next(b"a"), "00000000-0000-0000-0000-000000000001");
+    assert_eq!(ids.next(b"a"), "00000000-0000-0000-0000-000000000002");
+
+    let mut ids = IdSource::new(IdGenerator::Hashed);
+    let first = [ids.next(b"point"), ids.next(b"point"), ids.next(b"line")];
+    assert_ne!(first[0], first[1]);
+    assert_ne!(first[0], first[2]);
+    let mut again = IdSource::new(IdGenerator::Hashed);
+    assert_eq!(again.next(b"line"), first[2]);
+    assert_eq!([again.next(b"point"), again.next(b"point")], first[..2]);
+    assert!(uuid::Uuid::parse_str(&first[0]).is_ok());
+
+    assert_eq!(derived_guid(&first[0], 1), derived_guid(&first[0], 1));
+    assert_ne!(derived_guid(&first[0], 1), derived_guid(&first[0], 2));
+
+    let mut ids = IdSource::new(IdGenerator::Random);
+    assert_ne!(ids.next(b"a"), ids.next(b"a"));
+    assert_ne!(new_guid(), new_guid());
+}
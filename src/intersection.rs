@@ -1,12 +1,21 @@
-use crate::{Line, Point};
+use crate::predicates;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use crate::tolerance::PI;
+#[cfg(feature = "std")]
+use crate::Cylinder;
+use crate::{Line, Point, Scalar, Tolerance, Vector};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 pub fn line_line_parameters(
     line0: &Line,
     line1: &Line,
-    tolerance: f64,
+    tolerance: Scalar,
     intersect_segments: bool,
     near_parallel_as_closest: bool,
-) -> Option<(f64, f64)> {
+) -> Option<(Scalar, Scalar)> {
     let p0_start = line0.start();
     let p0_end = line0.end();
     let p1_start = line1.start();
@@ -37,7 +46,7 @@ pub fn line_line_parameters(
 
     let det = aa * bb - ab * ab;
 
-    let zero_tol = aa.max(bb) * f64::EPSILON;
+    let zero_tol = aa.max(bb) * Scalar::EPSILON;
     if det.abs() < zero_tol {
         if !near_parallel_as_closest {
             return None;
@@ -90,7 +99,7 @@ pub fn line_line_parameters(
 /// # Returns
 /// * `Some(Point)` - Intersection point (midpoint of closest approach for skew lines)
 /// * `None` - If lines don't intersect within tolerance
-pub fn line_line(line0: &Line, line1: &Line, tolerance: f64) -> Option<Point> {
+pub fn line_line(line0: &Line, line1: &Line, tolerance: Scalar) -> Option<Point> {
     let result = line_line_parameters(line0, line1, tolerance, true, false)?;
 
     let (t0, t1) = result;
@@ -104,6 +113,70 @@ pub fn line_line(line0: &Line, line1: &Line, tolerance: f64) -> Option<Point> {
     ))
 }
 
+/// Find the closest points between two lines and the distance between them.
+///
+/// # Arguments
+/// * `line0` - First line
+/// * `line1` - Second line
+/// * `bounded0` - If true, treat `line0` as a finite segment; if false, as infinite
+/// * `bounded1` - If true, treat `line1` as a finite segment; if false, as infinite
+///
+/// # Returns
+/// * `(Point, Point, Scalar)` - Closest point on `line0`, closest point on `line1`, and distance
+///
+/// # Note
+/// Parallel lines return the pair closest to the start of `line0`.
+pub fn line_line_closest_points(
+    line0: &Line,
+    line1: &Line,
+    bounded0: bool,
+    bounded1: bool,
+) -> (Point, Point, Scalar) {
+    let clamp0 = |s: Scalar| if bounded0 { s.clamp(0.0, 1.0) } else { s };
+    let clamp1 = |t: Scalar| if bounded1 { t.clamp(0.0, 1.0) } else { t };
+
+    let d0 = line0.to_vector();
+    let d1 = line1.to_vector();
+    let r = line0.start() - line1.start();
+
+    let a = d0.dot(&d0);
+    let e = d1.dot(&d1);
+    let f = d1.dot(&r);
+    let eps = Tolerance::ZERO_TOLERANCE;
+
+    let (s, t) = if a <= eps && e <= eps {
+        (0.0, 0.0)
+    } else if a <= eps {
+        (0.0, clamp1(f / e))
+    } else {
+        let c = d0.dot(&r);
+        if e <= eps {
+            (clamp0(-c / a), 0.0)
+        } else {
+            let b = d0.dot(&d1);
+            let denom = a * e - b * b;
+            let s = if denom > eps * a * e {
+                clamp0((b * f - c * e) / denom)
+            } else {
+                0.0
+            };
+            let t = (b * s + f) / e;
+            if bounded1 && t < 0.0 {
+                (clamp0(-c / a), 0.0)
+            } else if bounded1 && t > 1.0 {
+                (clamp0((b - c) / a), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+
+    let p0 = line0.point_at(s);
+    let p1 = line1.point_at(t);
+    let distance = p0.distance(&p1);
+    (p0, p1, distance)
+}
+
 /// Find intersection line between two planes.
 ///
 /// # Arguments
@@ -138,10 +211,6 @@ pub fn plane_plane(plane0: &crate::Plane, plane1: &crate::Plane) -> Option<Line>
     ))
 }
 
-fn plane_value_at(plane: &crate::Plane, point: &Point) -> f64 {
-    plane.a() * point.x() + plane.b() * point.y() + plane.c() * point.z() + plane.d()
-}
-
 /// Find intersection point between a line and a plane.
 ///
 /// # Arguments
@@ -156,8 +225,8 @@ pub fn line_plane(line: &Line, plane: &crate::Plane, is_finite: bool) -> Option<
     let pt0 = line.start();
     let pt1 = line.end();
 
-    let a = plane_value_at(plane, &pt0);
-    let b = plane_value_at(plane, &pt1);
+    let a = pt0.distance_to_plane(plane);
+    let b = pt1.distance_to_plane(plane);
     let d = a - b;
 
     let (t, rc) = if d == 0.0 {
@@ -172,7 +241,7 @@ pub fn line_plane(line: &Line, plane: &crate::Plane, is_finite: bool) -> Option<
     } else {
         let d_inv = 1.0 / d;
         let fd = d_inv.abs();
-        if fd > 1.0 && (a.abs() >= f64::MAX / fd || b.abs() >= f64::MAX / fd) {
+        if fd > 1.0 && (a.abs() >= Scalar::MAX / fd || b.abs() >= Scalar::MAX / fd) {
             (0.5, false)
         } else {
             (a / (a - b), true)
@@ -235,11 +304,14 @@ pub fn plane_plane_plane(
     Some(Point::new(p.x(), p.y(), p.z()))
 }
 
-/// Find intersection points between a line and an axis-aligned bounding box.
+/// Find intersection points between a line and a bounding box.
+///
+/// Axis-aligned boxes use the world slabs directly; oriented boxes are handled
+/// by running the slab test in the box's local frame.
 ///
 /// # Arguments
 /// * `line` - Line to intersect
-/// * `box_` - Axis-aligned bounding box
+/// * `box_` - Axis-aligned or oriented bounding box
 /// * `t0` - Minimum parameter value to consider (e.g., 0.0 for ray origin)
 /// * `t1` - Maximum parameter value to consider (e.g., 1000.0 for max distance)
 ///
@@ -249,50 +321,62 @@ pub fn plane_plane_plane(
 ///
 /// # Note
 /// Points are sorted from line start (entry first, exit second)
-pub fn ray_box(line: &Line, box_: &crate::BoundingBox, t0: f64, t1: f64) -> Option<Vec<Point>> {
+pub fn ray_box(
+    line: &Line,
+    box_: &crate::BoundingBox,
+    t0: Scalar,
+    t1: Scalar,
+) -> Option<Vec<Point>> {
     let origin = line.start();
     let direction = line.to_vector();
 
-    let box_min = box_.min_point();
-    let box_max = box_.max_point();
-
-    // Calculate inverse direction (avoid division by zero)
-    let inv_dir_x = if direction.x() != 0.0 {
-        1.0 / direction.x()
-    } else {
-        f64::INFINITY
-    };
-    let inv_dir_y = if direction.y() != 0.0 {
-        1.0 / direction.y()
-    } else {
-        f64::INFINITY
-    };
-    let inv_dir_z = if direction.z() != 0.0 {
-        1.0 / direction.z()
+    let (local_origin, local_direction) = if box_.is_axis_aligned() {
+        (
+            [
+                origin.x() - box_.center.x(),
+                origin.y() - box_.center.y(),
+                origin.z() - box_.center.z(),
+            ],
+            [direction.x(), direction.y(), direction.z()],
+        )
     } else {
-        f64::INFINITY
+        let offset = origin.clone() - box_.center.clone();
+        let axes = [
+            box_.x_axis.clone().normalize(),
+            box_.y_axis.clone().normalize(),
+            box_.z_axis.clone().normalize(),
+        ];
+        (
+            [
+                offset.dot(&axes[0]),
+                offset.dot(&axes[1]),
+                offset.dot(&axes[2]),
+            ],
+            [
+                direction.dot(&axes[0]),
+                direction.dot(&axes[1]),
+                direction.dot(&axes[2]),
+            ],
+        )
     };
-
-    // Calculate intersections with X slabs
-    let tx1 = (box_min.x() - origin.x()) * inv_dir_x;
-    let tx2 = (box_max.x() - origin.x()) * inv_dir_x;
-
-    let mut tmin = tx1.min(tx2);
-    let mut tmax = tx1.max(tx2);
-
-    // Calculate intersections with Y slabs
-    let ty1 = (box_min.y() - origin.y()) * inv_dir_y;
-    let ty2 = (box_max.y() - origin.y()) * inv_dir_y;
-
-    tmin = tmin.max(ty1.min(ty2));
-    tmax = tmax.min(ty1.max(ty2));
-
-    // Calculate intersections with Z slabs
-    let tz1 = (box_min.z() - origin.z()) * inv_dir_z;
-    let tz2 = (box_max.z() - origin.z()) * inv_dir_z;
-
-    tmin = tmin.max(tz1.min(tz2));
-    tmax = tmax.min(tz1.max(tz2));
+    let half_size = [box_.half_size.x(), box_.half_size.y(), box_.half_size.z()];
+
+    let mut tmin = Scalar::NEG_INFINITY;
+    let mut tmax = Scalar::INFINITY;
+    for i in 0..3 {
+        if local_direction[i] == 0.0 {
+            // Parallel to the slab: reject if the origin lies outside it
+            if local_origin[i].abs() > half_size[i] {
+                return None;
+            }
+            continue;
+        }
+        let inv_dir = 1.0 / local_direction[i];
+        let ta = (-half_size[i] - local_origin[i]) * inv_dir;
+        let tb = (half_size[i] - local_origin[i]) * inv_dir;
+        tmin = tmin.max(ta.min(tb));
+        tmax = tmax.min(ta.max(tb));
+    }
 
     // Clip to valid range
     tmin = tmin.max(t0);
@@ -332,7 +416,7 @@ pub fn ray_box(line: &Line, box_: &crate::BoundingBox, t0: f64, t1: f64) -> Opti
 ///
 /// # Note
 /// Points are sorted from line start
-pub fn ray_sphere(line: &Line, center: &Point, radius: f64) -> Option<Vec<Point>> {
+pub fn ray_sphere(line: &Line, center: &Point, radius: Scalar) -> Option<Vec<Point>> {
     let origin = line.start();
     let direction = line.to_vector();
 
@@ -368,7 +452,7 @@ pub fn ray_sphere(line: &Line, center: &Point, radius: f64) -> Option<Vec<Point>
 
     // Sort parameters
     if t0 > t1 {
-        std::mem::swap(&mut t0, &mut t1);
+        core::mem::swap(&mut t0, &mut t1);
     }
 
     // Calculate intersection points
@@ -395,6 +479,157 @@ pub fn ray_sphere(line: &Line, center: &Point, radius: f64) -> Option<Vec<Point>
     Some(points)
 }
 
+/// Find intersection points between a ray and a capped cylinder.
+///
+/// # Arguments
+/// * `line` - Ray, start point is the origin and the line vector the direction
+/// * `cylinder` - Cylinder with axis `cylinder.line` and radius `cylinder.radius`
+///
+/// # Returns
+/// * `Some(Vec<Point>)` - Hits on the side surface or end caps in front of the origin
+/// * `None` - If the ray misses the cylinder
+///
+/// # Note
+/// Points are sorted from ray origin
+#[cfg(feature = "std")]
+pub fn ray_cylinder(line: &Line, cylinder: &Cylinder) -> Option<Vec<Point>> {
+    let parameters = ray_capped_cylinder(line, &cylinder.line, cylinder.radius);
+    ray_hits_to_points(line, parameters)
+}
+
+/// Find intersection points between a ray and a solid cone.
+///
+/// # Arguments
+/// * `line` - Ray, start point is the origin and the line vector the direction
+/// * `base` - Center of the cone base
+/// * `apex` - Cone apex
+/// * `radius` - Radius of the cone base
+///
+/// # Returns
+/// * `Some(Vec<Point>)` - Hits on the lateral surface or the base disk in front of the origin
+/// * `None` - If the ray misses the cone
+///
+/// # Note
+/// Points are sorted from ray origin
+pub fn ray_cone(line: &Line, base: &Point, apex: &Point, radius: Scalar) -> Option<Vec<Point>> {
+    let parameters = ray_capped_cone(line, base, apex, radius);
+    ray_hits_to_points(line, parameters)
+}
+
+/// Ray parameters of hits on a cylinder side surface and its end caps.
+#[cfg(feature = "std")]
+pub(crate) fn ray_capped_cylinder(line: &Line, axis_line: &Line, radius: Scalar) -> Vec<Scalar> {
+    let mut parameters = Vec::new();
+    let height = axis_line.length();
+    if height < Tolerance::ZERO_TOLERANCE || radius <= 0.0 {
+        return parameters;
+    }
+    let axis = axis_line.to_vector().normalize();
+    let base = axis_line.start();
+    let origin = line.start();
+    let direction = line.to_vector();
+    let offset = origin.clone() - base.clone();
+
+    // Side surface: |(o + t·d - base)⊥|² = r²
+    let d_perp = direction.clone() - axis.clone() * direction.dot(&axis);
+    let o_perp = offset.clone() - axis.clone() * offset.dot(&axis);
+    let a = d_perp.length_squared();
+    let b = d_perp.dot(&o_perp);
+    let c = o_perp.length_squared() - radius * radius;
+    if a > Tolerance::ZERO_TOLERANCE {
+        let disc = b * b - a * c;
+        if disc >= 0.0 {
+            let root = disc.sqrt();
+            for t in [(-b - root) / a, (-b + root) / a] {
+                let s = offset.dot(&axis) + t * direction.dot(&axis);
+                if (0.0..=height).contains(&s) {
+                    parameters.push(t);
+                }
+            }
+        }
+    }
+
+    // End caps
+    let d_axis = direction.dot(&axis);
+    if d_axis.abs() > Tolerance::ZERO_TOLERANCE {
+        for s in [0.0, height] {
+            let t = (s - offset.dot(&axis)) / d_axis;
+            let radial = o_perp.clone() + d_perp.clone() * t;
+            if radial.length_squared() <= radius * radius {
+                parameters.push(t);
+            }
+        }
+    }
+    parameters
+}
+
+/// Ray parameters of hits on a cone lateral surface and its base disk.
+pub(crate) fn ray_capped_cone(
+    line: &Line,
+    base: &Point,
+    apex: &Point,
+    radius: Scalar,
+) -> Vec<Scalar> {
+    let mut parameters = Vec::new();
+    let axis_vector = base.clone() - apex.clone();
+    let height = axis_vector.compute_length();
+    if height < Tolerance::ZERO_TOLERANCE || radius <= 0.0 {
+        return parameters;
+    }
+    let axis = axis_vector.normalize();
+    let cos2 = height * height / (height * height + radius * radius);
+    let origin = line.start();
+    let direction = line.to_vector();
+    let co = origin.clone() - apex.clone();
+
+    // Lateral surface: ((P - apex)·v)² = cos²θ·|P - apex|²
+    let dv = direction.dot(&axis);
+    let cov = co.dot(&axis);
+    let a = dv * dv - cos2 * direction.length_squared();
+    let b = 2.0 * (dv * cov - cos2 * direction.dot(&co));
+    let c = cov * cov - cos2 * co.length_squared();
+    let roots: Vec<Scalar> = if a.abs() > Tolerance::ZERO_TOLERANCE {
+        let disc = b * b - 4.0 * a * c;
+        if disc < 0.0 {
+            Vec::new()
+        } else {
+            let root = disc.sqrt();
+            vec![(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+        }
+    } else if b.abs() > Tolerance::ZERO_TOLERANCE {
+        vec![-c / b]
+    } else {
+        Vec::new()
+    };
+    for t in roots {
+        let h = cov + t * dv;
+        if (0.0..=height).contains(&h) {
+            parameters.push(t);
+        }
+    }
+
+    // Base disk
+    if dv.abs() > Tolerance::ZERO_TOLERANCE {
+        let t = (height - cov) / dv;
+        let hit = line.point_at(t);
+        if hit.distance(base) <= radius {
+            parameters.push(t);
+        }
+    }
+    parameters
+}
+
+/// Convert ray parameters to sorted unique points in front of the ray origin.
+fn ray_hits_to_points(line: &Line, mut parameters: Vec<Scalar>) -> Option<Vec<Point>> {
+    parameters.retain(|t| *t >= 0.0);
+    if parameters.is_empty() {
+        return None;
+    }
+    parameters.sort_by(|a, b| a.total_cmp(b));
+    parameters.dedup_by(|a, b| (*a - *b).abs() < 1e-10);
+    Some(parameters.into_iter().map(|t| line.point_at(t)).collect())
+}
+
 /// Find intersection point between a line and a triangle.
 ///
 /// # Arguments
@@ -412,7 +647,7 @@ pub fn ray_triangle(
     v0: &Point,
     v1: &Point,
     v2: &Point,
-    epsilon: f64,
+    epsilon: Scalar,
 ) -> Option<Point> {
     let origin = line.start();
     let direction = line.to_vector();
@@ -475,42 +710,1558 @@ pub fn ray_triangle(
     ))
 }
 
+/// Find the intersection segment between two triangles.
+///
+/// Uses the Möller interval test: each triangle is tested against the plane of
+/// the other, and the intervals where both triangles cross the common line of
+/// their planes are overlapped to obtain the segment. Coplanar triangles are
+/// projected onto the dominant plane of their normal and their edges intersected;
+/// the overlap region is reported by its two points farthest apart.
+///
+/// # Arguments
+/// * `t1` - First triangle vertices
+/// * `t2` - Second triangle vertices
+///
+/// # Returns
+/// * `Some((Point, Point))` - Intersection segment endpoints (equal when touching at a point)
+/// * `None` - If triangles do not intersect or are degenerate
+pub fn triangle_triangle(t1: &[Point; 3], t2: &[Point; 3]) -> Option<(Point, Point)> {
+    let n1 = triangle_unit_normal(t1)?;
+    let n2 = triangle_unit_normal(t2)?;
+
+    let direction = n1.cross(&n2);
+    if direction.length_squared() < Tolerance::ZERO_TOLERANCE {
+        let coplanar = t2
+            .iter()
+            .all(|p| (p.clone() - t1[0].clone()).dot(&n1).abs() <= Tolerance::ABSOLUTE);
+        return if coplanar {
+            coplanar_triangle_overlap(t1, t2, &n1)
+        } else {
+            None
+        };
+    }
+
+    let d1 = triangle_plane_distances(t1, t2, &n2)?;
+    let d2 = triangle_plane_distances(t2, t1, &n1)?;
+
+    let (a0, a1) = triangle_plane_crossing(t1, &d1)?;
+    let (b0, b1) = triangle_plane_crossing(t2, &d2)?;
+
+    let param = |p: &Point| direction.x() * p.x() + direction.y() * p.y() + direction.z() * p.z();
+    let (a0, a1) = if param(&a0) <= param(&a1) {
+        (a0, a1)
+    } else {
+        (a1, a0)
+    };
+    let (b0, b1) = if param(&b0) <= param(&b1) {
+        (b0, b1)
+    } else {
+        (b1, b0)
+    };
+
+    let start = if param(&a0) >= param(&b0) { a0 } else { b0 };
+    let end = if param(&a1) <= param(&b1) { a1 } else { b1 };
+    if param(&start) > param(&end) + Tolerance::ABSOLUTE {
+        return None;
+    }
+
+    Some((start, end))
+}
+
+fn triangle_unit_normal(t: &[Point; 3]) -> Option<Vector> {
+    let normal = (t[1].clone() - t[0].clone()).cross(&(t[2].clone() - t[0].clone()));
+    if normal.length_squared() < Tolerance::ZERO_TOLERANCE * Tolerance::ZERO_TOLERANCE {
+        return None;
+    }
+    Some(normal.normalize())
+}
+
+/// Signed distances of triangle vertices to the plane of another triangle.
+/// The side of each vertex is decided by the exact `orient3d` predicate so that
+/// touching and coplanar configurations are classified consistently.
+/// Returns None when all vertices lie strictly on one side or the triangle is coplanar.
+fn triangle_plane_distances(
+    t: &[Point; 3],
+    other: &[Point; 3],
+    normal: &Vector,
+) -> Option<[Scalar; 3]> {
+    let mut d = [0.0; 3];
+    for (di, p) in d.iter_mut().zip(t.iter()) {
+        // orient3d is positive when p lies below the plane, opposite to the normal
+        let orientation = predicates::orient3d(&other[0], &other[1], &other[2], p);
+        if orientation == 0.0 {
+            continue;
+        }
+        let side = -orientation.signum() as Scalar;
+        let value = (p.clone() - other[0].clone()).dot(normal);
+        // Keep the rounded distance but never let it contradict the exact side
+        *di = if value * side > 0.0 {
+            value
+        } else {
+            side * Scalar::MIN_POSITIVE
+        };
+    }
+    let all_positive = d.iter().all(|&v| v > 0.0);
+    let all_negative = d.iter().all(|&v| v < 0.0);
+    let all_zero = d.iter().all(|&v| v == 0.0);
+    if all_positive || all_negative || all_zero {
+        return None;
+    }
+    Some(d)
+}
+
+/// Overlap of two coplanar triangles, as its two points farthest apart.
+/// Collects the vertices of each triangle inside the other and the crossings of
+/// their edges in the coordinate plane where the normal is largest.
+fn coplanar_triangle_overlap(
+    t1: &[Point; 3],
+    t2: &[Point; 3],
+    normal: &Vector,
+) -> Option<(Point, Point)> {
+    let (nx, ny, nz) = (normal.x().abs(), normal.y().abs(), normal.z().abs());
+    let to_2d = |p: &Point| -> [Scalar; 2] {
+        if nx >= ny && nx >= nz {
+            [p.y(), p.z()]
+        } else if ny >= nz {
+            [p.z(), p.x()]
+        } else {
+            [p.x(), p.y()]
+        }
+    };
+    let cross = |o: [Scalar; 2], a: [Scalar; 2], b: [Scalar; 2]| {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    };
+    let a: Vec<[Scalar; 2]> = t1.iter().map(to_2d).collect();
+    let b: Vec<[Scalar; 2]> = t2.iter().map(to_2d).collect();
+
+    // Inside when on the inner side of every edge, within tolerance of its line
+    let inside = |p: [Scalar; 2], t: &[[Scalar; 2]]| {
+        let area = cross(t[0], t[1], t[2]);
+        (0..3).all(|i| {
+            let (e0, e1) = (t[i], t[(i + 1) % 3]);
+            let length = ((e1[0] - e0[0]).powi(2) + (e1[1] - e0[1]).powi(2)).sqrt();
+            cross(e0, e1, p) * area.signum() >= -Tolerance::ABSOLUTE * length
+        })
+    };
+
+    let mut points: Vec<Point> = Vec::new();
+    for (i, p) in a.iter().enumerate() {
+        if inside(*p, &b) {
+            points.push(t1[i].clone());
+        }
+    }
+    for (i, p) in b.iter().enumerate() {
+        if inside(*p, &a) {
+            points.push(t2[i].clone());
+        }
+    }
+    for i in 0..3 {
+        let (a0, a1) = (a[i], a[(i + 1) % 3]);
+        let da = [a1[0] - a0[0], a1[1] - a0[1]];
+        for j in 0..3 {
+            let (b0, b1) = (b[j], b[(j + 1) % 3]);
+            let db = [b1[0] - b0[0], b1[1] - b0[1]];
+            let denominator = da[0] * db[1] - da[1] * db[0];
+            // Collinear edges overlap at vertices already collected above
+            if denominator.abs() < Tolerance::ZERO_TOLERANCE {
+                continue;
+            }
+            let offset = [b0[0] - a0[0], b0[1] - a0[1]];
+            let s = (offset[0] * db[1] - offset[1] * db[0]) / denominator;
+            let t = (offset[0] * da[1] - offset[1] * da[0]) / denominator;
+            if (0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&t) {
+                let (p0, p1) = (&t1[i], &t1[(i + 1) % 3]);
+                points.push(p0.clone() + (p1.clone() - p0.clone()) * s);
+            }
+        }
+    }
+
+    let mut best: Option<(usize, usize, Scalar)> = None;
+    for i in 0..points.len() {
+        for j in i..points.len() {
+            let distance = points[i].distance(&points[j]);
+            if best.is_none_or(|(_, _, d)| distance > d) {
+                best = Some((i, j, distance));
+            }
+        }
+    }
+    best.map(|(i, j, _)| (points[i].clone(), points[j].clone()))
+}
+
+/// Segment where a triangle crosses a plane, given signed vertex distances.
+fn triangle_plane_crossing(t: &[Point; 3], d: &[Scalar; 3]) -> Option<(Point, Point)> {
+    let mut points: Vec<Point> = Vec::with_capacity(3);
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        if d[i] == 0.0 {
+            points.push(t[i].clone());
+        }
+        if d[i] * d[j] < 0.0 {
+            let s = d[i] / (d[i] - d[j]);
+            points.push(t[i].clone() + (t[j].clone() - t[i].clone()) * s);
+        }
+    }
+    match points.len() {
+        0 => None,
+        1 => Some((points[0].clone(), points[0].clone())),
+        _ => Some((points[0].clone(), points[1].clone())),
+    }
+}
+
+/// Intersection between two polyline segments.
+#[derive(Debug, Clone)]
+pub struct PolylineIntersection {
+    /// Intersection point (midpoint of closest approach for near misses)
+    pub point: Point,
+    /// Segment index in the first polyline
+    pub segment_a: usize,
+    /// Segment index in the second polyline
+    pub segment_b: usize,
+    /// Normalized parameter on `segment_a`
+    pub t_a: Scalar,
+    /// Normalized parameter on `segment_b`
+    pub t_b: Scalar,
+}
+
+/// Find intersections between two polylines in 2D or 3D.
+///
+/// Segment bounding boxes inflated by the tolerance of both polylines are
+/// sorted along x and swept together, so each segment is only tested against
+/// the segments of the other polyline whose x range it overlaps.
+///
+/// # Arguments
+/// * `polyline_a` - First polyline
+/// * `polyline_b` - Second polyline
+/// * `tolerance` - Maximum distance between segments to consider them intersecting
+///
+/// # Returns
+/// Intersections sorted by segment index and parameter along `polyline_a`.
+/// Hits at shared vertices of consecutive segments are reported once.
+pub fn polyline_polyline(
+    polyline_a: &crate::Polyline,
+    polyline_b: &crate::Polyline,
+    tolerance: Scalar,
+) -> Vec<PolylineIntersection> {
+    let mut segments_a = polyline_segments(polyline_a, tolerance);
+    let mut segments_b = polyline_segments(polyline_b, tolerance);
+    segments_a.sort_by(|a, b| a.1[0].total_cmp(&b.1[0]));
+    segments_b.sort_by(|a, b| a.1[0].total_cmp(&b.1[0]));
+
+    let mut results: Vec<PolylineIntersection> = Vec::new();
+    let mut test = |(i, min_a, max_a, line_a): &SegmentBox,
+                    (j, min_b, max_b, line_b): &SegmentBox| {
+        if min_a[1] > max_b[1] || min_b[1] > max_a[1] || min_a[2] > max_b[2] || min_b[2] > max_a[2]
+        {
+            return;
+        }
+        let (p0, p1, distance) = line_line_closest_points(line_a, line_b, true, true);
+        if distance > tolerance {
+            return;
+        }
+        results.push(PolylineIntersection {
+            point: Point::new(
+                (p0.x() + p1.x()) * 0.5,
+                (p0.y() + p1.y()) * 0.5,
+                (p0.z() + p1.z()) * 0.5,
+            ),
+            segment_a: *i,
+            segment_b: *j,
+            t_a: segment_parameter(line_a, &p0),
+            t_b: segment_parameter(line_b, &p1),
+        });
+    };
+
+    // Segments become active at their min x and are dropped once the sweep passes their max x
+    let (mut next_a, mut next_b) = (0, 0);
+    let mut active_a: Vec<&SegmentBox> = Vec::new();
+    let mut active_b: Vec<&SegmentBox> = Vec::new();
+    while next_a < segments_a.len() || next_b < segments_b.len() {
+        let take_a = next_b == segments_b.len()
+            || (next_a < segments_a.len() && segments_a[next_a].1[0] <= segments_b[next_b].1[0]);
+        if take_a {
+            let segment = &segments_a[next_a];
+            next_a += 1;
+            active_b.retain(|other| other.2[0] >= segment.1[0]);
+            for other in &active_b {
+                test(segment, other);
+            }
+            active_a.push(segment);
+        } else {
+            let segment = &segments_b[next_b];
+            next_b += 1;
+            active_a.retain(|other| other.2[0] >= segment.1[0]);
+            for other in &active_a {
+                test(other, segment);
+            }
+            active_b.push(segment);
+        }
+    }
+
+    // Hits reported by neighbouring segments at a shared vertex sort next to each other
+    results.sort_by(|a, b| {
+        a.segment_a
+            .cmp(&b.segment_a)
+            .then(a.t_a.total_cmp(&b.t_a))
+            .then(a.segment_b.cmp(&b.segment_b))
+            .then(a.t_b.total_cmp(&b.t_b))
+    });
+    results.dedup_by(|hit, kept| hit.point.distance(&kept.point) <= tolerance);
+    results
+}
+
+/// A segment of a polyline with its index and inflated bounding box, see `polyline_segments`.
+type SegmentBox = (usize, [Scalar; 3], [Scalar; 3], Line);
+
+/// Segments of a polyline with their index and bounding box inflated by `inflate`.
+fn polyline_segments(polyline: &crate::Polyline, inflate: Scalar) -> Vec<SegmentBox> {
+    polyline
+        .points
+        .windows(2)
+        .enumerate()
+        .map(|(i, w)| {
+            let (p, q) = (&w[0], &w[1]);
+            let min = [
+                p.x().min(q.x()) - inflate,
+                p.y().min(q.y()) - inflate,
+                p.z().min(q.z()) - inflate,
+            ];
+            let max = [
+                p.x().max(q.x()) + inflate,
+                p.y().max(q.y()) + inflate,
+                p.z().max(q.z()) + inflate,
+            ];
+            (i, min, max, Line::from_points(p, q))
+        })
+        .collect()
+}
+
+/// Normalized parameter of a point lying on a segment.
+fn segment_parameter(line: &Line, point: &Point) -> Scalar {
+    let length_squared = line.squared_length();
+    if length_squared < Tolerance::ZERO_TOLERANCE {
+        return 0.0;
+    }
+    ((point.clone() - line.start()).dot(&line.to_vector()) / length_squared).clamp(0.0, 1.0)
+}
+
+//==========================================================================================
+// Plane Clipping Functions
+//==========================================================================================
+
+/// Side of a plane a bounding box lies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaneSide {
+    /// Entirely on the side the plane normal points to
+    Front,
+    /// Entirely on the opposite side of the plane normal
+    Back,
+    /// Crossing or touching the plane
+    Straddling,
+}
+
+/// Classify a bounding box against a plane.
+///
+/// The box axes are used, so oriented boxes are classified exactly as well.
+/// The BVH frustum queries behind the Session frustum and rectangle selection
+/// cull their nodes with the same test.
+///
+/// # Arguments
+/// * `plane` - Plane to classify against
+/// * `bbox` - Bounding box to classify
+pub fn plane_aabb_classify(plane: &crate::Plane, bbox: &crate::BoundingBox) -> PlaneSide {
+    let normal = plane.z_axis();
+    let distance = bbox.center.distance_to_plane(plane);
+    let extent = normal.dot(&bbox.x_axis.normalize()).abs() * bbox.half_size.x()
+        + normal.dot(&bbox.y_axis.normalize()).abs() * bbox.half_size.y()
+        + normal.dot(&bbox.z_axis.normalize()).abs() * bbox.half_size.z();
+    plane_side(distance, extent)
+}
+
+/// Classify an axis-aligned box given by its center and half size against a
+/// plane, for spatial indexes that store boxes without a `BoundingBox`.
+#[cfg(feature = "std")]
+pub(crate) fn plane_aabb_classify_extents(
+    plane: &crate::Plane,
+    center: [Scalar; 3],
+    half_size: [Scalar; 3],
+) -> PlaneSide {
+    let distance =
+        plane.a() * center[0] + plane.b() * center[1] + plane.c() * center[2] + plane.d();
+    let extent = half_size[0] * plane.a().abs()
+        + half_size[1] * plane.b().abs()
+        + half_size[2] * plane.c().abs();
+    plane_side(distance, extent)
+}
+
+/// Side of a plane for a box whose center lies at `distance` from it and
+/// whose projection onto the plane normal reaches `extent` either way.
+fn plane_side(distance: Scalar, extent: Scalar) -> PlaneSide {
+    if distance > extent {
+        PlaneSide::Front
+    } else if distance < -extent {
+        PlaneSide::Back
+    } else {
+        PlaneSide::Straddling
+    }
+}
+
+/// Find the cross-section polygon of a plane and a bounding box.
+///
+/// # Arguments
+/// * `plane` - Cutting plane
+/// * `bbox` - Bounding box to cut
+///
+/// # Returns
+/// * `Some(Polyline)` - Closed polygon ordered counter-clockwise around the plane normal
+/// * `None` - If the plane misses the box or only touches an edge or corner
+pub fn plane_box(plane: &crate::Plane, bbox: &crate::BoundingBox) -> Option<crate::Polyline> {
+    if plane_aabb_classify(plane, bbox) != PlaneSide::Straddling {
+        return None;
+    }
+
+    let corners = bbox.corners();
+    let d: Vec<Scalar> = corners.iter().map(|c| c.distance_to_plane(plane)).collect();
+    const EDGES: [(usize, usize); 12] = [
+        (0, 1),
+        (1, 2),
+        (2, 3),
+        (3, 0),
+        (4, 5),
+        (5, 6),
+        (6, 7),
+        (7, 4),
+        (0, 4),
+        (1, 5),
+        (2, 6),
+        (3, 7),
+    ];
+
+    let mut points: Vec<Point> = Vec::new();
+    let mut push_unique = |p: Point| {
+        if !points.iter().any(|q| q.distance(&p) < Tolerance::ABSOLUTE) {
+            points.push(p);
+        }
+    };
+    for (i, j) in EDGES {
+        let (di, dj) = (d[i], d[j]);
+        if di.abs() < Tolerance::ABSOLUTE {
+            push_unique(corners[i].clone());
+        }
+        if dj.abs() < Tolerance::ABSOLUTE {
+            push_unique(corners[j].clone());
+        }
+        if (di < -Tolerance::ABSOLUTE && dj > Tolerance::ABSOLUTE)
+            || (di > Tolerance::ABSOLUTE && dj < -Tolerance::ABSOLUTE)
+        {
+            let s = di / (di - dj);
+            push_unique(corners[i].clone() + (corners[j].clone() - corners[i].clone()) * s);
+        }
+    }
+    if points.len() < 3 {
+        return None;
+    }
+
+    // Order the convex section by angle around its centroid in plane coordinates
+    let n = points.len() as Scalar;
+    let centroid = Point::new(
+        points.iter().map(|p| p.x()).sum::<Scalar>() / n,
+        points.iter().map(|p| p.y()).sum::<Scalar>() / n,
+        points.iter().map(|p| p.z()).sum::<Scalar>() / n,
+    );
+    let (x_axis, y_axis) = (plane.x_axis(), plane.y_axis());
+    let angle = |p: &Point| {
+        let v = p.clone() - centroid.clone();
+        v.dot(&y_axis).atan2(v.dot(&x_axis))
+    };
+    points.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+    points.push(points[0].clone());
+
+    Some(crate::Polyline::new(points))
+}
+
+/// Find the section curves of a plane and a mesh.
+///
+/// Each triangle crossing the plane contributes one segment; segments sharing
+/// endpoints are chained into polylines, closed where the section loops.
+///
+/// # Arguments
+/// * `plane` - Cutting plane
+/// * `mesh` - Mesh to cut, faces are fan-triangulated
+/// * `tolerance` - Distance under which segment endpoints are joined
+#[cfg(feature = "std")]
+pub fn plane_mesh(
+    plane: &crate::Plane,
+    mesh: &crate::Mesh,
+    tolerance: Scalar,
+) -> Vec<crate::Polyline> {
+    let mut segments: Vec<(Point, Point)> = Vec::new();
+    for triangle in mesh.triangles() {
+        let mut d = [0.0; 3];
+        for (di, p) in d.iter_mut().zip(triangle.iter()) {
+            let value = p.distance_to_plane(plane);
+            *di = if value.abs() < Tolerance::ABSOLUTE {
+                0.0
+            } else {
+                value
+            };
+        }
+        if d.iter().all(|&v| v > 0.0) || d.iter().all(|&v| v < 0.0) || d.iter().all(|&v| v == 0.0) {
+            continue;
+        }
+        if let Some((a, b)) = triangle_plane_crossing(&triangle, &d) {
+            if a.distance(&b) > tolerance {
+                segments.push((a, b));
+            }
+        }
+    }
+
+    // Index segment endpoints on a grid of tolerance-sized cells, so each link
+    // only looks at the endpoints in the neighbouring cells
+    let cell = tolerance.max(Tolerance::ABSOLUTE);
+    let key = |p: &Point| {
+        [
+            (p.x() / cell).floor() as i64,
+            (p.y() / cell).floor() as i64,
+            (p.z() / cell).floor() as i64,
+        ]
+    };
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    for (k, (a, b)) in segments.iter().enumerate() {
+        grid.entry(key(a)).or_default().push(k);
+        grid.entry(key(b)).or_default().push(k);
+    }
+    let find_next = |tip: &Point, used: &[bool]| -> Option<(usize, Point)> {
+        let [x, y, z] = key(tip);
+        let mut best: Option<usize> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(candidates) = grid.get(&[x + dx, y + dy, z + dz]) else {
+                        continue;
+                    };
+                    for &k in candidates {
+                        if !used[k]
+                            && best.is_none_or(|b| k < b)
+                            && (segments[k].0.distance(tip) <= tolerance
+                                || segments[k].1.distance(tip) <= tolerance)
+                        {
+                            best = Some(k);
+                        }
+                    }
+                }
+            }
+        }
+        let k = best?;
+        let other = if segments[k].0.distance(tip) <= tolerance {
+            segments[k].1.clone()
+        } else {
+            segments[k].0.clone()
+        };
+        Some((k, other))
+    };
+
+    let mut used = vec![false; segments.len()];
+    let mut polylines = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+
+        // Grow the chain at its end, then grow its head backwards from the start
+        let mut chain = vec![segments[start].0.clone(), segments[start].1.clone()];
+        while let Some((k, other)) = find_next(chain.last().unwrap(), &used) {
+            used[k] = true;
+            chain.push(other);
+        }
+        let mut head = vec![segments[start].0.clone()];
+        while let Some((k, other)) = find_next(head.last().unwrap(), &used) {
+            used[k] = true;
+            head.push(other);
+        }
+        if head.len() > 1 {
+            head.reverse();
+            head.pop();
+            head.append(&mut chain);
+            chain = head;
+        }
+
+        // Snap nearly closed loops exactly closed
+        let last = chain.len() - 1;
+        if last > 1 && chain[0].distance(&chain[last]) <= tolerance {
+            chain[last] = chain[0].clone();
+        }
+        polylines.push(crate::Polyline::new(chain));
+    }
+    polylines
+}
+
+//==========================================================================================
+// Point Containment Functions
+//==========================================================================================
+
+/// Classification of a point against a closed region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Outside,
+    /// Within tolerance of the region boundary
+    Boundary,
+}
+
+/// Classify a point against a closed triangle mesh.
+///
+/// Uses the generalized winding number (sum of signed solid angles of all
+/// triangles), which tolerates small gaps. Faces must be consistently oriented,
+/// either all outward or all inward; the sign of the winding number is ignored,
+/// but mixed orientations cancel out and can classify inside points as outside.
+///
+/// # Arguments
+/// * `point` - Point to classify
+/// * `mesh` - Closed mesh, faces are fan-triangulated
+/// * `tolerance` - Distance to the surface classified as boundary
+#[cfg(feature = "std")]
+pub fn point_in_mesh(point: &Point, mesh: &crate::Mesh, tolerance: Scalar) -> Containment {
+    point_in_triangles(point, &mesh.triangles(), tolerance)
+}
+
+/// `point_in_mesh` for triangles extracted once, to classify many points.
+#[cfg(feature = "std")]
+pub(crate) fn point_in_triangles(
+    point: &Point,
+    triangles: &[[Point; 3]],
+    tolerance: Scalar,
+) -> Containment {
+    if triangles.is_empty() {
+        return Containment::Outside;
+    }
+
+    let mut solid_angle = 0.0;
+    for [v0, v1, v2] in triangles {
+        if closest_point_on_triangle(point, v0, v1, v2).distance(point) <= tolerance {
+            return Containment::Boundary;
+        }
+        let a = v0.clone() - point.clone();
+        let b = v1.clone() - point.clone();
+        let c = v2.clone() - point.clone();
+        let (la, lb, lc) = (a.compute_length(), b.compute_length(), c.compute_length());
+        let numerator = a.dot(&b.cross(&c));
+        let denominator = la * lb * lc + a.dot(&b) * lc + b.dot(&c) * la + c.dot(&a) * lb;
+        solid_angle += 2.0 * numerator.atan2(denominator);
+    }
+
+    let winding = solid_angle / (4.0 * PI);
+    if winding.abs() > 0.5 {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+/// Classify a point against a closed polygon lying in a plane.
+///
+/// The point and the polygon are projected onto the plane and tested with the
+/// winding number in plane coordinates.
+///
+/// # Arguments
+/// * `point` - Point to classify
+/// * `polygon` - Polygon vertices, closing segment is implied if not closed
+/// * `plane` - Plane of the polygon
+/// * `tolerance` - Distance to the polygon edges classified as boundary
+pub fn point_in_polygon(
+    point: &Point,
+    polygon: &crate::Polyline,
+    plane: &crate::Plane,
+    tolerance: Scalar,
+) -> Containment {
+    let origin = plane.origin();
+    let (x_axis, y_axis) = (plane.x_axis(), plane.y_axis());
+    let to_2d = |p: &Point| {
+        let v = p.clone() - origin.clone();
+        (v.dot(&x_axis), v.dot(&y_axis))
+    };
+
+    let mut vertices: Vec<(Scalar, Scalar)> = polygon.points.iter().map(to_2d).collect();
+    if polygon.is_closed() {
+        vertices.pop();
+    }
+    if vertices.len() < 3 {
+        return Containment::Outside;
+    }
+
+    let (px, py) = to_2d(point);
+    let mut winding = 0i32;
+    for i in 0..vertices.len() {
+        let (x0, y0) = vertices[i];
+        let (x1, y1) = vertices[(i + 1) % vertices.len()];
+
+        // Distance to the edge for boundary classification
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared > 0.0 {
+            (((px - x0) * dx + (py - y0) * dy) / length_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (ex, ey) = (x0 + t * dx - px, y0 + t * dy - py);
+        if (ex * ex + ey * ey).sqrt() <= tolerance {
+            return Containment::Boundary;
+        }
+
+        let side = dx * (py - y0) - dy * (px - x0);
+        if y0 <= py {
+            if y1 > py && side > 0.0 {
+                winding += 1;
+            }
+        } else if y1 <= py && side < 0.0 {
+            winding -= 1;
+        }
+    }
+
+    if winding != 0 {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+/// Classify a point against an oriented bounding box.
+///
+/// # Arguments
+/// * `point` - Point to classify
+/// * `bbox` - Bounding box, its axes are used so oriented boxes are supported
+/// * `tolerance` - Distance to the box faces classified as boundary
+pub fn point_in_bbox(point: &Point, bbox: &crate::BoundingBox, tolerance: Scalar) -> Containment {
+    let offset = point.clone() - bbox.center.clone();
+    let axes = [&bbox.x_axis, &bbox.y_axis, &bbox.z_axis];
+    let half = [bbox.half_size.x(), bbox.half_size.y(), bbox.half_size.z()];
+
+    let mut on_boundary = false;
+    for (axis, h) in axes.iter().zip(half.iter()) {
+        let d = offset.dot(&axis.normalize()).abs();
+        if d > h + tolerance {
+            return Containment::Outside;
+        }
+        if d >= h - tolerance {
+            on_boundary = true;
+        }
+    }
+
+    if on_boundary {
+        Containment::Boundary
+    } else {
+        Containment::Inside
+    }
+}
+
+/// Closest point on a triangle to a query point (Ericson, Real-Time Collision Detection).
+#[cfg(feature = "std")]
+pub(crate) fn closest_point_on_triangle(p: &Point, a: &Point, b: &Point, c: &Point) -> Point {
+    let ab = b.clone() - a.clone();
+    let ac = c.clone() - a.clone();
+    let ap = p.clone() - a.clone();
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a.clone();
+    }
+
+    let bp = p.clone() - b.clone();
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b.clone();
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a.clone() + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p.clone() - c.clone();
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c.clone();
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a.clone() + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let bc = c.clone() - b.clone();
+        return b.clone() + bc * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    a.clone() + ab * v + ac * w
+}
+
+//==========================================================================================
+// Sphere and Cylinder Intersection Functions
+//==========================================================================================
+
+/// Circle in 3D space, centered at the plane origin and lying in the plane.
+#[derive(Debug, Clone)]
+pub struct Circle {
+    pub plane: crate::Plane,
+    pub radius: Scalar,
+}
+
+/// Ellipse in 3D space, centered at the plane origin with the major axis along
+/// the plane x-axis and the minor axis along the plane y-axis.
+#[derive(Debug, Clone)]
+pub struct Ellipse {
+    pub plane: crate::Plane,
+    pub major_radius: Scalar,
+    pub minor_radius: Scalar,
+}
+
+/// Result of a sphere-sphere intersection.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum SphereSphereIntersection {
+    /// Spheres touch at a single point
+    Point(Point),
+    /// Spheres intersect in a circle
+    Circle(Circle),
+    /// Spheres are identical
+    Coincident,
+}
+
+/// Result of a plane-sphere intersection.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum PlaneSphereIntersection {
+    /// Plane is tangent to the sphere
+    Point(Point),
+    /// Plane cuts the sphere in a circle
+    Circle(Circle),
+}
+
+/// Result of a line-cylinder intersection.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum LineCylinderIntersection {
+    /// Line is tangent to the cylinder
+    Point(Point),
+    /// Line enters and exits the cylinder, sorted from line start
+    Points(Point, Point),
+    /// Line lies on the cylinder surface
+    Line(Line),
+}
+
+/// Result of a plane-cylinder intersection.
+#[derive(Debug, Clone)]
+pub enum PlaneCylinderIntersection {
+    /// Plane is parallel to the axis and tangent to the cylinder
+    Line(Line),
+    /// Plane is parallel to the axis and cuts the cylinder in two rulings
+    Lines(Line, Line),
+    /// Plane is perpendicular to the axis
+    Circle(Circle),
+    /// Plane is oblique to the axis
+    Ellipse(Ellipse),
+}
+
+/// Result of a sphere-cylinder intersection.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum SphereCylinderIntersection {
+    /// Sphere touches the cylinder at a single point
+    Point(Point),
+    /// Coaxial sphere tangent to the cylinder
+    Circle(Circle),
+    /// Coaxial sphere cutting the cylinder in two circles
+    Circles(Circle, Circle),
+    /// General case: closed intersection loops sampled as polylines
+    Curves(Vec<crate::Polyline>),
+}
+
+/// Number of samples per branch used to approximate sphere-cylinder curves.
+#[cfg(feature = "std")]
+const SPHERE_CYLINDER_SAMPLES: usize = 64;
+
+/// Find intersection between two spheres.
+///
+/// # Arguments
+/// * `center0` - First sphere center
+/// * `radius0` - First sphere radius
+/// * `center1` - Second sphere center
+/// * `radius1` - Second sphere radius
+///
+/// # Returns
+/// * `Some(SphereSphereIntersection)` - Tangent point, circle, or coincident spheres
+/// * `None` - If spheres are separate or one contains the other
+pub fn sphere_sphere(
+    center0: &Point,
+    radius0: Scalar,
+    center1: &Point,
+    radius1: Scalar,
+) -> Option<SphereSphereIntersection> {
+    let tol = Tolerance::ABSOLUTE;
+    let axis = center1.clone() - center0.clone();
+    let d = axis.compute_length();
+
+    if d < tol {
+        if (radius0 - radius1).abs() < tol {
+            return Some(SphereSphereIntersection::Coincident);
+        }
+        return None;
+    }
+    if d > radius0 + radius1 + tol || d < (radius0 - radius1).abs() - tol {
+        return None;
+    }
+
+    let n = axis.normalize();
+    let a = (d * d + radius0 * radius0 - radius1 * radius1) / (2.0 * d);
+    let center = center0.clone() + n.clone() * a;
+
+    if (d - (radius0 + radius1)).abs() <= tol || (d - (radius0 - radius1).abs()).abs() <= tol {
+        return Some(SphereSphereIntersection::Point(center));
+    }
+
+    let radius = (radius0 * radius0 - a * a).max(0.0).sqrt();
+    Some(SphereSphereIntersection::Circle(Circle {
+        plane: crate::Plane::from_point_normal(center, n),
+        radius,
+    }))
+}
+
+/// Find intersection between a plane and a sphere.
+///
+/// # Arguments
+/// * `plane` - Plane to intersect
+/// * `center` - Sphere center
+/// * `radius` - Sphere radius
+///
+/// # Returns
+/// * `Some(PlaneSphereIntersection)` - Tangent point or circle lying in the plane
+/// * `None` - If the plane misses the sphere
+pub fn plane_sphere(
+    plane: &crate::Plane,
+    center: &Point,
+    radius: Scalar,
+) -> Option<PlaneSphereIntersection> {
+    let tol = Tolerance::ABSOLUTE;
+    let distance = center.distance_to_plane(plane);
+    if distance.abs() > radius + tol {
+        return None;
+    }
+
+    let projected = center.project_to_plane(plane);
+    if (distance.abs() - radius).abs() <= tol {
+        return Some(PlaneSphereIntersection::Point(projected));
+    }
+
+    Some(PlaneSphereIntersection::Circle(Circle {
+        plane: crate::Plane::new(projected, plane.x_axis(), plane.y_axis()),
+        radius: (radius * radius - distance * distance).sqrt(),
+    }))
+}
+
+/// Find intersection between a line and a cylinder.
+///
+/// Both the line and the cylinder are treated as infinite; the cylinder axis
+/// is taken from `cylinder.line`.
+///
+/// # Arguments
+/// * `line` - Line to intersect
+/// * `cylinder` - Cylinder to intersect
+///
+/// # Returns
+/// * `Some(LineCylinderIntersection)` - Tangent point, entry/exit points, or the line itself
+/// * `None` - If the line misses the cylinder
+#[cfg(feature = "std")]
+pub fn line_cylinder(line: &Line, cylinder: &Cylinder) -> Option<LineCylinderIntersection> {
+    let tol = Tolerance::ABSOLUTE;
+    let axis = cylinder.line.to_vector().normalize();
+    let direction = line.to_vector();
+    let offset = line.start() - cylinder.line.start();
+
+    // Components perpendicular to the axis
+    let d_perp = direction.clone() - axis.clone() * direction.dot(&axis);
+    let o_perp = offset.clone() - axis.clone() * offset.dot(&axis);
+
+    let a = d_perp.length_squared();
+    let b = d_perp.dot(&o_perp);
+    let c = o_perp.length_squared() - cylinder.radius * cylinder.radius;
+
+    if a < Tolerance::ZERO_TOLERANCE * direction.length_squared().max(1.0) {
+        if (o_perp.compute_length() - cylinder.radius).abs() <= tol {
+            return Some(LineCylinderIntersection::Line(line.clone()));
+        }
+        return None;
+    }
+
+    // Distance between the line and the axis decides miss/tangent/secant
+    let axis_distance = (o_perp.length_squared() - b * b / a).max(0.0).sqrt();
+    if axis_distance > cylinder.radius + tol {
+        return None;
+    }
+    if (axis_distance - cylinder.radius).abs() <= tol {
+        return Some(LineCylinderIntersection::Point(line.point_at(-b / a)));
+    }
+
+    let disc = (b * b - a * c).max(0.0).sqrt();
+    let t0 = (-b - disc) / a;
+    let t1 = (-b + disc) / a;
+    Some(LineCylinderIntersection::Points(
+        line.point_at(t0),
+        line.point_at(t1),
+    ))
+}
+
+/// Find intersection between a plane and a cylinder.
+///
+/// The cylinder is treated as infinite; ruling lines returned for planes parallel
+/// to the axis span the length of `cylinder.line`.
+///
+/// # Arguments
+/// * `plane` - Plane to intersect
+/// * `cylinder` - Cylinder to intersect
+///
+/// # Returns
+/// * `Some(PlaneCylinderIntersection)` - Rulings, circle, or ellipse
+/// * `None` - If the plane is parallel to the axis and misses the cylinder
+#[cfg(feature = "std")]
+pub fn plane_cylinder(
+    plane: &crate::Plane,
+    cylinder: &Cylinder,
+) -> Option<PlaneCylinderIntersection> {
+    let tol = Tolerance::ABSOLUTE;
+    let radius = cylinder.radius;
+    let axis_vector = cylinder.line.to_vector();
+    let axis = axis_vector.normalize();
+    let normal = plane.z_axis();
+    let cos_angle = normal.dot(&axis);
+
+    if cos_angle.abs() < Tolerance::ANGULAR {
+        // Plane parallel to the axis: zero, one or two rulings
+        let base = cylinder.line.start();
+        let distance = base.distance_to_plane(plane);
+        if distance.abs() > radius + tol {
+            return None;
+        }
+        let foot = base - normal.clone() * distance;
+        let ruling = |p: Point| {
+            let end = p.clone() + axis_vector.clone();
+            Line::from_points(&p, &end)
+        };
+        if (distance.abs() - radius).abs() <= tol {
+            return Some(PlaneCylinderIntersection::Line(ruling(foot)));
+        }
+        let side = axis.cross(&normal).normalize();
+        let half_chord = (radius * radius - distance * distance).sqrt();
+        return Some(PlaneCylinderIntersection::Lines(
+            ruling(foot.clone() - side.clone() * half_chord),
+            ruling(foot + side * half_chord),
+        ));
+    }
+
+    let center = line_plane(&cylinder.line, plane, false)?;
+
+    if 1.0 - cos_angle.abs() < Tolerance::ANGULAR {
+        return Some(PlaneCylinderIntersection::Circle(Circle {
+            plane: crate::Plane::new(center, plane.x_axis(), plane.y_axis()),
+            radius,
+        }));
+    }
+
+    // Major axis is the projection of the cylinder axis onto the plane
+    let major = (axis.clone() - normal.clone() * cos_angle).normalize();
+    let minor = normal.cross(&major).normalize();
+    Some(PlaneCylinderIntersection::Ellipse(Ellipse {
+        plane: crate::Plane::new(center, major, minor),
+        major_radius: radius / cos_angle.abs(),
+        minor_radius: radius,
+    }))
+}
+
+/// Find intersection between a sphere and a cylinder.
+///
+/// The cylinder is treated as infinite. In cylinder coordinates the surface point
+/// at angle φ satisfies (z - cz)² = R² - r² - ρ² + 2rρ·cos(φ - φ0), where ρ and φ0
+/// are the polar coordinates of the sphere center around the axis, which gives the
+/// intersection loops in closed form.
+///
+/// # Arguments
+/// * `center` - Sphere center
+/// * `radius` - Sphere radius
+/// * `cylinder` - Cylinder to intersect
+///
+/// # Returns
+/// * `Some(SphereCylinderIntersection)` - Tangent point, circles for coaxial spheres,
+///   or sampled closed loops in the general case
+/// * `None` - If the sphere misses the cylinder
+#[cfg(feature = "std")]
+pub fn sphere_cylinder(
+    center: &Point,
+    radius: Scalar,
+    cylinder: &Cylinder,
+) -> Option<SphereCylinderIntersection> {
+    let tol = Tolerance::ABSOLUTE;
+    let r = cylinder.radius;
+    let base = cylinder.line.start();
+    let frame = crate::Plane::from_point_normal(base.clone(), cylinder.line.to_vector());
+    let (x_axis, y_axis, z_axis) = (frame.x_axis(), frame.y_axis(), frame.z_axis());
+
+    let offset = center.clone() - base.clone();
+    let cx = offset.dot(&x_axis);
+    let cy = offset.dot(&y_axis);
+    let cz = offset.dot(&z_axis);
+    let rho = (cx * cx + cy * cy).sqrt();
+
+    let to_world = |phi: Scalar, z: Scalar| {
+        base.clone()
+            + x_axis.clone() * (r * phi.cos())
+            + y_axis.clone() * (r * phi.sin())
+            + z_axis.clone() * z
+    };
+
+    if rho < tol {
+        // Coaxial: circles at cz ± sqrt(R² - r²)
+        if radius < r - tol {
+            return None;
+        }
+        let circle = |z: Scalar| Circle {
+            plane: crate::Plane::new(
+                base.clone() + z_axis.clone() * z,
+                x_axis.clone(),
+                y_axis.clone(),
+            ),
+            radius: r,
+        };
+        if (radius - r).abs() <= tol {
+            return Some(SphereCylinderIntersection::Circle(circle(cz)));
+        }
+        let h = (radius * radius - r * r).sqrt();
+        return Some(SphereCylinderIntersection::Circles(
+            circle(cz - h),
+            circle(cz + h),
+        ));
+    }
+
+    let nearest = (r - rho).abs();
+    if radius < nearest - tol {
+        return None;
+    }
+    let phi0 = cy.atan2(cx);
+    if radius <= nearest + tol {
+        // Tangent: inner touch when the center is outside, opposite side otherwise
+        let phi = if rho > r || radius < tol {
+            phi0
+        } else {
+            phi0 + PI
+        };
+        return Some(SphereCylinderIntersection::Point(to_world(phi, cz)));
+    }
+
+    let height = |phi: Scalar| {
+        (radius * radius - r * r - rho * rho + 2.0 * r * rho * (phi - phi0).cos())
+            .max(0.0)
+            .sqrt()
+    };
+    let n = SPHERE_CYLINDER_SAMPLES;
+
+    if radius >= r + rho {
+        // Sphere surrounds the cylinder cross-section: upper and lower loops
+        let lower = (0..=n)
+            .map(|i| {
+                let phi = phi0 + 2.0 * PI * i as Scalar / n as Scalar;
+                to_world(phi, cz - height(phi))
+            })
+            .collect();
+        let upper = (0..=n)
+            .map(|i| {
+                let phi = phi0 + 2.0 * PI * i as Scalar / n as Scalar;
+                to_world(phi, cz + height(phi))
+            })
+            .collect();
+        return Some(SphereCylinderIntersection::Curves(vec![
+            crate::Polyline::new(lower),
+            crate::Polyline::new(upper),
+        ]));
+    }
+
+    // Single loop over the angular interval where the sphere reaches the surface
+    let cos_beta = ((r * r + rho * rho - radius * radius) / (2.0 * r * rho)).clamp(-1.0, 1.0);
+    let beta = cos_beta.acos();
+    let phi_at = |i: usize| phi0 - beta + 2.0 * beta * i as Scalar / n as Scalar;
+    let mut points: Vec<Point> = (0..=n)
+        .map(|i| to_world(phi_at(i), cz + height(phi_at(i))))
+        .collect();
+    points.extend(
+        (1..n)
+            .rev()
+            .map(|i| to_world(phi_at(i), cz - height(phi_at(i)))),
+    );
+    points.push(points[0].clone());
+
+    Some(SphereCylinderIntersection::Curves(vec![
+        crate::Polyline::new(points),
+    ]))
+}
+
+//==========================================================================================
+// Intersection Results With Metadata
+//==========================================================================================
+
+/// Reason reported when two inputs do not intersect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoIntersection {
+    /// Inputs are parallel and apart
+    Parallel,
+    /// Lines are not parallel but pass each other beyond tolerance
+    Skew,
+    /// Inputs are apart, e.g. a line passing outside a sphere
+    Separate,
+    /// Infinite inputs would intersect, but outside the finite extents
+    OutOfRange,
+    /// An input has zero length or radius
+    Degenerate,
+}
+
+/// Intersection of two inputs with the parameters on each of them.
+///
+/// Parameters are normalized line parameters as used by `Line::point_at`.
+/// Inputs without a curve parameter, such as planes and spheres, report 0.0.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum IntersectionResult {
+    /// Inputs do not intersect
+    None(NoIntersection),
+    /// Inputs cross at a single point
+    Point {
+        point: Point,
+        t0: Scalar,
+        t1: Scalar,
+    },
+    /// Inputs touch at a single point without crossing
+    Tangent {
+        point: Point,
+        t0: Scalar,
+        t1: Scalar,
+    },
+    /// Inputs cross along a segment, e.g. two planes or a line through a sphere
+    Segment {
+        line: Line,
+        t0: [Scalar; 2],
+        t1: [Scalar; 2],
+    },
+    /// Inputs coincide along a region, e.g. overlapping collinear lines or a line
+    /// lying in a plane; `line` is None for coincident planes
+    Overlap {
+        line: Option<Line>,
+        t0: [Scalar; 2],
+        t1: [Scalar; 2],
+    },
+}
+
+impl IntersectionResult {
+    /// Check if the inputs share at least one point.
+    pub fn intersects(&self) -> bool {
+        !matches!(self, IntersectionResult::None(_))
+    }
+}
+
+/// Intersect two lines and classify the result.
+///
+/// # Arguments
+/// * `line0` - First line
+/// * `line1` - Second line
+/// * `tolerance` - Maximum distance between lines to consider them intersecting
+/// * `bounded` - If true, treat both lines as finite segments
+///
+/// # Returns
+/// * `Point` - Crossing point (midpoint of closest approach) and parameters
+/// * `Overlap` - Collinear lines; the shared part of `line0`, which is all of it
+///   when `bounded` is false
+/// * `None` - `Parallel`, `Skew`, `OutOfRange` or `Degenerate`
+pub fn line_line_ex(
+    line0: &Line,
+    line1: &Line,
+    tolerance: Scalar,
+    bounded: bool,
+) -> IntersectionResult {
+    let a = line0.to_vector();
+    let b = line1.to_vector();
+    let aa = a.dot(&a);
+    let bb = b.dot(&b);
+    if aa < Tolerance::ZERO_TOLERANCE || bb < Tolerance::ZERO_TOLERANCE {
+        return IntersectionResult::None(NoIntersection::Degenerate);
+    }
+
+    let s0 = line0.start();
+    let s1 = line1.start();
+    let c = s1.clone() - s0.clone();
+    let param0 = |p: &Point| (p.clone() - s0.clone()).dot(&a) / aa;
+    let param1 = |p: &Point| (p.clone() - s1.clone()).dot(&b) / bb;
+
+    let cross = a.cross(&b);
+    if cross.length_squared() <= Tolerance::ANGULAR * Tolerance::ANGULAR * aa * bb {
+        let offset = c.clone() - a.clone() * (c.dot(&a) / aa);
+        if offset.compute_length() > tolerance {
+            return IntersectionResult::None(NoIntersection::Parallel);
+        }
+
+        // Interval of line1 projected onto line0
+        let u0 = param0(&s1);
+        let u1 = param0(&line1.end());
+        let (mut lo, mut hi) = (u0.min(u1), u0.max(u1));
+        if bounded {
+            let slack = tolerance / aa.sqrt();
+            if hi < -slack || lo > 1.0 + slack {
+                return IntersectionResult::None(NoIntersection::OutOfRange);
+            }
+            lo = lo.max(0.0);
+            hi = hi.min(1.0);
+            if (hi - lo) * aa.sqrt() <= tolerance {
+                let t0 = (lo + hi) * 0.5;
+                let point = line0.point_at(t0);
+                let t1 = param1(&point);
+                return IntersectionResult::Point { point, t0, t1 };
+            }
+        } else {
+            lo = 0.0;
+            hi = 1.0;
+        }
+
+        let start = line0.point_at(lo);
+        let end = line0.point_at(hi);
+        let t1 = [param1(&start), param1(&end)];
+        return IntersectionResult::Overlap {
+            line: Some(Line::from_points(&start, &end)),
+            t0: [lo, hi],
+            t1,
+        };
+    }
+
+    let ab = a.dot(&b);
+    let det = aa * bb - ab * ab;
+    let t0 = (bb * c.dot(&a) - ab * c.dot(&b)) / det;
+    let t1 = (ab * c.dot(&a) - aa * c.dot(&b)) / det;
+
+    let p0 = line0.point_at(t0);
+    let p1 = line1.point_at(t1);
+    if p0.distance(&p1) > tolerance {
+        return IntersectionResult::None(NoIntersection::Skew);
+    }
+    if bounded {
+        let slack0 = tolerance / aa.sqrt();
+        let slack1 = tolerance / bb.sqrt();
+        if !(-slack0..=1.0 + slack0).contains(&t0) || !(-slack1..=1.0 + slack1).contains(&t1) {
+            return IntersectionResult::None(NoIntersection::OutOfRange);
+        }
+    }
+
+    IntersectionResult::Point {
+        point: Point::new(
+            (p0.x() + p1.x()) * 0.5,
+            (p0.y() + p1.y()) * 0.5,
+            (p0.z() + p1.z()) * 0.5,
+        ),
+        t0,
+        t1,
+    }
+}
+
+/// Intersect a line with a plane and classify the result.
+///
+/// # Arguments
+/// * `line` - Line to intersect
+/// * `plane` - Plane to intersect
+/// * `is_finite` - If true, treat line as finite segment; if false, treat as infinite
+///
+/// # Returns
+/// * `Point` - Crossing point with the line parameter in `t0`
+/// * `Overlap` - The line lies in the plane
+/// * `None` - `Parallel`, `OutOfRange` or `Degenerate`
+pub fn line_plane_ex(line: &Line, plane: &crate::Plane, is_finite: bool) -> IntersectionResult {
+    let length = line.to_vector().compute_length();
+    if length < Tolerance::ZERO_TOLERANCE {
+        return IntersectionResult::None(NoIntersection::Degenerate);
+    }
+
+    let a = line.start().distance_to_plane(plane);
+    let b = line.end().distance_to_plane(plane);
+    if (a - b).abs() <= Tolerance::ANGULAR * length {
+        if a.abs() <= Tolerance::ABSOLUTE {
+            return IntersectionResult::Overlap {
+                line: Some(line.clone()),
+                t0: [0.0, 1.0],
+                t1: [0.0, 0.0],
+            };
+        }
+        return IntersectionResult::None(NoIntersection::Parallel);
+    }
+
+    let t = a / (a - b);
+    if is_finite && !(0.0..=1.0).contains(&t) {
+        return IntersectionResult::None(NoIntersection::OutOfRange);
+    }
+    IntersectionResult::Point {
+        point: line.point_at(t),
+        t0: t,
+        t1: 0.0,
+    }
+}
+
+/// Intersect two planes and classify the result.
+///
+/// # Returns
+/// * `Segment` - Line common to both planes (infinite, unit direction)
+/// * `Overlap` - Coincident planes, with `line` set to None
+/// * `None` - `Parallel`
+pub fn plane_plane_ex(plane0: &crate::Plane, plane1: &crate::Plane) -> IntersectionResult {
+    let cross = plane0.z_axis().cross(&plane1.z_axis());
+    if cross.compute_length() <= Tolerance::ANGULAR {
+        if plane0.origin().distance_to_plane(plane1).abs() <= Tolerance::ABSOLUTE {
+            return IntersectionResult::Overlap {
+                line: None,
+                t0: [0.0, 0.0],
+                t1: [0.0, 0.0],
+            };
+        }
+        return IntersectionResult::None(NoIntersection::Parallel);
+    }
+
+    match plane_plane(plane0, plane1) {
+        Some(line) => IntersectionResult::Segment {
+            line,
+            t0: [0.0, 0.0],
+            t1: [0.0, 0.0],
+        },
+        None => IntersectionResult::None(NoIntersection::Parallel),
+    }
+}
+
+/// Intersect a line with a sphere and classify the result.
+///
+/// # Arguments
+/// * `line` - Line to intersect
+/// * `center` - Sphere center point
+/// * `radius` - Sphere radius
+/// * `is_finite` - If true, treat line as finite segment; if false, treat as infinite
+///
+/// # Returns
+/// * `Tangent` - The line touches the sphere, with the line parameter in `t0`
+/// * `Segment` - Chord of the line inside the sphere, clipped to the segment when finite
+/// * `None` - `Separate`, `OutOfRange` or `Degenerate`
+pub fn line_sphere_ex(
+    line: &Line,
+    center: &Point,
+    radius: Scalar,
+    is_finite: bool,
+) -> IntersectionResult {
+    let direction = line.to_vector();
+    let dd = direction.dot(&direction);
+    if dd < Tolerance::ZERO_TOLERANCE || radius <= 0.0 {
+        return IntersectionResult::None(NoIntersection::Degenerate);
+    }
+
+    let t = (center.clone() - line.start()).dot(&direction) / dd;
+    let closest = line.point_at(t);
+    let distance = closest.distance(center);
+    if distance > radius + Tolerance::ABSOLUTE {
+        return IntersectionResult::None(NoIntersection::Separate);
+    }
+    if (distance - radius).abs() <= Tolerance::ABSOLUTE {
+        if is_finite && !(0.0..=1.0).contains(&t) {
+            return IntersectionResult::None(NoIntersection::OutOfRange);
+        }
+        return IntersectionResult::Tangent {
+            point: closest,
+            t0: t,
+            t1: 0.0,
+        };
+    }
+
+    let half = (radius * radius - distance * distance).sqrt() / dd.sqrt();
+    let (mut ta, mut tb) = (t - half, t + half);
+    if is_finite {
+        if tb < 0.0 || ta > 1.0 {
+            return IntersectionResult::None(NoIntersection::OutOfRange);
+        }
+        ta = ta.max(0.0);
+        tb = tb.min(1.0);
+    }
+    IntersectionResult::Segment {
+        line: Line::from_points(&line.point_at(ta), &line.point_at(tb)),
+        t0: [ta, tb],
+        t1: [0.0, 0.0],
+    }
+}
+
 //==========================================================================================
 // NURBS Curve Intersection Functions
 //==========================================================================================
 
+#[cfg(feature = "std")]
 use crate::{NurbsCurve, Plane};
 
 /// Find all intersections between NURBS curve and plane
-pub fn curve_plane(curve: &NurbsCurve, plane: &Plane, tolerance: Option<f64>) -> Vec<f64> {
+#[cfg(feature = "std")]
+pub fn curve_plane(curve: &NurbsCurve, plane: &Plane, tolerance: Option<Scalar>) -> Vec<Scalar> {
     curve.intersect_plane(plane, tolerance)
 }
 
 /// Find all intersection points between NURBS curve and plane
-pub fn curve_plane_points(curve: &NurbsCurve, plane: &Plane, tolerance: Option<f64>) -> Vec<Point> {
+#[cfg(feature = "std")]
+pub fn curve_plane_points(
+    curve: &NurbsCurve,
+    plane: &Plane,
+    tolerance: Option<Scalar>,
+) -> Vec<Point> {
     curve.intersect_plane_points(plane, tolerance)
 }
 
 /// Curve-plane intersection using Bézier clipping (advanced method)
-pub fn curve_plane_bezier_clipping(curve: &NurbsCurve, plane: &Plane, tolerance: Option<f64>) -> Vec<f64> {
+#[cfg(feature = "std")]
+pub fn curve_plane_bezier_clipping(
+    curve: &NurbsCurve,
+    plane: &Plane,
+    tolerance: Option<Scalar>,
+) -> Vec<Scalar> {
     // Not yet implemented in Rust - delegate to standard method
     curve.intersect_plane(plane, tolerance)
 }
 
 /// Curve-plane intersection using algebraic/hodograph method
-pub fn curve_plane_algebraic(curve: &NurbsCurve, plane: &Plane, tolerance: Option<f64>) -> Vec<f64> {
+#[cfg(feature = "std")]
+pub fn curve_plane_algebraic(
+    curve: &NurbsCurve,
+    plane: &Plane,
+    tolerance: Option<Scalar>,
+) -> Vec<Scalar> {
     // Not yet implemented in Rust - delegate to standard method
     curve.intersect_plane(plane, tolerance)
 }
 
 /// Curve-plane intersection using production CAD kernel method
-pub fn curve_plane_production(curve: &NurbsCurve, plane: &Plane, tolerance: Option<f64>) -> Vec<f64> {
+#[cfg(feature = "std")]
+pub fn curve_plane_production(
+    curve: &NurbsCurve,
+    plane: &Plane,
+    tolerance: Option<Scalar>,
+) -> Vec<Scalar> {
     // Not yet implemented in Rust - delegate to standard method
     curve.intersect_plane(plane, tolerance)
 }
 
 /// Find closest point on NURBS curve to test point
-pub fn curve_closest_point(curve: &NurbsCurve, test_point: &Point, t0: f64, t1: f64) -> (f64, f64) {
+#[cfg(feature = "std")]
+pub fn curve_closest_point(
+    curve: &NurbsCurve,
+    test_point: &Point,
+    t0: Scalar,
+    t1: Scalar,
+) -> (Scalar, Scalar) {
     // Not yet implemented in Rust - return placeholder
     let (domain_t0, domain_t1) = curve.domain();
     let t_start = if t0 == 0.0 { domain_t0 } else { t0 };
@@ -518,12 +2269,12 @@ pub fn curve_closest_point(curve: &NurbsCurve, test_point: &Point, t0: f64, t1:
     
     // Simple grid search for now
     let samples = 100;
-    let dt = (t_end - t_start) / samples as f64;
+    let dt = (t_end - t_start) / samples as Scalar;
     let mut best_t = t_start;
-    let mut best_dist = f64::MAX;
+    let mut best_dist = Scalar::MAX;
     
     for i in 0..=samples {
-        let t = t_start + i as f64 * dt;
+        let t = t_start + i as Scalar * dt;
         let pt = curve.point_at(t);
         let dist = pt.distance(test_point);
         if dist < best_dist {
@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod tests {
     use crate::intersection::*;
-    use crate::{Line, Plane, Point, Tolerance, Vector};
+    use crate::scalar::test_tolerance;
+    use crate::Scalar;
+    use crate::{BoundingBox, Cylinder, Line, Mesh, Plane, Point, Polyline, Tolerance, Vector};
 
     #[test]
     fn test_line_line_intersection() {
@@ -120,6 +122,41 @@ mod tests {
         assert!((points[1].z() - 796.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_ray_box_oriented() {
+        // Unit cube rotated 45 degrees about Z: its corners reach sqrt(2)/2 along X
+        let s = crate::scalar::consts::FRAC_1_SQRT_2;
+        let box_ = crate::BoundingBox::new(
+            Point::new(0.0, 0.0, 0.0),
+            Vector::new(s, s, 0.0),
+            Vector::new(-s, s, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(0.5, 0.5, 0.5),
+        );
+        assert!(!box_.is_axis_aligned());
+
+        let l0 = Line::new(-5.0, 0.0, 0.0, 5.0, 0.0, 0.0);
+        let points = ray_box(&l0, &box_, 0.0, 1.0).expect("Should find intersection");
+        assert!((points[0].x() + s).abs() < test_tolerance(1e-9));
+        assert!((points[1].x() - s).abs() < test_tolerance(1e-9));
+
+        // Hits the rotated corner outside the unrotated extent
+        let l1 = Line::new(0.6, -5.0, 0.0, 0.6, 5.0, 0.0);
+        assert!(ray_box(&l1, &box_, 0.0, 1.0).is_some());
+
+        // Runs along the rotated X axis just outside the box
+        let offset = 0.6 * s;
+        let l2 = Line::new(
+            -5.0 - offset,
+            -5.0 + offset,
+            0.0,
+            5.0 - offset,
+            5.0 + offset,
+            0.0,
+        );
+        assert!(ray_box(&l2, &box_, 0.0, 1.0).is_none());
+    }
+
     #[test]
     fn test_ray_box_no_intersection() {
         let l0 = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
@@ -192,4 +229,732 @@ mod tests {
 
         assert!(triangle_hit.is_none());
     }
+
+    #[test]
+    fn test_sphere_sphere_circle() {
+        let c0 = Point::new(0.0, 0.0, 0.0);
+        let c1 = Point::new(2.0, 0.0, 0.0);
+        match sphere_sphere(&c0, 2.0, &c1, 2.0) {
+            Some(SphereSphereIntersection::Circle(circle)) => {
+                assert!(circle.plane.origin().distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+                assert!((circle.radius - Scalar::sqrt(3.0)).abs() < 1e-9);
+                assert!((circle.plane.z_axis().x().abs() - 1.0).abs() < 1e-9);
+            }
+            other => panic!("Expected circle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sphere_sphere_tangent_and_miss() {
+        let c0 = Point::new(0.0, 0.0, 0.0);
+        let c1 = Point::new(3.0, 0.0, 0.0);
+        match sphere_sphere(&c0, 1.0, &c1, 2.0) {
+            Some(SphereSphereIntersection::Point(p)) => {
+                assert!(p.distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9)
+            }
+            other => panic!("Expected point, got {:?}", other),
+        }
+        assert!(sphere_sphere(&c0, 1.0, &c1, 1.0).is_none());
+        assert!(sphere_sphere(&c0, 5.0, &c1, 1.0).is_none());
+        assert!(matches!(
+            sphere_sphere(&c0, 1.0, &c0, 1.0),
+            Some(SphereSphereIntersection::Coincident)
+        ));
+    }
+
+    #[test]
+    fn test_plane_sphere() {
+        let plane = Plane::from_point_normal(Point::new(0.0, 0.0, 1.0), Vector::z_axis());
+        let center = Point::new(0.0, 0.0, 0.0);
+        match plane_sphere(&plane, &center, 2.0) {
+            Some(PlaneSphereIntersection::Circle(circle)) => {
+                assert!(circle.plane.origin().distance(&Point::new(0.0, 0.0, 1.0)) < 1e-9);
+                assert!((circle.radius - Scalar::sqrt(3.0)).abs() < 1e-9);
+            }
+            other => panic!("Expected circle, got {:?}", other),
+        }
+        assert!(matches!(
+            plane_sphere(&plane, &center, 1.0),
+            Some(PlaneSphereIntersection::Point(_))
+        ));
+        assert!(plane_sphere(&plane, &center, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_line_cylinder() {
+        let cylinder = Cylinder::new(Line::new(0.0, 0.0, 0.0, 0.0, 0.0, 10.0), 1.0);
+
+        let line = Line::new(-5.0, 0.0, 5.0, 5.0, 0.0, 5.0);
+        match line_cylinder(&line, &cylinder) {
+            Some(LineCylinderIntersection::Points(p0, p1)) => {
+                assert!(p0.distance(&Point::new(-1.0, 0.0, 5.0)) < test_tolerance(1e-9));
+                assert!(p1.distance(&Point::new(1.0, 0.0, 5.0)) < test_tolerance(1e-9));
+            }
+            other => panic!("Expected two points, got {:?}", other),
+        }
+
+        let tangent = Line::new(-5.0, 1.0, 5.0, 5.0, 1.0, 5.0);
+        assert!(matches!(
+            line_cylinder(&tangent, &cylinder),
+            Some(LineCylinderIntersection::Point(_))
+        ));
+
+        let on_surface = Line::new(1.0, 0.0, 0.0, 1.0, 0.0, 3.0);
+        assert!(matches!(
+            line_cylinder(&on_surface, &cylinder),
+            Some(LineCylinderIntersection::Line(_))
+        ));
+
+        let miss = Line::new(-5.0, 2.0, 5.0, 5.0, 2.0, 5.0);
+        assert!(line_cylinder(&miss, &cylinder).is_none());
+    }
+
+    #[test]
+    fn test_plane_cylinder() {
+        let cylinder = Cylinder::new(Line::new(0.0, 0.0, 0.0, 0.0, 0.0, 10.0), 1.0);
+
+        let xy = Plane::from_point_normal(Point::new(0.0, 0.0, 2.0), Vector::z_axis());
+        match plane_cylinder(&xy, &cylinder) {
+            Some(PlaneCylinderIntersection::Circle(circle)) => {
+                assert!(circle.plane.origin().distance(&Point::new(0.0, 0.0, 2.0)) < 1e-9);
+                assert!((circle.radius - 1.0).abs() < 1e-9);
+            }
+            other => panic!("Expected circle, got {:?}", other),
+        }
+
+        let oblique =
+            Plane::from_point_normal(Point::new(0.0, 0.0, 5.0), Vector::new(1.0, 0.0, 1.0));
+        match plane_cylinder(&oblique, &cylinder) {
+            Some(PlaneCylinderIntersection::Ellipse(ellipse)) => {
+                assert!((ellipse.major_radius - Scalar::sqrt(2.0)).abs() < 1e-9);
+                assert!((ellipse.minor_radius - 1.0).abs() < 1e-9);
+                assert!(ellipse.plane.origin().distance(&Point::new(0.0, 0.0, 5.0)) < 1e-9);
+            }
+            other => panic!("Expected ellipse, got {:?}", other),
+        }
+
+        let parallel = Plane::from_point_normal(Point::new(0.5, 0.0, 0.0), Vector::x_axis());
+        match plane_cylinder(&parallel, &cylinder) {
+            Some(PlaneCylinderIntersection::Lines(l0, l1)) => {
+                assert!((l0.start().x() - 0.5).abs() < 1e-9);
+                assert!((l0.start().y().abs() - Scalar::sqrt(0.75)).abs() < 1e-9);
+                assert!((l0.start().y() + l1.start().y()).abs() < 1e-9);
+                assert!((l0.length() - 10.0).abs() < 1e-9);
+            }
+            other => panic!("Expected two lines, got {:?}", other),
+        }
+
+        let outside = Plane::from_point_normal(Point::new(2.0, 0.0, 0.0), Vector::x_axis());
+        assert!(plane_cylinder(&outside, &cylinder).is_none());
+    }
+
+    #[test]
+    fn test_sphere_cylinder_coaxial() {
+        let cylinder = Cylinder::new(Line::new(0.0, 0.0, 0.0, 0.0, 0.0, 10.0), 1.0);
+        let center = Point::new(0.0, 0.0, 5.0);
+        match sphere_cylinder(&center, 2.0, &cylinder) {
+            Some(SphereCylinderIntersection::Circles(c0, c1)) => {
+                let h = Scalar::sqrt(3.0);
+                assert!((c0.plane.origin().z() - (5.0 - h)).abs() < 1e-9);
+                assert!((c1.plane.origin().z() - (5.0 + h)).abs() < 1e-9);
+            }
+            other => panic!("Expected two circles, got {:?}", other),
+        }
+        assert!(sphere_cylinder(&center, 0.5, &cylinder).is_none());
+    }
+
+    #[test]
+    fn test_sphere_cylinder_general() {
+        let cylinder = Cylinder::new(Line::new(0.0, 0.0, 0.0, 0.0, 0.0, 10.0), 1.0);
+        let center = Point::new(1.5, 0.0, 5.0);
+
+        match sphere_cylinder(&center, 1.0, &cylinder) {
+            Some(SphereCylinderIntersection::Curves(curves)) => {
+                assert_eq!(curves.len(), 1);
+                assert!(curves[0].is_closed());
+                for p in &curves[0].points {
+                    let axis_distance = (p.x() * p.x() + p.y() * p.y()).sqrt();
+                    assert!((axis_distance - 1.0).abs() < test_tolerance(1e-9));
+                    assert!((p.distance(&center) - 1.0).abs() < test_tolerance(1e-9));
+                }
+            }
+            other => panic!("Expected one loop, got {:?}", other),
+        }
+
+        match sphere_cylinder(&center, 3.0, &cylinder) {
+            Some(SphereCylinderIntersection::Curves(curves)) => assert_eq!(curves.len(), 2),
+            other => panic!("Expected two loops, got {:?}", other),
+        }
+
+        assert!(matches!(
+            sphere_cylinder(&center, 0.5, &cylinder),
+            Some(SphereCylinderIntersection::Point(_))
+        ));
+        assert!(sphere_cylinder(&Point::new(5.0, 0.0, 5.0), 1.0, &cylinder).is_none());
+    }
+
+    #[test]
+    fn test_line_line_closest_points_skew() {
+        let l0 = Line::new(0.0, 0.0, 0.0, 2.0, 0.0, 0.0);
+        let l1 = Line::new(1.0, -1.0, 1.0, 1.0, 1.0, 1.0);
+        let (p0, p1, d) = line_line_closest_points(&l0, &l1, true, true);
+        assert!(p0.distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+        assert!(p1.distance(&Point::new(1.0, 0.0, 1.0)) < 1e-9);
+        assert!((d - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_line_line_closest_points_bounded_flags() {
+        let l0 = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let l1 = Line::new(3.0, -1.0, 0.0, 3.0, 1.0, 0.0);
+
+        let (p0, p1, d) = line_line_closest_points(&l0, &l1, true, true);
+        assert!(p0.distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+        assert!(p1.distance(&Point::new(3.0, 0.0, 0.0)) < 1e-9);
+        assert!((d - 2.0).abs() < 1e-9);
+
+        let (p0, _, d) = line_line_closest_points(&l0, &l1, false, true);
+        assert!(p0.distance(&Point::new(3.0, 0.0, 0.0)) < 1e-9);
+        assert!(d < 1e-9);
+
+        let l2 = Line::new(3.0, 1.0, 0.0, 3.0, 2.0, 0.0);
+        let (_, p1, d) = line_line_closest_points(&l0, &l2, false, true);
+        assert!(p1.distance(&Point::new(3.0, 1.0, 0.0)) < 1e-9);
+        assert!((d - 1.0).abs() < 1e-9);
+
+        let (_, p1, d) = line_line_closest_points(&l0, &l2, false, false);
+        assert!(p1.distance(&Point::new(3.0, 0.0, 0.0)) < 1e-9);
+        assert!(d < 1e-9);
+    }
+
+    #[test]
+    fn test_line_line_closest_points_parallel_and_degenerate() {
+        let l0 = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let l1 = Line::new(0.5, 2.0, 0.0, 1.5, 2.0, 0.0);
+        let (_, _, d) = line_line_closest_points(&l0, &l1, true, true);
+        assert!((d - 2.0).abs() < 1e-9);
+
+        let point = Line::new(0.5, 1.0, 0.0, 0.5, 1.0, 0.0);
+        let (p0, p1, d) = line_line_closest_points(&l0, &point, true, true);
+        assert!(p0.distance(&Point::new(0.5, 0.0, 0.0)) < 1e-9);
+        assert!(p1.distance(&Point::new(0.5, 1.0, 0.0)) < 1e-9);
+        assert!((d - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_triangle_triangle_segment() {
+        let t1 = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(4.0, 0.0, 0.0),
+            Point::new(0.0, 4.0, 0.0),
+        ];
+        let t2 = [
+            Point::new(1.0, 1.0, -1.0),
+            Point::new(1.0, 1.0, 1.0),
+            Point::new(5.0, 1.0, 0.0),
+        ];
+        let (p0, p1) = triangle_triangle(&t1, &t2).expect("Should intersect");
+        let (p0, p1) = if p0.x() < p1.x() { (p0, p1) } else { (p1, p0) };
+        assert!(p0.distance(&Point::new(1.0, 1.0, 0.0)) < 1e-9);
+        assert!(p1.distance(&Point::new(3.0, 1.0, 0.0)) < 1e-9);
+    }
+
+    #[test]
+    fn test_triangle_triangle_no_intersection() {
+        let t1 = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        ];
+        let above = [
+            Point::new(0.0, 0.0, 1.0),
+            Point::new(1.0, 0.0, 2.0),
+            Point::new(0.0, 1.0, 1.5),
+        ];
+        assert!(triangle_triangle(&t1, &above).is_none());
+
+        // Crosses the plane of t1 but outside of it
+        let beside = [
+            Point::new(5.0, 5.0, -1.0),
+            Point::new(5.0, 5.0, 1.0),
+            Point::new(6.0, 5.0, 0.0),
+        ];
+        assert!(triangle_triangle(&t1, &beside).is_none());
+
+        let coplanar = [
+            Point::new(2.0, 2.0, 0.0),
+            Point::new(3.0, 2.0, 0.0),
+            Point::new(2.0, 3.0, 0.0),
+        ];
+        assert!(triangle_triangle(&t1, &coplanar).is_none());
+    }
+
+    #[test]
+    fn test_triangle_triangle_coplanar() {
+        let t1 = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(4.0, 0.0, 0.0),
+            Point::new(0.0, 4.0, 0.0),
+        ];
+
+        // Contained triangle, the overlap is the triangle itself
+        let inner = [
+            Point::new(0.5, 0.5, 0.0),
+            Point::new(1.5, 0.5, 0.0),
+            Point::new(0.5, 2.5, 0.0),
+        ];
+        let (p0, p1) = triangle_triangle(&t1, &inner).expect("Should overlap");
+        assert!((p0.distance(&p1) - Point::new(1.5, 0.5, 0.0).distance(&inner[2])).abs() < 1e-9);
+
+        // In a tilted plane, a triangle touching the hypotenuse of t1 with one vertex
+        let tilt = |x: Scalar, y: Scalar| Point::new(x, y, 0.5 * x + 0.25 * y);
+        let t1 = t1.map(|p| tilt(p.x(), p.y()));
+        let crossing = [tilt(2.0, 2.0), tilt(6.0, 2.0), tilt(2.0, 6.0)];
+        let (p0, p1) = triangle_triangle(&t1, &crossing).expect("Should overlap");
+        let (p0, p1) = if p0.x() < p1.x() { (p0, p1) } else { (p1, p0) };
+        assert!(p0.distance(&tilt(2.0, 2.0)) < 1e-9);
+        assert!(p1.distance(&tilt(2.0, 2.0)) < 1e-9);
+
+        // Crossing the hypotenuse, the farthest overlap points are the edge crossings
+        let overlapping = [tilt(1.0, 1.0), tilt(5.0, 1.0), tilt(1.0, 5.0)];
+        let (p0, p1) = triangle_triangle(&t1, &overlapping).expect("Should overlap");
+        let (p0, p1) = if p0.x() < p1.x() { (p0, p1) } else { (p1, p0) };
+        assert!(p0.distance(&tilt(1.0, 3.0)) < 1e-9);
+        assert!(p1.distance(&tilt(3.0, 1.0)) < 1e-9);
+    }
+
+    #[test]
+    fn test_triangle_triangle_touching_vertex() {
+        let t1 = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+            Point::new(0.0, 2.0, 0.0),
+        ];
+        let t2 = [
+            Point::new(0.5, 0.5, 0.0),
+            Point::new(0.5, 0.0, 1.0),
+            Point::new(0.5, 1.0, 1.0),
+        ];
+        let (p0, p1) = triangle_triangle(&t1, &t2).expect("Should touch");
+        assert!(p0.distance(&Point::new(0.5, 0.5, 0.0)) < 1e-9);
+        assert!(p1.distance(&Point::new(0.5, 0.5, 0.0)) < 1e-9);
+    }
+
+    #[test]
+    fn test_polyline_polyline() {
+        let zigzag = Polyline::new(vec![
+            Point::new(0.0, -1.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(2.0, -1.0, 0.0),
+            Point::new(3.0, 1.0, 0.0),
+        ]);
+        let axis = Polyline::new(vec![Point::new(-1.0, 0.0, 0.0), Point::new(4.0, 0.0, 0.0)]);
+
+        let hits = polyline_polyline(&zigzag, &axis, Tolerance::ABSOLUTE);
+        assert_eq!(hits.len(), 3);
+        for (k, hit) in hits.iter().enumerate() {
+            assert_eq!(hit.segment_a, k);
+            assert_eq!(hit.segment_b, 0);
+            assert!((hit.t_a - 0.5).abs() < 1e-9);
+            assert!(hit.point.distance(&Point::new(0.5 + k as Scalar, 0.0, 0.0)) < 1e-9);
+            assert!((hit.t_b - (1.5 + k as Scalar) / 5.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_polyline_polyline_shared_vertex_and_3d() {
+        let a = Polyline::new(vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+        ]);
+        let b = Polyline::new(vec![Point::new(1.0, -1.0, 0.0), Point::new(1.0, 1.0, 0.0)]);
+        let hits = polyline_polyline(&a, &b, Tolerance::ABSOLUTE);
+        assert_eq!(hits.len(), 1);
+
+        let skew = Polyline::new(vec![Point::new(1.0, -1.0, 0.5), Point::new(1.0, 1.0, 0.5)]);
+        assert!(polyline_polyline(&a, &skew, 0.1).is_empty());
+        assert_eq!(polyline_polyline(&a, &skew, 0.6).len(), 1);
+    }
+
+    #[test]
+    fn test_polyline_polyline_many_segments() {
+        let zigzag = Polyline::new(
+            (0..=40)
+                .map(|i| Point::new(i as Scalar, (i % 2) as Scalar, 0.0))
+                .collect(),
+        );
+        // The straight line is split at every half unit, so each crossing lands on one of its
+        // vertices and is found by two segments.
+        let line = Polyline::new(
+            (0..=84)
+                .map(|i| Point::new(-1.0 + 0.5 * i as Scalar, 0.5, 0.0))
+                .collect(),
+        );
+
+        let hits = polyline_polyline(&zigzag, &line, Tolerance::ABSOLUTE);
+        assert_eq!(hits.len(), 40);
+        for (k, hit) in hits.iter().enumerate() {
+            assert_eq!(hit.segment_a, k);
+            assert!(hit.point.distance(&Point::new(0.5 + k as Scalar, 0.5, 0.0)) < 1e-6);
+        }
+    }
+
+    fn unit_cube_mesh() -> Mesh {
+        let p = |x: Scalar, y: Scalar, z: Scalar| Point::new(x, y, z);
+        Mesh::from_polygons(
+            vec![
+                vec![
+                    p(0.0, 0.0, 0.0),
+                    p(0.0, 1.0, 0.0),
+                    p(1.0, 1.0, 0.0),
+                    p(1.0, 0.0, 0.0),
+                ],
+                vec![
+                    p(0.0, 0.0, 1.0),
+                    p(1.0, 0.0, 1.0),
+                    p(1.0, 1.0, 1.0),
+                    p(0.0, 1.0, 1.0),
+                ],
+                vec![
+                    p(0.0, 0.0, 0.0),
+                    p(1.0, 0.0, 0.0),
+                    p(1.0, 0.0, 1.0),
+                    p(0.0, 0.0, 1.0),
+                ],
+                vec![
+                    p(1.0, 0.0, 0.0),
+                    p(1.0, 1.0, 0.0),
+                    p(1.0, 1.0, 1.0),
+                    p(1.0, 0.0, 1.0),
+                ],
+                vec![
+                    p(1.0, 1.0, 0.0),
+                    p(0.0, 1.0, 0.0),
+                    p(0.0, 1.0, 1.0),
+                    p(1.0, 1.0, 1.0),
+                ],
+                vec![
+                    p(0.0, 1.0, 0.0),
+                    p(0.0, 0.0, 0.0),
+                    p(0.0, 0.0, 1.0),
+                    p(0.0, 1.0, 1.0),
+                ],
+            ],
+            None,
+        )
+    }
+
+    #[test]
+    fn test_point_in_mesh() {
+        let mesh = unit_cube_mesh();
+        let tol = Tolerance::APPROXIMATION;
+        assert_eq!(
+            point_in_mesh(&Point::new(0.5, 0.5, 0.5), &mesh, tol),
+            Containment::Inside
+        );
+        assert_eq!(
+            point_in_mesh(&Point::new(0.9, 0.1, 0.2), &mesh, tol),
+            Containment::Inside
+        );
+        assert_eq!(
+            point_in_mesh(&Point::new(1.5, 0.5, 0.5), &mesh, tol),
+            Containment::Outside
+        );
+        assert_eq!(
+            point_in_mesh(&Point::new(1.0, 0.5, 0.5), &mesh, tol),
+            Containment::Boundary
+        );
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let polygon = Polyline::new(vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(4.0, 0.0, 0.0),
+            Point::new(4.0, 4.0, 0.0),
+            Point::new(2.0, 1.0, 0.0),
+            Point::new(0.0, 4.0, 0.0),
+        ]);
+        let plane = Plane::xy_plane();
+        let tol = Tolerance::APPROXIMATION;
+        assert_eq!(
+            point_in_polygon(&Point::new(1.0, 0.5, 5.0), &polygon, &plane, tol),
+            Containment::Inside
+        );
+        assert_eq!(
+            point_in_polygon(&Point::new(2.0, 3.0, 0.0), &polygon, &plane, tol),
+            Containment::Outside
+        );
+        assert_eq!(
+            point_in_polygon(&Point::new(2.0, 0.0, 0.0), &polygon, &plane, tol),
+            Containment::Boundary
+        );
+    }
+
+    #[test]
+    fn test_point_in_bbox() {
+        let bbox =
+            BoundingBox::from_points(&[Point::new(0.0, 0.0, 0.0), Point::new(2.0, 2.0, 2.0)], 0.0);
+        let tol = Tolerance::APPROXIMATION;
+        assert_eq!(
+            point_in_bbox(&Point::new(1.0, 1.0, 1.0), &bbox, tol),
+            Containment::Inside
+        );
+        assert_eq!(
+            point_in_bbox(&Point::new(2.0, 1.0, 1.0), &bbox, tol),
+            Containment::Boundary
+        );
+        assert_eq!(
+            point_in_bbox(&Point::new(2.1, 1.0, 1.0), &bbox, tol),
+            Containment::Outside
+        );
+        assert_eq!(
+            point_in_bbox(&Point::new(2.1, 1.0, 1.0), &bbox, 0.2),
+            Containment::Boundary
+        );
+    }
+
+    #[test]
+    fn test_plane_aabb_classify() {
+        let bbox =
+            BoundingBox::from_points(&[Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0)], 0.0);
+        let below = Plane::from_point_normal(Point::new(0.0, 0.0, -1.0), Vector::z_axis());
+        let above = Plane::from_point_normal(Point::new(0.0, 0.0, 2.0), Vector::z_axis());
+        let through =
+            Plane::from_point_normal(Point::new(0.5, 0.5, 0.5), Vector::new(1.0, 1.0, 1.0));
+        assert_eq!(plane_aabb_classify(&below, &bbox), PlaneSide::Front);
+        assert_eq!(plane_aabb_classify(&above, &bbox), PlaneSide::Back);
+        assert_eq!(plane_aabb_classify(&through, &bbox), PlaneSide::Straddling);
+    }
+
+    #[test]
+    fn test_plane_box() {
+        let bbox =
+            BoundingBox::from_points(&[Point::new(0.0, 0.0, 0.0), Point::new(2.0, 2.0, 2.0)], 0.0);
+        let horizontal = Plane::from_point_normal(Point::new(0.0, 0.0, 1.0), Vector::z_axis());
+        let section = plane_box(&horizontal, &bbox).expect("Should cut the box");
+        assert_eq!(section.len(), 5);
+        assert!(section.is_closed());
+        assert!((section.length() - 8.0).abs() < 1e-9);
+
+        // Diagonal cut through the center gives a regular hexagon
+        let diagonal =
+            Plane::from_point_normal(Point::new(1.0, 1.0, 1.0), Vector::new(1.0, 1.0, 1.0));
+        let hexagon = plane_box(&diagonal, &bbox).expect("Should cut the box");
+        assert_eq!(hexagon.len(), 7);
+        assert!((hexagon.length() - 6.0 * Scalar::sqrt(2.0)).abs() < 1e-9);
+
+        let miss = Plane::from_point_normal(Point::new(0.0, 0.0, 3.0), Vector::z_axis());
+        assert!(plane_box(&miss, &bbox).is_none());
+    }
+
+    #[test]
+    fn test_plane_mesh() {
+        let mesh = unit_cube_mesh();
+        let plane = Plane::from_point_normal(Point::new(0.0, 0.0, 0.5), Vector::z_axis());
+        let sections = plane_mesh(&plane, &mesh, Tolerance::APPROXIMATION);
+        assert_eq!(sections.len(), 1);
+        assert!(sections[0].is_closed());
+        assert!((sections[0].length() - 4.0).abs() < 1e-9);
+
+        let miss = Plane::from_point_normal(Point::new(0.0, 0.0, 2.0), Vector::z_axis());
+        assert!(plane_mesh(&miss, &mesh, Tolerance::APPROXIMATION).is_empty());
+    }
+
+    #[test]
+    fn test_plane_mesh_long_chains() {
+        // Unit radius tube of quads around `sweep` radians, listed from the middle
+        // so the first segment has to grow at both ends
+        let tube = |count: usize, sweep: Scalar| {
+            let at = |i: usize, z: Scalar| {
+                let angle = sweep * i as Scalar / count as Scalar;
+                Point::new(angle.cos(), angle.sin(), z)
+            };
+            let quads: Vec<Vec<Point>> = (0..count)
+                .map(|i| (i + count / 2) % count)
+                .map(|i| vec![at(i, 0.0), at(i + 1, 0.0), at(i + 1, 1.0), at(i, 1.0)])
+                .collect();
+            Mesh::from_polygons(quads, None)
+        };
+        let plane = Plane::from_point_normal(Point::new(0.0, 0.0, 0.5), Vector::z_axis());
+
+        let closed = plane_mesh(&plane, &tube(2000, 2.0 * crate::scalar::consts::PI), 1e-6);
+        assert_eq!(closed.len(), 1);
+        assert!(closed[0].is_closed());
+        // Each quad is split into two triangles, each adding one segment
+        assert_eq!(closed[0].len(), 4001);
+
+        let open = plane_mesh(&plane, &tube(2000, crate::scalar::consts::PI), 1e-6);
+        assert_eq!(open.len(), 1);
+        assert!(!open[0].is_closed());
+        assert_eq!(open[0].len(), 4001);
+        let ends = [open[0].points[0].clone(), open[0].points[4000].clone()];
+        let near = |q: Point| ends.iter().any(|p| p.distance(&q) < test_tolerance(1e-6));
+        assert!(near(Point::new(1.0, 0.0, 0.5)) && near(Point::new(-1.0, 0.0, 0.5)));
+    }
+
+    #[test]
+    fn test_ray_cylinder() {
+        let cylinder = Cylinder::new(Line::new(0.0, 0.0, 0.0, 0.0, 0.0, 4.0), 1.0);
+
+        let side = Line::new(-5.0, 0.0, 2.0, -4.0, 0.0, 2.0);
+        let hits = ray_cylinder(&side, &cylinder).expect("Should hit the side");
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].distance(&Point::new(-1.0, 0.0, 2.0)) < 1e-9);
+        assert!(hits[1].distance(&Point::new(1.0, 0.0, 2.0)) < 1e-9);
+
+        let cap = Line::new(0.5, 0.0, 10.0, 0.5, 0.0, 9.0);
+        let hits = ray_cylinder(&cap, &cylinder).expect("Should hit the caps");
+        assert!(hits[0].distance(&Point::new(0.5, 0.0, 4.0)) < 1e-9);
+        assert!(hits[1].distance(&Point::new(0.5, 0.0, 0.0)) < 1e-9);
+
+        let behind = Line::new(5.0, 0.0, 2.0, 6.0, 0.0, 2.0);
+        assert!(ray_cylinder(&behind, &cylinder).is_none());
+        let above = Line::new(-5.0, 0.0, 5.0, -4.0, 0.0, 5.0);
+        assert!(ray_cylinder(&above, &cylinder).is_none());
+    }
+
+    #[test]
+    fn test_ray_cone() {
+        let base = Point::new(0.0, 0.0, 0.0);
+        let apex = Point::new(0.0, 0.0, 2.0);
+
+        // Horizontal ray at half height hits the lateral surface at radius 0.5
+        let ray = Line::new(-5.0, 0.0, 1.0, -4.0, 0.0, 1.0);
+        let hits = ray_cone(&ray, &base, &apex, 1.0).expect("Should hit the cone");
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].distance(&Point::new(-0.5, 0.0, 1.0)) < test_tolerance(1e-9));
+        assert!(hits[1].distance(&Point::new(0.5, 0.0, 1.0)) < test_tolerance(1e-9));
+
+        // Vertical ray from above hits the lateral surface then the base
+        let down = Line::new(0.5, 0.0, 5.0, 0.5, 0.0, 4.0);
+        let hits = ray_cone(&down, &base, &apex, 1.0).expect("Should hit the cone");
+        assert!(hits[0].distance(&Point::new(0.5, 0.0, 1.0)) < test_tolerance(1e-9));
+        assert!(hits[1].distance(&Point::new(0.5, 0.0, 0.0)) < test_tolerance(1e-9));
+
+        // Ray passing above the apex misses
+        let miss = Line::new(-5.0, 0.0, 2.5, -4.0, 0.0, 2.5);
+        assert!(ray_cone(&miss, &base, &apex, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_line_line_ex_classification() {
+        let l0 = Line::new(0.0, 0.0, 0.0, 2.0, 0.0, 0.0);
+
+        let crossing = Line::new(1.0, -1.0, 0.0, 1.0, 1.0, 0.0);
+        match line_line_ex(&l0, &crossing, Tolerance::ABSOLUTE, true) {
+            IntersectionResult::Point { point, t0, t1 } => {
+                assert!(point.distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+                assert!((t0 - 0.5).abs() < 1e-9);
+                assert!((t1 - 0.5).abs() < 1e-9);
+            }
+            other => panic!("Expected point, got {:?}", other),
+        }
+
+        let skew = Line::new(1.0, -1.0, 1.0, 1.0, 1.0, 1.0);
+        assert!(matches!(
+            line_line_ex(&l0, &skew, Tolerance::ABSOLUTE, true),
+            IntersectionResult::None(NoIntersection::Skew)
+        ));
+
+        let parallel = Line::new(0.0, 1.0, 0.0, 2.0, 1.0, 0.0);
+        assert!(matches!(
+            line_line_ex(&l0, &parallel, Tolerance::ABSOLUTE, true),
+            IntersectionResult::None(NoIntersection::Parallel)
+        ));
+
+        let short = Line::new(5.0, -1.0, 0.0, 5.0, 1.0, 0.0);
+        assert!(matches!(
+            line_line_ex(&l0, &short, Tolerance::ABSOLUTE, true),
+            IntersectionResult::None(NoIntersection::OutOfRange)
+        ));
+        assert!(line_line_ex(&l0, &short, Tolerance::ABSOLUTE, false).intersects());
+
+        let collinear = Line::new(3.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        match line_line_ex(&l0, &collinear, Tolerance::ABSOLUTE, true) {
+            IntersectionResult::Overlap { line, t0, t1 } => {
+                let line = line.expect("Collinear overlap should have a line");
+                assert!(line.start().distance(&Point::new(1.0, 0.0, 0.0)) < 1e-9);
+                assert!(line.end().distance(&Point::new(2.0, 0.0, 0.0)) < 1e-9);
+                assert!((t0[0] - 0.5).abs() < 1e-9 && (t0[1] - 1.0).abs() < 1e-9);
+                assert!((t1[0] - 1.0).abs() < 1e-9 && (t1[1] - 0.5).abs() < 1e-9);
+            }
+            other => panic!("Expected overlap, got {:?}", other),
+        }
+
+        let touching = Line::new(2.0, 0.0, 0.0, 4.0, 0.0, 0.0);
+        assert!(matches!(
+            line_line_ex(&l0, &touching, Tolerance::ABSOLUTE, true),
+            IntersectionResult::Point { .. }
+        ));
+    }
+
+    #[test]
+    fn test_line_plane_ex_and_plane_plane_ex() {
+        let xy = Plane::xy_plane();
+        let vertical = Line::new(0.0, 0.0, -1.0, 0.0, 0.0, 1.0);
+        match line_plane_ex(&vertical, &xy, true) {
+            IntersectionResult::Point { point, t0, .. } => {
+                assert!(point.distance(&Point::new(0.0, 0.0, 0.0)) < 1e-9);
+                assert!((t0 - 0.5).abs() < 1e-9);
+            }
+            other => panic!("Expected point, got {:?}", other),
+        }
+
+        let inside = Line::new(0.0, 0.0, 0.0, 1.0, 1.0, 0.0);
+        assert!(matches!(
+            line_plane_ex(&inside, &xy, true),
+            IntersectionResult::Overlap { line: Some(_), .. }
+        ));
+        let above = Line::new(0.0, 0.0, 1.0, 1.0, 1.0, 1.0);
+        assert!(matches!(
+            line_plane_ex(&above, &xy, true),
+            IntersectionResult::None(NoIntersection::Parallel)
+        ));
+
+        let shifted =
+            Plane::from_point_normal(Point::new(0.0, 0.0, 3.0), Vector::new(0.0, 0.0, 1.0));
+        assert!(matches!(
+            plane_plane_ex(&xy, &shifted),
+            IntersectionResult::None(NoIntersection::Parallel)
+        ));
+        assert!(matches!(
+            plane_plane_ex(&xy, &xy),
+            IntersectionResult::Overlap { line: None, .. }
+        ));
+        assert!(matches!(
+            plane_plane_ex(&xy, &Plane::yz_plane()),
+            IntersectionResult::Segment { .. }
+        ));
+    }
+
+    #[test]
+    fn test_line_sphere_ex() {
+        let center = Point::new(0.0, 0.0, 0.0);
+
+        let tangent = Line::new(-2.0, 1.0, 0.0, 2.0, 1.0, 0.0);
+        match line_sphere_ex(&tangent, &center, 1.0, true) {
+            IntersectionResult::Tangent { point, t0, .. } => {
+                assert!(point.distance(&Point::new(0.0, 1.0, 0.0)) < 1e-9);
+                assert!((t0 - 0.5).abs() < 1e-9);
+            }
+            other => panic!("Expected tangent, got {:?}", other),
+        }
+
+        let through = Line::new(-2.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        match line_sphere_ex(&through, &center, 1.0, true) {
+            IntersectionResult::Segment { t0, .. } => {
+                assert!((t0[0] - 0.5).abs() < 1e-9);
+                assert!((t0[1] - 1.0).abs() < 1e-9);
+            }
+            other => panic!("Expected segment, got {:?}", other),
+        }
+
+        let miss = Line::new(-2.0, 2.0, 0.0, 2.0, 2.0, 0.0);
+        assert!(matches!(
+            line_sphere_ex(&miss, &center, 1.0, true),
+            IntersectionResult::None(NoIntersection::Separate)
+        ));
+    }
 }
@@ -0,0 +1,318 @@
+use crate::AsVec3;
+use crate::Scalar;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Balanced KD-tree over a fixed set of points.
+///
+/// The tree is stored implicitly: each node is the median of its index range,
+/// with the left subtree before it and the right subtree after it. Queries
+/// return indices into the point slice the tree was built from.
+#[derive(Debug, Clone, Default)]
+pub struct KdTree {
+    coords: Vec<[Scalar; 3]>,
+    nodes: Vec<KdNode>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct KdNode {
+    index: usize,
+    axis: usize,
+}
+
+// Max-heap entry holding the current k-th best candidate on top
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance_squared: Scalar,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_squared.total_cmp(&other.distance_squared)
+    }
+}
+
+struct Ray {
+    origin: [Scalar; 3],
+    direction: [Scalar; 3],
+    radius: Scalar,
+}
+
+impl Ray {
+    /// Slab test of the ray against the bounds inflated by the radius.
+    fn reaches(&self, [min, max]: &[[Scalar; 3]; 2]) -> bool {
+        let (mut enter, mut exit) = (0.0 as Scalar, Scalar::INFINITY);
+        for axis in 0..3 {
+            let (lo, hi) = (min[axis] - self.radius, max[axis] + self.radius);
+            let (o, d) = (self.origin[axis], self.direction[axis]);
+            if d == 0.0 {
+                if o < lo || o > hi {
+                    return false;
+                }
+                continue;
+            }
+            let (t0, t1) = ((lo - o) / d, (hi - o) / d);
+            enter = enter.max(t0.min(t1));
+            exit = exit.min(t0.max(t1));
+            if enter > exit {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl KdTree {
+    /// Build a tree from points, splitting each range at the median of its widest axis.
+    pub fn new<P: AsVec3>(points: &[P]) -> Self {
+        let coords: Vec<[Scalar; 3]> = points.iter().map(|p| p.as_vec3().into()).collect();
+        let mut nodes: Vec<KdNode> = (0..coords.len())
+            .map(|index| KdNode { index, axis: 0 })
+            .collect();
+        Self::build(&coords, &mut nodes);
+        KdTree { coords, nodes }
+    }
+
+    fn build(coords: &[[Scalar; 3]], nodes: &mut [KdNode]) {
+        if nodes.len() <= 1 {
+            return;
+        }
+
+        let mut min = [Scalar::INFINITY; 3];
+        let mut max = [Scalar::NEG_INFINITY; 3];
+        for node in nodes.iter() {
+            let p = coords[node.index];
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+        }
+        let axis = (0..3)
+            .max_by(|&a, &b| (max[a] - min[a]).total_cmp(&(max[b] - min[b])))
+            .unwrap_or(0);
+
+        let mid = nodes.len() / 2;
+        nodes.select_nth_unstable_by(mid, |a, b| {
+            coords[a.index][axis].total_cmp(&coords[b.index][axis])
+        });
+        nodes[mid].axis = axis;
+
+        let (left, rest) = nodes.split_at_mut(mid);
+        Self::build(coords, left);
+        Self::build(coords, &mut rest[1..]);
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Find the closest point.
+    ///
+    /// # Returns
+    /// Point index and distance, or None if the tree is empty.
+    pub fn nearest(&self, point: &impl AsVec3) -> Option<(usize, Scalar)> {
+        self.knn(point, 1).into_iter().next()
+    }
+
+    /// Find the `k` closest points.
+    ///
+    /// # Returns
+    /// Point indices and distances sorted from nearest to farthest.
+    pub fn knn(&self, point: &impl AsVec3, k: usize) -> Vec<(usize, Scalar)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let query: [Scalar; 3] = point.as_vec3().into();
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.knn_range(&query, k, 0, self.nodes.len(), &mut heap);
+        Self::sorted(heap.into_vec())
+    }
+
+    /// Find all points within a radius, boundary included.
+    ///
+    /// # Returns
+    /// Point indices and distances sorted from nearest to farthest.
+    pub fn radius_search(&self, point: &impl AsVec3, radius: Scalar) -> Vec<(usize, Scalar)> {
+        let query: [Scalar; 3] = point.as_vec3().into();
+        let mut found = Vec::new();
+        self.radius_range(&query, radius * radius, 0, self.nodes.len(), &mut found);
+        Self::sorted(found)
+    }
+
+    /// Find all points within a radius of a ray, boundary included.
+    ///
+    /// # Arguments
+    /// * `origin` - Ray origin
+    /// * `direction` - Unit ray direction
+    /// * `radius` - Largest distance between a point and the ray
+    ///
+    /// # Returns
+    /// Point indices and ray parameters of their projections onto the ray,
+    /// sorted along the ray. Points behind the origin are skipped.
+    pub fn ray_search(
+        &self,
+        origin: &impl AsVec3,
+        direction: &impl AsVec3,
+        radius: Scalar,
+    ) -> Vec<(usize, Scalar)> {
+        let ray = Ray {
+            origin: origin.as_vec3().into(),
+            direction: direction.as_vec3().into(),
+            radius,
+        };
+        let bounds = [[Scalar::NEG_INFINITY; 3], [Scalar::INFINITY; 3]];
+        let mut found = Vec::new();
+        self.ray_range(&ray, bounds, 0, self.nodes.len(), &mut found);
+        found.sort_by(|a: &(usize, Scalar), b| a.1.total_cmp(&b.1));
+        found
+    }
+
+    fn knn_range(
+        &self,
+        query: &[Scalar; 3],
+        k: usize,
+        lo: usize,
+        hi: usize,
+        heap: &mut BinaryHeap<Candidate>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let node = self.nodes[mid];
+        let p = self.coords[node.index];
+
+        let distance_squared = Self::distance_squared(query, &p);
+        if heap.len() < k {
+            heap.push(Candidate {
+                distance_squared,
+                index: node.index,
+            });
+        } else if heap
+            .peek()
+            .is_some_and(|worst| distance_squared < worst.distance_squared)
+        {
+            heap.pop();
+            heap.push(Candidate {
+                distance_squared,
+                index: node.index,
+            });
+        }
+
+        // Search the side containing the query first, the other only if it can improve
+        let diff = query[node.axis] - p[node.axis];
+        let (near, far) = if diff < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.knn_range(query, k, near.0, near.1, heap);
+        let worst = heap.peek().map_or(Scalar::INFINITY, |c| c.distance_squared);
+        if heap.len() < k || diff * diff < worst {
+            self.knn_range(query, k, far.0, far.1, heap);
+        }
+    }
+
+    fn radius_range(
+        &self,
+        query: &[Scalar; 3],
+        radius_squared: Scalar,
+        lo: usize,
+        hi: usize,
+        found: &mut Vec<Candidate>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let node = self.nodes[mid];
+        let p = self.coords[node.index];
+
+        let distance_squared = Self::distance_squared(query, &p);
+        if distance_squared <= radius_squared {
+            found.push(Candidate {
+                distance_squared,
+                index: node.index,
+            });
+        }
+
+        let diff = query[node.axis] - p[node.axis];
+        if diff <= 0.0 || diff * diff <= radius_squared {
+            self.radius_range(query, radius_squared, lo, mid, found);
+        }
+        if diff >= 0.0 || diff * diff <= radius_squared {
+            self.radius_range(query, radius_squared, mid + 1, hi, found);
+        }
+    }
+
+    fn ray_range(
+        &self,
+        ray: &Ray,
+        bounds: [[Scalar; 3]; 2],
+        lo: usize,
+        hi: usize,
+        found: &mut Vec<(usize, Scalar)>,
+    ) {
+        if lo >= hi || !ray.reaches(&bounds) {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let node = self.nodes[mid];
+        let p = self.coords[node.index];
+
+        let d = [0, 1, 2].map(|axis| p[axis] - ray.origin[axis]);
+        let t = (0..3)
+            .map(|axis| d[axis] * ray.direction[axis])
+            .sum::<Scalar>();
+        let closest = [0, 1, 2].map(|axis| ray.origin[axis] + ray.direction[axis] * t);
+        if t >= 0.0 && Self::distance_squared(&p, &closest) <= ray.radius * ray.radius {
+            found.push((node.index, t));
+        }
+
+        // The children lie on either side of the node along its axis
+        let (mut left, mut right) = (bounds, bounds);
+        left[1][node.axis] = p[node.axis];
+        right[0][node.axis] = p[node.axis];
+        self.ray_range(ray, left, lo, mid, found);
+        self.ray_range(ray, right, mid + 1, hi, found);
+    }
+
+    #[inline(always)]
+    fn distance_squared(a: &[Scalar; 3], b: &[Scalar; 3]) -> Scalar {
+        let dx = a[0] - b[0];
+        let dy = a[1] - b[1];
+        let dz = a[2] - b[2];
+        dx * dx + dy * dy + dz * dz
+    }
+
+    fn sorted(mut candidates: Vec<Candidate>) -> Vec<(usize, Scalar)> {
+        candidates.sort();
+        candidates
+            .into_iter()
+            .map(|c| (c.index, c.distance_squared.sqrt()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "kdtree_test.rs"]
+mod kdtree_test;
@@ -0,0 +1,121 @@
+use super::*;
+use crate::scalar::test_tolerance;
+use crate::Point;
+use rand::{Rng, SeedableRng};
+
+fn random_points(count: usize) -> Vec<Point> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    (0..count)
+        .map(|_| {
+            Point::new(
+                rng.gen_range(-10.0..10.0),
+                rng.gen_range(-10.0..10.0),
+                rng.gen_range(-10.0..10.0),
+            )
+        })
+        .collect()
+}
+
+fn brute_force(points: &[Point], query: &Point) -> Vec<(usize, Scalar)> {
+    let mut result: Vec<(usize, Scalar)> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i, p.distance(query)))
+        .collect();
+    result.sort_by(|a, b| a.1.total_cmp(&b.1));
+    result
+}
+
+#[test]
+fn test_kdtree_empty() {
+    let tree = KdTree::new::<Point>(&[]);
+    assert!(tree.is_empty());
+    assert!(tree.nearest(&Point::new(0.0, 0.0, 0.0)).is_none());
+    assert!(tree.knn(&Point::new(0.0, 0.0, 0.0), 3).is_empty());
+}
+
+#[test]
+fn test_kdtree_nearest_and_knn_match_brute_force() {
+    let points = random_points(500);
+    let tree = KdTree::new(&points);
+    assert_eq!(tree.len(), 500);
+
+    for query in random_points(20)
+        .iter()
+        .map(|p| Point::new(p.x() * 1.2, p.y(), -p.z()))
+    {
+        let expected = brute_force(&points, &query);
+
+        let (index, distance) = tree.nearest(&query).unwrap();
+        assert!((distance - expected[0].1).abs() < test_tolerance(1e-12));
+        assert!((points[index].distance(&query) - distance).abs() < test_tolerance(1e-12));
+
+        let knn = tree.knn(&query, 10);
+        assert_eq!(knn.len(), 10);
+        for (found, wanted) in knn.iter().zip(expected.iter()) {
+            assert!((found.1 - wanted.1).abs() < test_tolerance(1e-12));
+        }
+    }
+
+    let all = tree.knn(&Point::new(0.0, 0.0, 0.0), 1000);
+    assert_eq!(all.len(), 500);
+}
+
+#[test]
+fn test_kdtree_radius_search() {
+    let points = random_points(500);
+    let tree = KdTree::new(&points);
+    let query = Point::new(1.0, -2.0, 0.5);
+
+    let found = tree.radius_search(&query, 4.0);
+    let expected: Vec<(usize, Scalar)> = brute_force(&points, &query)
+        .into_iter()
+        .filter(|(_, d)| *d <= 4.0)
+        .collect();
+    assert_eq!(found.len(), expected.len());
+    assert!(found.windows(2).all(|w| w[0].1 <= w[1].1));
+    for (found, wanted) in found.iter().zip(expected.iter()) {
+        assert!((found.1 - wanted.1).abs() < test_tolerance(1e-12));
+    }
+}
+
+#[test]
+fn test_kdtree_duplicate_points() {
+    let points = vec![Point::new(1.0, 1.0, 1.0); 5];
+    let tree = KdTree::new(&points);
+    let found = tree.radius_search(&Point::new(1.0, 1.0, 1.0), 0.0);
+    assert_eq!(found.len(), 5);
+}
+
+#[test]
+fn test_kdtree_ray_search_matches_brute_force() {
+    let points = random_points(500);
+    let tree = KdTree::new(&points);
+    let origin = crate::Vec3::new(-12.0, 0.5, -1.0);
+    let direction = crate::Vec3::new(1.0, 0.2, 0.1).normalized().unwrap();
+
+    let found = tree.ray_search(&origin, &direction, 1.5);
+    let mut expected: Vec<usize> = points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| {
+            let d = p.as_vec3() - origin;
+            let t = d.dot(&direction);
+            t >= 0.0 && (d - direction * t).length() <= 1.5
+        })
+        .map(|(i, _)| i)
+        .collect();
+    assert!(!expected.is_empty());
+    assert!(found.windows(2).all(|w| w[0].1 <= w[1].1));
+    let mut indices: Vec<usize> = found.iter().map(|&(i, _)| i).collect();
+    indices.sort_unstable();
+    expected.sort_unstable();
+    assert_eq!(indices, expected);
+
+    let behind = tree.ray_search(
+        &crate::Vec3::new(20.0, 0.0, 0.0),
+        &crate::Vec3::new(1.0, 0.0, 0.0),
+        5.0,
+    );
+    assert!(behind.is_empty());
+}
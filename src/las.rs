@@ -0,0 +1,131 @@
+use crate::error::SessionError;
+use crate::{Color, PointCloud, Scalar, Vec3};
+
+/// Bit set in the point data format of LAZ files.
+const LAZ_COMPRESSED: u8 = 0x80;
+
+/// Read a point cloud from a LAS file (versions 1.0 to 1.4) or its LAZ
+/// compressed form.
+///
+/// Points are scaled and offset by the header into world coordinates,
+/// intensities are kept for every point format and colors for the formats
+/// that store them (2, 3, 5, 7, 8 and 10), reduced from 16 to 8 bits.
+/// LAZ files are decompressed for point formats 0 to 3, as written by LASzip
+/// with its pointwise or chunked compressor; the layered compression of
+/// formats 6 to 10 is rejected.
+///
+/// # Arguments
+/// * `filepath` - The path of the LAS file
+///
+/// # Returns
+/// The point cloud, or an error for unreadable files, unsupported point
+/// formats or LAZ compression, and truncated point data.
+pub fn read_las(filepath: &str) -> Result<PointCloud, SessionError> {
+    let data = std::fs::read(filepath)?;
+    let header = |offset: usize, len: usize| {
+        data.get(offset..offset + len)
+            .ok_or_else(|| SessionError::SchemaMismatch("LAS header is truncated".to_string()))
+    };
+    if header(0, 4)? != b"LASF" {
+        return Err(SessionError::SchemaMismatch(
+            "missing LASF signature".to_string(),
+        ));
+    }
+    let u16_at = |offset| header(offset, 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_at = |offset| header(offset, 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()));
+    let f64_at = |offset| header(offset, 8).map(|b| f64::from_le_bytes(b.try_into().unwrap()));
+
+    let header_size = u16_at(94)? as usize;
+    let point_offset = u32_at(96)? as usize;
+    let format = header(104, 1)?[0];
+    let record_length = u16_at(105)? as usize;
+    let mut count = u32_at(107)? as u64;
+    if count == 0 && header_size >= 375 {
+        // LAS 1.4 moved the point count to a 64-bit field
+        count = header(247, 8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))?;
+    }
+    let scale = [f64_at(131)?, f64_at(139)?, f64_at(147)?];
+    let offset = [f64_at(155)?, f64_at(163)?, f64_at(171)?];
+
+    // LAZ writers flag the format with bit 7, and older ones with bit 6 as well
+    let compressed = format & LAZ_COMPRESSED != 0;
+    let format = format & 0x3F;
+    let rgb_offset = match format {
+        0 | 1 | 4 | 6 | 9 => None,
+        2 => Some(20),
+        3 | 5 => Some(28),
+        7 | 8 | 10 => Some(30),
+        _ => {
+            return Err(SessionError::SchemaMismatch(format!(
+                "unsupported LAS point format {format}"
+            )))
+        }
+    };
+    let minimum_length = rgb_offset.map_or(14, |rgb| rgb + 6);
+    if record_length < minimum_length {
+        return Err(SessionError::SchemaMismatch(format!(
+            "LAS point records of {record_length} bytes are too short for format {format}"
+        )));
+    }
+
+    let missing =
+        || SessionError::InvalidGeometry(format!("LAS file holds fewer than {count} points"));
+    let decompressed;
+    let records = if compressed {
+        let count = usize::try_from(count).map_err(|_| missing())?;
+        decompressed = crate::laszip::decompress(
+            &data,
+            header_size,
+            u32_at(100)?,
+            point_offset,
+            record_length,
+            count,
+        )?;
+        &decompressed[..]
+    } else {
+        let end = usize::try_from(count)
+            .ok()
+            .and_then(|count| record_length.checked_mul(count))
+            .and_then(|length| point_offset.checked_add(length))
+            .ok_or_else(missing)?;
+        data.get(point_offset..end).ok_or_else(missing)?
+    };
+    let mut cloud = PointCloud::default();
+    let mut rgb16 = Vec::new();
+    for record in records.chunks_exact(record_length) {
+        let i32_at = |i: usize| i32::from_le_bytes(record[i..i + 4].try_into().unwrap()) as f64;
+        let u16_at = |i: usize| u16::from_le_bytes([record[i], record[i + 1]]);
+        cloud.points.push(Vec3::new(
+            (i32_at(0) * scale[0] + offset[0]) as Scalar,
+            (i32_at(4) * scale[1] + offset[1]) as Scalar,
+            (i32_at(8) * scale[2] + offset[2]) as Scalar,
+        ));
+        cloud.intensities.push(u16_at(12) as Scalar);
+        if let Some(rgb) = rgb_offset {
+            rgb16.push([u16_at(rgb), u16_at(rgb + 2), u16_at(rgb + 4)]);
+        }
+    }
+
+    // The specification asks for 16-bit colors, but many writers store 8-bit values
+    let shift = if rgb16.iter().flatten().any(|&c| c > 255) {
+        8
+    } else {
+        0
+    };
+    cloud.colors = rgb16
+        .iter()
+        .map(|c| {
+            Color::new(
+                (c[0] >> shift) as u8,
+                (c[1] >> shift) as u8,
+                (c[2] >> shift) as u8,
+                255,
+            )
+        })
+        .collect();
+    Ok(cloud)
+}
+
+#[cfg(test)]
+#[path = "las_test.rs"]
+mod las_test;
@@ -0,0 +1,93 @@
+use crate::las::read_las;
+use crate::SessionError;
+
+/// A LAS 1.2 file with point format 2 (XYZ, intensity and 16-bit RGB).
+fn las_bytes(format: u8, points: &[([i32; 3], u16, [u16; 3])]) -> Vec<u8> {
+    let mut data = vec![0u8; 227];
+    data[0..4].copy_from_slice(b"LASF");
+    data[24] = 1;
+    data[25] = 2;
+    data[94..96].copy_from_slice(&227u16.to_le_bytes());
+    data[96..100].copy_from_slice(&227u32.to_le_bytes());
+    data[104] = format;
+    data[105..107].copy_from_slice(&26u16.to_le_bytes());
+    data[107..111].copy_from_slice(&(points.len() as u32).to_le_bytes());
+    for (i, scale) in [0.01f64, 0.01, 0.001].iter().enumerate() {
+        data[131 + 8 * i..139 + 8 * i].copy_from_slice(&scale.to_le_bytes());
+    }
+    for (i, offset) in [1000.0f64, 2000.0, 0.0].iter().enumerate() {
+        data[155 + 8 * i..163 + 8 * i].copy_from_slice(&offset.to_le_bytes());
+    }
+    for (xyz, intensity, rgb) in points {
+        let mut record = vec![0u8; 26];
+        for (i, value) in xyz.iter().enumerate() {
+            record[4 * i..4 * i + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        record[12..14].copy_from_slice(&intensity.to_le_bytes());
+        for (i, value) in rgb.iter().enumerate() {
+            record[20 + 2 * i..22 + 2 * i].copy_from_slice(&value.to_le_bytes());
+        }
+        data.extend(record);
+    }
+    data
+}
+
+fn temp_path() -> String {
+    std::env::temp_dir()
+        .join(format!("scan_{}.las", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn test_read_las_scaled_points_and_colors() {
+    let path = temp_path();
+    let points = [
+        ([150, -250, 1500], 700, [65535, 0, 32768]),
+        ([0, 0, 0], 12, [256, 512, 1024]),
+    ];
+    std::fs::write(&path, las_bytes(2, &points)).unwrap();
+    let cloud = read_las(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(cloud.len(), 2);
+    assert!((cloud.points[0].x - 1001.5).abs() < 1e-9);
+    assert!((cloud.points[0].y - 1997.5).abs() < 1e-9);
+    assert!((cloud.points[0].z - 1.5).abs() < 1e-9);
+    assert_eq!(cloud.intensities, vec![700.0, 12.0]);
+    let c = &cloud.colors[0];
+    assert_eq!([c.r, c.g, c.b], [255, 0, 128]);
+    let c = &cloud.colors[1];
+    assert_eq!([c.r, c.g, c.b], [1, 2, 4]);
+}
+
+#[test]
+fn test_read_las_rejects_malformed_and_truncated_files() {
+    let path = temp_path();
+    // A LAZ file without the LASzip record describing its compression
+    std::fs::write(&path, las_bytes(2 | 0x80, &[])).unwrap();
+    assert!(matches!(
+        read_las(&path),
+        Err(SessionError::SchemaMismatch(_))
+    ));
+
+    let mut data = las_bytes(2, &[([0, 0, 0], 0, [0, 0, 0])]);
+    data.truncate(data.len() - 4);
+    std::fs::write(&path, data).unwrap();
+    assert!(matches!(
+        read_las(&path),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+
+    // A LAS 1.4 point count whose byte length overflows
+    let mut data = las_bytes(2, &[]);
+    data.resize(375, 0);
+    data[94..96].copy_from_slice(&375u16.to_le_bytes());
+    data[247..255].copy_from_slice(&u64::MAX.to_le_bytes());
+    std::fs::write(&path, data).unwrap();
+    assert!(matches!(
+        read_las(&path),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+    std::fs::remove_file(&path).ok();
+}
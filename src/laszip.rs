@@ -0,0 +1,1083 @@
+//! Decompression of LAZ point data as written by LASzip.
+//!
+//! Handles the pointwise and pointwise chunked compressors with version 2
+//! items: POINT10, GPSTIME11, RGB12 and extra BYTE data, which cover point
+//! formats 0 to 3. The layered compressor of point formats 6 to 10 and the
+//! wave packets of formats 4 and 5 are not handled.
+
+use crate::error::SessionError;
+
+const AC_MIN_LENGTH: u32 = 0x0100_0000;
+const AC_MAX_LENGTH: u32 = 0xFFFF_FFFF;
+const BM_LENGTH_SHIFT: u32 = 13;
+const BM_MAX_COUNT: u32 = 1 << BM_LENGTH_SHIFT;
+const DM_LENGTH_SHIFT: u32 = 15;
+const DM_MAX_COUNT: u32 = 1 << DM_LENGTH_SHIFT;
+
+/// User id and record id of the variable length record describing the compression.
+const LASZIP_USER_ID: &[u8] = b"laszip encoded";
+const LASZIP_RECORD_ID: u16 = 22204;
+
+const COMPRESSOR_POINTWISE: u16 = 1;
+const COMPRESSOR_POINTWISE_CHUNKED: u16 = 2;
+
+const ITEM_BYTE: u16 = 0;
+const ITEM_POINT10: u16 = 6;
+const ITEM_GPSTIME11: u16 = 7;
+const ITEM_RGB12: u16 = 8;
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Arithmetic Coding Models
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Adaptive probability of a binary symbol.
+struct BitModel {
+    bit_0_count: u32,
+    bit_count: u32,
+    bit_0_prob: u32,
+    bits_until_update: u32,
+    update_cycle: u32,
+}
+
+impl BitModel {
+    fn new() -> Self {
+        BitModel {
+            bit_0_count: 1,
+            bit_count: 2,
+            bit_0_prob: 1 << (BM_LENGTH_SHIFT - 1),
+            bits_until_update: 4,
+            update_cycle: 4,
+        }
+    }
+
+    /// Count a coded bit and periodically refresh the probability.
+    fn observe(&mut self, bit: u32) {
+        if bit == 0 {
+            self.bit_0_count += 1;
+        }
+        self.bits_until_update -= 1;
+        if self.bits_until_update == 0 {
+            self.update();
+        }
+    }
+
+    fn update(&mut self) {
+        self.bit_count += self.update_cycle;
+        if self.bit_count > BM_MAX_COUNT {
+            self.bit_count = (self.bit_count + 1) >> 1;
+            self.bit_0_count = (self.bit_0_count + 1) >> 1;
+            if self.bit_0_count == self.bit_count {
+                self.bit_count += 1;
+            }
+        }
+        let scale = 0x8000_0000 / self.bit_count;
+        self.bit_0_prob = (self.bit_0_count * scale) >> (31 - BM_LENGTH_SHIFT);
+        self.update_cycle = ((5 * self.update_cycle) >> 2).min(64);
+        self.bits_until_update = self.update_cycle;
+    }
+}
+
+/// Adaptive distribution over `symbols` symbols, with a lookup table to speed
+/// up decoding of larger alphabets.
+struct SymbolModel {
+    symbols: u32,
+    last_symbol: u32,
+    distribution: Vec<u32>,
+    symbol_count: Vec<u32>,
+    decoder_table: Vec<u32>,
+    table_size: u32,
+    table_shift: u32,
+    total_count: u32,
+    update_cycle: u32,
+    symbols_until_update: u32,
+}
+
+impl SymbolModel {
+    fn new(symbols: u32) -> Self {
+        let (table_size, table_shift) = if symbols > 16 {
+            let mut table_bits = 3;
+            while symbols > (1 << (table_bits + 2)) {
+                table_bits += 1;
+            }
+            (1 << table_bits, DM_LENGTH_SHIFT - table_bits)
+        } else {
+            (0, 0)
+        };
+        let mut model = SymbolModel {
+            symbols,
+            last_symbol: symbols - 1,
+            distribution: vec![0; symbols as usize],
+            symbol_count: vec![1; symbols as usize],
+            decoder_table: if table_size > 0 {
+                vec![0; table_size as usize + 2]
+            } else {
+                Vec::new()
+            },
+            table_size,
+            table_shift,
+            total_count: 0,
+            update_cycle: symbols,
+            symbols_until_update: 0,
+        };
+        model.update();
+        model.update_cycle = (symbols + 6) >> 1;
+        model.symbols_until_update = model.update_cycle;
+        model
+    }
+
+    /// Count a coded symbol and periodically refresh the distribution.
+    fn observe(&mut self, symbol: u32) {
+        self.symbol_count[symbol as usize] += 1;
+        self.symbols_until_update -= 1;
+        if self.symbols_until_update == 0 {
+            self.update();
+        }
+    }
+
+    fn update(&mut self) {
+        self.total_count += self.update_cycle;
+        if self.total_count > DM_MAX_COUNT {
+            self.total_count = 0;
+            for count in self.symbol_count.iter_mut() {
+                *count = (*count + 1) >> 1;
+                self.total_count += *count;
+            }
+        }
+
+        let scale = 0x8000_0000 / self.total_count;
+        let mut sum = 0u32;
+        let mut s = 0u32;
+        for k in 0..self.symbols {
+            self.distribution[k as usize] = (scale * sum) >> (31 - DM_LENGTH_SHIFT);
+            sum += self.symbol_count[k as usize];
+            if self.table_size > 0 {
+                let w = self.distribution[k as usize] >> self.table_shift;
+                while s < w {
+                    s += 1;
+                    self.decoder_table[s as usize] = k - 1;
+                }
+            }
+        }
+        if self.table_size > 0 {
+            self.decoder_table[0] = 0;
+            while s <= self.table_size {
+                s += 1;
+                self.decoder_table[s as usize] = self.symbols - 1;
+            }
+        }
+
+        self.update_cycle = ((5 * self.update_cycle) >> 2).min((self.symbols + 6) << 3);
+        self.symbols_until_update = self.update_cycle;
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Arithmetic Decoder
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Arithmetic decoder over a byte slice. Reads past the end yield zeros and
+/// set `overrun`, so truncated data is reported once per chunk.
+struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    value: u32,
+    length: u32,
+    overrun: bool,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8], position: usize) -> Self {
+        let mut decoder = Decoder {
+            data,
+            position,
+            value: 0,
+            length: AC_MAX_LENGTH,
+            overrun: false,
+        };
+        for _ in 0..4 {
+            decoder.value = (decoder.value << 8) | decoder.byte();
+        }
+        decoder
+    }
+
+    fn byte(&mut self) -> u32 {
+        match self.data.get(self.position) {
+            Some(&b) => {
+                self.position += 1;
+                b as u32
+            }
+            None => {
+                self.overrun = true;
+                0
+            }
+        }
+    }
+
+    fn renormalize(&mut self) {
+        while self.length < AC_MIN_LENGTH {
+            self.value = (self.value << 8) | self.byte();
+            self.length <<= 8;
+        }
+    }
+
+    fn decode_bit(&mut self, model: &mut BitModel) -> u32 {
+        let x = model.bit_0_prob * (self.length >> BM_LENGTH_SHIFT);
+        let bit = (self.value >= x) as u32;
+        if bit == 0 {
+            self.length = x;
+        } else {
+            self.value = self.value.wrapping_sub(x);
+            self.length = self.length.wrapping_sub(x);
+        }
+        self.renormalize();
+        model.observe(bit);
+        bit
+    }
+
+    fn decode_symbol(&mut self, model: &mut SymbolModel) -> u32 {
+        let mut y = self.length;
+        let mut symbol = 0;
+        let x;
+        self.length >>= DM_LENGTH_SHIFT;
+        if model.table_size > 0 {
+            // Start from the table's interval and bisect within it
+            let dv = self.value / self.length;
+            let t = ((dv >> model.table_shift) as usize).min(model.table_size as usize);
+            symbol = model.decoder_table[t];
+            let mut n = model.decoder_table[t + 1] + 1;
+            while n > symbol + 1 {
+                let k = (symbol + n) >> 1;
+                if model.distribution[k as usize] > dv {
+                    n = k;
+                } else {
+                    symbol = k;
+                }
+            }
+            x = model.distribution[symbol as usize].wrapping_mul(self.length);
+            if symbol != model.last_symbol {
+                y = model.distribution[symbol as usize + 1].wrapping_mul(self.length);
+            }
+        } else {
+            let mut low = 0;
+            let mut n = model.symbols;
+            let mut k = n >> 1;
+            loop {
+                let z = self.length.wrapping_mul(model.distribution[k as usize]);
+                if z > self.value {
+                    n = k;
+                    y = z;
+                } else {
+                    symbol = k;
+                    low = z;
+                }
+                k = (symbol + n) >> 1;
+                if k == symbol {
+                    break;
+                }
+            }
+            x = low;
+        }
+        self.value = self.value.wrapping_sub(x);
+        self.length = y.wrapping_sub(x);
+        self.renormalize();
+        model.observe(symbol);
+        symbol
+    }
+
+    fn read_bits(&mut self, bits: u32) -> u32 {
+        if bits > 19 {
+            let lower = self.read_short();
+            let upper = self.read_bits(bits - 16);
+            return (upper << 16) | lower;
+        }
+        self.length >>= bits;
+        let symbol = self.value / self.length;
+        self.value = self.value.wrapping_sub(self.length.wrapping_mul(symbol));
+        self.renormalize();
+        symbol
+    }
+
+    fn read_short(&mut self) -> u32 {
+        self.read_bits(16)
+    }
+
+    fn read_int(&mut self) -> u32 {
+        let lower = self.read_short();
+        let upper = self.read_short();
+        (upper << 16) | lower
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Integer Prediction Residuals
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Residuals of predicted integers, coded as a bit length `k` followed by the
+/// value within the interval of that length.
+struct IntegerCompressor {
+    bits_high: u32,
+    corr_range: u32,
+    corr_min: i32,
+    /// Bit length of the last residual
+    k: u32,
+    bits: Vec<SymbolModel>,
+    corrector_0: BitModel,
+    /// Models of residuals with bit length 1 to `corr_bits`
+    corrector: Vec<SymbolModel>,
+}
+
+impl IntegerCompressor {
+    fn new(bits: u32, contexts: u32) -> Self {
+        let bits_high = 8;
+        let (corr_bits, corr_range, corr_min) = if bits > 0 && bits < 32 {
+            let range = 1u32 << bits;
+            (bits, range, -((range / 2) as i32))
+        } else {
+            (32, 0, i32::MIN)
+        };
+        IntegerCompressor {
+            bits_high,
+            corr_range,
+            corr_min,
+            k: 0,
+            bits: (0..contexts)
+                .map(|_| SymbolModel::new(corr_bits + 1))
+                .collect(),
+            corrector_0: BitModel::new(),
+            corrector: (1..=corr_bits)
+                .map(|i| SymbolModel::new(1 << i.min(bits_high)))
+                .collect(),
+        }
+    }
+
+    fn decompress(&mut self, decoder: &mut Decoder, prediction: i32, context: u32) -> i32 {
+        let real = prediction.wrapping_add(self.read_corrector(decoder, context));
+        if self.corr_range == 0 {
+            real
+        } else if real < 0 {
+            real.wrapping_add(self.corr_range as i32)
+        } else if real as u32 >= self.corr_range {
+            real.wrapping_sub(self.corr_range as i32)
+        } else {
+            real
+        }
+    }
+
+    fn read_corrector(&mut self, decoder: &mut Decoder, context: u32) -> i32 {
+        self.k = decoder.decode_symbol(&mut self.bits[context as usize]);
+        let k = self.k;
+        if k == 0 {
+            return decoder.decode_bit(&mut self.corrector_0) as i32;
+        }
+        if k >= 32 {
+            return self.corr_min;
+        }
+        let model = &mut self.corrector[k as usize - 1];
+        let c = if k <= self.bits_high {
+            decoder.decode_symbol(model)
+        } else {
+            let k1 = k - self.bits_high;
+            let high = decoder.decode_symbol(model);
+            (high << k1) | decoder.read_bits(k1)
+        } as i64;
+        // Map [0, 2^k - 1] back onto [-(2^k - 1), -2^(k-1)] and [2^(k-1) + 1, 2^k]
+        if c >= 1 << (k - 1) {
+            (c + 1) as i32
+        } else {
+            (c - ((1 << k) - 1)) as i32
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Point Items
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Context of each return number (column) for a number of returns (row).
+const NUMBER_RETURN_MAP: [[usize; 8]; 8] = [
+    [15, 14, 13, 12, 11, 10, 9, 8],
+    [14, 0, 1, 3, 6, 10, 10, 9],
+    [13, 1, 2, 4, 7, 11, 11, 10],
+    [12, 3, 4, 5, 8, 12, 12, 11],
+    [11, 6, 7, 8, 9, 13, 13, 12],
+    [10, 10, 11, 12, 13, 14, 14, 13],
+    [9, 10, 11, 12, 13, 14, 15, 14],
+    [8, 9, 10, 11, 12, 13, 14, 15],
+];
+
+/// Distance of each return number (column) from the number of returns (row).
+const NUMBER_RETURN_LEVEL: [[usize; 8]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [1, 0, 1, 2, 3, 4, 5, 6],
+    [2, 1, 0, 1, 2, 3, 4, 5],
+    [3, 2, 1, 0, 1, 2, 3, 4],
+    [4, 3, 2, 1, 0, 1, 2, 3],
+    [5, 4, 3, 2, 1, 0, 1, 2],
+    [6, 5, 4, 3, 2, 1, 0, 1],
+    [7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+/// Median of the last five values, updated in constant time.
+#[derive(Clone, Copy)]
+struct StreamingMedian5 {
+    values: [i32; 5],
+    high: bool,
+}
+
+impl StreamingMedian5 {
+    fn new() -> Self {
+        StreamingMedian5 {
+            values: [0; 5],
+            high: true,
+        }
+    }
+
+    fn add(&mut self, v: i32) {
+        let values = &mut self.values;
+        if self.high {
+            if v < values[2] {
+                values[4] = values[3];
+                values[3] = values[2];
+                if v < values[0] {
+                    values[2] = values[1];
+                    values[1] = values[0];
+                    values[0] = v;
+                } else if v < values[1] {
+                    values[2] = values[1];
+                    values[1] = v;
+                } else {
+                    values[2] = v;
+                }
+            } else {
+                if v < values[3] {
+                    values[4] = values[3];
+                    values[3] = v;
+                } else {
+                    values[4] = v;
+                }
+                self.high = false;
+            }
+        } else if values[2] < v {
+            values[0] = values[1];
+            values[1] = values[2];
+            if values[4] < v {
+                values[2] = values[3];
+                values[3] = values[4];
+                values[4] = v;
+            } else if values[3] < v {
+                values[2] = values[3];
+                values[3] = v;
+            } else {
+                values[2] = v;
+            }
+        } else {
+            if values[1] < v {
+                values[0] = values[1];
+                values[1] = v;
+            } else {
+                values[0] = v;
+            }
+            self.high = true;
+        }
+    }
+
+    fn get(&self) -> i32 {
+        self.values[2]
+    }
+}
+
+fn i32_at(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+/// Core fields of point formats 0 to 5: coordinates, intensity, return and
+/// classification bytes, scan angle, user data and point source id.
+struct Point10 {
+    last: [u8; 20],
+    last_intensity: [u16; 16],
+    last_x_diff: [StreamingMedian5; 16],
+    last_y_diff: [StreamingMedian5; 16],
+    last_height: [i32; 8],
+    changed_values: SymbolModel,
+    scan_angle_rank: [SymbolModel; 2],
+    bit_byte: Vec<Option<SymbolModel>>,
+    classification: Vec<Option<SymbolModel>>,
+    user_data: Vec<Option<SymbolModel>>,
+    ic_intensity: IntegerCompressor,
+    ic_point_source_id: IntegerCompressor,
+    ic_dx: IntegerCompressor,
+    ic_dy: IntegerCompressor,
+    ic_z: IntegerCompressor,
+}
+
+impl Point10 {
+    fn new(first: &[u8]) -> Self {
+        let mut last = [0u8; 20];
+        last.copy_from_slice(first);
+        // The intensity is predicted per return context, starting from zero
+        last[12] = 0;
+        last[13] = 0;
+        Point10 {
+            last,
+            last_intensity: [0; 16],
+            last_x_diff: [StreamingMedian5::new(); 16],
+            last_y_diff: [StreamingMedian5::new(); 16],
+            last_height: [0; 8],
+            changed_values: SymbolModel::new(64),
+            scan_angle_rank: [SymbolModel::new(256), SymbolModel::new(256)],
+            bit_byte: (0..256).map(|_| None).collect(),
+            classification: (0..256).map(|_| None).collect(),
+            user_data: (0..256).map(|_| None).collect(),
+            ic_intensity: IntegerCompressor::new(16, 4),
+            ic_point_source_id: IntegerCompressor::new(16, 1),
+            ic_dx: IntegerCompressor::new(32, 2),
+            ic_dy: IntegerCompressor::new(32, 22),
+            ic_z: IntegerCompressor::new(32, 20),
+        }
+    }
+
+    /// Decode a byte with the model selected by its previous value.
+    fn decode_byte(decoder: &mut Decoder, models: &mut [Option<SymbolModel>], last: u8) -> u8 {
+        let model = models[last as usize].get_or_insert_with(|| SymbolModel::new(256));
+        decoder.decode_symbol(model) as u8
+    }
+
+    fn read(&mut self, decoder: &mut Decoder, item: &mut [u8]) {
+        let last = &mut self.last;
+        let changed = decoder.decode_symbol(&mut self.changed_values);
+        if changed & 32 != 0 {
+            last[14] = Self::decode_byte(decoder, &mut self.bit_byte, last[14]);
+        }
+
+        let r = (last[14] & 7) as usize;
+        let n = ((last[14] >> 3) & 7) as usize;
+        let m = NUMBER_RETURN_MAP[n][r];
+        let l = NUMBER_RETURN_LEVEL[n][r];
+
+        if changed != 0 {
+            if changed & 16 != 0 {
+                let context = m.min(3) as u32;
+                let intensity =
+                    self.ic_intensity
+                        .decompress(decoder, self.last_intensity[m] as i32, context)
+                        as u16;
+                self.last_intensity[m] = intensity;
+            }
+            last[12..14].copy_from_slice(&self.last_intensity[m].to_le_bytes());
+            if changed & 8 != 0 {
+                last[15] = Self::decode_byte(decoder, &mut self.classification, last[15]);
+            }
+            if changed & 4 != 0 {
+                let direction = ((last[14] >> 6) & 1) as usize;
+                let delta = decoder.decode_symbol(&mut self.scan_angle_rank[direction]);
+                last[16] = last[16].wrapping_add(delta as u8);
+            }
+            if changed & 2 != 0 {
+                last[17] = Self::decode_byte(decoder, &mut self.user_data, last[17]);
+            }
+            if changed & 1 != 0 {
+                let id = u16_at(last, 18) as i32;
+                let id = self.ic_point_source_id.decompress(decoder, id, 0) as u16;
+                last[18..20].copy_from_slice(&id.to_le_bytes());
+            }
+        }
+
+        let single = (n == 1) as u32;
+        let diff = self
+            .ic_dx
+            .decompress(decoder, self.last_x_diff[m].get(), single);
+        let x = i32_at(last, 0).wrapping_add(diff);
+        last[0..4].copy_from_slice(&x.to_le_bytes());
+        self.last_x_diff[m].add(diff);
+
+        let k = self.ic_dx.k;
+        let context = single + if k < 20 { k & !1 } else { 20 };
+        let diff = self
+            .ic_dy
+            .decompress(decoder, self.last_y_diff[m].get(), context);
+        let y = i32_at(last, 4).wrapping_add(diff);
+        last[4..8].copy_from_slice(&y.to_le_bytes());
+        self.last_y_diff[m].add(diff);
+
+        let k = (self.ic_dx.k + self.ic_dy.k) / 2;
+        let context = single + if k < 18 { k & !1 } else { 18 };
+        let z = self.ic_z.decompress(decoder, self.last_height[l], context);
+        last[8..12].copy_from_slice(&z.to_le_bytes());
+        self.last_height[l] = z;
+
+        item.copy_from_slice(last);
+    }
+}
+
+/// GPS time, predicted from up to four interleaved sequences of regularly
+/// spaced times.
+struct GpsTime11 {
+    last: usize,
+    next: usize,
+    last_time: [i64; 4],
+    last_diff: [i32; 4],
+    multi_extreme_counter: [i32; 4],
+    multi: SymbolModel,
+    zero_diff: SymbolModel,
+    ic_gpstime: IntegerCompressor,
+}
+
+const GPSTIME_MULTI: i32 = 500;
+const GPSTIME_MULTI_MINUS: i32 = -10;
+const GPSTIME_MULTI_UNCHANGED: i32 = GPSTIME_MULTI - GPSTIME_MULTI_MINUS + 1;
+const GPSTIME_MULTI_CODE_FULL: i32 = GPSTIME_MULTI - GPSTIME_MULTI_MINUS + 2;
+const GPSTIME_MULTI_TOTAL: u32 = (GPSTIME_MULTI - GPSTIME_MULTI_MINUS + 6) as u32;
+
+impl GpsTime11 {
+    fn new(first: &[u8]) -> Self {
+        GpsTime11 {
+            last: 0,
+            next: 0,
+            last_time: [i64::from_le_bytes(first.try_into().unwrap()), 0, 0, 0],
+            last_diff: [0; 4],
+            multi_extreme_counter: [0; 4],
+            multi: SymbolModel::new(GPSTIME_MULTI_TOTAL),
+            zero_diff: SymbolModel::new(6),
+            ic_gpstime: IntegerCompressor::new(32, 9),
+        }
+    }
+
+    /// Start a new sequence from a time coded in full.
+    fn read_full(&mut self, decoder: &mut Decoder) {
+        self.next = (self.next + 1) & 3;
+        let high_prediction = (self.last_time[self.last] as u64 >> 32) as i32;
+        let high = self.ic_gpstime.decompress(decoder, high_prediction, 8) as u32 as u64;
+        let low = decoder.read_int() as u64;
+        self.last = self.next;
+        self.last_time[self.last] = ((high << 32) | low) as i64;
+        self.last_diff[self.last] = 0;
+        self.multi_extreme_counter[self.last] = 0;
+    }
+
+    /// Decode a difference that replaces the sequence's spacing after repeated outliers.
+    fn read_extreme(&mut self, decoder: &mut Decoder, prediction: i32, context: u32) -> i32 {
+        let diff = self.ic_gpstime.decompress(decoder, prediction, context);
+        let last = self.last;
+        self.multi_extreme_counter[last] += 1;
+        if self.multi_extreme_counter[last] > 3 {
+            self.last_diff[last] = diff;
+            self.multi_extreme_counter[last] = 0;
+        }
+        diff
+    }
+
+    fn read(&mut self, decoder: &mut Decoder, item: &mut [u8]) {
+        loop {
+            let last = self.last;
+            if self.last_diff[last] == 0 {
+                let multi = decoder.decode_symbol(&mut self.zero_diff) as i32;
+                match multi {
+                    0 => {}
+                    1 => {
+                        let diff = self.ic_gpstime.decompress(decoder, 0, 0);
+                        self.last_diff[last] = diff;
+                        self.last_time[last] = self.last_time[last].wrapping_add(diff as i64);
+                        self.multi_extreme_counter[last] = 0;
+                    }
+                    2 => self.read_full(decoder),
+                    _ => {
+                        // Switch to another sequence and decode again
+                        self.last = (last + multi as usize - 2) & 3;
+                        continue;
+                    }
+                }
+            } else {
+                let multi = decoder.decode_symbol(&mut self.multi) as i32;
+                let spacing = self.last_diff[last];
+                if multi == 1 {
+                    let diff = self.ic_gpstime.decompress(decoder, spacing, 1);
+                    self.last_time[last] = self.last_time[last].wrapping_add(diff as i64);
+                    self.multi_extreme_counter[last] = 0;
+                } else if multi < GPSTIME_MULTI_UNCHANGED {
+                    let diff = if multi == 0 {
+                        self.read_extreme(decoder, 0, 7)
+                    } else if multi < GPSTIME_MULTI {
+                        let context = if multi < 10 { 2 } else { 3 };
+                        self.ic_gpstime
+                            .decompress(decoder, multi.wrapping_mul(spacing), context)
+                    } else if multi == GPSTIME_MULTI {
+                        self.read_extreme(decoder, GPSTIME_MULTI.wrapping_mul(spacing), 4)
+                    } else {
+                        let multi = GPSTIME_MULTI - multi;
+                        if multi > GPSTIME_MULTI_MINUS {
+                            self.ic_gpstime
+                                .decompress(decoder, multi.wrapping_mul(spacing), 5)
+                        } else {
+                            let prediction = GPSTIME_MULTI_MINUS.wrapping_mul(spacing);
+                            self.read_extreme(decoder, prediction, 6)
+                        }
+                    };
+                    self.last_time[last] = self.last_time[last].wrapping_add(diff as i64);
+                } else if multi == GPSTIME_MULTI_CODE_FULL {
+                    self.read_full(decoder);
+                } else if multi > GPSTIME_MULTI_CODE_FULL {
+                    self.last = (last + (multi - GPSTIME_MULTI_CODE_FULL) as usize) & 3;
+                    continue;
+                }
+            }
+            break;
+        }
+        item.copy_from_slice(&self.last_time[self.last].to_le_bytes());
+    }
+}
+
+/// 16-bit red, green and blue, with the low and high bytes of green and blue
+/// predicted from the change in red.
+struct Rgb12 {
+    last: [u16; 3],
+    byte_used: SymbolModel,
+    diff: [SymbolModel; 6],
+}
+
+impl Rgb12 {
+    fn new(first: &[u8]) -> Self {
+        Rgb12 {
+            last: [u16_at(first, 0), u16_at(first, 2), u16_at(first, 4)],
+            byte_used: SymbolModel::new(128),
+            diff: core::array::from_fn(|_| SymbolModel::new(256)),
+        }
+    }
+
+    fn read(&mut self, decoder: &mut Decoder, item: &mut [u8]) {
+        let last = self.last;
+        let mut rgb = [0u16; 3];
+        let sym = decoder.decode_symbol(&mut self.byte_used);
+        let clamp = |v: i32| v.clamp(0, 255) as u8;
+
+        // Fold a coded byte onto its prediction
+        let mut byte = |index: usize, prediction: u8, low: bool| -> u16 {
+            let corr = decoder.decode_symbol(&mut self.diff[index]) as u8;
+            let value = corr.wrapping_add(prediction) as u16;
+            if low {
+                value
+            } else {
+                value << 8
+            }
+        };
+
+        rgb[0] = if sym & 1 != 0 {
+            byte(0, last[0] as u8, true)
+        } else {
+            last[0] & 0xFF
+        };
+        rgb[0] |= if sym & 2 != 0 {
+            byte(1, (last[0] >> 8) as u8, false)
+        } else {
+            last[0] & 0xFF00
+        };
+
+        if sym & 64 != 0 {
+            let mut diff = (rgb[0] & 0xFF) as i32 - (last[0] & 0xFF) as i32;
+            rgb[1] = if sym & 4 != 0 {
+                byte(2, clamp(diff + (last[1] & 0xFF) as i32), true)
+            } else {
+                last[1] & 0xFF
+            };
+            rgb[2] = if sym & 16 != 0 {
+                diff = (diff + (rgb[1] & 0xFF) as i32 - (last[1] & 0xFF) as i32) / 2;
+                byte(4, clamp(diff + (last[2] & 0xFF) as i32), true)
+            } else {
+                last[2] & 0xFF
+            };
+
+            let mut diff = (rgb[0] >> 8) as i32 - (last[0] >> 8) as i32;
+            rgb[1] |= if sym & 8 != 0 {
+                byte(3, clamp(diff + (last[1] >> 8) as i32), false)
+            } else {
+                last[1] & 0xFF00
+            };
+            rgb[2] |= if sym & 32 != 0 {
+                diff = (diff + (rgb[1] >> 8) as i32 - (last[1] >> 8) as i32) / 2;
+                byte(5, clamp(diff + (last[2] >> 8) as i32), false)
+            } else {
+                last[2] & 0xFF00
+            };
+        } else {
+            rgb[1] = rgb[0];
+            rgb[2] = rgb[0];
+        }
+
+        self.last = rgb;
+        for (chunk, value) in item.chunks_exact_mut(2).zip(rgb) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+/// Extra bytes, each predicted from its previous value.
+struct Bytes {
+    last: Vec<u8>,
+    models: Vec<SymbolModel>,
+}
+
+impl Bytes {
+    fn new(first: &[u8]) -> Self {
+        Bytes {
+            last: first.to_vec(),
+            models: first.iter().map(|_| SymbolModel::new(256)).collect(),
+        }
+    }
+
+    fn read(&mut self, decoder: &mut Decoder, item: &mut [u8]) {
+        for ((value, model), out) in self.last.iter_mut().zip(&mut self.models).zip(item) {
+            *value = value.wrapping_add(decoder.decode_symbol(model) as u8);
+            *out = *value;
+        }
+    }
+}
+
+enum ItemDecoder {
+    Point10(Box<Point10>),
+    GpsTime11(Box<GpsTime11>),
+    Rgb12(Box<Rgb12>),
+    Bytes(Bytes),
+}
+
+impl ItemDecoder {
+    fn new(kind: u16, first: &[u8]) -> Self {
+        match kind {
+            ITEM_POINT10 => ItemDecoder::Point10(Box::new(Point10::new(first))),
+            ITEM_GPSTIME11 => ItemDecoder::GpsTime11(Box::new(GpsTime11::new(first))),
+            ITEM_RGB12 => ItemDecoder::Rgb12(Box::new(Rgb12::new(first))),
+            _ => ItemDecoder::Bytes(Bytes::new(first)),
+        }
+    }
+
+    fn read(&mut self, decoder: &mut Decoder, item: &mut [u8]) {
+        match self {
+            ItemDecoder::Point10(point) => point.read(decoder, item),
+            ItemDecoder::GpsTime11(time) => time.read(decoder, item),
+            ItemDecoder::Rgb12(rgb) => rgb.read(decoder, item),
+            ItemDecoder::Bytes(bytes) => bytes.read(decoder, item),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Chunks
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Layout of the compressed point data, from the LASzip variable length record.
+struct LaszipVlr {
+    compressor: u16,
+    chunk_size: u32,
+    /// Type and byte size of each item of a point record
+    items: Vec<(u16, usize)>,
+}
+
+fn schema_error(message: &str) -> SessionError {
+    SessionError::SchemaMismatch(message.to_string())
+}
+
+fn bytes_at(data: &[u8], offset: usize, len: usize) -> Result<&[u8], SessionError> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| SessionError::InvalidGeometry("LAZ data is truncated".to_string()))
+}
+
+impl LaszipVlr {
+    /// Find and parse the LASzip record among the variable length records after the header.
+    fn find(data: &[u8], header_size: usize, vlr_count: u32) -> Result<Self, SessionError> {
+        let mut offset = header_size;
+        for _ in 0..vlr_count {
+            let header = bytes_at(data, offset, 54)?;
+            let user_id = &header[2..18];
+            let record_id = u16_at(header, 18);
+            let length = u16_at(header, 20) as usize;
+            let payload = bytes_at(data, offset + 54, length)?;
+            if user_id.starts_with(LASZIP_USER_ID) && record_id == LASZIP_RECORD_ID {
+                return Self::parse(payload);
+            }
+            offset += 54 + length;
+        }
+        Err(schema_error("LAZ file has no LASzip record"))
+    }
+
+    fn parse(payload: &[u8]) -> Result<Self, SessionError> {
+        let short = || schema_error("LASzip record is truncated");
+        let fixed = payload.get(..34).ok_or_else(short)?;
+        let compressor = u16_at(fixed, 0);
+        let coder = u16_at(fixed, 2);
+        let chunk_size = u32::from_le_bytes(fixed[12..16].try_into().unwrap());
+        let item_count = u16_at(fixed, 32) as usize;
+        let items = payload.get(34..34 + 6 * item_count).ok_or_else(short)?;
+
+        if coder != 0 {
+            return Err(schema_error("unsupported LASzip coder"));
+        }
+        if compressor != COMPRESSOR_POINTWISE && compressor != COMPRESSOR_POINTWISE_CHUNKED {
+            return Err(SessionError::SchemaMismatch(format!(
+                "unsupported LASzip compressor {compressor}, only point formats 0 to 3 are read"
+            )));
+        }
+        let items = items
+            .chunks_exact(6)
+            .map(|item| {
+                let (kind, size, version) = (u16_at(item, 0), u16_at(item, 2), u16_at(item, 4));
+                let expected = match kind {
+                    ITEM_POINT10 => Some(20),
+                    ITEM_GPSTIME11 => Some(8),
+                    ITEM_RGB12 => Some(6),
+                    ITEM_BYTE => None,
+                    _ => {
+                        return Err(SessionError::SchemaMismatch(format!(
+                            "unsupported LASzip item type {kind}"
+                        )))
+                    }
+                };
+                if version != 2 || expected.is_some_and(|e| e != size) || size == 0 {
+                    return Err(SessionError::SchemaMismatch(format!(
+                        "unsupported LASzip item type {kind} version {version} of {size} bytes"
+                    )));
+                }
+                Ok((kind, size as usize))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(LaszipVlr {
+            compressor,
+            chunk_size,
+            items,
+        })
+    }
+
+    /// Start offset and point count of every chunk.
+    fn chunks(
+        &self,
+        data: &[u8],
+        point_offset: usize,
+        count: usize,
+    ) -> Result<Vec<(usize, usize)>, SessionError> {
+        if self.compressor == COMPRESSOR_POINTWISE {
+            return Ok(vec![(point_offset, count)]);
+        }
+
+        // The chunk table offset precedes the chunks, or ends the file when the
+        // writer could not seek back to fill it in
+        let mut table = i64::from_le_bytes(bytes_at(data, point_offset, 8)?.try_into().unwrap());
+        if table == -1 {
+            let end = data
+                .len()
+                .checked_sub(8)
+                .ok_or_else(|| schema_error("LAZ file is truncated"))?;
+            table = i64::from_le_bytes(bytes_at(data, end, 8)?.try_into().unwrap());
+        }
+        let table = usize::try_from(table).map_err(|_| schema_error("invalid LAZ chunk table"))?;
+        let header = bytes_at(data, table, 8)?;
+        if u32::from_le_bytes(header[0..4].try_into().unwrap()) != 0 {
+            return Err(schema_error("unsupported LAZ chunk table version"));
+        }
+        let chunk_count = u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize;
+
+        // Sizes and, for variable chunks, point counts are coded as differences
+        // to the previous chunk
+        let variable = self.chunk_size == u32::MAX;
+        let mut decoder = Decoder::new(data, table + 8);
+        let mut ic = IntegerCompressor::new(32, 2);
+        let mut chunks = Vec::with_capacity(chunk_count.min(count));
+        let (mut start, mut remaining) = (point_offset + 8, count);
+        let (mut last_total, mut last_size) = (0i32, 0i32);
+        for _ in 0..chunk_count {
+            if remaining == 0 {
+                break;
+            }
+            let points = if variable {
+                last_total = ic.decompress(&mut decoder, last_total, 0);
+                last_total as u32 as usize
+            } else {
+                self.chunk_size as usize
+            };
+            last_size = ic.decompress(&mut decoder, last_size, 1);
+            let points = points.min(remaining);
+            chunks.push((start, points));
+            remaining -= points;
+            start = start
+                .checked_add(last_size as u32 as usize)
+                .ok_or_else(|| schema_error("invalid LAZ chunk table"))?;
+        }
+        if decoder.overrun {
+            return Err(schema_error("LAZ chunk table is truncated"));
+        }
+        Ok(chunks)
+    }
+}
+
+/// Decompress the point records of a LAZ file into uncompressed LAS records.
+///
+/// # Arguments
+/// * `data` - The whole file
+/// * `header_size` - Size of the LAS header, where the variable length records start
+/// * `vlr_count` - Number of variable length records
+/// * `point_offset` - Offset of the compressed point data
+/// * `record_length` - Size of an uncompressed point record
+/// * `count` - Number of points
+///
+/// # Returns
+/// `count` records of `record_length` bytes, or an error for unsupported
+/// compression and truncated data.
+pub(crate) fn decompress(
+    data: &[u8],
+    header_size: usize,
+    vlr_count: u32,
+    point_offset: usize,
+    record_length: usize,
+    count: usize,
+) -> Result<Vec<u8>, SessionError> {
+    let vlr = LaszipVlr::find(data, header_size, vlr_count)?;
+    if vlr.items.iter().map(|&(_, size)| size).sum::<usize>() != record_length {
+        return Err(schema_error(
+            "LASzip items do not match the point record length",
+        ));
+    }
+    let chunks = vlr.chunks(data, point_offset, count)?;
+    if chunks.iter().map(|&(_, points)| points).sum::<usize>() != count {
+        return Err(SessionError::InvalidGeometry(format!(
+            "LAZ file holds fewer than {count} points"
+        )));
+    }
+
+    let mut records = Vec::with_capacity(record_length.saturating_mul(count));
+    let mut record = vec![0u8; record_length];
+    for (start, points) in chunks {
+        // The first point of a chunk is stored raw and seeds the predictions
+        let first = bytes_at(data, start, record_length)?;
+        records.extend_from_slice(first);
+        let mut offset = 0;
+        let mut items: Vec<(ItemDecoder, usize, usize)> = Vec::new();
+        for &(kind, size) in &vlr.items {
+            items.push((
+                ItemDecoder::new(kind, &first[offset..offset + size]),
+                offset,
+                size,
+            ));
+            offset += size;
+        }
+
+        let mut decoder = Decoder::new(data, start + record_length);
+        for _ in 1..points {
+            for (item, offset, size) in items.iter_mut() {
+                item.read(&mut decoder, &mut record[*offset..*offset + *size]);
+            }
+            records.extend_from_slice(&record);
+        }
+        if decoder.overrun {
+            return Err(SessionError::InvalidGeometry(
+                "LAZ point data is truncated".to_string(),
+            ));
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+#[path = "laszip_test.rs"]
+mod laszip_test;
@@ -0,0 +1,596 @@
+use super::*;
+use crate::las::read_las;
+use crate::Scalar;
+use rand::{Rng, SeedableRng};
+
+/// Arithmetic encoder mirroring LASzip, to produce LAZ data for the decoder.
+struct Encoder {
+    out: Vec<u8>,
+    base: u32,
+    length: u32,
+}
+
+impl Encoder {
+    fn new() -> Self {
+        Encoder {
+            out: Vec::new(),
+            base: 0,
+            length: AC_MAX_LENGTH,
+        }
+    }
+
+    fn propagate_carry(&mut self) {
+        for byte in self.out.iter_mut().rev() {
+            if *byte == 0xFF {
+                *byte = 0;
+            } else {
+                *byte += 1;
+                break;
+            }
+        }
+    }
+
+    fn renormalize(&mut self) {
+        while self.length < AC_MIN_LENGTH {
+            self.out.push((self.base >> 24) as u8);
+            self.base <<= 8;
+            self.length <<= 8;
+        }
+    }
+
+    fn add_to_base(&mut self, x: u32) {
+        let init_base = self.base;
+        self.base = self.base.wrapping_add(x);
+        if init_base > self.base {
+            self.propagate_carry();
+        }
+    }
+
+    fn encode_bit(&mut self, model: &mut BitModel, bit: u32) {
+        let x = model.bit_0_prob * (self.length >> BM_LENGTH_SHIFT);
+        if bit == 0 {
+            self.length = x;
+        } else {
+            self.add_to_base(x);
+            self.length -= x;
+        }
+        self.renormalize();
+        model.observe(bit);
+    }
+
+    fn encode_symbol(&mut self, model: &mut SymbolModel, symbol: u32) {
+        let y = self.length;
+        self.length >>= DM_LENGTH_SHIFT;
+        let x = model.distribution[symbol as usize] * self.length;
+        let y = if symbol == model.last_symbol {
+            y
+        } else {
+            model.distribution[symbol as usize + 1] * self.length
+        };
+        self.add_to_base(x);
+        self.length = y - x;
+        self.renormalize();
+        model.observe(symbol);
+    }
+
+    fn write_bits(&mut self, mut bits: u32, mut value: u32) {
+        if bits > 19 {
+            self.write_bits(16, value & 0xFFFF);
+            value >>= 16;
+            bits -= 16;
+        }
+        self.length >>= bits;
+        self.add_to_base(value * self.length);
+        self.renormalize();
+    }
+
+    fn write_int(&mut self, value: u32) {
+        self.write_bits(16, value & 0xFFFF);
+        self.write_bits(16, value >> 16);
+    }
+
+    fn done(mut self) -> Vec<u8> {
+        let another_byte = self.length > 2 * AC_MIN_LENGTH;
+        if another_byte {
+            self.add_to_base(AC_MIN_LENGTH);
+            self.length = AC_MIN_LENGTH >> 1;
+        } else {
+            self.add_to_base(AC_MIN_LENGTH >> 1);
+            self.length = AC_MIN_LENGTH >> 9;
+        }
+        self.renormalize();
+        self.out.extend([0, 0]);
+        if another_byte {
+            self.out.push(0);
+        }
+        self.out
+    }
+}
+
+fn compress(
+    ic: &mut IntegerCompressor,
+    enc: &mut Encoder,
+    prediction: i32,
+    real: i32,
+    context: u32,
+) {
+    let mut corr = real.wrapping_sub(prediction);
+    if ic.corr_range != 0 {
+        let corr_max = ic.corr_min + ic.corr_range as i32 - 1;
+        if corr < ic.corr_min {
+            corr += ic.corr_range as i32;
+        } else if corr > corr_max {
+            corr -= ic.corr_range as i32;
+        }
+    }
+
+    let magnitude = if corr <= 0 {
+        corr.wrapping_neg() as u32
+    } else {
+        corr as u32 - 1
+    };
+    let k = 32 - magnitude.leading_zeros();
+    ic.k = k;
+    enc.encode_symbol(&mut ic.bits[context as usize], k);
+    if k == 0 {
+        enc.encode_bit(&mut ic.corrector_0, corr as u32);
+    } else if k < 32 {
+        let c = if corr < 0 {
+            (corr as i64 + (1i64 << k) - 1) as u32
+        } else {
+            corr as u32 - 1
+        };
+        let model = &mut ic.corrector[k as usize - 1];
+        if k <= ic.bits_high {
+            enc.encode_symbol(model, c);
+        } else {
+            let k1 = k - ic.bits_high;
+            enc.encode_symbol(model, c >> k1);
+            enc.write_bits(k1, c & ((1 << k1) - 1));
+        }
+    }
+}
+
+fn encode_byte(enc: &mut Encoder, models: &mut [Option<SymbolModel>], last: u8, value: u8) {
+    let model = models[last as usize].get_or_insert_with(|| SymbolModel::new(256));
+    enc.encode_symbol(model, value as u32);
+}
+
+fn write_point10(p: &mut Point10, enc: &mut Encoder, item: &[u8]) {
+    let last = p.last;
+    let r = (item[14] & 7) as usize;
+    let n = ((item[14] >> 3) & 7) as usize;
+    let m = NUMBER_RETURN_MAP[n][r];
+    let l = NUMBER_RETURN_LEVEL[n][r];
+    let intensity = u16_at(item, 12);
+    let changed = ((last[14] != item[14]) as u32) << 5
+        | ((p.last_intensity[m] != intensity) as u32) << 4
+        | ((last[15] != item[15]) as u32) << 3
+        | ((last[16] != item[16]) as u32) << 2
+        | ((last[17] != item[17]) as u32) << 1
+        | (u16_at(&last, 18) != u16_at(item, 18)) as u32;
+    enc.encode_symbol(&mut p.changed_values, changed);
+    if changed & 32 != 0 {
+        encode_byte(enc, &mut p.bit_byte, last[14], item[14]);
+    }
+    if changed & 16 != 0 {
+        let prediction = p.last_intensity[m] as i32;
+        compress(
+            &mut p.ic_intensity,
+            enc,
+            prediction,
+            intensity as i32,
+            m.min(3) as u32,
+        );
+        p.last_intensity[m] = intensity;
+    }
+    if changed & 8 != 0 {
+        encode_byte(enc, &mut p.classification, last[15], item[15]);
+    }
+    if changed & 4 != 0 {
+        let model = &mut p.scan_angle_rank[((item[14] >> 6) & 1) as usize];
+        enc.encode_symbol(model, item[16].wrapping_sub(last[16]) as u32);
+    }
+    if changed & 2 != 0 {
+        encode_byte(enc, &mut p.user_data, last[17], item[17]);
+    }
+    if changed & 1 != 0 {
+        let (prediction, id) = (u16_at(&last, 18) as i32, u16_at(item, 18) as i32);
+        compress(&mut p.ic_point_source_id, enc, prediction, id, 0);
+    }
+
+    let single = (n == 1) as u32;
+    let diff = i32_at(item, 0).wrapping_sub(i32_at(&last, 0));
+    compress(&mut p.ic_dx, enc, p.last_x_diff[m].get(), diff, single);
+    p.last_x_diff[m].add(diff);
+    let k = p.ic_dx.k;
+    let diff = i32_at(item, 4).wrapping_sub(i32_at(&last, 4));
+    let context = single + if k < 20 { k & !1 } else { 20 };
+    compress(&mut p.ic_dy, enc, p.last_y_diff[m].get(), diff, context);
+    p.last_y_diff[m].add(diff);
+    let k = (p.ic_dx.k + p.ic_dy.k) / 2;
+    let context = single + if k < 18 { k & !1 } else { 18 };
+    let z = i32_at(item, 8);
+    compress(&mut p.ic_z, enc, p.last_height[l], z, context);
+    p.last_height[l] = z;
+    p.last.copy_from_slice(item);
+}
+
+/// Encode repeated times, one to nine times the previous spacing, and jumps
+/// coded in full.
+fn write_gpstime(g: &mut GpsTime11, enc: &mut Encoder, item: &[u8]) {
+    let time = i64::from_le_bytes(item.try_into().unwrap());
+    let last = g.last;
+    let diff = time.wrapping_sub(g.last_time[last]);
+    let spacing = g.last_diff[last];
+    let full = |g: &mut GpsTime11, enc: &mut Encoder| {
+        let prediction = (g.last_time[g.last] as u64 >> 32) as i32;
+        compress(
+            &mut g.ic_gpstime,
+            enc,
+            prediction,
+            (time as u64 >> 32) as i32,
+            8,
+        );
+        enc.write_int(time as u32);
+        g.next = (g.next + 1) & 3;
+        g.last = g.next;
+        g.last_time[g.last] = time;
+        g.last_diff[g.last] = 0;
+        g.multi_extreme_counter[g.last] = 0;
+    };
+    if spacing == 0 {
+        if diff == 0 {
+            enc.encode_symbol(&mut g.zero_diff, 0);
+        } else if let Ok(diff) = i32::try_from(diff) {
+            enc.encode_symbol(&mut g.zero_diff, 1);
+            compress(&mut g.ic_gpstime, enc, 0, diff, 0);
+            g.last_diff[last] = diff;
+            g.last_time[last] = time;
+            g.multi_extreme_counter[last] = 0;
+        } else {
+            enc.encode_symbol(&mut g.zero_diff, 2);
+            full(g, enc);
+        }
+    } else if diff == 0 {
+        enc.encode_symbol(&mut g.multi, GPSTIME_MULTI_UNCHANGED as u32);
+    } else if diff % spacing as i64 == 0 && (1..10).contains(&(diff / spacing as i64)) {
+        let multi = (diff / spacing as i64) as i32;
+        enc.encode_symbol(&mut g.multi, multi as u32);
+        let context = if multi == 1 { 1 } else { 2 };
+        compress(
+            &mut g.ic_gpstime,
+            enc,
+            multi.wrapping_mul(spacing),
+            diff as i32,
+            context,
+        );
+        if multi == 1 {
+            g.multi_extreme_counter[last] = 0;
+        }
+        g.last_time[last] = time;
+    } else {
+        enc.encode_symbol(&mut g.multi, GPSTIME_MULTI_CODE_FULL as u32);
+        full(g, enc);
+    }
+}
+
+fn write_rgb12(c: &mut Rgb12, enc: &mut Encoder, item: &[u8]) {
+    let last = c.last;
+    let rgb = [u16_at(item, 0), u16_at(item, 2), u16_at(item, 4)];
+    let low = |v: u16| (v & 0xFF) as i32;
+    let high = |v: u16| (v >> 8) as i32;
+    let clamp = |v: i32| v.clamp(0, 255);
+    let sym = (low(last[0]) != low(rgb[0])) as u32
+        | ((high(last[0]) != high(rgb[0])) as u32) << 1
+        | ((low(last[1]) != low(rgb[1])) as u32) << 2
+        | ((high(last[1]) != high(rgb[1])) as u32) << 3
+        | ((low(last[2]) != low(rgb[2])) as u32) << 4
+        | ((high(last[2]) != high(rgb[2])) as u32) << 5
+        | ((rgb[0] != rgb[1] || rgb[0] != rgb[2]) as u32) << 6;
+    enc.encode_symbol(&mut c.byte_used, sym);
+    let mut put = |index: usize, value: i32| {
+        enc.encode_symbol(&mut c.diff[index], (value as u8) as u32);
+    };
+
+    let diff_l = low(rgb[0]) - low(last[0]);
+    if sym & 1 != 0 {
+        put(0, diff_l);
+    }
+    let diff_h = high(rgb[0]) - high(last[0]);
+    if sym & 2 != 0 {
+        put(1, diff_h);
+    }
+    if sym & 64 != 0 {
+        if sym & 4 != 0 {
+            put(2, low(rgb[1]) - clamp(diff_l + low(last[1])));
+        }
+        if sym & 16 != 0 {
+            let diff = (diff_l + low(rgb[1]) - low(last[1])) / 2;
+            put(4, low(rgb[2]) - clamp(diff + low(last[2])));
+        }
+        if sym & 8 != 0 {
+            put(3, high(rgb[1]) - clamp(diff_h + high(last[1])));
+        }
+        if sym & 32 != 0 {
+            let diff = (diff_h + high(rgb[1]) - high(last[1])) / 2;
+            put(5, high(rgb[2]) - clamp(diff + high(last[2])));
+        }
+    }
+    c.last = rgb;
+}
+
+fn write_bytes(b: &mut Bytes, enc: &mut Encoder, item: &[u8]) {
+    for ((last, model), &value) in b.last.iter_mut().zip(&mut b.models).zip(item) {
+        enc.encode_symbol(model, value.wrapping_sub(*last) as u32);
+        *last = value;
+    }
+}
+
+/// Compress one chunk: the first record raw, the others arithmetic coded.
+fn compress_chunk(items: &[(u16, usize)], records: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = records[0].clone();
+    let mut decoders = Vec::new();
+    let mut offset = 0;
+    for &(kind, size) in items {
+        decoders.push((
+            ItemDecoder::new(kind, &records[0][offset..offset + size]),
+            offset,
+            size,
+        ));
+        offset += size;
+    }
+    let mut enc = Encoder::new();
+    for record in &records[1..] {
+        for (item, offset, size) in decoders.iter_mut() {
+            let bytes = &record[*offset..*offset + *size];
+            match item {
+                ItemDecoder::Point10(p) => write_point10(p, &mut enc, bytes),
+                ItemDecoder::GpsTime11(g) => write_gpstime(g, &mut enc, bytes),
+                ItemDecoder::Rgb12(c) => write_rgb12(c, &mut enc, bytes),
+                ItemDecoder::Bytes(b) => write_bytes(b, &mut enc, bytes),
+            }
+        }
+    }
+    out.extend(enc.done());
+    out
+}
+
+/// A LAZ 1.2 file of the records in chunks of `chunk_size` points, with the
+/// chunk table offset stored before the chunks or, when `table_at_end`, after
+/// the chunk table at the end of the file.
+fn laz_bytes(
+    format: u8,
+    items: &[(u16, usize)],
+    compressor: u16,
+    chunk_size: u32,
+    records: &[Vec<u8>],
+    table_at_end: bool,
+) -> Vec<u8> {
+    let record_length: usize = items.iter().map(|&(_, size)| size).sum();
+    let payload_length = 34 + 6 * items.len();
+    let point_offset = 227 + 54 + payload_length;
+    let mut data = vec![0u8; 227];
+    data[0..4].copy_from_slice(b"LASF");
+    data[24] = 1;
+    data[25] = 2;
+    data[94..96].copy_from_slice(&227u16.to_le_bytes());
+    data[96..100].copy_from_slice(&(point_offset as u32).to_le_bytes());
+    data[100..104].copy_from_slice(&1u32.to_le_bytes());
+    data[104] = format | 0x80;
+    data[105..107].copy_from_slice(&(record_length as u16).to_le_bytes());
+    data[107..111].copy_from_slice(&(records.len() as u32).to_le_bytes());
+    for (i, scale) in [0.01f64, 0.01, 0.01].iter().enumerate() {
+        data[131 + 8 * i..139 + 8 * i].copy_from_slice(&scale.to_le_bytes());
+    }
+
+    let mut vlr = vec![0u8; 54];
+    vlr[2..2 + LASZIP_USER_ID.len()].copy_from_slice(LASZIP_USER_ID);
+    vlr[18..20].copy_from_slice(&LASZIP_RECORD_ID.to_le_bytes());
+    vlr[20..22].copy_from_slice(&(payload_length as u16).to_le_bytes());
+    vlr.extend(compressor.to_le_bytes());
+    vlr.extend(0u16.to_le_bytes());
+    vlr.extend([2, 2, 0, 0]);
+    vlr.extend(0u32.to_le_bytes());
+    vlr.extend(chunk_size.to_le_bytes());
+    vlr.extend((-1i64).to_le_bytes());
+    vlr.extend((-1i64).to_le_bytes());
+    vlr.extend((items.len() as u16).to_le_bytes());
+    for &(kind, size) in items {
+        vlr.extend(kind.to_le_bytes());
+        vlr.extend((size as u16).to_le_bytes());
+        vlr.extend(2u16.to_le_bytes());
+    }
+    data.extend(vlr);
+
+    if compressor == COMPRESSOR_POINTWISE {
+        data.extend(compress_chunk(items, records));
+        return data;
+    }
+    data.extend([0u8; 8]);
+    let mut sizes = Vec::new();
+    for chunk in records.chunks(chunk_size as usize) {
+        let bytes = compress_chunk(items, chunk);
+        sizes.push(bytes.len() as i32);
+        data.extend(bytes);
+    }
+    let table = data.len() as i64;
+    data.extend(0u32.to_le_bytes());
+    data.extend((sizes.len() as u32).to_le_bytes());
+    let mut enc = Encoder::new();
+    let mut ic = IntegerCompressor::new(32, 2);
+    let mut last_size = 0;
+    for size in sizes {
+        compress(&mut ic, &mut enc, last_size, size, 1);
+        last_size = size;
+    }
+    data.extend(enc.done());
+    if table_at_end {
+        data[point_offset..point_offset + 8].copy_from_slice(&(-1i64).to_le_bytes());
+        data.extend(table.to_le_bytes());
+    } else {
+        data[point_offset..point_offset + 8].copy_from_slice(&table.to_le_bytes());
+    }
+    data
+}
+
+const FORMAT_3_ITEMS: [(u16, usize); 3] =
+    [(ITEM_POINT10, 20), (ITEM_GPSTIME11, 8), (ITEM_RGB12, 6)];
+
+/// Point format 3 records along a noisy scan line, with varying returns,
+/// attributes, colors and GPS times.
+fn format_3_records(count: usize, seed: u64) -> Vec<Vec<u8>> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut xyz = [100_000i32, -50_000, 2_000];
+    let mut time = 1i64 << 40;
+    let mut spacing = 1_000i64;
+    let mut rgb = [30_000u16, 20_000, 10_000];
+    (0..count)
+        .map(|i| {
+            xyz[0] += rng.gen_range(-20..200);
+            xyz[1] += rng.gen_range(-100..100);
+            xyz[2] += rng.gen_range(-500..500);
+            if i % 97 == 0 {
+                xyz[1] = rng.gen_range(-10_000_000..10_000_000);
+            }
+            let returns = rng.gen_range(1..=3u8);
+            let bits = rng.gen_range(1..=returns) | returns << 3 | ((i / 50 % 2) as u8) << 6;
+            match rng.gen_range(0..20) {
+                0 => {}
+                1 => time += 3 * spacing,
+                2 => time += 1i64 << 36,
+                3 => {
+                    spacing = rng.gen_range(1..5_000);
+                    time += spacing;
+                }
+                _ => time += spacing,
+            }
+            if rng.gen_bool(0.3) {
+                for c in rgb.iter_mut() {
+                    *c = c.wrapping_add(rng.gen_range(0..600)).wrapping_sub(300);
+                }
+            }
+            let color = if i % 13 == 0 { [rgb[0]; 3] } else { rgb };
+
+            let mut record = Vec::with_capacity(34);
+            for v in xyz {
+                record.extend(v.to_le_bytes());
+            }
+            record.extend(rng.gen_range(0..4_000u16).to_le_bytes());
+            record.push(bits);
+            record.push(if rng.gen_bool(0.1) { rng.gen() } else { 2 });
+            record.push(rng.gen_range(-10..10i8) as u8);
+            record.push(if rng.gen_bool(0.05) { rng.gen() } else { 0 });
+            record.extend(((i / 300) as u16 + 7).to_le_bytes());
+            record.extend(time.to_le_bytes());
+            for c in color {
+                record.extend(c.to_le_bytes());
+            }
+            record
+        })
+        .collect()
+}
+
+fn temp_path() -> String {
+    std::env::temp_dir()
+        .join(format!("scan_{}.laz", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn test_decompress_chunked_format_3() {
+    let records = format_3_records(1000, 3);
+    let data = laz_bytes(
+        3,
+        &FORMAT_3_ITEMS,
+        COMPRESSOR_POINTWISE_CHUNKED,
+        128,
+        &records,
+        false,
+    );
+    let point_offset = u32::from_le_bytes(data[96..100].try_into().unwrap()) as usize;
+    let decompressed = decompress(&data, 227, 1, point_offset, 34, records.len()).unwrap();
+    assert_eq!(decompressed, records.concat());
+
+    let path = temp_path();
+    std::fs::write(&path, &data).unwrap();
+    let cloud = read_las(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(cloud.len(), 1000);
+    assert_eq!(cloud.colors.len(), 1000);
+    for (point, record) in cloud.points.iter().zip(&records) {
+        assert!((point.x - i32_at(record, 0) as Scalar * 0.01).abs() < 1e-3);
+        assert!((point.z - i32_at(record, 8) as Scalar * 0.01).abs() < 1e-3);
+    }
+    assert_eq!(cloud.intensities[999], u16_at(&records[999], 12) as Scalar);
+}
+
+#[test]
+fn test_decompress_pointwise_and_extra_bytes() {
+    // Point format 1 with three extra bytes per point
+    let items = [(ITEM_POINT10, 20), (ITEM_GPSTIME11, 8), (ITEM_BYTE, 3)];
+    let records: Vec<Vec<u8>> = format_3_records(300, 11)
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let mut record = record[..28].to_vec();
+            record.extend([i as u8, (i / 7) as u8, 42]);
+            record
+        })
+        .collect();
+    for (compressor, table_at_end) in [
+        (COMPRESSOR_POINTWISE, false),
+        (COMPRESSOR_POINTWISE_CHUNKED, true),
+    ] {
+        let data = laz_bytes(1, &items, compressor, 50, &records, table_at_end);
+        let point_offset = u32::from_le_bytes(data[96..100].try_into().unwrap()) as usize;
+        let decompressed = decompress(&data, 227, 1, point_offset, 31, records.len()).unwrap();
+        assert_eq!(decompressed, records.concat());
+    }
+}
+
+#[test]
+fn test_decompress_rejects_unsupported_and_truncated_data() {
+    let records = format_3_records(200, 5);
+    let data = laz_bytes(
+        3,
+        &FORMAT_3_ITEMS,
+        COMPRESSOR_POINTWISE_CHUNKED,
+        64,
+        &records,
+        false,
+    );
+    let point_offset = u32::from_le_bytes(data[96..100].try_into().unwrap()) as usize;
+
+    // Version 1 items, and the layered compressor of point formats 6 to 10
+    let payload = 227 + 54;
+    let mut old = data.clone();
+    old[payload + 38..payload + 40].copy_from_slice(&1u16.to_le_bytes());
+    let mut layered = data.clone();
+    layered[payload..payload + 2].copy_from_slice(&3u16.to_le_bytes());
+    for data in [old, layered] {
+        assert!(matches!(
+            decompress(&data, 227, 1, point_offset, 34, records.len()),
+            Err(SessionError::SchemaMismatch(_))
+        ));
+    }
+
+    // More points than the file holds, and point data cut short
+    assert!(matches!(
+        decompress(&data, 227, 1, point_offset, 34, records.len() + 1),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+    let data = laz_bytes(3, &FORMAT_3_ITEMS, COMPRESSOR_POINTWISE, 0, &records, false);
+    assert!(matches!(
+        decompress(
+            &data[..data.len() - 100],
+            227,
+            1,
+            point_offset,
+            34,
+            records.len()
+        ),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+}
@@ -1,62 +1,185 @@
 //! Cross-language geometry library with Point, Color, and Vector types.
 //! Supports JSON serialization for interoperability between Rust, Python, and C++.
+//!
+//! Without the default `std` feature only the geometry kernel is built (scalar,
+//! tolerance, color, point, points, vector, vec3, xform, quaternion, line, plane,
+//! polyline, bounding box, bounding sphere, predicates and intersection), on
+//! `alloc` and `libm`.
 
 // Module declarations - makes modules publicly accessible
 // Usage: session_rust::point::Point
 #![allow(static_mut_refs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "no_std")))]
+compile_error!("enable the default `std` feature, or `no_std` for the geometry kernel alone");
+
+#[cfg(feature = "std")]
+pub mod animation;
+#[cfg(feature = "std")]
 pub mod arrow;
 pub mod boundingbox;
+pub mod boundingsphere;
+#[cfg(feature = "std")]
 pub mod bvh;
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod bvh_test;
+#[cfg(feature = "ffi")]
+pub mod capi;
+#[cfg(feature = "std")]
+pub mod chunkedpointcloud;
+#[cfg(feature = "std")]
+pub mod collision;
 pub mod color;
+#[cfg(feature = "std")]
+pub mod curve;
+#[cfg(feature = "std")]
 pub mod cylinder;
+#[cfg(feature = "std")]
+pub mod distance;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub mod dxf;
+#[cfg(feature = "std")]
 pub mod edge;
+#[cfg(feature = "std")]
 pub mod encoders;
+#[cfg(feature = "std")]
+pub mod error;
+pub mod gjk;
+#[cfg(feature = "std")]
 pub mod graph;
+pub mod guid;
 pub mod intersection;
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod intersection_test;
+#[cfg(feature = "std")]
+pub mod kdtree;
+#[cfg(feature = "las")]
+pub mod las;
+#[cfg(feature = "las")]
+mod laszip;
 pub mod line;
+#[cfg(feature = "std")]
 pub mod mesh;
+#[cfg(feature = "std")]
 pub mod nurbscurve;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 pub mod obj;
+#[cfg(feature = "std")]
 pub mod objects;
+#[cfg(feature = "std")]
+pub mod octree;
 pub mod plane;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub mod ply;
 pub mod point;
+#[cfg(feature = "std")]
 pub mod pointcloud;
+pub mod points;
 pub mod polyline;
+pub mod predicates;
+#[cfg(not(feature = "std"))]
+mod prelude;
 pub mod quaternion;
+#[cfg(feature = "std")]
+pub mod query;
+pub mod scalar;
+#[cfg(feature = "std")]
+pub mod schema;
+#[cfg(feature = "std")]
 pub mod session;
+#[cfg(feature = "std")]
+pub mod spatialhash;
+pub mod testing;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub mod threemf;
 pub mod tolerance;
+#[cfg(feature = "std")]
 pub mod tree;
+#[cfg(feature = "std")]
 pub mod treenode;
+pub mod vec3;
 pub mod vector;
+#[cfg(feature = "std")]
 pub mod vertex;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod xform;
 
+#[cfg(feature = "std")]
+pub use animation::AnimationTrack;
+#[cfg(feature = "std")]
 pub use arrow::Arrow;
 pub use boundingbox::BoundingBox;
-pub use bvh::BVH;
-pub use color::Color;
+pub use boundingsphere::BoundingSphere;
+#[cfg(feature = "std")]
+pub use bvh::{BvhStats, BVH};
+#[cfg(feature = "std")]
+pub use chunkedpointcloud::ChunkedPointCloud;
+#[cfg(feature = "std")]
+pub use collision::{CollisionFilter, CollisionOptions, Contact};
+pub use color::{Color, Colormap};
+#[cfg(feature = "std")]
 pub use cylinder::Cylinder;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub use dxf::write_dxf;
+#[cfg(feature = "std")]
 pub use edge::Edge;
+#[cfg(feature = "std")]
+pub use error::SessionError;
+#[cfg(feature = "std")]
 pub use graph::Graph;
+#[cfg(feature = "std")]
+pub use guid::IdGenerator;
+#[cfg(feature = "std")]
+pub use kdtree::KdTree;
+#[cfg(feature = "las")]
+pub use las::read_las;
 pub use line::Line;
-pub use mesh::Mesh;
+#[cfg(feature = "std")]
+pub use mesh::{BufferLayout, Mesh, MeshBuffers, NormalWeighting, SectionProperties};
+#[cfg(feature = "std")]
 pub use nurbscurve::NurbsCurve;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 pub use obj::{read_obj, write_obj};
+#[cfg(feature = "std")]
 pub use objects::Objects;
+#[cfg(feature = "std")]
+pub use octree::{Octree, OctreeNode};
 pub use plane::Plane;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub use ply::{read_ply, write_ply, PlyFormat};
 pub use point::Point;
-pub use pointcloud::PointCloud;
+#[cfg(feature = "std")]
+pub use pointcloud::{
+    deviation, read_pts, read_xyz, write_pts, write_xyz, DeviationStats, PointChunk, PointCloud,
+};
 pub use polyline::Polyline;
-pub use quaternion::Quaternion;
-pub use session::{Geometry, Session};
+pub use quaternion::{EulerOrder, Quaternion};
+#[cfg(feature = "std")]
+pub use query::{GeometryType, Query};
+pub use scalar::Scalar;
+#[cfg(feature = "std")]
+pub use schema::write_schemas;
+#[cfg(feature = "std")]
+pub use session::{
+    AttrValue, BroadPhase, DisplayState, ExternalLink, Geometry, GeometryMut, Group, MergeStrategy,
+    RayCastMode, RayCastOptions, RayHit, RemovalPolicy, RemovalReport, SelectionMode, Session,
+    SessionDelta, SessionEvent, SessionState, SessionStats, SweepHit, ValidationIssue,
+};
+#[cfg(feature = "std")]
+pub use spatialhash::SpatialHash;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub use threemf::write_3mf;
 pub use tolerance::Tolerance;
+#[cfg(feature = "std")]
 pub use tree::Tree;
+#[cfg(feature = "std")]
 pub use treenode::TreeNode;
+pub use vec3::{AsVec3, Vec3};
 pub use vector::Vector;
+#[cfg(feature = "std")]
 pub use vertex::Vertex;
 pub use xform::Xform;
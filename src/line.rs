@@ -1,8 +1,11 @@
-use crate::{Color, Point, Vector, Xform};
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{Color, Point, Scalar, Vector, Xform};
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
 use serde::{Deserialize, Serialize};
-use std::fmt;
-use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
-use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename = "Line")]
@@ -10,18 +13,18 @@ pub struct Line {
     pub guid: String,
     pub name: String,
     #[serde(rename = "x0")]
-    _x0: f64,
+    _x0: Scalar,
     #[serde(rename = "y0")]
-    _y0: f64,
+    _y0: Scalar,
     #[serde(rename = "z0")]
-    _z0: f64,
+    _z0: Scalar,
     #[serde(rename = "x1")]
-    _x1: f64,
+    _x1: Scalar,
     #[serde(rename = "y1")]
-    _y1: f64,
+    _y1: Scalar,
     #[serde(rename = "z1")]
-    _z1: f64,
-    pub width: f64,
+    _z1: Scalar,
+    pub width: Scalar,
     pub linecolor: Color,
     #[serde(default = "Xform::identity")]
     pub xform: Xform,
@@ -36,7 +39,7 @@ impl Default for Line {
             _x1: 0.0,
             _y1: 0.0,
             _z1: 1.0,
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_line".to_string(),
             linecolor: Color::white(),
             width: 1.0,
@@ -46,7 +49,7 @@ impl Default for Line {
 }
 
 impl Line {
-    pub fn new(x0: f64, y0: f64, z0: f64, x1: f64, y1: f64, z1: f64) -> Self {
+    pub fn new(x0: Scalar, y0: Scalar, z0: Scalar, x1: Scalar, y1: Scalar, z1: Scalar) -> Self {
         Self {
             _x0: x0,
             _y0: y0,
@@ -62,7 +65,15 @@ impl Line {
         Self::new(p1.x(), p1.y(), p1.z(), p2.x(), p2.y(), p2.z())
     }
 
-    pub fn with_name(name: &str, x0: f64, y0: f64, z0: f64, x1: f64, y1: f64, z1: f64) -> Self {
+    pub fn with_name(
+        name: &str,
+        x0: Scalar,
+        y0: Scalar,
+        z0: Scalar,
+        x1: Scalar,
+        y1: Scalar,
+        z1: Scalar,
+    ) -> Self {
         Self {
             name: name.to_string(),
             _x0: x0,
@@ -75,52 +86,52 @@ impl Line {
         }
     }
 
-    pub fn x0(&self) -> f64 {
+    pub fn x0(&self) -> Scalar {
         self._x0
     }
-    pub fn y0(&self) -> f64 {
+    pub fn y0(&self) -> Scalar {
         self._y0
     }
-    pub fn z0(&self) -> f64 {
+    pub fn z0(&self) -> Scalar {
         self._z0
     }
-    pub fn x1(&self) -> f64 {
+    pub fn x1(&self) -> Scalar {
         self._x1
     }
-    pub fn y1(&self) -> f64 {
+    pub fn y1(&self) -> Scalar {
         self._y1
     }
-    pub fn z1(&self) -> f64 {
+    pub fn z1(&self) -> Scalar {
         self._z1
     }
 
-    pub fn set_x0(&mut self, v: f64) {
+    pub fn set_x0(&mut self, v: Scalar) {
         self._x0 = v;
     }
-    pub fn set_y0(&mut self, v: f64) {
+    pub fn set_y0(&mut self, v: Scalar) {
         self._y0 = v;
     }
-    pub fn set_z0(&mut self, v: f64) {
+    pub fn set_z0(&mut self, v: Scalar) {
         self._z0 = v;
     }
-    pub fn set_x1(&mut self, v: f64) {
+    pub fn set_x1(&mut self, v: Scalar) {
         self._x1 = v;
     }
-    pub fn set_y1(&mut self, v: f64) {
+    pub fn set_y1(&mut self, v: Scalar) {
         self._y1 = v;
     }
-    pub fn set_z1(&mut self, v: f64) {
+    pub fn set_z1(&mut self, v: Scalar) {
         self._z1 = v;
     }
 
-    pub fn length(&self) -> f64 {
+    pub fn length(&self) -> Scalar {
         let dx = self._x1 - self._x0;
         let dy = self._y1 - self._y0;
         let dz = self._z1 - self._z0;
         (dx * dx + dy * dy + dz * dz).sqrt()
     }
 
-    pub fn squared_length(&self) -> f64 {
+    pub fn squared_length(&self) -> Scalar {
         let dx = self._x1 - self._x0;
         let dy = self._y1 - self._y0;
         let dz = self._z1 - self._z0;
@@ -135,7 +146,7 @@ impl Line {
         )
     }
 
-    pub fn point_at(&self, t: f64) -> Point {
+    pub fn point_at(&self, t: Scalar) -> Point {
         let s = 1.0 - t;
         Point::new(
             s * self._x0 + t * self._x1,
@@ -154,7 +165,7 @@ impl Line {
 }
 
 impl Index<usize> for Line {
-    type Output = f64;
+    type Output = Scalar;
 
     fn index(&self, index: usize) -> &Self::Output {
         match index {
@@ -205,8 +216,8 @@ impl SubAssign<&Vector> for Line {
     }
 }
 
-impl MulAssign<f64> for Line {
-    fn mul_assign(&mut self, factor: f64) {
+impl MulAssign<Scalar> for Line {
+    fn mul_assign(&mut self, factor: Scalar) {
         self._x0 *= factor;
         self._y0 *= factor;
         self._z0 *= factor;
@@ -216,8 +227,8 @@ impl MulAssign<f64> for Line {
     }
 }
 
-impl DivAssign<f64> for Line {
-    fn div_assign(&mut self, factor: f64) {
+impl DivAssign<Scalar> for Line {
+    fn div_assign(&mut self, factor: Scalar) {
         self._x0 /= factor;
         self._y0 /= factor;
         self._z0 /= factor;
@@ -247,20 +258,20 @@ impl Sub<&Vector> for Line {
     }
 }
 
-impl Mul<f64> for Line {
+impl Mul<Scalar> for Line {
     type Output = Line;
 
-    fn mul(self, factor: f64) -> Line {
+    fn mul(self, factor: Scalar) -> Line {
         let mut result = self;
         result *= factor;
         result
     }
 }
 
-impl Div<f64> for Line {
+impl Div<Scalar> for Line {
     type Output = Line;
 
-    fn div(self, factor: f64) -> Line {
+    fn div(self, factor: Scalar) -> Line {
         let mut result = self;
         result /= factor;
         result
@@ -301,11 +312,13 @@ impl Line {
         result
     }
 
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
 
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 }
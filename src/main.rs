@@ -1,10 +1,51 @@
+// The sample coordinates below are written at f64 precision
+#![cfg_attr(feature = "f32", allow(clippy::excessive_precision))]
+
+use session_rust::testing::{random_boxes, Pcg32};
 use session_rust::{
-    read_obj, BoundingBox, Line, Mesh, NurbsCurve, Plane, Point, Session, Tolerance, Vector, BVH,
+    read_obj, BoundingBox, Line, Mesh, NurbsCurve, Plane, Point, Scalar, Session, Tolerance,
+    Vector, BVH,
 };
 use std::path::Path;
 use std::time::Instant;
 
+/// Convert a session file between JSON and MessagePack, files ending in `.json` are JSON.
+fn convert(input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let is_json = |path: &str| Path::new(path).extension().is_some_and(|e| e == "json");
+    let session = if is_json(input) {
+        Session::from_json(input)?
+    } else {
+        Session::from_binary(input)?
+    };
+    if is_json(output) {
+        session.to_json(output)?;
+    } else {
+        session.to_binary(output)?;
+    }
+    println!("Converted {input} to {output}");
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // session_rust --convert input.json output.msgpack
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "--convert") {
+        if args.len() != 4 {
+            return Err("usage: session_rust --convert <input> <output>".into());
+        }
+        return convert(&args[2], &args[3]);
+    }
+    // session_rust --schema output_directory
+    if args.get(1).is_some_and(|arg| arg == "--schema") {
+        let [_, _, directory] = &args[..] else {
+            return Err("usage: session_rust --schema <directory>".into());
+        };
+        for path in session_rust::write_schemas(directory)? {
+            println!("Wrote {path}");
+        }
+        return Ok(());
+    }
+
     println!("=== Intersection Examples (Rust) ===");
 
     let l0 = Line::new(500.0, -573.576, -819.152, 500.0, 573.576, 819.152);
@@ -203,30 +244,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== BVH Collision Detection (Rust) ===");
     let box_counts = [100usize, 5000usize, 10000usize];
     for &box_count in box_counts.iter() {
-        let world_size = 100.0f64;
-        let min_size = 5.0f64;
-        let max_size = 10.0f64;
-        unsafe { libc::srand(42) }; // match C++ seeding per dataset
-        let rand_max = 2147483647.0f64; // typical RAND_MAX on macOS
-        let next_rand01 = || -> f64 { unsafe { (libc::rand() as i64) as f64 / rand_max } };
-        let mut boxes: Vec<BoundingBox> = Vec::with_capacity(box_count);
-        for _ in 0..box_count {
-            let x = (next_rand01() - 0.5) * world_size;
-            let y = (next_rand01() - 0.5) * world_size;
-            let z = (next_rand01() - 0.5) * world_size;
-            let w = min_size + next_rand01() * (max_size - min_size);
-            let h = min_size + next_rand01() * (max_size - min_size);
-            let d = min_size + next_rand01() * (max_size - min_size);
-            let center = Point::new(x, y, z);
-            let half = Vector::new(w * 0.5, h * 0.5, d * 0.5);
-            boxes.push(BoundingBox::new(
-                center,
-                Vector::new(1.0, 0.0, 0.0),
-                Vector::new(0.0, 1.0, 0.0),
-                Vector::new(0.0, 0.0, 1.0),
-                half,
-            ));
-        }
+        let world_size: Scalar = 100.0;
+        // Same boxes as the C++ and Python benchmarks, see `testing::Pcg32`
+        let boxes = random_boxes(42, box_count, world_size);
         let bvh_start = Instant::now();
         let bvh = BVH::from_boxes(&boxes, world_size);
         let bvh_end = Instant::now();
@@ -309,15 +329,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Performance Test (10k Objects) (Rust) ===");
     {
         let object_count = 10_000usize;
-        let world_size = 100.0f64;
+        let world_size: Scalar = 100.0;
         let mut scene = Session::new("perf_test");
         let mut pure_boxes: Vec<BoundingBox> = Vec::with_capacity(object_count);
-        unsafe { libc::srand(42) }; // match C++
-        let rand_max = 2147483647.0f64;
+        let mut rng = Pcg32::new(42);
+        let half_world = world_size * 0.5;
         for i in 0..object_count {
-            let x = (unsafe { libc::rand() } as f64 / rand_max - 0.5) * world_size;
-            let y = (unsafe { libc::rand() } as f64 / rand_max - 0.5) * world_size;
-            let z = (unsafe { libc::rand() } as f64 / rand_max - 0.5) * world_size;
+            let x = rng.range(-half_world, half_world);
+            let y = rng.range(-half_world, half_world);
+            let z = rng.range(-half_world, half_world);
             let mut pt = Point::new(x, y, z);
             pt.name = format!("point_{i}");
             scene.add_point(pt.clone());
@@ -383,7 +403,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Create planes perpendicular to X-axis at regular intervals
         let mut planes = Vec::new();
         for i in 0..7 {
-            let origin = Point::new(i as f64 * 500.0, 0.0, 0.0);
+            let origin = Point::new(i as Scalar * 500.0, 0.0, 0.0);
             let normal = Vector::new(1.0, 0.0, 0.0);
             planes.push(Plane::from_point_normal(origin, normal));
         }
@@ -1,7 +1,23 @@
-use crate::{BoundingBox, Color, Line, Point, Tolerance, Vector, Xform, BVH};
+use crate::error::SessionError;
+use crate::intersection::{
+    closest_point_on_triangle, point_in_mesh, triangle_triangle, Containment,
+};
+use crate::scalar::{to_f32, to_f64};
+use crate::{
+    AsVec3, BoundingBox, Color, Line, Plane, Point, Scalar, Tolerance, Vec3, Vector, Xform, BVH,
+};
+use once_cell::sync::OnceCell;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+/// Leading bytes of a `Mesh::to_bytes` blob
+pub const MESH_BYTES_MAGIC: [u8; 4] = *b"SMSH";
+
+/// Layout version written by `Mesh::to_bytes`
+pub const MESH_BYTES_VERSION: u32 = 1;
+
 /// Weighting scheme for vertex normal computation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NormalWeighting {
@@ -10,6 +26,42 @@ pub enum NormalWeighting {
     Uniform,
 }
 
+/// Vertex layout of `Mesh::to_buffers`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BufferLayout {
+    /// Positions, normals and colors in their own arrays
+    #[default]
+    Separate,
+    /// `x y z nx ny nz r g b a` per vertex in one array
+    Interleaved,
+}
+
+/// Flat triangulated arrays for uploading a mesh to the GPU
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshBuffers {
+    pub positions: Vec<f32>,   // x y z per vertex (separate layout)
+    pub normals: Vec<f32>,     // nx ny nz per vertex (separate layout)
+    pub colors: Vec<f32>,      // r g b a in 0-1 per vertex (separate layout)
+    pub interleaved: Vec<f32>, // All attributes per vertex (interleaved layout)
+    pub indices: Vec<u32>,     // Three vertex indices per triangle
+}
+
+impl MeshBuffers {
+    /// Floats per vertex in the interleaved layout
+    pub const STRIDE: usize = 10;
+}
+
+/// Area properties of a planar cut through a closed mesh, see `Mesh::section_properties`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionProperties {
+    pub area: Scalar,
+    pub centroid: Point, // On the cutting plane
+    /// Second moments of area about the principal axes through the centroid, largest first
+    pub principal_moments: [Scalar; 2],
+    /// In-plane unit directions of the principal axes, in the order of `principal_moments`
+    pub principal_axes: [Vector; 2],
+}
+
 /// A halfedge mesh data structure for representing polygonal surfaces
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename = "Mesh")]
@@ -17,11 +69,11 @@ pub struct Mesh {
     pub halfedge: HashMap<usize, HashMap<usize, Option<usize>>>, // Halfedge connectivity
     pub vertex: HashMap<usize, VertexData>,                      // Vertex data
     pub face: HashMap<usize, Vec<usize>>,                        // Face vertex lists
-    pub facedata: HashMap<usize, HashMap<String, f64>>,          // Face attributes
-    pub edgedata: HashMap<(usize, usize), HashMap<String, f64>>, // Edge attributes
-    pub default_vertex_attributes: HashMap<String, f64>,         // Default vertex attrs
-    pub default_face_attributes: HashMap<String, f64>,           // Default face attrs
-    pub default_edge_attributes: HashMap<String, f64>,           // Default edge attrs
+    pub facedata: HashMap<usize, HashMap<String, Scalar>>,       // Face attributes
+    pub edgedata: HashMap<(usize, usize), HashMap<String, Scalar>>, // Edge attributes
+    pub default_vertex_attributes: HashMap<String, Scalar>,      // Default vertex attrs
+    pub default_face_attributes: HashMap<String, Scalar>,        // Default face attrs
+    pub default_edge_attributes: HashMap<String, Scalar>,        // Default edge attrs
     #[serde(skip)]
     pub triangulation: HashMap<usize, Vec<[usize; 3]>>, // Cached triangulations
     max_vertex: usize,                                           // Next vertex key
@@ -35,25 +87,33 @@ pub struct Mesh {
     #[serde(skip)]
     pub linecolors: Vec<Color>,                // Edge colors
     #[serde(skip)]
-    pub widths: Vec<f64>,                      // Edge widths
+    pub widths: Vec<Scalar>,                   // Edge widths
     #[serde(default = "Xform::identity")]
     pub xform: Xform,   // Transformation matrix
-    // Cached triangle BVH for ray queries (not serialized)
+    // Cached triangle BVH for ray queries (not serialized), filled through shared references
     #[serde(skip)]
-    pub tri_bvh: Option<BVH>,
+    pub(crate) triangle_bvh: OnceCell<TriangleBvh>,
+    // Cached vertex to faces adjacency (not serialized)
     #[serde(skip)]
-    pub tri_tris: Vec<[usize; 3]>,
-    #[serde(skip)]
-    pub tri_vertices: Vec<Point>,
+    vertex_face_cache: OnceCell<HashMap<usize, Vec<usize>>>,
+}
+
+/// Triangles of a mesh in its local frame with a BVH over their boxes
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TriangleBvh {
+    /// None for a mesh without faces
+    pub(crate) bvh: Option<BVH>,
+    pub(crate) triangles: Vec<[usize; 3]>,
+    pub(crate) vertices: Vec<Point>,
 }
 
 /// Vertex data containing position and attributes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VertexData {
-    pub x: f64,                           // X coordinate
-    pub y: f64,                           // Y coordinate
-    pub z: f64,                           // Z coordinate
-    pub attributes: HashMap<String, f64>, // Vertex attributes
+    pub x: Scalar,                           // X coordinate
+    pub y: Scalar,                           // Y coordinate
+    pub z: Scalar,                           // Z coordinate
+    pub attributes: HashMap<String, Scalar>, // Vertex attributes
 }
 
 impl VertexData {
@@ -70,13 +130,17 @@ impl VertexData {
         Point::new(self.x, self.y, self.z)
     }
 
+    pub fn coords(&self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
     pub fn set_position(&mut self, point: Point) {
         self.x = point.x();
         self.y = point.y();
         self.z = point.z();
     }
 
-    pub fn color(&self) -> [f64; 3] {
+    pub fn color(&self) -> [Scalar; 3] {
         [
             self.attributes.get("r").copied().unwrap_or(0.5),
             self.attributes.get("g").copied().unwrap_or(0.5),
@@ -84,24 +148,51 @@ impl VertexData {
         ]
     }
 
-    pub fn set_color(&mut self, r: f64, g: f64, b: f64) {
+    pub fn set_color(&mut self, r: Scalar, g: Scalar, b: Scalar) {
         self.attributes.insert("r".to_string(), r);
         self.attributes.insert("g".to_string(), g);
         self.attributes.insert("b".to_string(), b);
     }
 
-    pub fn normal(&self) -> Option<[f64; 3]> {
+    pub fn normal(&self) -> Option<[Scalar; 3]> {
         let nx = self.attributes.get("nx")?;
         let ny = self.attributes.get("ny")?;
         let nz = self.attributes.get("nz")?;
         Some([*nx, *ny, *nz])
     }
 
-    pub fn set_normal(&mut self, nx: f64, ny: f64, nz: f64) {
+    pub fn set_normal(&mut self, nx: Scalar, ny: Scalar, nz: Scalar) {
         self.attributes.insert("nx".to_string(), nx);
         self.attributes.insert("ny".to_string(), ny);
         self.attributes.insert("nz".to_string(), nz);
     }
+
+    pub fn uv(&self) -> Option<[Scalar; 2]> {
+        let u = self.attributes.get("u")?;
+        let v = self.attributes.get("v")?;
+        Some([*u, *v])
+    }
+
+    pub fn set_uv(&mut self, u: Scalar, v: Scalar) {
+        self.attributes.insert("u".to_string(), u);
+        self.attributes.insert("v".to_string(), v);
+    }
+
+    /// Tangent direction and the handedness `tw` of the bitangent, +1 or -1.
+    pub fn tangent(&self) -> Option<[Scalar; 4]> {
+        let tx = self.attributes.get("tx")?;
+        let ty = self.attributes.get("ty")?;
+        let tz = self.attributes.get("tz")?;
+        let tw = self.attributes.get("tw")?;
+        Some([*tx, *ty, *tz, *tw])
+    }
+
+    pub fn set_tangent(&mut self, tx: Scalar, ty: Scalar, tz: Scalar, tw: Scalar) {
+        self.attributes.insert("tx".to_string(), tx);
+        self.attributes.insert("ty".to_string(), ty);
+        self.attributes.insert("tz".to_string(), tz);
+        self.attributes.insert("tw".to_string(), tw);
+    }
 }
 
 impl Default for Mesh {
@@ -130,16 +221,15 @@ impl Mesh {
             triangulation: HashMap::new(),
             max_vertex: 0,
             max_face: 0,
-            guid: uuid::Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_mesh".to_string(),
             pointcolors: Vec::new(),
             facecolors: Vec::new(),
             linecolors: Vec::new(),
             widths: Vec::new(),
             xform: Xform::identity(),
-            tri_bvh: None,
-            tri_tris: Vec::new(),
-            tri_vertices: Vec::new(),
+            triangle_bvh: OnceCell::new(),
+            vertex_face_cache: OnceCell::new(),
         }
     }
 
@@ -161,6 +251,7 @@ impl Mesh {
         self.linecolors.clear();
         self.widths.clear();
         self.invalidate_triangle_bvh();
+        self.invalidate_adjacency();
     }
 
     pub fn number_of_vertices(&self) -> usize {
@@ -244,6 +335,7 @@ impl Mesh {
         self.triangulation.remove(&face_key);
         self.facecolors.push(Color::white());
         self.invalidate_triangle_bvh();
+        self.invalidate_adjacency();
 
         for i in 0..vertices.len() {
             let u = vertices[i];
@@ -270,6 +362,11 @@ impl Mesh {
         self.vertex.get(&vertex_key).map(|v| v.position())
     }
 
+    /// Vertex coordinates without building a `Point`, for internal computations.
+    fn vertex_coords(&self, vertex_key: usize) -> Option<Vec3> {
+        self.vertex.get(&vertex_key).map(VertexData::coords)
+    }
+
     pub fn face_vertices(&self, face_key: usize) -> Option<&Vec<usize>> {
         self.face.get(&face_key)
     }
@@ -281,59 +378,73 @@ impl Mesh {
             .unwrap_or_default()
     }
 
+    /// Faces around a vertex in ascending key order.
     pub fn vertex_faces(&self, vertex_key: usize) -> Vec<usize> {
-        let mut faces = Vec::new();
-        for (face_key, face_vertices) in &self.face {
-            if face_vertices.contains(&vertex_key) {
-                faces.push(*face_key);
-            }
-        }
-        faces
+        self.faces_around(vertex_key).to_vec()
     }
 
-    pub fn is_vertex_on_boundary(&self, vertex_key: usize) -> bool {
-        if let Some(neigh) = self.halfedge.get(&vertex_key) {
-            for (_v, face_opt) in neigh.iter() {
-                if face_opt.is_none() {
-                    return true;
-                }
-            }
-        }
+    fn faces_around(&self, vertex_key: usize) -> &[usize] {
+        self.vertex_face_map()
+            .get(&vertex_key)
+            .map_or(&[], Vec::as_slice)
+    }
 
-        for (_u, neigh) in self.halfedge.iter() {
-            if let Some(face_opt) = neigh.get(&vertex_key) {
-                if face_opt.is_none() {
-                    return true;
+    /// Vertex to faces adjacency, built on first use from a single pass over the faces.
+    fn vertex_face_map(&self) -> &HashMap<usize, Vec<usize>> {
+        self.vertex_face_cache.get_or_init(|| {
+            let mut face_keys: Vec<usize> = self.face.keys().copied().collect();
+            face_keys.sort();
+            let mut map: HashMap<usize, Vec<usize>> = HashMap::with_capacity(self.vertex.len());
+            for face_key in face_keys {
+                for &vertex_key in &self.face[&face_key] {
+                    let faces = map.entry(vertex_key).or_default();
+                    if faces.last() != Some(&face_key) {
+                        faces.push(face_key);
+                    }
                 }
             }
-        }
-        false
+            map
+        })
+    }
+
+    /// Drop the cached vertex to faces adjacency.
+    ///
+    /// Mesh methods that change faces do this themselves, call it after editing `face` directly.
+    pub fn invalidate_adjacency(&mut self) {
+        self.vertex_face_cache = OnceCell::new();
+    }
+
+    pub fn is_vertex_on_boundary(&self, vertex_key: usize) -> bool {
+        // Every edge has halfedges both ways, a missing face on either side marks the boundary
+        let Some(neighbors) = self.halfedge.get(&vertex_key) else {
+            return false;
+        };
+        neighbors.iter().any(|(other, face)| {
+            face.is_none()
+                || self
+                    .halfedge
+                    .get(other)
+                    .and_then(|back| back.get(&vertex_key))
+                    .is_some_and(Option::is_none)
+        })
     }
 
     pub fn face_normal(&self, face_key: usize) -> Option<Vector> {
         let vertices = self.face.get(&face_key)?;
+        self.face_normal_coords(vertices).map(Vector::from)
+    }
+
+    fn face_normal_coords(&self, vertices: &[usize]) -> Option<Vec3> {
         if vertices.len() < 3 {
             return None;
         }
+        let p0 = self.vertex_coords(vertices[0])?;
+        let p1 = self.vertex_coords(vertices[1])?;
+        let p2 = self.vertex_coords(vertices[2])?;
 
-        let p0 = self.vertex_position(vertices[0])?;
-        let p1 = self.vertex_position(vertices[1])?;
-        let p2 = self.vertex_position(vertices[2])?;
-
-        let u = Vector::new(p1.x() - p0.x(), p1.y() - p0.y(), p1.z() - p0.z());
-        let v = Vector::new(p2.x() - p0.x(), p2.y() - p0.y(), p2.z() - p0.z());
-
-        let mut normal = u.cross(&v);
-        let len = normal.magnitude();
-        if len > Tolerance::ZERO_TOLERANCE {
-            Some(Vector::new(
-                normal.x() / len,
-                normal.y() / len,
-                normal.z() / len,
-            ))
-        } else {
-            None
-        }
+        let normal = (p1 - p0).cross(&(p2 - p0));
+        let len = normal.length();
+        (len > Tolerance::ZERO_TOLERANCE).then(|| normal * (1.0 / len))
     }
 
     pub fn vertex_normal(&self, vertex_key: usize) -> Option<Vector> {
@@ -345,15 +456,19 @@ impl Mesh {
         vertex_key: usize,
         weighting: NormalWeighting,
     ) -> Option<Vector> {
-        let faces = self.vertex_faces(vertex_key);
+        let faces = self.faces_around(vertex_key);
         if faces.is_empty() {
             return None;
         }
 
-        let mut normal_acc = Vector::new(0.0, 0.0, 0.0);
+        let mut normal_acc = Vec3::ZERO;
 
-        for face_key in faces {
-            if let Some(face_normal) = self.face_normal(face_key) {
+        for &face_key in faces {
+            let face_normal = self
+                .face
+                .get(&face_key)
+                .and_then(|vertices| self.face_normal_coords(vertices));
+            if let Some(face_normal) = face_normal {
                 let weight = match weighting {
                     NormalWeighting::Area => self.face_area(face_key).unwrap_or(1.0),
                     NormalWeighting::Angle => self
@@ -362,47 +477,34 @@ impl Mesh {
                     NormalWeighting::Uniform => 1.0,
                 };
 
-                normal_acc.set_x(normal_acc.x() + face_normal.x() * weight);
-                normal_acc.set_y(normal_acc.y() + face_normal.y() * weight);
-                normal_acc.set_z(normal_acc.z() + face_normal.z() * weight);
+                normal_acc += face_normal * weight;
             }
         }
 
-        let len = normal_acc.magnitude();
-        if len > Tolerance::ZERO_TOLERANCE {
-            Some(Vector::new(
-                normal_acc.x() / len,
-                normal_acc.y() / len,
-                normal_acc.z() / len,
-            ))
-        } else {
-            None
-        }
+        let len = normal_acc.length();
+        (len > Tolerance::ZERO_TOLERANCE).then(|| Vector::from(normal_acc * (1.0 / len)))
     }
 
-    pub fn face_area(&self, face_key: usize) -> Option<f64> {
+    pub fn face_area(&self, face_key: usize) -> Option<Scalar> {
         let vertices = self.face.get(&face_key)?;
         if vertices.len() < 3 {
             return Some(0.0);
         }
 
         let mut area = 0.0;
-        let p0 = self.vertex_position(vertices[0])?;
+        let p0 = self.vertex_coords(vertices[0])?;
 
         for i in 1..(vertices.len() - 1) {
-            let p1 = self.vertex_position(vertices[i])?;
-            let p2 = self.vertex_position(vertices[i + 1])?;
+            let p1 = self.vertex_coords(vertices[i])?;
+            let p2 = self.vertex_coords(vertices[i + 1])?;
 
-            let u = Vector::new(p1.x() - p0.x(), p1.y() - p0.y(), p1.z() - p0.z());
-            let v = Vector::new(p2.x() - p0.x(), p2.y() - p0.y(), p2.z() - p0.z());
-
-            area += u.cross(&v).magnitude() * 0.5;
+            area += (p1 - p0).cross(&(p2 - p0)).length() * 0.5;
         }
 
         Some(area)
     }
 
-    pub fn vertex_angle_in_face(&self, vertex_key: usize, face_key: usize) -> Option<f64> {
+    pub fn vertex_angle_in_face(&self, vertex_key: usize, face_key: usize) -> Option<Scalar> {
         let vertices = self.face.get(&face_key)?;
         let vertex_index = vertices.iter().position(|&v| v == vertex_key)?;
 
@@ -410,23 +512,12 @@ impl Mesh {
         let prev_vertex = vertices[(vertex_index + n - 1) % n];
         let next_vertex = vertices[(vertex_index + 1) % n];
 
-        let center = self.vertex_position(vertex_key)?;
-        let prev_pos = self.vertex_position(prev_vertex)?;
-        let next_pos = self.vertex_position(next_vertex)?;
-
-        let mut u = Vector::new(
-            prev_pos.x() - center.x(),
-            prev_pos.y() - center.y(),
-            prev_pos.z() - center.z(),
-        );
-        let mut v = Vector::new(
-            next_pos.x() - center.x(),
-            next_pos.y() - center.y(),
-            next_pos.z() - center.z(),
-        );
+        let center = self.vertex_coords(vertex_key)?;
+        let u = self.vertex_coords(prev_vertex)? - center;
+        let v = self.vertex_coords(next_vertex)? - center;
 
-        let u_len = u.magnitude();
-        let v_len = v.magnitude();
+        let u_len = u.length();
+        let v_len = v.length();
 
         if u_len < Tolerance::ZERO_TOLERANCE || v_len < Tolerance::ZERO_TOLERANCE {
             return Some(0.0);
@@ -452,13 +543,141 @@ impl Mesh {
     }
 
     pub fn vertex_normals_weighted(&self, weighting: NormalWeighting) -> HashMap<usize, Vector> {
-        let mut normals = HashMap::new();
-        for vertex_key in self.vertex.keys() {
-            if let Some(normal) = self.vertex_normal_weighted(*vertex_key, weighting) {
-                normals.insert(*vertex_key, normal);
+        self.accumulate_vertex_normals(weighting)
+            .into_iter()
+            .filter_map(|(key, sum)| {
+                let len = sum.length();
+                (len > Tolerance::ZERO_TOLERANCE).then(|| (key, Vector::from(sum * (1.0 / len))))
+            })
+            .collect()
+    }
+
+    /// Weighted sums of face normals per vertex in a single pass over the faces.
+    fn accumulate_vertex_normals(&self, weighting: NormalWeighting) -> HashMap<usize, Vec3> {
+        let mut face_keys: Vec<usize> = self.face.keys().copied().collect();
+        face_keys.sort();
+
+        let mut sums: HashMap<usize, Vec3> = HashMap::with_capacity(self.vertex.len());
+        for face_key in face_keys {
+            let vertices = &self.face[&face_key];
+            let Some(face_normal) = self.face_normal_coords(vertices) else {
+                continue;
+            };
+            let area = match weighting {
+                NormalWeighting::Area => self.face_area(face_key).unwrap_or(1.0),
+                _ => 1.0,
+            };
+            for &vertex_key in vertices {
+                let weight = match weighting {
+                    NormalWeighting::Angle => self
+                        .vertex_angle_in_face(vertex_key, face_key)
+                        .unwrap_or(1.0),
+                    _ => area,
+                };
+                *sums.entry(vertex_key).or_insert(Vec3::ZERO) += face_normal * weight;
             }
         }
-        normals
+        sums
+    }
+
+    /// Write unit vertex normals into the `nx`, `ny`, `nz` attributes of all vertices.
+    ///
+    /// Faces are visited once, unlike calling `vertex_normal` per vertex. Tangents are
+    /// generated as well when every vertex has `u` and `v` attributes, see
+    /// `compute_tangents_into_attributes`.
+    ///
+    /// # Returns
+    /// Number of vertices that received a normal; isolated vertices are left unchanged.
+    pub fn compute_vertex_normals_into_attributes(&mut self, weighting: NormalWeighting) -> usize {
+        let normals = self.vertex_normals_weighted(weighting);
+        for (key, normal) in &normals {
+            if let Some(vertex) = self.vertex.get_mut(key) {
+                vertex.set_normal(normal.x(), normal.y(), normal.z());
+            }
+        }
+        if !self.vertex.is_empty() && self.vertex.values().all(|v| v.uv().is_some()) {
+            self.compute_tangents_into_attributes();
+        }
+        normals.len()
+    }
+
+    /// Write per-vertex tangents from the `u` and `v` attributes into `tx`, `ty`, `tz`, `tw`.
+    ///
+    /// Tangents follow the direction of increasing `u`, are made orthogonal to the
+    /// stored vertex normal (or the area weighted one), and `tw` is the sign of the
+    /// bitangent so that `bitangent = tw * normal x tangent`, as normal maps expect.
+    /// Faces with a vertex lacking UVs are skipped.
+    ///
+    /// # Returns
+    /// Number of vertices that received a tangent.
+    pub fn compute_tangents_into_attributes(&mut self) -> usize {
+        let mut face_keys: Vec<usize> = self.face.keys().copied().collect();
+        face_keys.sort();
+
+        // Sum the UV gradients of the fan triangles around each vertex
+        let mut sums: HashMap<usize, (Vec3, Vec3)> = HashMap::new();
+        for face_key in face_keys {
+            let corners: Option<Vec<(usize, Vec3, [Scalar; 2])>> = self.face[&face_key]
+                .iter()
+                .map(|&key| {
+                    let vertex = self.vertex.get(&key)?;
+                    Some((key, vertex.coords(), vertex.uv()?))
+                })
+                .collect();
+            let Some(corners) = corners else {
+                continue;
+            };
+            for i in 1..corners.len().saturating_sub(1) {
+                let triangle = [&corners[0], &corners[i], &corners[i + 1]];
+                let (e1, e2) = (triangle[1].1 - triangle[0].1, triangle[2].1 - triangle[0].1);
+                let (du1, dv1) = (
+                    triangle[1].2[0] - triangle[0].2[0],
+                    triangle[1].2[1] - triangle[0].2[1],
+                );
+                let (du2, dv2) = (
+                    triangle[2].2[0] - triangle[0].2[0],
+                    triangle[2].2[1] - triangle[0].2[1],
+                );
+                let det = du1 * dv2 - du2 * dv1;
+                if det.abs() < Tolerance::ZERO_TOLERANCE {
+                    continue;
+                }
+                let r = 1.0 / det;
+                let tangent = (e1 * dv2 - e2 * dv1) * r;
+                let bitangent = (e2 * du1 - e1 * du2) * r;
+                for &(key, _, _) in triangle {
+                    let sum = sums.entry(key).or_insert((Vec3::ZERO, Vec3::ZERO));
+                    sum.0 += tangent;
+                    sum.1 += bitangent;
+                }
+            }
+        }
+
+        let computed = self.accumulate_vertex_normals(NormalWeighting::Area);
+        let mut count = 0;
+        for (key, (tangent, bitangent)) in sums {
+            let normal = match self.vertex[&key].normal() {
+                Some([nx, ny, nz]) => Vec3::new(nx, ny, nz),
+                None => computed.get(&key).copied().unwrap_or(Vec3::ZERO),
+            };
+            let Some(normal) = normal.normalized() else {
+                continue;
+            };
+            // Gram-Schmidt against the normal
+            let Some(tangent) = (tangent - normal * normal.dot(&tangent)).normalized() else {
+                continue;
+            };
+            let handedness = if normal.cross(&tangent).dot(&bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            if let Some(vertex) = self.vertex.get_mut(&key) {
+                vertex.set_tangent(tangent.x, tangent.y, tangent.z, handedness);
+                count += 1;
+            }
+        }
+        count
     }
 
     pub fn vertex_index(&self) -> HashMap<usize, usize> {
@@ -493,10 +712,93 @@ impl Mesh {
         (vertices, faces)
     }
 
-    pub fn from_polygons(polygons: Vec<Vec<Point>>, precision: Option<f64>) -> Self {
+    /// Triangles of all faces, fan-triangulated from the first vertex of each face.
+    pub fn triangles(&self) -> Vec<[Point; 3]> {
+        let mut face_keys: Vec<usize> = self.face.keys().copied().collect();
+        face_keys.sort();
+
+        let mut triangles = Vec::new();
+        for face_key in face_keys {
+            let points: Vec<Point> = self.face[&face_key]
+                .iter()
+                .filter_map(|v| self.vertex_position(*v))
+                .collect();
+            for i in 1..points.len().saturating_sub(1) {
+                triangles.push([points[0].clone(), points[i].clone(), points[i + 1].clone()]);
+            }
+        }
+        triangles
+    }
+
+    /// Flat arrays of the vertices and fan-triangulated faces, in local coordinates.
+    ///
+    /// Vertex `i` is the `i`-th of `to_vertices_and_faces`. Normals are the
+    /// stored vertex normals, or area weighted ones for vertices without, and
+    /// colors are `pointcolors`, or white when there is not one per vertex.
+    pub fn to_buffers(&self, layout: BufferLayout) -> MeshBuffers {
+        let (vertices, faces) = self.to_vertices_and_faces();
+        let mut keys: Vec<usize> = self.vertex.keys().copied().collect();
+        keys.sort();
+        let computed = self.vertex_normals();
+        let normals: Vec<[f32; 3]> = keys
+            .iter()
+            .map(|key| match self.vertex[key].normal() {
+                Some(n) => n.map(to_f32),
+                None => computed
+                    .get(key)
+                    .map_or([0.0; 3], |n| [n.x(), n.y(), n.z()].map(to_f32)),
+            })
+            .collect();
+        let has_colors = self.pointcolors.len() == vertices.len();
+        let colors: Vec<[f32; 4]> = (0..vertices.len())
+            .map(|i| {
+                if has_colors {
+                    let c = &self.pointcolors[i];
+                    [c.r, c.g, c.b, c.a].map(|v| v as f32 / 255.0)
+                } else {
+                    [1.0; 4]
+                }
+            })
+            .collect();
+        let positions: Vec<[f32; 3]> = vertices
+            .iter()
+            .map(|p| [p.x(), p.y(), p.z()].map(to_f32))
+            .collect();
+
+        let mut buffers = MeshBuffers {
+            indices: faces
+                .iter()
+                .flat_map(|face| {
+                    (1..face.len().saturating_sub(1))
+                        .flat_map(move |i| [face[0] as u32, face[i] as u32, face[i + 1] as u32])
+                })
+                .collect(),
+            ..Default::default()
+        };
+        match layout {
+            BufferLayout::Separate => {
+                buffers.positions = positions.concat();
+                buffers.normals = normals.concat();
+                buffers.colors = colors.concat();
+            }
+            BufferLayout::Interleaved => {
+                buffers
+                    .interleaved
+                    .reserve(vertices.len() * MeshBuffers::STRIDE);
+                for i in 0..vertices.len() {
+                    buffers.interleaved.extend_from_slice(&positions[i]);
+                    buffers.interleaved.extend_from_slice(&normals[i]);
+                    buffers.interleaved.extend_from_slice(&colors[i]);
+                }
+            }
+        }
+        buffers
+    }
+
+    pub fn from_polygons(polygons: Vec<Vec<Point>>, precision: Option<Scalar>) -> Self {
         let mut mesh = Mesh::new();
         let mut map_eps: HashMap<(i64, i64, i64), usize> = HashMap::new();
-        let mut map_exact: HashMap<(u64, u64, u64), usize> = HashMap::new();
+        let mut map_exact = HashMap::new();
         let eps = precision.unwrap_or(0.0);
         let use_eps = eps > 0.0;
 
@@ -542,58 +844,52 @@ impl Mesh {
     // Triangle BVH cache and ray casting
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    fn invalidate_triangle_bvh(&mut self) {
-        self.tri_bvh = None;
-        self.tri_tris.clear();
-        self.tri_vertices.clear();
+    /// Drop the cached triangle BVH.
+    ///
+    /// Mesh methods that move vertices or change faces do this themselves, call
+    /// it after editing `vertex` or `face` directly.
+    pub fn invalidate_triangle_bvh(&mut self) {
+        self.triangle_bvh = OnceCell::new();
     }
 
-    fn ensure_triangle_bvh(&mut self) {
-        if self.tri_bvh.is_some() && !self.tri_tris.is_empty() && !self.tri_vertices.is_empty() {
-            return;
-        }
-
-        let (vertices, faces) = self.to_vertices_and_faces();
-        let mut tris: Vec<[usize; 3]> = Vec::new();
-        let mut tri_boxes: Vec<BoundingBox> = Vec::new();
-
-        for face in faces {
-            if face.len() < 3 {
-                continue;
-            }
-            let v0 = face[0];
-            for i in 1..(face.len() - 1) {
-                let t = [v0, face[i], face[i + 1]];
-                tris.push(t);
-                let pts = [
-                    vertices[t[0]].clone(),
-                    vertices[t[1]].clone(),
-                    vertices[t[2]].clone(),
-                ];
-                tri_boxes.push(BoundingBox::from_points(&pts, 0.0));
+    /// The triangle BVH, built on first use.
+    ///
+    /// The cache fills through a shared reference, so meshes shared between a
+    /// session and its snapshots are queried without being copied.
+    pub(crate) fn triangle_bvh(&self) -> &TriangleBvh {
+        self.triangle_bvh.get_or_init(|| {
+            let (vertices, faces) = self.to_vertices_and_faces();
+            let mut triangles: Vec<[usize; 3]> = Vec::new();
+            let mut tri_boxes: Vec<BoundingBox> = Vec::new();
+
+            for face in faces {
+                if face.len() < 3 {
+                    continue;
+                }
+                let v0 = face[0];
+                for i in 1..(face.len() - 1) {
+                    let t = [v0, face[i], face[i + 1]];
+                    triangles.push(t);
+                    let pts = t.map(|i| vertices[i].as_vec3());
+                    tri_boxes.push(BoundingBox::from_points(&pts, 0.0));
+                }
             }
-        }
 
-        if tris.is_empty() {
-            self.tri_bvh = None;
-            self.tri_tris.clear();
-            self.tri_vertices = vertices; // keep for consistency
-            return;
-        }
-
-        let world_size = BVH::compute_world_size(&tri_boxes);
-        let bvh = BVH::from_boxes(&tri_boxes, world_size);
-        self.tri_vertices = vertices;
-        self.tri_tris = tris;
-        self.tri_bvh = Some(bvh);
+            let bvh = (!triangles.is_empty()).then(|| {
+                let world_size = BVH::compute_world_size(&tri_boxes);
+                BVH::from_boxes(&tri_boxes, world_size)
+            });
+            TriangleBvh {
+                bvh,
+                triangles,
+                vertices,
+            }
+        })
     }
 
-    pub fn ray_cast_bvh(&mut self, ray: &Line, epsilon: f64) -> Option<Point> {
-        self.ensure_triangle_bvh();
-        let bvh = match &self.tri_bvh {
-            Some(b) => b,
-            None => return None,
-        };
+    pub fn ray_cast_bvh(&self, ray: &Line, epsilon: Scalar) -> Option<Point> {
+        let cache = self.triangle_bvh();
+        let bvh = cache.bvh.as_ref()?;
 
         let origin = ray.start();
         let dir = ray.to_vector();
@@ -603,36 +899,281 @@ impl Mesh {
         }
         let dir_unit = Vector::new(dir.x() / len, dir.y() / len, dir.z() / len);
 
-        let mut candidate_ids: Vec<usize> = Vec::new();
-        bvh.ray_cast(&origin, &dir_unit, &mut candidate_ids, true);
-        if candidate_ids.is_empty() {
-            return None;
+        // Triangles are tested front to back and boxes beyond the best hit are skipped
+        let tris = &cache.triangles;
+        let vertices = &cache.vertices;
+        let (_, t) = bvh.ray_cast_nearest(&origin, &dir_unit, |idx| {
+            let tri = tris.get(idx)?;
+            let p = crate::intersection::ray_triangle(
+                ray,
+                &vertices[tri[0]],
+                &vertices[tri[1]],
+                &vertices[tri[2]],
+                epsilon,
+            )?;
+            let dx = p.x() - origin.x();
+            let dy = p.y() - origin.y();
+            let dz = p.z() - origin.z();
+            Some(dx * dir_unit.x() + dy * dir_unit.y() + dz * dir_unit.z())
+        })?;
+
+        Some(origin + dir_unit * t)
+    }
+
+    /// Ray cast against the mesh placed by its xform, without applying the xform to vertices.
+    ///
+    /// The ray is moved into the mesh's local frame, so the cached triangle BVH
+    /// stays valid when only the xform changes.
+    ///
+    /// # Returns
+    /// Closest hit point in world coordinates.
+    pub fn ray_cast_instance(&self, ray: &Line, epsilon: Scalar) -> Option<Point> {
+        if self.xform.is_identity() {
+            return self.ray_cast_bvh(ray, epsilon);
         }
+        let xform = self.xform.clone();
+        let inverse = xform.inverse()?;
+        let local_ray = Line::from_points(
+            &inverse.transformed_point(&ray.start()),
+            &inverse.transformed_point(&ray.end()),
+        );
+        let hit = self.ray_cast_bvh(&local_ray, epsilon)?;
+        Some(xform.transformed_point(&hit))
+    }
 
-        let mut best_t = f64::INFINITY;
-        let mut best_p: Option<Point> = None;
+    /// Every hit of a ray with the mesh placed by its xform.
+    ///
+    /// # Returns
+    /// Hit points in world coordinates sorted along the ray.
+    pub fn ray_cast_all_instance(&self, ray: &Line, epsilon: Scalar) -> Vec<Point> {
+        let xform = self.xform.clone();
+        let Some(inverse) = xform.inverse() else {
+            return Vec::new();
+        };
+        let local_ray = Line::from_points(
+            &inverse.transformed_point(&ray.start()),
+            &inverse.transformed_point(&ray.end()),
+        );
+        let cache = self.triangle_bvh();
+        let Some(bvh) = &cache.bvh else {
+            return Vec::new();
+        };
 
-        for idx in candidate_ids {
-            if idx >= self.tri_tris.len() {
-                continue;
-            }
-            let tri = self.tri_tris[idx];
-            let v0 = &self.tri_vertices[tri[0]];
-            let v1 = &self.tri_vertices[tri[1]];
-            let v2 = &self.tri_vertices[tri[2]];
-            if let Some(p) = crate::intersection::ray_triangle(ray, v0, v1, v2, epsilon) {
-                let dx = p.x() - origin.x();
-                let dy = p.y() - origin.y();
-                let dz = p.z() - origin.z();
-                let t = dx * dir_unit.x() + dy * dir_unit.y() + dz * dir_unit.z();
-                if t >= 0.0 && t < best_t {
-                    best_t = t;
-                    best_p = Some(p);
+        let origin = local_ray.start();
+        let mut hits: Vec<(Scalar, Point)> = bvh
+            .ray_cast_entries(&origin, &local_ray.to_vector())
+            .into_iter()
+            .filter_map(|(idx, _)| {
+                let tri = cache.triangles.get(idx)?;
+                let p = crate::intersection::ray_triangle(
+                    &local_ray,
+                    &cache.vertices[tri[0]],
+                    &cache.vertices[tri[1]],
+                    &cache.vertices[tri[2]],
+                    epsilon,
+                )?;
+                Some((p.distance(&origin), p))
+            })
+            .collect();
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+        hits.into_iter()
+            .map(|(_, p)| xform.transformed_point(&p))
+            .collect()
+    }
+
+    /// Find the closest point on the mesh surface, without applying the xform.
+    ///
+    /// # Returns
+    /// The closest point and the signed distance to it, positive on the side
+    /// the faces point to, or None for a mesh without faces.
+    pub fn closest_point(&self, point: &Point) -> Option<(Point, Scalar)> {
+        let cache = self.triangle_bvh();
+        let bvh = cache.bvh.as_ref()?;
+        let closest = |idx: usize| {
+            let [a, b, c] = cache.triangles.get(idx)?.map(|i| &cache.vertices[i]);
+            Some(closest_point_on_triangle(point, a, b, c))
+        };
+        let (idx, distance) = bvh.nearest_by(point, |idx| Some(closest(idx)?.distance(point)))?;
+        let hit = closest(idx)?;
+        let [a, b, c] = cache.triangles[idx].map(|i| &cache.vertices[i]);
+        let normal = (b.clone() - a.clone()).cross(&(c.clone() - a.clone()));
+        let side = (point.clone() - hit.clone()).dot(&normal);
+        Some((hit, if side < 0.0 { -distance } else { distance }))
+    }
+
+    /// Test whether two meshes placed by their xforms intersect or one contains the other.
+    ///
+    /// Triangles of `other` are moved into this mesh's local frame and tested
+    /// against the cached triangle BVH, so neither mesh has to be transformed.
+    /// Containment is only detected for closed meshes.
+    ///
+    /// # Arguments
+    /// * `other` - Second mesh
+    /// * `tolerance` - Inflation of the triangle boxes used to find candidate pairs
+    pub fn intersects_mesh(&self, other: &Mesh, tolerance: Scalar) -> bool {
+        let cache = self.triangle_bvh();
+        let (Some(bvh), Some(inverse)) = (&cache.bvh, self.xform.inverse()) else {
+            return false;
+        };
+        let to_local = &inverse * &other.xform;
+        let other_triangles: Vec<[Point; 3]> = other
+            .triangles()
+            .into_iter()
+            .map(|t| t.map(|p| to_local.transformed_point(&p)))
+            .collect();
+
+        for triangle in &other_triangles {
+            let query = BoundingBox::from_points(triangle, tolerance);
+            for index in bvh.query_box(&query) {
+                let [a, b, c] = cache.triangles[index];
+                let own = [
+                    cache.vertices[a].clone(),
+                    cache.vertices[b].clone(),
+                    cache.vertices[c].clone(),
+                ];
+                if triangle_triangle(&own, triangle).is_some() {
+                    return true;
                 }
             }
         }
 
-        best_p
+        // Without crossing triangles the meshes are either apart or nested
+        if let Some(vertex) = other_triangles.first().map(|t| t[0].clone()) {
+            if point_in_mesh(&vertex, self, tolerance) != Containment::Outside {
+                return true;
+            }
+        }
+        let Some(other_inverse) = other.xform.inverse() else {
+            return false;
+        };
+        let to_other = &other_inverse * &self.xform;
+        match cache.vertices.first() {
+            Some(vertex) => {
+                let vertex = to_other.transformed_point(vertex);
+                point_in_mesh(&vertex, other, tolerance) != Containment::Outside
+            }
+            None => false,
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Draft and Overhang Analysis
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Angle in radians between each face normal and a direction, without applying the xform.
+    ///
+    /// Faces above 90 degrees point away from the direction. For a mold pulled
+    /// along the direction, the draft of a face is its angle minus 90 degrees
+    /// and faces with negative draft are undercuts. Degenerate faces are left out.
+    pub fn face_angles_to_direction(&self, direction: &Vector) -> HashMap<usize, Scalar> {
+        let Some(direction) = direction.as_vec3().normalized() else {
+            return HashMap::new();
+        };
+        self.face
+            .iter()
+            .filter_map(|(&key, vertices)| {
+                let cos = self.face_normal_coords(vertices)?.dot(&direction);
+                Some((key, cos.clamp(-1.0, 1.0).acos()))
+            })
+            .collect()
+    }
+
+    /// Sorted keys of the faces whose normals are within an angle of pointing against a direction.
+    ///
+    /// For 3D printing along the build direction a threshold of 45 degrees
+    /// finds the faces that need supports, and for a mold pulled along the
+    /// direction a threshold of 90 degrees finds the undercuts.
+    ///
+    /// # Arguments
+    /// * `direction` - Build or pull direction
+    /// * `threshold` - Largest angle in radians between a face normal and `-direction`
+    pub fn overhang_faces(&self, direction: &Vector, threshold: Scalar) -> Vec<usize> {
+        let limit = crate::scalar::consts::PI - threshold;
+        let mut faces: Vec<usize> = self
+            .face_angles_to_direction(direction)
+            .into_iter()
+            .filter_map(|(key, angle)| (angle > limit).then_some(key))
+            .collect();
+        faces.sort_unstable();
+        faces
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Sections
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Area, centroid and principal second moments of the cut by a plane, without applying the xform.
+    ///
+    /// The mesh must be closed with consistently oriented faces. Each triangle
+    /// crossing the plane adds one edge of the section outline, and the area
+    /// integrals are summed over those edges by Green's theorem, so sections
+    /// with several loops or holes need no loop tracing.
+    ///
+    /// # Returns
+    /// None if the plane misses the mesh or only touches it.
+    pub fn section_properties(&self, plane: &Plane) -> Option<SectionProperties> {
+        let origin = plane.origin().as_vec3();
+        let (x_axis, y_axis) = (plane.x_axis().as_vec3(), plane.y_axis().as_vec3());
+        let normal = x_axis.cross(&y_axis);
+        let to_plane = |p: Vec3| [(p - origin).dot(&x_axis), (p - origin).dot(&y_axis)];
+
+        // Integrals of 1, u, v, u², v² and uv over the section in plane coordinates
+        let mut sums = [0.0; 6];
+        for triangle in self.triangles() {
+            let p = triangle.map(|p| p.as_vec3());
+            let d = p.map(|p| (p - origin).dot(&normal));
+            let crossings: Vec<Vec3> = (0..3)
+                .filter(|&i| (d[i] >= 0.0) != (d[(i + 1) % 3] >= 0.0))
+                .map(|i| {
+                    let j = (i + 1) % 3;
+                    p[i] + (p[j] - p[i]) * (d[i] / (d[i] - d[j]))
+                })
+                .collect();
+            let [start, end] = crossings[..] else {
+                continue;
+            };
+            // Walk the outline with the solid on the left, opposite to the face normal
+            let tangent = normal.cross(&(p[1] - p[0]).cross(&(p[2] - p[0])));
+            let (start, end) = if (end - start).dot(&tangent) < 0.0 {
+                (end, start)
+            } else {
+                (start, end)
+            };
+            let ([u0, v0], [u1, v1]) = (to_plane(start), to_plane(end));
+            let cross = u0 * v1 - u1 * v0;
+            sums[0] += cross / 2.0;
+            sums[1] += (u0 + u1) * cross / 6.0;
+            sums[2] += (v0 + v1) * cross / 6.0;
+            sums[3] += (u0 * u0 + u0 * u1 + u1 * u1) * cross / 12.0;
+            sums[4] += (v0 * v0 + v0 * v1 + v1 * v1) * cross / 12.0;
+            sums[5] += (2.0 * u0 * v0 + u0 * v1 + u1 * v0 + 2.0 * u1 * v1) * cross / 24.0;
+        }
+
+        // Inward facing faces trace the outline clockwise
+        if sums[0] < 0.0 {
+            sums = sums.map(|s| -s);
+        }
+        let [area, su, sv, suu, svv, suv] = sums;
+        if area <= Tolerance::ZERO_TOLERANCE {
+            return None;
+        }
+        let (cu, cv) = (su / area, sv / area);
+        let i_uu = svv - area * cv * cv; // About the axis along u
+        let i_vv = suu - area * cu * cu;
+        let product = suv - area * cu * cv;
+
+        // Eigenvalues of the tensor [[i_uu, -product], [-product, i_vv]]
+        let mean = (i_uu + i_vv) / 2.0;
+        let radius = ((i_uu - i_vv) / 2.0).hypot(product);
+        let angle = (-2.0 * product).atan2(i_uu - i_vv) / 2.0;
+        let (sin, cos) = angle.sin_cos();
+        let major = x_axis * cos + y_axis * sin;
+        Some(SectionProperties {
+            area,
+            centroid: (origin + x_axis * cu + y_axis * cv).into(),
+            principal_moments: [mean + radius, mean - radius],
+            principal_axes: [major.into(), normal.cross(&major).into()],
+        })
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
@@ -651,13 +1192,30 @@ impl Mesh {
         }
     }
 
+    /// Paint faces by key, for example the result of `overhang_faces`.
+    ///
+    /// `facecolors` holds one color per face in ascending key order and is
+    /// filled with white first if it does not.
+    pub fn set_faces_color(&mut self, face_keys: &[usize], color: Color) {
+        let mut keys: Vec<usize> = self.face.keys().copied().collect();
+        keys.sort_unstable();
+        if self.facecolors.len() != keys.len() {
+            self.facecolors = vec![Color::white(); keys.len()];
+        }
+        for key in face_keys {
+            if let Ok(index) = keys.binary_search(key) {
+                self.facecolors[index] = color.clone();
+            }
+        }
+    }
+
     pub fn set_edge_color(&mut self, index: usize, color: Color) {
         if index < self.linecolors.len() {
             self.linecolors[index] = color;
         }
     }
 
-    pub fn set_edge_width(&mut self, index: usize, width: f64) {
+    pub fn set_edge_width(&mut self, index: usize, width: Scalar) {
         if index < self.widths.len() {
             self.widths[index] = width;
         }
@@ -668,14 +1226,13 @@ impl Mesh {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn transform(&mut self) {
+        // Coordinates are transformed directly, a Point per vertex would allocate a GUID
         let xform = self.xform.clone();
-        for v in self.vertex.values_mut() {
-            let mut pt = Point::new(v.x, v.y, v.z);
-            xform.transform_point(&mut pt);
-            v.x = pt.x();
-            v.y = pt.y();
-            v.z = pt.z();
-        }
+        let apply = |v: &mut VertexData| [v.x, v.y, v.z] = xform.apply_to_point([v.x, v.y, v.z]);
+        #[cfg(feature = "parallel")]
+        self.vertex.par_iter_mut().for_each(|(_, v)| apply(v));
+        #[cfg(not(feature = "parallel"))]
+        self.vertex.values_mut().for_each(apply);
         self.xform = Xform::identity();
         self.invalidate_triangle_bvh();
     }
@@ -816,24 +1373,171 @@ impl Mesh {
         }
 
         if let Some(widths) = data.get("widths").and_then(|v| v.as_array()) {
-            mesh.widths = widths.iter().filter_map(|v| v.as_f64()).collect();
+            mesh.widths = widths
+                .iter()
+                .filter_map(|v| v.as_f64())
+                .map(|v| v as Scalar)
+                .collect();
         }
 
         Some(mesh)
     }
 
-    pub fn to_json(&self, filename: &str) -> std::io::Result<()> {
+    pub fn to_json(&self, filename: &str) -> Result<(), SessionError> {
         let data = self.jsondump();
-        std::fs::write(filename, serde_json::to_string_pretty(&data)?)
+        std::fs::write(filename, serde_json::to_string_pretty(&data)?)?;
+        Ok(())
     }
 
-    pub fn from_json(filename: &str) -> std::io::Result<Self> {
+    pub fn from_json(filename: &str) -> Result<Self, SessionError> {
         let content = std::fs::read_to_string(filename)?;
-        let data: serde_json::Value = serde_json::from_str(&content)?;
+        let data: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| SessionError::from(e).with_path(filename))?;
         Self::jsonload(&data).ok_or_else(|| {
-            std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid mesh data")
+            SessionError::InvalidGeometry(format!("invalid mesh data in {filename}"))
         })
     }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Binary
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Serializes the Mesh to a compact little-endian blob, without serde.
+    ///
+    /// The layout of version 1 is:
+    /// * `SMSH` magic and `u32` version
+    /// * `u32` counts: vertices, faces, face indices, GUID bytes and name bytes
+    /// * GUID and name as UTF-8
+    /// * 16 `f64` xform values in column-major order
+    /// * `f64` `x y z` per vertex, in `to_vertices_and_faces` order
+    /// * `u32` vertex count per face, then the `u32` vertex indices of all faces
+    ///
+    /// Vertex and face attributes, colors and widths are not written.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (vertices, faces) = self.to_vertices_and_faces();
+        let index_count: usize = faces.iter().map(Vec::len).sum();
+        let mut bytes = Vec::with_capacity(
+            32 + self.guid.len()
+                + self.name.len()
+                + 8 * (16 + 3 * vertices.len())
+                + 4 * (faces.len() + index_count),
+        );
+        bytes.extend_from_slice(&MESH_BYTES_MAGIC);
+        for value in [
+            MESH_BYTES_VERSION,
+            vertices.len() as u32,
+            faces.len() as u32,
+            index_count as u32,
+            self.guid.len() as u32,
+            self.name.len() as u32,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(self.guid.as_bytes());
+        bytes.extend_from_slice(self.name.as_bytes());
+        for value in self.xform.m {
+            bytes.extend_from_slice(&to_f64(value).to_le_bytes());
+        }
+        for point in &vertices {
+            for value in [point.x(), point.y(), point.z()] {
+                bytes.extend_from_slice(&to_f64(value).to_le_bytes());
+            }
+        }
+        for face in &faces {
+            bytes.extend_from_slice(&(face.len() as u32).to_le_bytes());
+        }
+        for &index in faces.iter().flatten() {
+            bytes.extend_from_slice(&(index as u32).to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a Mesh from a blob written by `to_bytes`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The blob, starting with the `SMSH` magic
+    ///
+    /// # Returns
+    /// The mesh, or an error for unknown magic or versions, truncated data and
+    /// faces that reference missing vertices or cannot be added.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SessionError> {
+        let mut offset = 0;
+        let mut take = |len: usize| -> Result<&[u8], SessionError> {
+            let chunk = bytes.get(offset..offset + len).ok_or_else(|| {
+                SessionError::SchemaMismatch("mesh bytes are truncated".to_string())
+            })?;
+            offset += len;
+            Ok(chunk)
+        };
+        if take(4)? != MESH_BYTES_MAGIC {
+            return Err(SessionError::SchemaMismatch(
+                "missing SMSH mesh signature".to_string(),
+            ));
+        }
+        let mut header = [0usize; 6];
+        for value in header.iter_mut() {
+            *value = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+        }
+        let [version, vertex_count, face_count, index_count, guid_len, name_len] = header;
+        if version != MESH_BYTES_VERSION as usize {
+            return Err(SessionError::SchemaMismatch(format!(
+                "unsupported mesh bytes version {version}"
+            )));
+        }
+        let text = |chunk: &[u8]| String::from_utf8(chunk.to_vec());
+        let guid = text(take(guid_len)?)?;
+        let name = text(take(name_len)?)?;
+        let f64s = |chunk: &[u8]| -> Vec<Scalar> {
+            chunk
+                .chunks_exact(8)
+                .map(|b| f64::from_le_bytes(b.try_into().unwrap()) as Scalar)
+                .collect()
+        };
+        let u32s = |chunk: &[u8]| -> Vec<usize> {
+            chunk
+                .chunks_exact(4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+                .collect()
+        };
+        let matrix = f64s(take(16 * 8)?);
+        let coordinates = f64s(take(vertex_count * 3 * 8)?);
+        let face_sizes = u32s(take(face_count * 4)?);
+        let indices = u32s(take(index_count * 4)?);
+        if face_sizes.iter().sum::<usize>() != index_count {
+            return Err(SessionError::InvalidGeometry(format!(
+                "face sizes do not add up to {index_count} indices"
+            )));
+        }
+        if let Some(index) = indices.iter().find(|&&i| i >= vertex_count) {
+            return Err(SessionError::InvalidGeometry(format!(
+                "face index {index} is out of range for {vertex_count} vertices"
+            )));
+        }
+
+        let mut mesh = Mesh::new();
+        mesh.guid = guid;
+        mesh.name = name;
+        mesh.xform.m.copy_from_slice(&matrix);
+        let keys: Vec<usize> = coordinates
+            .chunks_exact(3)
+            .map(|c| mesh.add_vertex(Point::new(c[0], c[1], c[2]), None))
+            .collect();
+        let mut start = 0;
+        for size in face_sizes {
+            let face = indices[start..start + size]
+                .iter()
+                .map(|&i| keys[i])
+                .collect();
+            start += size;
+            if mesh.add_face(face, None).is_none() {
+                return Err(SessionError::InvalidGeometry(format!(
+                    "face {} is degenerate",
+                    mesh.number_of_faces()
+                )));
+            }
+        }
+        Ok(mesh)
+    }
 }
 
 #[cfg(test)]
@@ -3,6 +3,8 @@ mod tests {
     use crate::encoders::{json_dump, json_load};
     use crate::mesh::Mesh;
     use crate::point::Point;
+    use crate::Scalar;
+    use crate::{BufferLayout, Color, Line, MeshBuffers, NormalWeighting, Plane, Vector, Xform};
 
     #[test]
     fn test_mesh_constructor() {
@@ -53,4 +55,365 @@ mod tests {
             original.number_of_vertices()
         );
     }
+
+    #[test]
+    fn test_closest_point() {
+        let p = |x: Scalar, y: Scalar| Point::new(x, y, 0.0);
+        let polygons = (0..10)
+            .flat_map(|i| {
+                (0..10).map(move |j| {
+                    let (x, y) = (i as Scalar, j as Scalar);
+                    vec![p(x, y), p(x + 1.0, y), p(x + 1.0, y + 1.0), p(x, y + 1.0)]
+                })
+            })
+            .collect();
+        let mesh = Mesh::from_polygons(polygons, None);
+
+        let (hit, distance) = mesh.closest_point(&Point::new(2.3, 4.1, 0.5)).unwrap();
+        assert!(hit.distance(&p(2.3, 4.1)) < 1e-9);
+        assert!((distance - 0.5).abs() < 1e-9);
+
+        let (hit, distance) = mesh.closest_point(&Point::new(13.0, 5.5, -4.0)).unwrap();
+        assert!(hit.distance(&p(10.0, 5.5)) < 1e-9);
+        assert!((distance + 5.0).abs() < 1e-9);
+
+        assert!(Mesh::new().closest_point(&p(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_section_properties() {
+        // Closed box with outward faces, corners at min and max
+        let cuboid = |min: [Scalar; 3], max: [Scalar; 3]| {
+            let c = |i: usize| {
+                let pick = |axis: usize| {
+                    if i >> axis & 1 == 1 {
+                        max[axis]
+                    } else {
+                        min[axis]
+                    }
+                };
+                Point::new(pick(0), pick(1), pick(2))
+            };
+            [
+                [0, 2, 3, 1],
+                [4, 5, 7, 6],
+                [0, 1, 5, 4],
+                [2, 6, 7, 3],
+                [0, 4, 6, 2],
+                [1, 3, 7, 5],
+            ]
+            .iter()
+            .map(|f| f.map(c).to_vec())
+            .collect::<Vec<_>>()
+        };
+        let close = |a: Scalar, b: Scalar| (a - b).abs() < 1e-4;
+        let mesh = Mesh::from_polygons(cuboid([0.0, 0.0, 0.0], [2.0, 4.0, 1.0]), None);
+
+        // A 2 x 4 rectangle, b h³ / 12 about each centroidal axis
+        let cut = Plane::from_point_normal(Point::new(5.0, 5.0, 0.5), Vector::new(0.0, 0.0, 1.0));
+        let section = mesh.section_properties(&cut).unwrap();
+        assert!(close(section.area, 8.0));
+        assert!(section.centroid.distance(&Point::new(1.0, 2.0, 0.5)) < 1e-4);
+        assert!(close(section.principal_moments[0], 2.0 * 64.0 / 12.0));
+        assert!(close(section.principal_moments[1], 4.0 * 8.0 / 12.0));
+        assert!(close(section.principal_axes[0].x().abs(), 1.0));
+        assert!(close(section.principal_axes[1].y().abs(), 1.0));
+
+        // Plane axes at 45 degrees and reversed faces give the same section
+        let tilted = Plane::new(
+            Point::new(0.0, 0.0, 0.5),
+            Vector::new(1.0, 1.0, 0.0),
+            Vector::new(-1.0, 1.0, 0.0),
+        );
+        let mut polygons = cuboid([0.0, 0.0, 0.0], [2.0, 4.0, 1.0]);
+        polygons.iter_mut().for_each(|p| p.reverse());
+        let reversed = Mesh::from_polygons(polygons, None);
+        let rotated = reversed.section_properties(&tilted).unwrap();
+        assert!(close(rotated.area, 8.0));
+        assert!(rotated.centroid.distance(&section.centroid) < 1e-4);
+        assert!(close(
+            rotated.principal_moments[0],
+            section.principal_moments[0]
+        ));
+        assert!(close(rotated.principal_axes[0].x().abs(), 1.0));
+
+        // Separate parts add up, a vertical cut through two boxes side by side
+        let mut polygons = cuboid([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+        polygons.extend(cuboid([3.0, 0.0, 0.0], [4.0, 1.0, 2.0]));
+        let pair = Mesh::from_polygons(polygons, None);
+        let cut = Plane::from_point_normal(Point::new(0.0, 0.5, 0.0), Vector::new(0.0, 1.0, 0.0));
+        let both = pair.section_properties(&cut).unwrap();
+        assert!(close(both.area, 3.0));
+        assert!(both.centroid.distance(&Point::new(2.5, 0.5, 2.5 / 3.0)) < 1e-4);
+
+        let miss = Plane::from_point_normal(Point::new(0.0, 0.0, 9.0), Vector::new(0.0, 0.0, 1.0));
+        assert!(mesh.section_properties(&miss).is_none());
+    }
+
+    #[test]
+    fn test_overhang_faces() {
+        // Unit cube from polygons, faces 0 and 1 point down and up
+        let c = |i: usize| {
+            Point::new(
+                (i & 1) as Scalar,
+                (i >> 1 & 1) as Scalar,
+                (i >> 2 & 1) as Scalar,
+            )
+        };
+        let polygons = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ]
+        .iter()
+        .map(|f| f.map(c).to_vec())
+        .collect();
+        let mut mesh = Mesh::from_polygons(polygons, None);
+        let mut keys: Vec<usize> = mesh.face.keys().copied().collect();
+        keys.sort_unstable();
+        let (bottom, top) = (keys[0], keys[1]);
+
+        let up = Vector::new(0.0, 0.0, 2.0);
+        let angles = mesh.face_angles_to_direction(&up);
+        assert_eq!(angles.len(), 6);
+        assert!(angles[&top].abs() < 1e-6);
+        assert!((angles[&bottom] - crate::scalar::consts::PI).abs() < 1e-6);
+        assert!((angles[&keys[2]] - crate::scalar::consts::FRAC_PI_2).abs() < 1e-6);
+
+        let quarter = crate::scalar::consts::FRAC_PI_4;
+        assert_eq!(mesh.overhang_faces(&up, quarter), vec![bottom]);
+        assert_eq!(mesh.overhang_faces(&-&up, quarter), vec![top]);
+        // Walls are not undercuts, tilting the pull direction makes two of them
+        assert_eq!(mesh.overhang_faces(&up, 1.5), vec![bottom]);
+        let tilted = Vector::new(1.0, 1.0, 1.0);
+        assert_eq!(mesh.overhang_faces(&tilted, 1.5).len(), 3);
+        assert!(mesh
+            .overhang_faces(&Vector::new(0.0, 0.0, 0.0), 1.5)
+            .is_empty());
+
+        let red = Color::new(255, 0, 0, 255);
+        mesh.facecolors.clear();
+        mesh.set_faces_color(&[bottom, 999], red.clone());
+        assert_eq!(mesh.facecolors.len(), 6);
+        assert_eq!(mesh.facecolors[0], red);
+        assert_eq!(mesh.facecolors[1], Color::white());
+    }
+
+    #[test]
+    fn test_vertex_faces_and_boundary() {
+        // Two triangles sharing the edge 2-3
+        let mut mesh = Mesh::new();
+        let keys: Vec<usize> = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (2.0, 1.0)]
+            .iter()
+            .map(|&(x, y)| mesh.add_vertex(Point::new(x, y, 0.0), None))
+            .collect();
+        let first = mesh
+            .add_face(vec![keys[0], keys[1], keys[2]], None)
+            .unwrap();
+        let second = mesh
+            .add_face(vec![keys[0], keys[2], keys[3]], None)
+            .unwrap();
+        assert_eq!(mesh.vertex_faces(keys[0]), vec![first, second]);
+        assert_eq!(mesh.vertex_faces(keys[1]), vec![first]);
+        assert!(mesh.vertex_faces(keys[4]).is_empty());
+        assert!(mesh.is_vertex_on_boundary(keys[0]));
+        assert!(!mesh.is_vertex_on_boundary(keys[4]));
+
+        // Adding a face refreshes the cached adjacency
+        let third = mesh
+            .add_face(vec![keys[1], keys[4], keys[2]], None)
+            .unwrap();
+        assert_eq!(mesh.vertex_faces(keys[2]), vec![first, second, third]);
+        assert_eq!(mesh.vertex_faces(keys[4]), vec![third]);
+        let normal = mesh.vertex_normal(keys[4]).unwrap();
+        assert!((normal.z() - 1.0).abs() < 1e-9);
+
+        mesh.face.remove(&third);
+        mesh.invalidate_adjacency();
+        assert_eq!(mesh.vertex_faces(keys[2]), vec![first, second]);
+        mesh.clear();
+        assert!(mesh.vertex_faces(keys[0]).is_empty());
+    }
+
+    #[test]
+    fn test_compute_vertex_normals_and_tangents() {
+        let c = |i: usize| {
+            Point::new(
+                (i & 1) as Scalar,
+                (i >> 1 & 1) as Scalar,
+                (i >> 2 & 1) as Scalar,
+            )
+        };
+        let polygons = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ]
+        .iter()
+        .map(|f| f.map(c).to_vec())
+        .collect();
+        let mut cube = Mesh::from_polygons(polygons, None);
+        for weighting in [NormalWeighting::Area, NormalWeighting::Angle] {
+            assert_eq!(cube.compute_vertex_normals_into_attributes(weighting), 8);
+            for (key, vertex) in &cube.vertex {
+                let [nx, ny, nz] = vertex.normal().unwrap();
+                let expected = cube.vertex_normal_weighted(*key, weighting).unwrap();
+                assert!((nx - expected.x()).abs() < 1e-9);
+                assert!((ny - expected.y()).abs() < 1e-9);
+                assert!((nz - expected.z()).abs() < 1e-9);
+                // Corners of a cube point away from its center
+                assert!((nx - (vertex.x - 0.5) * 2.0 / (3.0 as Scalar).sqrt()).abs() < 1e-6);
+                assert!(vertex.tangent().is_none());
+                assert!(!cube.is_vertex_on_boundary(*key));
+            }
+        }
+
+        // UVs follow x and y, the second square has them mirrored in u
+        let p = |x: Scalar, y: Scalar| Point::new(x, y, 0.0);
+        let square = || vec![vec![p(0.0, 0.0), p(1.0, 0.0), p(1.0, 1.0), p(0.0, 1.0)]];
+        for (mirror, handedness) in [(false, 1.0), (true, -1.0)] {
+            let mut mesh = Mesh::from_polygons(square(), None);
+            for vertex in mesh.vertex.values_mut() {
+                let u = if mirror { 1.0 - vertex.x } else { vertex.x };
+                vertex.set_uv(u, vertex.y);
+            }
+            mesh.compute_vertex_normals_into_attributes(NormalWeighting::Area);
+            for vertex in mesh.vertex.values() {
+                assert_eq!(vertex.normal(), Some([0.0, 0.0, 1.0]));
+                let [tx, ty, tz, tw] = vertex.tangent().unwrap();
+                assert!((tx - handedness).abs() < 1e-9 && ty.abs() < 1e-9 && tz.abs() < 1e-9);
+                assert_eq!(tw, handedness);
+            }
+        }
+
+        // Faces without UVs get no tangents
+        let mut mesh = Mesh::from_polygons(square(), None);
+        assert_eq!(mesh.compute_tangents_into_attributes(), 0);
+    }
+
+    #[test]
+    fn test_ray_cast_all_instance() {
+        let p = |x: Scalar, y: Scalar| Point::new(x, y, 0.0);
+        let mut mesh = Mesh::from_polygons(
+            vec![vec![p(-1.0, -1.0), p(1.0, -1.0), p(1.0, 1.0), p(-1.0, 1.0)]],
+            None,
+        );
+        let mut upper = mesh.clone();
+        upper.xform = Xform::translation(0.0, 0.0, 2.0);
+        let ray = Line::new(0.2, 0.3, 5.0, 0.2, 0.3, -5.0);
+        let hits = upper.ray_cast_all_instance(&ray, 1e-9);
+        assert_eq!(hits.len(), 1);
+        assert!((hits[0].z() - 2.0).abs() < 1e-9);
+
+        // Two layers of the same mesh are both reported, nearest first
+        let triangles = mesh.triangles();
+        for triangle in triangles {
+            let lifted: Vec<Point> = triangle
+                .iter()
+                .map(|v| Point::new(v.x(), v.y(), v.z() + 1.0))
+                .collect();
+            let keys: Vec<usize> = lifted
+                .into_iter()
+                .map(|v| mesh.add_vertex(v, None))
+                .collect();
+            mesh.add_face(keys, None);
+        }
+        let hits = mesh.ray_cast_all_instance(&ray, 1e-9);
+        assert_eq!(hits.len(), 2);
+        assert!((hits[0].z() - 1.0).abs() < 1e-9);
+        assert!(hits[1].z().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mesh_to_buffers() {
+        let p = |x: Scalar, y: Scalar| Point::new(x, y, 0.0);
+        let mut mesh = Mesh::from_polygons(
+            vec![vec![p(0.0, 0.0), p(1.0, 0.0), p(1.0, 1.0), p(0.0, 1.0)]],
+            None,
+        );
+        mesh.pointcolors[2] = Color::new(255, 0, 0, 255);
+
+        let buffers = mesh.to_buffers(BufferLayout::Separate);
+        assert_eq!(buffers.positions.len(), 12);
+        assert_eq!(buffers.positions[3..6], [1.0, 0.0, 0.0]);
+        assert_eq!(buffers.indices, vec![0, 1, 2, 0, 2, 3]);
+        for normal in buffers.normals.chunks_exact(3) {
+            assert_eq!(normal, [0.0, 0.0, 1.0]);
+        }
+        assert_eq!(buffers.colors.len(), 16);
+        assert_eq!(buffers.colors[8..12], [1.0, 0.0, 0.0, 1.0]);
+        assert!(buffers.interleaved.is_empty());
+
+        let interleaved = mesh.to_buffers(BufferLayout::Interleaved);
+        assert!(interleaved.positions.is_empty());
+        assert_eq!(interleaved.indices, buffers.indices);
+        assert_eq!(interleaved.interleaved.len(), 4 * MeshBuffers::STRIDE);
+        assert_eq!(
+            interleaved.interleaved[2 * MeshBuffers::STRIDE..3 * MeshBuffers::STRIDE],
+            [1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_mesh_bytes_roundtrip() {
+        let p = |x: Scalar, y: Scalar, z: Scalar| Point::new(x, y, z);
+        let mut mesh = Mesh::from_polygons(
+            vec![
+                vec![
+                    p(0.0, 0.0, 0.0),
+                    p(1.0, 0.0, 0.0),
+                    p(1.0, 1.0, 0.0),
+                    p(0.0, 1.0, 0.0),
+                ],
+                vec![p(1.0, 0.0, 0.0), p(2.0, 0.0, 0.5), p(1.0, 1.0, 0.0)],
+            ],
+            None,
+        );
+        mesh.name = "bracket".to_string();
+        mesh.xform = Xform::translation(1.0, 2.0, 3.0);
+
+        let bytes = mesh.to_bytes();
+        assert_eq!(bytes[..4], *b"SMSH");
+        let loaded = Mesh::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.guid, mesh.guid);
+        assert_eq!(loaded.name, "bracket");
+        assert_eq!(loaded.xform.m, mesh.xform.m);
+        assert_eq!(loaded.to_vertices_and_faces(), mesh.to_vertices_and_faces());
+        assert_eq!(loaded.to_bytes(), bytes);
+
+        let empty = Mesh::from_bytes(&Mesh::new().to_bytes()).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_mesh_from_bytes_errors() {
+        let mesh = Mesh::from_polygons(
+            vec![vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ]],
+            None,
+        );
+        let bytes = mesh.to_bytes();
+        let error = |bytes: &[u8]| Mesh::from_bytes(bytes).unwrap_err().to_string();
+
+        assert!(error(b"PLY\n").contains("signature"));
+        assert!(error(&bytes[..bytes.len() - 1]).contains("truncated"));
+
+        let mut version = bytes.clone();
+        version[4] = 9;
+        assert!(error(&version).contains("version 9"));
+
+        let mut index = bytes.clone();
+        let last = index.len() - 4;
+        index[last..].copy_from_slice(&7u32.to_le_bytes());
+        assert!(error(&index).contains("out of range"));
+    }
 }
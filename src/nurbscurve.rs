@@ -2,6 +2,7 @@ use crate::point::Point;
 use crate::vector::Vector;
 use crate::plane::Plane;
 use crate::tolerance::Tolerance;
+use crate::Scalar;
 
 /// Non-Uniform Rational B-Spline (NURBS) curve implementation
 /// 
@@ -14,8 +15,8 @@ pub struct NurbsCurve {
     pub m_order: usize,         // Order = degree + 1 (order >= 2)
     pub m_cv_count: usize,      // Number of control vertices (>= order)
     pub m_cv_stride: usize,     // Stride between control vertices in m_cv array
-    pub m_knot: Vec<f64>,       // Knot vector (length = m_order + m_cv_count - 2)
-    pub m_cv: Vec<f64>,         // Control vertex data (homogeneous if rational)
+    pub m_knot: Vec<Scalar>,    // Knot vector (length = m_order + m_cv_count - 2)
+    pub m_cv: Vec<Scalar>,      // Control vertex data (homogeneous if rational)
 }
 
 impl NurbsCurve {
@@ -55,7 +56,7 @@ impl NurbsCurve {
         dimension: usize,
         order: usize,
         points: &[Point],
-        knot_delta: f64,
+        knot_delta: Scalar,
     ) -> Option<Self> {
         let point_count = points.len();
         
@@ -105,7 +106,7 @@ impl NurbsCurve {
         dimension: usize,
         order: usize,
         points: &[Point],
-        knot_delta: f64,
+        knot_delta: Scalar,
     ) -> Option<Self> {
         let point_count = points.len();
         
@@ -134,12 +135,281 @@ impl NurbsCurve {
         // Create uniform knot vector
         let knot_count = order + cv_count - 2;
         for i in 0..knot_count {
-            curve.m_knot[i] = i as f64 * knot_delta;
+            curve.m_knot[i] = i as Scalar * knot_delta;
         }
 
         Some(curve)
     }
 
+    /// Fit a clamped NURBS curve to points by least-squares approximation
+    ///
+    /// # Arguments
+    /// * `points` - Points to approximate, e.g. a scanned polyline
+    /// * `degree` - Degree of the curve (order = degree + 1)
+    /// * `cv_count` - Number of control points, from `degree + 1` up to the
+    ///   number of points; fewer control points smooth out more noise
+    /// * `smoothness` - Weight of the second-difference penalty on control points,
+    ///   0.0 gives a plain least-squares fit
+    ///
+    /// The curve interpolates the first and last point.
+    pub fn fit(
+        points: &[Point],
+        degree: usize,
+        cv_count: usize,
+        smoothness: Scalar,
+    ) -> Option<Self> {
+        Self::fit_with_tangents(points, degree, cv_count, smoothness, None, None)
+    }
+
+    /// Fit a clamped NURBS curve to points with optional end-tangent constraints
+    ///
+    /// # Arguments
+    /// * `points` - Points to approximate
+    /// * `degree` - Degree of the curve (order = degree + 1)
+    /// * `cv_count` - Number of control points, from `degree + 1` up to the
+    ///   number of points, and at least 4 with both tangents
+    /// * `smoothness` - Weight of the second-difference penalty on control points
+    /// * `start_tangent` - Optional tangent direction at the start point
+    /// * `end_tangent` - Optional tangent direction at the end point
+    ///
+    /// Implementation follows Piegl & Tiller global approximation: chord length
+    /// parameters, averaged interior knots, and fixed end control points. The
+    /// normal equations are banded and solved by a banded Cholesky factorization,
+    /// so the fit runs in linear time in the number of points.
+    pub fn fit_with_tangents(
+        points: &[Point],
+        degree: usize,
+        cv_count: usize,
+        smoothness: Scalar,
+        start_tangent: Option<&Vector>,
+        end_tangent: Option<&Vector>,
+    ) -> Option<Self> {
+        let order = degree + 1;
+        let point_count = points.len();
+        let fixed_count = 2 + start_tangent.is_some() as usize + end_tangent.is_some() as usize;
+        if degree < 1
+            || point_count < order
+            || cv_count < order.max(fixed_count)
+            || cv_count > point_count.max(fixed_count)
+            || smoothness < 0.0
+        {
+            return None;
+        }
+
+        // Chord length parameters normalized to [0, 1]
+        let mut params = vec![0.0; point_count];
+        for i in 1..point_count {
+            params[i] = params[i - 1] + points[i].distance(&points[i - 1]);
+        }
+        let total_length = params[point_count - 1];
+        if total_length < Tolerance::ZERO_TOLERANCE {
+            return None;
+        }
+        for t in params.iter_mut() {
+            *t /= total_length;
+        }
+
+        // Full clamped knot vector with averaged interior knots
+        let mut full_knots = vec![0.0; cv_count + order];
+        for knot in full_knots.iter_mut().skip(cv_count) {
+            *knot = 1.0;
+        }
+        let d = point_count as Scalar / (cv_count - degree) as Scalar;
+        for j in 1..(cv_count - degree) {
+            let i = (j as Scalar * d) as usize;
+            let alpha = j as Scalar * d - i as Scalar;
+            let i = i.clamp(1, point_count - 1);
+            full_knots[j + degree] = (1.0 - alpha) * params[i - 1] + alpha * params[i];
+        }
+
+        let mut curve = Self::new();
+        if !curve.initialize_curve(3, false, order, cv_count) {
+            return None;
+        }
+        curve.m_knot = full_knots[1..cv_count + order - 1].to_vec();
+
+        // Fixed control points: interpolated ends and tangent-derived neighbours
+        let first = &points[0];
+        let last = &points[point_count - 1];
+        let mut fixed: Vec<Option<[Scalar; 3]>> = vec![None; cv_count];
+        fixed[0] = Some([first.x(), first.y(), first.z()]);
+        fixed[cv_count - 1] = Some([last.x(), last.y(), last.z()]);
+        if let Some(tangent) = start_tangent {
+            let t = tangent.normalize();
+            let s = total_length * (full_knots[order] - full_knots[1]) / degree as Scalar;
+            fixed[1] = Some([
+                first.x() + t.x() * s,
+                first.y() + t.y() * s,
+                first.z() + t.z() * s,
+            ]);
+        }
+        if let Some(tangent) = end_tangent {
+            let t = tangent.normalize();
+            let s = total_length * (full_knots[cv_count + degree - 1] - full_knots[cv_count - 1])
+                / degree as Scalar;
+            fixed[cv_count - 2] = Some([
+                last.x() - t.x() * s,
+                last.y() - t.y() * s,
+                last.z() - t.z() * s,
+            ]);
+        }
+
+        // Normal equations restricted to the free control points, which form the
+        // contiguous range [lo, hi). Basis functions overlap `degree` neighbours
+        // and the smoothing stencil two, so only the lower band of that width is kept.
+        let lo = 1 + start_tangent.is_some() as usize;
+        let hi = cv_count - 1 - end_tangent.is_some() as usize;
+        let width = degree.max(2);
+        let mut band = vec![vec![0.0; width + 1]; hi - lo];
+        let mut rhs = vec![[0.0; 3]; hi - lo];
+        let mut b = vec![[0.0; 3]; cv_count];
+        let mut add = |i: usize, j: usize, value: Scalar| {
+            if i < lo || i >= hi {
+                return;
+            }
+            match fixed[j] {
+                Some(p) => {
+                    for c in 0..3 {
+                        rhs[i - lo][c] -= value * p[c];
+                    }
+                }
+                None if j <= i => band[i - lo][i - j] += value,
+                None => {}
+            }
+        };
+        for (point, &t) in points.iter().zip(params.iter()) {
+            let span = curve.find_span(t);
+            let basis = curve.basis_functions(span, t);
+            for (i, &ni) in basis.iter().enumerate() {
+                let row = span + i;
+                if row < lo || row >= hi {
+                    continue;
+                }
+                for (j, &nj) in basis.iter().enumerate() {
+                    if span + j < cv_count {
+                        add(row, span + j, ni * nj);
+                    }
+                }
+                b[row][0] += ni * point.x();
+                b[row][1] += ni * point.y();
+                b[row][2] += ni * point.z();
+            }
+        }
+        if smoothness > 0.0 {
+            for k in 1..cv_count.saturating_sub(1) {
+                let stencil = [(k - 1, 1.0), (k, -2.0), (k + 1, 1.0)];
+                for &(i, wi) in &stencil {
+                    for &(j, wj) in &stencil {
+                        add(i, j, smoothness * wi * wj);
+                    }
+                }
+            }
+        }
+        for (r, value) in rhs.iter_mut().zip(&b[lo..hi]) {
+            for c in 0..3 {
+                r[c] += value[c];
+            }
+        }
+        let solution = Self::solve_banded_system(band, rhs)?;
+
+        for (i, value) in fixed.iter().enumerate() {
+            if let Some(p) = value {
+                curve.set_cv(i, &Point::new(p[0], p[1], p[2]));
+            }
+        }
+        for (ri, p) in solution.iter().enumerate() {
+            curve.set_cv(lo + ri, &Point::new(p[0], p[1], p[2]));
+        }
+
+        Some(curve)
+    }
+
+    /// Solve a symmetric positive definite banded system with three right-hand
+    /// sides using Cholesky factorization
+    ///
+    /// `band[i][d]` holds the entry at row `i`, column `i - d`. The factor
+    /// overwrites the band, keeping the cost at O(n * width^2).
+    fn solve_banded_system(
+        mut band: Vec<Vec<Scalar>>,
+        mut b: Vec<[Scalar; 3]>,
+    ) -> Option<Vec<[Scalar; 3]>> {
+        let n = band.len();
+        let width = band.first().map_or(0, |row| row.len() - 1);
+        for i in 0..n {
+            for d in (0..=width.min(i)).rev() {
+                let j = i - d;
+                let mut sum = band[i][d];
+                for k in i.saturating_sub(width)..j {
+                    sum -= band[i][i - k] * band[j][j - k];
+                }
+                if d == 0 {
+                    if sum <= Tolerance::ZERO_TOLERANCE {
+                        return None;
+                    }
+                    band[i][0] = sum.sqrt();
+                } else {
+                    band[i][d] = sum / band[j][0];
+                }
+            }
+        }
+
+        // Forward substitution with L, then back substitution with L^T
+        let eliminate = |b: &mut [[Scalar; 3]], i: usize, k: usize, factor: Scalar| {
+            let known = b[k];
+            for (v, p) in b[i].iter_mut().zip(&known) {
+                *v -= factor * p;
+            }
+        };
+        for i in 0..n {
+            for k in i.saturating_sub(width)..i {
+                eliminate(&mut b, i, k, band[i][i - k]);
+            }
+            b[i].iter_mut().for_each(|v| *v /= band[i][0]);
+        }
+        for i in (0..n).rev() {
+            for k in (i + 1)..(i + width + 1).min(n) {
+                eliminate(&mut b, i, k, band[k][k - i]);
+            }
+            b[i].iter_mut().for_each(|v| *v /= band[i][0]);
+        }
+        Some(b)
+    }
+
+    /// Create a circular arc as a rational quadratic NURBS curve
+    ///
+    /// The arc starts at `start`, ends at `end` and is tangent to the legs
+    /// `corner -> start` and `corner -> end`, where `corner` is the intersection
+    /// of the end tangents. Both legs must have equal length for a circular arc.
+    pub fn create_arc(start: &Point, corner: &Point, end: &Point) -> Option<Self> {
+        let u = (start.clone() - corner.clone()).normalize();
+        let v = (end.clone() - corner.clone()).normalize();
+        let cos_theta = u.dot(&v).clamp(-1.0, 1.0);
+        if u.length_squared() < 0.5 || v.length_squared() < 0.5 || cos_theta.abs() >= 1.0 {
+            return None;
+        }
+
+        // Middle weight is cos of half the sweep angle = sin of half the corner angle
+        let middle_weight = (cos_theta.acos() * 0.5).sin();
+
+        let mut curve = Self::new();
+        if !curve.initialize_curve(3, true, 3, 3) {
+            return None;
+        }
+        for (i, (point, weight)) in [(start, 1.0), (corner, middle_weight), (end, 1.0)]
+            .iter()
+            .enumerate()
+        {
+            let idx = i * curve.m_cv_stride;
+            curve.m_cv[idx] = point.x() * weight;
+            curve.m_cv[idx + 1] = point.y() * weight;
+            curve.m_cv[idx + 2] = point.z() * weight;
+            curve.m_cv[idx + 3] = *weight;
+        }
+        curve.m_knot = vec![0.0, 0.0, 1.0, 1.0];
+
+        Some(curve)
+    }
+
     /// Initialize curve with specified parameters
     fn initialize_curve(
         &mut self,
@@ -212,7 +482,7 @@ impl NurbsCurve {
     }
 
     /// Get weight at control vertex index (returns 1.0 if non-rational)
-    pub fn weight(&self, cv_index: usize) -> f64 {
+    pub fn weight(&self, cv_index: usize) -> Scalar {
         if !self.m_is_rat || cv_index >= self.m_cv_count {
             return 1.0;
         }
@@ -221,7 +491,7 @@ impl NurbsCurve {
     }
 
     /// Set weight at control vertex index
-    pub fn set_weight(&mut self, cv_index: usize, weight: f64) -> bool {
+    pub fn set_weight(&mut self, cv_index: usize, weight: Scalar) -> bool {
         if cv_index >= self.m_cv_count {
             return false;
         }
@@ -235,7 +505,7 @@ impl NurbsCurve {
     }
 
     /// Get knot value at index
-    pub fn knot(&self, knot_index: usize) -> Option<f64> {
+    pub fn knot(&self, knot_index: usize) -> Option<Scalar> {
         if knot_index >= self.m_knot.len() {
             return None;
         }
@@ -243,7 +513,7 @@ impl NurbsCurve {
     }
 
     /// Set knot value at index
-    pub fn set_knot(&mut self, knot_index: usize, knot_value: f64) -> bool {
+    pub fn set_knot(&mut self, knot_index: usize, knot_value: Scalar) -> bool {
         if knot_index >= self.m_knot.len() {
             return false;
         }
@@ -304,22 +574,22 @@ impl NurbsCurve {
     }
 
     /// Get all knot values
-    pub fn get_knots(&self) -> Vec<f64> {
+    pub fn get_knots(&self) -> Vec<Scalar> {
         self.m_knot.clone()
     }
 
     /// Get knot array pointer (for compatibility)
-    pub fn knot_array(&self) -> &[f64] {
+    pub fn knot_array(&self) -> &[Scalar] {
         &self.m_knot
     }
 
     /// Get CV array pointer (for compatibility)
-    pub fn cv_array(&self) -> &[f64] {
+    pub fn cv_array(&self) -> &[Scalar] {
         &self.m_cv
     }
 
     /// Get CV array mutable pointer (for expert use)
-    pub fn cv_array_mut(&mut self) -> &mut [f64] {
+    pub fn cv_array_mut(&mut self) -> &mut [Scalar] {
         &mut self.m_cv
     }
 
@@ -343,7 +613,7 @@ impl NurbsCurve {
     }
 
     /// Get curve domain [t_start, t_end]
-    pub fn domain(&self) -> (f64, f64) {
+    pub fn domain(&self) -> (Scalar, Scalar) {
         if !self.is_valid() {
             return (0.0, 0.0);
         }
@@ -356,7 +626,7 @@ impl NurbsCurve {
     ///
     /// Implementation matches OpenNURBS ON_NurbsSpanIndex with offset knot pointer.
     /// OpenNURBS shifts knot pointer by (order-2) to work with compressed format.
-    fn find_span(&self, t: f64) -> usize {
+    fn find_span(&self, t: Scalar) -> usize {
         // OpenNURBS shifts knot pointer by (order-2) to work with compressed format
         // Domain is knot[order-2] to knot[cv_count-1]
         let offset = self.m_order - 2;
@@ -389,7 +659,7 @@ impl NurbsCurve {
     /// Compute non-zero basis functions at parameter t
     ///
     /// Implementation matches OpenNURBS Cox-de Boor algorithm with offset knot pointer.
-    fn basis_functions(&self, span: usize, t: f64) -> Vec<f64> {
+    fn basis_functions(&self, span: usize, t: Scalar) -> Vec<Scalar> {
         let mut basis = vec![0.0; self.m_order];
         let mut left = vec![0.0; self.m_order];
         let mut right = vec![0.0; self.m_order];
@@ -417,7 +687,7 @@ impl NurbsCurve {
     }
 
     /// Set curve domain
-    pub fn set_domain(&mut self, t0: f64, t1: f64) -> bool {
+    pub fn set_domain(&mut self, t0: Scalar, t1: Scalar) -> bool {
         if !self.is_valid() || t0 >= t1 {
             return false;
         }
@@ -440,7 +710,8 @@ impl NurbsCurve {
     /// Evaluate point at parameter t
     ///
     /// Implementation matches OpenNURBS evaluation approach.
-    pub fn point_at(&self, t: f64) -> Point {
+    #[allow(clippy::needless_range_loop)]
+    pub fn point_at(&self, t: Scalar) -> Point {
         if !self.is_valid() {
             return Point::new(0.0, 0.0, 0.0);
         }
@@ -509,14 +780,15 @@ impl NurbsCurve {
     }
 
     /// Get tangent vector at parameter t
-    pub fn tangent_at(&self, t: f64) -> Vector {
+    pub fn tangent_at(&self, t: Scalar) -> Vector {
         if !self.is_valid() {
             return Vector::new(0.0, 0.0, 0.0);
         }
 
-        // Use numerical differentiation for simplicity
+        // Use numerical differentiation for simplicity, with a step that
+        // stays resolvable in either precision
         let (t0, t1) = self.domain();
-        let eps = (t1 - t0) * 1e-8;
+        let eps = (t1 - t0) * Scalar::EPSILON.sqrt();
         
         let p1 = self.point_at((t - eps).max(t0));
         let p2 = self.point_at((t + eps).min(t1));
@@ -551,7 +823,7 @@ impl NurbsCurve {
     }
 
     /// Check if curve is a straight line within tolerance
-    pub fn is_linear(&self, tolerance: Option<f64>) -> bool {
+    pub fn is_linear(&self, tolerance: Option<Scalar>) -> bool {
         let tol = tolerance.unwrap_or(Tolerance::ZERO_TOLERANCE);
         
         if !self.is_valid() || self.m_cv_count < 2 {
@@ -589,6 +861,7 @@ impl NurbsCurve {
     }
 
     /// Reverse curve direction
+    #[allow(clippy::needless_range_loop)]
     pub fn reverse(&mut self) -> bool {
         if !self.is_valid() {
             return false;
@@ -625,7 +898,7 @@ impl NurbsCurve {
     }
 
     /// Get span vector (parameter values at span boundaries)
-    pub fn get_span_vector(&self) -> Vec<f64> {
+    pub fn get_span_vector(&self) -> Vec<Scalar> {
         let mut spans = Vec::new();
         if !self.is_valid() {
             return spans;
@@ -651,7 +924,11 @@ impl NurbsCurve {
     ///
     /// # Returns
     /// Tuple of (points, parameters)
-    pub fn divide_by_count(&self, count: usize, include_endpoints: bool) -> (Vec<Point>, Vec<f64>) {
+    pub fn divide_by_count(
+        &self,
+        count: usize,
+        include_endpoints: bool,
+    ) -> (Vec<Point>, Vec<Scalar>) {
         let mut points = Vec::new();
         let mut params = Vec::new();
 
@@ -661,11 +938,11 @@ impl NurbsCurve {
 
         let (t0, t1) = self.domain();
         let n = if include_endpoints { count - 1 } else { count + 1 };
-        let dt = (t1 - t0) / n as f64;
+        let dt = (t1 - t0) / n as Scalar;
 
         for i in 0..count {
             let offset = if include_endpoints { 0 } else { 1 };
-            let t = t0 + (i + offset) as f64 * dt;
+            let t = t0 + (i + offset) as Scalar * dt;
             params.push(t);
             points.push(self.point_at(t));
         }
@@ -676,7 +953,8 @@ impl NurbsCurve {
     /// Find all intersections between curve and plane
     ///
     /// Implementation matches C++ version with span-based subdivision and endpoint checking.
-    pub fn intersect_plane(&self, plane: &Plane, tolerance: Option<f64>) -> Vec<f64> {
+    #[allow(clippy::collapsible_if)]
+    pub fn intersect_plane(&self, plane: &Plane, tolerance: Option<Scalar>) -> Vec<Scalar> {
         let tol = tolerance.unwrap_or(Tolerance::ZERO_TOLERANCE);
         let mut results = Vec::new();
 
@@ -684,14 +962,7 @@ impl NurbsCurve {
             return results;
         }
 
-        let signed_distance = |p: &Point| -> f64 {
-            let v = Vector::new(
-                p.x() - plane.origin().x(),
-                p.y() - plane.origin().y(),
-                p.z() - plane.origin().z(),
-            );
-            v.dot(&plane.z_axis())
-        };
+        let signed_distance = |p: &Point| plane.signed_distance(p);
 
         let (_t_start, t_end) = self.domain();
         let span_params = self.get_span_vector();
@@ -756,7 +1027,7 @@ impl NurbsCurve {
     }
 
     /// Find all intersection points between curve and plane
-    pub fn intersect_plane_points(&self, plane: &Plane, tolerance: Option<f64>) -> Vec<Point> {
+    pub fn intersect_plane_points(&self, plane: &Plane, tolerance: Option<Scalar>) -> Vec<Point> {
         self.intersect_plane(plane, tolerance)
             .iter()
             .map(|&t| self.point_at(t))
@@ -769,3 +1040,7 @@ impl Default for NurbsCurve {
         Self::new()
     }
 }
+
+#[cfg(test)]
+#[path = "nurbscurve_test.rs"]
+mod nurbscurve_test;
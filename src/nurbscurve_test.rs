@@ -0,0 +1,111 @@
+use crate::Scalar;
+use crate::{NurbsCurve, Point, Vector};
+
+fn sample_sine(count: usize, noise: Scalar) -> Vec<Point> {
+    (0..count)
+        .map(|i| {
+            let x = i as Scalar / (count - 1) as Scalar * crate::scalar::consts::TAU;
+            let jitter = if i % 2 == 0 { noise } else { -noise };
+            Point::new(x, x.sin() + jitter, 0.0)
+        })
+        .collect()
+}
+
+#[test]
+fn test_fit_interpolates_ends() {
+    let points = sample_sine(40, 0.0);
+    let curve = NurbsCurve::fit(&points, 3, 20, 0.0).unwrap();
+    assert_eq!(curve.degree(), 3);
+    assert!(curve.is_valid());
+    assert!(curve.point_at_start().distance(&points[0]) < 1e-9);
+    assert!(curve.point_at_end().distance(&points[39]) < 1e-9);
+}
+
+#[test]
+fn test_fit_approximates_points() {
+    let points = sample_sine(40, 0.0);
+    let curve = NurbsCurve::fit(&points, 3, 20, 0.0).unwrap();
+    assert!(curve.cv_count() < points.len());
+    let (t0, t1) = curve.domain();
+    for i in 0..=50 {
+        let p = curve.point_at(t0 + (t1 - t0) * i as Scalar / 50.0);
+        assert!((p.y() - p.x().sin()).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_fit_smoothness_reduces_noise() {
+    let points = sample_sine(40, 0.1);
+    let rough = NurbsCurve::fit(&points, 3, 20, 0.0).unwrap();
+    let smooth = NurbsCurve::fit(&points, 3, 20, 10.0).unwrap();
+    let roughness = |curve: &NurbsCurve| -> Scalar {
+        (1..curve.cv_count() - 1)
+            .map(|i| {
+                let a = curve.get_cv(i - 1).unwrap();
+                let b = curve.get_cv(i).unwrap();
+                let c = curve.get_cv(i + 1).unwrap();
+                (a.y() - 2.0 * b.y() + c.y()).abs()
+            })
+            .sum()
+    };
+    assert!(roughness(&smooth) < roughness(&rough));
+}
+
+#[test]
+fn test_fit_control_point_count() {
+    let points = sample_sine(200, 0.0);
+    let curve = NurbsCurve::fit(&points, 3, 12, 0.5).unwrap();
+    assert_eq!(curve.cv_count(), 12);
+    assert!(NurbsCurve::fit(&points, 3, 3, 0.0).is_none());
+    assert!(NurbsCurve::fit(&points, 3, 201, 0.0).is_none());
+
+    // As many control points as points interpolates them
+    let points = sample_sine(12, 0.0);
+    let curve = NurbsCurve::fit(&points, 3, 12, 0.0).unwrap();
+    let (t0, t1) = curve.domain();
+    let closest = |p: &Point| {
+        (0..=2000)
+            .map(|i| {
+                curve
+                    .point_at(t0 + (t1 - t0) * i as Scalar / 2000.0)
+                    .distance(p)
+            })
+            .fold(Scalar::MAX, Scalar::min)
+    };
+    for point in &points {
+        assert!(closest(point) < 0.01);
+    }
+}
+
+#[test]
+fn test_fit_many_points() {
+    let points = sample_sine(50_000, 0.0);
+    let curve = NurbsCurve::fit(&points, 3, 5_000, 0.1).unwrap();
+    let (t0, t1) = curve.domain();
+    for i in 0..=100 {
+        let p = curve.point_at(t0 + (t1 - t0) * i as Scalar / 100.0);
+        assert!((p.y() - p.x().sin()).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_fit_with_tangents() {
+    let points = sample_sine(20, 0.0);
+    let start = Vector::new(0.0, 1.0, 0.0);
+    let end = Vector::new(1.0, 0.0, 0.0);
+    let curve =
+        NurbsCurve::fit_with_tangents(&points, 3, 10, 0.0, Some(&start), Some(&end)).unwrap();
+    let (t0, t1) = curve.domain();
+    let tangent_start = curve.tangent_at(t0);
+    let tangent_end = curve.tangent_at(t1);
+    assert!(tangent_start.dot(&start) > 0.999);
+    assert!(tangent_end.dot(&end) > 0.999);
+}
+
+#[test]
+fn test_fit_invalid() {
+    let points = vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0)];
+    assert!(NurbsCurve::fit(&points, 3, 4, 0.0).is_none());
+    let same = vec![Point::new(1.0, 1.0, 1.0); 5];
+    assert!(NurbsCurve::fit(&same, 2, 3, 0.0).is_none());
+}
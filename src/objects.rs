@@ -1,6 +1,7 @@
 use crate::arrow::Arrow;
 use crate::boundingbox::BoundingBox;
 use crate::cylinder::Cylinder;
+use crate::error::SessionError;
 use crate::line::Line;
 use crate::mesh::Mesh;
 use crate::plane::Plane;
@@ -10,7 +11,6 @@ use crate::polyline::Polyline;
 use serde::{ser::Serialize as SerTrait, Deserialize, Serialize};
 use std::fmt;
 use std::fs;
-use uuid::Uuid;
 
 /// A collection of all geometry objects.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,7 +32,7 @@ pub struct Objects {
 impl Default for Objects {
     fn default() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_objects".to_string(),
             points: Vec::new(),
             lines: Vec::new(),
@@ -60,7 +60,7 @@ impl Objects {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Serializes the Objects to a JSON string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
@@ -69,21 +69,21 @@ impl Objects {
     }
 
     /// Deserializes Objects from a JSON string.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
     /// Serializes the Objects to a JSON file.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         fs::write(filepath, json)?;
         Ok(())
     }
 
     /// Deserializes Objects from a JSON file.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
     }
 }
 
@@ -0,0 +1,254 @@
+use crate::{BoundingBox, Point, Scalar, Vector};
+
+/// Node of an `Octree` with aggregates over its whole subtree.
+#[derive(Debug, Clone)]
+pub struct OctreeNode {
+    /// Cubic cell covered by the node
+    pub bounds: BoundingBox,
+    /// Depth of the node, the root is at depth 0
+    pub depth: usize,
+    /// Indices of the eight children in `Octree::nodes`, empty for leaves
+    pub children: Vec<usize>,
+    /// Objects stored directly in this node
+    pub objects: Vec<usize>,
+    /// Number of objects in the subtree
+    pub count: usize,
+    /// Combined axis-aligned box of all objects in the subtree, None if empty
+    pub aabb: Option<BoundingBox>,
+}
+
+impl OctreeNode {
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// Octree over object bounding boxes for level-of-detail traversal.
+///
+/// Each object is assigned to the leaf containing its box center, so every
+/// object lives in exactly one node. Node cells may therefore be smaller than
+/// the objects they hold; use `OctreeNode::aabb` for the true extent.
+#[derive(Debug, Clone, Default)]
+pub struct Octree {
+    /// Nodes in depth-first order, the root is at index 0
+    pub nodes: Vec<OctreeNode>,
+    /// GUIDs of the objects, indexed by object id (may be empty)
+    pub object_guids: Vec<String>,
+    pub max_depth: usize,
+    pub max_objects: usize,
+}
+
+impl Octree {
+    /// Build an octree from boxes with object ids equal to their indices.
+    ///
+    /// # Arguments
+    /// * `bounding_boxes` - Object boxes
+    /// * `max_depth` - Maximum subdivision depth
+    /// * `max_objects` - Nodes with more objects than this are subdivided
+    pub fn from_boxes(
+        bounding_boxes: &[BoundingBox],
+        max_depth: usize,
+        max_objects: usize,
+    ) -> Self {
+        let mut octree = Octree {
+            nodes: Vec::new(),
+            object_guids: Vec::new(),
+            max_depth,
+            max_objects: max_objects.max(1),
+        };
+        if bounding_boxes.is_empty() {
+            return octree;
+        }
+
+        let extents: Vec<([Scalar; 3], [Scalar; 3])> = bounding_boxes.iter().map(min_max).collect();
+        let mut min = [Scalar::MAX; 3];
+        let mut max = [Scalar::MIN; 3];
+        for (lo, hi) in &extents {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(lo[axis]);
+                max[axis] = max[axis].max(hi[axis]);
+            }
+        }
+        let half = (0..3)
+            .map(|i| (max[i] - min[i]) * 0.5)
+            .fold(0.0, Scalar::max);
+        let center = [
+            (min[0] + max[0]) * 0.5,
+            (min[1] + max[1]) * 0.5,
+            (min[2] + max[2]) * 0.5,
+        ];
+
+        let ids: Vec<usize> = (0..bounding_boxes.len()).collect();
+        octree.build_node(&extents, ids, center, half, 0);
+        octree
+    }
+
+    /// Build an octree from boxes paired with object GUIDs.
+    pub fn from_boxes_with_guids(
+        boxes_with_guids: &[(BoundingBox, String)],
+        max_depth: usize,
+        max_objects: usize,
+    ) -> Self {
+        let boxes: Vec<BoundingBox> = boxes_with_guids.iter().map(|(b, _)| b.clone()).collect();
+        let mut octree = Self::from_boxes(&boxes, max_depth, max_objects);
+        octree.object_guids = boxes_with_guids.iter().map(|(_, g)| g.clone()).collect();
+        octree
+    }
+
+    fn build_node(
+        &mut self,
+        extents: &[([Scalar; 3], [Scalar; 3])],
+        ids: Vec<usize>,
+        center: [Scalar; 3],
+        half: Scalar,
+        depth: usize,
+    ) -> usize {
+        let index = self.nodes.len();
+        let mut aabb_min = [Scalar::MAX; 3];
+        let mut aabb_max = [Scalar::MIN; 3];
+        for &id in &ids {
+            for axis in 0..3 {
+                aabb_min[axis] = aabb_min[axis].min(extents[id].0[axis]);
+                aabb_max[axis] = aabb_max[axis].max(extents[id].1[axis]);
+            }
+        }
+        self.nodes.push(OctreeNode {
+            bounds: cube(center, half),
+            depth,
+            children: Vec::new(),
+            objects: Vec::new(),
+            count: ids.len(),
+            aabb: (!ids.is_empty()).then(|| aabb_from_min_max(aabb_min, aabb_max)),
+        });
+
+        if ids.len() <= self.max_objects || depth >= self.max_depth {
+            self.nodes[index].objects = ids;
+            return index;
+        }
+
+        // Split by box centers into octants, bit 0 = +x, bit 1 = +y, bit 2 = +z
+        let mut octants: [Vec<usize>; 8] = Default::default();
+        for id in ids {
+            let (lo, hi) = &extents[id];
+            let mut octant = 0;
+            for axis in 0..3 {
+                if (lo[axis] + hi[axis]) * 0.5 >= center[axis] {
+                    octant |= 1 << axis;
+                }
+            }
+            octants[octant].push(id);
+        }
+
+        let quarter = half * 0.5;
+        let mut children = Vec::with_capacity(8);
+        for (octant, child_ids) in octants.into_iter().enumerate() {
+            let offset = |axis: usize| {
+                if octant & (1 << axis) != 0 {
+                    quarter
+                } else {
+                    -quarter
+                }
+            };
+            let child_center = [
+                center[0] + offset(0),
+                center[1] + offset(1),
+                center[2] + offset(2),
+            ];
+            children.push(self.build_node(extents, child_ids, child_center, quarter, depth + 1));
+        }
+        self.nodes[index].children = children;
+        index
+    }
+
+    pub fn root(&self) -> Option<&OctreeNode> {
+        self.nodes.first()
+    }
+
+    /// Visit nodes depth-first down to a maximum depth.
+    ///
+    /// # Arguments
+    /// * `max_depth` - Deepest level to visit
+    /// * `visitor` - Called for each non-empty node, return false to skip its children
+    pub fn traverse<'a, F>(&'a self, max_depth: usize, mut visitor: F)
+    where
+        F: FnMut(&'a OctreeNode) -> bool,
+    {
+        let mut stack: Vec<usize> = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.count == 0 || !visitor(node) || node.depth >= max_depth {
+                continue;
+            }
+            stack.extend(node.children.iter().rev());
+        }
+    }
+
+    /// Cut the tree at a level of detail.
+    ///
+    /// # Returns
+    /// Non-empty nodes at `depth` and non-empty leaves above it. Together they
+    /// hold every object exactly once, so their aggregates summarize the scene.
+    pub fn level_of_detail(&self, depth: usize) -> Vec<&OctreeNode> {
+        let mut result = Vec::new();
+        self.traverse(depth, |node| {
+            if node.depth == depth || node.is_leaf() {
+                result.push(node);
+                return false;
+            }
+            true
+        });
+        result
+    }
+
+    /// Collect all object ids in a node's subtree.
+    pub fn objects_in(&self, node: &OctreeNode) -> Vec<usize> {
+        let mut result = node.objects.clone();
+        for &child in &node.children {
+            result.extend(self.objects_in(&self.nodes[child]));
+        }
+        result
+    }
+}
+
+fn min_max(bbox: &BoundingBox) -> ([Scalar; 3], [Scalar; 3]) {
+    let aabb = if bbox.is_axis_aligned() {
+        bbox.clone()
+    } else {
+        BoundingBox::from_points(&bbox.corners(), 0.0)
+    };
+    let (lo, hi) = (aabb.min_point(), aabb.max_point());
+    ([lo.x(), lo.y(), lo.z()], [hi.x(), hi.y(), hi.z()])
+}
+
+fn cube(center: [Scalar; 3], half: Scalar) -> BoundingBox {
+    aabb_from_min_max(
+        [center[0] - half, center[1] - half, center[2] - half],
+        [center[0] + half, center[1] + half, center[2] + half],
+    )
+}
+
+fn aabb_from_min_max(min: [Scalar; 3], max: [Scalar; 3]) -> BoundingBox {
+    BoundingBox::new(
+        Point::new(
+            (min[0] + max[0]) * 0.5,
+            (min[1] + max[1]) * 0.5,
+            (min[2] + max[2]) * 0.5,
+        ),
+        Vector::new(1.0, 0.0, 0.0),
+        Vector::new(0.0, 1.0, 0.0),
+        Vector::new(0.0, 0.0, 1.0),
+        Vector::new(
+            (max[0] - min[0]) * 0.5,
+            (max[1] - min[1]) * 0.5,
+            (max[2] - min[2]) * 0.5,
+        ),
+    )
+}
+
+#[cfg(test)]
+#[path = "octree_test.rs"]
+mod octree_test;
@@ -0,0 +1,82 @@
+use super::*;
+use crate::scalar::test_tolerance;
+use rand::{Rng, SeedableRng};
+
+fn random_boxes(count: usize) -> Vec<BoundingBox> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+    (0..count)
+        .map(|_| {
+            let center = Point::new(
+                rng.gen_range(-50.0..50.0),
+                rng.gen_range(-50.0..50.0),
+                rng.gen_range(-50.0..50.0),
+            );
+            BoundingBox::from_point(center, rng.gen_range(0.1..2.0))
+        })
+        .collect()
+}
+
+#[test]
+fn test_octree_empty() {
+    let octree = Octree::from_boxes(&[], 4, 8);
+    assert!(octree.root().is_none());
+    assert!(octree.level_of_detail(2).is_empty());
+}
+
+#[test]
+fn test_octree_aggregates() {
+    let boxes = random_boxes(300);
+    let octree = Octree::from_boxes(&boxes, 5, 8);
+    let root = octree.root().unwrap();
+    assert_eq!(root.count, 300);
+    assert!(!root.is_leaf());
+
+    let eps = test_tolerance(1e-12);
+    for node in &octree.nodes {
+        let child_count: usize = node.children.iter().map(|&c| octree.nodes[c].count).sum();
+        assert_eq!(node.count, node.objects.len() + child_count);
+        assert!(node.is_leaf() || node.objects.is_empty());
+        assert!(node.is_leaf() || node.depth < octree.max_depth);
+
+        // The combined box contains every object in the subtree
+        if let Some(aabb) = &node.aabb {
+            let (lo, hi) = (aabb.min_point(), aabb.max_point());
+            for id in octree.objects_in(node) {
+                let (a, b) = (boxes[id].min_point(), boxes[id].max_point());
+                assert!(a.x() >= lo.x() - eps && a.y() >= lo.y() - eps && a.z() >= lo.z() - eps);
+                assert!(b.x() <= hi.x() + eps && b.y() <= hi.y() + eps && b.z() <= hi.z() + eps);
+            }
+        } else {
+            assert_eq!(node.count, 0);
+        }
+    }
+}
+
+#[test]
+fn test_octree_level_of_detail() {
+    let boxes = random_boxes(300);
+    let octree = Octree::from_boxes(&boxes, 5, 4);
+
+    let coarse = octree.level_of_detail(0);
+    assert_eq!(coarse.len(), 1);
+
+    for depth in 1..=5 {
+        let cut = octree.level_of_detail(depth);
+        assert!(cut.iter().all(|n| n.depth <= depth && n.count > 0));
+        assert!(cut.iter().all(|n| n.depth == depth || n.is_leaf()));
+
+        // Every object appears exactly once in the cut
+        let mut ids: Vec<usize> = cut.iter().flat_map(|n| octree.objects_in(n)).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (0..300).collect::<Vec<_>>());
+        assert!(cut.len() >= octree.level_of_detail(depth - 1).len());
+    }
+
+    let mut visited = 0;
+    octree.traverse(1, |node| {
+        assert!(node.depth <= 1);
+        visited += 1;
+        true
+    });
+    assert_eq!(visited, 1 + octree.level_of_detail(1).len());
+}
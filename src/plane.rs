@@ -1,6 +1,9 @@
-use crate::{Point, Vector, Xform};
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{Point, Scalar, Tolerance, Vector, Xform};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename = "Plane")]
@@ -16,13 +19,13 @@ pub struct Plane {
     #[serde(rename = "z_axis")]
     _z_axis: Vector,
     #[serde(rename = "a")]
-    _a: f64,
+    _a: Scalar,
     #[serde(rename = "b")]
-    _b: f64,
+    _b: Scalar,
     #[serde(rename = "c")]
-    _c: f64,
+    _c: Scalar,
     #[serde(rename = "d")]
-    _d: f64,
+    _d: Scalar,
     #[serde(default = "Xform::identity")]
     pub xform: Xform,
 }
@@ -30,7 +33,7 @@ pub struct Plane {
 impl Default for Plane {
     fn default() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_plane".to_string(),
             _origin: Point::default(),
             _x_axis: Vector::x_axis(),
@@ -60,7 +63,7 @@ impl Plane {
         let d = -(a * point.x() + b * point.y() + c * point.z());
 
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_plane".to_string(),
             _origin: point,
             _x_axis: x_axis,
@@ -88,7 +91,7 @@ impl Plane {
         let d = -(a * point.x() + b * point.y() + c * point.z());
 
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name,
             _origin: point,
             _x_axis: x_axis,
@@ -118,7 +121,7 @@ impl Plane {
         let d = -(a * origin.x() + b * origin.y() + c * origin.z());
 
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_plane".to_string(),
             _origin: origin,
             _x_axis: x_axis,
@@ -157,7 +160,7 @@ impl Plane {
         let d = -(a * origin.x() + b * origin.y() + c * origin.z());
 
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_plane".to_string(),
             _origin: origin,
             _x_axis: x_axis,
@@ -171,6 +174,68 @@ impl Plane {
         }
     }
 
+    /// Best-fit plane of unordered points, see `points::pca_frame`.
+    ///
+    /// Unlike `from_points`, which uses the first three points, every point
+    /// contributes and the origin is their centroid.
+    ///
+    /// # Returns
+    /// None if there are no points.
+    pub fn best_fit(points: &[Point]) -> Option<Self> {
+        crate::points::pca_frame(points)
+    }
+
+    /// Best-fit plane of unordered points with the quality of the fit.
+    ///
+    /// # Returns
+    /// The plane of `best_fit` and the root mean square distance of the
+    /// points to it, or None if there are no points.
+    pub fn fit_from_points(points: &[Point]) -> Option<(Self, Scalar)> {
+        let plane = Self::best_fit(points)?;
+        let sum: Scalar = points
+            .iter()
+            .map(|point| plane.signed_distance(point).powi(2))
+            .sum();
+        let rms = (sum / points.len() as Scalar).sqrt();
+        Some((plane, rms))
+    }
+
+    /// Signed distance of a point, positive on the side the normal points to.
+    pub fn signed_distance(&self, point: &Point) -> Scalar {
+        (point.clone() - self._origin.clone()).dot(&self._z_axis)
+    }
+
+    /// A copy moved along the normal, keeping the axes and the name.
+    pub fn offset(&self, distance: Scalar) -> Plane {
+        let origin = self._origin.clone() + self._z_axis.clone() * distance;
+        let mut plane = Plane::with_name(
+            origin,
+            self._x_axis.clone(),
+            self._y_axis.clone(),
+            self.name.clone(),
+        );
+        plane.xform = self.xform.clone();
+        plane
+    }
+
+    /// Angle between the normals of two planes in radians, in `[0, PI]`.
+    pub fn angle_to(&self, other: &Plane) -> Scalar {
+        self._z_axis.dot(&other._z_axis).clamp(-1.0, 1.0).acos()
+    }
+
+    /// Intersect the ray `origin + t * direction` with the plane.
+    ///
+    /// # Returns
+    /// The ray parameter `t` of the hit, negative behind the origin, or None
+    /// if the ray is parallel to the plane.
+    pub fn intersect_ray(&self, origin: &Point, direction: &Vector) -> Option<Scalar> {
+        let along_normal = direction.dot(&self._z_axis);
+        if along_normal.abs() < Tolerance::ZERO_TOLERANCE {
+            return None;
+        }
+        Some(-self.signed_distance(origin) / along_normal)
+    }
+
     pub fn from_two_points(point1: Point, point2: Point) -> Self {
         let origin = point1.clone();
 
@@ -190,7 +255,7 @@ impl Plane {
         let d = -(a * origin.x() + b * origin.y() + c * origin.z());
 
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_plane".to_string(),
             _origin: origin,
             _x_axis: x_axis,
@@ -206,7 +271,7 @@ impl Plane {
 
     pub fn xy_plane() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "xy_plane".to_string(),
             _origin: Point::new(0.0, 0.0, 0.0),
             _x_axis: Vector::x_axis(),
@@ -222,7 +287,7 @@ impl Plane {
 
     pub fn yz_plane() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "yz_plane".to_string(),
             _origin: Point::new(0.0, 0.0, 0.0),
             _x_axis: Vector::y_axis(),
@@ -238,7 +303,7 @@ impl Plane {
 
     pub fn xz_plane() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "xz_plane".to_string(),
             _origin: Point::new(0.0, 0.0, 0.0),
             _x_axis: Vector::x_axis(),
@@ -268,24 +333,24 @@ impl Plane {
         self._z_axis.clone()
     }
 
-    pub fn a(&self) -> f64 {
+    pub fn a(&self) -> Scalar {
         self._a
     }
 
-    pub fn b(&self) -> f64 {
+    pub fn b(&self) -> Scalar {
         self._b
     }
 
-    pub fn c(&self) -> f64 {
+    pub fn c(&self) -> Scalar {
         self._c
     }
 
-    pub fn d(&self) -> f64 {
+    pub fn d(&self) -> Scalar {
         self._d
     }
 
     pub fn reverse(&mut self) {
-        std::mem::swap(&mut self._x_axis, &mut self._y_axis);
+        core::mem::swap(&mut self._x_axis, &mut self._y_axis);
         self._z_axis.reverse();
 
         self._a = self._z_axis.x();
@@ -295,7 +360,7 @@ impl Plane {
             -(self._a * self._origin.x() + self._b * self._origin.y() + self._c * self._origin.z());
     }
 
-    pub fn rotate(&mut self, angles_in_radians: f64) {
+    pub fn rotate(&mut self, angles_in_radians: Scalar) {
         let cos_angle = angles_in_radians.cos();
         let sin_angle = angles_in_radians.sin();
 
@@ -357,7 +422,7 @@ impl Plane {
     }
 }
 
-impl std::ops::Index<usize> for Plane {
+impl core::ops::Index<usize> for Plane {
     type Output = Vector;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -369,7 +434,7 @@ impl std::ops::Index<usize> for Plane {
     }
 }
 
-impl std::ops::IndexMut<usize> for Plane {
+impl core::ops::IndexMut<usize> for Plane {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         match index {
             0 => &mut self._x_axis,
@@ -379,7 +444,7 @@ impl std::ops::IndexMut<usize> for Plane {
     }
 }
 
-impl std::ops::AddAssign<Vector> for Plane {
+impl core::ops::AddAssign<Vector> for Plane {
     fn add_assign(&mut self, other: Vector) {
         self._origin += other;
         self._d =
@@ -387,7 +452,7 @@ impl std::ops::AddAssign<Vector> for Plane {
     }
 }
 
-impl std::ops::SubAssign<Vector> for Plane {
+impl core::ops::SubAssign<Vector> for Plane {
     fn sub_assign(&mut self, other: Vector) {
         self._origin -= other;
         self._d =
@@ -395,7 +460,7 @@ impl std::ops::SubAssign<Vector> for Plane {
     }
 }
 
-impl std::ops::Add<Vector> for Plane {
+impl core::ops::Add<Vector> for Plane {
     type Output = Plane;
 
     fn add(self, other: Vector) -> Plane {
@@ -405,7 +470,7 @@ impl std::ops::Add<Vector> for Plane {
     }
 }
 
-impl std::ops::Sub<Vector> for Plane {
+impl core::ops::Sub<Vector> for Plane {
     type Output = Plane;
 
     fn sub(self, other: Vector) -> Plane {
@@ -423,7 +488,7 @@ impl PartialEq<Point> for Plane {
 
 impl Plane {
     /// Translate (move) a plane along its normal direction by a specified distance
-    pub fn translate_by_normal(&self, distance: f64) -> Plane {
+    pub fn translate_by_normal(&self, distance: Scalar) -> Plane {
         let mut normal = self._z_axis.clone();
         normal.normalize_self();
 
@@ -433,8 +498,8 @@ impl Plane {
     }
 }
 
-impl std::fmt::Display for Plane {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Plane {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Plane(origin={}, x_axis={}, y_axis={}, z_axis={}, guid={}, name={})",
@@ -459,11 +524,13 @@ impl Plane {
         result
     }
 
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
 
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 }
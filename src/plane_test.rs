@@ -1,6 +1,6 @@
 use crate::encoders::{json_dump, json_load};
+use crate::scalar::consts::PI;
 use crate::{Plane, Point, Vector};
-use std::f64::consts::PI;
 
 #[test]
 fn test_plane_default_constructor() {
@@ -272,3 +272,57 @@ fn test_plane_translate_by_normal() {
     assert_eq!(yz_translated.origin().y(), 0.0);
     assert_eq!(yz_translated.origin().z(), 0.0);
 }
+
+#[test]
+fn test_plane_fit_offset_angle_and_ray() {
+    let points = [
+        Point::new(0.0, 0.0, 1.0),
+        Point::new(4.0, 0.0, 1.0),
+        Point::new(4.0, 2.0, 1.0),
+        Point::new(0.0, 2.0, 1.0),
+    ];
+    let (plane, rms) = Plane::fit_from_points(&points).unwrap();
+    assert!(rms < 1e-6);
+    assert!(plane.signed_distance(&Point::new(1.0, 1.0, 1.0)).abs() < 1e-6);
+    let (_, rms) = Plane::fit_from_points(&[
+        Point::new(0.0, 0.0, 1.0),
+        Point::new(4.0, 0.0, -1.0),
+        Point::new(4.0, 4.0, 1.0),
+        Point::new(0.0, 4.0, -1.0),
+    ])
+    .unwrap();
+    assert!((rms - 1.0).abs() < 1e-6);
+    assert!(Plane::fit_from_points(&[]).is_none());
+
+    let xy = Plane::with_name(
+        Point::new(0.0, 0.0, 0.0),
+        Vector::new(1.0, 0.0, 0.0),
+        Vector::new(0.0, 1.0, 0.0),
+        "floor".to_string(),
+    );
+    assert_eq!(xy.signed_distance(&Point::new(3.0, 4.0, -2.0)), -2.0);
+    let raised = xy.offset(2.5);
+    assert_eq!(raised.name, "floor");
+    assert_eq!(raised.origin().z(), 2.5);
+    assert_eq!(raised.d(), -2.5);
+    assert_eq!(raised.x_axis().x(), 1.0);
+
+    assert_eq!(xy.angle_to(&raised), 0.0);
+    assert!((xy.angle_to(&Plane::yz_plane()) - PI / 2.0).abs() < 1e-6);
+    let mut flipped = xy.clone();
+    flipped.reverse();
+    assert!((xy.angle_to(&flipped) - PI).abs() < 1e-6);
+
+    let origin = Point::new(1.0, 1.0, 5.0);
+    assert_eq!(
+        raised.intersect_ray(&origin, &Vector::new(0.0, 0.0, -2.0)),
+        Some(1.25)
+    );
+    assert_eq!(
+        raised.intersect_ray(&origin, &Vector::new(0.0, 0.0, 1.0)),
+        Some(-2.5)
+    );
+    assert!(raised
+        .intersect_ray(&origin, &Vector::new(1.0, 0.0, 0.0))
+        .is_none());
+}
@@ -0,0 +1,366 @@
+use crate::error::SessionError;
+use crate::scalar::to_f64;
+use crate::{Color, Mesh, Point};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+/// Encoding of the data section of a PLY file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+/// Scalar property types, with the C style and sized names accepted by PLY readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Result<Self, SessionError> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(header_error(format!("unknown property type `{name}`"))),
+        })
+    }
+
+    fn is_float(self) -> bool {
+        matches!(self, Scalar::F32 | Scalar::F64)
+    }
+}
+
+enum Property {
+    Scalar(String, Scalar),
+    List(String, Scalar, Scalar),
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+fn header_error(message: String) -> SessionError {
+    SessionError::SchemaMismatch(format!("PLY header: {message}"))
+}
+
+/// Reads the header up to and including `end_header`.
+fn read_header(reader: &mut impl BufRead) -> Result<(PlyFormat, Vec<Element>), SessionError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut line = String::new();
+    let mut first = true;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(header_error("missing end_header".to_string()));
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if first {
+            if tokens != ["ply"] {
+                return Err(header_error("missing `ply` magic".to_string()));
+            }
+            first = false;
+            continue;
+        }
+        match tokens.as_slice() {
+            ["format", "ascii", _] => format = Some(PlyFormat::Ascii),
+            ["format", "binary_little_endian", _] => format = Some(PlyFormat::BinaryLittleEndian),
+            ["format", other, ..] => {
+                return Err(header_error(format!("unsupported format `{other}`")))
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| header_error(format!("invalid count for `{name}`")))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => elements
+                .last_mut()
+                .ok_or_else(|| header_error(format!("property `{name}` before any element")))?
+                .properties
+                .push(Property::List(
+                    name.to_string(),
+                    Scalar::parse(count)?,
+                    Scalar::parse(item)?,
+                )),
+            ["property", ty, name] => elements
+                .last_mut()
+                .ok_or_else(|| header_error(format!("property `{name}` before any element")))?
+                .properties
+                .push(Property::Scalar(name.to_string(), Scalar::parse(ty)?)),
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(header_error(format!("unexpected line `{}`", line.trim()))),
+        }
+    }
+    let format = format.ok_or_else(|| header_error("missing format".to_string()))?;
+    Ok((format, elements))
+}
+
+/// Reads scalars from the data section in either encoding.
+struct DataReader<R> {
+    reader: R,
+    format: PlyFormat,
+    tokens: VecDeque<String>,
+}
+
+impl<R: BufRead> DataReader<R> {
+    fn scalar(&mut self, ty: Scalar) -> Result<f64, SessionError> {
+        match self.format {
+            PlyFormat::Ascii => {
+                while self.tokens.is_empty() {
+                    let mut line = String::new();
+                    if self.reader.read_line(&mut line)? == 0 {
+                        return Err(SessionError::InvalidGeometry(
+                            "PLY data ends early".to_string(),
+                        ));
+                    }
+                    self.tokens
+                        .extend(line.split_whitespace().map(str::to_string));
+                }
+                let token = self.tokens.pop_front().unwrap_or_default();
+                token.parse().map_err(|_| {
+                    SessionError::InvalidGeometry(format!("invalid PLY value `{token}`"))
+                })
+            }
+            PlyFormat::BinaryLittleEndian => Ok(match ty {
+                Scalar::I8 => i8::from_le_bytes(self.bytes()?) as f64,
+                Scalar::U8 => u8::from_le_bytes(self.bytes()?) as f64,
+                Scalar::I16 => i16::from_le_bytes(self.bytes()?) as f64,
+                Scalar::U16 => u16::from_le_bytes(self.bytes()?) as f64,
+                Scalar::I32 => i32::from_le_bytes(self.bytes()?) as f64,
+                Scalar::U32 => u32::from_le_bytes(self.bytes()?) as f64,
+                Scalar::F32 => f32::from_le_bytes(self.bytes()?) as f64,
+                Scalar::F64 => f64::from_le_bytes(self.bytes()?),
+            }),
+        }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], SessionError> {
+        let mut buf = [0u8; N];
+        self.reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Read a PLY file in ASCII or binary little endian format.
+///
+/// The `vertex` element gives the positions, `nx`/`ny`/`nz` are stored as
+/// vertex normal attributes and `red`/`green`/`blue`/`alpha` become
+/// `Mesh::pointcolors` (float colors are scaled from 0-1). Faces are read from
+/// the `vertex_indices` list of the `face` element; other elements and
+/// properties are skipped.
+///
+/// # Arguments
+/// * `filepath` - The path of the PLY file
+///
+/// # Returns
+/// The mesh, or an error for unreadable files, unsupported headers and
+/// faces that reference missing vertices.
+pub fn read_ply(filepath: &str) -> Result<Mesh, SessionError> {
+    let mut reader = BufReader::new(std::fs::File::open(filepath)?);
+    let (format, elements) = read_header(&mut reader)?;
+    let mut data = DataReader {
+        reader,
+        format,
+        tokens: VecDeque::new(),
+    };
+
+    let mut mesh = Mesh::new();
+    let mut keys: Vec<usize> = Vec::new();
+    let mut faces: Vec<Vec<usize>> = Vec::new();
+    for element in &elements {
+        for _ in 0..element.count {
+            let mut values: Vec<(&str, Scalar, f64)> = Vec::new();
+            let mut indices: Option<Vec<usize>> = None;
+            for property in &element.properties {
+                match property {
+                    Property::Scalar(name, ty) => values.push((name, *ty, data.scalar(*ty)?)),
+                    Property::List(name, count, item) => {
+                        let count = data.scalar(*count)? as usize;
+                        let mut items = Vec::with_capacity(count);
+                        for _ in 0..count {
+                            items.push(data.scalar(*item)? as usize);
+                        }
+                        if name == "vertex_indices" || name == "vertex_index" {
+                            indices = Some(items);
+                        }
+                    }
+                }
+            }
+            match element.name.as_str() {
+                "vertex" => keys.push(add_ply_vertex(&mut mesh, &values, keys.len())),
+                "face" => faces.extend(indices),
+                _ => {}
+            }
+        }
+    }
+
+    for face in faces {
+        let vertices = face
+            .iter()
+            .map(|&index| keys.get(index).copied())
+            .collect::<Option<Vec<usize>>>()
+            .ok_or_else(|| {
+                SessionError::InvalidGeometry(format!(
+                    "PLY face references a vertex outside 0..{}",
+                    keys.len()
+                ))
+            })?;
+        if vertices.len() >= 3 {
+            let _ = mesh.add_face(vertices, None);
+        }
+    }
+    Ok(mesh)
+}
+
+fn add_ply_vertex(mesh: &mut Mesh, values: &[(&str, Scalar, f64)], index: usize) -> usize {
+    let get = |name: &str| values.iter().find(|(n, _, _)| *n == name);
+    let coordinate = |name: &str| get(name).map_or(0.0, |&(_, _, value)| value as crate::Scalar);
+    let key = mesh.add_vertex(
+        Point::new(coordinate("x"), coordinate("y"), coordinate("z")),
+        None,
+    );
+
+    if let (Some(nx), Some(ny), Some(nz)) = (get("nx"), get("ny"), get("nz")) {
+        if let Some(vertex) = mesh.vertex.get_mut(&key) {
+            let [nx, ny, nz] = [nx.2, ny.2, nz.2].map(|value| value as crate::Scalar);
+            vertex.set_normal(nx, ny, nz);
+        }
+    }
+
+    let channel = |name: &str, default: u8| match get(name) {
+        Some(&(_, ty, value)) if ty.is_float() => (value * 255.0).round().clamp(0.0, 255.0) as u8,
+        Some(&(_, _, value)) => value.clamp(0.0, 255.0) as u8,
+        None => default,
+    };
+    if get("red").is_some() || get("green").is_some() || get("blue").is_some() {
+        let color = Color::new(
+            channel("red", 0),
+            channel("green", 0),
+            channel("blue", 0),
+            channel("alpha", 255),
+        );
+        mesh.set_vertex_color(index, color);
+    }
+    key
+}
+
+/// Write a mesh as a PLY file.
+///
+/// Positions are written as doubles in local coordinates, like `write_obj`.
+/// Normals are written when every vertex has one, and `pointcolors` when
+/// there is one per vertex.
+///
+/// # Arguments
+/// * `mesh` - The mesh to write
+/// * `filepath` - The path of the PLY file
+/// * `format` - ASCII or binary little endian data
+///
+/// # Returns
+/// A Result indicating success or failure of the file write operation.
+pub fn write_ply(mesh: &Mesh, filepath: &str, format: PlyFormat) -> Result<(), SessionError> {
+    let (vertices, faces) = mesh.to_vertices_and_faces();
+    let mut keys: Vec<usize> = mesh.vertex.keys().copied().collect();
+    keys.sort();
+    let normals: Option<Vec<[f64; 3]>> = keys
+        .iter()
+        .map(|key| mesh.vertex[key].normal().map(|normal| normal.map(to_f64)))
+        .collect();
+    let colors = (mesh.pointcolors.len() == vertices.len()).then_some(&mesh.pointcolors);
+    let faces: Vec<Vec<usize>> = faces.into_iter().filter(|f| f.len() >= 3).collect();
+    let count_type = if faces.iter().all(|f| f.len() <= u8::MAX as usize) {
+        "uchar"
+    } else {
+        "uint"
+    };
+
+    let mut out = BufWriter::new(std::fs::File::create(filepath)?);
+    writeln!(out, "ply")?;
+    match format {
+        PlyFormat::Ascii => writeln!(out, "format ascii 1.0")?,
+        PlyFormat::BinaryLittleEndian => writeln!(out, "format binary_little_endian 1.0")?,
+    }
+    writeln!(out, "element vertex {}", vertices.len())?;
+    for name in ["x", "y", "z"] {
+        writeln!(out, "property double {name}")?;
+    }
+    if normals.is_some() {
+        for name in ["nx", "ny", "nz"] {
+            writeln!(out, "property double {name}")?;
+        }
+    }
+    if colors.is_some() {
+        for name in ["red", "green", "blue", "alpha"] {
+            writeln!(out, "property uchar {name}")?;
+        }
+    }
+    writeln!(out, "element face {}", faces.len())?;
+    writeln!(out, "property list {count_type} uint vertex_indices")?;
+    writeln!(out, "end_header")?;
+
+    for (i, point) in vertices.iter().enumerate() {
+        let mut floats = vec![to_f64(point.x()), to_f64(point.y()), to_f64(point.z())];
+        if let Some(normals) = &normals {
+            floats.extend_from_slice(&normals[i]);
+        }
+        let rgba = colors.map(|colors| [colors[i].r, colors[i].g, colors[i].b, colors[i].a]);
+        match format {
+            PlyFormat::Ascii => {
+                let mut fields: Vec<String> = floats.iter().map(f64::to_string).collect();
+                fields.extend(rgba.iter().flatten().map(u8::to_string));
+                writeln!(out, "{}", fields.join(" "))?;
+            }
+            PlyFormat::BinaryLittleEndian => {
+                for value in floats {
+                    out.write_all(&value.to_le_bytes())?;
+                }
+                if let Some(rgba) = rgba {
+                    out.write_all(&rgba)?;
+                }
+            }
+        }
+    }
+
+    for face in &faces {
+        match format {
+            PlyFormat::Ascii => {
+                let fields: Vec<String> = face.iter().map(usize::to_string).collect();
+                writeln!(out, "{} {}", face.len(), fields.join(" "))?;
+            }
+            PlyFormat::BinaryLittleEndian => {
+                if count_type == "uchar" {
+                    out.write_all(&[face.len() as u8])?;
+                } else {
+                    out.write_all(&(face.len() as u32).to_le_bytes())?;
+                }
+                for &index in face {
+                    out.write_all(&(index as u32).to_le_bytes())?;
+                }
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "ply_test.rs"]
+mod ply_test;
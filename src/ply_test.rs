@@ -0,0 +1,94 @@
+use crate::ply::{read_ply, write_ply, PlyFormat};
+use crate::{Color, Mesh, Point, SessionError};
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("{}_{}.ply", name, uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn colored_quad() -> Mesh {
+    let mut mesh = Mesh::new();
+    let keys: Vec<usize> = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        .iter()
+        .map(|&(x, y)| mesh.add_vertex(Point::new(x, y, 0.25), None))
+        .collect();
+    mesh.add_face(keys.clone(), None);
+    for (i, key) in keys.iter().enumerate() {
+        mesh.vertex.get_mut(key).unwrap().set_normal(0.0, 0.0, 1.0);
+        mesh.set_vertex_color(i, Color::new(10 * i as u8, 20, 30, 255));
+    }
+    mesh
+}
+
+#[test]
+fn test_ply_roundtrip_ascii_and_binary() {
+    let mesh = colored_quad();
+    for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian] {
+        let path = temp_path("roundtrip");
+        write_ply(&mesh, &path, format).unwrap();
+        let loaded = read_ply(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.to_vertices_and_faces(), mesh.to_vertices_and_faces());
+        let mut keys: Vec<usize> = loaded.vertex.keys().copied().collect();
+        keys.sort();
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(loaded.vertex[key].normal(), Some([0.0, 0.0, 1.0]));
+            let color = &loaded.pointcolors[i];
+            assert_eq!(
+                [color.r, color.g, color.b, color.a],
+                [10 * i as u8, 20, 30, 255]
+            );
+        }
+    }
+}
+
+#[test]
+fn test_read_ply_scanner_ascii() {
+    // Float colors, an unknown property and an extra element are accepted
+    let path = temp_path("scanner");
+    std::fs::write(
+        &path,
+        "ply\nformat ascii 1.0\ncomment scanner\nelement vertex 3\nproperty float x\n\
+         property float y\nproperty float z\nproperty float confidence\nproperty float red\n\
+         property float green\nproperty float blue\nelement face 1\n\
+         property list uchar int vertex_index\nelement camera 1\nproperty float fov\n\
+         end_header\n0 0 0 0.9 1 0 0\n2 0 0 0.8 0 1 0\n0 2 0 0.7 0 0 0.5\n3 0 1 2\n60\n",
+    )
+    .unwrap();
+    let mesh = read_ply(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    let (vertices, faces) = mesh.to_vertices_and_faces();
+    assert_eq!(vertices.len(), 3);
+    assert_eq!(vertices[1].x(), 2.0);
+    assert_eq!(faces, vec![vec![0, 1, 2]]);
+    let blue = &mesh.pointcolors[2];
+    assert_eq!([blue.r, blue.g, blue.b, blue.a], [0, 0, 128, 255]);
+    assert!(mesh.vertex.values().all(|v| v.normal().is_none()));
+}
+
+#[test]
+fn test_read_ply_errors() {
+    let path = temp_path("errors");
+    std::fs::write(
+        &path,
+        "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\n\
+         property float z\nelement face 1\nproperty list uchar int vertex_indices\nend_header\n\
+         0 0 0\n3 0 1 2\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        read_ply(&path),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+
+    std::fs::write(&path, "ply\nformat binary_big_endian 1.0\nend_header\n").unwrap();
+    assert!(matches!(
+        read_ply(&path),
+        Err(SessionError::SchemaMismatch(_))
+    ));
+    std::fs::remove_file(&path).ok();
+}
@@ -1,8 +1,15 @@
-use crate::{Color, Vector, Xform};
-use serde::{ser::Serialize as SerTrait, Deserialize, Serialize};
-use std::fmt;
-use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
-use uuid::Uuid;
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{Color, Line, Plane, Scalar, Vector, Xform};
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use serde::ser::Serialize as SerTrait;
+use serde::{Deserialize, Serialize};
 
 /// A 3D point with visual properties and JSON serialization support.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,12 +18,12 @@ pub struct Point {
     pub guid: String, // Unique identifier
     pub name: String, // Name of the point
     #[serde(rename = "x")]
-    _x: f64, // X coordinate (private)
+    _x: Scalar, // X coordinate (private)
     #[serde(rename = "y")]
-    _y: f64, // Y coordinate (private)
+    _y: Scalar, // Y coordinate (private)
     #[serde(rename = "z")]
-    _z: f64, // Z coordinate (private)
-    pub width: f64,   // Width of the point
+    _z: Scalar, // Z coordinate (private)
+    pub width: Scalar, // Width of the point
     pub pointcolor: Color, // Color of the point
     #[serde(default = "Xform::identity")]
     pub xform: Xform, // Transformation matrix
@@ -28,7 +35,7 @@ impl Default for Point {
             _x: 0.0,
             _y: 0.0,
             _z: 0.0,
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_point".to_string(),
             pointcolor: Color::white(),
             width: 1.0,
@@ -39,7 +46,7 @@ impl Default for Point {
 
 impl Point {
     /// Creates a new Point with specified coordinates.
-    pub fn new(x: f64, y: f64, z: f64) -> Self {
+    pub fn new(x: Scalar, y: Scalar, z: Scalar) -> Self {
         Self {
             _x: x,
             _y: y,
@@ -49,24 +56,24 @@ impl Point {
     }
 
     /// Getters for coordinates
-    pub fn x(&self) -> f64 {
+    pub fn x(&self) -> Scalar {
         self._x
     }
-    pub fn y(&self) -> f64 {
+    pub fn y(&self) -> Scalar {
         self._y
     }
-    pub fn z(&self) -> f64 {
+    pub fn z(&self) -> Scalar {
         self._z
     }
 
     /// Setters for coordinates
-    pub fn set_x(&mut self, v: f64) {
+    pub fn set_x(&mut self, v: Scalar) {
         self._x = v;
     }
-    pub fn set_y(&mut self, v: f64) {
+    pub fn set_y(&mut self, v: Scalar) {
         self._y = v;
     }
-    pub fn set_z(&mut self, v: f64) {
+    pub fn set_z(&mut self, v: Scalar) {
         self._z = v;
     }
 
@@ -75,7 +82,8 @@ impl Point {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Serializes the Point to a JSON string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
@@ -84,12 +92,14 @@ impl Point {
     }
 
     /// Deserializes a Point from a JSON string.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
     /// Serializes the Point to a JSON file.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
@@ -107,19 +117,48 @@ impl Point {
         result
     }
 
+    /// Transform many points in place by one xform, see `Xform::transform_point`.
+    ///
+    /// Affine xforms take a branch free loop over the coordinates, and with the
+    /// `parallel` feature the points are split in chunks over the rayon thread pool.
+    pub fn transform_many(xform: &Xform, points: &mut [Point]) {
+        let m = xform.m;
+        let affine = xform.is_affine();
+        let apply = |chunk: &mut [Point]| {
+            if affine {
+                for point in chunk {
+                    let (x, y, z) = (point._x, point._y, point._z);
+                    point._x = m[0] * x + m[4] * y + m[8] * z + m[12];
+                    point._y = m[1] * x + m[5] * y + m[9] * z + m[13];
+                    point._z = m[2] * x + m[6] * y + m[10] * z + m[14];
+                }
+            } else {
+                for point in chunk {
+                    xform.transform_point(point);
+                }
+            }
+        };
+        #[cfg(feature = "parallel")]
+        points.par_chunks_mut(4096).for_each(apply);
+        #[cfg(not(feature = "parallel"))]
+        apply(points);
+    }
+
     /// Deserializes a Point from a JSON file.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
     // Details
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    /// Check if the points are in counter-clockwise order.
+    /// Check if the points are in counter-clockwise order in the XY plane.
+    /// Uses the exact `orient2d` predicate, so collinear points are never ccw.
     pub fn ccw(a: &Point, b: &Point, c: &Point) -> bool {
-        (c._y - a._y) * (b._x - a._x) > (b._y - a._y) * (c._x - a._x)
+        crate::predicates::orient2d(a, b, c) > 0.0
     }
 
     /// Calculate the mid point between this point and another point.
@@ -132,21 +171,21 @@ impl Point {
     }
 
     /// Calculate the distance between this point and another point.
-    pub fn distance(&self, p: &Point) -> f64 {
+    pub fn distance(&self, p: &Point) -> Scalar {
         self.distance_with_min(p, 1e-12)
     }
 
     /// Calculate the distance between this point and another point with custom minimum.
-    pub fn distance_with_min(&self, p: &Point, double_min: f64) -> f64 {
+    pub fn distance_with_min(&self, p: &Point, double_min: Scalar) -> Scalar {
         let mut dx = (self[0] - p[0]).abs();
         let mut dy = (self[1] - p[1]).abs();
         let mut dz = (self[2] - p[2]).abs();
 
         // Reorder coordinates to put largest in dx
         if dy >= dx && dy >= dz {
-            std::mem::swap(&mut dx, &mut dy);
+            core::mem::swap(&mut dx, &mut dy);
         } else if dz >= dx && dz >= dy {
-            std::mem::swap(&mut dx, &mut dz);
+            core::mem::swap(&mut dx, &mut dz);
         }
 
         if dx > double_min {
@@ -160,8 +199,39 @@ impl Point {
         }
     }
 
+    /// Signed distance to a plane, positive on the side its normal points to.
+    pub fn distance_to_plane(&self, plane: &Plane) -> Scalar {
+        plane.signed_distance(self)
+    }
+
+    /// Closest point on a plane, along the plane normal.
+    pub fn project_to_plane(&self, plane: &Plane) -> Point {
+        let distance = self.distance_to_plane(plane);
+        self.clone() - plane.z_axis() * distance
+    }
+
+    /// Closest point on the infinite line through a line segment.
+    ///
+    /// A zero length line projects every point to its start.
+    pub fn project_to_line(&self, line: &Line) -> Point {
+        let start = line.start();
+        let direction = line.to_vector();
+        let length_squared = direction.length_squared();
+        if length_squared == 0.0 {
+            return start;
+        }
+        let t = (self.clone() - start.clone()).dot(&direction) / length_squared;
+        start + direction * t
+    }
+
+    /// Mirror image on the other side of a plane.
+    pub fn mirror(&self, plane: &Plane) -> Point {
+        let distance = self.distance_to_plane(plane);
+        self.clone() - plane.z_axis() * (2.0 * distance)
+    }
+
     /// Calculate the area of a polygon.
-    pub fn area(points: &[Point]) -> f64 {
+    pub fn area(points: &[Point]) -> Scalar {
         let n = points.len();
         let mut area = 0.0;
 
@@ -209,13 +279,16 @@ impl Point {
 
 impl fmt::Display for Point {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use crate::tolerance::TOL;
+        #[cfg(feature = "std")]
+        let tol = &*crate::tolerance::TOL;
+        #[cfg(not(feature = "std"))]
+        let tol = &crate::Tolerance::default();
         write!(
             f,
             "Point(x={}, y={}, z={})",
-            TOL.format_number(self._x, None),
-            TOL.format_number(self._y, None),
-            TOL.format_number(self._z, None)
+            tol.format_number(self._x, None),
+            tol.format_number(self._y, None),
+            tol.format_number(self._z, None)
         )
     }
 }
@@ -236,7 +309,7 @@ impl PartialEq for Point {
 ///////////////////////////////////////////////////////////////////////////////////////////
 
 impl Index<usize> for Point {
-    type Output = f64;
+    type Output = Scalar;
 
     fn index(&self, index: usize) -> &Self::Output {
         match index {
@@ -263,16 +336,16 @@ impl IndexMut<usize> for Point {
 // No-copy operators
 ///////////////////////////////////////////////////////////////////////////////////////////
 
-impl MulAssign<f64> for Point {
-    fn mul_assign(&mut self, rhs: f64) {
+impl MulAssign<Scalar> for Point {
+    fn mul_assign(&mut self, rhs: Scalar) {
         self._x *= rhs;
         self._y *= rhs;
         self._z *= rhs;
     }
 }
 
-impl DivAssign<f64> for Point {
-    fn div_assign(&mut self, rhs: f64) {
+impl DivAssign<Scalar> for Point {
+    fn div_assign(&mut self, rhs: Scalar) {
         self._x /= rhs;
         self._y /= rhs;
         self._z /= rhs;
@@ -299,18 +372,18 @@ impl SubAssign<Vector> for Point {
 // Copy operators
 ///////////////////////////////////////////////////////////////////////////////////////////
 
-impl Mul<f64> for Point {
+impl Mul<Scalar> for Point {
     type Output = Point;
 
-    fn mul(self, rhs: f64) -> Self::Output {
+    fn mul(self, rhs: Scalar) -> Self::Output {
         Point::new(self._x * rhs, self._y * rhs, self._z * rhs)
     }
 }
 
-impl Div<f64> for Point {
+impl Div<Scalar> for Point {
     type Output = Point;
 
-    fn div(self, rhs: f64) -> Self::Output {
+    fn div(self, rhs: Scalar) -> Self::Output {
         Point::new(self._x / rhs, self._y / rhs, self._z / rhs)
     }
 }
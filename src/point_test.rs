@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::encoders::{json_dump, json_load};
-    use crate::{Color, Point, Vector};
+    use crate::{Color, Line, Plane, Point, Scalar, Vector, Xform};
 
     #[test]
     fn test_point_constructor() {
@@ -251,4 +251,64 @@ mod tests {
         assert_eq!((centroid.y() * 1000000.0).round() / 1000000.0, 0.5);
         assert_eq!((centroid.z() * 1000000.0).round() / 1000000.0, 0.0);
     }
+
+    #[test]
+    fn test_point_plane_projection_and_mirror() {
+        let plane = Plane::from_point_normal(Point::new(0.0, 0.0, 1.0), Vector::new(0.0, 0.0, 2.0));
+        let point = Point::new(2.0, 3.0, 4.0);
+        assert_eq!(point.distance_to_plane(&plane), 3.0);
+        assert_eq!(Point::new(0.0, 0.0, -1.0).distance_to_plane(&plane), -2.0);
+
+        let projected = point.project_to_plane(&plane);
+        assert_eq!(
+            (projected.x(), projected.y(), projected.z()),
+            (2.0, 3.0, 1.0)
+        );
+        let mirrored = point.mirror(&plane);
+        assert_eq!((mirrored.x(), mirrored.y(), mirrored.z()), (2.0, 3.0, -2.0));
+        assert_eq!(mirrored.mirror(&plane).z(), 4.0);
+    }
+
+    #[test]
+    fn test_point_project_to_line() {
+        let line = Line::new(1.0, 1.0, 0.0, 3.0, 1.0, 0.0);
+        // Beyond the end of the segment, on the infinite line
+        let projected = Point::new(5.0, 4.0, 2.0).project_to_line(&line);
+        assert_eq!(
+            (projected.x(), projected.y(), projected.z()),
+            (5.0, 1.0, 0.0)
+        );
+
+        let degenerate = Line::new(1.0, 2.0, 3.0, 1.0, 2.0, 3.0);
+        let projected = Point::new(5.0, 4.0, 2.0).project_to_line(&degenerate);
+        assert_eq!(
+            (projected.x(), projected.y(), projected.z()),
+            (1.0, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn test_point_transform_many() {
+        let mut projective = Xform::rotation_z(0.5);
+        projective.m[3] = 0.1;
+        for xform in [
+            &Xform::translation(1.0, 2.0, 3.0) * &Xform::rotation_z(0.5),
+            projective.clone(),
+        ] {
+            let mut points: Vec<Point> = (0..10_000)
+                .map(|i| Point::new(i as Scalar * 0.01, 1.0, -(i as Scalar)))
+                .collect();
+            let mut expected = points.clone();
+            for point in &mut expected {
+                xform.transform_point(point);
+            }
+            Point::transform_many(&xform, &mut points);
+            assert!(points
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| a.distance(b) < 1e-9));
+        }
+        assert!(!projective.is_affine());
+        Point::transform_many(&Xform::identity(), &mut []);
+    }
 }
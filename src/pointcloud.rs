@@ -1,34 +1,172 @@
-use crate::{Color, Point, Vector, Xform};
+use crate::bvh::calculate_morton_code;
+use crate::error::SessionError;
+use crate::intersection::{self, Containment};
+use crate::scalar::{to_f32, to_f64};
+use crate::{
+    points, AsVec3, BoundingBox, Color, Colormap, KdTree, Mesh, Plane, Point, Scalar, Tolerance,
+    Vec3, Vector, Xform,
+};
+use once_cell::sync::OnceCell;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
 use std::fmt;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
-use uuid::Uuid;
+use std::ops::{Add, AddAssign, Range, Sub, SubAssign};
 
+/// A point cloud with optional per-point normals, colors and intensities.
+///
+/// Points and normals are stored as plain `Vec3` coordinates, so large clouds
+/// carry no per-point GUID or name.
+/// Clouds to load part by part are stored as blocks in `ChunkedPointCloud`.
 #[derive(Debug, Clone)]
 pub struct PointCloud {
     pub guid: String,
     pub name: String,
-    pub points: Vec<Point>,
-    pub normals: Vec<Vector>,
+    pub points: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
     pub colors: Vec<Color>,
+    // Scanner intensities, empty when the source has none
+    pub intensities: Vec<Scalar>,
     pub xform: Xform,
+    // Cached KD-tree for closest point queries (not serialized), filled through shared
+    // references, reset with `kdtree.take()` after editing points in place
+    pub kdtree: OnceCell<KdTree>,
+}
+
+/// A block of points with their normals, colors and intensities, and the bounds of the points.
+///
+/// `ChunkedPointCloud` stores, writes and loads clouds as such blocks.
+#[derive(Debug, Clone)]
+pub struct PointChunk {
+    /// Axis-aligned bounds of the points
+    pub bbox: BoundingBox,
+    pub points: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub colors: Vec<Color>,
+    pub intensities: Vec<Scalar>,
+}
+
+impl PointChunk {
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Find the first point along a ray, see `PointCloud::ray_cast`.
+    ///
+    /// Every point of the chunk is tested, chunks are small enough to skip a KD-tree.
+    ///
+    /// # Returns
+    /// Point index in the chunk and distance from the origin to its
+    /// projection onto the ray, or None if no point is within the radius.
+    pub fn ray_cast(
+        &self,
+        origin: &Point,
+        direction: &Vector,
+        splat_radius: Scalar,
+    ) -> Option<(usize, Scalar)> {
+        ray_cast_points(&self.points, origin, direction, splat_radius)
+    }
+}
+
+// Min-heap entry of the orientation spanning tree, the most parallel pair on top
+#[derive(Debug, Clone, Copy)]
+struct OrientationEdge {
+    weight: Scalar,
+    from: usize,
+    to: usize,
+}
+
+impl PartialEq for OrientationEdge {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrientationEdge {}
+
+impl PartialOrd for OrientationEdge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrientationEdge {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.weight.total_cmp(&self.weight)
+    }
+}
+
+/// Next value of the splitmix64 generator, used for reproducible sampling.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The point nearest to the ray origin among the points within `splat_radius` of the ray.
+fn ray_cast_points(
+    points: &[Vec3],
+    origin: &Point,
+    direction: &Vector,
+    splat_radius: Scalar,
+) -> Option<(usize, Scalar)> {
+    let direction = direction.as_vec3().normalized()?;
+    let origin = origin.as_vec3();
+    let radius_squared = splat_radius * splat_radius;
+    let mut first: Option<(usize, Scalar)> = None;
+    for (index, point) in points.iter().enumerate() {
+        let offset = *point - origin;
+        let t = offset.dot(&direction);
+        if t < 0.0 || first.is_some_and(|(_, best)| t >= best) {
+            continue;
+        }
+        let closest = offset - direction * t;
+        if closest.dot(&closest) <= radius_squared {
+            first = Some((index, t));
+        }
+    }
+    first
 }
 
 impl Default for PointCloud {
     fn default() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_pointcloud".to_string(),
             points: Vec::new(),
             normals: Vec::new(),
             colors: Vec::new(),
+            intensities: Vec::new(),
             xform: Xform::identity(),
+            kdtree: OnceCell::new(),
         }
     }
 }
 
 impl PointCloud {
+    /// Points per chunk of `ChunkedPointCloud`s built for sessions, and the
+    /// size above which a `Session` splits a cloud into chunk leaves.
+    pub const CHUNK_SIZE: usize = 65_536;
+
     pub fn new(points: Vec<Point>, normals: Vec<Vector>, colors: Vec<Color>) -> Self {
+        Self::from_coords(
+            points.iter().map(Vec3::from).collect(),
+            normals.iter().map(Vec3::from).collect(),
+            colors,
+        )
+    }
+
+    /// Create a point cloud from coordinates, without building `Point`s first.
+    pub fn from_coords(points: Vec<Vec3>, normals: Vec<Vec3>, colors: Vec<Color>) -> Self {
         Self {
             points,
             normals,
@@ -37,6 +175,24 @@ impl PointCloud {
         }
     }
 
+    /// The point at an index as a `Point`.
+    pub fn point(&self, index: usize) -> Option<Point> {
+        self.points.get(index).map(|&p| p.into())
+    }
+
+    /// The normal at an index as a `Vector`.
+    pub fn normal(&self, index: usize) -> Option<Vector> {
+        self.normals.get(index).map(|&n| n.into())
+    }
+
+    /// Point coordinates as `x y z` floats, ready for a GPU vertex buffer.
+    pub fn positions_f32(&self) -> Vec<f32> {
+        self.points
+            .iter()
+            .flat_map(|&p| <[Scalar; 3]>::from(p).map(to_f32))
+            .collect()
+    }
+
     pub fn len(&self) -> usize {
         self.points.len()
     }
@@ -45,19 +201,522 @@ impl PointCloud {
         self.points.is_empty()
     }
 
+    /// Average of the points, None if the cloud is empty.
+    pub fn centroid(&self) -> Option<Point> {
+        points::centroid(&self.points)
+    }
+
+    /// Best-fit frame of the points, see `points::pca_frame`.
+    pub fn pca_frame(&self) -> Option<Plane> {
+        points::pca_frame(&self.points)
+    }
+
+    /// A copy without points within a tolerance of an earlier point.
+    ///
+    /// Normals, colors and intensities of the kept points are kept with them.
+    pub fn deduplicated(&self, tolerance: Scalar) -> PointCloud {
+        self.select(&points::deduplicate_indices(&self.points, tolerance))
+    }
+
+    /// A copy with one point per occupied cell of a grid.
+    ///
+    /// The point, normal, color and intensity of a cell are the averages of
+    /// the points in it, normals are normalized again. Cells are ordered by
+    /// their first point.
+    ///
+    /// # Arguments
+    /// * `cell_size` - Edge length of the cubic grid cells
+    pub fn voxel_downsample(&self, cell_size: Scalar) -> PointCloud {
+        let cell_size = cell_size.max(Scalar::MIN_POSITIVE);
+        let cell = |value: Scalar| (value / cell_size).floor() as i64;
+        let mut cells: HashMap<(i64, i64, i64), usize> = HashMap::new();
+        let mut members: Vec<Vec<usize>> = Vec::new();
+        for (index, point) in self.points.iter().enumerate() {
+            let key = (cell(point.x), cell(point.y), cell(point.z));
+            let slot = *cells.entry(key).or_insert_with(|| {
+                members.push(Vec::new());
+                members.len() - 1
+            });
+            members[slot].push(index);
+        }
+
+        let complete = |len: usize| len == self.points.len();
+        let mean = |values: &[Vec3], indices: &[usize]| {
+            indices.iter().fold(Vec3::ZERO, |sum, &i| sum + values[i])
+                * (1.0 / indices.len() as Scalar)
+        };
+        let mut result = PointCloud {
+            guid: crate::guid::new_guid(),
+            name: self.name.clone(),
+            xform: self.xform.clone(),
+            ..Default::default()
+        };
+        for indices in &members {
+            result.points.push(mean(&self.points, indices));
+            if complete(self.normals.len()) {
+                let normal = mean(&self.normals, indices);
+                result.normals.push(normal.normalized().unwrap_or(normal));
+            }
+            if complete(self.colors.len()) {
+                let channel = |value: fn(&Color) -> u8| {
+                    let sum: usize = indices
+                        .iter()
+                        .map(|&i| value(&self.colors[i]) as usize)
+                        .sum();
+                    ((sum + indices.len() / 2) / indices.len()) as u8
+                };
+                result.colors.push(Color::new(
+                    channel(|c| c.r),
+                    channel(|c| c.g),
+                    channel(|c| c.b),
+                    channel(|c| c.a),
+                ));
+            }
+            if complete(self.intensities.len()) {
+                let sum: Scalar = indices.iter().map(|&i| self.intensities[i]).sum();
+                result.intensities.push(sum / indices.len() as Scalar);
+            }
+        }
+        result
+    }
+
+    /// A copy with a random subset of the points, in their original order.
+    ///
+    /// The same seed always keeps the same points, so downsampled scans are
+    /// reproducible.
+    ///
+    /// # Arguments
+    /// * `fraction` - Share of the points to keep, clamped to [0-1]
+    /// * `seed` - Seed of the random selection
+    pub fn random_downsample(&self, fraction: Scalar, seed: u64) -> PointCloud {
+        let count = ((self.points.len() as Scalar * fraction.clamp(0.0, 1.0)).round() as usize)
+            .min(self.points.len());
+        // Partial Fisher-Yates shuffle
+        let mut state = seed;
+        let mut indices: Vec<usize> = (0..self.points.len()).collect();
+        for i in 0..count {
+            let j = i + (splitmix64(&mut state) % (indices.len() - i) as u64) as usize;
+            indices.swap(i, j);
+        }
+        indices.truncate(count);
+        indices.sort_unstable();
+        self.select(&indices)
+    }
+
+    /// Remove points far from their neighbours compared to the rest of the cloud.
+    ///
+    /// The mean distance of every point to its `k` nearest neighbours is
+    /// computed, points whose mean exceeds the average of all means by more
+    /// than `std_ratio` standard deviations are removed.
+    ///
+    /// # Returns
+    /// The filtered cloud and the indices of the removed points.
+    pub fn remove_statistical_outliers(
+        &mut self,
+        k: usize,
+        std_ratio: Scalar,
+    ) -> (PointCloud, Vec<usize>) {
+        let k = k.max(1);
+        self.ensure_kdtree();
+        let Some(tree) = self.kdtree.get() else {
+            return (self.select(&[]), Vec::new());
+        };
+        let mean_distance = |(index, point): (usize, &Vec3)| {
+            let distances: Vec<Scalar> = tree
+                .knn(point, k + 1)
+                .into_iter()
+                .filter(|&(other, _)| other != index)
+                .take(k)
+                .map(|(_, distance)| distance)
+                .collect();
+            distances.iter().sum::<Scalar>() / distances.len().max(1) as Scalar
+        };
+        #[cfg(feature = "parallel")]
+        let means: Vec<Scalar> = self
+            .points
+            .par_iter()
+            .enumerate()
+            .map(mean_distance)
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let means: Vec<Scalar> = self.points.iter().enumerate().map(mean_distance).collect();
+
+        let count = means.len().max(1) as Scalar;
+        let average = means.iter().sum::<Scalar>() / count;
+        let variance = means
+            .iter()
+            .map(|m| (m - average) * (m - average))
+            .sum::<Scalar>()
+            / count;
+        let limit = average + std_ratio * variance.sqrt();
+        self.split_by(|index| means[index] <= limit)
+    }
+
+    /// Remove points with fewer than `min_neighbors` other points within `radius`.
+    ///
+    /// # Returns
+    /// The filtered cloud and the indices of the removed points.
+    pub fn remove_radius_outliers(
+        &mut self,
+        radius: Scalar,
+        min_neighbors: usize,
+    ) -> (PointCloud, Vec<usize>) {
+        self.ensure_kdtree();
+        let Some(tree) = self.kdtree.get() else {
+            return (self.select(&[]), Vec::new());
+        };
+        let enough = |(index, point): (usize, &Vec3)| {
+            tree.radius_search(point, radius)
+                .iter()
+                .filter(|&&(other, _)| other != index)
+                .count()
+                >= min_neighbors
+        };
+        #[cfg(feature = "parallel")]
+        let kept: Vec<bool> = self.points.par_iter().enumerate().map(enough).collect();
+        #[cfg(not(feature = "parallel"))]
+        let kept: Vec<bool> = self.points.iter().enumerate().map(enough).collect();
+        self.split_by(|index| kept[index])
+    }
+
+    /// The cloud of the points that pass a test, and the indices of the others.
+    fn split_by(&self, keep: impl Fn(usize) -> bool) -> (PointCloud, Vec<usize>) {
+        let (kept, removed): (Vec<usize>, Vec<usize>) =
+            (0..self.points.len()).partition(|&index| keep(index));
+        (self.select(&kept), removed)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Chunks
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// A copy with the points reordered along a Morton curve, so nearby points are stored together.
+    ///
+    /// Sorting before chunking keeps the chunk bounds compact. Points with the
+    /// same code keep their order.
+    pub fn spatially_sorted(&self) -> PointCloud {
+        let bbox = BoundingBox::from_points(&self.points, 0.0);
+        let center = bbox.center.as_vec3();
+        let world_size = 2.0
+            * bbox
+                .half_size
+                .x()
+                .max(bbox.half_size.y())
+                .max(bbox.half_size.z())
+                .max(Tolerance::ZERO_TOLERANCE);
+        let code = |point: &Vec3| {
+            let p = *point - center;
+            calculate_morton_code(p.x, p.y, p.z, world_size)
+        };
+        #[cfg(feature = "parallel")]
+        let codes: Vec<u32> = self.points.par_iter().map(code).collect();
+        #[cfg(not(feature = "parallel"))]
+        let codes: Vec<u32> = self.points.iter().map(code).collect();
+
+        let mut order: Vec<usize> = (0..self.points.len()).collect();
+        order.sort_by_key(|&index| codes[index]);
+        self.select(&order)
+    }
+
+    /// Bounds of blocks of `chunk_size` consecutive points, the chunks of `ChunkedPointCloud`.
+    ///
+    /// A `Session` gives clouds of more than `CHUNK_SIZE` points one ray and
+    /// collision BVH leaf per chunk. Sort with `spatially_sorted` first to
+    /// keep the bounds compact.
+    pub fn chunk_bounds(&self, chunk_size: usize) -> Vec<BoundingBox> {
+        let bounds = |points: &[Vec3]| BoundingBox::from_points(points, 0.0);
+        #[cfg(feature = "parallel")]
+        let boxes = self
+            .points
+            .par_chunks(chunk_size.max(1))
+            .map(bounds)
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let boxes = self.points.chunks(chunk_size.max(1)).map(bounds).collect();
+        boxes
+    }
+
+    /// Find the first point of a range along a ray, testing each point without the KD-tree.
+    ///
+    /// # Returns
+    /// Point index in the cloud and distance from the origin to its projection
+    /// onto the ray, or None if no point of the range is within the radius.
+    pub fn ray_cast_range(
+        &self,
+        range: Range<usize>,
+        origin: &Point,
+        direction: &Vector,
+        splat_radius: Scalar,
+    ) -> Option<(usize, Scalar)> {
+        let start = range.start;
+        let points = self.points.get(range)?;
+        ray_cast_points(points, origin, direction, splat_radius)
+            .map(|(index, t)| (start + index, t))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Cropping
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Keep the points inside a box or on its boundary, see `BoundingBox::contains_point`.
+    ///
+    /// # Returns
+    /// The cropped cloud and a mask that is true for the kept points.
+    pub fn crop_box(&self, bbox: &BoundingBox) -> (PointCloud, Vec<bool>) {
+        let inside = |point: &Vec3| bbox.contains_point(point);
+        #[cfg(feature = "parallel")]
+        let mask: Vec<bool> = self.points.par_iter().map(inside).collect();
+        #[cfg(not(feature = "parallel"))]
+        let mask: Vec<bool> = self.points.iter().map(inside).collect();
+        self.crop_by_mask(mask)
+    }
+
+    /// Keep the points on one side of a plane, points on the plane are kept on both sides.
+    ///
+    /// # Arguments
+    /// * `plane` - The cutting plane
+    /// * `keep_side` - True keeps the side the plane normal points to, false the other side
+    ///
+    /// # Returns
+    /// The cropped cloud and a mask that is true for the kept points.
+    pub fn crop_plane(&self, plane: &Plane, keep_side: bool) -> (PointCloud, Vec<bool>) {
+        let (origin, normal) = (plane.origin().as_vec3(), plane.z_axis().as_vec3());
+        let sign = if keep_side { 1.0 } else { -1.0 };
+        let mask = self
+            .points
+            .iter()
+            .map(|point| sign * (*point - origin).dot(&normal) >= -Tolerance::ZERO_TOLERANCE)
+            .collect();
+        self.crop_by_mask(mask)
+    }
+
+    /// Keep the points inside or outside a closed mesh, see `intersection::point_in_mesh`.
+    ///
+    /// Points on the mesh surface count as inside. Coordinates are compared as
+    /// stored, neither xform is applied.
+    ///
+    /// # Arguments
+    /// * `mesh` - Closed mesh bounding the region
+    /// * `inside` - True keeps the points inside the mesh, false the points outside
+    ///
+    /// # Returns
+    /// The cropped cloud and a mask that is true for the kept points.
+    pub fn select_by_mesh(&self, mesh: &Mesh, inside: bool) -> (PointCloud, Vec<bool>) {
+        let triangles = mesh.triangles();
+        let keep = |point: &Vec3| {
+            let containment = intersection::point_in_triangles(
+                &Point::from(*point),
+                &triangles,
+                Tolerance::ZERO_TOLERANCE,
+            );
+            (containment != Containment::Outside) == inside
+        };
+        #[cfg(feature = "parallel")]
+        let mask: Vec<bool> = self.points.par_iter().map(keep).collect();
+        #[cfg(not(feature = "parallel"))]
+        let mask: Vec<bool> = self.points.iter().map(keep).collect();
+        self.crop_by_mask(mask)
+    }
+
+    fn crop_by_mask(&self, mask: Vec<bool>) -> (PointCloud, Vec<bool>) {
+        let kept: Vec<usize> = (0..mask.len()).filter(|&index| mask[index]).collect();
+        (self.select(&kept), mask)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Segmentation
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Group points connected by chains of close points, DBSCAN style.
+    ///
+    /// Points with at least `min_points` points within `distance_threshold`,
+    /// counting themselves, are core points. Core points closer than the
+    /// threshold share a cluster, other points join the cluster of a core
+    /// point near them and are noise otherwise.
+    ///
+    /// # Returns
+    /// Clusters of sorted point indices, ordered by their first point. Noise
+    /// points are in no cluster.
+    pub fn cluster_euclidean(
+        &mut self,
+        distance_threshold: Scalar,
+        min_points: usize,
+    ) -> Vec<Vec<usize>> {
+        self.ensure_kdtree();
+        let Some(tree) = self.kdtree.get() else {
+            return Vec::new();
+        };
+        let neighbours = |point: &Vec3| -> Vec<usize> {
+            tree.radius_search(point, distance_threshold)
+                .into_iter()
+                .map(|(index, _)| index)
+                .collect()
+        };
+        #[cfg(feature = "parallel")]
+        let neighbourhoods: Vec<Vec<usize>> = self.points.par_iter().map(neighbours).collect();
+        #[cfg(not(feature = "parallel"))]
+        let neighbourhoods: Vec<Vec<usize>> = self.points.iter().map(neighbours).collect();
+        let is_core = |index: usize| neighbourhoods[index].len() >= min_points.max(1);
+
+        let mut cluster_of: Vec<Option<usize>> = vec![None; self.points.len()];
+        let mut clusters: Vec<Vec<usize>> = Vec::new();
+        for seed in 0..self.points.len() {
+            if cluster_of[seed].is_some() || !is_core(seed) {
+                continue;
+            }
+            let cluster = clusters.len();
+            let mut members = vec![seed];
+            cluster_of[seed] = Some(cluster);
+            let mut stack = vec![seed];
+            while let Some(core) = stack.pop() {
+                for &next in &neighbourhoods[core] {
+                    if cluster_of[next].is_some() {
+                        continue;
+                    }
+                    cluster_of[next] = Some(cluster);
+                    members.push(next);
+                    if is_core(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            members.sort_unstable();
+            clusters.push(members);
+        }
+        clusters.sort_by_key(|members| members[0]);
+        clusters
+    }
+
+    /// Find the plane with the most points within `distance` by RANSAC.
+    ///
+    /// Planes through random triples of points are scored by their inliers,
+    /// the best one is refit to its inliers by least squares. The samples
+    /// come from a fixed seed, so the result is reproducible.
+    ///
+    /// # Arguments
+    /// * `ransac_iterations` - Number of sampled triples
+    /// * `distance` - Largest distance of an inlier to the plane
+    ///
+    /// # Returns
+    /// The plane and the sorted indices of its inliers, or None for fewer
+    /// than 3 points or only collinear samples.
+    pub fn segment_plane(
+        &self,
+        ransac_iterations: usize,
+        distance: Scalar,
+    ) -> Option<(Plane, Vec<usize>)> {
+        let candidates: Vec<usize> = (0..self.points.len()).collect();
+        self.ransac_plane(&candidates, ransac_iterations, distance, &mut 0)
+    }
+
+    /// Extract dominant planes one after the other, see `segment_plane`.
+    ///
+    /// The inliers of each plane are removed before searching the next one.
+    ///
+    /// # Arguments
+    /// * `ransac_iterations` - Number of sampled triples per plane
+    /// * `distance` - Largest distance of an inlier to its plane
+    /// * `min_inliers` - Stop when the best remaining plane has fewer inliers
+    ///
+    /// # Returns
+    /// Planes with the sorted indices of their inliers, largest first.
+    pub fn segment_planes(
+        &self,
+        ransac_iterations: usize,
+        distance: Scalar,
+        min_inliers: usize,
+    ) -> Vec<(Plane, Vec<usize>)> {
+        let mut remaining: Vec<usize> = (0..self.points.len()).collect();
+        let mut planes = Vec::new();
+        let mut state = 0;
+        while let Some((plane, inliers)) =
+            self.ransac_plane(&remaining, ransac_iterations, distance, &mut state)
+        {
+            if inliers.len() < min_inliers.max(3) {
+                break;
+            }
+            remaining.retain(|index| inliers.binary_search(index).is_err());
+            planes.push((plane, inliers));
+        }
+        planes
+    }
+
+    /// RANSAC plane among the candidate points, see `segment_plane`.
+    fn ransac_plane(
+        &self,
+        candidates: &[usize],
+        iterations: usize,
+        distance: Scalar,
+        state: &mut u64,
+    ) -> Option<(Plane, Vec<usize>)> {
+        if candidates.len() < 3 {
+            return None;
+        }
+        let inliers_of = |origin: Vec3, normal: Vec3| -> Vec<usize> {
+            candidates
+                .iter()
+                .copied()
+                .filter(|&i| (self.points[i] - origin).dot(&normal).abs() <= distance)
+                .collect()
+        };
+        let mut best: Option<Vec<usize>> = None;
+        for _ in 0..iterations {
+            let mut pick =
+                || self.points[candidates[(splitmix64(state) % candidates.len() as u64) as usize]];
+            let (a, b, c) = (pick(), pick(), pick());
+            let Some(normal) = (b - a).cross(&(c - a)).normalized() else {
+                continue;
+            };
+            let inliers = inliers_of(a, normal);
+            if best.as_ref().is_none_or(|best| inliers.len() > best.len()) {
+                best = Some(inliers);
+            }
+        }
+
+        // Least squares refit, keeping the sampled inliers if the refit loses points
+        let sampled = best?;
+        let coords: Vec<Vec3> = sampled.iter().map(|&i| self.points[i]).collect();
+        let plane = points::pca_frame(&coords)?;
+        let refit = inliers_of(plane.origin().as_vec3(), plane.z_axis().as_vec3());
+        let inliers = if refit.len() >= sampled.len() {
+            refit
+        } else {
+            sampled
+        };
+        let coords: Vec<Vec3> = inliers.iter().map(|&i| self.points[i]).collect();
+        Some((points::pca_frame(&coords)?, inliers))
+    }
+
+    /// A copy with the points at the indices and their normals, colors and intensities.
+    fn select(&self, indices: &[usize]) -> PointCloud {
+        fn pick<T: Clone>(values: &[T], indices: &[usize]) -> Vec<T> {
+            indices
+                .iter()
+                .filter_map(|&i| values.get(i).cloned())
+                .collect()
+        }
+        PointCloud {
+            guid: crate::guid::new_guid(),
+            name: self.name.clone(),
+            points: pick(&self.points, indices),
+            normals: pick(&self.normals, indices),
+            colors: pick(&self.colors, indices),
+            intensities: pick(&self.intensities, indices),
+            xform: self.xform.clone(),
+            kdtree: OnceCell::new(),
+        }
+    }
+
     ///////////////////////////////////////////////////////////////////////////////////////////
     // Transformation
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     pub fn transform(&mut self) {
         let xform = self.xform.clone();
-        for pt in &mut self.points {
-            xform.transform_point(pt);
-        }
-        for n in &mut self.normals {
-            xform.transform_vector(n);
-        }
+        Vec3::transform_points(&xform, &mut self.points);
+        Vec3::transform_vectors(&xform, &mut self.normals);
         self.xform = Xform::identity();
+        self.kdtree.take();
     }
 
     pub fn transformed(&self) -> Self {
@@ -66,11 +725,184 @@ impl PointCloud {
         result
     }
 
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Closest point queries
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    fn ensure_kdtree(&mut self) -> &KdTree {
+        if self
+            .kdtree
+            .get()
+            .is_some_and(|tree| tree.len() != self.points.len())
+        {
+            self.kdtree.take();
+        }
+        self.kdtree.get_or_init(|| KdTree::new(&self.points))
+    }
+
+    /// The KD-tree, built on first use through a shared reference.
+    ///
+    /// A cached tree of a different number of points is stale and only replaced
+    /// by `ensure_kdtree`, queries here use a temporary tree instead.
+    fn kdtree(&self) -> Cow<'_, KdTree> {
+        let tree = self.kdtree.get_or_init(|| KdTree::new(&self.points));
+        if tree.len() == self.points.len() {
+            Cow::Borrowed(tree)
+        } else {
+            Cow::Owned(KdTree::new(&self.points))
+        }
+    }
+
+    /// Find the point closest to a query point.
+    ///
+    /// The KD-tree is built on first use and reused until the points change.
+    ///
+    /// # Returns
+    /// Point index and distance, or None if the cloud is empty.
+    pub fn closest_point(&self, point: &Point) -> Option<(usize, Scalar)> {
+        self.kdtree().nearest(point)
+    }
+
+    /// Find the `k` points closest to a query point.
+    ///
+    /// # Returns
+    /// Point indices and distances sorted from nearest to farthest.
+    pub fn closest_points(&self, point: &Point, k: usize) -> Vec<(usize, Scalar)> {
+        self.kdtree().knn(point, k)
+    }
+
+    /// Find all points within `radius` of a query point.
+    ///
+    /// # Returns
+    /// Point indices and distances sorted from nearest to farthest.
+    pub fn points_within(&self, point: &Point, radius: Scalar) -> Vec<(usize, Scalar)> {
+        self.kdtree().radius_search(point, radius)
+    }
+
+    /// Find the first point along a ray, treating points as splats of a radius.
+    ///
+    /// # Arguments
+    /// * `origin` - Ray origin
+    /// * `direction` - Ray direction, need not be unit length
+    /// * `splat_radius` - Largest distance between a hit point and the ray
+    ///
+    /// # Returns
+    /// Point index and distance from the origin to its projection onto the
+    /// ray, or None if no point is within the radius.
+    pub fn ray_cast(
+        &self,
+        origin: &Point,
+        direction: &Vector,
+        splat_radius: Scalar,
+    ) -> Option<(usize, Scalar)> {
+        let direction = direction.as_vec3().normalized()?;
+        self.kdtree()
+            .ray_search(origin, &direction, splat_radius)
+            .into_iter()
+            .next()
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Normals
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Estimate a unit normal per point, replacing `normals`.
+    ///
+    /// Each normal is the direction of least spread of the point and its
+    /// nearest neighbours. The normals are then oriented consistently by
+    /// walking a minimum spanning tree of the neighbour graph that prefers
+    /// nearly parallel normals, starting from the highest point of each
+    /// connected part with its normal facing +Z.
+    ///
+    /// # Arguments
+    /// * `k_neighbors` - Neighbours per point, including the point itself, at least 3
+    pub fn estimate_normals(&mut self, k_neighbors: usize) {
+        let k = k_neighbors.max(3).min(self.points.len());
+        self.ensure_kdtree();
+        let (Some(tree), points) = (self.kdtree.get(), &self.points) else {
+            return;
+        };
+        let estimate = |point: &Vec3| {
+            let neighbours: Vec<usize> = tree.knn(point, k).into_iter().map(|(i, _)| i).collect();
+            let coords: Vec<Vec3> = neighbours.iter().map(|&i| points[i]).collect();
+            let normal = points::pca_frame(&coords)
+                .map(|frame| frame.z_axis().as_vec3())
+                .unwrap_or(Vec3::new(0.0, 0.0, 1.0));
+            (neighbours, normal)
+        };
+        #[cfg(feature = "parallel")]
+        let local: Vec<(Vec<usize>, Vec3)> = points.par_iter().map(estimate).collect();
+        #[cfg(not(feature = "parallel"))]
+        let local: Vec<(Vec<usize>, Vec3)> = points.iter().map(estimate).collect();
+
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+        for (i, (neighbours, _)) in local.iter().enumerate() {
+            for &j in neighbours.iter().filter(|&&j| j != i) {
+                adjacency[i].push(j);
+                adjacency[j].push(i);
+            }
+        }
+        let mut normals: Vec<Vec3> = local.into_iter().map(|(_, normal)| normal).collect();
+
+        let mut seeds: Vec<usize> = (0..points.len()).collect();
+        seeds.sort_by(|&a, &b| points[b].z.total_cmp(&points[a].z));
+        let mut oriented = vec![false; points.len()];
+        let mut heap = BinaryHeap::new();
+        for seed in seeds {
+            if oriented[seed] {
+                continue;
+            }
+            if normals[seed].z < 0.0 {
+                normals[seed] = -normals[seed];
+            }
+            heap.push(OrientationEdge {
+                weight: 0.0,
+                from: seed,
+                to: seed,
+            });
+            while let Some(OrientationEdge { from, to, .. }) = heap.pop() {
+                if oriented[to] {
+                    continue;
+                }
+                if normals[from].dot(&normals[to]) < 0.0 {
+                    normals[to] = -normals[to];
+                }
+                oriented[to] = true;
+                for &next in adjacency[to].iter().filter(|&&next| !oriented[next]) {
+                    heap.push(OrientationEdge {
+                        weight: 1.0 - normals[to].dot(&normals[next]).abs(),
+                        from: to,
+                        to: next,
+                    });
+                }
+            }
+        }
+        self.normals = normals;
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Deviation
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Replace the colors by a colormap of per-point deviations, see `deviation`.
+    ///
+    /// # Arguments
+    /// * `deviations` - One value per point
+    /// * `range` - Deviation shown with the last palette color, `-range` gets the first
+    /// * `palette` - The colormap
+    pub fn color_by_deviation(&mut self, deviations: &[Scalar], range: Scalar, palette: Colormap) {
+        let range = to_f64(range.abs());
+        self.colors = deviations
+            .iter()
+            .map(|&value| Color::map(to_f64(value), -range, range, palette))
+            .collect();
+    }
+
     ///////////////////////////////////////////////////////////////////////////////////////////
     // JSON
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
@@ -78,20 +910,219 @@ impl PointCloud {
         Ok(String::from_utf8(buf)?)
     }
 
-    pub fn jsonload(json_str: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_str: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_str)?)
     }
 
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json_str = self.jsondump()?;
         std::fs::write(filepath, json_str)?;
         Ok(())
     }
 
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json_str = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json_str)
+        Self::jsonload(&json_str).map_err(|e| e.with_path(filepath))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// XYZ and PTS Files
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Signed distance of every point to a mesh, for scan versus design inspection.
+///
+/// Distances are positive on the side the mesh faces point to. Points and
+/// mesh are compared as stored, neither xform is applied. The closest points
+/// are found with the triangle BVH of the mesh, which is built on first use.
+///
+/// # Returns
+/// One distance per point, empty for a mesh without faces.
+pub fn deviation(cloud: &PointCloud, mesh: &Mesh) -> Vec<Scalar> {
+    let distance = |point: &Vec3| {
+        mesh.closest_point(&Point::from(*point))
+            .map(|(_, distance)| distance)
+    };
+    #[cfg(feature = "parallel")]
+    let distances: Option<Vec<Scalar>> = cloud.points.par_iter().map(distance).collect();
+    #[cfg(not(feature = "parallel"))]
+    let distances: Option<Vec<Scalar>> = cloud.points.iter().map(distance).collect();
+    distances.unwrap_or_default()
+}
+
+/// Summary statistics of the distances returned by `deviation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviationStats {
+    pub min: Scalar,
+    pub max: Scalar,
+    pub mean: Scalar,
+    /// Mean of the absolute distances
+    pub mean_absolute: Scalar,
+    /// Root mean square of the distances
+    pub rms: Scalar,
+    pub std_dev: Scalar,
+}
+
+impl DeviationStats {
+    /// Statistics of a set of distances, None if there are none.
+    pub fn from_deviations(deviations: &[Scalar]) -> Option<Self> {
+        if deviations.is_empty() {
+            return None;
+        }
+        let count = deviations.len() as Scalar;
+        let mean = deviations.iter().sum::<Scalar>() / count;
+        let squares = deviations.iter().map(|d| d * d).sum::<Scalar>() / count;
+        Some(DeviationStats {
+            min: deviations
+                .iter()
+                .copied()
+                .fold(Scalar::INFINITY, Scalar::min),
+            max: deviations
+                .iter()
+                .copied()
+                .fold(Scalar::NEG_INFINITY, Scalar::max),
+            mean,
+            mean_absolute: deviations.iter().map(|d| d.abs()).sum::<Scalar>() / count,
+            rms: squares.sqrt(),
+            std_dev: (squares - mean * mean).max(0.0).sqrt(),
+        })
+    }
+}
+
+/// Read a point cloud from an XYZ text file.
+///
+/// Each line holds `x y z`, optionally followed by an intensity and/or
+/// `r g b` colors in 0-255, so lines have 3, 4, 6 or 7 columns separated by
+/// spaces, tabs or commas. Empty lines and lines starting with `#` or `//`
+/// are skipped. Intensities and colors are kept when every point has them.
+///
+/// # Arguments
+/// * `filepath` - The path of the XYZ file
+///
+/// # Returns
+/// The point cloud, or an error for unreadable files and malformed lines.
+pub fn read_xyz(filepath: &str) -> Result<PointCloud, SessionError> {
+    read_columns(&std::fs::read_to_string(filepath)?, false)
+}
+
+/// Write a point cloud as an XYZ text file with one `x y z` line per point.
+///
+/// Intensities and colors are appended when there is one per point, in the
+/// layout read by `read_xyz`. Points are written without applying the xform.
+///
+/// # Arguments
+/// * `cloud` - The point cloud to write
+/// * `filepath` - The path of the XYZ file
+///
+/// # Returns
+/// A Result indicating success or failure of the file write operation.
+pub fn write_xyz(cloud: &PointCloud, filepath: &str) -> Result<(), SessionError> {
+    write_columns(cloud, filepath, false)
+}
+
+/// Read a point cloud from a Leica PTS file, an XYZ file whose first line is the point count.
+///
+/// # Arguments
+/// * `filepath` - The path of the PTS file
+///
+/// # Returns
+/// The point cloud, or an error for unreadable files and malformed lines.
+pub fn read_pts(filepath: &str) -> Result<PointCloud, SessionError> {
+    read_columns(&std::fs::read_to_string(filepath)?, true)
+}
+
+/// Write a point cloud as a Leica PTS file.
+///
+/// Lines are `x y z intensity r g b`, with intensity 0 and white for clouds
+/// without intensities or colors.
+///
+/// # Arguments
+/// * `cloud` - The point cloud to write
+/// * `filepath` - The path of the PTS file
+///
+/// # Returns
+/// A Result indicating success or failure of the file write operation.
+pub fn write_pts(cloud: &PointCloud, filepath: &str) -> Result<(), SessionError> {
+    write_columns(cloud, filepath, true)
+}
+
+fn read_columns(text: &str, has_count: bool) -> Result<PointCloud, SessionError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+        .peekable();
+    // PTS files start with the point count
+    if has_count
+        && lines
+            .peek()
+            .is_some_and(|(_, line)| line.parse::<usize>().is_ok())
+    {
+        lines.next();
+    }
+
+    let mut cloud = PointCloud::default();
+    let mut intensities = Vec::new();
+    let mut colors = Vec::new();
+    for (number, line) in lines {
+        let invalid = || SessionError::InvalidGeometry(format!("invalid point on line {number}"));
+        let values = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse::<Scalar>().map_err(|_| invalid()))
+            .collect::<Result<Vec<Scalar>, SessionError>>()?;
+        let (intensity, rgb) = match values.len() {
+            3 => (None, None),
+            4 => (Some(values[3]), None),
+            6 => (None, Some(&values[3..6])),
+            7 => (Some(values[3]), Some(&values[4..7])),
+            _ => return Err(invalid()),
+        };
+        cloud
+            .points
+            .push(Vec3::new(values[0], values[1], values[2]));
+        intensities.extend(intensity);
+        colors.extend(rgb.map(|rgb| {
+            let channel = |value: Scalar| value.clamp(0.0, 255.0) as u8;
+            Color::new(channel(rgb[0]), channel(rgb[1]), channel(rgb[2]), 255)
+        }));
+    }
+    if intensities.len() == cloud.points.len() {
+        cloud.intensities = intensities;
     }
+    if colors.len() == cloud.points.len() {
+        cloud.colors = colors;
+    }
+    Ok(cloud)
+}
+
+fn write_columns(cloud: &PointCloud, filepath: &str, pts: bool) -> Result<(), SessionError> {
+    use std::io::Write;
+
+    let count = cloud.points.len();
+    let intensities = (cloud.intensities.len() == count).then_some(&cloud.intensities);
+    let colors = (cloud.colors.len() == count).then_some(&cloud.colors);
+    let mut out = std::io::BufWriter::new(std::fs::File::create(filepath)?);
+    if pts {
+        writeln!(out, "{count}")?;
+    }
+    for (i, point) in cloud.points.iter().enumerate() {
+        write!(out, "{} {} {}", point.x, point.y, point.z)?;
+        match intensities {
+            Some(intensities) => write!(out, " {}", intensities[i])?,
+            None if pts => write!(out, " 0")?,
+            None => {}
+        }
+        match colors {
+            Some(colors) => write!(out, " {} {} {}", colors[i].r, colors[i].g, colors[i].b)?,
+            None if pts => write!(out, " 255 255 255")?,
+            None => {}
+        }
+        writeln!(out)?;
+    }
+    out.flush()?;
+    Ok(())
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////////
@@ -100,17 +1131,21 @@ impl PointCloud {
 
 impl AddAssign<Vector> for PointCloud {
     fn add_assign(&mut self, other: Vector) {
+        let offset = other.as_vec3();
         for p in &mut self.points {
-            *p += other.clone();
+            *p += offset;
         }
+        self.kdtree.take();
     }
 }
 
 impl SubAssign<Vector> for PointCloud {
     fn sub_assign(&mut self, other: Vector) {
+        let offset = other.as_vec3();
         for p in &mut self.points {
-            *p -= other.clone();
+            *p -= offset;
         }
+        self.kdtree.take();
     }
 }
 
@@ -169,18 +1204,18 @@ impl Serialize for PointCloud {
         state.serialize_field("name", &self.name)?;
 
         // Flatten points to [x, y, z, x, y, z, ...]
-        let points_flat: Vec<f64> = self
+        let points_flat: Vec<Scalar> = self
             .points
             .iter()
-            .flat_map(|p| vec![p.x(), p.y(), p.z()])
+            .flat_map(|&p| <[Scalar; 3]>::from(p))
             .collect();
         state.serialize_field("points", &points_flat)?;
 
         // Flatten normals to [x, y, z, x, y, z, ...]
-        let normals_flat: Vec<f64> = self
+        let normals_flat: Vec<Scalar> = self
             .normals
             .iter()
-            .flat_map(|n| vec![n.x(), n.y(), n.z()])
+            .flat_map(|&n| <[Scalar; 3]>::from(n))
             .collect();
         state.serialize_field("normals", &normals_flat)?;
 
@@ -192,6 +1227,11 @@ impl Serialize for PointCloud {
             .collect();
         state.serialize_field("colors", &colors_flat)?;
 
+        // Intensities are only written for clouds that have them
+        if !self.intensities.is_empty() {
+            state.serialize_field("intensities", &self.intensities)?;
+        }
+
         state.serialize_field("xform", &self.xform)?;
 
         state.end()
@@ -214,6 +1254,7 @@ impl<'de> Deserialize<'de> for PointCloud {
             Points,
             Normals,
             Colors,
+            Intensities,
             Xform,
         }
 
@@ -232,9 +1273,10 @@ impl<'de> Deserialize<'de> for PointCloud {
             {
                 let mut guid = None;
                 let mut name = None;
-                let mut points_flat: Option<Vec<f64>> = None;
-                let mut normals_flat: Option<Vec<f64>> = None;
+                let mut points_flat: Option<Vec<Scalar>> = None;
+                let mut normals_flat: Option<Vec<Scalar>> = None;
                 let mut colors_flat: Option<Vec<u8>> = None;
+                let mut intensities: Vec<Scalar> = Vec::new();
                 let mut xform = None;
 
                 while let Some(key) = map.next_key()? {
@@ -257,6 +1299,9 @@ impl<'de> Deserialize<'de> for PointCloud {
                         Field::Colors => {
                             colors_flat = Some(map.next_value()?);
                         }
+                        Field::Intensities => {
+                            intensities = map.next_value()?;
+                        }
                         Field::Xform => {
                             xform = Some(map.next_value()?);
                         }
@@ -272,15 +1317,15 @@ impl<'de> Deserialize<'de> for PointCloud {
                 let xform = xform.ok_or_else(|| de::Error::missing_field("xform"))?;
 
                 // Reconstruct points from flat array
-                let points: Vec<Point> = points_flat
-                    .chunks(3)
-                    .map(|chunk| Point::new(chunk[0], chunk[1], chunk[2]))
+                let points: Vec<Vec3> = points_flat
+                    .chunks_exact(3)
+                    .map(|chunk| Vec3::new(chunk[0], chunk[1], chunk[2]))
                     .collect();
 
                 // Reconstruct normals from flat array
-                let normals: Vec<Vector> = normals_flat
-                    .chunks(3)
-                    .map(|chunk| Vector::new(chunk[0], chunk[1], chunk[2]))
+                let normals: Vec<Vec3> = normals_flat
+                    .chunks_exact(3)
+                    .map(|chunk| Vec3::new(chunk[0], chunk[1], chunk[2]))
                     .collect();
 
                 // Reconstruct colors from flat array (RGB only, alpha always 255)
@@ -295,13 +1340,22 @@ impl<'de> Deserialize<'de> for PointCloud {
                     points,
                     normals,
                     colors,
+                    intensities,
                     xform,
+                    kdtree: OnceCell::new(),
                 })
             }
         }
 
         const FIELDS: &[&str] = &[
-            "type", "guid", "name", "points", "normals", "colors", "xform",
+            "type",
+            "guid",
+            "name",
+            "points",
+            "normals",
+            "colors",
+            "intensities",
+            "xform",
         ];
         deserializer.deserialize_struct("PointCloud", FIELDS, PointCloudVisitor)
     }
@@ -1,5 +1,6 @@
 use super::*;
 use crate::encoders::{json_dump, json_load};
+use crate::scalar::test_tolerance;
 
 #[test]
 fn test_pointcloud_new() {
@@ -40,9 +41,9 @@ fn test_pointcloud_add_assign_vector() {
     );
     let v = Vector::new(4.0, 5.0, 6.0);
     cloud += v;
-    assert_eq!(cloud.points[0].x(), 5.0);
-    assert_eq!(cloud.points[0].y(), 7.0);
-    assert_eq!(cloud.points[0].z(), 9.0);
+    assert_eq!(cloud.points[0].x, 5.0);
+    assert_eq!(cloud.points[0].y, 7.0);
+    assert_eq!(cloud.points[0].z, 9.0);
 }
 
 #[test]
@@ -54,9 +55,9 @@ fn test_pointcloud_add_vector() {
     );
     let v = Vector::new(4.0, 5.0, 6.0);
     let cloud2 = cloud + v;
-    assert_eq!(cloud2.points[0].x(), 5.0);
-    assert_eq!(cloud2.points[0].y(), 7.0);
-    assert_eq!(cloud2.points[0].z(), 9.0);
+    assert_eq!(cloud2.points[0].x, 5.0);
+    assert_eq!(cloud2.points[0].y, 7.0);
+    assert_eq!(cloud2.points[0].z, 9.0);
 }
 
 #[test]
@@ -68,9 +69,9 @@ fn test_pointcloud_sub_assign_vector() {
     );
     let v = Vector::new(4.0, 5.0, 6.0);
     cloud -= v;
-    assert_eq!(cloud.points[0].x(), -3.0);
-    assert_eq!(cloud.points[0].y(), -3.0);
-    assert_eq!(cloud.points[0].z(), -3.0);
+    assert_eq!(cloud.points[0].x, -3.0);
+    assert_eq!(cloud.points[0].y, -3.0);
+    assert_eq!(cloud.points[0].z, -3.0);
 }
 
 #[test]
@@ -82,9 +83,9 @@ fn test_pointcloud_sub_vector() {
     );
     let v = Vector::new(4.0, 5.0, 6.0);
     let cloud2 = cloud - v;
-    assert_eq!(cloud2.points[0].x(), -3.0);
-    assert_eq!(cloud2.points[0].y(), -3.0);
-    assert_eq!(cloud2.points[0].z(), -3.0);
+    assert_eq!(cloud2.points[0].x, -3.0);
+    assert_eq!(cloud2.points[0].y, -3.0);
+    assert_eq!(cloud2.points[0].z, -3.0);
 }
 
 #[test]
@@ -108,9 +109,9 @@ fn test_pointcloud_json_serialization() {
     );
     let json = cloud.jsondump().unwrap();
     let cloud2 = PointCloud::jsonload(&json).unwrap();
-    assert_eq!(cloud2.points[0].x(), 1.0);
-    assert_eq!(cloud2.points[0].y(), 2.0);
-    assert_eq!(cloud2.points[0].z(), 3.0);
+    assert_eq!(cloud2.points[0].x, 1.0);
+    assert_eq!(cloud2.points[0].y, 2.0);
+    assert_eq!(cloud2.points[0].z, 3.0);
 }
 
 #[test]
@@ -134,9 +135,9 @@ fn test_pointcloud_json_file() {
     );
     json_dump(&cloud, "test_pointcloud.json", true).unwrap();
     let cloud2 = json_load::<PointCloud>("test_pointcloud.json").unwrap();
-    assert_eq!(cloud2.points[0].x(), 1.0);
-    assert_eq!(cloud2.points[1].y(), 5.0);
-    assert_eq!(cloud2.points[2].z(), 9.0);
+    assert_eq!(cloud2.points[0].x, 1.0);
+    assert_eq!(cloud2.points[1].y, 5.0);
+    assert_eq!(cloud2.points[2].z, 9.0);
     assert_eq!(cloud2.len(), 3);
 }
 
@@ -163,13 +164,501 @@ fn test_pointcloud_json_multiple_points() {
     let cloud2 = PointCloud::jsonload(&json).unwrap();
 
     assert_eq!(cloud2.len(), 3);
-    assert_eq!(cloud2.points[0].x(), 1.0);
-    assert_eq!(cloud2.points[1].y(), 5.0);
-    assert_eq!(cloud2.points[2].z(), 9.0);
-    assert_eq!(cloud2.normals[0].z(), 1.0);
+    assert_eq!(cloud2.points[0].x, 1.0);
+    assert_eq!(cloud2.points[1].y, 5.0);
+    assert_eq!(cloud2.points[2].z, 9.0);
+    assert_eq!(cloud2.normals[0].z, 1.0);
     assert_eq!(cloud2.colors[1].g, 255);
     // Verify alpha is always 255 after deserialization
     assert_eq!(cloud2.colors[0].a, 255);
     assert_eq!(cloud2.colors[1].a, 255);
     assert_eq!(cloud2.colors[2].a, 255);
 }
+
+#[test]
+fn test_pointcloud_closest_point() {
+    let points = vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(0.0, 2.0, 0.0),
+        Point::new(0.0, 0.0, 3.0),
+    ];
+    let mut cloud = PointCloud::new(points, vec![], vec![]);
+
+    let (index, distance) = cloud.closest_point(&Point::new(0.9, 0.1, 0.0)).unwrap();
+    assert_eq!(index, 1);
+    assert!((distance - Scalar::sqrt(0.02)).abs() < test_tolerance(1e-12));
+
+    let nearest: Vec<usize> = cloud
+        .closest_points(&Point::new(0.0, 0.0, 0.0), 3)
+        .iter()
+        .map(|(i, _)| *i)
+        .collect();
+    assert_eq!(nearest, vec![0, 1, 2]);
+
+    let within: Vec<usize> = cloud
+        .points_within(&Point::new(0.0, 0.0, 0.0), 2.0)
+        .iter()
+        .map(|(i, _)| *i)
+        .collect();
+    assert_eq!(within, vec![0, 1, 2]);
+
+    // Moving the cloud drops the cached tree
+    cloud += Vector::new(10.0, 0.0, 0.0);
+    assert!(cloud.kdtree.get().is_none());
+    let (index, _) = cloud.closest_point(&Point::new(10.0, 0.0, 2.9)).unwrap();
+    assert_eq!(index, 3);
+}
+
+#[test]
+fn test_pointcloud_xyz_and_pts_files() {
+    let dir = std::env::temp_dir();
+    let path = |ext: &str| {
+        dir.join(format!("cloud_{}.{ext}", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .into_owned()
+    };
+
+    // Columns may be separated by commas and carry intensity and colors
+    let xyz = path("xyz");
+    std::fs::write(&xyz, "# scan\n1,2,3,0.5,255,0,10\n\n4 5 6 0.25 0 128 255\n").unwrap();
+    let cloud = read_xyz(&xyz).unwrap();
+    assert_eq!(cloud.len(), 2);
+    assert_eq!(cloud.points[1].z, 6.0);
+    assert_eq!(cloud.intensities, vec![0.5, 0.25]);
+    assert_eq!([cloud.colors[1].g, cloud.colors[1].b], [128, 255]);
+
+    write_xyz(&cloud, &xyz).unwrap();
+    let loaded = read_xyz(&xyz).unwrap();
+    assert_eq!(loaded.points, cloud.points);
+    assert_eq!(loaded.intensities, cloud.intensities);
+    assert_eq!(loaded.colors, cloud.colors);
+
+    // Plain XYZ clouds get default intensity and color columns in PTS
+    let plain = PointCloud::new(vec![Point::new(1.0, 2.0, 3.0)], vec![], vec![]);
+    let pts = path("pts");
+    write_pts(&plain, &pts).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&pts).unwrap(),
+        "1\n1 2 3 0 255 255 255\n"
+    );
+    let loaded = read_pts(&pts).unwrap();
+    assert_eq!(loaded.points, plain.points);
+    assert_eq!(loaded.intensities, vec![0.0]);
+
+    std::fs::write(&xyz, "1 2\n").unwrap();
+    assert!(matches!(
+        read_xyz(&xyz),
+        Err(SessionError::InvalidGeometry(_))
+    ));
+
+    // Intensities survive JSON
+    let json = cloud.jsondump().unwrap();
+    assert_eq!(
+        PointCloud::jsonload(&json).unwrap().intensities,
+        cloud.intensities
+    );
+    assert!(!plain.jsondump().unwrap().contains("intensities"));
+
+    std::fs::remove_file(&xyz).ok();
+    std::fs::remove_file(&pts).ok();
+}
+
+#[test]
+fn test_pointcloud_positions_f32() {
+    let cloud = PointCloud::new(
+        vec![Point::new(1.0, 2.0, 3.0), Point::new(-4.5, 0.0, 6.25)],
+        vec![],
+        vec![],
+    );
+    assert_eq!(cloud.positions_f32(), vec![1.0, 2.0, 3.0, -4.5, 0.0, 6.25]);
+    assert!(PointCloud::default().positions_f32().is_empty());
+}
+
+#[test]
+fn test_pointcloud_from_coords() {
+    let cloud = PointCloud::from_coords(
+        vec![Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 5.0, 6.0)],
+        vec![Vec3::new(0.0, 0.0, 1.0); 2],
+        vec![],
+    );
+    let point = cloud.point(1).unwrap();
+    assert_eq!((point.x(), point.y(), point.z()), (4.0, 5.0, 6.0));
+    assert_eq!(cloud.normal(0).unwrap().z(), 1.0);
+    assert!(cloud.point(2).is_none());
+
+    // Coordinates are written as the same flat arrays as before
+    let json: serde_json::Value = serde_json::from_str(&cloud.jsondump().unwrap()).unwrap();
+    assert_eq!(
+        json["points"],
+        serde_json::json!([1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+    );
+    assert_eq!(
+        json["normals"],
+        serde_json::json!([0.0, 0.0, 1.0, 0.0, 0.0, 1.0])
+    );
+    let loaded = PointCloud::jsonload(&cloud.jsondump().unwrap()).unwrap();
+    assert_eq!(loaded.points, cloud.points);
+}
+
+#[test]
+fn test_pointcloud_estimate_normals() {
+    // Fibonacci sphere, the normals face outwards from the top point
+    let count = 400;
+    let golden = crate::scalar::consts::PI * (3.0 - (5.0 as Scalar).sqrt());
+    let sphere: Vec<Vec3> = (0..count)
+        .map(|i| {
+            let z = 1.0 - 2.0 * (i as Scalar + 0.5) / count as Scalar;
+            let r = (1.0 - z * z).sqrt();
+            let angle = golden * i as Scalar;
+            Vec3::new(r * angle.cos(), r * angle.sin(), z)
+        })
+        .collect();
+    let mut cloud = PointCloud::from_coords(sphere, vec![], vec![]);
+    cloud.estimate_normals(8);
+    assert_eq!(cloud.normals.len(), count);
+    for (point, normal) in cloud.points.iter().zip(&cloud.normals) {
+        assert!((normal.length() - 1.0).abs() < 1e-4);
+        assert!(normal.dot(point) > 0.95);
+    }
+    let loaded = PointCloud::jsonload(&cloud.jsondump().unwrap()).unwrap();
+    assert_eq!(loaded.normals.len(), count);
+
+    // Two separate flat patches are both oriented upwards
+    let mut points = Vec::new();
+    for (offset, z) in [(0.0, 0.0), (10.0, -3.0)] {
+        for i in 0..10 {
+            for j in 0..10 {
+                points.push(Vec3::new(offset + i as Scalar * 0.1, j as Scalar * 0.1, z));
+            }
+        }
+    }
+    let mut patches = PointCloud::from_coords(points, vec![], vec![]);
+    patches.estimate_normals(6);
+    assert!(patches.normals.iter().all(|n| n.z > 0.999));
+
+    let mut empty = PointCloud::default();
+    empty.estimate_normals(8);
+    assert!(empty.normals.is_empty());
+}
+
+#[test]
+fn test_pointcloud_downsample() {
+    let mut cloud = PointCloud::from_coords(
+        vec![
+            Vec3::new(0.1, 0.1, 0.1),
+            Vec3::new(0.3, 0.3, 0.3),
+            Vec3::new(1.5, 0.5, 0.5),
+            Vec3::new(-0.5, 0.5, 0.5),
+        ],
+        vec![
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+        ],
+        vec![
+            Color::new(0, 0, 0, 255),
+            Color::new(255, 100, 0, 255),
+            Color::red(),
+            Color::blue(),
+        ],
+    );
+    cloud.intensities = vec![1.0, 3.0, 5.0, 7.0];
+
+    let voxels = cloud.voxel_downsample(1.0);
+    assert_eq!(voxels.len(), 3);
+    assert!(voxels.points[0].distance(&Vec3::new(0.2, 0.2, 0.2)) < 1e-6);
+    let half = (0.5 as Scalar).sqrt();
+    assert!(voxels.normals[0].distance(&Vec3::new(0.0, half, half)) < 1e-6);
+    assert_eq!((voxels.colors[0].r, voxels.colors[0].g), (128, 50));
+    assert_eq!(voxels.intensities, vec![2.0, 5.0, 7.0]);
+    assert_eq!(voxels.points[2].x, -0.5);
+
+    // Attributes that are not per point are dropped
+    cloud.intensities.clear();
+    assert!(cloud.voxel_downsample(10.0).intensities.is_empty());
+    // The negative x coordinate is in the cell below zero
+    assert_eq!(cloud.voxel_downsample(10.0).len(), 2);
+
+    let large = PointCloud::from_coords(
+        (0..1000)
+            .map(|i| Vec3::new(i as Scalar, 0.0, 0.0))
+            .collect(),
+        vec![],
+        vec![],
+    );
+    let sample = large.random_downsample(0.25, 7);
+    assert_eq!(sample.len(), 250);
+    assert!(sample.points.windows(2).all(|w| w[0].x < w[1].x));
+    assert_eq!(sample.points, large.random_downsample(0.25, 7).points);
+    assert_ne!(sample.points, large.random_downsample(0.25, 8).points);
+    assert_eq!(large.random_downsample(2.0, 0).len(), 1000);
+    assert!(large.random_downsample(0.0, 0).is_empty());
+    assert_eq!(cloud.random_downsample(0.5, 1).colors.len(), 2);
+}
+
+#[test]
+fn test_pointcloud_outlier_removal() {
+    let mut coords: Vec<Vec3> = (0..100)
+        .map(|i| Vec3::new((i % 10) as Scalar * 0.1, (i / 10) as Scalar * 0.1, 0.0))
+        .collect();
+    coords.insert(42, Vec3::new(5.0, 5.0, 5.0));
+    coords.push(Vec3::new(-3.0, 0.0, 0.0));
+    let colors = vec![Color::white(); coords.len()];
+    let mut cloud = PointCloud::from_coords(coords, vec![], colors);
+
+    let (filtered, removed) = cloud.remove_statistical_outliers(8, 2.0);
+    assert_eq!(removed, vec![42, 101]);
+    assert_eq!(filtered.len(), 100);
+    assert_eq!(filtered.colors.len(), 100);
+    assert_eq!(cloud.len(), 102);
+
+    let (filtered, removed) = cloud.remove_radius_outliers(0.15, 2);
+    assert_eq!(removed, vec![42, 101]);
+    assert_eq!(filtered.len(), 100);
+    // Corner points have only two neighbours within the radius
+    let (_, removed) = cloud.remove_radius_outliers(0.12, 3);
+    assert_eq!(removed.len(), 6);
+
+    let (empty, removed) = PointCloud::default().remove_statistical_outliers(8, 1.0);
+    assert!(empty.is_empty() && removed.is_empty());
+}
+
+#[test]
+fn test_pointcloud_cluster_euclidean() {
+    let mut coords = Vec::new();
+    for offset in [0.0, 5.0] {
+        for i in 0..20 {
+            coords.push(Vec3::new(offset + i as Scalar * 0.1, 0.0, 0.0));
+        }
+    }
+    // An isolated point and a border point next to the end of the second line
+    coords.push(Vec3::new(0.0, 3.0, 0.0));
+    coords.push(Vec3::new(6.9 + 0.1, 0.0, 0.0));
+    let mut cloud = PointCloud::from_coords(coords, vec![], vec![]);
+
+    let clusters = cloud.cluster_euclidean(0.15, 3);
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[0], (0..20).collect::<Vec<_>>());
+    assert_eq!(clusters[1].len(), 21);
+    assert!(clusters[1].contains(&41));
+    assert!(!clusters.iter().flatten().any(|&i| i == 40));
+
+    // Without enough neighbours every point is noise
+    assert!(cloud.cluster_euclidean(0.05, 2).is_empty());
+    assert!(PointCloud::default().cluster_euclidean(1.0, 1).is_empty());
+}
+
+#[test]
+fn test_pointcloud_segment_plane() {
+    // A floor, a wall and some scattered points
+    let mut coords = Vec::new();
+    for i in 0..20 {
+        for j in 0..20 {
+            let (u, v) = (i as Scalar * 0.1, j as Scalar * 0.1);
+            coords.push(Vec3::new(u, v, 0.001 * ((i + j) % 3) as Scalar));
+        }
+    }
+    for i in 0..15 {
+        for j in 0..10 {
+            coords.push(Vec3::new(i as Scalar * 0.1, 3.0, 0.5 + j as Scalar * 0.1));
+        }
+    }
+    for i in 0..10 {
+        let t = i as Scalar;
+        coords.push(Vec3::new(t.sin() * 4.0, 6.0 + (t * 1.7).cos(), 2.0 + t));
+    }
+    let cloud = PointCloud::from_coords(coords, vec![], vec![]);
+
+    let (floor, inliers) = cloud.segment_plane(200, 0.01).unwrap();
+    assert_eq!(inliers, (0..400).collect::<Vec<_>>());
+    assert!(floor.z_axis().z().abs() > 0.999);
+    let (again, _) = cloud.segment_plane(200, 0.01).unwrap();
+    assert_eq!(again.origin().x(), floor.origin().x());
+
+    let planes = cloud.segment_planes(200, 0.01, 50);
+    assert_eq!(planes.len(), 2);
+    assert_eq!(planes[1].1.len(), 150);
+    assert!(planes[1].0.z_axis().y().abs() > 0.999);
+
+    let line = PointCloud::from_coords(
+        (0..10).map(|i| Vec3::new(i as Scalar, 0.0, 0.0)).collect(),
+        vec![],
+        vec![],
+    );
+    assert!(line.segment_plane(50, 0.01).is_none());
+}
+
+#[test]
+fn test_pointcloud_cropping() {
+    let mut coords = Vec::new();
+    for i in 0..4 {
+        for j in 0..4 {
+            for k in 0..4 {
+                coords.push(Vec3::new(i as Scalar, j as Scalar, k as Scalar));
+            }
+        }
+    }
+    let colors = vec![Color::new(255, 0, 0, 255); coords.len()];
+    let cloud = PointCloud::from_coords(coords.clone(), vec![], colors);
+
+    let bbox =
+        BoundingBox::from_points(&[Point::new(0.5, 0.5, 0.5), Point::new(2.0, 2.0, 2.0)], 0.0);
+    let (cropped, mask) = cloud.crop_box(&bbox);
+    assert_eq!(mask.len(), 64);
+    assert_eq!(cropped.len(), 8);
+    assert_eq!(cropped.colors.len(), 8);
+    assert_eq!(mask.iter().filter(|&&kept| kept).count(), 8);
+    assert!(cropped.points.iter().all(|p| p.x >= 1.0 && p.x <= 2.0));
+
+    let plane = Plane::from_point_normal(Point::new(0.0, 0.0, 1.0), Vector::new(0.0, 0.0, 1.0));
+    let (above, mask) = cloud.crop_plane(&plane, true);
+    let (below, _) = cloud.crop_plane(&plane, false);
+    assert_eq!(above.len(), 48);
+    assert_eq!(below.len(), 32);
+    assert!(mask[1] && !mask[0]);
+
+    let p = |x: Scalar, y: Scalar, z: Scalar| Point::new(x, y, z);
+    let tetrahedron = Mesh::from_polygons(
+        vec![
+            vec![p(0.0, 0.0, 0.0), p(0.0, 2.0, 0.0), p(2.0, 0.0, 0.0)],
+            vec![p(0.0, 0.0, 0.0), p(2.0, 0.0, 0.0), p(0.0, 0.0, 2.0)],
+            vec![p(0.0, 0.0, 0.0), p(0.0, 0.0, 2.0), p(0.0, 2.0, 0.0)],
+            vec![p(2.0, 0.0, 0.0), p(0.0, 2.0, 0.0), p(0.0, 0.0, 2.0)],
+        ],
+        None,
+    );
+    let (inside, mask) = cloud.select_by_mesh(&tetrahedron, true);
+    let (outside, _) = cloud.select_by_mesh(&tetrahedron, false);
+    let expected: Vec<bool> = coords.iter().map(|c| c.x + c.y + c.z <= 2.0).collect();
+    assert_eq!(mask, expected);
+    assert_eq!(inside.len(), 10);
+    assert_eq!(outside.len(), 54);
+}
+
+#[test]
+fn test_pointcloud_ray_cast() {
+    let cloud = PointCloud::from_coords(
+        vec![
+            Vec3::new(5.0, 0.05, 0.0),
+            Vec3::new(3.0, 0.5, 0.0),
+            Vec3::new(8.0, 0.0, 0.02),
+        ],
+        vec![],
+        vec![],
+    );
+    let origin = Point::new(0.0, 0.0, 0.0);
+    let (index, distance) = cloud
+        .ray_cast(&origin, &Vector::new(2.0, 0.0, 0.0), 0.1)
+        .unwrap();
+    assert_eq!(index, 0);
+    assert!((distance - 5.0).abs() < 1e-6);
+    assert_eq!(
+        cloud
+            .ray_cast(&origin, &Vector::new(2.0, 0.0, 0.0), 1.0)
+            .unwrap()
+            .0,
+        1
+    );
+    assert!(cloud
+        .ray_cast(&origin, &Vector::new(-1.0, 0.0, 0.0), 0.1)
+        .is_none());
+    assert!(cloud
+        .ray_cast(&origin, &Vector::new(0.0, 0.0, 0.0), 0.1)
+        .is_none());
+}
+
+#[test]
+fn test_pointcloud_chunks() {
+    // Two interleaved clusters, far apart
+    let coords: Vec<Vec3> = (0..100)
+        .map(|i| {
+            let offset = if i % 2 == 0 { 0.0 } else { 100.0 };
+            Vec3::new(offset + (i / 2) as Scalar * 0.01, 0.0, 0.0)
+        })
+        .collect();
+    let intensities: Vec<Scalar> = (0..100).map(|i| i as Scalar).collect();
+    let mut cloud = PointCloud::from_coords(coords, vec![], vec![]);
+    cloud.intensities = intensities;
+
+    let bounds = cloud.chunk_bounds(30);
+    assert_eq!(bounds.len(), 4);
+    assert!(bounds[0].half_size.x() > 40.0);
+
+    let sorted = cloud.spatially_sorted();
+    assert_eq!(sorted.len(), 100);
+    assert!(sorted.intensities[..50]
+        .iter()
+        .all(|i| (*i as usize).is_multiple_of(2)));
+    let bounds = sorted.chunk_bounds(50);
+    assert_eq!(bounds.len(), 2);
+    assert!(bounds.iter().all(|bbox| bbox.half_size.x() < 1.0));
+
+    // Only the points of the range are hit, by index in the cloud
+    let origin = Point::new(100.2, 0.0, 5.0);
+    let down = Vector::new(0.0, 0.0, -1.0);
+    let (index, t) = sorted
+        .ray_cast_range(50..100, &origin, &down, 0.006)
+        .unwrap();
+    assert!(index >= 50);
+    assert!((sorted.points[index].x - 100.2).abs() <= 0.006);
+    assert!((t - 5.0).abs() < test_tolerance(1e-12));
+    assert!(sorted
+        .ray_cast_range(0..50, &origin, &down, 0.006)
+        .is_none());
+    assert!(sorted
+        .ray_cast_range(90..200, &origin, &down, 0.006)
+        .is_none());
+
+    assert!(PointCloud::default().chunk_bounds(10).is_empty());
+    assert!(PointCloud::default().spatially_sorted().is_empty());
+}
+
+#[test]
+fn test_pointcloud_deviation() {
+    let p = |x: Scalar, y: Scalar| Point::new(x, y, 0.0);
+    let mesh = Mesh::from_polygons(
+        vec![vec![p(0.0, 0.0), p(4.0, 0.0), p(4.0, 4.0), p(0.0, 4.0)]],
+        None,
+    );
+    let mut cloud = PointCloud::from_coords(
+        vec![
+            Vec3::new(1.0, 1.0, 0.1),
+            Vec3::new(2.0, 3.0, -0.3),
+            Vec3::new(3.0, 2.0, 0.0),
+            Vec3::new(3.0, 3.0, 0.2),
+        ],
+        vec![],
+        vec![],
+    );
+
+    let deviations = deviation(&cloud, &mesh);
+    let expected = [0.1, -0.3, 0.0, 0.2];
+    assert_eq!(deviations.len(), 4);
+    for (found, wanted) in deviations.iter().zip(expected) {
+        assert!((found - wanted).abs() < 1e-6);
+    }
+
+    let stats = DeviationStats::from_deviations(&deviations).unwrap();
+    assert!((stats.min + 0.3).abs() < 1e-6);
+    assert!((stats.max - 0.2).abs() < 1e-6);
+    assert!(stats.mean.abs() < 1e-6);
+    assert!((stats.mean_absolute - 0.15).abs() < 1e-6);
+    assert!((stats.rms - (0.14 as Scalar / 4.0).sqrt()).abs() < 1e-6);
+    assert!((stats.std_dev - stats.rms).abs() < 1e-6);
+    assert!(DeviationStats::from_deviations(&[]).is_none());
+
+    cloud.color_by_deviation(&deviations, 0.3, Colormap::Grayscale);
+    assert_eq!(cloud.colors.len(), 4);
+    assert_eq!(
+        cloud.colors[1],
+        Color::map(0.0, 0.0, 1.0, Colormap::Grayscale)
+    );
+    assert_eq!(
+        cloud.colors[2],
+        Color::map(0.5, 0.0, 1.0, Colormap::Grayscale)
+    );
+
+    assert!(deviation(&cloud, &Mesh::new()).is_empty());
+}
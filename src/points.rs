@@ -0,0 +1,178 @@
+//! Utilities for sets of points.
+//!
+//! Centroid, tolerance based deduplication and best-fit frames, shared by
+//! Polyline, PointCloud and Plane. The functions take `Point` or `Vec3` slices.
+
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{AsVec3, Plane, Point, Scalar, Tolerance, Vec3, Vector};
+use alloc::collections::BTreeMap;
+
+/// Average of the points.
+///
+/// # Returns
+/// None if there are no points.
+pub fn centroid<P: AsVec3>(points: &[P]) -> Option<Point> {
+    mean(points).map(Point::from)
+}
+
+fn mean<P: AsVec3>(points: &[P]) -> Option<Vec3> {
+    if points.is_empty() {
+        return None;
+    }
+    let sum = points
+        .iter()
+        .fold(Vec3::ZERO, |sum, point| sum + point.as_vec3());
+    Some(sum * (1.0 / points.len() as Scalar))
+}
+
+/// Indices of the points that are kept by `deduplicate`, in input order.
+pub fn deduplicate_indices<P: AsVec3>(points: &[P], tolerance: Scalar) -> Vec<usize> {
+    // Points closer than the tolerance are in the same or a neighbouring grid cell
+    let cell_size = tolerance.max(Scalar::MIN_POSITIVE);
+    let cell = |value: Scalar| (value / cell_size).floor() as i64;
+    let mut grid: BTreeMap<(i64, i64, i64), Vec<usize>> = BTreeMap::new();
+    let mut kept = Vec::new();
+
+    for (index, point) in points.iter().enumerate() {
+        let point = point.as_vec3();
+        let key = (cell(point.x), cell(point.y), cell(point.z));
+        let mut duplicate = false;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let neighbour = (
+                        key.0.saturating_add(dx),
+                        key.1.saturating_add(dy),
+                        key.2.saturating_add(dz),
+                    );
+                    if let Some(candidates) = grid.get(&neighbour) {
+                        if candidates
+                            .iter()
+                            .any(|&other| points[other].as_vec3().distance(&point) <= tolerance)
+                        {
+                            duplicate = true;
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+        if !duplicate {
+            grid.entry(key).or_default().push(index);
+            kept.push(index);
+        }
+    }
+    kept
+}
+
+/// Remove points within a tolerance of an earlier point.
+///
+/// Uses a hash grid with the tolerance as cell size, so the cost is linear
+/// for points that are not clustered much tighter than the tolerance.
+///
+/// # Arguments
+/// * `points` - The points, the first of each cluster of duplicates is kept
+/// * `tolerance` - Largest distance between duplicates
+pub fn deduplicate<P: AsVec3 + Clone>(points: &[P], tolerance: Scalar) -> Vec<P> {
+    deduplicate_indices(points, tolerance)
+        .into_iter()
+        .map(|index| points[index].clone())
+        .collect()
+}
+
+/// Plane through the centroid of an ordered loop of points.
+///
+/// The normal is computed with Newell's method, so it follows the winding of
+/// the loop: counter-clockwise loops face the normal.
+///
+/// # Returns
+/// None for fewer than 3 points or collinear points.
+pub fn average_plane<P: AsVec3>(points: &[P]) -> Option<Plane> {
+    let origin = centroid(points)?;
+    let mut normal = Vec3::ZERO;
+    for (i, a) in points.iter().enumerate() {
+        let (a, b) = (a.as_vec3(), points[(i + 1) % points.len()].as_vec3());
+        normal += Vec3::new(
+            (a.y - b.y) * (a.z + b.z),
+            (a.z - b.z) * (a.x + b.x),
+            (a.x - b.x) * (a.y + b.y),
+        );
+    }
+    if normal.length_squared() < Tolerance::ZERO_TOLERANCE * Tolerance::ZERO_TOLERANCE {
+        return None;
+    }
+    Some(Plane::from_point_normal(origin, normal.into()))
+}
+
+/// Best-fit orthonormal frame of unordered points by principal component analysis.
+///
+/// The origin is the centroid, the x axis the direction of largest spread and
+/// the z axis the direction of smallest spread, the normal of the best-fit
+/// plane. The signs of the axes are arbitrary.
+///
+/// # Returns
+/// None if there are no points.
+pub fn pca_frame<P: AsVec3>(points: &[P]) -> Option<Plane> {
+    let origin = mean(points)?;
+    let mut covariance = [[0.0; 3]; 3];
+    for point in points {
+        let d: [Scalar; 3] = (point.as_vec3() - origin).into();
+        for (row, di) in covariance.iter_mut().zip(d) {
+            for (value, dj) in row.iter_mut().zip(d) {
+                *value += di * dj;
+            }
+        }
+    }
+
+    let (values, vectors) = symmetric_eigen(covariance);
+    let mut order = [0, 1, 2];
+    order.sort_by(|&a, &b| {
+        values[b]
+            .partial_cmp(&values[a])
+            .unwrap_or(core::cmp::Ordering::Equal)
+    });
+    let axis = |i: usize| Vector::new(vectors[0][i], vectors[1][i], vectors[2][i]);
+    let x_axis = axis(order[0]);
+    let y_axis = axis(order[2]).cross(&x_axis);
+    Some(Plane::new(origin.into(), x_axis, y_axis))
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric 3x3 matrix by cyclic Jacobi rotations.
+fn symmetric_eigen(mut a: [[Scalar; 3]; 3]) -> ([Scalar; 3], [[Scalar; 3]; 3]) {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let scale = a[0][0].abs() + a[1][1].abs() + a[2][2].abs();
+    for _ in 0..32 {
+        let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+        if off <= Scalar::EPSILON * Scalar::EPSILON * scale * scale {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q] == 0.0 {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            // A' = J^T A J and V' = V J for the rotation J in the (p, q) plane
+            let rotate_columns = |m: &mut [[Scalar; 3]; 3]| {
+                for row in m.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+            };
+            rotate_columns(&mut a);
+            rotate_columns(&mut v);
+            let (rp, rq) = (a[p], a[q]);
+            a[p] = [0, 1, 2].map(|k| c * rp[k] - s * rq[k]);
+            a[q] = [0, 1, 2].map(|k| s * rp[k] + c * rq[k]);
+        }
+    }
+    ([a[0][0], a[1][1], a[2][2]], v)
+}
+
+#[cfg(test)]
+#[path = "points_test.rs"]
+mod points_test;
@@ -0,0 +1,104 @@
+use crate::points::{average_plane, centroid, deduplicate, deduplicate_indices, pca_frame};
+use crate::scalar::test_tolerance;
+use crate::{Plane, Point, PointCloud, Scalar};
+
+fn close(a: Scalar, b: Scalar) -> bool {
+    (a - b).abs() < test_tolerance(1e-9)
+}
+
+#[test]
+fn test_points_centroid() {
+    let points = [
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(2.0, 0.0, 0.0),
+        Point::new(2.0, 4.0, 6.0),
+    ];
+    let center = centroid(&points).unwrap();
+    assert_eq!(
+        (center.x(), center.y(), center.z()),
+        (4.0 / 3.0, 4.0 / 3.0, 2.0)
+    );
+    assert!(centroid::<Point>(&[]).is_none());
+}
+
+#[test]
+fn test_points_deduplicate() {
+    let points = [
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(0.05, 0.0, 0.0),
+        // Across a grid cell border from the second point
+        Point::new(0.99, 0.02, -0.01),
+        Point::new(1.0, 1.0, 1.0),
+        Point::new(0.0, 0.0, 0.0),
+    ];
+    assert_eq!(deduplicate_indices(&points, 0.1), vec![0, 1, 4]);
+    assert_eq!(deduplicate(&points, 0.1)[2].z(), 1.0);
+    assert_eq!(deduplicate(&points, 0.0).len(), 5);
+    assert!(deduplicate::<Point>(&[], 0.1).is_empty());
+
+    let mut cloud = PointCloud::new(points.to_vec(), Vec::new(), Vec::new());
+    cloud.intensities = vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5];
+    let deduplicated = cloud.deduplicated(0.1);
+    assert_eq!(deduplicated.len(), 3);
+    assert_eq!(deduplicated.intensities, vec![0.0, 0.1, 0.4]);
+    assert!(deduplicated.normals.is_empty());
+}
+
+#[test]
+fn test_points_average_plane() {
+    // Counter-clockwise square at z = 2
+    let square = [
+        Point::new(0.0, 0.0, 2.0),
+        Point::new(2.0, 0.0, 2.0),
+        Point::new(2.0, 2.0, 2.0),
+        Point::new(0.0, 2.0, 2.0),
+    ];
+    let plane = average_plane(&square).unwrap();
+    assert!(close(plane.z_axis().z(), 1.0));
+    assert_eq!(
+        (plane.origin().x(), plane.origin().y(), plane.origin().z()),
+        (1.0, 1.0, 2.0)
+    );
+    let mut reversed = square.to_vec();
+    reversed.reverse();
+    assert!(close(average_plane(&reversed).unwrap().z_axis().z(), -1.0));
+
+    let collinear = [
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 1.0, 1.0),
+        Point::new(2.0, 2.0, 2.0),
+    ];
+    assert!(average_plane(&collinear).is_none());
+}
+
+#[test]
+fn test_points_pca_frame() {
+    // Points spread along a tilted line in the plane z = x
+    let points: Vec<Point> = (0..20)
+        .flat_map(|i| {
+            let t = i as Scalar;
+            [Point::new(t, 0.5, t), Point::new(t, -0.5, t)]
+        })
+        .collect();
+    let frame = pca_frame(&points).unwrap();
+    let x_axis = frame.x_axis();
+    let z_axis = frame.z_axis();
+    let h = std::f64::consts::FRAC_1_SQRT_2 as Scalar;
+    assert!(close(x_axis.x().abs(), h) && close(x_axis.z().abs(), h));
+    assert!(close(x_axis.y(), 0.0));
+    assert!(close(z_axis.x().abs(), h) && close(z_axis.z().abs(), h));
+    assert!(close(z_axis.x() * z_axis.z(), -0.5));
+    assert!(close(frame.y_axis().y().abs(), 1.0));
+    assert!(close(frame.origin().x(), 9.5));
+
+    assert_eq!(
+        Plane::best_fit(&points).unwrap().z_axis().z(),
+        frame.z_axis().z()
+    );
+    let center = PointCloud::new(points, Vec::new(), Vec::new())
+        .centroid()
+        .unwrap();
+    assert!(close(center.z(), 9.5));
+    assert!(pca_frame::<Point>(&[]).is_none());
+}
@@ -1,8 +1,11 @@
-use crate::{Color, Plane, Point, Tolerance, Vector, Xform};
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{Color, Plane, Point, Scalar, Tolerance, Vector, Xform};
+use core::fmt;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
 use serde::{Deserialize, Serialize};
-use std::fmt;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
-use uuid::Uuid;
 
 /// A polyline defined by a collection of points with an associated plane.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,7 +15,7 @@ pub struct Polyline {
     pub name: String,
     pub points: Vec<Point>,
     pub plane: Plane,
-    pub width: f64,
+    pub width: Scalar,
     pub linecolor: Color,
     #[serde(default = "Xform::identity")]
     pub xform: Xform,
@@ -21,7 +24,7 @@ pub struct Polyline {
 impl Default for Polyline {
     fn default() -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_polyline".to_string(),
             points: Vec::new(),
             plane: Plane::default(),
@@ -47,7 +50,7 @@ impl Polyline {
         };
 
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_polyline".to_string(),
             points,
             plane,
@@ -78,7 +81,7 @@ impl Polyline {
     }
 
     /// Calculates the total length of the polyline.
-    pub fn length(&self) -> f64 {
+    pub fn length(&self) -> Scalar {
         let mut total_length = 0.0;
         for i in 0..self.segment_count() {
             let mut segment_vector = self.points[i + 1].clone() - self.points[i].clone();
@@ -144,9 +147,7 @@ impl Polyline {
 
     pub fn transform(&mut self) {
         let xform = self.xform.clone();
-        for pt in &mut self.points {
-            xform.transform_point(pt);
-        }
+        Point::transform_many(&xform, &mut self.points);
         self.xform = Xform::identity();
     }
 
@@ -157,7 +158,8 @@ impl Polyline {
     }
 
     /// Serializes the Polyline to a JSON string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
@@ -166,21 +168,24 @@ impl Polyline {
     }
 
     /// Deserializes a Polyline from a JSON string.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
     /// Serializes the Polyline to a JSON file.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
     }
 
     /// Deserializes a Polyline from a JSON file.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
@@ -198,8 +203,8 @@ impl Polyline {
     }
 
     /// Calculate squared length of polyline (faster, no sqrt)
-    pub fn length_squared(&self) -> f64 {
-        let mut length = 0.0f64;
+    pub fn length_squared(&self) -> Scalar {
+        let mut length: Scalar = 0.0;
         for i in 0..self.segment_count() {
             let segment = self.points[i + 1].clone() - self.points[i].clone();
             length += segment.length_squared();
@@ -208,7 +213,7 @@ impl Polyline {
     }
 
     /// Get point at parameter t along a line segment (t=0 is start, t=1 is end)
-    pub fn point_at_parameter(start: &Point, end: &Point, t: f64) -> Point {
+    pub fn point_at_parameter(start: &Point, end: &Point, t: Scalar) -> Point {
         let s = 1.0 - t;
         let t_f32 = t;
         let s_f32 = s;
@@ -231,8 +236,107 @@ impl Polyline {
         )
     }
 
+    /// Get the point at a distance along the polyline, measured from the first point.
+    ///
+    /// The distance is clamped to the length of the polyline.
+    ///
+    /// # Returns
+    /// None if the polyline has no points.
+    pub fn point_at_length(&self, s: Scalar) -> Option<Point> {
+        self.point_at(self.parameter_at_length(s))
+    }
+
+    /// Divide the polyline into segments of equal length.
+    ///
+    /// # Arguments
+    /// * `count` - Number of segments, the result has `count + 1` points including both ends
+    ///
+    /// # Returns
+    /// Tuple of (points, parameters), a parameter is the segment index plus the
+    /// position on that segment in [0, 1]. Empty if `count` is 0 or the polyline
+    /// has no points.
+    pub fn divide_by_count(&self, count: usize) -> (Vec<Point>, Vec<Scalar>) {
+        if count == 0 || self.points.is_empty() {
+            return (Vec::new(), Vec::new());
+        }
+        let step = self.length() / count as Scalar;
+        let params: Vec<Scalar> = (0..=count)
+            .map(|i| match i {
+                i if i == count => self.segment_count() as Scalar,
+                i => self.parameter_at_length(i as Scalar * step),
+            })
+            .collect();
+        self.points_at(params)
+    }
+
+    /// Divide the polyline into segments of a fixed length, measured along the polyline.
+    ///
+    /// The last segment is shorter when the length does not divide the polyline.
+    ///
+    /// # Arguments
+    /// * `segment_length` - Distance between consecutive points
+    /// * `include_ends` - If true, includes the first and last point of the polyline
+    ///
+    /// # Returns
+    /// Tuple of (points, parameters), see `divide_by_count`. Empty if the
+    /// segment length is not positive or the polyline has no points.
+    pub fn divide_by_length(
+        &self,
+        segment_length: Scalar,
+        include_ends: bool,
+    ) -> (Vec<Point>, Vec<Scalar>) {
+        if segment_length <= 0.0 || self.points.is_empty() {
+            return (Vec::new(), Vec::new());
+        }
+        let length = self.length();
+        let mut params = Vec::new();
+        if include_ends {
+            params.push(0.0);
+        }
+        let mut i = 1;
+        while (i as Scalar) * segment_length < length - Tolerance::ZERO_TOLERANCE {
+            params.push(self.parameter_at_length(i as Scalar * segment_length));
+            i += 1;
+        }
+        if include_ends && length > Tolerance::ZERO_TOLERANCE {
+            params.push(self.segment_count() as Scalar);
+        }
+        self.points_at(params)
+    }
+
+    /// The polyline parameter at a distance along the polyline, see `divide_by_count`.
+    fn parameter_at_length(&self, s: Scalar) -> Scalar {
+        let mut remaining = s.max(0.0);
+        for i in 0..self.segment_count() {
+            let segment_length = self.points[i].distance(&self.points[i + 1]);
+            if remaining <= segment_length && segment_length > 0.0 {
+                return i as Scalar + remaining / segment_length;
+            }
+            remaining -= segment_length;
+        }
+        self.segment_count() as Scalar
+    }
+
+    /// The point at a polyline parameter, see `divide_by_count`.
+    fn point_at(&self, t: Scalar) -> Option<Point> {
+        if self.segment_count() == 0 {
+            return self.points.first().cloned();
+        }
+        let i = (t.max(0.0) as usize).min(self.segment_count() - 1);
+        Some(Self::point_at_parameter(
+            &self.points[i],
+            &self.points[i + 1],
+            t - i as Scalar,
+        ))
+    }
+
+    fn points_at(&self, params: Vec<Scalar>) -> (Vec<Point>, Vec<Scalar>) {
+        let points = params.iter().filter_map(|&t| self.point_at(t)).collect();
+        (points, params)
+    }
+
     /// Find closest point on line segment to given point, returns parameter t
-    pub fn closest_point_to_line(point: &Point, line_start: &Point, line_end: &Point) -> f64 {
+    pub fn closest_point_to_line(point: &Point, line_start: &Point, line_end: &Point) -> Scalar {
         let d = line_end.clone() - line_start.clone();
         let dod = d.length_squared();
 
@@ -352,7 +456,7 @@ impl Polyline {
             return None;
         }
 
-        let mut t_values: Vec<f64> = points
+        let mut t_values: Vec<Scalar> = points
             .iter()
             .map(|p| Self::closest_point_to_line(p, line_start, line_end))
             .collect();
@@ -371,9 +475,9 @@ impl Polyline {
     }
 
     /// Find closest distance and point from a point to this polyline
-    pub fn closest_distance_and_point(&self, point: &Point) -> (f64, usize, Point) {
+    pub fn closest_distance_and_point(&self, point: &Point) -> (Scalar, usize, Point) {
         let mut edge_id = 0;
-        let mut closest_distance = f64::MAX;
+        let mut closest_distance = Scalar::MAX;
         let mut best_t = 0.0;
 
         for i in 0..self.segment_count() {
@@ -412,27 +516,13 @@ impl Polyline {
 
     /// Calculate center point of polyline
     pub fn center(&self) -> Point {
-        if self.points.is_empty() {
-            return Point::new(0.0, 0.0, 0.0);
-        }
-
-        let n = if self.is_closed() && self.points.len() > 1 {
+        let n = if self.is_closed() {
             self.points.len() - 1
         } else {
             self.points.len()
         };
 
-        let mut sum_x = 0.0;
-        let mut sum_y = 0.0;
-        let mut sum_z = 0.0;
-
-        for i in 0..n {
-            sum_x += self.points[i].x();
-            sum_y += self.points[i].y();
-            sum_z += self.points[i].z();
-        }
-
-        Point::new(sum_x / n as f64, sum_y / n as f64, sum_z / n as f64)
+        crate::points::centroid(&self.points[..n]).unwrap_or_else(|| Point::new(0.0, 0.0, 0.0))
     }
 
     /// Calculate center as vector
@@ -497,8 +587,8 @@ impl Polyline {
     pub fn extend_line(
         line_start: &mut Point,
         line_end: &mut Point,
-        distance0: f64,
-        distance1: f64,
+        distance0: Scalar,
+        distance1: Scalar,
     ) {
         let mut v = line_end.clone() - line_start.clone();
         v.normalize_self();
@@ -508,7 +598,7 @@ impl Polyline {
     }
 
     /// Scale line segment inward by specified distance
-    pub fn scale_line(line_start: &mut Point, line_end: &mut Point, distance: f64) {
+    pub fn scale_line(line_start: &mut Point, line_end: &mut Point, distance: Scalar) {
         let v = line_end.clone() - line_start.clone();
         *line_start = line_start.clone() + (v.clone() * distance);
         *line_end = line_end.clone() - (v * distance);
@@ -518,10 +608,10 @@ impl Polyline {
     pub fn extend_segment(
         &mut self,
         segment_id: usize,
-        dist0: f64,
-        dist1: f64,
-        proportion0: f64,
-        proportion1: f64,
+        dist0: Scalar,
+        dist1: Scalar,
+        proportion0: Scalar,
+        proportion1: Scalar,
     ) {
         if segment_id >= self.segment_count() {
             return;
@@ -559,8 +649,8 @@ impl Polyline {
     pub fn extend_segment_equally_static(
         segment_start: &mut Point,
         segment_end: &mut Point,
-        dist: f64,
-        proportion: f64,
+        dist: Scalar,
+        proportion: Scalar,
     ) {
         if dist == 0.0 && proportion == 0.0 {
             return;
@@ -580,7 +670,7 @@ impl Polyline {
     }
 
     /// Extend polyline segment equally
-    pub fn extend_segment_equally(&mut self, segment_id: usize, dist: f64, proportion: f64) {
+    pub fn extend_segment_equally(&mut self, segment_id: usize, dist: Scalar, proportion: Scalar) {
         if segment_id >= self.segment_count() {
             return;
         }
@@ -609,26 +699,205 @@ impl Polyline {
         }
     }
 
-    /// Check if polyline is clockwise oriented
-    pub fn is_clockwise(&self, _plane: &Plane) -> bool {
-        if self.points.len() < 3 {
-            return false;
+    /// Check if polyline is clockwise oriented when seen from the plane normal.
+    ///
+    /// The points are projected to the plane and form a loop, whether closed or not.
+    pub fn is_clockwise(&self, plane: &Plane) -> bool {
+        self.signed_area(plane) < 0.0
+    }
+
+    /// Area enclosed by the polyline projected to a plane.
+    ///
+    /// The points form a loop, whether closed or not, and the result does not
+    /// depend on the orientation.
+    pub fn area(&self, plane: &Plane) -> Scalar {
+        self.signed_area(plane).abs()
+    }
+
+    /// Centroid of the area enclosed by the polyline projected to a plane.
+    ///
+    /// # Returns
+    /// The centroid on the plane, None if the enclosed area is zero.
+    pub fn centroid(&self, plane: &Plane) -> Option<Point> {
+        let (_, centroid, _) = self.area_properties(plane)?;
+        Some(Self::from_uv(plane, centroid))
+    }
+
+    /// Second moments of the area enclosed by the polyline projected to a plane.
+    ///
+    /// The moments are taken about the centroid, along the plane axes.
+    ///
+    /// # Returns
+    /// Tuple of (Ixx, Iyy, Ixy): the integrals of y², x² and xy over the area,
+    /// None if the enclosed area is zero.
+    pub fn moments_of_inertia(&self, plane: &Plane) -> Option<(Scalar, Scalar, Scalar)> {
+        let (area, [cx, cy], [ixx, iyy, ixy]) = self.area_properties(plane)?;
+        Some((
+            ixx - area * cy * cy,
+            iyy - area * cx * cx,
+            ixy - area * cx * cy,
+        ))
+    }
+
+    /// Clip the polyline against a closed boundary, both projected to a plane.
+    ///
+    /// Segments are split where they cross the boundary and the parts inside
+    /// or outside are kept. Points are interpolated on the original segments,
+    /// so the pieces keep their height above the plane.
+    ///
+    /// # Arguments
+    /// * `boundary` - The boundary loop, closed or not
+    /// * `plane` - The plane in which inside and outside are decided
+    /// * `keep_inside` - If true, keeps the parts inside the boundary, else the parts outside
+    ///
+    /// # Returns
+    /// The pieces in polyline order, with new GUIDs and the width, color and
+    /// xform of this polyline.
+    pub fn clip(&self, boundary: &Polyline, plane: &Plane, keep_inside: bool) -> Vec<Polyline> {
+        let loop_uv = boundary.loop_uv(plane);
+        let mut pieces: Vec<Vec<Point>> = Vec::new();
+        let mut current: Vec<Point> = Vec::new();
+
+        for i in 0..self.segment_count() {
+            let (a, b) = (&self.points[i], &self.points[i + 1]);
+            let (ua, ub) = (Self::to_uv(plane, a), Self::to_uv(plane, b));
+
+            // Parameters on the segment where it crosses the boundary
+            let mut params = vec![0.0, 1.0];
+            for j in 0..loop_uv.len() {
+                let (c, d) = (loop_uv[j], loop_uv[(j + 1) % loop_uv.len()]);
+                let r = [ub[0] - ua[0], ub[1] - ua[1]];
+                let e = [d[0] - c[0], d[1] - c[1]];
+                let denominator = r[0] * e[1] - r[1] * e[0];
+                if denominator.abs() < Tolerance::ZERO_TOLERANCE {
+                    continue;
+                }
+                let w = [c[0] - ua[0], c[1] - ua[1]];
+                let t = (w[0] * e[1] - w[1] * e[0]) / denominator;
+                let u = (w[0] * r[1] - w[1] * r[0]) / denominator;
+                if t > 0.0 && t < 1.0 && (0.0..=1.0).contains(&u) {
+                    params.push(t);
+                }
+            }
+            params.sort_by(|x, y| x.partial_cmp(y).unwrap_or(core::cmp::Ordering::Equal));
+
+            for interval in params.windows(2) {
+                let (t0, t1) = (interval[0], interval[1]);
+                if t1 - t0 < Tolerance::ZERO_TOLERANCE {
+                    continue;
+                }
+                let tm = (t0 + t1) * 0.5;
+                let middle = [ua[0] + (ub[0] - ua[0]) * tm, ua[1] + (ub[1] - ua[1]) * tm];
+                if Self::contains_uv(&loop_uv, middle) == keep_inside {
+                    if current.is_empty() {
+                        current.push(Self::point_at_parameter(a, b, t0));
+                    }
+                    current.push(Self::point_at_parameter(a, b, t1));
+                } else if !current.is_empty() {
+                    pieces.push(core::mem::take(&mut current));
+                }
+            }
         }
+        if !current.is_empty() {
+            pieces.push(current);
+        }
+
+        // A closed polyline kept across its seam is one piece
+        if self.is_closed() && pieces.len() > 1 {
+            let at = |point: &Point, index: usize| {
+                point.distance(&self.points[index]) < Tolerance::ZERO_TOLERANCE
+            };
+            let last = pieces.last().unwrap();
+            if at(&pieces[0][0], 0) && at(&last[last.len() - 1], self.points.len() - 1) {
+                let first = pieces.remove(0);
+                pieces.last_mut().unwrap().extend(first.into_iter().skip(1));
+            }
+        }
+
+        pieces
+            .into_iter()
+            .map(|points| {
+                let mut piece = Polyline::new(points);
+                piece.name = self.name.clone();
+                piece.width = self.width;
+                piece.linecolor = self.linecolor.clone();
+                piece.xform = self.xform.clone();
+                piece
+            })
+            .collect()
+    }
+
+    /// Even-odd test of a point against a loop in plane coordinates.
+    fn contains_uv(loop_uv: &[[Scalar; 2]], point: [Scalar; 2]) -> bool {
+        let mut inside = false;
+        for i in 0..loop_uv.len() {
+            let (a, b) = (loop_uv[i], loop_uv[(i + 1) % loop_uv.len()]);
+            if (a[1] > point[1]) != (b[1] > point[1])
+                && point[0] < a[0] + (point[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0])
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Signed area in the plane, positive for counter-clockwise loops.
+    fn signed_area(&self, plane: &Plane) -> Scalar {
+        let uv = self.loop_uv(plane);
+        let n = uv.len();
+        (0..n)
+            .map(|i| {
+                let (a, b) = (uv[i], uv[(i + 1) % n]);
+                a[0] * b[1] - b[0] * a[1]
+            })
+            .sum::<Scalar>()
+            * 0.5
+    }
+
+    /// Area, centroid and second moments about the plane origin, all in plane coordinates.
+    ///
+    /// The loop is walked counter-clockwise so that the area and moments are positive.
+    fn area_properties(&self, plane: &Plane) -> Option<(Scalar, [Scalar; 2], [Scalar; 3])> {
+        let uv = self.loop_uv(plane);
+        let n = uv.len();
+        let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+        let (mut ixx, mut iyy, mut ixy) = (0.0, 0.0, 0.0);
+        for i in 0..n {
+            let ([x0, y0], [x1, y1]) = (uv[i], uv[(i + 1) % n]);
+            let c = x0 * y1 - x1 * y0;
+            area += c;
+            cx += (x0 + x1) * c;
+            cy += (y0 + y1) * c;
+            ixx += (y0 * y0 + y0 * y1 + y1 * y1) * c;
+            iyy += (x0 * x0 + x0 * x1 + x1 * x1) * c;
+            ixy += (x0 * y1 + 2.0 * x0 * y0 + 2.0 * x1 * y1 + x1 * y0) * c;
+        }
+        area *= 0.5;
+        if area.abs() < Tolerance::ZERO_TOLERANCE {
+            return None;
+        }
+        let sign = area.signum();
+        Some((
+            area.abs(),
+            [cx / (6.0 * area), cy / (6.0 * area)],
+            [sign * ixx / 12.0, sign * iyy / 12.0, sign * ixy / 24.0],
+        ))
+    }
 
-        let mut sum = 0.0;
+    /// The points in plane coordinates, without the closing point of a closed polyline.
+    fn loop_uv(&self, plane: &Plane) -> Vec<[Scalar; 2]> {
         let n = if self.is_closed() {
             self.points.len() - 1
         } else {
             self.points.len()
         };
-
-        for i in 0..n {
-            let current = &self.points[i];
-            let next = &self.points[(i + 1) % n];
-            sum += (next.x() - current.x()) * (next.y() + current.y());
+        if n < 3 {
+            return Vec::new();
         }
-
-        sum > 0.0
+        self.points[..n]
+            .iter()
+            .map(|p| Self::to_uv(plane, p))
+            .collect()
     }
 
     /// Flip polyline direction (reverse point order)
@@ -676,7 +945,7 @@ impl Polyline {
     pub fn tween_two_polylines(
         polyline0: &Polyline,
         polyline1: &Polyline,
-        weight: f64,
+        weight: Scalar,
     ) -> Polyline {
         if polyline0.points.len() != polyline1.points.len() {
             return polyline0.clone();
@@ -694,6 +963,137 @@ impl Polyline {
         result
     }
 
+    /// Convex hull of points projected to a plane.
+    ///
+    /// # Arguments
+    /// * `points` - The points, projected along the plane normal
+    /// * `plane` - The plane of the hull
+    ///
+    /// # Returns
+    /// A closed polyline on the plane, counter-clockwise around its normal.
+    /// Empty for no points, and open for collinear points.
+    pub fn convex_hull_2d(points: &[Point], plane: &Plane) -> Polyline {
+        let hull = Self::hull_uv(points, plane);
+        let mut result = Polyline::new(hull.iter().map(|&uv| Self::from_uv(plane, uv)).collect());
+        if hull.len() > 2 {
+            result.points.push(result.points[0].clone());
+        }
+        result
+    }
+
+    /// Smallest rectangle around points projected to a plane.
+    ///
+    /// One side of the rectangle is aligned with an edge of the convex hull,
+    /// which is where the minimum is reached.
+    ///
+    /// # Arguments
+    /// * `points` - The points, projected along the plane normal
+    /// * `plane` - The plane of the rectangle
+    ///
+    /// # Returns
+    /// A closed polyline of 5 points on the plane, counter-clockwise around
+    /// its normal. Empty for no points, degenerate for collinear points.
+    pub fn minimum_area_rectangle(points: &[Point], plane: &Plane) -> Polyline {
+        let hull = Self::hull_uv(points, plane);
+        if hull.is_empty() {
+            return Polyline::default();
+        }
+
+        // For every hull edge direction: (area, direction, min and max along and across it)
+        let mut best: Option<(Scalar, [Scalar; 2], [Scalar; 4])> = None;
+        for i in 0..hull.len() {
+            let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+            let length = (b[0] - a[0]).hypot(b[1] - a[1]);
+            let direction = if length > Tolerance::ZERO_TOLERANCE {
+                [(b[0] - a[0]) / length, (b[1] - a[1]) / length]
+            } else {
+                [1.0, 0.0]
+            };
+            let mut extents = [Scalar::MAX, Scalar::MIN, Scalar::MAX, Scalar::MIN];
+            for p in &hull {
+                let along = p[0] * direction[0] + p[1] * direction[1];
+                let across = p[1] * direction[0] - p[0] * direction[1];
+                extents[0] = extents[0].min(along);
+                extents[1] = extents[1].max(along);
+                extents[2] = extents[2].min(across);
+                extents[3] = extents[3].max(across);
+            }
+            let area = (extents[1] - extents[0]) * (extents[3] - extents[2]);
+            if best.is_none_or(|(best_area, _, _)| area < best_area) {
+                best = Some((area, direction, extents));
+            }
+        }
+
+        let (_, [dx, dy], [a0, a1, c0, c1]) = best.unwrap();
+        let corner = |along: Scalar, across: Scalar| {
+            Self::from_uv(plane, [along * dx - across * dy, along * dy + across * dx])
+        };
+        Polyline::new(vec![
+            corner(a0, c0),
+            corner(a1, c0),
+            corner(a1, c1),
+            corner(a0, c1),
+            corner(a0, c0),
+        ])
+    }
+
+    /// Convex hull in plane coordinates by the monotone chain algorithm, counter-clockwise.
+    fn hull_uv(points: &[Point], plane: &Plane) -> Vec<[Scalar; 2]> {
+        let mut uv: Vec<[Scalar; 2]> = points.iter().map(|p| Self::to_uv(plane, p)).collect();
+        uv.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+        uv.dedup_by(|a, b| {
+            (a[0] - b[0]).abs() < Tolerance::ZERO_TOLERANCE
+                && (a[1] - b[1]).abs() < Tolerance::ZERO_TOLERANCE
+        });
+        if uv.len() < 3 {
+            return uv;
+        }
+
+        let cross = |o: &[Scalar; 2], a: &[Scalar; 2], b: &[Scalar; 2]| {
+            (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+        };
+        // Lower hull left to right, then upper hull right to left
+        let mut hull: Vec<[Scalar; 2]> = Vec::with_capacity(uv.len() * 2);
+        let push = |hull: &mut Vec<[Scalar; 2]>, p: &[Scalar; 2], floor: usize| {
+            while hull.len() >= floor
+                && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], p)
+                    <= Tolerance::ZERO_TOLERANCE
+            {
+                hull.pop();
+            }
+            hull.push(*p);
+        };
+        for p in &uv {
+            push(&mut hull, p, 2);
+        }
+        let floor = hull.len() + 1;
+        for p in uv.iter().rev().skip(1) {
+            push(&mut hull, p, floor);
+        }
+        hull.pop();
+        if hull.len() < 3 {
+            // Collinear points, keep the two ends
+            return vec![uv[0], uv[uv.len() - 1]];
+        }
+        hull
+    }
+
+    /// Coordinates of a point projected to a plane, along its x and y axes.
+    fn to_uv(plane: &Plane, point: &Point) -> [Scalar; 2] {
+        let d = point.clone() - plane.origin();
+        [d.dot(&plane.x_axis()), d.dot(&plane.y_axis())]
+    }
+
+    /// The point on a plane at coordinates along its x and y axes.
+    fn from_uv(plane: &Plane, uv: [Scalar; 2]) -> Point {
+        let (origin, x_axis, y_axis) = (plane.origin(), plane.x_axis(), plane.y_axis());
+        Point::new(
+            origin.x() + uv[0] * x_axis.x() + uv[1] * y_axis.x(),
+            origin.y() + uv[0] * x_axis.y() + uv[1] * y_axis.y(),
+            origin.z() + uv[0] * x_axis.z() + uv[1] * y_axis.z(),
+        )
+    }
+
     /// Calculate average normal from polyline points
     fn average_normal(&self) -> Vector {
         let len = self.points.len();
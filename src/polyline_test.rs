@@ -1,4 +1,5 @@
 use crate::encoders::{json_dump, json_load};
+use crate::scalar::test_tolerance;
 use crate::{Plane, Point, Polyline, Vector};
 
 #[test]
@@ -521,8 +522,68 @@ fn test_polyline_is_clockwise() {
     ]);
     let plane = Plane::default();
 
-    let _clockwise = polyline.is_clockwise(&plane);
-    // Just test it doesn't crash - the function returns a boolean value
+    assert!(!polyline.is_clockwise(&plane));
+    assert!(polyline.reversed().is_clockwise(&plane));
+
+    // Seen from below the orientation flips
+    let below = Plane::new(
+        Point::new(0.0, 0.0, 0.0),
+        Vector::new(1.0, 0.0, 0.0),
+        Vector::new(0.0, -1.0, 0.0),
+    );
+    assert!(polyline.is_clockwise(&below));
+}
+
+#[test]
+fn test_polyline_area_and_centroid() {
+    // 4 x 2 rectangle, closed and clockwise
+    let rectangle = Polyline::new(vec![
+        Point::new(1.0, 1.0, 3.0),
+        Point::new(1.0, 3.0, 3.0),
+        Point::new(5.0, 3.0, 3.0),
+        Point::new(5.0, 1.0, 3.0),
+        Point::new(1.0, 1.0, 3.0),
+    ]);
+    let plane = Plane::xy_plane();
+    assert!(rectangle.is_clockwise(&plane));
+    assert!((rectangle.area(&plane) - 8.0).abs() < test_tolerance(1e-9));
+    assert_eq!(rectangle.area(&plane), rectangle.reversed().area(&plane));
+
+    let centroid = rectangle.centroid(&plane).unwrap();
+    assert!((centroid.x() - 3.0).abs() < test_tolerance(1e-9));
+    assert!((centroid.y() - 2.0).abs() < test_tolerance(1e-9));
+    assert_eq!(centroid.z(), 0.0);
+
+    // b h³ / 12 and h b³ / 12 about the centroid
+    let (ixx, iyy, ixy) = rectangle.moments_of_inertia(&plane).unwrap();
+    assert!((ixx - 4.0 * 8.0 / 12.0).abs() < test_tolerance(1e-9));
+    assert!((iyy - 2.0 * 64.0 / 12.0).abs() < test_tolerance(1e-9));
+    assert!(ixy.abs() < test_tolerance(1e-9));
+    let (rxx, ryy, rxy) = rectangle.reversed().moments_of_inertia(&plane).unwrap();
+    assert!(
+        (rxx - ixx).abs() < test_tolerance(1e-9)
+            && (ryy - iyy).abs() < test_tolerance(1e-9)
+            && rxy.abs() < test_tolerance(1e-9)
+    );
+
+    // L-shape with a non-zero product of inertia
+    let l_shape = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(2.0, 0.0, 0.0),
+        Point::new(2.0, 1.0, 0.0),
+        Point::new(1.0, 1.0, 0.0),
+        Point::new(1.0, 2.0, 0.0),
+        Point::new(0.0, 2.0, 0.0),
+    ]);
+    assert!((l_shape.area(&plane) - 3.0).abs() < test_tolerance(1e-9));
+    let centroid = l_shape.centroid(&plane).unwrap();
+    assert!((centroid.x() - 5.0 / 6.0).abs() < test_tolerance(1e-9));
+    assert!(l_shape.moments_of_inertia(&plane).unwrap().2 < 0.0);
+
+    let segment = Polyline::new(vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0)]);
+    assert_eq!(segment.area(&plane), 0.0);
+    assert!(segment.centroid(&plane).is_none());
+    assert!(segment.moments_of_inertia(&plane).is_none());
 }
 
 #[test]
@@ -564,3 +625,154 @@ fn test_polyline_tween_two_polylines() {
     assert!((result.points[0].y() - 1.0).abs() < 1e-5);
     assert!((result.points[1].y() - 1.0).abs() < 1e-5);
 }
+
+#[test]
+fn test_polyline_point_at_length() {
+    let polyline = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(2.0, 0.0, 0.0),
+        Point::new(2.0, 3.0, 0.0),
+    ]);
+    let point = polyline.point_at_length(3.0).unwrap();
+    assert!(
+        (point.x() - 2.0).abs() < test_tolerance(1e-9)
+            && (point.y() - 1.0).abs() < test_tolerance(1e-9)
+    );
+    assert_eq!(polyline.point_at_length(-1.0).unwrap().x(), 0.0);
+    assert_eq!(polyline.point_at_length(10.0).unwrap().y(), 3.0);
+    assert!(Polyline::default().point_at_length(1.0).is_none());
+}
+
+#[test]
+fn test_polyline_divide_by_count() {
+    let polyline = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(2.0, 0.0, 0.0),
+        Point::new(2.0, 2.0, 0.0),
+    ]);
+    let (points, params) = polyline.divide_by_count(4);
+    assert_eq!(points.len(), 5);
+    assert_eq!(params, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+    assert!((points[1].x() - 1.0).abs() < 1e-9);
+    assert!((points[3].y() - 1.0).abs() < 1e-9);
+    assert_eq!(points[4].y(), 2.0);
+    assert!(polyline.divide_by_count(0).0.is_empty());
+}
+
+#[test]
+fn test_polyline_divide_by_length() {
+    let polyline = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(2.0, 0.0, 0.0),
+        Point::new(2.0, 3.0, 0.0),
+    ]);
+    let (points, params) = polyline.divide_by_length(2.0, true);
+    assert_eq!(params, vec![0.0, 1.0, 1.0 + 2.0 / 3.0, 2.0]);
+    assert!((points[2].y() - 2.0).abs() < test_tolerance(1e-9));
+
+    let (points, params) = polyline.divide_by_length(2.0, false);
+    assert_eq!(points.len(), 2);
+    assert_eq!(params[0], 1.0);
+    // A length dividing the polyline exactly does not repeat the end
+    assert_eq!(polyline.divide_by_length(2.5, true).0.len(), 3);
+    assert!(polyline.divide_by_length(0.0, true).0.is_empty());
+}
+
+#[test]
+fn test_polyline_convex_hull_2d() {
+    let points = vec![
+        Point::new(0.0, 0.0, 5.0),
+        Point::new(2.0, 0.0, 1.0),
+        Point::new(1.0, 1.0, 0.0),
+        Point::new(2.0, 2.0, 0.0),
+        Point::new(0.0, 2.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+    ];
+    let hull = Polyline::convex_hull_2d(&points, &Plane::xy_plane());
+    assert_eq!(hull.len(), 5);
+    assert!(hull.is_closed());
+    assert!(hull.points.iter().all(|p| p.z() == 0.0));
+    // Counter-clockwise from the leftmost point
+    assert_eq!((hull.points[1].x(), hull.points[1].y()), (2.0, 0.0));
+    assert_eq!((hull.points[2].x(), hull.points[2].y()), (2.0, 2.0));
+
+    let line = [
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 1.0, 0.0),
+        Point::new(2.0, 2.0, 0.0),
+    ];
+    assert_eq!(Polyline::convex_hull_2d(&line, &Plane::xy_plane()).len(), 2);
+    assert!(Polyline::convex_hull_2d(&[], &Plane::xy_plane()).is_empty());
+}
+
+#[test]
+fn test_polyline_minimum_area_rectangle() {
+    // A 4 x 1 rectangle rotated by 45 degrees
+    let h = std::f64::consts::FRAC_1_SQRT_2 as crate::Scalar;
+    let points = vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(4.0 * h, 4.0 * h, 0.0),
+        Point::new(4.0 * h - h, 4.0 * h + h, 0.0),
+        Point::new(-h, h, 0.0),
+        Point::new(2.0 * h, 2.0 * h, 0.0),
+    ];
+    let rectangle = Polyline::minimum_area_rectangle(&points, &Plane::xy_plane());
+    assert_eq!(rectangle.len(), 5);
+    assert!(rectangle.is_closed());
+    let sides = [
+        rectangle.points[0].distance(&rectangle.points[1]),
+        rectangle.points[1].distance(&rectangle.points[2]),
+    ];
+    assert!((sides[0] * sides[1] - 4.0).abs() < 1e-6);
+    assert!(Polyline::minimum_area_rectangle(&[], &Plane::xy_plane()).is_empty());
+}
+
+#[test]
+fn test_polyline_clip() {
+    let boundary = Polyline::new(vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(4.0, 0.0, 0.0),
+        Point::new(4.0, 4.0, 0.0),
+        Point::new(0.0, 4.0, 0.0),
+    ]);
+    let plane = Plane::xy_plane();
+    // Zigzag toolpath above the plane, crossing the right side twice
+    let mut path = Polyline::new(vec![
+        Point::new(-1.0, 1.0, 2.0),
+        Point::new(6.0, 1.0, 2.0),
+        Point::new(6.0, 3.0, 2.0),
+        Point::new(2.0, 3.0, 2.0),
+    ]);
+    path.width = 0.5;
+
+    let digits = (1.0 / test_tolerance(1e-9)).round();
+    let x = |piece: &Polyline, i: usize| (piece.points[i].x() * digits).round() / digits;
+
+    let inside = path.clip(&boundary, &plane, true);
+    assert_eq!(inside.len(), 2);
+    assert_eq!(inside[0].len(), 2);
+    assert_eq!((x(&inside[0], 0), x(&inside[0], 1)), (0.0, 4.0));
+    assert_eq!(inside[0].points[0].z(), 2.0);
+    assert_eq!((x(&inside[1], 0), x(&inside[1], 1)), (4.0, 2.0));
+    assert_eq!(inside[1].width, 0.5);
+    assert_ne!(inside[1].guid, path.guid);
+
+    let outside = path.clip(&boundary, &plane, false);
+    assert_eq!(outside.len(), 2);
+    assert_eq!(outside[1].len(), 4);
+    assert_eq!(x(&outside[1], 3), 4.0);
+
+    // A closed loop crossing the boundary at its seam stays in one piece
+    let square = Polyline::new(vec![
+        Point::new(3.0, 1.0, 0.0),
+        Point::new(5.0, 1.0, 0.0),
+        Point::new(5.0, 3.0, 0.0),
+        Point::new(3.0, 3.0, 0.0),
+        Point::new(3.0, 1.0, 0.0),
+    ]);
+    let inside = square.clip(&boundary, &plane, true);
+    assert_eq!(inside.len(), 1);
+    assert_eq!(inside[0].len(), 4);
+    assert!(square.clip(&Polyline::default(), &plane, true).is_empty());
+    assert_eq!(square.clip(&Polyline::default(), &plane, false).len(), 1);
+}
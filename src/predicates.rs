@@ -0,0 +1,323 @@
+//! Robust geometric predicates.
+//!
+//! Adaptive versions of Shewchuk's orientation and in-circle tests: the determinant
+//! is first evaluated in floating point and accepted when its magnitude exceeds a
+//! forward error bound; otherwise it is recomputed exactly with floating-point
+//! expansion arithmetic. The sign of the result is always correct.
+
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::scalar::to_f64;
+use crate::Point;
+
+/// Machine epsilon as used by Shewchuk (half ulp of 1.0).
+const EPSILON: f64 = f64::EPSILON * 0.5;
+const CCW_ERRBOUND_A: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const O3D_ERRBOUND_A: f64 = (7.0 + 56.0 * EPSILON) * EPSILON;
+const ICC_ERRBOUND_A: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+const ISP_ERRBOUND_A: f64 = (16.0 + 224.0 * EPSILON) * EPSILON;
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Predicates
+///////////////////////////////////////////////////////////////////////////////////////////
+
+// Coordinates are widened to f64 so the error bounds hold in either `Scalar` precision
+fn x(p: &Point) -> f64 {
+    to_f64(p.x())
+}
+
+fn y(p: &Point) -> f64 {
+    to_f64(p.y())
+}
+
+fn z(p: &Point) -> f64 {
+    to_f64(p.z())
+}
+
+/// Orientation of three points in the XY plane.
+///
+/// # Returns
+/// Positive if `a`, `b`, `c` are in counter-clockwise order, negative if clockwise,
+/// and zero if collinear. The value approximates twice the signed triangle area.
+pub fn orient2d(a: &Point, b: &Point, c: &Point) -> f64 {
+    let detleft = (x(a) - x(c)) * (y(b) - y(c));
+    let detright = (y(a) - y(c)) * (x(b) - x(c));
+    let det = detleft - detright;
+
+    let detsum = if detleft > 0.0 {
+        if detright <= 0.0 {
+            return det;
+        }
+        detleft + detright
+    } else if detleft < 0.0 {
+        if detright >= 0.0 {
+            return det;
+        }
+        -detleft - detright
+    } else {
+        return det;
+    };
+
+    if det.abs() >= CCW_ERRBOUND_A * detsum {
+        return det;
+    }
+
+    let (ax, ay) = (Expansion::from(x(a)), Expansion::from(y(a)));
+    let (bx, by) = (Expansion::from(x(b)), Expansion::from(y(b)));
+    let (cx, cy) = (Expansion::from(x(c)), Expansion::from(y(c)));
+    let acx = ax.sub(&cx);
+    let acy = ay.sub(&cy);
+    let bcx = bx.sub(&cx);
+    let bcy = by.sub(&cy);
+    acx.mul(&bcy).sub(&acy.mul(&bcx)).estimate()
+}
+
+/// Orientation of four points in 3D.
+///
+/// # Returns
+/// Positive if `d` lies below the plane through `a`, `b`, `c` (where `a`, `b`, `c`
+/// appear counter-clockwise when viewed from above), negative if above, and zero if
+/// coplanar. The value approximates six times the signed tetrahedron volume.
+pub fn orient3d(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let (adx, ady, adz) = (x(a) - x(d), y(a) - y(d), z(a) - z(d));
+    let (bdx, bdy, bdz) = (x(b) - x(d), y(b) - y(d), z(b) - z(d));
+    let (cdx, cdy, cdz) = (x(c) - x(d), y(c) - y(d), z(c) - z(d));
+
+    let bdxcdy = bdx * cdy;
+    let cdxbdy = cdx * bdy;
+    let cdxady = cdx * ady;
+    let adxcdy = adx * cdy;
+    let adxbdy = adx * bdy;
+    let bdxady = bdx * ady;
+
+    let det = adz * (bdxcdy - cdxbdy) + bdz * (cdxady - adxcdy) + cdz * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * adz.abs()
+        + (cdxady.abs() + adxcdy.abs()) * bdz.abs()
+        + (adxbdy.abs() + bdxady.abs()) * cdz.abs();
+    if det.abs() > O3D_ERRBOUND_A * permanent {
+        return det;
+    }
+
+    let [ad, bd, cd] = [a, b, c].map(|p| exact_difference(p, d));
+    let cross = |u: &[Expansion; 3], v: &[Expansion; 3]| u[0].mul(&v[1]).sub(&u[1].mul(&v[0]));
+    let bc = cross(&bd, &cd);
+    let ca = cross(&cd, &ad);
+    let ab = cross(&ad, &bd);
+    ad[2]
+        .mul(&bc)
+        .add(&bd[2].mul(&ca))
+        .add(&cd[2].mul(&ab))
+        .estimate()
+}
+
+/// In-circle test for four points in the XY plane.
+///
+/// # Returns
+/// Positive if `d` lies inside the circle through `a`, `b`, `c` (given in
+/// counter-clockwise order), negative if outside, and zero if cocircular.
+pub fn incircle(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let (adx, ady) = (x(a) - x(d), y(a) - y(d));
+    let (bdx, bdy) = (x(b) - x(d), y(b) - y(d));
+    let (cdx, cdy) = (x(c) - x(d), y(c) - y(d));
+
+    let bdxcdy = bdx * cdy;
+    let cdxbdy = cdx * bdy;
+    let alift = adx * adx + ady * ady;
+    let cdxady = cdx * ady;
+    let adxcdy = adx * cdy;
+    let blift = bdx * bdx + bdy * bdy;
+    let adxbdy = adx * bdy;
+    let bdxady = bdx * ady;
+    let clift = cdx * cdx + cdy * cdy;
+
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    if det.abs() > ICC_ERRBOUND_A * permanent {
+        return det;
+    }
+
+    let [ad, bd, cd] = [a, b, c].map(|p| exact_difference(p, d));
+    let lift = |v: &[Expansion; 3]| v[0].mul(&v[0]).add(&v[1].mul(&v[1]));
+    let cross = |u: &[Expansion; 3], v: &[Expansion; 3]| u[0].mul(&v[1]).sub(&u[1].mul(&v[0]));
+    lift(&ad)
+        .mul(&cross(&bd, &cd))
+        .add(&lift(&bd).mul(&cross(&cd, &ad)))
+        .add(&lift(&cd).mul(&cross(&ad, &bd)))
+        .estimate()
+}
+
+/// In-sphere test for five points in 3D.
+///
+/// # Returns
+/// Positive if `e` lies inside the sphere through `a`, `b`, `c`, `d` (ordered so
+/// that `orient3d(a, b, c, d)` is positive), negative if outside, and zero if
+/// cospherical.
+pub fn insphere(a: &Point, b: &Point, c: &Point, d: &Point, e: &Point) -> f64 {
+    let [ae, be, ce, de] = [a, b, c, d].map(|p| [x(p) - x(e), y(p) - y(e), z(p) - z(e)]);
+
+    let minor = |u: &[f64; 3], v: &[f64; 3], w: &[f64; 3]| {
+        u[2] * (v[0] * w[1] - w[0] * v[1])
+            + v[2] * (w[0] * u[1] - u[0] * w[1])
+            + w[2] * (u[0] * v[1] - v[0] * u[1])
+    };
+    let minor_permanent = |u: &[f64; 3], v: &[f64; 3], w: &[f64; 3]| {
+        u[2].abs() * ((v[0] * w[1]).abs() + (w[0] * v[1]).abs())
+            + v[2].abs() * ((w[0] * u[1]).abs() + (u[0] * w[1]).abs())
+            + w[2].abs() * ((u[0] * v[1]).abs() + (v[0] * u[1]).abs())
+    };
+    let lift = |v: &[f64; 3]| v[0] * v[0] + v[1] * v[1] + v[2] * v[2];
+
+    let det = lift(&de) * minor(&ae, &be, &ce) - lift(&ce) * minor(&de, &ae, &be)
+        + lift(&be) * minor(&ce, &de, &ae)
+        - lift(&ae) * minor(&be, &ce, &de);
+    let permanent = lift(&de) * minor_permanent(&ae, &be, &ce)
+        + lift(&ce) * minor_permanent(&de, &ae, &be)
+        + lift(&be) * minor_permanent(&ce, &de, &ae)
+        + lift(&ae) * minor_permanent(&be, &ce, &de);
+    if det.abs() > ISP_ERRBOUND_A * permanent {
+        return det;
+    }
+
+    let [ae, be, ce, de] = [a, b, c, d].map(|p| exact_difference(p, e));
+    let minor = |u: &[Expansion; 3], v: &[Expansion; 3], w: &[Expansion; 3]| {
+        let m = |p: &[Expansion; 3], q: &[Expansion; 3]| p[0].mul(&q[1]).sub(&q[0].mul(&p[1]));
+        u[2].mul(&m(v, w))
+            .add(&v[2].mul(&m(w, u)))
+            .add(&w[2].mul(&m(u, v)))
+    };
+    let lift = |v: &[Expansion; 3]| v[0].mul(&v[0]).add(&v[1].mul(&v[1])).add(&v[2].mul(&v[2]));
+
+    lift(&de)
+        .mul(&minor(&ae, &be, &ce))
+        .sub(&lift(&ce).mul(&minor(&de, &ae, &be)))
+        .add(&lift(&be).mul(&minor(&ce, &de, &ae)))
+        .sub(&lift(&ae).mul(&minor(&be, &ce, &de)))
+        .estimate()
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Expansion arithmetic
+///////////////////////////////////////////////////////////////////////////////////////////
+
+/// Exact coordinate differences `p - q` as expansions.
+fn exact_difference(p: &Point, q: &Point) -> [Expansion; 3] {
+    [
+        Expansion::from(x(p)).sub(&Expansion::from(x(q))),
+        Expansion::from(y(p)).sub(&Expansion::from(y(q))),
+        Expansion::from(z(p)).sub(&Expansion::from(z(q))),
+    ]
+}
+
+/// Nonoverlapping floating-point expansion, components sorted by increasing magnitude.
+/// The exact value is the sum of all components.
+#[derive(Debug, Clone)]
+struct Expansion(Vec<f64>);
+
+impl From<f64> for Expansion {
+    fn from(value: f64) -> Self {
+        if value == 0.0 {
+            Expansion(Vec::new())
+        } else {
+            Expansion(vec![value])
+        }
+    }
+}
+
+impl Expansion {
+    /// Exact sum, Shewchuk's EXPANSION-SUM with zero elimination.
+    fn add(&self, other: &Expansion) -> Expansion {
+        let mut result = self.0.clone();
+        for &b in &other.0 {
+            result = grow_expansion(&result, b);
+        }
+        Expansion(result)
+    }
+
+    fn sub(&self, other: &Expansion) -> Expansion {
+        self.add(&other.neg())
+    }
+
+    fn neg(&self) -> Expansion {
+        Expansion(self.0.iter().map(|v| -v).collect())
+    }
+
+    /// Exact product as a sum of scaled expansions.
+    fn mul(&self, other: &Expansion) -> Expansion {
+        let mut result = Expansion(Vec::new());
+        for &b in &other.0 {
+            result = result.add(&Expansion(scale_expansion(&self.0, b)));
+        }
+        result
+    }
+
+    /// Approximate value with the correct sign.
+    fn estimate(&self) -> f64 {
+        self.0.iter().sum()
+    }
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    let b_roundoff = b - b_virtual;
+    let a_roundoff = a - a_virtual;
+    (x, a_roundoff + b_roundoff)
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, a.mul_add(b, -x))
+}
+
+/// Add a double to an expansion (GROW-EXPANSION with zero elimination).
+fn grow_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut result = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for &component in e {
+        let (sum, error) = two_sum(q, component);
+        q = sum;
+        if error != 0.0 {
+            result.push(error);
+        }
+    }
+    if q != 0.0 || result.is_empty() {
+        result.push(q);
+    }
+    result.retain(|v| *v != 0.0);
+    result
+}
+
+/// Multiply an expansion by a double (SCALE-EXPANSION with zero elimination).
+fn scale_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut result = Vec::with_capacity(2 * e.len());
+    let Some((&first, rest)) = e.split_first() else {
+        return result;
+    };
+    let (mut q, error) = two_product(first, b);
+    if error != 0.0 {
+        result.push(error);
+    }
+    for &component in rest {
+        let (product, product_error) = two_product(component, b);
+        let (sum, sum_error) = two_sum(q, product_error);
+        if sum_error != 0.0 {
+            result.push(sum_error);
+        }
+        let (next, next_error) = two_sum(product, sum);
+        q = next;
+        if next_error != 0.0 {
+            result.push(next_error);
+        }
+    }
+    if q != 0.0 {
+        result.push(q);
+    }
+    result
+}
+
+#[cfg(test)]
+#[path = "predicates_test.rs"]
+mod predicates_test;
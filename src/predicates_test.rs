@@ -0,0 +1,82 @@
+use crate::predicates::{incircle, insphere, orient2d, orient3d};
+use crate::Point;
+use crate::Scalar;
+
+#[test]
+fn test_orient2d() {
+    let a = Point::new(0.0, 0.0, 0.0);
+    let b = Point::new(1.0, 0.0, 0.0);
+    let c = Point::new(0.0, 1.0, 0.0);
+    assert!(orient2d(&a, &b, &c) > 0.0);
+    assert!(orient2d(&a, &c, &b) < 0.0);
+    assert_eq!(orient2d(&a, &b, &Point::new(2.0, 0.0, 0.0)), 0.0);
+}
+
+#[test]
+fn test_orient2d_near_degenerate() {
+    // Points nearly on the line y = x where the naive determinant loses the sign
+    let a = Point::new(0.5, 0.5, 0.0);
+    let b = Point::new(12.0, 12.0, 0.0);
+    let c = Point::new(24.0, 24.0, 0.0);
+    let eps = Scalar::EPSILON;
+    for i in 0..64 {
+        let p = Point::new(0.5 + i as Scalar * eps, 0.5, 0.0);
+        let exact = orient2d(&p, &b, &c);
+        if i == 0 {
+            assert_eq!(exact, 0.0);
+        } else {
+            // p is shifted right of the line through b and c, so b, c, p turn clockwise
+            assert!(exact < 0.0, "i = {}", i);
+        }
+    }
+    assert_eq!(orient2d(&a, &b, &c), 0.0);
+}
+
+#[test]
+fn test_orient3d() {
+    let a = Point::new(0.0, 0.0, 0.0);
+    let b = Point::new(1.0, 0.0, 0.0);
+    let c = Point::new(0.0, 1.0, 0.0);
+    assert!(orient3d(&a, &b, &c, &Point::new(0.0, 0.0, -1.0)) > 0.0);
+    assert!(orient3d(&a, &b, &c, &Point::new(0.0, 0.0, 1.0)) < 0.0);
+    assert_eq!(orient3d(&a, &b, &c, &Point::new(0.3, 0.7, 0.0)), 0.0);
+
+    // Tiny offset off a plane with non-representable coefficients
+    let a = Point::new(0.1, 0.2, 0.3);
+    let b = Point::new(1.1, 0.7, 0.9);
+    let c = Point::new(0.4, 1.3, 0.6);
+    let above = Point::new(0.5, 0.5, 1e-300 + 0.5);
+    let d_on = orient3d(&a, &b, &c, &a);
+    assert_eq!(d_on, 0.0);
+    let s = orient3d(&a, &b, &c, &above);
+    assert_eq!(
+        s.signum(),
+        orient3d(&a, &b, &c, &Point::new(0.5, 0.5, 0.5)).signum()
+    );
+}
+
+#[test]
+fn test_incircle() {
+    let a = Point::new(1.0, 0.0, 0.0);
+    let b = Point::new(0.0, 1.0, 0.0);
+    let c = Point::new(-1.0, 0.0, 0.0);
+    assert!(incircle(&a, &b, &c, &Point::new(0.0, 0.0, 0.0)) > 0.0);
+    assert!(incircle(&a, &b, &c, &Point::new(2.0, 0.0, 0.0)) < 0.0);
+    assert_eq!(incircle(&a, &b, &c, &Point::new(0.0, -1.0, 0.0)), 0.0);
+    let just_inside = Point::new(0.0, -1.0 + Scalar::EPSILON, 0.0);
+    assert!(incircle(&a, &b, &c, &just_inside) > 0.0);
+}
+
+#[test]
+fn test_insphere() {
+    let a = Point::new(1.0, 0.0, 0.0);
+    let b = Point::new(0.0, 1.0, 0.0);
+    let c = Point::new(-1.0, 0.0, 0.0);
+    let d = Point::new(0.0, 0.0, -1.0);
+    assert!(orient3d(&a, &b, &c, &d) > 0.0);
+    assert!(insphere(&a, &b, &c, &d, &Point::new(0.0, 0.0, 0.0)) > 0.0);
+    assert!(insphere(&a, &b, &c, &d, &Point::new(0.0, 0.0, 2.0)) < 0.0);
+    assert_eq!(insphere(&a, &b, &c, &d, &Point::new(0.0, 0.0, 1.0)), 0.0);
+    let just_inside = Point::new(0.0, 0.0, 1.0 - Scalar::EPSILON);
+    assert!(insphere(&a, &b, &c, &d, &just_inside) > 0.0);
+}
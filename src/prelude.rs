@@ -0,0 +1,7 @@
+//! Names of the `std` prelude that the kernel modules import under `no_std`.
+
+pub use alloc::string::{String, ToString};
+pub use alloc::vec::Vec;
+pub use alloc::{format, vec};
+
+pub use crate::scalar::Float;
@@ -1,14 +1,48 @@
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::Scalar;
 use crate::Vector;
+use core::ops::Mul;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::ops::Mul;
-use uuid::Uuid;
+
+/// Order of Euler angle rotations about the fixed world axes.
+///
+/// `Xyz` rotates about X first, then Y, then Z, which equals the intrinsic
+/// Z, Y', X'' (yaw, pitch, roll) sequence. Angles are given in the order the
+/// rotations are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EulerOrder {
+    #[default]
+    Xyz,
+    Xzy,
+    Yxz,
+    Yzx,
+    Zxy,
+    Zyx,
+}
+
+impl EulerOrder {
+    /// Axis indices in application order, and 1 for cyclic orders or -1 otherwise.
+    fn axes(self) -> ([usize; 3], Scalar) {
+        match self {
+            EulerOrder::Xyz => ([0, 1, 2], 1.0),
+            EulerOrder::Yzx => ([1, 2, 0], 1.0),
+            EulerOrder::Zxy => ([2, 0, 1], 1.0),
+            EulerOrder::Xzy => ([0, 2, 1], -1.0),
+            EulerOrder::Yxz => ([1, 0, 2], -1.0),
+            EulerOrder::Zyx => ([2, 1, 0], -1.0),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Quaternion {
     pub typ: String,
     pub guid: String,
     pub name: String,
-    pub s: f64,
+    pub s: Scalar,
     pub v: Vector,
 }
 
@@ -43,10 +77,10 @@ impl<'de> Deserialize<'de> for Quaternion {
             typ: String,
             guid: String,
             name: String,
-            s: f64,
-            x: f64,
-            y: f64,
-            z: f64,
+            s: Scalar,
+            x: Scalar,
+            y: Scalar,
+            z: Scalar,
         }
 
         let helper = QuaternionHelper::deserialize(deserializer)?;
@@ -61,20 +95,20 @@ impl<'de> Deserialize<'de> for Quaternion {
 }
 
 impl Quaternion {
-    pub fn new(s: f64, v: Vector) -> Self {
+    pub fn new(s: Scalar, v: Vector) -> Self {
         Quaternion {
             typ: "Quaternion".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_quaternion".to_string(),
             s,
             v,
         }
     }
 
-    pub fn from_sv(s: f64, x: f64, y: f64, z: f64) -> Self {
+    pub fn from_sv(s: Scalar, x: Scalar, y: Scalar, z: Scalar) -> Self {
         Quaternion {
             typ: "Quaternion".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_quaternion".to_string(),
             s,
             v: Vector::new(x, y, z),
@@ -84,21 +118,21 @@ impl Quaternion {
     pub fn identity() -> Self {
         Quaternion {
             typ: "Quaternion".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_quaternion".to_string(),
             s: 1.0,
             v: Vector::new(0.0, 0.0, 0.0),
         }
     }
 
-    pub fn from_axis_angle(axis: Vector, angle: f64) -> Self {
+    pub fn from_axis_angle(axis: Vector, angle: Scalar) -> Self {
         let axis = axis.normalize();
         let half_angle = angle * 0.5;
         let s = half_angle.cos();
         let v = axis * half_angle.sin();
         Quaternion {
             typ: "Quaternion".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_quaternion".to_string(),
             s,
             v,
@@ -112,7 +146,7 @@ impl Quaternion {
         v + (uv * self.s + uuv) * 2.0
     }
 
-    pub fn magnitude(&self) -> f64 {
+    pub fn magnitude(&self) -> Scalar {
         (self.s * self.s
             + self.v.x() * self.v.x()
             + self.v.y() * self.v.y()
@@ -145,24 +179,287 @@ impl Quaternion {
         }
     }
 
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn dot(&self, other: &Quaternion) -> Scalar {
+        dot(self.parts(), other.parts())
+    }
+
+    /// The shortest rotation that turns one direction into another.
+    ///
+    /// Opposite directions rotate half a turn about an axis perpendicular to them.
+    pub fn from_two_vectors(from: &Vector, to: &Vector) -> Self {
+        let from = from.normalize();
+        let to = to.normalize();
+        let cos = from.dot(&to);
+        if 1.0 + cos <= Scalar::EPSILON {
+            let mut axis = Vector::default();
+            axis.perpendicular_to(&from);
+            return Self::from_axis_angle(axis, crate::scalar::consts::PI);
+        }
+        let axis = from.cross(&to);
+        Self::from_sv(1.0 + cos, axis.x(), axis.y(), axis.z()).normalize()
+    }
+
+    /// Rotation axis and angle in radians, with the angle in `[0, PI]`.
+    ///
+    /// The identity rotation returns the X axis and a zero angle.
+    pub fn to_axis_angle(&self) -> (Vector, Scalar) {
+        let [s, x, y, z] = self.normalize().parts();
+        let sign = if s < 0.0 { -1.0 } else { 1.0 };
+        let angle = 2.0 * (s * sign).clamp(-1.0, 1.0).acos();
+        let sin = (x * x + y * y + z * z).sqrt();
+        if sin < 1e-12 {
+            return (Vector::new(1.0, 0.0, 0.0), 0.0);
+        }
+        let scale = sign / sin;
+        (Vector::new(x * scale, y * scale, z * scale), angle)
+    }
+
+    /// Rotation from Euler angles in radians about the fixed world axes.
+    ///
+    /// # Arguments
+    /// * `angles` - Angles in the order the rotations are applied
+    /// * `order` - The axes of the rotations
+    pub fn from_euler(angles: [Scalar; 3], order: EulerOrder) -> Self {
+        let ([i, j, k], _) = order.axes();
+        let about = |axis: usize, angle: Scalar| {
+            let mut direction = [0.0; 3];
+            direction[axis] = 1.0;
+            let [x, y, z] = direction;
+            Quaternion::from_axis_angle(Vector::new(x, y, z), angle)
+        };
+        about(k, angles[2]) * about(j, angles[1]) * about(i, angles[0])
+    }
+
+    /// Euler angles in radians that `from_euler` turns back into this rotation.
+    ///
+    /// The middle angle is in `[-PI/2, PI/2]`. At gimbal lock, when it is a
+    /// quarter turn, the last angle is zero and the first takes the rotation.
+    pub fn to_euler(&self, order: EulerOrder) -> [Scalar; 3] {
+        let r = self.normalize().to_matrix();
+        let ([i, j, k], e) = order.axes();
+        let sin_b = (-e * r[k][i]).clamp(-1.0, 1.0);
+        let b = sin_b.asin();
+        if sin_b.abs() < 1.0 - 1e-9 {
+            let a = (e * r[k][j]).atan2(r[k][k]);
+            let c = (e * r[j][i]).atan2(r[i][i]);
+            [a, b, c]
+        } else {
+            // The middle rotation leaves row j of the matrix unchanged
+            [(-e * r[j][k]).atan2(r[j][j]), b, 0.0]
+        }
+    }
+
+    /// Spherical linear interpolation between two rotations along the shorter arc.
+    ///
+    /// # Arguments
+    /// * `a` - Rotation at `t = 0`
+    /// * `b` - Rotation at `t = 1`
+    /// * `t` - Interpolation parameter
+    pub fn slerp(a: &Quaternion, b: &Quaternion, t: Scalar) -> Quaternion {
+        let (a, mut b) = (a.normalize().parts(), b.normalize().parts());
+        if dot(a, b) < 0.0 {
+            b = b.map(|c| -c);
+        }
+        from_parts(slerp_parts(a, b, t))
+    }
+
+    /// Spherical quadrangle interpolation between two keys of a rotation sequence.
+    ///
+    /// Unlike chained `slerp`, the angular velocity is continuous across keys.
+    ///
+    /// # Arguments
+    /// * `q1` - Rotation at `t = 0`
+    /// * `a` - Tangent of `q1` from `squad_tangent`
+    /// * `b` - Tangent of `q2` from `squad_tangent`
+    /// * `q2` - Rotation at `t = 1`
+    /// * `t` - Interpolation parameter
+    pub fn squad(
+        q1: &Quaternion,
+        a: &Quaternion,
+        b: &Quaternion,
+        q2: &Quaternion,
+        t: Scalar,
+    ) -> Quaternion {
+        let [q1, a, b] = [q1, a, b].map(|q| q.normalize().parts());
+        let mut q2 = q2.normalize().parts();
+        if dot(q1, q2) < 0.0 {
+            q2 = q2.map(|c| -c);
+        }
+        let outer = slerp_parts(q1, q2, t);
+        let inner = slerp_parts(a, b, t);
+        from_parts(slerp_parts(outer, inner, 2.0 * t * (1.0 - t)))
+    }
+
+    /// Tangent of a key for `squad`, from the keys before and after it.
+    ///
+    /// At the ends of a sequence pass the key itself as the missing neighbour.
+    pub fn squad_tangent(
+        previous: &Quaternion,
+        current: &Quaternion,
+        next: &Quaternion,
+    ) -> Quaternion {
+        let current = current.normalize().parts();
+        let inverse = conjugate(current);
+        let [log_previous, log_next] = [previous, next].map(|q| {
+            let mut q = q.normalize().parts();
+            if dot(q, current) < 0.0 {
+                q = q.map(|c| -c);
+            }
+            log(mul(inverse, q))
+        });
+        let tangent = [0, 1, 2].map(|i| -(log_previous[i] + log_next[i]) * 0.25);
+        from_parts(mul(current, exp(tangent)))
+    }
+
+    /// Rotation matrix as rows, for a unit quaternion.
+    pub(crate) fn to_matrix(&self) -> [[Scalar; 3]; 3] {
+        let [s, x, y, z] = self.parts();
+        [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - s * z),
+                2.0 * (x * z + s * y),
+            ],
+            [
+                2.0 * (x * y + s * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - s * x),
+            ],
+            [
+                2.0 * (x * z - s * y),
+                2.0 * (y * z + s * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ]
+    }
+
+    /// Unit quaternion of a rotation matrix given as rows.
+    pub(crate) fn from_matrix(r: [[Scalar; 3]; 3]) -> Self {
+        // Divide by the largest of the four candidates for a stable result
+        let trace = r[0][0] + r[1][1] + r[2][2];
+        let parts = if trace > 0.0 {
+            let f = (trace + 1.0).sqrt() * 2.0;
+            [
+                0.25 * f,
+                (r[2][1] - r[1][2]) / f,
+                (r[0][2] - r[2][0]) / f,
+                (r[1][0] - r[0][1]) / f,
+            ]
+        } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+            let f = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+            [
+                (r[2][1] - r[1][2]) / f,
+                0.25 * f,
+                (r[0][1] + r[1][0]) / f,
+                (r[0][2] + r[2][0]) / f,
+            ]
+        } else if r[1][1] > r[2][2] {
+            let f = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+            [
+                (r[0][2] - r[2][0]) / f,
+                (r[0][1] + r[1][0]) / f,
+                0.25 * f,
+                (r[1][2] + r[2][1]) / f,
+            ]
+        } else {
+            let f = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+            [
+                (r[1][0] - r[0][1]) / f,
+                (r[0][2] + r[2][0]) / f,
+                (r[1][2] + r[2][1]) / f,
+                0.25 * f,
+            ]
+        };
+        from_parts(parts).normalize()
+    }
+
+    fn parts(&self) -> [Scalar; 4] {
+        [self.s, self.v.x(), self.v.y(), self.v.z()]
+    }
+
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
 
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
     }
 
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json_data = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json_data)
+        Self::jsonload(&json_data).map_err(|e| e.with_path(filepath))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Component helpers, as [s, x, y, z]
+///////////////////////////////////////////////////////////////////////////////////////////
+
+fn from_parts([s, x, y, z]: [Scalar; 4]) -> Quaternion {
+    Quaternion::from_sv(s, x, y, z)
+}
+
+fn dot(a: [Scalar; 4], b: [Scalar; 4]) -> Scalar {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3]
+}
+
+fn conjugate([s, x, y, z]: [Scalar; 4]) -> [Scalar; 4] {
+    [s, -x, -y, -z]
+}
+
+fn mul([s1, x1, y1, z1]: [Scalar; 4], [s2, x2, y2, z2]: [Scalar; 4]) -> [Scalar; 4] {
+    [
+        s1 * s2 - x1 * x2 - y1 * y2 - z1 * z2,
+        s1 * x2 + x1 * s2 + y1 * z2 - z1 * y2,
+        s1 * y2 - x1 * z2 + y1 * s2 + z1 * x2,
+        s1 * z2 + x1 * y2 - y1 * x2 + z1 * s2,
+    ]
+}
+
+/// Logarithm of a unit quaternion, the vector part of a pure quaternion.
+fn log([s, x, y, z]: [Scalar; 4]) -> [Scalar; 3] {
+    let sin = (x * x + y * y + z * z).sqrt();
+    if sin < 1e-12 {
+        return [0.0; 3];
+    }
+    let scale = sin.atan2(s) / sin;
+    [x * scale, y * scale, z * scale]
+}
+
+/// Exponential of the pure quaternion with vector part `w`.
+fn exp(w: [Scalar; 3]) -> [Scalar; 4] {
+    let angle = (w[0] * w[0] + w[1] * w[1] + w[2] * w[2]).sqrt();
+    if angle < 1e-12 {
+        return [1.0, w[0], w[1], w[2]];
     }
+    let scale = angle.sin() / angle;
+    [angle.cos(), w[0] * scale, w[1] * scale, w[2] * scale]
+}
+
+/// Slerp of unit quaternions without choosing the shorter arc.
+fn slerp_parts(a: [Scalar; 4], b: [Scalar; 4], t: Scalar) -> [Scalar; 4] {
+    let cos = dot(a, b).clamp(-1.0, 1.0);
+    // Nearly equal rotations are interpolated linearly to avoid dividing by zero
+    let (wa, wb) = if cos > 1.0 - 1e-6 {
+        (1.0 - t, t)
+    } else {
+        let angle = cos.acos();
+        let sin = angle.sin();
+        (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+    };
+    let q = [0, 1, 2, 3].map(|i| wa * a[i] + wb * b[i]);
+    let length = dot(q, q).sqrt();
+    q.map(|c| c / length)
 }
 
 impl Mul<Quaternion> for Quaternion {
@@ -173,7 +470,7 @@ impl Mul<Quaternion> for Quaternion {
         let v = rhs.v.clone() * self.s + self.v.clone() * rhs.s + self.v.cross(&rhs.v);
         Quaternion {
             typ: "Quaternion".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_quaternion".to_string(),
             s,
             v,
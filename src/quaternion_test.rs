@@ -1,10 +1,11 @@
 #[cfg(test)]
 mod quaternion_tests {
     use crate::encoders::{json_dump, json_load};
-    use crate::{Quaternion, Vector};
-    use std::f64::consts::PI;
+    use crate::scalar::consts::PI;
+    use crate::Scalar;
+    use crate::{EulerOrder, Quaternion, Vector};
 
-    fn approx_f32(a: f64, b: f64) -> bool {
+    fn approx_f32(a: Scalar, b: Scalar) -> bool {
         (a - b).abs() < 1e-5
     }
 
@@ -147,4 +148,124 @@ mod quaternion_tests {
         assert!(approx_f32(loaded.s, orig.s));
         assert!(vectors_close(&loaded.v, &orig.v));
     }
+
+    fn rotations_close(a: &Quaternion, b: &Quaternion) -> bool {
+        [Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)]
+            .into_iter()
+            .all(|v| vectors_close(&a.rotate_vector(v.clone()), &b.rotate_vector(v)))
+    }
+
+    #[test]
+    fn test_quaternion_slerp() {
+        let a = Quaternion::identity();
+        let b = Quaternion::from_axis_angle(Vector::new(0.0, 0.0, 1.0), PI / 2.0);
+        let half = Quaternion::slerp(&a, &b, 0.5);
+        let expected = Quaternion::from_axis_angle(Vector::new(0.0, 0.0, 1.0), PI / 4.0);
+        assert!(rotations_close(&half, &expected));
+        assert!(rotations_close(&Quaternion::slerp(&a, &b, 0.0), &a));
+        assert!(rotations_close(&Quaternion::slerp(&a, &b, 1.0), &b));
+
+        // The negated quaternion is the same rotation, slerp takes the short way
+        let negated = Quaternion::from_sv(-b.s, -b.v.x(), -b.v.y(), -b.v.z());
+        assert!(rotations_close(
+            &Quaternion::slerp(&a, &negated, 0.5),
+            &expected
+        ));
+        assert!(rotations_close(&Quaternion::slerp(&b, &b, 0.3), &b));
+    }
+
+    #[test]
+    fn test_quaternion_squad() {
+        let z = Vector::new(0.0, 0.0, 1.0);
+        let keys: Vec<Quaternion> = [0.0, 0.5, 1.0, 1.5]
+            .iter()
+            .map(|&angle| Quaternion::from_axis_angle(z.clone(), angle))
+            .collect();
+        let tangent = |i: usize| {
+            Quaternion::squad_tangent(&keys[i.saturating_sub(1)], &keys[i], &keys[(i + 1).min(3)])
+        };
+        let (a, b) = (tangent(1), tangent(2));
+        assert!(rotations_close(
+            &Quaternion::squad(&keys[1], &a, &b, &keys[2], 0.0),
+            &keys[1]
+        ));
+        assert!(rotations_close(
+            &Quaternion::squad(&keys[1], &a, &b, &keys[2], 1.0),
+            &keys[2]
+        ));
+        // Evenly spaced keys about one axis are interpolated at constant speed
+        let middle = Quaternion::squad(&keys[1], &a, &b, &keys[2], 0.5);
+        assert!(rotations_close(
+            &middle,
+            &Quaternion::from_axis_angle(z, 0.75)
+        ));
+    }
+
+    #[test]
+    fn test_quaternion_from_two_vectors() {
+        let from = Vector::new(1.0, 0.0, 0.0);
+        for to in [
+            Vector::new(0.0, 2.0, 0.0),
+            Vector::new(1.0, 1.0, 1.0),
+            Vector::new(-1.0, 0.0, 0.0),
+            Vector::new(3.0, 0.0, 0.0),
+        ] {
+            let q = Quaternion::from_two_vectors(&from, &to);
+            assert!(vectors_close(
+                &q.rotate_vector(from.clone()),
+                &to.normalize()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_quaternion_to_axis_angle() {
+        let axis = Vector::new(1.0, 2.0, 2.0);
+        let q = Quaternion::from_axis_angle(axis.clone(), 2.0);
+        let (result_axis, angle) = q.to_axis_angle();
+        assert!(approx_f32(angle, 2.0));
+        assert!(vectors_close(&result_axis, &axis.normalize()));
+
+        // Angles above half a turn come back as the opposite rotation
+        let (result_axis, angle) =
+            Quaternion::from_axis_angle(axis.clone(), 1.5 * PI).to_axis_angle();
+        assert!(approx_f32(angle, 0.5 * PI));
+        assert!(vectors_close(&result_axis, &(axis.normalize() * -1.0)));
+
+        let (result_axis, angle) = Quaternion::identity().to_axis_angle();
+        assert_eq!(angle, 0.0);
+        assert_eq!(result_axis.x(), 1.0);
+    }
+
+    #[test]
+    fn test_quaternion_euler() {
+        // Xyz rotates about X first: a quarter turn about X then about Z
+        let q = Quaternion::from_euler([PI / 2.0, 0.0, PI / 2.0], EulerOrder::Xyz);
+        let rotated = q.rotate_vector(Vector::new(0.0, 1.0, 0.0));
+        assert!(vectors_close(&rotated, &Vector::new(0.0, 0.0, 1.0)));
+
+        let orders = [
+            EulerOrder::Xyz,
+            EulerOrder::Xzy,
+            EulerOrder::Yxz,
+            EulerOrder::Yzx,
+            EulerOrder::Zxy,
+            EulerOrder::Zyx,
+        ];
+        for order in orders {
+            let angles = [0.3, -0.7, 1.1];
+            let q = Quaternion::from_euler(angles, order);
+            let result = q.to_euler(order);
+            for (a, b) in angles.iter().zip(result) {
+                assert!(approx_f32(*a, b), "{order:?}");
+            }
+            // At gimbal lock the angles differ but the rotation is the same
+            let locked = Quaternion::from_euler([0.4, PI / 2.0, 0.2], order);
+            let result = locked.to_euler(order);
+            assert!(
+                rotations_close(&Quaternion::from_euler(result, order), &locked),
+                "{order:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,127 @@
+use crate::session::Geometry;
+use crate::{Arrow, BoundingBox, Cylinder, Line, Mesh, Plane, Point, PointCloud, Polyline};
+
+/// Geometry types that can be stored in a Session, used for typed queries.
+pub trait GeometryType {
+    /// Get the object if the geometry holds this type.
+    fn from_geometry(geometry: &Geometry) -> Option<&Self>;
+}
+
+macro_rules! impl_geometry_type {
+    ($($variant:ident => $ty:ty),* $(,)?) => {
+        $(
+            impl GeometryType for $ty {
+                fn from_geometry(geometry: &Geometry) -> Option<&Self> {
+                    match geometry {
+                        Geometry::$variant(g) => Some(g),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_geometry_type!(
+    Arrow => Arrow,
+    BoundingBox => BoundingBox,
+    Cylinder => Cylinder,
+    Line => Line,
+    Mesh => Mesh,
+    Plane => Plane,
+    Point => Point,
+    PointCloud => PointCloud,
+    Polyline => Polyline,
+);
+
+/// Composable filter over Session objects, run with `Session::query`.
+///
+/// All set conditions must hold. Layers are read from the `"layer"` object
+/// attribute, see `Session::set_attribute`.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    type_filter: Option<fn(&Geometry) -> bool>,
+    name_pattern: Option<String>,
+    layer: Option<String>,
+    region: Option<BoundingBox>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep objects of type `T`.
+    pub fn of_type<T: GeometryType>(mut self) -> Self {
+        self.type_filter = Some(is_type::<T>);
+        self
+    }
+
+    /// Keep objects whose name matches a glob pattern with `*` and `?` wildcards.
+    pub fn name(mut self, pattern: &str) -> Self {
+        self.name_pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// Keep objects whose `"layer"` attribute equals `layer`.
+    pub fn layer(mut self, layer: &str) -> Self {
+        self.layer = Some(layer.to_string());
+        self
+    }
+
+    /// Keep objects whose bounding box overlaps a region.
+    pub fn within(mut self, region: &BoundingBox) -> Self {
+        self.region = Some(region.clone());
+        self
+    }
+
+    pub(crate) fn type_filter(&self) -> Option<fn(&Geometry) -> bool> {
+        self.type_filter
+    }
+
+    pub(crate) fn name_pattern(&self) -> Option<&str> {
+        self.name_pattern.as_deref()
+    }
+
+    pub(crate) fn layer_name(&self) -> Option<&str> {
+        self.layer.as_deref()
+    }
+
+    pub(crate) fn region(&self) -> Option<&BoundingBox> {
+        self.region.as_ref()
+    }
+}
+
+fn is_type<T: GeometryType>(geometry: &Geometry) -> bool {
+    T::from_geometry(geometry).is_some()
+}
+
+/// Match text against a glob pattern where `*` matches any run of characters
+/// and `?` matches a single character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it is matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p + 1, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+#[path = "query_test.rs"]
+mod query_test;
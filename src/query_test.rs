@@ -0,0 +1,64 @@
+use super::*;
+use crate::Scalar;
+use crate::Session;
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("beam_*", "beam_01"));
+    assert!(glob_match("beam_*", "beam_"));
+    assert!(!glob_match("beam_*", "column_01"));
+    assert!(glob_match("*_0?", "beam_01"));
+    assert!(!glob_match("*_0?", "beam_011"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("a*b*c", "aXXbYYbc"));
+    assert!(!glob_match("a*b*c", "aXXbYY"));
+    assert!(glob_match("exact", "exact"));
+    assert!(!glob_match("exact", "exactly"));
+}
+
+#[test]
+fn test_query_conditions() {
+    let mut session = Session::new("query");
+    let mut guids = Vec::new();
+    for i in 0..4 {
+        let mut line = Line::new(
+            i as Scalar * 10.0,
+            0.0,
+            0.0,
+            i as Scalar * 10.0 + 1.0,
+            0.0,
+            0.0,
+        );
+        line.name = format!("beam_{i}");
+        guids.push(line.guid.clone());
+        session.add_line(line);
+    }
+    let mut point = Point::new(0.0, 0.0, 0.0);
+    point.name = "beam_marker".to_string();
+    let point_guid = point.guid.clone();
+    session.add_point(point);
+    session.set_attribute(&guids[1], "layer", "structure".into());
+    session.set_attribute(&guids[2], "layer", "structure".into());
+    session.set_attribute(&point_guid, "layer", "structure".into());
+
+    let mut beams = guids.clone();
+    beams.sort();
+    assert_eq!(session.find_by_type::<Line>(), beams);
+    assert_eq!(session.find_by_type::<Point>(), vec![point_guid.clone()]);
+    assert!(session.find_by_type::<Mesh>().is_empty());
+    assert_eq!(session.find_by_name("beam_*").len(), 5);
+    assert_eq!(session.find_by_name("beam_?"), beams);
+
+    let query = Query::new().of_type::<Line>().layer("structure");
+    let mut expected = vec![guids[1].clone(), guids[2].clone()];
+    expected.sort();
+    assert_eq!(session.query(&query), expected);
+
+    let region = BoundingBox::from_points(
+        &[Point::new(15.0, -1.0, -1.0), Point::new(40.0, 1.0, 1.0)],
+        0.0,
+    );
+    let query = query.within(&region);
+    assert_eq!(session.query(&query), vec![guids[2].clone()]);
+    assert!(session.query(&query.name("column_*")).is_empty());
+}
@@ -0,0 +1,151 @@
+//! Floating point type of all geometry, `f64` unless the `f32` feature is enabled.
+//!
+//! File formats and foreign interfaces that store doubles keep `f64` and
+//! convert at the boundary, as do the exact predicates in `predicates`.
+
+#[cfg(not(feature = "std"))]
+use libm::Libm;
+
+/// Coordinate and length type of the geometry.
+#[cfg(not(feature = "f32"))]
+pub type Scalar = f64;
+
+/// Coordinate and length type of the geometry.
+#[cfg(feature = "f32")]
+pub type Scalar = f32;
+
+/// Mathematical constants of `Scalar` precision.
+#[cfg(not(feature = "f32"))]
+pub use core::f64::consts;
+
+/// Mathematical constants of `Scalar` precision.
+#[cfg(feature = "f32")]
+pub use core::f32::consts;
+
+/// Widen a `Scalar` to `f64`, for formats and interfaces that store doubles.
+#[inline]
+#[allow(clippy::unnecessary_cast)]
+pub fn to_f64(value: Scalar) -> f64 {
+    value as f64
+}
+
+/// Narrow a `Scalar` to `f32`, for GPU buffers.
+#[inline]
+#[allow(clippy::unnecessary_cast)]
+pub fn to_f32(value: Scalar) -> f32 {
+    value as f32
+}
+
+/// Tolerance for a test comparison: as given in `f64`, and no tighter than
+/// 1e-4 in `f32`, which resolves about seven significant digits.
+#[cfg(test)]
+pub(crate) fn test_tolerance(tolerance: Scalar) -> Scalar {
+    if cfg!(feature = "f32") {
+        tolerance.max(1e-4)
+    } else {
+        tolerance
+    }
+}
+
+/// The float methods of `std` that the geometry kernel calls, from `libm`
+/// when it is built without `std`.
+#[cfg(not(feature = "std"))]
+pub trait Float: Sized {
+    fn sqrt(self) -> Self;
+    fn cbrt(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn log10(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn trunc(self) -> Self;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+}
+
+#[cfg(not(feature = "std"))]
+macro_rules! impl_float {
+    ($float:ty) => {
+        impl Float for $float {
+            fn sqrt(self) -> Self {
+                Libm::<$float>::sqrt(self)
+            }
+            fn cbrt(self) -> Self {
+                Libm::<$float>::cbrt(self)
+            }
+            fn hypot(self, other: Self) -> Self {
+                Libm::<$float>::hypot(self, other)
+            }
+            fn powi(self, n: i32) -> Self {
+                Libm::<$float>::pow(self, n as $float)
+            }
+            fn powf(self, n: Self) -> Self {
+                Libm::<$float>::pow(self, n)
+            }
+            fn exp(self) -> Self {
+                Libm::<$float>::exp(self)
+            }
+            fn ln(self) -> Self {
+                Libm::<$float>::log(self)
+            }
+            fn log10(self) -> Self {
+                Libm::<$float>::log10(self)
+            }
+            fn sin(self) -> Self {
+                Libm::<$float>::sin(self)
+            }
+            fn cos(self) -> Self {
+                Libm::<$float>::cos(self)
+            }
+            fn tan(self) -> Self {
+                Libm::<$float>::tan(self)
+            }
+            fn asin(self) -> Self {
+                Libm::<$float>::asin(self)
+            }
+            fn acos(self) -> Self {
+                Libm::<$float>::acos(self)
+            }
+            fn atan(self) -> Self {
+                Libm::<$float>::atan(self)
+            }
+            fn atan2(self, other: Self) -> Self {
+                Libm::<$float>::atan2(self, other)
+            }
+            fn sin_cos(self) -> (Self, Self) {
+                Libm::<$float>::sincos(self)
+            }
+            fn floor(self) -> Self {
+                Libm::<$float>::floor(self)
+            }
+            fn ceil(self) -> Self {
+                Libm::<$float>::ceil(self)
+            }
+            fn round(self) -> Self {
+                Libm::<$float>::round(self)
+            }
+            fn trunc(self) -> Self {
+                Libm::<$float>::trunc(self)
+            }
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                Libm::<$float>::fma(self, a, b)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "std"))]
+impl_float!(f32);
+#[cfg(not(feature = "std"))]
+impl_float!(f64);
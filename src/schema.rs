@@ -0,0 +1,585 @@
+//! JSON Schema documents describing the JSON written by `jsondump`.
+//!
+//! The schemas follow the serializers of this crate, so the Python and C++
+//! implementations can validate their files against them. Every document is
+//! self-contained: it references its type in `$defs`, which holds all types.
+
+use crate::error::SessionError;
+use crate::Session;
+use serde_json::{json, Map, Value};
+
+/// JSON Schema dialect of the generated documents.
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Types with a schema document, in the order `write_schemas` writes them.
+pub const SCHEMA_TYPES: [&str; 20] = [
+    "Session",
+    "Objects",
+    "Point",
+    "Vector",
+    "Color",
+    "Xform",
+    "Line",
+    "Plane",
+    "BoundingBox",
+    "Polyline",
+    "PointCloud",
+    "Mesh",
+    "Cylinder",
+    "Arrow",
+    "Quaternion",
+    "Tree",
+    "TreeNode",
+    "Graph",
+    "Vertex",
+    "Edge",
+];
+
+/// The JSON Schema document of a serialized type.
+///
+/// # Arguments
+/// * `type_name` - One of `SCHEMA_TYPES`, as written in the `type` field
+///
+/// # Returns
+/// The schema, or None for unknown type names.
+pub fn schema(type_name: &str) -> Option<Value> {
+    if !SCHEMA_TYPES.contains(&type_name) {
+        return None;
+    }
+    Some(json!({
+        "$schema": SCHEMA_DIALECT,
+        "$id": format!("urn:session_rust:schema:{type_name}"),
+        "title": type_name,
+        "$ref": format!("#/$defs/{type_name}"),
+        "$defs": definitions(),
+    }))
+}
+
+/// Write the schema of every type in `SCHEMA_TYPES` to `<directory>/<Type>.schema.json`.
+///
+/// # Arguments
+/// * `directory` - The output directory, created if missing
+///
+/// # Returns
+/// The paths of the written files.
+pub fn write_schemas(directory: &str) -> Result<Vec<String>, SessionError> {
+    std::fs::create_dir_all(directory)?;
+    let mut paths = Vec::new();
+    for type_name in SCHEMA_TYPES {
+        let path = std::path::Path::new(directory)
+            .join(format!("{type_name}.schema.json"))
+            .to_string_lossy()
+            .into_owned();
+        let document = schema(type_name).unwrap_or_default();
+        std::fs::write(&path, serde_json::to_string_pretty(&document)?)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn reference(type_name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{type_name}") })
+}
+
+fn number() -> Value {
+    json!({ "type": "number" })
+}
+
+fn integer(minimum: i64, maximum: i64) -> Value {
+    json!({ "type": "integer", "minimum": minimum, "maximum": maximum })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+fn map(values: Value) -> Value {
+    json!({ "type": "object", "additionalProperties": values })
+}
+
+/// A closed object whose properties are all required except `optional`.
+///
+/// A `type_tag` adds the `type` property holding that constant.
+fn object(type_tag: Option<&str>, properties: Vec<(&str, Value)>, optional: &[&str]) -> Value {
+    let mut fields = Map::new();
+    if let Some(tag) = type_tag {
+        fields.insert("type".to_string(), json!({ "const": tag }));
+    }
+    for (name, value) in properties {
+        fields.insert(name.to_string(), value);
+    }
+    let required: Vec<&String> = fields
+        .keys()
+        .filter(|name| !optional.contains(&name.as_str()))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": fields,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// The schemas of all types, keyed by type name.
+fn definitions() -> Value {
+    let guid = || ("guid", string());
+    let name = || ("name", string());
+    let xform = || ("xform", reference("Xform"));
+    // Mesh vertex and face keys are integers written as object keys
+    let by_key = |values: Value| {
+        json!({
+            "type": "object",
+            "propertyNames": { "pattern": "^[0-9]+$" },
+            "additionalProperties": values,
+        })
+    };
+    let rgb_flat = || array(integer(0, 255));
+
+    let definitions = [
+        (
+            "Color",
+            object(
+                Some("Color"),
+                vec![
+                    guid(),
+                    name(),
+                    ("r", integer(0, 255)),
+                    ("g", integer(0, 255)),
+                    ("b", integer(0, 255)),
+                    ("a", integer(0, 255)),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "Xform",
+            object(
+                Some("Xform"),
+                vec![
+                    guid(),
+                    name(),
+                    (
+                        "m",
+                        json!({ "type": "array", "items": number(), "minItems": 16, "maxItems": 16 }),
+                    ),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "Point",
+            object(
+                Some("Point"),
+                vec![
+                    guid(),
+                    name(),
+                    ("x", number()),
+                    ("y", number()),
+                    ("z", number()),
+                    ("width", number()),
+                    ("pointcolor", reference("Color")),
+                    xform(),
+                ],
+                &["xform"],
+            ),
+        ),
+        (
+            "Vector",
+            object(
+                Some("Vector"),
+                vec![
+                    guid(),
+                    name(),
+                    ("x", number()),
+                    ("y", number()),
+                    ("z", number()),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "Line",
+            object(
+                Some("Line"),
+                vec![
+                    guid(),
+                    name(),
+                    ("x0", number()),
+                    ("y0", number()),
+                    ("z0", number()),
+                    ("x1", number()),
+                    ("y1", number()),
+                    ("z1", number()),
+                    ("width", number()),
+                    ("linecolor", reference("Color")),
+                    xform(),
+                ],
+                &["xform"],
+            ),
+        ),
+        (
+            "Plane",
+            object(
+                Some("Plane"),
+                vec![
+                    guid(),
+                    name(),
+                    ("origin", reference("Point")),
+                    ("x_axis", reference("Vector")),
+                    ("y_axis", reference("Vector")),
+                    ("z_axis", reference("Vector")),
+                    ("a", number()),
+                    ("b", number()),
+                    ("c", number()),
+                    ("d", number()),
+                    xform(),
+                ],
+                &["xform"],
+            ),
+        ),
+        (
+            "BoundingBox",
+            object(
+                Some("BoundingBox"),
+                vec![
+                    ("center", reference("Point")),
+                    ("x_axis", reference("Vector")),
+                    ("y_axis", reference("Vector")),
+                    ("z_axis", reference("Vector")),
+                    ("half_size", reference("Vector")),
+                    guid(),
+                    name(),
+                    xform(),
+                ],
+                &["xform"],
+            ),
+        ),
+        (
+            "Polyline",
+            object(
+                Some("Polyline"),
+                vec![
+                    guid(),
+                    name(),
+                    ("points", array(reference("Point"))),
+                    ("plane", reference("Plane")),
+                    ("width", number()),
+                    ("linecolor", reference("Color")),
+                    xform(),
+                ],
+                &["xform"],
+            ),
+        ),
+        (
+            "PointCloud",
+            object(
+                Some("PointCloud"),
+                vec![
+                    guid(),
+                    name(),
+                    ("points", array(number())),
+                    ("normals", array(number())),
+                    ("colors", rgb_flat()),
+                    ("intensities", array(number())),
+                    xform(),
+                ],
+                &["intensities", "xform"],
+            ),
+        ),
+        (
+            // Sessions write meshes with their xform, `Mesh::jsondump` with flat colors
+            "Mesh",
+            object(
+                Some("Mesh"),
+                vec![
+                    guid(),
+                    name(),
+                    (
+                        "halfedge",
+                        by_key(by_key(json!({ "type": ["integer", "null"], "minimum": 0 }))),
+                    ),
+                    (
+                        "vertex",
+                        by_key(object(
+                            None,
+                            vec![
+                                ("x", number()),
+                                ("y", number()),
+                                ("z", number()),
+                                ("attributes", map(number())),
+                            ],
+                            &[],
+                        )),
+                    ),
+                    ("face", by_key(array(integer(0, i64::MAX)))),
+                    ("facedata", by_key(map(number()))),
+                    ("edgedata", map(map(number()))),
+                    ("default_vertex_attributes", map(number())),
+                    ("default_face_attributes", map(number())),
+                    ("default_edge_attributes", map(number())),
+                    ("max_vertex", integer(0, i64::MAX)),
+                    ("max_face", integer(0, i64::MAX)),
+                    ("pointcolors", rgb_flat()),
+                    ("facecolors", rgb_flat()),
+                    ("linecolors", rgb_flat()),
+                    ("widths", array(number())),
+                    xform(),
+                ],
+                &["pointcolors", "facecolors", "linecolors", "widths", "xform"],
+            ),
+        ),
+        (
+            "Cylinder",
+            object(
+                Some("Cylinder"),
+                vec![
+                    guid(),
+                    name(),
+                    ("radius", number()),
+                    ("line", reference("Line")),
+                    ("mesh", reference("Mesh")),
+                    xform(),
+                ],
+                &["xform"],
+            ),
+        ),
+        (
+            "Arrow",
+            object(
+                Some("Arrow"),
+                vec![
+                    ("line", reference("Line")),
+                    ("mesh", reference("Mesh")),
+                    ("radius", number()),
+                    guid(),
+                    name(),
+                    xform(),
+                ],
+                &["xform"],
+            ),
+        ),
+        (
+            "Objects",
+            object(
+                Some("Objects"),
+                vec![
+                    guid(),
+                    name(),
+                    ("points", array(reference("Point"))),
+                    ("lines", array(reference("Line"))),
+                    ("planes", array(reference("Plane"))),
+                    ("bboxes", array(reference("BoundingBox"))),
+                    ("polylines", array(reference("Polyline"))),
+                    ("pointclouds", array(reference("PointCloud"))),
+                    ("meshes", array(reference("Mesh"))),
+                    ("cylinders", array(reference("Cylinder"))),
+                    ("arrows", array(reference("Arrow"))),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "Quaternion",
+            object(
+                Some("Quaternion"),
+                vec![
+                    guid(),
+                    name(),
+                    ("s", number()),
+                    ("x", number()),
+                    ("y", number()),
+                    ("z", number()),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "TreeNode",
+            object(
+                Some("TreeNode"),
+                vec![
+                    guid(),
+                    name(),
+                    xform(),
+                    ("attributes", map(reference("AttrValue"))),
+                    ("children", array(reference("TreeNode"))),
+                ],
+                &["xform", "attributes"],
+            ),
+        ),
+        (
+            "Tree",
+            object(
+                Some("Tree"),
+                vec![
+                    guid(),
+                    name(),
+                    (
+                        "root",
+                        json!({ "anyOf": [reference("TreeNode"), { "type": "null" }] }),
+                    ),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "Vertex",
+            object(
+                Some("Vertex"),
+                vec![
+                    guid(),
+                    name(),
+                    ("attribute", string()),
+                    ("index", integer(i32::MIN as i64, i32::MAX as i64)),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "Edge",
+            object(
+                Some("Edge"),
+                vec![
+                    guid(),
+                    name(),
+                    ("v0", string()),
+                    ("v1", string()),
+                    ("attribute", string()),
+                    ("index", integer(i32::MIN as i64, i32::MAX as i64)),
+                ],
+                &[],
+            ),
+        ),
+        (
+            // The layout of `Graph::jsondump`, not the keyed maps of `encoders::json_dump`
+            "Graph",
+            object(
+                Some("Graph"),
+                vec![
+                    guid(),
+                    name(),
+                    ("vertices", array(reference("Vertex"))),
+                    ("edges", array(reference("Edge"))),
+                    ("vertex_count", integer(0, i32::MAX as i64)),
+                    ("edge_count", integer(0, i32::MAX as i64)),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "AttrValue",
+            json!({
+                "anyOf": [boolean(), number(), string(), array(reference("AttrValue"))]
+            }),
+        ),
+        (
+            "CollisionFilter",
+            object(
+                None,
+                vec![
+                    ("group", integer(0, u32::MAX as i64)),
+                    ("mask", integer(0, u32::MAX as i64)),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "Group",
+            object(
+                None,
+                vec![
+                    guid(),
+                    name(),
+                    ("members", array(string())),
+                    xform(),
+                    ("visible", boolean()),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "ExternalLink",
+            object(None, vec![guid(), ("path", string()), xform()], &[]),
+        ),
+        (
+            "DisplayState",
+            object(
+                None,
+                vec![
+                    ("visible", boolean()),
+                    ("selected", boolean()),
+                    (
+                        "highlight_color",
+                        json!({ "anyOf": [reference("Color"), { "type": "null" }] }),
+                    ),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "AnimationTrack",
+            object(
+                None,
+                vec![(
+                    "keyframes",
+                    array(json!({
+                        "type": "array",
+                        "prefixItems": [number(), reference("Xform")],
+                        "minItems": 2,
+                        "maxItems": 2,
+                    })),
+                )],
+                &[],
+            ),
+        ),
+        (
+            "Session",
+            object(
+                Some("Session"),
+                vec![
+                    ("version", integer(0, Session::SCHEMA_VERSION as i64)),
+                    guid(),
+                    name(),
+                    ("objects", reference("Objects")),
+                    ("tree", reference("Tree")),
+                    ("graph", reference("Graph")),
+                    ("attributes", map(map(reference("AttrValue")))),
+                    ("collision_filters", map(reference("CollisionFilter"))),
+                    ("groups", map(reference("Group"))),
+                    ("links", array(reference("ExternalLink"))),
+                    ("display", map(reference("DisplayState"))),
+                    ("animations", map(reference("AnimationTrack"))),
+                ],
+                // Version 0 files have no version and may lack the other sections,
+                // version 1 files have no animations
+                &[
+                    "version",
+                    "attributes",
+                    "collision_filters",
+                    "groups",
+                    "links",
+                    "display",
+                    "animations",
+                ],
+            ),
+        ),
+    ];
+    Value::Object(
+        definitions
+            .into_iter()
+            .map(|(name, schema)| (name.to_string(), schema))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+#[path = "schema_test.rs"]
+mod schema_test;
@@ -0,0 +1,282 @@
+use super::*;
+use crate::{
+    AnimationTrack, AttrValue, Color, Line, Mesh, Objects, Plane, Point, PointCloud, Polyline,
+    Quaternion, Tree, TreeNode, Vector, Xform,
+};
+
+/// Check a value against the subset of JSON Schema used by this module.
+fn validate(value: &Value, schema: &Value, root: &Value, path: &str) -> Result<(), String> {
+    if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        let name = target.trim_start_matches("#/$defs/");
+        return validate(value, &root["$defs"][name], root, path);
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            return Err(format!("{path}: expected {expected}, found {value}"));
+        }
+    }
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        if !options
+            .iter()
+            .any(|option| validate(value, option, root, path).is_ok())
+        {
+            return Err(format!("{path}: no alternative matches {value}"));
+        }
+    }
+    if let Some(kind) = schema.get("type") {
+        let kinds: Vec<&str> = match kind {
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            kind => vec![kind.as_str().unwrap()],
+        };
+        let matches = |kind: &str| match kind {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "boolean" => value.is_boolean(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "null" => value.is_null(),
+            _ => false,
+        };
+        if !kinds.into_iter().any(matches) {
+            return Err(format!("{path}: {value} is not of type {kind}"));
+        }
+    }
+    if let Some(number) = value.as_f64() {
+        let minimum = schema.get("minimum").and_then(Value::as_f64);
+        let maximum = schema.get("maximum").and_then(Value::as_f64);
+        if minimum.is_some_and(|m| number < m) || maximum.is_some_and(|m| number > m) {
+            return Err(format!("{path}: {number} is out of range"));
+        }
+    }
+    if let Some(items) = value.as_array() {
+        let length_is = |key: &str, fits: fn(usize, u64) -> bool| {
+            schema
+                .get(key)
+                .and_then(Value::as_u64)
+                .is_none_or(|limit| fits(items.len(), limit))
+        };
+        if !length_is("minItems", |n, l| n as u64 >= l)
+            || !length_is("maxItems", |n, l| n as u64 <= l)
+        {
+            return Err(format!("{path}: wrong number of items"));
+        }
+        for (i, (value, item)) in items
+            .iter()
+            .zip(
+                schema
+                    .get("prefixItems")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten(),
+            )
+            .enumerate()
+        {
+            validate(value, item, root, &format!("{path}/{i}"))?;
+        }
+        if let Some(item) = schema.get("items") {
+            for (i, value) in items.iter().enumerate() {
+                validate(value, item, root, &format!("{path}/{i}"))?;
+            }
+        }
+    }
+    if let Some(fields) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let required = required.as_str().unwrap();
+            if !fields.contains_key(required) {
+                return Err(format!("{path}: missing {required}"));
+            }
+        }
+        for (key, field) in fields {
+            let field_path = format!("{path}/{key}");
+            if let Some(pattern) = schema.pointer("/propertyNames/pattern") {
+                assert_eq!(pattern, "^[0-9]+$");
+                if !key.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(format!("{field_path}: key is not an integer"));
+                }
+            }
+            match (
+                properties.and_then(|p| p.get(key)),
+                schema.get("additionalProperties"),
+            ) {
+                (Some(property), _) => validate(field, property, root, &field_path)?,
+                (None, Some(Value::Bool(false))) => {
+                    return Err(format!("{field_path}: unexpected property"))
+                }
+                (None, Some(additional)) => validate(field, additional, root, &field_path)?,
+                (None, None) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check(type_name: &str, json: &str) -> Result<(), String> {
+    let document = schema(type_name).unwrap();
+    let value: Value = serde_json::from_str(json).unwrap();
+    validate(&value, &document, &document, "")
+}
+
+fn quad() -> Mesh {
+    Mesh::from_polygons(
+        vec![vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        ]],
+        None,
+    )
+}
+
+#[test]
+fn test_schema_documents() {
+    for type_name in SCHEMA_TYPES {
+        let document = schema(type_name).unwrap();
+        assert_eq!(document["$schema"], SCHEMA_DIALECT);
+        assert_eq!(document["title"], type_name);
+        assert!(document["$defs"][type_name].is_object());
+
+        // Every reference resolves to a definition
+        let text = document.to_string();
+        for target in text.split("\"#/$defs/").skip(1) {
+            let name = &target[..target.find('"').unwrap()];
+            assert!(document["$defs"][name].is_object(), "{name}");
+        }
+    }
+    assert!(schema("NurbsCurve").is_none());
+    assert_eq!(schema("Point").unwrap()["$ref"], "#/$defs/Point");
+}
+
+#[test]
+fn test_schema_matches_geometry_json() {
+    let point = Point::new(1.0, 2.0, 3.0);
+    let plane = Plane::default();
+    let mut cloud = PointCloud::new(
+        vec![Point::new(0.0, 0.0, 0.0)],
+        vec![Vector::new(0.0, 0.0, 1.0)],
+        vec![Color::new(255, 0, 0, 255)],
+    );
+    check("Point", &point.jsondump().unwrap()).unwrap();
+    check("Vector", &Vector::new(0.0, 1.0, 0.0).jsondump().unwrap()).unwrap();
+    check("Color", &Color::new(1, 2, 3, 4).jsondump().unwrap()).unwrap();
+    check(
+        "Xform",
+        &Xform::translation(1.0, 2.0, 3.0).jsondump().unwrap(),
+    )
+    .unwrap();
+    check(
+        "Line",
+        &Line::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0).jsondump().unwrap(),
+    )
+    .unwrap();
+    check("Plane", &plane.jsondump().unwrap()).unwrap();
+    let polyline = Polyline::new(vec![point.clone(), Point::new(4.0, 5.0, 6.0)]);
+    check("Polyline", &polyline.jsondump().unwrap()).unwrap();
+    check("PointCloud", &cloud.jsondump().unwrap()).unwrap();
+    cloud.intensities = vec![0.5];
+    check("PointCloud", &cloud.jsondump().unwrap()).unwrap();
+    check("Quaternion", &Quaternion::identity().jsondump().unwrap()).unwrap();
+    check("Mesh", &quad().jsondump().to_string()).unwrap();
+    check("Mesh", &serde_json::to_string(&quad()).unwrap()).unwrap();
+
+    let mut objects = Objects::new();
+    objects.points.push(point);
+    objects.meshes.push(quad());
+    check("Objects", &objects.jsondump().unwrap()).unwrap();
+}
+
+#[test]
+fn test_schema_matches_session_json() {
+    let mut session = Session::new("schema");
+    let a = session.add_point(Point::new(0.0, 0.0, 0.0)).name();
+    let b = session.add_mesh(quad()).name();
+    session.add_edge(&a, &b, "supports");
+    session.set_attribute(&a, "material", AttrValue::from("oak"));
+    session.set_attribute(&a, "tags", AttrValue::Array(vec![1.0.into(), true.into()]));
+    session.set_collision_filter(&b, 2, 1);
+    session.add_group("pair", &[a.clone(), b.clone()]);
+    session.set_visible(&a, false);
+    session.set_highlight_color(&b, Some(Color::new(255, 0, 0, 255)));
+    let mut track = AnimationTrack::new();
+    track.add_keyframe(0.0, Xform::identity());
+    track.add_keyframe(2.5, Xform::translation(1.0, 0.0, 0.0));
+    session.set_animation(&a, track);
+    let json = session.jsondump().unwrap();
+    check("Session", &json).unwrap();
+    check("Graph", &session.graph.jsondump().unwrap()).unwrap();
+
+    let mut tree = Tree::new("tree");
+    let root = TreeNode::new("root");
+    let child = TreeNode::new("child");
+    child.set_xform(Some(Xform::translation(1.0, 0.0, 0.0)));
+    child.set_attribute("layer", AttrValue::from("walls"));
+    root.add(&child);
+    tree.add(&root, None);
+    check("Tree", &tree.jsondump().unwrap()).unwrap();
+    check("Tree", &Tree::new("empty").jsondump().unwrap()).unwrap();
+}
+
+#[test]
+fn test_schema_rejects_invalid_json() {
+    let point: Value =
+        serde_json::from_str(&Point::new(1.0, 2.0, 3.0).jsondump().unwrap()).unwrap();
+    let with = |edit: fn(&mut Value)| {
+        let mut value = point.clone();
+        edit(&mut value);
+        check("Point", &value.to_string())
+    };
+
+    assert!(with(|p| p["type"] = "Vector".into())
+        .unwrap_err()
+        .contains("expected"));
+    assert!(with(|p| {
+        p.as_object_mut().unwrap().remove("x");
+    })
+    .unwrap_err()
+    .contains("missing x"));
+    assert!(with(|p| p["x"] = "1".into()).is_err());
+    assert!(with(|p| p["pointcolor"]["r"] = 256.into())
+        .unwrap_err()
+        .contains("out of range"));
+    assert!(with(|p| p["extra"] = 1.into())
+        .unwrap_err()
+        .contains("unexpected"));
+    assert!(with(|p| p["xform"]["m"] = serde_json::json!([1.0])).is_err());
+    // Points written without an xform are accepted
+    assert!(with(|p| {
+        p.as_object_mut().unwrap().remove("xform");
+    })
+    .is_ok());
+
+    let mut session: Value = serde_json::from_str(&Session::new("s").jsondump().unwrap()).unwrap();
+    check("Session", &session.to_string()).unwrap();
+    session["version"] = (Session::SCHEMA_VERSION + 1).into();
+    assert!(check("Session", &session.to_string())
+        .unwrap_err()
+        .contains("/version"));
+    session["version"] = 0.into();
+    session["attributes"] = serde_json::json!({ "guid": { "nested": { "a": 1 } } });
+    assert!(check("Session", &session.to_string())
+        .unwrap_err()
+        .contains("/attributes/guid/nested"));
+}
+
+#[test]
+fn test_write_schemas() {
+    let directory = std::env::temp_dir().join(format!("schemas_{}", uuid::Uuid::new_v4()));
+    let paths = write_schemas(directory.to_str().unwrap()).unwrap();
+    assert_eq!(paths.len(), SCHEMA_TYPES.len());
+    let session: Value = serde_json::from_str(
+        &std::fs::read_to_string(directory.join("Session.schema.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(session, schema("Session").unwrap());
+    std::fs::remove_dir_all(&directory).ok();
+}
@@ -1,11 +1,25 @@
+use crate::collision::{contact_between, narrow_phase, ConvexShape};
+use crate::error::SessionError;
+use crate::graph::NodeEntry;
+use crate::guid::IdSource;
+use crate::query::glob_match;
 use crate::{
-    Arrow, BoundingBox, Cylinder, Graph, Line, Mesh, Objects, Plane, Point, PointCloud, Polyline,
-    Tolerance, Tree, TreeNode, BVH,
+    AnimationTrack, Arrow, AsVec3, BoundingBox, CollisionFilter, CollisionOptions, Color, Contact,
+    Cylinder, GeometryType, Graph, IdGenerator, Line, Mesh, Objects, Octree, Plane, Point,
+    PointCloud, Polyline, Query, Scalar, SpatialHash, Tolerance, Tree, TreeNode, Vec3, Vector,
+    Xform, BVH,
 };
+use once_cell::unsync::OnceCell;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::ops::Range;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Enum representing all possible geometry types in a Session.
@@ -23,6 +37,44 @@ pub enum Geometry {
     Polyline(Polyline),
 }
 
+/// Serialized as the wrapped object, whose `type` field selects the variant.
+impl Serialize for Geometry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Geometry::Arrow(g) => g.serialize(serializer),
+            Geometry::BoundingBox(g) => g.serialize(serializer),
+            Geometry::Cylinder(g) => g.serialize(serializer),
+            Geometry::Line(g) => g.serialize(serializer),
+            Geometry::Mesh(g) => g.serialize(serializer),
+            Geometry::Plane(g) => g.serialize(serializer),
+            Geometry::Point(g) => g.serialize(serializer),
+            Geometry::PointCloud(g) => g.serialize(serializer),
+            Geometry::Polyline(g) => g.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Geometry {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let kind = value["type"].as_str().unwrap_or_default().to_string();
+        match kind.as_str() {
+            "Arrow" => serde_json::from_value(value).map(Geometry::Arrow),
+            "BoundingBox" => serde_json::from_value(value).map(Geometry::BoundingBox),
+            "Cylinder" => serde_json::from_value(value).map(Geometry::Cylinder),
+            "Line" => serde_json::from_value(value).map(Geometry::Line),
+            "Mesh" => serde_json::from_value(value).map(Geometry::Mesh),
+            "Plane" => serde_json::from_value(value).map(Geometry::Plane),
+            "Point" => serde_json::from_value(value).map(Geometry::Point),
+            "PointCloud" => serde_json::from_value(value).map(Geometry::PointCloud),
+            "Polyline" => serde_json::from_value(value).map(Geometry::Polyline),
+            _ => return Err(D::Error::custom(format!("unknown geometry type '{kind}'"))),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
 impl Geometry {
     /// Get the GUID of the geometry object
     pub fn guid(&self) -> &str {
@@ -38,6 +90,110 @@ impl Geometry {
             Geometry::Polyline(g) => &g.guid,
         }
     }
+
+    fn guid_mut(&mut self) -> &mut String {
+        match self {
+            Geometry::Arrow(g) => &mut g.guid,
+            Geometry::BoundingBox(g) => &mut g.guid,
+            Geometry::Cylinder(g) => &mut g.guid,
+            Geometry::Line(g) => &mut g.guid,
+            Geometry::Mesh(g) => &mut g.guid,
+            Geometry::Plane(g) => &mut g.guid,
+            Geometry::Point(g) => &mut g.guid,
+            Geometry::PointCloud(g) => &mut g.guid,
+            Geometry::Polyline(g) => &mut g.guid,
+        }
+    }
+
+    /// Get the transformation of the geometry object
+    pub fn xform(&self) -> &Xform {
+        match self {
+            Geometry::Arrow(g) => &g.xform,
+            Geometry::BoundingBox(g) => &g.xform,
+            Geometry::Cylinder(g) => &g.xform,
+            Geometry::Line(g) => &g.xform,
+            Geometry::Mesh(g) => &g.xform,
+            Geometry::Plane(g) => &g.xform,
+            Geometry::Point(g) => &g.xform,
+            Geometry::PointCloud(g) => &g.xform,
+            Geometry::Polyline(g) => &g.xform,
+        }
+    }
+
+    /// Get the transformation of the geometry object for editing
+    pub fn xform_mut(&mut self) -> &mut Xform {
+        match self {
+            Geometry::Arrow(g) => &mut g.xform,
+            Geometry::BoundingBox(g) => &mut g.xform,
+            Geometry::Cylinder(g) => &mut g.xform,
+            Geometry::Line(g) => &mut g.xform,
+            Geometry::Mesh(g) => &mut g.xform,
+            Geometry::Plane(g) => &mut g.xform,
+            Geometry::Point(g) => &mut g.xform,
+            Geometry::PointCloud(g) => &mut g.xform,
+            Geometry::Polyline(g) => &mut g.xform,
+        }
+    }
+
+    /// Apply the xform to the geometry and reset it to identity
+    pub fn transform(&mut self) {
+        match self {
+            Geometry::Arrow(g) => g.transform(),
+            Geometry::BoundingBox(g) => g.transform(),
+            Geometry::Cylinder(g) => g.transform(),
+            Geometry::Line(g) => g.transform(),
+            Geometry::Mesh(g) => g.transform(),
+            Geometry::Plane(g) => g.transform(),
+            Geometry::Point(g) => g.transform(),
+            Geometry::PointCloud(g) => g.transform(),
+            Geometry::Polyline(g) => g.transform(),
+        }
+    }
+
+    /// Copy of the geometry with its xform applied
+    pub fn transformed(&self) -> Geometry {
+        match self {
+            Geometry::Arrow(g) => Geometry::Arrow(g.transformed()),
+            Geometry::BoundingBox(g) => Geometry::BoundingBox(g.transformed()),
+            Geometry::Cylinder(g) => Geometry::Cylinder(g.transformed()),
+            Geometry::Line(g) => Geometry::Line(g.transformed()),
+            Geometry::Mesh(g) => Geometry::Mesh(g.transformed()),
+            Geometry::Plane(g) => Geometry::Plane(g.transformed()),
+            Geometry::Point(g) => Geometry::Point(g.transformed()),
+            Geometry::PointCloud(g) => Geometry::PointCloud(g.transformed()),
+            Geometry::Polyline(g) => Geometry::Polyline(g.transformed()),
+        }
+    }
+
+    /// Get the name of the geometry object
+    pub fn name(&self) -> &str {
+        match self {
+            Geometry::Arrow(g) => &g.name,
+            Geometry::BoundingBox(g) => &g.name,
+            Geometry::Cylinder(g) => &g.name,
+            Geometry::Line(g) => &g.name,
+            Geometry::Mesh(g) => &g.name,
+            Geometry::Plane(g) => &g.name,
+            Geometry::Point(g) => &g.name,
+            Geometry::PointCloud(g) => &g.name,
+            Geometry::Polyline(g) => &g.name,
+        }
+    }
+
+    /// Get the type name of the geometry object, as written in its JSON `type` field
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Geometry::Arrow(_) => "Arrow",
+            Geometry::BoundingBox(_) => "BoundingBox",
+            Geometry::Cylinder(_) => "Cylinder",
+            Geometry::Line(_) => "Line",
+            Geometry::Mesh(_) => "Mesh",
+            Geometry::Plane(_) => "Plane",
+            Geometry::Point(_) => "Point",
+            Geometry::PointCloud(_) => "PointCloud",
+            Geometry::Polyline(_) => "Polyline",
+        }
+    }
 }
 
 /// A Session containing geometry objects with hierarchical and graph structures.
@@ -45,47 +201,754 @@ impl Geometry {
 /// The Session serves as a container for managing geometry objects (currently Points)
 /// along with their relationships through tree and graph data structures. It provides
 /// JSON serialization capabilities for cross-language interoperability.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename = "Session")]
+#[derive(Debug, Clone)]
 pub struct Session {
     /// Unique identifier for the session
     pub guid: String,
     /// Human-readable name for the session
     pub name: String,
-    /// Collection of geometry objects (Points)
-    #[serde(rename = "objects")]
-    pub objects: Objects,
-    /// Lookup table mapping object GUIDs to geometry objects (fast heterogeneous lookup)
-    #[serde(skip)]
-    pub lookup: HashMap<String, Geometry>,
+    /// Lookup table mapping object GUIDs to geometry objects, the only copy of each object.
+    ///
+    /// Objects are shared with snapshots and the undo history, so they are
+    /// copied only when edited while a snapshot still refers to them.
+    pub lookup: HashMap<String, Arc<Geometry>>,
+    /// Insertion order of the objects, their order in the saved `objects` collection
+    order: ObjectOrder,
     /// Hierarchical tree structure for organizing objects
-    #[serde(rename = "tree")]
     pub tree: Tree,
     /// Graph structure for representing object relationships
-    #[serde(rename = "graph")]
     pub graph: Graph,
+    /// User attributes per object GUID, e.g. material or fabrication ids
+    pub attributes: HashMap<String, HashMap<String, AttrValue>>,
+    /// Collision group and mask per object GUID, objects without one use the default
+    pub collision_filters: HashMap<String, CollisionFilter>,
+    /// Groups by GUID, see `add_group`
+    pub groups: HashMap<String, Group>,
+    /// Session files composited into `get_geometry`, see `link_external`
+    pub links: Vec<ExternalLink>,
+    /// Viewer state per object GUID, objects without one are visible and not selected
+    pub display: HashMap<String, DisplayState>,
+    /// Keyframed poses per object GUID, see `set_animation`
+    pub animations: HashMap<String, AnimationTrack>,
     /// Boundary Volume Hierarchy for spatial collision detection
-    #[serde(skip)]
     pub bvh: BVH,
     /// Cached BVH for ray casting (indices map to cached_guids)
-    #[serde(skip)]
     pub cached_ray_bvh: Option<BVH>,
-    /// Cached GUIDs corresponding to cached_boxes order
-    #[serde(skip)]
+    /// Cached GUIDs corresponding to cached_boxes order, repeated for each chunk
+    /// leaf of a point cloud
     pub cached_guids: Vec<String>,
+    /// Point range of each leaf covering one chunk of a point cloud, None for whole objects
+    cached_chunks: Vec<Option<Range<usize>>>,
+    /// Leaf indices of each GUID in cached_guids
+    cached_index: HashMap<String, Vec<usize>>,
     /// Cached AABBs for ray-casting BVH
-    #[serde(skip)]
     pub cached_boxes: Vec<BoundingBox>,
     /// Dirty flag for cached ray BVH
-    #[serde(skip)]
     pub bvh_cache_dirty: bool,
+    /// Broad phase used for collision detection
+    pub broad_phase: BroadPhase,
+    /// What `remove_object` does with the tree children of a removed object
+    pub removal_policy: RemovalPolicy,
+    /// State captured by `begin_transaction`, None outside a transaction
+    transaction: Option<SessionState>,
+    /// Changes of each committed transaction, most recent last
+    undo_stack: Vec<UndoStep>,
+    /// Changes of each undone transaction, most recent last
+    redo_stack: Vec<UndoStep>,
+    /// Callbacks registered with `on_change`
+    listeners: SessionListeners,
+    /// Operation log opened with `open_log`
+    log: SessionLog,
+    /// Creates the GUIDs of everything the session adds, see `with_id_generator`
+    ids: IdSource,
+}
+
+/// Serialized in the same layout as `jsondump`.
+impl Serialize for Session {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+        (self.json_value().map_err(S::Error::custom)?).serialize(serializer)
+    }
+}
+
+/// Deserialized like `jsonload`, including the upgrade of older versions.
+impl<'de> Deserialize<'de> for Session {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Session::from_json_value(value).map_err(D::Error::custom)
+    }
+}
+
+/// Checkpoint of the editable Session contents, see `Session::snapshot`.
+///
+/// Objects are shared with the Session, cloning a state does not copy them.
+#[derive(Debug, Clone)]
+pub struct SessionState {
+    order: ObjectOrder,
+    lookup: HashMap<String, Arc<Geometry>>,
+    tree: Tree,
+    graph: Graph,
+    attributes: HashMap<String, HashMap<String, AttrValue>>,
+    collision_filters: HashMap<String, CollisionFilter>,
+    groups: HashMap<String, Group>,
+    links: Vec<ExternalLink>,
+    display: HashMap<String, DisplayState>,
+    animations: HashMap<String, AnimationTrack>,
+}
+
+/// Recorded entries by key, None for entries that did not exist.
+type Entries<V> = Vec<(String, Option<V>)>;
+
+/// Changes of one transaction, see `Session::undo`.
+///
+/// Holds the values to put back for everything the transaction changed, None
+/// for entries it added. Applying a step returns the step that reverts it.
+#[derive(Debug, Clone, Default)]
+struct UndoStep {
+    /// Objects with their key in the object order
+    objects: Entries<(u64, Arc<Geometry>)>,
+    attributes: Entries<HashMap<String, AttrValue>>,
+    collision_filters: Entries<CollisionFilter>,
+    groups: Entries<Group>,
+    display: Entries<DisplayState>,
+    animations: Entries<AnimationTrack>,
+    /// Graph vertices with their edge lists
+    graph_nodes: Vec<(String, NodeEntry)>,
+    /// Vertex and edge counters of the graph
+    graph_counts: (i32, i32),
+    /// The whole tree, only if it changed
+    tree: Option<Tree>,
+    /// All links, only if they changed
+    links: Option<Vec<ExternalLink>>,
+}
+
+/// Entries of `after` that differ from `before`, with their value in `before`.
+fn changed_entries<V: PartialEq>(
+    before: HashMap<String, V>,
+    after: &HashMap<String, V>,
+) -> Entries<V> {
+    let mut changes: Entries<V> = (after.keys())
+        .filter(|key| !before.contains_key(*key))
+        .map(|key| (key.clone(), None))
+        .collect();
+    changes.extend(
+        (before.into_iter())
+            .filter(|(key, value)| after.get(key) != Some(value))
+            .map(|(key, value)| (key, Some(value))),
+    );
+    changes
+}
+
+/// Put back recorded entries, returning the entries they replaced.
+fn replace_entries<V>(map: &mut HashMap<String, V>, entries: Entries<V>) -> Entries<V> {
+    (entries.into_iter())
+        .map(|(key, value)| {
+            let previous = match value {
+                Some(value) => map.insert(key.clone(), value),
+                None => map.remove(&key),
+            };
+            (key, previous)
+        })
+        .collect()
+}
+
+/// Insertion order of the objects in `Session::lookup`.
+///
+/// Saved sessions list objects grouped by type in this order, edits keep an
+/// object in place and removed objects can be put back at their old place.
+#[derive(Debug, Clone)]
+struct ObjectOrder {
+    /// GUID and name written for the `objects` collection
+    guid: String,
+    name: String,
+    guids: BTreeMap<u64, String>,
+    keys: HashMap<String, u64>,
+    next: u64,
+}
+
+impl ObjectOrder {
+    fn new() -> Self {
+        let objects = Objects::new();
+        ObjectOrder {
+            guid: objects.guid,
+            name: objects.name,
+            guids: BTreeMap::new(),
+            keys: HashMap::new(),
+            next: 0,
+        }
+    }
+
+    /// Append a GUID, a GUID that is already listed keeps its place.
+    fn push(&mut self, guid: &str) {
+        if !self.keys.contains_key(guid) {
+            self.insert(guid, self.next);
+        }
+    }
+
+    /// List a GUID at a key returned by `remove`.
+    fn insert(&mut self, guid: &str, key: u64) {
+        self.next = self.next.max(key + 1);
+        self.guids.insert(key, guid.to_string());
+        self.keys.insert(guid.to_string(), key);
+    }
+
+    /// Unlist a GUID, returning its key.
+    fn remove(&mut self, guid: &str) -> Option<u64> {
+        let key = self.keys.remove(guid)?;
+        self.guids.remove(&key);
+        Some(key)
+    }
+
+    fn contains(&self, guid: &str) -> bool {
+        self.keys.contains_key(guid)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &String> {
+        self.guids.values()
+    }
+}
+
+/// The `Objects` layout borrowing the geometry of a Session, for saving it without copies.
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "Objects")]
+struct ObjectsRef<'a> {
+    guid: &'a str,
+    name: &'a str,
+    points: Vec<&'a Point>,
+    lines: Vec<&'a Line>,
+    planes: Vec<&'a Plane>,
+    bboxes: Vec<&'a BoundingBox>,
+    polylines: Vec<&'a Polyline>,
+    pointclouds: Vec<&'a PointCloud>,
+    meshes: Vec<&'a Mesh>,
+    cylinders: Vec<&'a Cylinder>,
+    arrows: Vec<&'a Arrow>,
+}
+
+/// Named set of objects that is moved, hidden and copied as one, see `Session::add_group`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    pub guid: String,
+    pub name: String,
+    /// Member object GUIDs
+    pub members: Vec<String>,
+    /// Placement of the group, already applied to the members' xforms
+    pub xform: Xform,
+    /// Members of hidden groups are skipped by ray casts
+    pub visible: bool,
+}
+
+/// Viewer state of an object, see `Session::set_visible`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayState {
+    pub visible: bool,
+    pub selected: bool,
+    /// Color drawn instead of the object's own colors, e.g. for hover feedback
+    pub highlight_color: Option<Color>,
+}
+
+impl Default for DisplayState {
+    /// Visible, not selected and not highlighted.
+    fn default() -> Self {
+        DisplayState {
+            visible: true,
+            selected: false,
+            highlight_color: None,
+        }
+    }
+}
+
+impl DisplayState {
+    fn is_default(&self) -> bool {
+        self.visible && !self.selected && self.highlight_color.is_none()
+    }
+}
+
+/// Session file referenced by another session, see `Session::link_external`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalLink {
+    pub guid: String,
+    /// Path of the linked session JSON file
+    pub path: String,
+    /// Placement of the linked session
+    pub xform: Xform,
+    /// Placed geometry of the linked file, loaded on first use, None if it could not be read
+    #[serde(skip)]
+    geometry: OnceCell<Option<Objects>>,
+}
+
+/// Broad phase algorithm used by `Session::get_collisions`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BroadPhase {
+    /// Bounding volume hierarchy, robust for objects of very different sizes
+    #[default]
+    Bvh,
+    /// Uniform spatial hash grid, faster for objects of similar size
+    SpatialHash,
+}
+
+/// What `Session::remove_object` does with the tree children of a removed object.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RemovalPolicy {
+    /// Move the children to the parent of the removed node, keeping their placement
+    #[default]
+    Reparent,
+    /// Remove the children and their objects with the node
+    DeleteSubtree,
+    /// Remove the children from the tree, keeping their objects
+    Detach,
+}
+
+/// Objects and tree nodes affected by `Session::remove_object_with`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemovalReport {
+    /// GUIDs of the removed objects, the requested one first
+    pub removed: Vec<String>,
+    /// Names of the tree nodes moved to the parent of the removed node
+    pub reparented: Vec<String>,
+    /// Names of the tree nodes removed from the tree with their subtrees
+    pub detached: Vec<String>,
+}
+
+/// Change notification passed to callbacks registered with `Session::on_change`.
+#[derive(Debug, Clone)]
+pub enum SessionEvent {
+    ObjectAdded {
+        guid: String,
+    },
+    ObjectRemoved {
+        guid: String,
+    },
+    /// The object was replaced or edited through `update_object` or `get_object_mut`
+    ObjectModified {
+        guid: String,
+    },
+    ObjectTransformed {
+        guid: String,
+        xform: Xform,
+    },
+    EdgeAdded {
+        from: String,
+        to: String,
+        attribute: String,
+    },
+    EdgeRemoved {
+        from: String,
+        to: String,
+    },
+}
+
+type SessionListener = Box<dyn FnMut(&SessionEvent)>;
+
+/// Registered change callbacks; a cloned Session starts without any.
+#[derive(Default)]
+struct SessionListeners {
+    next_id: usize,
+    callbacks: Vec<(usize, SessionListener)>,
+}
+
+impl Clone for SessionListeners {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for SessionListeners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SessionListeners({})", self.callbacks.len())
+    }
+}
+
+/// One line of the operation log written by `Session::open_log`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum LogEntry<'a> {
+    Add {
+        geometry: Cow<'a, Geometry>,
+    },
+    Remove {
+        guid: String,
+    },
+    Update {
+        geometry: Cow<'a, Geometry>,
+    },
+    Transform {
+        guid: String,
+        xform: Xform,
+    },
+    AddEdge {
+        from: String,
+        to: String,
+        attribute: String,
+    },
+    RemoveEdge {
+        from: String,
+        to: String,
+    },
+}
+
+/// Open operation log; a cloned Session does not write to it.
+#[derive(Default)]
+struct SessionLog {
+    writer: Option<BufWriter<fs::File>>,
+    /// First write error, reported by `close_log`
+    error: Option<std::io::Error>,
+}
+
+impl SessionLog {
+    fn write(&mut self, entry: &LogEntry<'_>) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        if self.error.is_some() {
+            return;
+        }
+        let result = serde_json::to_writer(&mut *writer, entry)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"))
+            .and_then(|_| writer.flush());
+        self.error = result.err();
+    }
+}
+
+impl Clone for SessionLog {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for SessionLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SessionLog(open={})", self.writer.is_some())
+    }
+}
+
+/// Value of a user attribute attached to a Session object.
+///
+/// Serialized as the plain JSON value, so attributes written by other
+/// languages load without a type tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AttrValue {
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<AttrValue>),
+}
+
+impl From<bool> for AttrValue {
+    fn from(value: bool) -> Self {
+        AttrValue::Bool(value)
+    }
+}
+
+impl From<f64> for AttrValue {
+    fn from(value: f64) -> Self {
+        AttrValue::Number(value)
+    }
+}
+
+impl From<&str> for AttrValue {
+    fn from(value: &str) -> Self {
+        AttrValue::String(value.to_string())
+    }
+}
+
+impl From<String> for AttrValue {
+    fn from(value: String) -> Self {
+        AttrValue::String(value)
+    }
+}
+
+impl From<Vec<AttrValue>> for AttrValue {
+    fn from(value: Vec<AttrValue>) -> Self {
+        AttrValue::Array(value)
+    }
+}
+
+/// Differences that turn one Session into another, see `Session::diff`.
+///
+/// Objects are matched by GUID and graph edges by their unordered pair of GUIDs.
+#[derive(Debug, Clone, Default)]
+pub struct SessionDelta {
+    /// Objects only in the other session
+    pub added: Vec<Geometry>,
+    /// GUIDs of objects only in this session
+    pub removed: Vec<String>,
+    /// Objects in both sessions with different data, as in the other session
+    pub modified: Vec<Geometry>,
+    /// Edges (u, v, attribute) that are new or have a different attribute
+    pub edges_added: Vec<(String, String, String)>,
+    /// Edges (u, v) only in this session
+    pub edges_removed: Vec<(String, String)>,
+}
+
+impl SessionDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.edges_added.is_empty()
+            && self.edges_removed.is_empty()
+    }
+}
+
+/// How `Session::merge` resolves objects and edges present on both sides.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Apply the whole delta, the result matches the other session
+    #[default]
+    Theirs,
+    /// Only add what is missing, keeping local versions and local objects
+    Ours,
+}
+
+/// Object counts, cache sizes and memory use of a Session, see `Session::stats`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    /// Number of objects per geometry type name, e.g. "Mesh"
+    pub object_counts: BTreeMap<String, usize>,
+    pub object_count: usize,
+    /// Points, line end points, polyline and point cloud points and mesh vertices
+    pub vertex_count: usize,
+    /// Mesh faces, including the display meshes of cylinders and arrows
+    pub face_count: usize,
+    pub graph_vertex_count: usize,
+    pub graph_edge_count: usize,
+    pub tree_node_count: usize,
+    /// Boxes cached for ray casts and spatial queries, one per chunk of large point clouds
+    pub cached_box_count: usize,
+    /// Memory of the cached ray BVH in bytes, 0 before the first query
+    pub ray_bvh_bytes: usize,
+    /// Memory of the BVH built by `get_collisions` in bytes
+    pub collision_bvh_bytes: usize,
+    /// Approximate memory used by the Session in bytes
+    pub memory_bytes: usize,
+}
+
+/// Problem found by `Session::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// The object is in the object order but not in the lookup table
+    MissingFromLookup { guid: String },
+    /// The object is in the lookup table but not in the object order
+    MissingFromObjects { guid: String },
+    /// Graph vertex without an object
+    DanglingGraphNode { key: String },
+    /// Graph edge to a vertex that does not exist
+    DanglingGraphEdge { from: String, to: String },
+    /// Tree node named by a GUID without an object, e.g. left behind by a removal
+    OrphanTreeNode { name: String },
+    /// NaN or infinite coordinates or xform entries
+    NonFiniteCoordinates { guid: String },
+    /// Geometry that cannot be used, e.g. a zero length line or a mesh without faces
+    DegenerateGeometry { guid: String, reason: String },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::MissingFromLookup { guid } => {
+                write!(f, "object {guid} is missing from the lookup table")
+            }
+            ValidationIssue::MissingFromObjects { guid } => {
+                write!(f, "object {guid} is missing from objects")
+            }
+            ValidationIssue::DanglingGraphNode { key } => {
+                write!(f, "graph vertex {key} has no object")
+            }
+            ValidationIssue::DanglingGraphEdge { from, to } => {
+                write!(f, "graph edge {from} - {to} has a missing vertex")
+            }
+            ValidationIssue::OrphanTreeNode { name } => {
+                write!(f, "tree node {name} has no object")
+            }
+            ValidationIssue::NonFiniteCoordinates { guid } => {
+                write!(f, "object {guid} has non-finite coordinates")
+            }
+            ValidationIssue::DegenerateGeometry { guid, reason } => {
+                write!(f, "object {guid} is degenerate: {reason}")
+            }
+        }
+    }
+}
+
+/// A world ray moved into the local frame of an object, see `Session::ray_cast_with`.
+struct LocalRay {
+    origin: Point,
+    /// Unit direction in the local frame
+    direction: Vector,
+    /// Segment from the origin to the far end of the ray
+    line: Line,
+    /// Length of `line` in the local frame
+    length: Scalar,
+    /// Hit tolerance in local units
+    tolerance: Scalar,
+}
+
+impl LocalRay {
+    /// Move a ray into the frame placed by `xform`, None if the xform cannot be inverted.
+    fn new(
+        xform: &Xform,
+        origin: &Point,
+        direction: &Vector,
+        far: Scalar,
+        tolerance: Scalar,
+    ) -> Option<Self> {
+        let end = origin.clone() + direction.clone() * far;
+        let (origin, end) = if xform.is_identity() {
+            (origin.clone(), end)
+        } else {
+            let inverse = xform.inverse()?;
+            (
+                inverse.transformed_point(origin),
+                inverse.transformed_point(&end),
+            )
+        };
+        let length = origin.distance(&end);
+        if length <= 0.0 {
+            return None;
+        }
+        Some(LocalRay {
+            direction: (end.clone() - origin.clone()) / length,
+            line: Line::from_points(&origin, &end),
+            origin,
+            length,
+            tolerance: tolerance * length / far,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RayHit {
     pub guid: String,
     pub point: Point,
-    pub distance: f64,
+    pub distance: Scalar,
+}
+
+/// Which hits `Session::ray_cast_with` reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RayCastMode {
+    /// The closest hit and hits within tolerance of it
+    #[default]
+    Nearest,
+    /// Every hit along the ray, several per object where the ray enters and leaves it
+    All,
+    /// The closest hit of every object along the ray
+    FirstPerObject,
+}
+
+/// Which objects `Session::select_frustum` and `Session::select_rect` report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Objects whose bounding boxes are fully inside, like a left-to-right window drag
+    #[default]
+    Window,
+    /// Objects whose bounding boxes are inside or crossing, like a right-to-left crossing drag
+    Crossing,
+}
+
+/// Options for `Session::ray_cast_with`.
+#[derive(Debug, Clone)]
+pub struct RayCastOptions {
+    pub mode: RayCastMode,
+    /// Hits farther from the ray origin are ignored
+    pub max_distance: Scalar,
+    /// Objects for which the filter returns false are ignored
+    pub type_filter: Option<fn(&Geometry) -> bool>,
+    /// Collision groups the ray can hit, see `Session::set_collision_filter`
+    pub mask: u32,
+}
+
+impl Default for RayCastOptions {
+    fn default() -> Self {
+        RayCastOptions {
+            mode: RayCastMode::Nearest,
+            max_distance: Scalar::INFINITY,
+            type_filter: None,
+            mask: u32::MAX,
+        }
+    }
+}
+
+/// Object hit by a moving object, see `Session::check_sweep`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepHit {
+    pub guid: String,
+    /// Index of the keyframe that starts the first colliding motion
+    pub frame: usize,
+    /// Position along the path where the collision starts, `frame` plus the fraction of the motion
+    pub position: Scalar,
+}
+
+/// Top-level fields of the current Session JSON layout.
+const SESSION_FIELDS: [&str; 13] = [
+    "type",
+    "version",
+    "guid",
+    "name",
+    "objects",
+    "tree",
+    "graph",
+    "attributes",
+    "collision_filters",
+    "groups",
+    "links",
+    "display",
+    "animations",
+];
+
+/// Upgrades of the Session JSON layout, the migration at index `i` turns version `i` into `i + 1`.
+const MIGRATIONS: [fn(&mut serde_json::Map<String, serde_json::Value>); 2] =
+    [migrate_v0_to_v1, migrate_v1_to_v2];
+
+/// Version 1 always writes the per-object and per-group sections, older files may lack them.
+fn migrate_v0_to_v1(fields: &mut serde_json::Map<String, serde_json::Value>) {
+    for key in ["attributes", "collision_filters", "groups", "display"] {
+        fields
+            .entry(key)
+            .or_insert_with(|| serde_json::Value::Object(Default::default()));
+    }
+    fields
+        .entry("links")
+        .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+}
+
+/// Version 2 adds the animation tracks.
+fn migrate_v1_to_v2(fields: &mut serde_json::Map<String, serde_json::Value>) {
+    fields
+        .entry("animations")
+        .or_insert_with(|| serde_json::Value::Object(Default::default()));
+}
+
+/// Start of the edge attributes holding a contact of `get_contacts`, followed by its JSON.
+const CONTACT_ATTRIBUTE_PREFIX: &str = "contact:";
+
+/// Whether an edge attribute marks a collision, a `bvh_collision` or a contact of `get_contacts`.
+fn is_collision_attribute(attribute: &str) -> bool {
+    attribute == "bvh_collision" || attribute.starts_with(CONTACT_ATTRIBUTE_PREFIX)
+}
+
+/// Replace every `guid` field in a JSON value, depth first in the order of the keys.
+fn replace_guids(value: &mut serde_json::Value, guid: &mut impl FnMut() -> String) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                if key == "guid" && field.is_string() {
+                    *field = serde_json::Value::String(guid());
+                } else {
+                    replace_guids(field, guid);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                replace_guids(item, guid);
+            }
+        }
+        _ => {}
+    }
 }
 
 impl Default for Session {
@@ -96,6 +959,9 @@ impl Default for Session {
 }
 
 impl Session {
+    /// Version of the JSON layout written by `jsondump`, see `jsonload` for older files.
+    pub const SCHEMA_VERSION: u64 = 2;
+
     /// Creates a new Session with the specified name.
     ///
     /// # Arguments
@@ -105,8 +971,7 @@ impl Session {
     /// A new Session instance with a unique GUID, empty objects collection,
     /// and initialized tree and graph structures.
     pub fn new(name: &str) -> Self {
-        let guid = Uuid::new_v4().to_string();
-        let objects = Objects::new();
+        let guid = crate::guid::new_guid();
         let lookup = HashMap::new();
         let mut tree = Tree::new(&format!("{name}_tree"));
         let graph = Graph::new(&format!("{name}_graph"));
@@ -121,16 +986,65 @@ impl Session {
         Self {
             guid,
             name: name.to_string(),
-            objects,
             lookup,
+            order: ObjectOrder::new(),
             tree,
             graph,
+            attributes: HashMap::new(),
+            collision_filters: HashMap::new(),
+            groups: HashMap::new(),
+            links: Vec::new(),
+            display: HashMap::new(),
+            animations: HashMap::new(),
             bvh,
             cached_ray_bvh: None,
             cached_guids: Vec::new(),
+            cached_chunks: Vec::new(),
+            cached_index: HashMap::new(),
             cached_boxes: Vec::new(),
             bvh_cache_dirty: true,
+            broad_phase: BroadPhase::default(),
+            removal_policy: RemovalPolicy::default(),
+            transaction: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            listeners: SessionListeners::default(),
+            log: SessionLog::default(),
+            ids: IdSource::default(),
+        }
+    }
+
+    /// Creates a new Session that generates GUIDs with the given generator.
+    ///
+    /// The generator is stored in the Session and gives GUIDs to the session
+    /// itself, its tree, graph and objects collection, and to everything added
+    /// afterwards: objects, tree nodes, graph vertices and edges, groups and links.
+    /// Objects get their GUID when they are added, replacing the one from their
+    /// constructor, so use the name of the returned tree node to refer to them;
+    /// GUIDs nested in an object, e.g. of its xform, are derived from its GUID.
+    /// Use `IdGenerator::Sequential` or `IdGenerator::Hashed` for byte-stable
+    /// golden files and fixtures shared with other languages. The generator is
+    /// not saved, loaded sessions generate random GUIDs.
+    ///
+    /// # Arguments
+    /// * `name` - The name for the session
+    /// * `generator` - How GUIDs are generated
+    pub fn with_id_generator(name: &str, generator: IdGenerator) -> Self {
+        let mut session = Self::new(name);
+        session.ids = IdSource::new(generator);
+        session.guid = session.ids.next(format!("session {name}").as_bytes());
+        session.order.guid = session.ids.next(format!("objects {name}").as_bytes());
+        session.tree.guid = session.ids.next(format!("tree {name}").as_bytes());
+        session.graph.guid = session.ids.next(format!("graph {name}").as_bytes());
+        if let Some(root) = session.tree.root() {
+            session.identify_nodes(&root);
         }
+        session
+    }
+
+    /// The generator of the GUIDs created by the session.
+    pub fn id_generator(&self) -> IdGenerator {
+        self.ids.generator()
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
@@ -142,32 +1056,94 @@ impl Session {
     /// # Returns
     /// A Result containing the JSON string representation of the Session,
     /// or an error if serialization fails.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
+        Ok(serde_json::to_string_pretty(&self.json_value()?)?)
+    }
+
+    /// The Session as a JSON value, shared by the JSON and binary formats.
+    fn json_value(&self) -> Result<serde_json::Value, SessionError> {
         // Use custom serialization to ensure consistent structure with C++/Python
         // Convert graph to use array structure instead of nested objects
         let graph_json: serde_json::Value = serde_json::from_str(&self.graph.jsondump()?)?;
 
         let json_obj = serde_json::json!({
             "type": "Session",
+            "version": Self::SCHEMA_VERSION,
             "guid": self.guid,
             "name": self.name,
-            "objects": self.objects,
+            "objects": self.objects_ref(),
             "tree": self.tree,
-            "graph": graph_json
+            "graph": graph_json,
+            "attributes": self.attributes,
+            "collision_filters": self.collision_filters,
+            "groups": self.groups,
+            "links": self.links,
+            "display": self.display,
+            "animations": self.animations
         });
 
-        Ok(serde_json::to_string_pretty(&json_obj)?)
+        Ok(json_obj)
     }
 
     /// Deserializes Session from a JSON string.
     ///
+    /// Files written with an older `version` are upgraded to the current layout.
+    ///
     /// # Arguments
     /// * `json_data` - The JSON string to deserialize
     ///
     /// # Returns
-    /// A Result containing the deserialized Session, or an error if parsing fails.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let json_obj: serde_json::Value = serde_json::from_str(json_data)?;
+    /// A Result containing the deserialized Session, or an error if parsing fails,
+    /// the file is newer than `SCHEMA_VERSION` or it has unknown top-level fields.
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
+        Self::from_json_value(serde_json::from_str(json_data)?)
+    }
+
+    /// Upgrade a Session JSON value written by an older version to the current layout.
+    ///
+    /// Files without a `version` field are version 0. Each registered migration
+    /// upgrades by one version, then fields not in the current layout are reported.
+    fn migrate(json_obj: &mut serde_json::Value) -> Result<(), SessionError> {
+        let fields = json_obj
+            .as_object_mut()
+            .ok_or_else(|| SessionError::SchemaMismatch("Session JSON must be an object".into()))?;
+        let version = match fields.get("version") {
+            Some(value) => value.as_u64().ok_or_else(|| {
+                SessionError::SchemaMismatch(
+                    "Session JSON version must be an unsigned integer".into(),
+                )
+            })?,
+            None => 0,
+        };
+        if version > Self::SCHEMA_VERSION {
+            return Err(SessionError::SchemaMismatch(format!(
+                "Session JSON version {version} is newer than the supported version {}",
+                Self::SCHEMA_VERSION
+            )));
+        }
+        for migration in &MIGRATIONS[version as usize..] {
+            migration(fields);
+        }
+        fields.insert("version".to_string(), Self::SCHEMA_VERSION.into());
+
+        let mut unknown: Vec<&str> = fields
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !SESSION_FIELDS.contains(key))
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            return Err(SessionError::SchemaMismatch(format!(
+                "Unknown fields in Session JSON: {}",
+                unknown.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Build a Session from a JSON value, shared by the JSON and binary formats.
+    fn from_json_value(mut json_obj: serde_json::Value) -> Result<Self, SessionError> {
+        Self::migrate(&mut json_obj)?;
 
         // Deserialize components using their custom methods
         let objects: Objects = serde_json::from_value(json_obj["objects"].clone())?;
@@ -175,38 +1151,26 @@ impl Session {
         // Convert graph JSON value to properly formatted string
         let graph_json_str = serde_json::to_string(&json_obj["graph"])?;
         let graph: Graph = Graph::jsonload(&graph_json_str)?;
+        let attributes = serde_json::from_value(json_obj["attributes"].clone())?;
+        let collision_filters = serde_json::from_value(json_obj["collision_filters"].clone())?;
+        let groups = serde_json::from_value(json_obj["groups"].clone())?;
+        let links = serde_json::from_value(json_obj["links"].clone())?;
+        let display = serde_json::from_value(json_obj["display"].clone())?;
+        let animations = serde_json::from_value(json_obj["animations"].clone())?;
 
-        // Rebuild lookup table from all objects
+        // Move the objects into the lookup table, keeping their order
+        let mut order = ObjectOrder::new();
+        (order.guid, order.name) = (objects.guid.clone(), objects.name.clone());
         let mut lookup = HashMap::new();
-        for arrow in &objects.arrows {
-            lookup.insert(arrow.guid.clone(), Geometry::Arrow(arrow.clone()));
-        }
-        for bbox in &objects.bboxes {
-            lookup.insert(bbox.guid.clone(), Geometry::BoundingBox(bbox.clone()));
-        }
-        for cylinder in &objects.cylinders {
-            lookup.insert(cylinder.guid.clone(), Geometry::Cylinder(cylinder.clone()));
-        }
-        for line in &objects.lines {
-            lookup.insert(line.guid.clone(), Geometry::Line(line.clone()));
-        }
-        for mesh in &objects.meshes {
-            lookup.insert(mesh.guid.clone(), Geometry::Mesh(mesh.clone()));
-        }
-        for plane in &objects.planes {
-            lookup.insert(plane.guid.clone(), Geometry::Plane(plane.clone()));
-        }
-        for point in &objects.points {
-            lookup.insert(point.guid.clone(), Geometry::Point(point.clone()));
-        }
-        for pointcloud in &objects.pointclouds {
-            lookup.insert(
-                pointcloud.guid.clone(),
-                Geometry::PointCloud(pointcloud.clone()),
-            );
-        }
-        for polyline in &objects.polylines {
-            lookup.insert(polyline.guid.clone(), Geometry::Polyline(polyline.clone()));
+        for geometry in Self::object_geometries(objects) {
+            let guid = geometry.guid().to_string();
+            if lookup.contains_key(&guid) {
+                return Err(SessionError::SchemaMismatch(format!(
+                    "Duplicate object GUID {guid}"
+                )));
+            }
+            order.push(&guid);
+            lookup.insert(guid, Arc::new(geometry));
         }
 
         let session = Session {
@@ -215,15 +1179,31 @@ impl Session {
                 .as_str()
                 .unwrap_or("my_session")
                 .to_string(),
-            objects,
             lookup,
+            order,
             tree,
             graph,
+            attributes,
+            collision_filters,
+            groups,
+            links,
+            display,
+            animations,
             bvh: BVH::new(),
             cached_ray_bvh: None,
             cached_guids: Vec::new(),
+            cached_chunks: Vec::new(),
+            cached_index: HashMap::new(),
             cached_boxes: Vec::new(),
             bvh_cache_dirty: true,
+            broad_phase: BroadPhase::default(),
+            removal_policy: RemovalPolicy::default(),
+            transaction: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            listeners: SessionListeners::default(),
+            log: SessionLog::default(),
+            ids: IdSource::default(),
         };
 
         Ok(session)
@@ -236,7 +1216,7 @@ impl Session {
     ///
     /// # Returns
     /// A Result indicating success or failure of the file write operation.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         fs::write(filepath, json)?;
         Ok(())
@@ -249,17 +1229,108 @@ impl Session {
     ///
     /// # Returns
     /// A Result containing the deserialized Session, or an error if file reading or parsing fails.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Binary
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Serializes the Session to MessagePack bytes.
+    ///
+    /// The data has the same schema as `jsondump`, so any MessagePack reader
+    /// gets the same maps and arrays as from the JSON file, in a fraction of
+    /// the size and parse time for mesh-heavy sessions.
+    ///
+    /// # Returns
+    /// A Result containing the MessagePack bytes, or an error if serialization fails.
+    pub fn binarydump(&self) -> Result<Vec<u8>, SessionError> {
+        Ok(rmp_serde::to_vec_named(&self.json_value()?)?)
+    }
+
+    /// Deserializes Session from MessagePack bytes written by `binarydump`.
+    ///
+    /// # Arguments
+    /// * `data` - The MessagePack bytes to deserialize
+    ///
+    /// # Returns
+    /// A Result containing the deserialized Session, or an error if parsing fails.
+    pub fn binaryload(data: &[u8]) -> Result<Self, SessionError> {
+        Self::from_json_value(rmp_serde::from_slice(data)?)
+    }
+
+    /// Serializes the Session to a MessagePack file.
+    ///
+    /// # Arguments
+    /// * `filepath` - The path where the MessagePack file will be written
+    ///
+    /// # Returns
+    /// A Result indicating success or failure of the file write operation.
+    pub fn to_binary(&self, filepath: &str) -> Result<(), SessionError> {
+        fs::write(filepath, self.binarydump()?)?;
+        Ok(())
+    }
+
+    /// Deserializes Session from a MessagePack file.
+    ///
+    /// # Arguments
+    /// * `filepath` - The path to the MessagePack file to read
+    ///
+    /// # Returns
+    /// A Result containing the deserialized Session, or an error if file reading or parsing fails.
+    pub fn from_binary(filepath: &str) -> Result<Self, SessionError> {
+        let data = fs::read(filepath)?;
+        Self::binaryload(&data)
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
     // BVH Collision Detection
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    /// Compute bounding box for a geometry object, inflated by tolerance
+    /// Compute bounding box for a geometry object, inflated by tolerance.
+    ///
+    /// Objects with a non-identity xform get the box of their transformed local box corners.
     fn compute_bounding_box(geometry: &Geometry) -> BoundingBox {
+        Self::placed_box(Self::compute_local_bounding_box(geometry), geometry.xform())
+    }
+
+    /// A box in an object's local coordinates placed by the object's xform.
+    fn placed_box(local: BoundingBox, xform: &Xform) -> BoundingBox {
+        if xform.is_identity() {
+            return local;
+        }
+        let corners = local
+            .corners()
+            .map(|corner| xform.transformed_point(&corner));
+        BoundingBox::from_points(&corners, 0.0)
+    }
+
+    /// Boxes of the leaves an object takes in the ray and collision BVHs, with
+    /// the point range of each chunk leaf.
+    ///
+    /// Point clouds of more than `PointCloud::CHUNK_SIZE` points take a leaf
+    /// per chunk, see `PointCloud::chunk_bounds`, so box queries and rays only
+    /// visit the chunks they reach. Other objects take one leaf.
+    fn leaf_boxes(geometry: &Geometry) -> Vec<(BoundingBox, Option<Range<usize>>)> {
+        let size = PointCloud::CHUNK_SIZE;
+        match geometry {
+            Geometry::PointCloud(pc) if pc.len() > size => (pc.chunk_bounds(size).into_iter())
+                .enumerate()
+                .map(|(index, mut bbox)| {
+                    bbox.inflate(Tolerance::APPROXIMATION);
+                    let start = index * size;
+                    let range = start..(start + size).min(pc.len());
+                    (Self::placed_box(bbox, &pc.xform), Some(range))
+                })
+                .collect(),
+            _ => vec![(Self::compute_bounding_box(geometry), None)],
+        }
+    }
+
+    /// Compute bounding box for a geometry object in its local coordinates
+    fn compute_local_bounding_box(geometry: &Geometry) -> BoundingBox {
         let inflate = Tolerance::APPROXIMATION;
         match geometry {
             Geometry::Point(p) => BoundingBox::from_point(p.clone(), inflate),
@@ -326,552 +1397,2868 @@ impl Session {
         }
     }
 
-    /// Get all collision pairs using BVH and add them as graph edges.
+    /// Get all collision pairs using the selected broad phase and add them as graph edges.
     ///
     /// Automatically:
     /// - Computes bounding boxes for all objects with tolerance inflation
-    /// - Builds/rebuilds the BVH with auto-computed world size
+    /// - Builds/rebuilds the BVH or spatial hash, see `broad_phase`
     /// - Detects all collision pairs
     /// - Adds collision edges to the graph
     ///
     /// # Returns
     /// A vector of tuples (guid1, guid2) representing colliding geometry pairs
     pub fn get_collisions(&mut self) -> Vec<(String, String)> {
+        self.get_collisions_with(&CollisionOptions::default())
+    }
+
+    /// Get collision pairs like `get_collisions`, optionally confirmed by a narrow phase.
+    ///
+    /// Box overlaps alone report long diagonal lines as colliding with
+    /// everything their box covers, the narrow phase removes such pairs.
+    /// Pairs excluded by their collision filters are never reported.
+    ///
+    /// # Arguments
+    /// * `options` - Narrow phase, contact tolerance and pair filter
+    ///
+    /// # Returns
+    /// A vector of tuples (guid1, guid2) representing colliding geometry pairs
+    pub fn get_collisions_with(&mut self, options: &CollisionOptions) -> Vec<(String, String)> {
         // Collect all objects with their bounding boxes and GUIDs
         let mut boxes_with_guids: Vec<(BoundingBox, String)> = Vec::new();
 
+        // Boxes already carry the approximation tolerance
+        let extra = (options.tolerance - Tolerance::APPROXIMATION).max(0.0);
         for (guid, geometry) in &self.lookup {
-            let bbox = Self::compute_bounding_box(geometry);
-            boxes_with_guids.push((bbox, guid.clone()));
+            for (mut bbox, _) in Self::leaf_boxes(geometry) {
+                if extra > 0.0 {
+                    bbox.inflate(extra);
+                }
+                boxes_with_guids.push((bbox, guid.clone()));
+            }
         }
 
         if boxes_with_guids.is_empty() {
             return Vec::new();
         }
 
-        // Build BVH with GUIDs (auto-computes world size)
-        self.bvh.build_with_guids(&boxes_with_guids);
-
         // Extract just the boxes for collision checking
         let boxes: Vec<BoundingBox> = boxes_with_guids
             .iter()
             .map(|(bbox, _)| bbox.clone())
             .collect();
 
-        // Get collision pairs as GUIDs directly
-        let collision_pairs = self.bvh.check_all_collisions_guids(&boxes);
+        let mut collision_pairs = match self.broad_phase {
+            BroadPhase::Bvh => {
+                // Build BVH with GUIDs (auto-computes world size)
+                self.bvh.build_with_guids(&boxes_with_guids);
+                // Get collision pairs as GUIDs directly
+                self.bvh.check_all_collisions_guids(&boxes)
+            }
+            BroadPhase::SpatialHash => SpatialHash::from_boxes(&boxes)
+                .find_all_pairs()
+                .into_iter()
+                .map(|(i, j)| (boxes_with_guids[i].1.clone(), boxes_with_guids[j].1.clone()))
+                .collect(),
+        };
+
+        // Chunks of a point cloud overlap each other, and other objects more than once
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        collision_pairs.retain(|(guid1, guid2)| {
+            let pair = if guid1 < guid2 {
+                (guid1.clone(), guid2.clone())
+            } else {
+                (guid2.clone(), guid1.clone())
+            };
+            guid1 != guid2
+                && seen.insert(pair)
+                && self
+                    .collision_filter(guid1)
+                    .collides_with(&self.collision_filter(guid2))
+        });
+        if options.filter.is_some() || options.narrow_phase {
+            // Triangle BVHs fill their caches through shared references, so
+            // pairs are tested without copying objects shared with snapshots
+            let lookup = &self.lookup;
+            let keep = |(guid1, guid2): &(String, String)| {
+                let (Some(a), Some(b)) = (lookup.get(guid1), lookup.get(guid2)) else {
+                    return false;
+                };
+                if let Some(filter) = options.filter {
+                    if !filter(a, b) {
+                        return false;
+                    }
+                }
+                !options.narrow_phase || narrow_phase(a, b, options.tolerance)
+            };
+            #[cfg(feature = "parallel")]
+            {
+                collision_pairs = collision_pairs.into_par_iter().filter(keep).collect();
+            }
+            #[cfg(not(feature = "parallel"))]
+            collision_pairs.retain(keep);
+        }
 
         // Add collision edges to graph
         for (guid1, guid2) in &collision_pairs {
-            self.graph.add_edge(guid1, guid2, "bvh_collision");
+            if !self.graph.has_edge((guid1, guid2)) {
+                self.add_graph_edge(guid1, guid2, "bvh_collision");
+            }
         }
 
         collision_pairs
     }
 
-    ///////////////////////////////////////////////////////////////////////////////////////////
-    // Ray BVH Cache
-    ///////////////////////////////////////////////////////////////////////////////////////////
-
-    fn cache_geometry_aabb(&mut self, guid: &str, geometry: &Geometry) {
-        let bbox = Self::compute_bounding_box(geometry);
-        self.cached_boxes.push(bbox);
-        self.cached_guids.push(guid.to_string());
-        self.bvh_cache_dirty = true;
-    }
+    /// Get collision pairs like `get_collisions_with` with their contact points and depths.
+    ///
+    /// Pairs of convex objects are tested by GJK and EPA, see
+    /// `collision::contact`, and their `bvh_collision` edge attribute is
+    /// replaced by `contact:` and the contact as JSON, read back with `contact`. Convex pairs
+    /// found apart lose their edge. Pairs with other objects keep their edge
+    /// and are not returned.
+    ///
+    /// # Arguments
+    /// * `options` - Narrow phase, contact tolerance and pair filter
+    ///
+    /// # Returns
+    /// The GUIDs of each pair in contact with the contact seen from the first.
+    pub fn get_contacts(&mut self, options: &CollisionOptions) -> Vec<(String, String, Contact)> {
+        let pairs = self.get_collisions_with(options);
+        let shapes: HashMap<&str, ConvexShape> = pairs
+            .iter()
+            .flat_map(|(guid1, guid2)| [guid1, guid2])
+            .filter_map(|guid| Some((guid.as_str(), ConvexShape::of(self.lookup.get(guid)?)?)))
+            .collect();
+        let test = |(guid1, guid2): &(String, String)| {
+            let (a, b) = (shapes.get(guid1.as_str())?, shapes.get(guid2.as_str())?);
+            Some(contact_between(a, b, options.tolerance))
+        };
+        #[cfg(feature = "parallel")]
+        let results: Vec<Option<Option<Contact>>> = pairs.par_iter().map(test).collect();
+        #[cfg(not(feature = "parallel"))]
+        let results: Vec<Option<Option<Contact>>> = pairs.iter().map(test).collect();
 
-    fn rebuild_ray_bvh_cache(&mut self) {
-        if self.cached_boxes.len() != self.lookup.len() {
-            self.cached_boxes.clear();
-            self.cached_guids.clear();
-            self.cached_boxes.reserve(self.lookup.len());
-            self.cached_guids.reserve(self.lookup.len());
-            for (guid, geometry) in &self.lookup {
-                let bbox = Self::compute_bounding_box(geometry);
-                self.cached_boxes.push(bbox);
-                self.cached_guids.push(guid.clone());
+        let mut contacts = Vec::new();
+        for ((guid1, guid2), result) in pairs.into_iter().zip(results) {
+            match result {
+                Some(Some(contact)) => {
+                    // Stored from the smaller GUID to the larger one
+                    let stored = if guid1 < guid2 {
+                        contact
+                    } else {
+                        contact.flipped()
+                    };
+                    let json = serde_json::to_string(&stored).unwrap_or_default();
+                    let attribute = format!("{CONTACT_ATTRIBUTE_PREFIX}{json}");
+                    self.graph.edge_attribute(&guid1, &guid2, Some(&attribute));
+                    contacts.push((guid1, guid2, contact));
+                }
+                Some(None) => {
+                    self.graph.remove_edge((&guid1, &guid2));
+                    self.emit(SessionEvent::EdgeRemoved {
+                        from: guid1,
+                        to: guid2,
+                    });
+                }
+                None => {}
             }
         }
-        if !self.cached_boxes.is_empty() {
-            let world_size = BVH::compute_world_size(&self.cached_boxes);
-            self.cached_ray_bvh = Some(BVH::from_boxes(&self.cached_boxes, world_size));
-        } else {
-            self.cached_ray_bvh = None;
-        }
+        contacts
     }
 
-    fn invalidate_bvh_cache(&mut self) {
-        self.bvh_cache_dirty = true;
+    /// Contact stored on the edge of two objects by `get_contacts`.
+    ///
+    /// # Returns
+    /// The contact seen from `guid1`, or None if the edge holds no contact.
+    pub fn contact(&self, guid1: &str, guid2: &str) -> Option<Contact> {
+        let edge = self.graph.edges.get(guid1)?.get(guid2)?;
+        let json = edge.attribute.strip_prefix(CONTACT_ATTRIBUTE_PREFIX)?;
+        let stored: Contact = serde_json::from_str(json).ok()?;
+        Some(if guid1 < guid2 {
+            stored
+        } else {
+            stored.flipped()
+        })
     }
 
-    pub fn ray_cast(
-        &mut self,
-        origin: &Point,
-        direction: &crate::Vector,
-        tolerance: f64,
-    ) -> Vec<RayHit> {
-        let dir_len = direction.compute_length();
-        if dir_len <= 0.0 {
+    /// Check the motion of an object through keyframed poses for collisions, e.g. a robot path.
+    ///
+    /// Poses are interpolated with `Xform::lerp` and sampled so densely that
+    /// no corner of the object's box moves more than a sixteenth of the box
+    /// diagonal between samples. Each sample is tested with the narrow phase
+    /// and a tolerance of half that step, so objects the motion passes
+    /// between samples are found as well. The object itself is not moved and
+    /// collision filters apply as in `get_collisions`.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the moving object
+    /// * `path` - Absolute poses replacing the object's xform, as in an `AnimationTrack`
+    ///
+    /// # Returns
+    /// One hit per colliding object, ordered by position along the path.
+    pub fn check_sweep(&mut self, guid: &str, path: &[Xform]) -> Vec<SweepHit> {
+        let Some(geometry) = self.lookup.get(guid) else {
             return Vec::new();
-        }
-        let dir_unit = crate::Vector::new(
-            direction.x() / dir_len,
-            direction.y() / dir_len,
-            direction.z() / dir_len,
-        );
-
-        let far = 1e6f64;
-        let ray_end = Point::new(
-            origin.x() + dir_unit.x() * far,
-            origin.y() + dir_unit.y() * far,
-            origin.z() + dir_unit.z() * far,
-        );
-        let ray_line = Line::from_points(origin, &ray_end);
-
-        // Use cached BVH for ray casting
-        if self.bvh_cache_dirty || self.cached_ray_bvh.is_none() {
-            self.rebuild_ray_bvh_cache();
-            self.bvh_cache_dirty = false;
-        }
-        let bvh = match &self.cached_ray_bvh {
-            Some(b) => b,
-            None => return Vec::new(),
         };
+        let Some(last) = path.last() else {
+            return Vec::new();
+        };
+        let mut moving = Geometry::clone(geometry);
+        let local = Self::compute_local_bounding_box(geometry);
+        let corners = local.corners();
+        let step = (local.half_size.as_vec3().length() / 8.0).max(Tolerance::APPROXIMATION);
+        let tolerance = step / 2.0 + Tolerance::APPROXIMATION;
 
-        let mut candidates: Vec<usize> = Vec::new();
-        bvh.ray_cast(origin, &dir_unit, &mut candidates, true);
-
-        let mut hits_all: Vec<RayHit> = Vec::new();
-
-        for idx in candidates {
-            if idx >= self.cached_guids.len() {
-                continue;
+        // Samples of each motion, spaced by the farthest moving corner with room for arcs
+        let mut samples: Vec<(usize, Scalar, Xform)> = Vec::new();
+        for (frame, pair) in path.windows(2).enumerate() {
+            let chord = corners
+                .iter()
+                .map(|c| {
+                    pair[0]
+                        .transformed_point(c)
+                        .distance(&pair[1].transformed_point(c))
+                })
+                .fold(0.0, Scalar::max);
+            let count =
+                ((chord * crate::scalar::consts::FRAC_PI_2 / step).ceil() as usize).clamp(1, 4096);
+            for i in 0..count {
+                let t = i as Scalar / count as Scalar;
+                samples.push((frame, t, Xform::lerp(&pair[0], &pair[1], t)));
             }
-            let guid = self.cached_guids[idx].clone();
-            let geom = match self.lookup.get_mut(&guid) {
-                Some(g) => g,
-                None => continue,
-            };
-
-            let mut hit_point: Option<Point> = None;
+        }
+        samples.push((path.len() - 1, 0.0, last.clone()));
 
-            match geom {
-                Geometry::BoundingBox(bb) => {
-                    if let Some(pts) = crate::intersection::ray_box(&ray_line, bb, 0.0, far) {
-                        if !pts.is_empty() {
-                            hit_point = Some(pts[0].clone());
-                        }
-                    }
-                }
-                Geometry::Plane(pl) => {
-                    if let Some(p) = crate::intersection::line_plane(&ray_line, pl, true) {
-                        hit_point = Some(p);
-                    }
-                }
-                Geometry::Line(l) => {
-                    if let Some(p) =
-                        crate::intersection::line_line(&ray_line, l, Tolerance::APPROXIMATION)
-                    {
-                        hit_point = Some(p);
-                    }
-                }
-                Geometry::Polyline(pl) => {
-                    let mut best_t = f64::INFINITY;
-                    let mut best_p: Option<Point> = None;
-                    if pl.points.len() >= 2 {
-                        for i in 0..(pl.points.len() - 1) {
-                            let seg = Line::from_points(&pl.points[i], &pl.points[i + 1]);
-                            if let Some(p) = crate::intersection::line_line(
-                                &ray_line,
-                                &seg,
-                                Tolerance::APPROXIMATION,
-                            ) {
-                                let dx = p.x() - origin.x();
-                                let dy = p.y() - origin.y();
-                                let dz = p.z() - origin.z();
-                                let t = dx * dir_unit.x() + dy * dir_unit.y() + dz * dir_unit.z();
-                                if t >= 0.0 && t < best_t {
-                                    best_t = t;
-                                    best_p = Some(p);
-                                }
-                            }
-                        }
-                    }
-                    if let Some(p) = best_p {
-                        hit_point = Some(p);
-                    }
-                }
-                Geometry::Mesh(m) => {
-                    if let Some(p) = m.ray_cast_bvh(&ray_line, 1e-6) {
-                        hit_point = Some(p);
-                    }
-                }
-                Geometry::Cylinder(cy) => {
-                    if let Some(p) = crate::intersection::line_line(
-                        &ray_line,
-                        &cy.line,
-                        Tolerance::APPROXIMATION,
-                    ) {
-                        hit_point = Some(p);
-                    }
-                }
-                Geometry::Arrow(ar) => {
-                    if let Some(p) = crate::intersection::line_line(
-                        &ray_line,
-                        &ar.line,
-                        Tolerance::APPROXIMATION,
-                    ) {
-                        hit_point = Some(p);
-                    }
-                }
-                Geometry::Point(p) => {
-                    let vx = p.x() - origin.x();
-                    let vy = p.y() - origin.y();
-                    let vz = p.z() - origin.z();
-                    let cross_x = vy * dir_unit.z() - vz * dir_unit.y();
-                    let cross_y = vz * dir_unit.x() - vx * dir_unit.z();
-                    let cross_z = vx * dir_unit.y() - vy * dir_unit.x();
-                    let dist = (cross_x * cross_x + cross_y * cross_y + cross_z * cross_z).sqrt();
-                    if dist <= tolerance {
-                        let t = vx * dir_unit.x() + vy * dir_unit.y() + vz * dir_unit.z();
-                        if t >= 0.0 {
-                            let hp = Point::new(
-                                origin.x() + dir_unit.x() * t,
-                                origin.y() + dir_unit.y() * t,
-                                origin.z() + dir_unit.z() * t,
-                            );
-                            hit_point = Some(hp);
-                        }
-                    }
+        let filter = self.collision_filter(guid);
+        let mut hits: Vec<SweepHit> = Vec::new();
+        for (frame, t, xform) in samples {
+            let mut bbox = local.transformed_by(&xform);
+            bbox.half_size = crate::Vector::new(
+                bbox.half_size.x() + tolerance,
+                bbox.half_size.y() + tolerance,
+                bbox.half_size.z() + tolerance,
+            );
+            *moving.xform_mut() = xform;
+            for other in self.objects_in_box(&bbox) {
+                if other == guid
+                    || hits.iter().any(|hit| hit.guid == other)
+                    || !filter.collides_with(&self.collision_filter(&other))
+                {
+                    continue;
                 }
-                Geometry::PointCloud(_) => {}
-            }
-
-            if let Some(hp) = hit_point {
-                let dx = hp.x() - origin.x();
-                let dy = hp.y() - origin.y();
-                let dz = hp.z() - origin.z();
-                let forward = dx * dir_unit.x() + dy * dir_unit.y() + dz * dir_unit.z();
-                if forward >= 0.0 {
-                    let dist = (dx * dx + dy * dy + dz * dz).sqrt();
-                    hits_all.push(RayHit {
-                        guid: guid.clone(),
-                        point: hp,
-                        distance: dist,
+                // Stored objects are only read, their triangle BVHs fill through shared references
+                let Some(target) = self.lookup.get(&other) else {
+                    continue;
+                };
+                if narrow_phase(target, &moving, tolerance) {
+                    hits.push(SweepHit {
+                        guid: other,
+                        frame,
+                        position: frame as Scalar + t,
                     });
                 }
             }
         }
+        hits
+    }
 
-        if hits_all.is_empty() {
-            return Vec::new();
+    /// Re-test moved objects against the cached BVH and update their collision edges.
+    ///
+    /// Only the moved objects are queried, so this is much cheaper than
+    /// `get_collisions` when few objects changed. `bvh_collision` edges of the
+    /// moved objects that no longer overlap are removed, as are their contact
+    /// edges, other edges are kept.
+    ///
+    /// # Arguments
+    /// * `moved_guids` - GUIDs of the objects whose geometry or xform changed
+    ///
+    /// # Returns
+    /// Colliding pairs involving a moved object, each pair ordered and listed once.
+    pub fn update_collisions(&mut self, moved_guids: &[String]) -> Vec<(String, String)> {
+        for guid in moved_guids {
+            self.refresh_geometry_aabb(guid);
         }
+        self.update_ray_bvh_cache();
+
+        let mut pairs = Vec::new();
+        for guid in moved_guids {
+            let Some(leaves) = self.cached_index.get(guid) else {
+                continue;
+            };
+            let filter = self.collision_filter(guid);
+            let colliding: HashSet<String> = match &self.cached_ray_bvh {
+                Some(bvh) => (leaves.iter())
+                    .flat_map(|&index| bvh.query_box(&self.cached_boxes[index]))
+                    .filter_map(|other| self.cached_guids.get(other))
+                    .filter(|other| *other != guid)
+                    .filter(|other| filter.collides_with(&self.collision_filter(other)))
+                    .cloned()
+                    .collect(),
+                None => HashSet::new(),
+            };
 
-        let mut min_d = f64::INFINITY;
-        for h in &hits_all {
-            if h.distance < min_d {
-                min_d = h.distance;
+            for neighbor in self.graph.neighbors(guid) {
+                let attribute = self.graph.edge_attribute(guid, &neighbor, None);
+                if !colliding.contains(&neighbor)
+                    && attribute.as_deref().is_some_and(is_collision_attribute)
+                {
+                    self.graph.remove_edge((guid, &neighbor));
+                    self.emit(SessionEvent::EdgeRemoved {
+                        from: guid.clone(),
+                        to: neighbor,
+                    });
+                }
+            }
+            for other in colliding {
+                if !self.graph.has_edge((guid, &other)) {
+                    self.add_graph_edge(guid, &other, "bvh_collision");
+                }
+                if guid < &other {
+                    pairs.push((guid.clone(), other));
+                } else {
+                    pairs.push((other, guid.clone()));
+                }
             }
         }
-        let eps = tolerance;
-        let mut hits: Vec<RayHit> = hits_all
-            .into_iter()
-            .filter(|h| (h.distance - min_d).abs() <= eps)
-            .collect();
-        hits.sort_by(|a, b| {
-            a.distance
-                .partial_cmp(&b.distance)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        hits
+        pairs.sort();
+        pairs.dedup();
+        pairs
     }
 
-    ///////////////////////////////////////////////////////////////////////////////////////////
-    // Details
-    ///////////////////////////////////////////////////////////////////////////////////////////
+    /// Group objects into contact clusters, the connected parts of the
+    /// `bvh_collision` edges found by `get_collisions` and the contact edges of `get_contacts`.
+    ///
+    /// Clusters are a first split for assembly sequencing: objects in
+    /// different clusters can be placed independently.
+    ///
+    /// # Returns
+    /// The object GUIDs of each cluster sorted, largest cluster first. Objects
+    /// without contacts form clusters of their own.
+    pub fn contact_clusters(&self) -> Vec<Vec<String>> {
+        self.graph
+            .clusters(|edge| is_collision_attribute(&edge.attribute))
+            .into_iter()
+            .map(|cluster| {
+                cluster
+                    .into_iter()
+                    .filter(|guid| self.lookup.contains_key(guid))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cluster| !cluster.is_empty())
+            .collect()
+    }
 
-    /// Adds a point to the Session.
+    /// Set the collision group and mask bits of an object.
     ///
-    /// The point is added to the objects collection, lookup table, graph as a node,
-    /// and tree as a child of the root node.
+    /// Use group 0 for scaffolding or reference geometry that should stay in
+    /// the scene but never collide or be hit by ray casts.
     ///
     /// # Arguments
-    /// * `point` - The Point object to add to the session
+    /// * `guid` - The GUID of the object
+    /// * `group` - Groups the object belongs to
+    /// * `mask` - Groups the object collides with
     ///
     /// # Returns
-    /// The TreeNode created for this point
-    pub fn add_point(&mut self, point: Point) -> TreeNode {
-        let point_guid = point.guid.clone();
-        let point_name = point.name.clone();
-        let geometry = Geometry::Point(point.clone());
-
-        self.objects.points.push(point);
-        self.lookup.insert(point_guid.clone(), geometry);
-        if let Some(Geometry::Point(p)) = self.lookup.get(&point_guid) {
-            self.cache_geometry_aabb(&point_guid, &Geometry::Point(p.clone()));
+    /// `false` if no object has the GUID.
+    pub fn set_collision_filter(&mut self, guid: &str, group: u32, mask: u32) -> bool {
+        if !self.lookup.contains_key(guid) {
+            return false;
         }
-        self.graph
-            .add_node(&point_guid, &format!("point_{point_name}"));
+        self.collision_filters
+            .insert(guid.to_string(), CollisionFilter::new(group, mask));
+        true
+    }
 
-        TreeNode::new(&point_guid)
+    /// Get the collision filter of an object, the default one if it was never set.
+    pub fn collision_filter(&self, guid: &str) -> CollisionFilter {
+        self.collision_filters
+            .get(guid)
+            .copied()
+            .unwrap_or_default()
     }
 
-    pub fn add_line(&mut self, line: Line) -> TreeNode {
-        let guid = line.guid.clone();
-        let name = line.name.clone();
-        let geometry = Geometry::Line(line.clone());
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Ray BVH Cache
+    ///////////////////////////////////////////////////////////////////////////////////////////
 
-        self.objects.lines.push(line);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::Line(l)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::Line(l.clone()));
+    /// Cache the boxes of an object's leaves in the lookup table, without copying the object.
+    fn cache_geometry_aabb(&mut self, guid: &str) {
+        let Some(geometry) = self.lookup.get(guid) else {
+            return;
+        };
+        let leaves = Self::leaf_boxes(geometry);
+        let mut indices = Vec::with_capacity(leaves.len());
+        for (bbox, chunk) in leaves {
+            let object_id = self.cached_boxes.len();
+            // A clean cache is updated in place, a dirty one is rebuilt on the next ray cast
+            match (&mut self.cached_ray_bvh, self.bvh_cache_dirty) {
+                (Some(bvh), false) => bvh.insert(&bbox, object_id),
+                _ => self.bvh_cache_dirty = true,
+            }
+            self.cached_boxes.push(bbox);
+            self.cached_guids.push(guid.to_string());
+            self.cached_chunks.push(chunk);
+            indices.push(object_id);
         }
-        self.graph.add_node(&guid, &format!("line_{name}"));
-
-        TreeNode::new(&guid)
+        self.cached_index.insert(guid.to_string(), indices);
     }
 
-    pub fn add_plane(&mut self, plane: Plane) -> TreeNode {
-        let guid = plane.guid.clone();
-        let name = plane.name.clone();
-        let geometry = Geometry::Plane(plane.clone());
-
-        self.objects.planes.push(plane);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::Plane(p)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::Plane(p.clone()));
+    fn uncache_geometry_aabb(&mut self, guid: &str) {
+        let Some(mut leaves) = self.cached_index.remove(guid) else {
+            self.invalidate_bvh_cache();
+            return;
+        };
+        // From the back, so no leaf of this object is moved into a freed index
+        leaves.sort_unstable();
+        for index in leaves.into_iter().rev() {
+            self.uncache_leaf(index);
         }
-        self.graph.add_node(&guid, &format!("plane_{name}"));
-
-        TreeNode::new(&guid)
     }
 
-    pub fn add_bbox(&mut self, bbox: BoundingBox) -> TreeNode {
-        let guid = bbox.guid.clone();
-        let name = bbox.name.clone();
-        let geometry = Geometry::BoundingBox(bbox.clone());
-
-        self.objects.bboxes.push(bbox);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::BoundingBox(b)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::BoundingBox(b.clone()));
+    /// Remove a leaf, moving the last leaf into the freed index to keep ids dense.
+    fn uncache_leaf(&mut self, index: usize) {
+        let last = self.cached_boxes.len() - 1;
+        if let (Some(bvh), false) = (&mut self.cached_ray_bvh, self.bvh_cache_dirty) {
+            bvh.remove(index);
+            if index != last {
+                bvh.remove(last);
+                bvh.insert(&self.cached_boxes[last], index);
+            }
+        }
+        self.cached_boxes.swap_remove(index);
+        self.cached_guids.swap_remove(index);
+        self.cached_chunks.swap_remove(index);
+        if let Some(moved) = self.cached_guids.get(index) {
+            for leaf in self.cached_index.get_mut(moved).into_iter().flatten() {
+                if *leaf == last {
+                    *leaf = index;
+                }
+            }
         }
-        self.graph.add_node(&guid, &format!("bbox_{name}"));
-
-        TreeNode::new(&guid)
     }
 
-    pub fn add_polyline(&mut self, polyline: Polyline) -> TreeNode {
-        let guid = polyline.guid.clone();
-        let name = polyline.name.clone();
-        let geometry = Geometry::Polyline(polyline.clone());
-
-        self.objects.polylines.push(polyline);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::Polyline(p)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::Polyline(p.clone()));
+    /// Move a mesh by setting its xform without touching its vertices.
+    ///
+    /// The ray cast BVH over scene objects is the top level and each mesh keeps
+    /// its own triangle BVH in local coordinates as the bottom level. Setting
+    /// the xform only updates the mesh's leaf in the top level, the triangle
+    /// BVH is reused and rays are transformed into the mesh's local frame.
+    ///
+    /// # Returns
+    /// `true` if the GUID refers to a mesh.
+    pub fn set_mesh_xform(&mut self, guid: &str, xform: Xform) -> bool {
+        if !matches!(
+            self.lookup.get(guid).map(Arc::as_ref),
+            Some(Geometry::Mesh(_))
+        ) {
+            return false;
+        }
+        self.set_transform(guid, xform, false)
+    }
+
+    /// Set the xform of a stored object and move it in the cached ray BVH.
+    ///
+    /// `get_geometry` already composes xforms along the tree, so propagation is
+    /// only needed when descendants must move in ray casts and spatial queries too.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the object
+    /// * `xform` - The new xform
+    /// * `propagate` - Also apply the change in xform to the objects below it in the tree
+    ///
+    /// # Returns
+    /// `false` if no object has the GUID.
+    pub fn set_transform(&mut self, guid: &str, xform: Xform, propagate: bool) -> bool {
+        let Some(geometry) = self.lookup.get_mut(guid) else {
+            return false;
+        };
+        let previous = std::mem::replace(Arc::make_mut(geometry).xform_mut(), xform.clone());
+        self.refresh_geometry_aabb(guid);
+        self.emit(SessionEvent::ObjectTransformed {
+            guid: guid.to_string(),
+            xform: xform.clone(),
+        });
+
+        if propagate {
+            let delta = match previous.inverse() {
+                Some(inverse) => &xform * &inverse,
+                None => return true,
+            };
+            let descendants = match self.tree.get_node_by_name(guid) {
+                Some(node) => node.descendants(),
+                None => return true,
+            };
+            for node in descendants {
+                let child = node.name();
+                let Some(current) = self.lookup.get(&child).map(|g| g.xform().clone()) else {
+                    continue;
+                };
+                self.set_transform(&child, &delta * &current, false);
+            }
+        }
+        true
+    }
+
+    /// Move a stored object, see `set_transform`.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the object
+    /// * `offset` - Translation applied after the current xform
+    /// * `propagate` - Also move the objects below it in the tree
+    pub fn translate(&mut self, guid: &str, offset: &crate::Vector, propagate: bool) -> bool {
+        let Some(current) = self.lookup.get(guid).map(|g| g.xform().clone()) else {
+            return false;
+        };
+        let translation = Xform::translation(offset.x(), offset.y(), offset.z());
+        self.set_transform(guid, &translation * &current, propagate)
+    }
+
+    /// Rotate a stored object about an axis through the world origin, see `set_transform`.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the object
+    /// * `axis` - Rotation axis
+    /// * `angle` - Rotation angle in radians
+    /// * `propagate` - Also rotate the objects below it in the tree
+    pub fn rotate(
+        &mut self,
+        guid: &str,
+        axis: &crate::Vector,
+        angle: Scalar,
+        propagate: bool,
+    ) -> bool {
+        let Some(current) = self.lookup.get(guid).map(|g| g.xform().clone()) else {
+            return false;
+        };
+        let rotation = Xform::rotation(axis, angle);
+        self.set_transform(guid, &rotation * &current, propagate)
+    }
+
+    /// Recompute an object's cached boxes and move its leaves in a clean ray BVH.
+    fn refresh_geometry_aabb(&mut self, guid: &str) {
+        let Some(geometry) = self.lookup.get(guid) else {
+            return;
+        };
+        let leaves = Self::leaf_boxes(geometry);
+        let Some(indices) = self.cached_index.get(guid).cloned() else {
+            self.invalidate_bvh_cache();
+            return;
+        };
+        // A point cloud that gained or lost chunks gets new leaves
+        if indices.len() != leaves.len() {
+            self.uncache_geometry_aabb(guid);
+            self.cache_geometry_aabb(guid);
+            return;
+        }
+        for (index, (bbox, chunk)) in indices.into_iter().zip(leaves) {
+            if let (Some(bvh), false) = (&mut self.cached_ray_bvh, self.bvh_cache_dirty) {
+                bvh.remove(index);
+                bvh.insert(&bbox, index);
+            }
+            self.cached_boxes[index] = bbox;
+            self.cached_chunks[index] = chunk;
+        }
+    }
+
+    fn rebuild_ray_bvh_cache(&mut self) {
+        if self.cached_index.len() != self.lookup.len() {
+            self.cached_boxes.clear();
+            self.cached_guids.clear();
+            self.cached_chunks.clear();
+            self.cached_index.clear();
+            self.cached_boxes.reserve(self.lookup.len());
+            self.cached_guids.reserve(self.lookup.len());
+            for (guid, geometry) in &self.lookup {
+                let mut indices = Vec::new();
+                for (bbox, chunk) in Self::leaf_boxes(geometry) {
+                    indices.push(self.cached_guids.len());
+                    self.cached_boxes.push(bbox);
+                    self.cached_guids.push(guid.clone());
+                    self.cached_chunks.push(chunk);
+                }
+                self.cached_index.insert(guid.clone(), indices);
+            }
+        }
+        if !self.cached_boxes.is_empty() {
+            let world_size = BVH::compute_world_size(&self.cached_boxes);
+            self.cached_ray_bvh = Some(BVH::from_boxes(&self.cached_boxes, world_size));
+        } else {
+            self.cached_ray_bvh = None;
+        }
+    }
+
+    fn invalidate_bvh_cache(&mut self) {
+        self.bvh_cache_dirty = true;
+    }
+
+    fn update_ray_bvh_cache(&mut self) {
+        if self.bvh_cache_dirty || self.cached_ray_bvh.is_none() {
+            self.rebuild_ray_bvh_cache();
+            self.bvh_cache_dirty = false;
+        }
+    }
+
+    /// Find objects whose bounding boxes overlap a box, e.g. for rectangular selection.
+    pub fn objects_in_box(&mut self, bbox: &BoundingBox) -> Vec<String> {
+        self.update_ray_bvh_cache();
+        match &self.cached_ray_bvh {
+            Some(bvh) => self.cached_guids_at(&bvh.query_box(bbox)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Find objects whose bounding boxes are within `radius` of a point.
+    ///
+    /// # Returns
+    /// GUIDs sorted from nearest to farthest box.
+    pub fn objects_in_sphere(&mut self, center: &Point, radius: Scalar) -> Vec<String> {
+        self.update_ray_bvh_cache();
+        match &self.cached_ray_bvh {
+            Some(bvh) => {
+                let indices: Vec<usize> = bvh
+                    .query_sphere(center, radius)
+                    .into_iter()
+                    .map(|(idx, _)| idx)
+                    .collect();
+                self.cached_guids_at(&indices)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Find the object closest to a point, measured to its bounding box.
+    ///
+    /// # Returns
+    /// GUID and distance, or None if the session is empty.
+    pub fn nearest_object(&mut self, point: &Point) -> Option<(String, Scalar)> {
+        self.closest_objects(point, 1).into_iter().next()
+    }
+
+    /// Find objects whose bounding boxes are inside or crossing a view frustum.
+    ///
+    /// # Arguments
+    /// * `planes` - Frustum planes with normals pointing into the frustum
+    pub fn objects_in_frustum(&mut self, planes: &[Plane; 6]) -> Vec<String> {
+        self.update_ray_bvh_cache();
+        match &self.cached_ray_bvh {
+            Some(bvh) => self.cached_guids_at(&bvh.query_frustum(planes)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Select objects by their bounding boxes against a view frustum, using the cached BVH.
+    ///
+    /// # Arguments
+    /// * `planes` - Frustum planes with normals pointing into the frustum
+    /// * `mode` - Whether objects crossing the frustum are selected
+    pub fn select_frustum(&mut self, planes: &[Plane; 6], mode: SelectionMode) -> Vec<String> {
+        self.update_ray_bvh_cache();
+        let classified = match &self.cached_ray_bvh {
+            Some(bvh) => bvh.query_frustum_classified(planes),
+            None => return Vec::new(),
+        };
+        // A point cloud is inside when all its chunk leaves are
+        let inside: HashSet<usize> = (classified.iter())
+            .filter(|&&(_, inside)| inside)
+            .map(|&(idx, _)| idx)
+            .collect();
+        let all_inside = |idx: usize| {
+            (self.cached_index.get(&self.cached_guids[idx]))
+                .is_some_and(|leaves| leaves.iter().all(|leaf| inside.contains(leaf)))
+        };
+        let indices: Vec<usize> = classified
+            .into_iter()
+            .filter(|&(idx, _)| mode == SelectionMode::Crossing || all_inside(idx))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.cached_guids_at(&indices)
+    }
+
+    /// Select objects inside a screen rectangle, for window selection in a viewport.
+    ///
+    /// The rectangle is given in normalized device coordinates, from -1 to 1 left to right
+    /// and bottom to top; a pixel (px, py) of a w x h viewport maps to
+    /// (2 px / w - 1, 1 - 2 py / h).
+    ///
+    /// # Arguments
+    /// * `view` - World to camera xform, e.g. from `Xform::look_at_rh`
+    /// * `projection` - Camera to clip space xform with depth from -1 to 1 (OpenGL convention)
+    /// * `rect` - Opposite corners [x0, y0, x1, y1] in normalized device coordinates
+    /// * `mode` - Whether objects crossing the rectangle are selected
+    pub fn select_rect(
+        &mut self,
+        view: &Xform,
+        projection: &Xform,
+        rect: [Scalar; 4],
+        mode: SelectionMode,
+    ) -> Vec<String> {
+        let clip = projection * view;
+        let row = |i: usize| [clip[(i, 0)], clip[(i, 1)], clip[(i, 2)], clip[(i, 3)]];
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        let (x_min, x_max) = (rect[0].min(rect[2]), rect[0].max(rect[2]));
+        let (y_min, y_max) = (rect[1].min(rect[3]), rect[1].max(rect[3]));
+        // Each clip-space bound such as x >= x_min * w is a plane through the view volume
+        let plane = |coefficients: [Scalar; 4]| {
+            let [a, b, c, d] = coefficients;
+            let normal = Vector::new(a, b, c);
+            let scale = -d / normal.dot(&normal);
+            Plane::from_point_normal(Point::new(a * scale, b * scale, c * scale), normal)
+        };
+        let bound = |p: [Scalar; 4], sign: Scalar, limit: Scalar| {
+            plane([0, 1, 2, 3].map(|i| sign * p[i] + limit * w[i]))
+        };
+        let planes = [
+            bound(x, 1.0, -x_min),
+            bound(x, -1.0, x_max),
+            bound(y, 1.0, -y_min),
+            bound(y, -1.0, y_max),
+            bound(z, 1.0, 1.0),
+            bound(z, -1.0, 1.0),
+        ];
+        self.select_frustum(&planes, mode)
+    }
+
+    /// Build an octree over all objects for level-of-detail streaming.
+    ///
+    /// Point clouds split into chunk leaves take one object per chunk, so
+    /// their chunks stream on their own.
+    ///
+    /// # Arguments
+    /// * `max_depth` - Maximum subdivision depth
+    /// * `max_objects` - Nodes with more objects than this are subdivided
+    ///
+    /// # Returns
+    /// Octree whose object ids index `object_guids`.
+    pub fn octree(&self, max_depth: usize, max_objects: usize) -> Octree {
+        let boxes_with_guids: Vec<(BoundingBox, String)> = self
+            .cached_boxes
+            .iter()
+            .cloned()
+            .zip(self.cached_guids.iter().cloned())
+            .collect();
+        Octree::from_boxes_with_guids(&boxes_with_guids, max_depth, max_objects)
+    }
+
+    /// GUIDs of leaves, each once in the order of its first leaf.
+    fn cached_guids_at(&self, indices: &[usize]) -> Vec<String> {
+        let mut seen = HashSet::new();
+        indices
+            .iter()
+            .filter_map(|&idx| self.cached_guids.get(idx))
+            .filter(|guid| seen.insert(guid.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Find the `k` objects closest to a point, e.g. the objects nearest to the cursor.
+    ///
+    /// Distances are measured to the objects' axis-aligned bounding boxes, so a
+    /// point inside a box is at distance zero.
+    ///
+    /// # Arguments
+    /// * `point` - Query point
+    /// * `k` - Maximum number of objects to return
+    ///
+    /// # Returns
+    /// GUIDs and distances sorted from nearest to farthest.
+    pub fn closest_objects(&mut self, point: &Point, k: usize) -> Vec<(String, Scalar)> {
+        self.update_ray_bvh_cache();
+        let bvh = match &self.cached_ray_bvh {
+            Some(b) => b,
+            None => return Vec::new(),
+        };
+        // Chunk leaves of point clouds could take the places of other objects
+        let extra = self
+            .cached_boxes
+            .len()
+            .saturating_sub(self.cached_index.len());
+        let mut seen = HashSet::new();
+        bvh.knn(point, k.saturating_add(extra))
+            .into_iter()
+            .filter_map(|(idx, distance)| {
+                self.cached_guids
+                    .get(idx)
+                    .filter(|guid| seen.insert(guid.as_str()))
+                    .map(|guid| (guid.clone(), distance))
+            })
+            .take(k)
+            .collect()
+    }
+
+    pub fn ray_cast(
+        &mut self,
+        origin: &Point,
+        direction: &crate::Vector,
+        tolerance: Scalar,
+    ) -> Vec<RayHit> {
+        self.ray_cast_with(origin, direction, tolerance, &RayCastOptions::default())
+    }
+
+    /// Ray cast against objects whose collision group shares a bit with `mask`.
+    ///
+    /// # Arguments
+    /// * `origin` - Ray origin
+    /// * `direction` - Ray direction, need not be unit length
+    /// * `tolerance` - Distance within which hits count as equally close
+    /// * `mask` - Collision groups the ray can hit
+    ///
+    /// # Returns
+    /// The closest hits, several if they are within `tolerance` of each other.
+    pub fn ray_cast_masked(
+        &mut self,
+        origin: &Point,
+        direction: &crate::Vector,
+        tolerance: Scalar,
+        mask: u32,
+    ) -> Vec<RayHit> {
+        let options = RayCastOptions {
+            mask,
+            ..Default::default()
+        };
+        self.ray_cast_with(origin, direction, tolerance, &options)
+    }
+
+    /// Ray cast with a choice of which hits to report, e.g. to cycle through
+    /// objects hidden behind the first hit when picking.
+    ///
+    /// # Arguments
+    /// * `origin` - Ray origin
+    /// * `direction` - Ray direction, need not be unit length
+    /// * `tolerance` - Distance within which hits count as equally close
+    /// * `options` - Hit mode, maximum distance, type filter and collision mask
+    ///
+    /// # Returns
+    /// Hits sorted from nearest to farthest.
+    pub fn ray_cast_with(
+        &mut self,
+        origin: &Point,
+        direction: &crate::Vector,
+        tolerance: Scalar,
+        options: &RayCastOptions,
+    ) -> Vec<RayHit> {
+        let dir_len = direction.compute_length();
+        if dir_len <= 0.0 {
+            return Vec::new();
+        }
+        let dir_unit = crate::Vector::new(
+            direction.x() / dir_len,
+            direction.y() / dir_len,
+            direction.z() / dir_len,
+        );
+
+        let far: Scalar = 1e6;
+        let ray_end = Point::new(
+            origin.x() + dir_unit.x() * far,
+            origin.y() + dir_unit.y() * far,
+            origin.z() + dir_unit.z() * far,
+        );
+        let ray_line = Line::from_points(origin, &ray_end);
+
+        // Use cached BVH for ray casting
+        self.update_ray_bvh_cache();
+        let bvh = match &self.cached_ray_bvh {
+            Some(b) => b,
+            None => return Vec::new(),
+        };
+
+        // Candidates come sorted by the distance where the ray enters their boxes
+        let candidates = bvh.ray_cast_entries(origin, &dir_unit);
+
+        let hidden: HashSet<String> = self
+            .groups
+            .values()
+            .filter(|group| !group.visible)
+            .flat_map(|group| group.members.iter().cloned())
+            .chain(
+                (self.display.iter())
+                    .filter(|(_, state)| !state.visible)
+                    .map(|(guid, _)| guid.clone()),
+            )
+            .collect();
+
+        let mut hits_all: Vec<RayHit> = Vec::new();
+        let mut min_d = Scalar::INFINITY;
+        // Index in hits_all of the hit of each point cloud split into chunk leaves
+        let mut chunked_hits: HashMap<String, usize> = HashMap::new();
+
+        for (idx, entry) in candidates {
+            if entry > options.max_distance {
+                break;
+            }
+            // Every remaining box starts beyond the closest hit and its ties
+            if options.mode == RayCastMode::Nearest && entry > min_d + tolerance {
+                break;
+            }
+            if idx >= self.cached_guids.len() {
+                continue;
+            }
+            let guid = self.cached_guids[idx].clone();
+            let chunk = self.cached_chunks[idx].clone();
+            if self.collision_filter(&guid).group & options.mask == 0 || hidden.contains(&guid) {
+                continue;
+            }
+            let geom: &Geometry = match self.lookup.get(&guid) {
+                Some(g) => g,
+                None => continue,
+            };
+            if options.type_filter.is_some_and(|filter| !filter(geom)) {
+                continue;
+            }
+            // The ray is moved into the object's frame, so no object is copied per ray,
+            // and triangle BVHs and kd-trees fill their caches through shared references
+            let xform = geom.xform().clone();
+            let Some(ray) = LocalRay::new(&xform, origin, &dir_unit, far, tolerance) else {
+                continue;
+            };
+            let hit_points: Vec<Point> = match geom {
+                // Meshes move the ray into their frame themselves
+                Geometry::Mesh(m) => match options.mode {
+                    RayCastMode::All => m.ray_cast_all_instance(&ray_line, 1e-6),
+                    _ => m.ray_cast_instance(&ray_line, 1e-6).into_iter().collect(),
+                },
+                // A chunk leaf tests its own points, a whole cloud its kd-tree
+                // built once in its local frame
+                Geometry::PointCloud(pc) => match chunk.clone() {
+                    Some(range) => {
+                        pc.ray_cast_range(range, &ray.origin, &ray.direction, ray.tolerance)
+                    }
+                    None => pc.ray_cast(&ray.origin, &ray.direction, ray.tolerance),
+                }
+                .map(|(_, t)| {
+                    let local = ray.origin.clone() + ray.direction.clone() * t;
+                    xform.transformed_point(&local)
+                })
+                .into_iter()
+                .collect(),
+                other => Self::local_ray_hits(other, &ray, far)
+                    .iter()
+                    .map(|point| xform.transformed_point(point))
+                    .collect(),
+            };
+
+            let mut object_hits: Vec<RayHit> = hit_points
+                .into_iter()
+                .filter_map(|hp| {
+                    let dx = hp.x() - origin.x();
+                    let dy = hp.y() - origin.y();
+                    let dz = hp.z() - origin.z();
+                    let forward = dx * dir_unit.x() + dy * dir_unit.y() + dz * dir_unit.z();
+                    let dist = (dx * dx + dy * dy + dz * dz).sqrt();
+                    (forward >= 0.0 && dist <= options.max_distance).then(|| RayHit {
+                        guid: guid.clone(),
+                        point: hp,
+                        distance: dist,
+                    })
+                })
+                .collect();
+            object_hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+            if options.mode != RayCastMode::All {
+                object_hits.truncate(1);
+            }
+            if let Some(first) = object_hits.first() {
+                min_d = min_d.min(first.distance);
+            }
+            // A point cloud keeps its nearest hit over all its chunks
+            if let (Some(_), Some(hit)) = (&chunk, object_hits.first()) {
+                match chunked_hits.get(&guid) {
+                    Some(&at) if hits_all[at].distance <= hit.distance => {}
+                    Some(&at) => hits_all[at] = hit.clone(),
+                    None => {
+                        chunked_hits.insert(guid, hits_all.len());
+                        hits_all.push(hit.clone());
+                    }
+                }
+                continue;
+            }
+            hits_all.extend(object_hits);
+        }
+
+        if options.mode == RayCastMode::Nearest {
+            hits_all.retain(|h| (h.distance - min_d).abs() <= tolerance);
+        }
+        hits_all.sort_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        hits_all
+    }
+
+    /// Points where a ray hits an object, both in the object's local frame.
+    ///
+    /// Meshes and point clouds are cast against their cached trees instead.
+    fn local_ray_hits(geometry: &Geometry, ray: &LocalRay, far: Scalar) -> Vec<Point> {
+        let origin = &ray.origin;
+        let dir_unit = &ray.direction;
+        let ray_line = &ray.line;
+        match geometry {
+            Geometry::BoundingBox(bb) => {
+                crate::intersection::ray_box(ray_line, bb, 0.0, far).unwrap_or_default()
+            }
+            Geometry::Plane(pl) => pl
+                .intersect_ray(origin, dir_unit)
+                .filter(|t| (0.0..=ray.length).contains(t))
+                .map(|t| origin.clone() + dir_unit.clone() * t)
+                .into_iter()
+                .collect(),
+            Geometry::Line(l) => {
+                crate::intersection::line_line(ray_line, l, Tolerance::APPROXIMATION)
+                    .into_iter()
+                    .collect()
+            }
+            Geometry::Polyline(pl) => pl
+                .points
+                .windows(2)
+                .filter_map(|w| {
+                    let seg = Line::from_points(&w[0], &w[1]);
+                    crate::intersection::line_line(ray_line, &seg, Tolerance::APPROXIMATION)
+                })
+                .collect(),
+            Geometry::Cylinder(cy) => {
+                crate::intersection::ray_cylinder(ray_line, cy).unwrap_or_default()
+            }
+            Geometry::Arrow(ar) => {
+                // Arrow mesh: body over 80% of the line, cone head with 1.5x radius
+                let start = ar.line.start();
+                let neck = ar.line.point_at(0.8);
+                let body = Line::from_points(&start, &neck);
+                let mut ts = crate::intersection::ray_capped_cylinder(ray_line, &body, ar.radius);
+                ts.extend(crate::intersection::ray_capped_cone(
+                    ray_line,
+                    &neck,
+                    &ar.line.end(),
+                    ar.radius * 1.5,
+                ));
+                ts.into_iter()
+                    .filter(|t| *t >= 0.0)
+                    .map(|t| ray_line.point_at(t))
+                    .collect()
+            }
+            Geometry::Point(p) => {
+                let vx = p.x() - origin.x();
+                let vy = p.y() - origin.y();
+                let vz = p.z() - origin.z();
+                let cross_x = vy * dir_unit.z() - vz * dir_unit.y();
+                let cross_y = vz * dir_unit.x() - vx * dir_unit.z();
+                let cross_z = vx * dir_unit.y() - vy * dir_unit.x();
+                let dist = (cross_x * cross_x + cross_y * cross_y + cross_z * cross_z).sqrt();
+                let t = vx * dir_unit.x() + vy * dir_unit.y() + vz * dir_unit.z();
+                if dist <= ray.tolerance && t >= 0.0 {
+                    vec![Point::new(
+                        origin.x() + dir_unit.x() * t,
+                        origin.y() + dir_unit.y() * t,
+                        origin.z() + dir_unit.z() * t,
+                    )]
+                } else {
+                    Vec::new()
+                }
+            }
+            Geometry::Mesh(_) | Geometry::PointCloud(_) => Vec::new(),
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Details
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Adds a point to the Session.
+    ///
+    /// The point is added to the objects collection, lookup table, graph as a node,
+    /// and tree as a child of the root node.
+    ///
+    /// # Arguments
+    /// * `point` - The Point object to add to the session
+    ///
+    /// # Returns
+    /// The TreeNode created for this point
+    pub fn add_point(&mut self, point: Point) -> TreeNode {
+        let point_name = point.name.clone();
+
+        let point_guid = self.insert_object(Geometry::Point(point));
+        self.add_graph_node(&point_guid, &format!("point_{point_name}"));
+        self.emit(SessionEvent::ObjectAdded {
+            guid: point_guid.clone(),
+        });
+
+        TreeNode::new(&point_guid)
+    }
+
+    pub fn add_line(&mut self, line: Line) -> TreeNode {
+        let name = line.name.clone();
+
+        let guid = self.insert_object(Geometry::Line(line));
+        self.add_graph_node(&guid, &format!("line_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    pub fn add_plane(&mut self, plane: Plane) -> TreeNode {
+        let name = plane.name.clone();
+
+        let guid = self.insert_object(Geometry::Plane(plane));
+        self.add_graph_node(&guid, &format!("plane_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    pub fn add_bbox(&mut self, bbox: BoundingBox) -> TreeNode {
+        let name = bbox.name.clone();
+
+        let guid = self.insert_object(Geometry::BoundingBox(bbox));
+        self.add_graph_node(&guid, &format!("bbox_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    pub fn add_polyline(&mut self, polyline: Polyline) -> TreeNode {
+        let name = polyline.name.clone();
+
+        let guid = self.insert_object(Geometry::Polyline(polyline));
+        self.add_graph_node(&guid, &format!("polyline_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    pub fn add_pointcloud(&mut self, pointcloud: PointCloud) -> TreeNode {
+        let name = pointcloud.name.clone();
+
+        let guid = self.insert_object(Geometry::PointCloud(pointcloud));
+        self.add_graph_node(&guid, &format!("pointcloud_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    pub fn add_mesh(&mut self, mesh: Mesh) -> TreeNode {
+        let name = mesh.name.clone();
+
+        let guid = self.insert_object(Geometry::Mesh(mesh));
+        self.add_graph_node(&guid, &format!("mesh_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    pub fn add_cylinder(&mut self, cylinder: Cylinder) -> TreeNode {
+        let name = cylinder.name.clone();
+
+        let guid = self.insert_object(Geometry::Cylinder(cylinder));
+        self.add_graph_node(&guid, &format!("cylinder_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    pub fn add_arrow(&mut self, arrow: Arrow) -> TreeNode {
+        let name = arrow.name.clone();
+
+        let guid = self.insert_object(Geometry::Arrow(arrow));
+        self.add_graph_node(&guid, &format!("arrow_{name}"));
+        self.emit(SessionEvent::ObjectAdded { guid: guid.clone() });
+
+        TreeNode::new(&guid)
+    }
+
+    /// Adds a TreeNode to the tree hierarchy.
+    ///
+    /// # Arguments
+    /// * `node` - The TreeNode to add
+    /// * `parent` - Optional parent TreeNode (defaults to root if None)
+    pub fn add<'a>(&mut self, node: &TreeNode, parent: impl Into<Option<&'a TreeNode>>)
+    where
+        TreeNode: 'a,
+    {
+        let parent_opt = parent.into();
+        // Nodes without a parent are new to the tree, moved nodes keep their GUIDs
+        if node.parent().is_none() {
+            self.identify_nodes(node);
+        }
+        if parent_opt.is_none() {
+            if let Some(root) = self.tree.root() {
+                self.tree.add(node, Some(&root));
+            }
+        } else {
+            self.tree.add(node, parent_opt);
+        }
+    }
+
+    /// Adds an edge between two geometry objects in the graph.
+    ///
+    /// # Arguments
+    /// * `from_guid` - The GUID of the source object
+    /// * `to_guid` - The GUID of the target object
+    /// * `attribute` - The attribute or label for the edge
+    pub fn add_edge(&mut self, from_guid: &str, to_guid: &str, attribute: &str) {
+        self.add_graph_edge(from_guid, to_guid, attribute);
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Details - Lookup
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Gets a geometry object by its GUID.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the object to retrieve
+    ///
+    /// # Returns
+    /// An Option containing a reference to the Geometry enum if found, or None if not found.
+    pub fn get_object(&self, guid: &str) -> Option<&Geometry> {
+        self.lookup.get(guid).map(Arc::as_ref)
+    }
+
+    /// All objects in the order they were added.
+    ///
+    /// Replaces the former public `objects` field, which was removed when the objects moved
+    /// into shared `lookup` entries. Add objects with the `add_*` methods instead of pushing.
+    pub fn objects(&self) -> impl Iterator<Item = &Geometry> {
+        self.iter_objects().map(|(_, geometry)| geometry)
+    }
+
+    /// All objects with their GUIDs in the order they were added.
+    pub fn iter_objects(&self) -> impl Iterator<Item = (&str, &Geometry)> {
+        self.order.iter().filter_map(|guid| {
+            self.lookup
+                .get(guid)
+                .map(|geometry| (guid.as_str(), geometry.as_ref()))
+        })
+    }
+
+    /// Objects of one geometry type in the order they were added, e.g. `objects_of::<Mesh>()`.
+    pub fn objects_of<T: GeometryType>(&self) -> Vec<&T> {
+        self.objects().filter_map(T::from_geometry).collect()
+    }
+
+    /// The objects in the `Objects` layout for saving, borrowing the geometry.
+    fn objects_ref(&self) -> ObjectsRef<'_> {
+        let mut objects = ObjectsRef {
+            guid: &self.order.guid,
+            name: &self.order.name,
+            points: Vec::new(),
+            lines: Vec::new(),
+            planes: Vec::new(),
+            bboxes: Vec::new(),
+            polylines: Vec::new(),
+            pointclouds: Vec::new(),
+            meshes: Vec::new(),
+            cylinders: Vec::new(),
+            arrows: Vec::new(),
+        };
+        for geometry in self.objects() {
+            match geometry {
+                Geometry::Arrow(g) => objects.arrows.push(g),
+                Geometry::BoundingBox(g) => objects.bboxes.push(g),
+                Geometry::Cylinder(g) => objects.cylinders.push(g),
+                Geometry::Line(g) => objects.lines.push(g),
+                Geometry::Mesh(g) => objects.meshes.push(g),
+                Geometry::Plane(g) => objects.planes.push(g),
+                Geometry::Point(g) => objects.points.push(g),
+                Geometry::PointCloud(g) => objects.pointclouds.push(g),
+                Geometry::Polyline(g) => objects.polylines.push(g),
+            }
+        }
+        objects
+    }
+
+    /// Remove a geometry object by its GUID.
+    ///
+    /// The tree children of the object are handled by `removal_policy`.
+    ///
+    /// # Arguments
+    /// * `guid` - The UUID of the geometry object to remove.
+    ///
+    /// # Returns
+    /// `true` if the object was removed, `false` if not found.
+    pub fn remove_object(&mut self, guid: &str) -> bool {
+        self.remove_object_with(guid, self.removal_policy).is_some()
+    }
+
+    /// Remove a geometry object, handling its tree children with a policy.
+    ///
+    /// Reparented children keep their placement in `get_geometry`: the node
+    /// and geometry xforms of the removed object are folded into their node xforms.
+    ///
+    /// # Arguments
+    /// * `guid` - The UUID of the geometry object to remove
+    /// * `policy` - What to do with the tree children of the object
+    ///
+    /// # Returns
+    /// The removed objects and affected tree nodes, None if the object is not found.
+    pub fn remove_object_with(
+        &mut self,
+        guid: &str,
+        policy: RemovalPolicy,
+    ) -> Option<RemovalReport> {
+        let carried = self.lookup.get(guid)?.xform().clone();
+        let mut report = RemovalReport {
+            removed: vec![guid.to_string()],
+            ..Default::default()
+        };
+
+        if let Some(node) = self.tree.get_node_by_name(guid) {
+            let parent = node.parent();
+            let index = node.sibling_index().unwrap_or(0);
+            self.tree.remove(&node);
+            let children = node.children();
+            match (policy, parent) {
+                (RemovalPolicy::Reparent, Some(parent)) => {
+                    let carried = match node.xform() {
+                        Some(xform) => &xform * &carried,
+                        None => carried,
+                    };
+                    for (offset, child) in children.iter().enumerate() {
+                        node.remove(child);
+                        if carried.m != Xform::identity().m {
+                            let local = child.xform().unwrap_or_else(Xform::identity);
+                            child.set_xform(Some(&carried * &local));
+                        }
+                        parent.insert(index + offset, child);
+                        report.reparented.push(child.name());
+                    }
+                }
+                (RemovalPolicy::DeleteSubtree, _) => {
+                    for descendant in node.nodes().into_iter().skip(1) {
+                        let name = descendant.name();
+                        if self.lookup.contains_key(&name) && !report.removed.contains(&name) {
+                            self.remove_geometry(&name);
+                            report.removed.push(name);
+                        }
+                    }
+                }
+                _ => report.detached = children.iter().map(TreeNode::name).collect(),
+            }
+        }
+
+        self.remove_geometry(guid);
+        Some(report)
+    }
+
+    /// Remove an object from the collections, caches and graph, but not the tree.
+    fn remove_geometry(&mut self, guid: &str) {
+        self.order.remove(guid);
+        self.attributes.remove(guid);
+        self.collision_filters.remove(guid);
+        self.display.remove(guid);
+        self.animations.remove(guid);
+        for group in self.groups.values_mut() {
+            group.members.retain(|member| member != guid);
+        }
+
+        // Remove from lookup table
+        self.lookup.remove(guid);
+        self.uncache_geometry_aabb(guid);
+        self.emit(SessionEvent::ObjectRemoved {
+            guid: guid.to_string(),
+        });
+
+        // Remove from graph using string GUID
+        if self.graph.has_node(guid) {
+            self.graph.remove_node(guid);
+        }
+    }
+
+    /// Gets a geometry object by its GUID for editing.
+    ///
+    /// The returned guard dereferences to the Geometry in the lookup table,
+    /// which is copied first if a snapshot shares it. When it is dropped the
+    /// cached bounding box is refreshed. The GUID must not be changed.
+    ///
+    /// # Returns
+    /// None if no object has the GUID.
+    pub fn get_object_mut(&mut self, guid: &str) -> Option<GeometryMut<'_>> {
+        if !self.lookup.contains_key(guid) {
+            return None;
+        }
+        Some(GeometryMut {
+            session: self,
+            guid: guid.to_string(),
+        })
+    }
+
+    /// Replace a geometry object, keeping its tree node and graph edges.
+    ///
+    /// The geometry may be of a different type than the one it replaces.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the object to replace
+    /// * `geometry` - The new geometry, must have the same GUID
+    ///
+    /// # Returns
+    /// `false` if no object has the GUID or the GUIDs differ.
+    pub fn update_object(&mut self, guid: &str, geometry: Geometry) -> bool {
+        if geometry.guid() != guid || !self.lookup.contains_key(guid) {
+            return false;
+        }
+        self.lookup.insert(guid.to_string(), Arc::new(geometry));
+        self.sync_object(guid);
+        true
+    }
+
+    /// Refresh the cached box of an object edited in place and notify listeners.
+    fn sync_object(&mut self, guid: &str) {
+        self.refresh_geometry_aabb(guid);
+        self.emit(SessionEvent::ObjectModified {
+            guid: guid.to_string(),
+        });
+    }
+
+    /// Put an object into the lookup table and the object order and cache its box.
+    ///
+    /// An object with the GUID of a stored one replaces it in place.
+    /// Store an object, with GUIDs from the generator if it is new, and return its GUID.
+    fn insert_object(&mut self, geometry: Geometry) -> String {
+        let geometry = self.identify(geometry);
+        let guid = geometry.guid().to_string();
+        let replaced = self.lookup.insert(guid.clone(), Arc::new(geometry));
+        self.order.push(&guid);
+        if replaced.is_some() {
+            self.refresh_geometry_aabb(&guid);
+        } else {
+            self.cache_geometry_aabb(&guid);
+        }
+        guid
+    }
+
+    /// Give an object that is not in the session yet GUIDs from a non-random generator.
+    ///
+    /// The object GUID is created from the serialized object without its GUIDs,
+    /// the GUIDs nested in it are derived from the object GUID in serialization order.
+    fn identify(&mut self, geometry: Geometry) -> Geometry {
+        if self.ids.generator() == IdGenerator::Random || self.lookup.contains_key(geometry.guid())
+        {
+            return geometry;
+        }
+        let Ok(mut value) = serde_json::to_value(&geometry) else {
+            return geometry;
+        };
+        replace_guids(&mut value, &mut || String::new());
+        let content = serde_json::to_vec(&value).unwrap_or_default();
+        let guid = self.ids.next(&content);
+        let mut index = 0;
+        replace_guids(&mut value, &mut || {
+            index += 1;
+            crate::guid::derived_guid(&guid, index)
+        });
+        match serde_json::from_value::<Geometry>(value) {
+            Ok(mut identified) => {
+                *identified.guid_mut() = guid;
+                identified
+            }
+            Err(_) => geometry,
+        }
+    }
+
+    /// Give a tree node and the nodes below it GUIDs from a non-random generator.
+    fn identify_nodes(&mut self, node: &TreeNode) {
+        if self.ids.generator() == IdGenerator::Random {
+            return;
+        }
+        for node in node.nodes() {
+            node.set_guid(self.ids.next(format!("node {}", node.name()).as_bytes()));
+        }
+    }
+
+    fn push_object(objects: &mut Objects, geometry: Geometry) {
+        match geometry {
+            Geometry::Arrow(g) => objects.arrows.push(g),
+            Geometry::BoundingBox(g) => objects.bboxes.push(g),
+            Geometry::Cylinder(g) => objects.cylinders.push(g),
+            Geometry::Line(g) => objects.lines.push(g),
+            Geometry::Mesh(g) => objects.meshes.push(g),
+            Geometry::Plane(g) => objects.planes.push(g),
+            Geometry::Point(g) => objects.points.push(g),
+            Geometry::PointCloud(g) => objects.pointclouds.push(g),
+            Geometry::Polyline(g) => objects.polylines.push(g),
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Diff and Merge
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Compare this session with another one.
+    ///
+    /// Objects are compared by their serialized data. The tree hierarchy is
+    /// not compared.
+    ///
+    /// # Returns
+    /// The changes that turn this session into `other`, sorted by GUID.
+    pub fn diff(&self, other: &Session) -> SessionDelta {
+        let mut delta = SessionDelta::default();
+
+        for (guid, theirs) in &other.lookup {
+            match self.lookup.get(guid) {
+                None => delta.added.push(Geometry::clone(theirs)),
+                Some(ours) => {
+                    if Self::geometry_value(ours) != Self::geometry_value(theirs) {
+                        delta.modified.push(Geometry::clone(theirs));
+                    }
+                }
+            }
+        }
+        delta.removed = self
+            .lookup
+            .keys()
+            .filter(|guid| !other.lookup.contains_key(*guid))
+            .cloned()
+            .collect();
+
+        let our_edges = Self::edge_attributes(&self.graph);
+        let their_edges = Self::edge_attributes(&other.graph);
+        for ((u, v), attribute) in &their_edges {
+            if our_edges.get(&(u.clone(), v.clone())) != Some(attribute) {
+                delta
+                    .edges_added
+                    .push((u.clone(), v.clone(), attribute.clone()));
+            }
+        }
+        delta.edges_removed = our_edges
+            .keys()
+            .filter(|edge| !their_edges.contains_key(*edge))
+            .cloned()
+            .collect();
+
+        delta.added.sort_by(|a, b| a.guid().cmp(b.guid()));
+        delta.modified.sort_by(|a, b| a.guid().cmp(b.guid()));
+        delta.removed.sort();
+        delta.edges_added.sort();
+        delta.edges_removed.sort();
+        delta
+    }
+
+    /// Apply a delta produced by `diff`.
+    ///
+    /// Added objects are placed under the tree root.
+    ///
+    /// # Arguments
+    /// * `delta` - Changes to apply
+    /// * `strategy` - Whether removals and modifications are applied as well
+    pub fn merge(&mut self, delta: &SessionDelta, strategy: MergeStrategy) {
+        for geometry in &delta.added {
+            if !self.lookup.contains_key(geometry.guid()) {
+                let node = self.add_geometry(geometry.clone());
+                self.add(&node, None);
+            }
+        }
+        for (u, v, attribute) in &delta.edges_added {
+            if !self.graph.has_edge((u, v)) {
+                self.add_edge(u, v, attribute);
+            } else if strategy == MergeStrategy::Theirs {
+                self.graph.edge_attribute(u, v, Some(attribute));
+            }
+        }
+        if strategy == MergeStrategy::Ours {
+            return;
+        }
+
+        for geometry in &delta.modified {
+            self.update_object(geometry.guid(), geometry.clone());
+        }
+        for guid in &delta.removed {
+            self.remove_object(guid);
+        }
+        for (u, v) in &delta.edges_removed {
+            self.graph.remove_edge((u, v));
+        }
+    }
+
+    fn add_geometry(&mut self, geometry: Geometry) -> TreeNode {
+        match geometry {
+            Geometry::Arrow(g) => self.add_arrow(g),
+            Geometry::BoundingBox(g) => self.add_bbox(g),
+            Geometry::Cylinder(g) => self.add_cylinder(g),
+            Geometry::Line(g) => self.add_line(g),
+            Geometry::Mesh(g) => self.add_mesh(g),
+            Geometry::Plane(g) => self.add_plane(g),
+            Geometry::Point(g) => self.add_point(g),
+            Geometry::PointCloud(g) => self.add_pointcloud(g),
+            Geometry::Polyline(g) => self.add_polyline(g),
+        }
+    }
+
+    fn geometry_value(geometry: &Geometry) -> Option<serde_json::Value> {
+        match geometry {
+            Geometry::Arrow(g) => serde_json::to_value(g),
+            Geometry::BoundingBox(g) => serde_json::to_value(g),
+            Geometry::Cylinder(g) => serde_json::to_value(g),
+            Geometry::Line(g) => serde_json::to_value(g),
+            Geometry::Mesh(g) => serde_json::to_value(g),
+            Geometry::Plane(g) => serde_json::to_value(g),
+            Geometry::Point(g) => serde_json::to_value(g),
+            Geometry::PointCloud(g) => serde_json::to_value(g),
+            Geometry::Polyline(g) => serde_json::to_value(g),
+        }
+        .ok()
+    }
+
+    /// Graph edges keyed by (u, v) with u < v.
+    fn edge_attributes(graph: &Graph) -> HashMap<(String, String), String> {
+        let mut edges = HashMap::new();
+        for (u, neighbors) in &graph.edges {
+            for (v, edge) in neighbors {
+                if u < v {
+                    edges.insert((u.clone(), v.clone()), edge.attribute.clone());
+                }
+            }
+        }
+        edges
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Change Events
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Register a callback that is called after every change to the session.
+    ///
+    /// Undo, redo and rollback replace the whole state and emit no events.
+    ///
+    /// # Returns
+    /// An id that can be passed to `remove_listener`.
+    pub fn on_change<F>(&mut self, callback: F) -> usize
+    where
+        F: FnMut(&SessionEvent) + 'static,
+    {
+        let id = self.listeners.next_id;
+        self.listeners.next_id += 1;
+        self.listeners.callbacks.push((id, Box::new(callback)));
+        id
+    }
+
+    /// Unregister a callback added with `on_change`.
+    ///
+    /// # Returns
+    /// `false` if no callback has the id.
+    pub fn remove_listener(&mut self, id: usize) -> bool {
+        let count = self.listeners.callbacks.len();
+        self.listeners.callbacks.retain(|(i, _)| *i != id);
+        self.listeners.callbacks.len() != count
+    }
+
+    fn emit(&mut self, event: SessionEvent) {
+        for (_, callback) in &mut self.listeners.callbacks {
+            callback(&event);
+        }
+        if self.log.writer.is_some() {
+            if let Some(entry) = Self::log_entry(&self.lookup, event) {
+                self.log.write(&entry);
+            }
+        }
+    }
+
+    /// Add a graph vertex for an object, with a GUID from a non-random generator.
+    fn add_graph_node(&mut self, guid: &str, attribute: &str) {
+        if self.graph.has_node(guid) {
+            return;
+        }
+        self.graph.add_node(guid, attribute);
+        if self.ids.generator() != IdGenerator::Random {
+            let vertex_guid = self.ids.next(format!("vertex {guid}").as_bytes());
+            self.graph.set_node_guid(guid, &vertex_guid);
+        }
+    }
+
+    fn add_graph_edge(&mut self, from_guid: &str, to_guid: &str, attribute: &str) {
+        let new_edge = !self.graph.has_edge((from_guid, to_guid));
+        self.add_graph_node(from_guid, "");
+        self.add_graph_node(to_guid, "");
+        self.graph.add_edge(from_guid, to_guid, attribute);
+        if new_edge && self.ids.generator() != IdGenerator::Random {
+            let content = format!("edge {from_guid} {to_guid} {attribute}");
+            let edge_guid = self.ids.next(content.as_bytes());
+            self.graph.set_edge_guid(from_guid, to_guid, &edge_guid);
+        }
+        self.emit(SessionEvent::EdgeAdded {
+            from: from_guid.to_string(),
+            to: to_guid.to_string(),
+            attribute: attribute.to_string(),
+        });
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Details - Queries
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Find objects whose name matches a glob pattern, e.g. `"beam_*"`.
+    ///
+    /// # Returns
+    /// Sorted GUIDs of the matching objects.
+    pub fn find_by_name(&self, pattern: &str) -> Vec<String> {
+        self.query(&Query::new().name(pattern))
+    }
+
+    /// Find all objects of one geometry type, e.g. `find_by_type::<Mesh>()`.
+    ///
+    /// # Returns
+    /// Sorted GUIDs of the matching objects.
+    pub fn find_by_type<T: GeometryType>(&self) -> Vec<String> {
+        self.query(&Query::new().of_type::<T>())
+    }
+
+    /// Find objects matching every condition of a query.
+    ///
+    /// # Returns
+    /// Sorted GUIDs of the matching objects.
+    pub fn query(&self, query: &Query) -> Vec<String> {
+        let mut guids: Vec<String> = self
+            .lookup
+            .iter()
+            .filter(|(guid, geometry)| {
+                query.type_filter().is_none_or(|is_type| is_type(geometry))
+                    && query
+                        .name_pattern()
+                        .is_none_or(|pattern| glob_match(pattern, geometry.name()))
+                    && query.layer_name().is_none_or(|layer| {
+                        matches!(
+                            self.get_attribute(guid, "layer"),
+                            Some(AttrValue::String(value)) if value == layer
+                        )
+                    })
+                    && query.region().is_none_or(|region| {
+                        Self::compute_bounding_box(geometry).collides_with(region)
+                    })
+            })
+            .map(|(guid, _)| guid.clone())
+            .collect();
+        guids.sort();
+        guids
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Details - Attributes
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Attach a key-value attribute to an object, replacing any previous value.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the geometry object
+    /// * `key` - Attribute name
+    /// * `value` - Attribute value, e.g. `"oak".into()` or `12.5.into()`
+    ///
+    /// # Returns
+    /// `false` if no object has the GUID.
+    pub fn set_attribute(&mut self, guid: &str, key: &str, value: AttrValue) -> bool {
+        if !self.lookup.contains_key(guid) {
+            return false;
+        }
+        self.attributes
+            .entry(guid.to_string())
+            .or_default()
+            .insert(key.to_string(), value);
+        true
+    }
+
+    pub fn get_attribute(&self, guid: &str, key: &str) -> Option<&AttrValue> {
+        self.attributes.get(guid)?.get(key)
+    }
+
+    /// Remove an attribute from an object.
+    ///
+    /// # Returns
+    /// The removed value, or None if it was not set.
+    pub fn remove_attribute(&mut self, guid: &str, key: &str) -> Option<AttrValue> {
+        let values = self.attributes.get_mut(guid)?;
+        let value = values.remove(key);
+        if values.is_empty() {
+            self.attributes.remove(guid);
+        }
+        value
+    }
+
+    /// Get all attributes of an object.
+    pub fn get_attributes(&self, guid: &str) -> Option<&HashMap<String, AttrValue>> {
+        self.attributes.get(guid)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Statistics
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Count objects, vertices and faces and estimate memory use.
+    ///
+    /// Memory is estimated from collection sizes and capacities, without
+    /// allocator overhead, and is meant for monitoring rather than exact accounting.
+    pub fn stats(&self) -> SessionStats {
+        let mut stats = SessionStats {
+            object_count: self.lookup.len(),
+            graph_vertex_count: self.graph.number_of_vertices(),
+            graph_edge_count: self.graph.number_of_edges(),
+            tree_node_count: self.tree.nodes().len(),
+            cached_box_count: self.cached_boxes.len(),
+            ray_bvh_bytes: self.cached_ray_bvh.as_ref().map_or(0, BVH::memory_bytes),
+            collision_bvh_bytes: self.bvh.memory_bytes(),
+            ..Default::default()
+        };
+
+        let mut geometry_bytes = 0;
+        for geometry in self.lookup.values() {
+            *stats
+                .object_counts
+                .entry(geometry.type_name().to_string())
+                .or_default() += 1;
+            let (vertices, faces) = match geometry.as_ref() {
+                Geometry::Arrow(a) => (2, a.mesh.number_of_faces()),
+                Geometry::Cylinder(c) => (2, c.mesh.number_of_faces()),
+                Geometry::Line(_) => (2, 0),
+                Geometry::Mesh(m) => (m.number_of_vertices(), m.number_of_faces()),
+                Geometry::Point(_) => (1, 0),
+                Geometry::PointCloud(pc) => (pc.points.len(), 0),
+                Geometry::Polyline(pl) => (pl.points.len(), 0),
+                Geometry::BoundingBox(_) | Geometry::Plane(_) => (0, 0),
+            };
+            stats.vertex_count += vertices;
+            stats.face_count += faces;
+            geometry_bytes += Self::geometry_bytes(geometry);
+        }
+
+        let string_bytes = |s: &String| std::mem::size_of::<String>() + s.capacity();
+        // Objects shared with snapshots are counted as if this Session owned them
+        stats.memory_bytes = std::mem::size_of::<Self>()
+            + geometry_bytes
+            + self.lookup.keys().map(string_bytes).sum::<usize>()
+            + (self.order.keys.keys().chain(self.order.guids.values()))
+                .map(string_bytes)
+                .sum::<usize>()
+            + self.cached_boxes.capacity() * std::mem::size_of::<BoundingBox>()
+            + self.cached_chunks.capacity() * std::mem::size_of::<Option<Range<usize>>>()
+            + 2 * self.cached_guids.iter().map(string_bytes).sum::<usize>()
+            + stats.ray_bvh_bytes
+            + stats.collision_bvh_bytes
+            + stats.graph_vertex_count * std::mem::size_of::<crate::Vertex>()
+            + stats.graph_edge_count * 2 * std::mem::size_of::<crate::Edge>()
+            + stats.tree_node_count * std::mem::size_of::<TreeNode>();
+        stats
+    }
+
+    /// Axis-aligned box around all objects placed by their own xforms.
+    ///
+    /// Tree node xforms are not applied, as in ray casts and collisions.
+    ///
+    /// # Returns
+    /// None for a Session without objects.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let corners: Vec<Point> = self
+            .lookup
+            .values()
+            .flat_map(|geometry| Self::compute_bounding_box(geometry).corners())
+            .collect();
+        (!corners.is_empty()).then(|| BoundingBox::from_points(&corners, 0.0))
+    }
+
+    /// Approximate memory of one object in bytes.
+    fn geometry_bytes(geometry: &Geometry) -> usize {
+        fn mesh_bytes(mesh: &Mesh) -> usize {
+            let faces: usize = mesh
+                .face
+                .values()
+                .map(|f| std::mem::size_of::<(usize, Vec<usize>)>() + f.capacity() * 8)
+                .sum();
+            let halfedges: usize = mesh
+                .halfedge
+                .values()
+                .map(|h| h.len() * std::mem::size_of::<(usize, Option<usize>)>())
+                .sum();
+            mesh.vertex.len() * std::mem::size_of::<(usize, crate::mesh::VertexData)>()
+                + faces
+                + halfedges
+                + mesh.triangle_bvh.get().map_or(0, |cache| {
+                    cache.triangles.capacity() * std::mem::size_of::<[usize; 3]>()
+                        + cache.vertices.capacity() * std::mem::size_of::<Point>()
+                        + cache.bvh.as_ref().map_or(0, BVH::memory_bytes)
+                })
+        }
+        let heap = match geometry {
+            Geometry::Arrow(a) => mesh_bytes(&a.mesh),
+            Geometry::Cylinder(c) => mesh_bytes(&c.mesh),
+            Geometry::Mesh(m) => mesh_bytes(m),
+            Geometry::PointCloud(pc) => {
+                (pc.points.capacity() + pc.normals.capacity()) * std::mem::size_of::<Vec3>()
+                    + pc.colors.capacity() * std::mem::size_of::<crate::Color>()
+            }
+            Geometry::Polyline(pl) => pl.points.capacity() * std::mem::size_of::<Point>(),
+            Geometry::BoundingBox(_)
+            | Geometry::Line(_)
+            | Geometry::Plane(_)
+            | Geometry::Point(_) => 0,
+        };
+        std::mem::size_of::<Geometry>() + heap
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Validation
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Check the Session for inconsistent or corrupted contents.
+    ///
+    /// Checks that the object order and the lookup table hold the same objects,
+    /// that graph vertices and edges refer to existing objects and vertices,
+    /// that tree nodes named by a GUID have an object, and that geometry has
+    /// finite coordinates and is not degenerate. Useful after `jsonload` of
+    /// files written by other languages.
+    ///
+    /// # Returns
+    /// All issues found, empty for a valid Session.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        for guid in self.order.iter() {
+            if !self.lookup.contains_key(guid) {
+                issues.push(ValidationIssue::MissingFromLookup { guid: guid.clone() });
+            }
+        }
+        let mut lookup_guids: Vec<&String> = self.lookup.keys().collect();
+        lookup_guids.sort();
+        for guid in lookup_guids {
+            if !self.order.contains(guid) {
+                issues.push(ValidationIssue::MissingFromObjects { guid: guid.clone() });
+            }
+        }
+
+        let mut vertices: Vec<String> = self
+            .graph
+            .get_vertices()
+            .into_iter()
+            .map(|v| v.name)
+            .collect();
+        vertices.sort();
+        for key in vertices {
+            if !self.lookup.contains_key(&key) {
+                issues.push(ValidationIssue::DanglingGraphNode { key });
+            }
+        }
+        let mut edges = self.graph.get_edges();
+        edges.sort();
+        for (from, to) in edges {
+            if !self.graph.has_node(&from) || !self.graph.has_node(&to) {
+                issues.push(ValidationIssue::DanglingGraphEdge { from, to });
+            }
+        }
+
+        for node in self.tree.nodes() {
+            let name = node.name();
+            let is_object = self.lookup.contains_key(&name) || self.groups.contains_key(&name);
+            if Uuid::parse_str(&name).is_ok() && !is_object {
+                issues.push(ValidationIssue::OrphanTreeNode { name });
+            }
+        }
+
+        let mut geometries: Vec<&Geometry> = self.lookup.values().map(Arc::as_ref).collect();
+        geometries.sort_by(|a, b| a.guid().cmp(b.guid()));
+        for geometry in geometries {
+            let guid = geometry.guid().to_string();
+            if !Self::is_finite(geometry) {
+                issues.push(ValidationIssue::NonFiniteCoordinates { guid });
+            } else if let Some(reason) = Self::degenerate_reason(geometry) {
+                issues.push(ValidationIssue::DegenerateGeometry {
+                    guid,
+                    reason: reason.to_string(),
+                });
+            }
+        }
+        issues
+    }
+
+    fn is_finite(geometry: &Geometry) -> bool {
+        let coords = |p: Vec3| p.x.is_finite() && p.y.is_finite() && p.z.is_finite();
+        let point = |p: &Point| coords(p.as_vec3());
+        let line = |l: &Line| point(&l.start()) && point(&l.end());
+        let vector =
+            |v: &crate::Vector| v.x().is_finite() && v.y().is_finite() && v.z().is_finite();
+        let finite = match geometry {
+            Geometry::Arrow(a) => line(&a.line) && a.radius.is_finite(),
+            Geometry::BoundingBox(b) => point(&b.center) && vector(&b.half_size),
+            Geometry::Cylinder(c) => line(&c.line) && c.radius.is_finite(),
+            Geometry::Line(l) => line(l),
+            Geometry::Mesh(m) => m.vertex.values().all(|v| coords(v.coords())),
+            Geometry::Plane(p) => point(&p.origin()) && vector(&p.z_axis()),
+            Geometry::Point(p) => point(p),
+            Geometry::PointCloud(pc) => pc.points.iter().all(|&p| coords(p)),
+            Geometry::Polyline(pl) => pl.points.iter().all(point),
+        };
+        finite && geometry.xform().m.iter().all(|v| v.is_finite())
+    }
+
+    fn degenerate_reason(geometry: &Geometry) -> Option<&'static str> {
+        let zero = Tolerance::ZERO_TOLERANCE;
+        match geometry {
+            Geometry::Arrow(Arrow { line, radius, .. })
+            | Geometry::Cylinder(Cylinder { line, radius, .. }) => {
+                if line.length() < zero {
+                    Some("zero length axis")
+                } else if *radius <= 0.0 {
+                    Some("radius is not positive")
+                } else {
+                    None
+                }
+            }
+            Geometry::BoundingBox(b) => {
+                let h = &b.half_size;
+                (h.x() < 0.0 || h.y() < 0.0 || h.z() < 0.0).then_some("negative half size")
+            }
+            Geometry::Line(l) => (l.length() < zero).then_some("zero length"),
+            Geometry::Mesh(m) => {
+                if m.number_of_faces() == 0 {
+                    Some("no faces")
+                } else if m
+                    .face
+                    .values()
+                    .any(|f| f.len() < 3 || f.iter().any(|v| !m.vertex.contains_key(v)))
+                {
+                    Some("face with fewer than 3 vertices or a missing vertex")
+                } else {
+                    None
+                }
+            }
+            Geometry::Plane(p) => (p.z_axis().compute_length() < zero).then_some("zero normal"),
+            Geometry::Point(_) => None,
+            Geometry::PointCloud(pc) => {
+                let mismatch = |n: usize| n != 0 && n != pc.points.len();
+                (mismatch(pc.normals.len()) || mismatch(pc.colors.len()))
+                    .then_some("normal or color count differs from point count")
+            }
+            Geometry::Polyline(pl) => (pl.points.len() < 2).then_some("fewer than 2 points"),
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Operation Log
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Append every following change to an NDJSON operation log.
+    ///
+    /// Each added, removed, modified or transformed object and each added or
+    /// removed graph edge becomes one JSON line, so long-running processes can
+    /// persist incrementally and `replay_log` the file on top of the last full
+    /// JSON checkpoint. Tree layout, attributes and the wholesale state changes
+    /// of undo, redo, rollback and `restore` are not logged; write a new
+    /// checkpoint after those.
+    ///
+    /// # Arguments
+    /// * `filepath` - Log file, created if missing and appended to otherwise
+    pub fn open_log(&mut self, filepath: &str) -> std::io::Result<()> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(filepath)?;
+        self.log = SessionLog {
+            writer: Some(BufWriter::new(file)),
+            error: None,
+        };
+        Ok(())
+    }
+
+    /// Stop logging and flush the log file.
+    ///
+    /// # Returns
+    /// The first error that occurred while writing, if any.
+    pub fn close_log(&mut self) -> std::io::Result<()> {
+        let log = std::mem::take(&mut self.log);
+        if let Some(error) = log.error {
+            return Err(error);
+        }
+        match log.writer {
+            Some(mut writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Apply the operations of a log written by `open_log`.
+    ///
+    /// Adds of existing objects replace them, so a log may be replayed on a
+    /// checkpoint written after it was opened. Replayed operations are not
+    /// written to this Session's own log.
+    ///
+    /// # Returns
+    /// The number of operations applied, or an error for unreadable files and malformed lines.
+    pub fn replay_log(&mut self, filepath: &str) -> Result<usize, SessionError> {
+        let reader = BufReader::new(fs::File::open(filepath)?);
+        let log = std::mem::take(&mut self.log);
+        let result = self.apply_log_lines(reader);
+        self.log = log;
+        result
+    }
+
+    fn apply_log_lines(&mut self, reader: impl BufRead) -> Result<usize, SessionError> {
+        let mut count = 0;
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            self.apply_log_entry(serde_json::from_str(&line)?);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn apply_log_entry(&mut self, entry: LogEntry<'_>) {
+        match entry {
+            LogEntry::Add { geometry } | LogEntry::Update { geometry } => {
+                let geometry = geometry.into_owned();
+                let guid = geometry.guid().to_string();
+                if self.lookup.contains_key(&guid) {
+                    self.update_object(&guid, geometry);
+                } else {
+                    self.add_geometry(geometry);
+                }
+            }
+            LogEntry::Remove { guid } => {
+                self.remove_object(&guid);
+            }
+            LogEntry::Transform { guid, xform } => {
+                self.set_transform(&guid, xform, false);
+            }
+            LogEntry::AddEdge {
+                from,
+                to,
+                attribute,
+            } => self.add_graph_edge(&from, &to, &attribute),
+            LogEntry::RemoveEdge { from, to } => {
+                self.graph.remove_edge((&from, &to));
+                self.emit(SessionEvent::EdgeRemoved { from, to });
+            }
+        }
+    }
+
+    fn log_entry(
+        lookup: &HashMap<String, Arc<Geometry>>,
+        event: SessionEvent,
+    ) -> Option<LogEntry<'_>> {
+        let entry = match event {
+            SessionEvent::ObjectAdded { guid } => LogEntry::Add {
+                geometry: Cow::Borrowed(lookup.get(&guid)?),
+            },
+            SessionEvent::ObjectRemoved { guid } => LogEntry::Remove { guid },
+            SessionEvent::ObjectModified { guid } => LogEntry::Update {
+                geometry: Cow::Borrowed(lookup.get(&guid)?),
+            },
+            SessionEvent::ObjectTransformed { guid, xform } => LogEntry::Transform { guid, xform },
+            SessionEvent::EdgeAdded {
+                from,
+                to,
+                attribute,
+            } => LogEntry::AddEdge {
+                from,
+                to,
+                attribute,
+            },
+            SessionEvent::EdgeRemoved { from, to } => LogEntry::RemoveEdge { from, to },
+        };
+        Some(entry)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Transactions
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Start recording changes to objects, tree and graph.
+    ///
+    /// Everything changed until `commit` becomes a single undo step.
+    ///
+    /// # Returns
+    /// `false` if a transaction is already open.
+    pub fn begin_transaction(&mut self) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        self.transaction = Some(self.snapshot());
+        true
+    }
+
+    /// Close the open transaction and push its changes onto the undo stack.
+    ///
+    /// # Returns
+    /// `false` if no transaction is open.
+    pub fn commit(&mut self) -> bool {
+        match self.transaction.take() {
+            Some(state) => {
+                let step = self.changes_since(state);
+                self.undo_stack.push(step);
+                self.redo_stack.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Discard all changes made since `begin_transaction`.
+    ///
+    /// # Returns
+    /// `false` if no transaction is open.
+    pub fn rollback(&mut self) -> bool {
+        match self.transaction.take() {
+            Some(state) => {
+                let step = self.changes_since(state);
+                self.apply_step(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Revert the last committed transaction.
+    ///
+    /// Only the objects and entries it changed are put back.
+    ///
+    /// # Returns
+    /// `false` if there is nothing to undo or a transaction is open.
+    pub fn undo(&mut self) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        match self.undo_stack.pop() {
+            Some(step) => {
+                let redo = self.apply_step(step);
+                self.redo_stack.push(redo);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapply the last undone transaction.
+    ///
+    /// # Returns
+    /// `false` if there is nothing to redo or a transaction is open.
+    pub fn redo(&mut self) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        match self.redo_stack.pop() {
+            Some(step) => {
+                let undo = self.apply_step(step);
+                self.undo_stack.push(undo);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.transaction.is_none() && !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.transaction.is_none() && !self.redo_stack.is_empty()
+    }
+
+    /// Capture objects, tree, graph and the per-object and per-group state.
+    ///
+    /// Objects are shared with the Session rather than copied, an object is
+    /// only copied when it is edited afterwards. Used for transactions and for
+    /// speculative edits such as drag previews, independent of the undo history.
+    pub fn snapshot(&self) -> SessionState {
+        SessionState {
+            order: self.order.clone(),
+            lookup: self.lookup.clone(),
+            tree: self.tree.deep_copy(),
+            graph: self.graph.clone(),
+            attributes: self.attributes.clone(),
+            collision_filters: self.collision_filters.clone(),
+            groups: self.groups.clone(),
+            links: self.links.clone(),
+            display: self.display.clone(),
+            animations: self.animations.clone(),
+        }
+    }
+
+    /// The changes made since `before` was captured, as the values to put back.
+    ///
+    /// Objects are compared by pointer, an object that was not edited still
+    /// shares its geometry with `before`.
+    fn changes_since(&self, before: SessionState) -> UndoStep {
+        let mut step = UndoStep::default();
+        for guid in self.lookup.keys() {
+            if !before.lookup.contains_key(guid) {
+                step.objects.push((guid.clone(), None));
+            }
+        }
+        for (guid, geometry) in before.lookup {
+            let key = before.order.keys.get(&guid).copied();
+            let unchanged = self
+                .lookup
+                .get(&guid)
+                .is_some_and(|g| Arc::ptr_eq(g, &geometry))
+                && self.order.keys.get(&guid).copied() == key;
+            if !unchanged {
+                step.objects.push((guid, key.map(|key| (key, geometry))));
+            }
+        }
+        step.attributes = changed_entries(before.attributes, &self.attributes);
+        step.collision_filters = changed_entries(before.collision_filters, &self.collision_filters);
+        step.groups = changed_entries(before.groups, &self.groups);
+        step.display = changed_entries(before.display, &self.display);
+        step.animations = changed_entries(before.animations, &self.animations);
+
+        let keys: HashSet<&String> = before
+            .graph
+            .node_keys()
+            .chain(self.graph.node_keys())
+            .collect();
+        step.graph_nodes = keys
+            .into_iter()
+            .map(|key| (key, before.graph.node_entry(key)))
+            .filter(|(key, entry)| self.graph.node_entry(key) != *entry)
+            .map(|(key, entry)| (key.clone(), entry))
+            .collect();
+        step.graph_counts = (before.graph.vertex_count, before.graph.edge_count);
+
+        let tree_value = |tree: &Tree| serde_json::to_value(tree).ok();
+        if tree_value(&before.tree) != tree_value(&self.tree) {
+            step.tree = Some(before.tree);
+        }
+        let same_links = before.links.len() == self.links.len()
+            && (before.links.iter().zip(&self.links))
+                .all(|(a, b)| a.guid == b.guid && a.path == b.path && a.xform == b.xform);
+        if !same_links {
+            step.links = Some(before.links);
+        }
+        step
+    }
+
+    /// Put back the values recorded in a step, returning the step that reverts it.
+    ///
+    /// Cached boxes are updated per changed object, no change events are emitted.
+    fn apply_step(&mut self, step: UndoStep) -> UndoStep {
+        let mut reverse = UndoStep::default();
+        for (guid, previous) in step.objects {
+            let current = (self.order.remove(&guid)).zip(self.lookup.remove(&guid));
+            if current.is_some() {
+                self.uncache_geometry_aabb(&guid);
+            }
+            if let Some((key, geometry)) = previous {
+                self.order.insert(&guid, key);
+                self.lookup.insert(guid.clone(), geometry);
+                self.cache_geometry_aabb(&guid);
+            }
+            reverse.objects.push((guid, current));
+        }
+        reverse.attributes = replace_entries(&mut self.attributes, step.attributes);
+        reverse.collision_filters =
+            replace_entries(&mut self.collision_filters, step.collision_filters);
+        reverse.groups = replace_entries(&mut self.groups, step.groups);
+        reverse.display = replace_entries(&mut self.display, step.display);
+        reverse.animations = replace_entries(&mut self.animations, step.animations);
+
+        reverse.graph_nodes = (step.graph_nodes.into_iter())
+            .map(|(key, entry)| {
+                let previous = self.graph.replace_node_entry(&key, entry);
+                (key, previous)
+            })
+            .collect();
+        reverse.graph_counts = (self.graph.vertex_count, self.graph.edge_count);
+        (self.graph.vertex_count, self.graph.edge_count) = step.graph_counts;
+
+        reverse.tree = step
+            .tree
+            .map(|tree| std::mem::replace(&mut self.tree, tree));
+        reverse.links = step
+            .links
+            .map(|links| std::mem::replace(&mut self.links, links));
+        reverse
+    }
+
+    /// Replace the Session contents with a snapshot.
+    ///
+    /// Undo and redo stacks and listeners are kept, no change events are emitted.
+    pub fn restore(&mut self, state: SessionState) {
+        self.order = state.order;
+        self.lookup = state.lookup;
+        self.tree = state.tree;
+        self.graph = state.graph;
+        self.attributes = state.attributes;
+        self.collision_filters = state.collision_filters;
+        self.groups = state.groups;
+        self.links = state.links;
+        self.display = state.display;
+        self.animations = state.animations;
+        // Cached boxes refer to the replaced objects
+        self.cached_boxes.clear();
+        self.cached_guids.clear();
+        self.cached_chunks.clear();
+        self.cached_index.clear();
+        self.cached_ray_bvh = None;
+        self.invalidate_bvh_cache();
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Details - Tree
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Add a parent-child relationship in the tree structure.
+    ///
+    /// # Arguments
+    /// * `parent_guid` - The GUID of the parent geometry object.
+    /// * `child_guid` - The GUID of the child geometry object.
+    ///
+    /// # Returns
+    /// `true` if the relationship was added successfully.
+    pub fn add_hierarchy(&mut self, parent_guid: &str, child_guid: &str) -> bool {
+        self.tree
+            .add_child_by_guid(&parent_guid.to_string(), &child_guid.to_string())
+    }
+
+    /// Get all children GUIDs of a geometry object in the tree.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the geometry object.
+    ///
+    /// # Returns
+    /// A vector containing the GUIDs of all children of the specified geometry object.
+    pub fn get_children(&self, guid: &str) -> Vec<String> {
+        self.tree.get_children(guid)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Groups
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Group objects so they are moved, hidden and copied as one, like a CAD block.
+    ///
+    /// A tree node named by the group GUID is added below the root and the
+    /// members' tree nodes are moved below it. An object belongs to at most one
+    /// group, adding it to a new group removes it from its previous one.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the group
+    /// * `guids` - GUIDs of the member objects, unknown GUIDs are ignored
+    ///
+    /// # Returns
+    /// The GUID of the new group.
+    pub fn add_group(&mut self, name: &str, guids: &[String]) -> String {
+        let mut members: Vec<String> = Vec::new();
+        for guid in guids {
+            if self.lookup.contains_key(guid) && !members.contains(guid) {
+                members.push(guid.clone());
+            }
+        }
+        for group in self.groups.values_mut() {
+            group.members.retain(|member| !members.contains(member));
+        }
+
+        let guid = self
+            .ids
+            .next(format!("group {name} {}", members.join(" ")).as_bytes());
+        let group_node = TreeNode::new(&guid);
+        self.add(&group_node, None);
+        for member in &members {
+            let node = match self.tree.get_node_by_name(member) {
+                Some(node) => {
+                    if let Some(parent) = node.parent() {
+                        parent.remove(&node);
+                    }
+                    node
+                }
+                None => {
+                    let node = TreeNode::new(member);
+                    self.identify_nodes(&node);
+                    node
+                }
+            };
+            group_node.add(&node);
+        }
+
+        let mut xform = Xform::identity();
+        if self.ids.generator() != IdGenerator::Random {
+            xform.guid = crate::guid::derived_guid(&guid, 1);
+        }
+        self.groups.insert(
+            guid.clone(),
+            Group {
+                guid: guid.clone(),
+                name: name.to_string(),
+                members,
+                xform,
+                visible: true,
+            },
+        );
+        guid
+    }
+
+    /// Get a group by its GUID.
+    pub fn get_group(&self, guid: &str) -> Option<&Group> {
+        self.groups.get(guid)
+    }
+
+    /// Dissolve a group, its members stay in the session and move below the tree root.
+    ///
+    /// # Returns
+    /// `false` if no group has the GUID.
+    pub fn ungroup(&mut self, guid: &str) -> bool {
+        if self.groups.remove(guid).is_none() {
+            return false;
+        }
+        if let (Some(node), Some(root)) = (self.tree.get_node_by_name(guid), self.tree.root()) {
+            for child in node.children() {
+                node.remove(&child);
+                root.add(&child);
+            }
+            self.tree.remove(&node);
+        }
+        true
+    }
+
+    /// Place a group, the change in its xform is applied to every member.
+    ///
+    /// # Arguments
+    /// * `guid` - The GUID of the group
+    /// * `xform` - The new placement of the group
+    ///
+    /// # Returns
+    /// `false` if no group has the GUID or its current xform cannot be inverted.
+    pub fn set_group_transform(&mut self, guid: &str, xform: Xform) -> bool {
+        let Some(group) = self.groups.get_mut(guid) else {
+            return false;
+        };
+        let Some(inverse) = group.xform.inverse() else {
+            return false;
+        };
+        let delta = &xform * &inverse;
+        group.xform = xform;
+        for member in group.members.clone() {
+            let Some(current) = self.lookup.get(&member).map(|g| g.xform().clone()) else {
+                continue;
+            };
+            self.set_transform(&member, &delta * &current, false);
         }
-        self.graph.add_node(&guid, &format!("polyline_{name}"));
+        true
+    }
 
-        TreeNode::new(&guid)
+    /// Show or hide all members of a group.
+    ///
+    /// # Returns
+    /// `false` if no group has the GUID.
+    pub fn set_group_visible(&mut self, guid: &str, visible: bool) -> bool {
+        match self.groups.get_mut(guid) {
+            Some(group) => {
+                group.visible = visible;
+                true
+            }
+            None => false,
+        }
     }
 
-    pub fn add_pointcloud(&mut self, pointcloud: PointCloud) -> TreeNode {
-        let guid = pointcloud.guid.clone();
-        let name = pointcloud.name.clone();
-        let geometry = Geometry::PointCloud(pointcloud.clone());
+    /// Whether an object is shown, i.e. neither hidden itself nor a member of a hidden group.
+    pub fn is_visible(&self, guid: &str) -> bool {
+        self.display.get(guid).is_none_or(|state| state.visible)
+            && !self
+                .groups
+                .values()
+                .any(|group| !group.visible && group.members.iter().any(|m| m == guid))
+    }
 
-        self.objects.pointclouds.push(pointcloud);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::PointCloud(p)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::PointCloud(p.clone()));
+    /// Copy a group and its members with new GUIDs.
+    ///
+    /// Attributes, collision filters, display states and graph edges between members are
+    /// copied as well. The copy keeps the placement and visibility of the group.
+    ///
+    /// # Returns
+    /// The GUID of the new group, or None if no group has the GUID.
+    pub fn duplicate_group(&mut self, guid: &str) -> Option<String> {
+        let group = self.groups.get(guid)?.clone();
+        let new_guids = self.copy_objects(&group.members);
+        let members: Vec<String> = group
+            .members
+            .iter()
+            .filter_map(|member| new_guids.get(member).cloned())
+            .collect();
+        let copy_guid = self.add_group(&group.name, &members);
+        if let Some(copy) = self.groups.get_mut(&copy_guid) {
+            copy.xform = group.xform;
+            copy.visible = group.visible;
         }
-        self.graph.add_node(&guid, &format!("pointcloud_{name}"));
-
-        TreeNode::new(&guid)
+        Some(copy_guid)
     }
 
-    pub fn add_mesh(&mut self, mesh: Mesh) -> TreeNode {
-        let guid = mesh.guid.clone();
-        let name = mesh.name.clone();
-        let geometry = Geometry::Mesh(mesh.clone());
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Duplication
+    ///////////////////////////////////////////////////////////////////////////////////////////
 
-        self.objects.meshes.push(mesh);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::Mesh(m)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::Mesh(m.clone()));
+    /// Copy an object with a new GUID.
+    ///
+    /// Attributes, collision filter and display state are copied. If the
+    /// object has a tree node, the copy gets a node next to it.
+    ///
+    /// # Returns
+    /// The GUID of the copy, or None if no object has the GUID.
+    pub fn duplicate_object(&mut self, guid: &str) -> Option<String> {
+        let copy_guid = self.copy_objects(&[guid.to_string()]).remove(guid)?;
+        if let Some(parent) = self
+            .tree
+            .get_node_by_name(guid)
+            .and_then(|node| node.parent())
+        {
+            let copy = TreeNode::new(&copy_guid);
+            self.identify_nodes(&copy);
+            parent.add(&copy);
         }
-        self.graph.add_node(&guid, &format!("mesh_{name}"));
-
-        TreeNode::new(&guid)
+        Some(copy_guid)
     }
 
-    pub fn add_cylinder(&mut self, cylinder: Cylinder) -> TreeNode {
-        let guid = cylinder.guid.clone();
-        let name = cylinder.name.clone();
-        let geometry = Geometry::Cylinder(cylinder.clone());
+    /// Copy a tree node, the nodes below it and their objects with new GUIDs.
+    ///
+    /// The copied nodes are added next to `node`, below the root if it has no
+    /// parent, with the same hierarchy. Graph edges between copied objects are
+    /// copied too, edges to objects outside the subtree are not. Nodes without
+    /// an object keep their name.
+    ///
+    /// # Returns
+    /// GUIDs of the copied objects in depth-first order of their nodes.
+    pub fn duplicate_subtree(&mut self, node: &TreeNode) -> Vec<String> {
+        let guids: Vec<String> = node.nodes().iter().map(|n| n.name()).collect();
+        let new_guids = self.copy_objects(&guids);
 
-        self.objects.cylinders.push(cylinder);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::Cylinder(c)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::Cylinder(c.clone()));
+        fn copy_node(node: &TreeNode, new_guids: &HashMap<String, String>) -> TreeNode {
+            let name = node.name();
+            let copy = TreeNode::new(new_guids.get(&name).unwrap_or(&name));
+            for child in node.children() {
+                copy.add(&copy_node(&child, new_guids));
+            }
+            copy
+        }
+        let copy = copy_node(node, &new_guids);
+        match node.parent() {
+            Some(parent) => {
+                self.identify_nodes(&copy);
+                parent.add(&copy);
+            }
+            None => self.add(&copy, None),
         }
-        self.graph.add_node(&guid, &format!("cylinder_{name}"));
 
-        TreeNode::new(&guid)
+        guids
+            .iter()
+            .filter_map(|guid| new_guids.get(guid).cloned())
+            .collect()
     }
 
-    pub fn add_arrow(&mut self, arrow: Arrow) -> TreeNode {
-        let guid = arrow.guid.clone();
-        let name = arrow.name.clone();
-        let geometry = Geometry::Arrow(arrow.clone());
-
-        self.objects.arrows.push(arrow);
-        self.lookup.insert(guid.clone(), geometry);
-        if let Some(Geometry::Arrow(a)) = self.lookup.get(&guid) {
-            self.cache_geometry_aabb(&guid, &Geometry::Arrow(a.clone()));
+    /// Copy objects with new GUIDs along with their per-object state and the edges between them.
+    ///
+    /// GUIDs without an object are skipped. Tree nodes are left to the caller.
+    ///
+    /// # Returns
+    /// New GUIDs by original GUID.
+    fn copy_objects(&mut self, guids: &[String]) -> HashMap<String, String> {
+        let mut new_guids: HashMap<String, String> = HashMap::new();
+        for guid in guids {
+            if new_guids.contains_key(guid) {
+                continue;
+            }
+            let Some(mut geometry) = self.lookup.get(guid).map(|g| Geometry::clone(g)) else {
+                continue;
+            };
+            *geometry.guid_mut() = crate::guid::new_guid();
+            let copy_guid = self.add_geometry(geometry).name();
+            if let Some(attributes) = self.attributes.get(guid).cloned() {
+                self.attributes.insert(copy_guid.clone(), attributes);
+            }
+            if let Some(filter) = self.collision_filters.get(guid).copied() {
+                self.collision_filters.insert(copy_guid.clone(), filter);
+            }
+            if let Some(state) = self.display.get(guid).cloned() {
+                self.display.insert(copy_guid.clone(), state);
+            }
+            new_guids.insert(guid.clone(), copy_guid);
         }
-        self.graph.add_node(&guid, &format!("arrow_{name}"));
 
-        TreeNode::new(&guid)
+        let mut edges = self.graph.get_edges();
+        edges.sort();
+        for (from, to) in edges {
+            let (Some(new_from), Some(new_to)) = (new_guids.get(&from), new_guids.get(&to)) else {
+                continue;
+            };
+            let attribute = self
+                .graph
+                .edge_attribute(&from, &to, None)
+                .unwrap_or_default();
+            self.add_graph_edge(new_from, new_to, &attribute);
+        }
+        new_guids
     }
 
-    /// Adds a TreeNode to the tree hierarchy.
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Details - Graph
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Add a relationship edge in the graph structure.
     ///
     /// # Arguments
-    /// * `node` - The TreeNode to add
-    /// * `parent` - Optional parent TreeNode (defaults to root if None)
-    pub fn add<'a>(&mut self, node: &TreeNode, parent: impl Into<Option<&'a TreeNode>>)
-    where
-        TreeNode: 'a,
-    {
-        let parent_opt = parent.into();
-        if parent_opt.is_none() {
-            if let Some(root) = self.tree.root() {
-                self.tree.add(node, Some(&root));
-            }
-        } else {
-            self.tree.add(node, parent_opt);
-        }
+    /// * `from_guid` - The GUID of the source geometry object.
+    /// * `to_guid` - The GUID of the target geometry object.
+    /// * `relationship_type` - The type of relationship.
+    pub fn add_relationship(&mut self, from_guid: &str, to_guid: &str, relationship_type: &str) {
+        self.add_graph_edge(from_guid, to_guid, relationship_type);
     }
 
-    /// Adds an edge between two geometry objects in the graph.
+    /// Get all GUIDs connected to the given GUID in the graph.
     ///
     /// # Arguments
-    /// * `from_guid` - The GUID of the source object
-    /// * `to_guid` - The GUID of the target object
-    /// * `attribute` - The attribute or label for the edge
-    pub fn add_edge(&mut self, from_guid: &str, to_guid: &str, attribute: &str) {
-        self.graph.add_edge(from_guid, to_guid, attribute);
+    /// * `guid` - The GUID of the geometry object.
+    ///
+    /// # Returns
+    /// A vector containing the GUIDs of all connected geometry objects.
+    pub fn get_neighbours(&self, guid: &str) -> Vec<String> {
+        self.graph.get_neighbors(guid)
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
-    // Details - Lookup
+    // Display State
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    /// Gets a geometry object by its GUID.
-    ///
-    /// # Arguments
-    /// * `guid` - The GUID of the object to retrieve
+    /// Show or hide an object. Hidden objects are skipped by ray casts.
     ///
     /// # Returns
-    /// An Option containing a reference to the Geometry enum if found, or None if not found.
-    pub fn get_object(&self, guid: &str) -> Option<&Geometry> {
-        self.lookup.get(guid)
+    /// `false` if no object has the GUID.
+    pub fn set_visible(&mut self, guid: &str, visible: bool) -> bool {
+        self.update_display(guid, |state| state.visible = visible)
     }
 
-    /// Remove a geometry object by its GUID.
+    /// Select or deselect an object.
     ///
-    /// # Arguments
-    /// * `guid` - The UUID of the geometry object to remove.
+    /// # Returns
+    /// `false` if no object has the GUID.
+    pub fn set_selected(&mut self, guid: &str, selected: bool) -> bool {
+        self.update_display(guid, |state| state.selected = selected)
+    }
+
+    /// Set or clear the highlight color of an object.
     ///
     /// # Returns
-    /// `true` if the object was removed, `false` if not found.
-    pub fn remove_object(&mut self, guid: &str) -> bool {
-        // Check if object exists in lookup table
-        if !self.lookup.contains_key(guid) {
-            return false;
-        }
+    /// `false` if no object has the GUID.
+    pub fn set_highlight_color(&mut self, guid: &str, color: Option<Color>) -> bool {
+        self.update_display(guid, |state| state.highlight_color = color)
+    }
 
-        // Remove from all object collections
-        self.objects.points.retain(|p| p.guid != guid);
-        self.objects.lines.retain(|l| l.guid != guid);
-        self.objects.polylines.retain(|p| p.guid != guid);
-        self.objects.planes.retain(|p| p.guid != guid);
-        self.objects.bboxes.retain(|b| b.guid != guid);
-        self.objects.meshes.retain(|m| m.guid != guid);
-        self.objects.cylinders.retain(|c| c.guid != guid);
-        self.objects.arrows.retain(|a| a.guid != guid);
-        self.objects.pointclouds.retain(|p| p.guid != guid);
+    /// Get the viewer state of an object, the default one if it was never set.
+    pub fn display_state(&self, guid: &str) -> DisplayState {
+        self.display.get(guid).cloned().unwrap_or_default()
+    }
 
-        // Remove from lookup table
-        self.lookup.remove(guid);
-        self.invalidate_bvh_cache();
+    /// GUIDs of the objects to draw in the order they were added, see `is_visible`.
+    pub fn visible_objects(&self) -> Vec<String> {
+        self.order
+            .iter()
+            .filter(|guid| self.is_visible(guid))
+            .cloned()
+            .collect()
+    }
 
-        // Remove from tree - find node by GUID and remove it
-        if let Some(node) = self.tree.find_node_by_guid(&guid.to_string()) {
-            self.tree.remove(&node);
-        }
+    /// GUIDs of the selected objects in the order they were added.
+    pub fn selected_objects(&self) -> Vec<String> {
+        self.order
+            .iter()
+            .filter(|guid| self.display.get(*guid).is_some_and(|state| state.selected))
+            .cloned()
+            .collect()
+    }
 
-        // Remove from graph using string GUID
-        if self.graph.has_node(guid) {
-            self.graph.remove_node(guid);
+    /// Edit the display state of an object, states back at the default are not stored.
+    fn update_display(&mut self, guid: &str, edit: impl FnOnce(&mut DisplayState)) -> bool {
+        if !self.lookup.contains_key(guid) {
+            return false;
+        }
+        let state = self.display.entry(guid.to_string()).or_default();
+        edit(state);
+        if state.is_default() {
+            self.display.remove(guid);
         }
-
         true
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
-    // Details - Tree
+    // Animation
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    /// Add a parent-child relationship in the tree structure.
+    /// Store the keyframed poses of an object, replacing its previous track.
     ///
-    /// # Arguments
-    /// * `parent_guid` - The GUID of the parent geometry object.
-    /// * `child_guid` - The GUID of the child geometry object.
+    /// Tracks hold absolute xforms, so copies of the object do not get one.
     ///
     /// # Returns
-    /// `true` if the relationship was added successfully.
-    pub fn add_hierarchy(&mut self, parent_guid: &str, child_guid: &str) -> bool {
-        self.tree
-            .add_child_by_guid(&parent_guid.to_string(), &child_guid.to_string())
+    /// `false` if no object has the GUID.
+    pub fn set_animation(&mut self, guid: &str, track: AnimationTrack) -> bool {
+        if !self.lookup.contains_key(guid) {
+            return false;
+        }
+        self.animations.insert(guid.to_string(), track);
+        true
     }
 
-    /// Get all children GUIDs of a geometry object in the tree.
+    /// Get the track of an object, None if it has none.
+    pub fn animation(&self, guid: &str) -> Option<&AnimationTrack> {
+        self.animations.get(guid)
+    }
+
+    /// Remove the track of an object, the object keeps its current xform.
+    pub fn remove_animation(&mut self, guid: &str) -> Option<AnimationTrack> {
+        self.animations.remove(guid)
+    }
+
+    /// Move every animated object to its pose at a time.
     ///
-    /// # Arguments
-    /// * `guid` - The GUID of the geometry object.
+    /// Objects are transformed with `set_transform` without propagation, in
+    /// GUID order. Empty tracks leave their object unchanged.
     ///
-    /// # Returns
-    /// A vector containing the GUIDs of all children of the specified geometry object.
-    pub fn get_children(&self, guid: &str) -> Vec<String> {
-        self.tree.get_children(guid)
+    /// # Arguments
+    /// * `time` - Time to evaluate the tracks at
+    pub fn apply_animations(&mut self, time: f64) {
+        let mut poses: Vec<(String, Xform)> = self
+            .animations
+            .iter()
+            .filter_map(|(guid, track)| Some((guid.clone(), track.evaluate(time)?)))
+            .collect();
+        poses.sort_by(|a, b| a.0.cmp(&b.0));
+        for (guid, xform) in poses {
+            self.set_transform(&guid, xform, false);
+        }
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
-    // Details - Graph
+    // External Links
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    /// Add a relationship edge in the graph structure.
+    /// Reference another session file instead of copying its objects.
+    ///
+    /// The file is read the first time `get_geometry` is called and its
+    /// geometry, including its own links, is appended as read-only context.
+    /// Linked objects are not part of the lookup table, ray casts or collisions.
+    /// A file that cannot be read contributes no geometry.
     ///
     /// # Arguments
-    /// * `from_guid` - The GUID of the source geometry object.
-    /// * `to_guid` - The GUID of the target geometry object.
-    /// * `relationship_type` - The type of relationship.
-    pub fn add_relationship(&mut self, from_guid: &str, to_guid: &str, relationship_type: &str) {
-        self.graph.add_edge(from_guid, to_guid, relationship_type);
+    /// * `path` - Path of the linked session JSON file
+    /// * `xform` - Placement of the linked session
+    ///
+    /// # Returns
+    /// The GUID of the link.
+    pub fn link_external(&mut self, path: &str, mut xform: Xform) -> String {
+        let guid = self.ids.next(format!("link {path}").as_bytes());
+        if self.ids.generator() != IdGenerator::Random {
+            xform.guid = crate::guid::derived_guid(&guid, 1);
+        }
+        self.links.push(ExternalLink {
+            guid: guid.clone(),
+            path: path.to_string(),
+            xform,
+            geometry: OnceCell::new(),
+        });
+        guid
     }
 
-    /// Get all GUIDs connected to the given GUID in the graph.
-    ///
-    /// # Arguments
-    /// * `guid` - The GUID of the geometry object.
+    /// Remove a link added with `link_external`.
     ///
     /// # Returns
-    /// A vector containing the GUIDs of all connected geometry objects.
-    pub fn get_neighbours(&self, guid: &str) -> Vec<String> {
-        self.graph.get_neighbors(guid)
+    /// `false` if no link has the GUID.
+    pub fn unlink_external(&mut self, guid: &str) -> bool {
+        let count = self.links.len();
+        self.links.retain(|link| link.guid != guid);
+        self.links.len() != count
+    }
+
+    /// Drop the loaded geometry of all links so changed files are read again.
+    pub fn reload_links(&mut self) {
+        for link in &mut self.links {
+            link.geometry = OnceCell::new();
+        }
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////
@@ -882,214 +4269,156 @@ impl Session {
     ///
     /// Recursively traverses the tree and applies parent transformations to children.
     /// Each child's transformation is the composition of all ancestor transformations
-    /// multiplied by its own transformation.
+    /// multiplied by its own transformation. Tree node xforms apply to their subtree
+    /// before the node geometry xform, so group nodes move their children. The geometry of linked session files
+    /// is appended, placed by the link xforms, see `link_external`.
     ///
     /// # Returns
     /// Objects collection with transformed geometry
     pub fn get_geometry(&self) -> Objects {
-        use crate::Xform;
-
-        // Deep copy all objects
-        let mut transformed_objects = self.objects.clone();
-
-        // Rebuild lookup from copied objects
-        let mut transformed_lookup: HashMap<String, Geometry> = HashMap::new();
+        self.get_geometry_linked(&mut Vec::new())
+    }
 
-        for point in &transformed_objects.points {
-            transformed_lookup.insert(point.guid.clone(), Geometry::Point(point.clone()));
-        }
-        for line in &transformed_objects.lines {
-            transformed_lookup.insert(line.guid.clone(), Geometry::Line(line.clone()));
-        }
-        for plane in &transformed_objects.planes {
-            transformed_lookup.insert(plane.guid.clone(), Geometry::Plane(plane.clone()));
-        }
-        for bbox in &transformed_objects.bboxes {
-            transformed_lookup.insert(bbox.guid.clone(), Geometry::BoundingBox(bbox.clone()));
-        }
-        for polyline in &transformed_objects.polylines {
-            transformed_lookup.insert(polyline.guid.clone(), Geometry::Polyline(polyline.clone()));
-        }
-        for pointcloud in &transformed_objects.pointclouds {
-            transformed_lookup.insert(
-                pointcloud.guid.clone(),
-                Geometry::PointCloud(pointcloud.clone()),
-            );
-        }
-        for mesh in &transformed_objects.meshes {
-            transformed_lookup.insert(mesh.guid.clone(), Geometry::Mesh(mesh.clone()));
-        }
-        for cylinder in &transformed_objects.cylinders {
-            transformed_lookup.insert(cylinder.guid.clone(), Geometry::Cylinder(cylinder.clone()));
+    /// `get_geometry` skipping links to the files in `open`, which are already being composited.
+    fn get_geometry_linked(&self, open: &mut Vec<String>) -> Objects {
+        let mut objects = self.get_tree_geometry();
+        for link in &self.links {
+            if open.contains(&link.path) {
+                continue;
+            }
+            let linked = match link.geometry.get() {
+                Some(linked) => linked,
+                None => {
+                    open.push(link.path.clone());
+                    let loaded = Self::load_linked_geometry(link, open);
+                    open.pop();
+                    link.geometry.get_or_init(|| loaded)
+                }
+            };
+            for geometry in linked.iter().cloned().flat_map(Self::object_geometries) {
+                Self::push_object(&mut objects, geometry);
+            }
         }
-        for arrow in &transformed_objects.arrows {
-            transformed_lookup.insert(arrow.guid.clone(), Geometry::Arrow(arrow.clone()));
+        objects
+    }
+
+    /// Read a linked session file and place its geometry with the link xform.
+    fn load_linked_geometry(link: &ExternalLink, open: &mut Vec<String>) -> Option<Objects> {
+        let session = Self::from_json(&link.path).ok()?;
+        let mut placed = Objects::new();
+        for mut geometry in Self::object_geometries(session.get_geometry_linked(open)) {
+            *geometry.xform_mut() = link.xform.clone();
+            Self::push_object(&mut placed, geometry.transformed());
         }
+        Some(placed)
+    }
 
-        fn transform_node(
-            node: &TreeNode,
-            parent_xform: &Xform,
-            transformed_lookup: &HashMap<String, Geometry>,
-            transformed_objects: &mut Objects,
-        ) {
-            // Get geometry from the lookup
-            let node_name = node.name();
-            let geometry = transformed_lookup.get(&node_name);
-
-            let current_xform = if let Some(geom) = geometry {
-                // Get mutable reference and transform in-place
-                let combined_xform = parent_xform
-                    * match geom {
-                        Geometry::Point(g) => &g.xform,
-                        Geometry::Line(g) => &g.xform,
-                        Geometry::Plane(g) => &g.xform,
-                        Geometry::BoundingBox(g) => &g.xform,
-                        Geometry::Polyline(g) => &g.xform,
-                        Geometry::PointCloud(g) => &g.xform,
-                        Geometry::Mesh(g) => &g.xform,
-                        Geometry::Cylinder(g) => &g.xform,
-                        Geometry::Arrow(g) => &g.xform,
-                    };
+    /// All objects of a collection in `Objects` order.
+    fn object_geometries(objects: Objects) -> Vec<Geometry> {
+        let o = objects;
+        (o.points.into_iter().map(Geometry::Point))
+            .chain(o.lines.into_iter().map(Geometry::Line))
+            .chain(o.planes.into_iter().map(Geometry::Plane))
+            .chain(o.bboxes.into_iter().map(Geometry::BoundingBox))
+            .chain(o.polylines.into_iter().map(Geometry::Polyline))
+            .chain(o.pointclouds.into_iter().map(Geometry::PointCloud))
+            .chain(o.meshes.into_iter().map(Geometry::Mesh))
+            .chain(o.cylinders.into_iter().map(Geometry::Cylinder))
+            .chain(o.arrows.into_iter().map(Geometry::Arrow))
+            .collect()
+    }
 
-                // Find and update the geometry in the collections
-                match geom {
-                    Geometry::Point(_) => {
-                        if let Some(g) = transformed_objects
-                            .points
-                            .iter_mut()
-                            .find(|p| p.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::Line(_) => {
-                        if let Some(g) = transformed_objects
-                            .lines
-                            .iter_mut()
-                            .find(|l| l.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::Plane(_) => {
-                        if let Some(g) = transformed_objects
-                            .planes
-                            .iter_mut()
-                            .find(|p| p.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::BoundingBox(_) => {
-                        if let Some(g) = transformed_objects
-                            .bboxes
-                            .iter_mut()
-                            .find(|b| b.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::Polyline(_) => {
-                        if let Some(g) = transformed_objects
-                            .polylines
-                            .iter_mut()
-                            .find(|p| p.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::PointCloud(_) => {
-                        if let Some(g) = transformed_objects
-                            .pointclouds
-                            .iter_mut()
-                            .find(|p| p.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::Mesh(_) => {
-                        if let Some(g) = transformed_objects
-                            .meshes
-                            .iter_mut()
-                            .find(|m| m.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::Cylinder(_) => {
-                        if let Some(g) = transformed_objects
-                            .cylinders
-                            .iter_mut()
-                            .find(|c| c.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                    Geometry::Arrow(_) => {
-                        if let Some(g) = transformed_objects
-                            .arrows
-                            .iter_mut()
-                            .find(|a| a.guid == node_name)
-                        {
-                            g.xform = combined_xform.clone();
-                        }
-                    }
-                }
+    /// Geometry of the session's own objects with the tree transformations applied.
+    ///
+    /// The combined xforms are computed in one pass over the tree, then every
+    /// object is copied and transformed, in parallel with the `parallel` feature.
+    fn get_tree_geometry(&self) -> Objects {
+        let own: HashMap<&str, &Xform> = self
+            .lookup
+            .iter()
+            .map(|(guid, geometry)| (guid.as_str(), geometry.xform()))
+            .collect();
 
-                combined_xform
-            } else {
-                parent_xform.clone()
+        // Depth-first in child order, so an object listed twice gets its last placement
+        let mut combined: HashMap<String, Xform> = HashMap::with_capacity(own.len());
+        let mut stack: Vec<(TreeNode, Xform)> = self
+            .tree
+            .root()
+            .map(|root| (root, Xform::identity()))
+            .into_iter()
+            .collect();
+        while let Some((node, parent_xform)) = stack.pop() {
+            // The node xform moves its geometry and the whole subtree
+            let node_xform = match node.xform() {
+                Some(xform) => &parent_xform * &xform,
+                None => parent_xform,
             };
-
-            for child in node.children() {
-                transform_node(
-                    &child,
-                    &current_xform,
-                    transformed_lookup,
-                    transformed_objects,
-                );
+            let name = node.name();
+            let current_xform = match own.get(name.as_str()) {
+                Some(&xform) => {
+                    let xform = &node_xform * xform;
+                    combined.insert(name, xform.clone());
+                    xform
+                }
+                None => node_xform,
+            };
+            for child in node.children().into_iter().rev() {
+                stack.push((child, current_xform.clone()));
             }
         }
 
-        if let Some(root) = self.tree.root() {
-            transform_node(
-                &root,
-                &Xform::identity(),
-                &transformed_lookup,
-                &mut transformed_objects,
-            );
-        }
+        // Objects outside the tree keep their own xform
+        let placed = |geometry: &Geometry| {
+            let mut geometry = geometry.clone();
+            if let Some(xform) = combined.get(geometry.guid()) {
+                *geometry.xform_mut() = xform.clone();
+            }
+            geometry.transform();
+            geometry
+        };
+        let geometries: Vec<&Geometry> = self.objects().collect();
+        #[cfg(feature = "parallel")]
+        let placed: Vec<Geometry> = geometries.into_par_iter().map(placed).collect();
+        #[cfg(not(feature = "parallel"))]
+        let placed: Vec<Geometry> = geometries.into_iter().map(placed).collect();
 
-        // Apply accumulated transformations to actual geometry coordinates
-        for point in &mut transformed_objects.points {
-            point.transform();
-        }
-        for line in &mut transformed_objects.lines {
-            line.transform();
-        }
-        for plane in &mut transformed_objects.planes {
-            plane.transform();
-        }
-        for bbox in &mut transformed_objects.bboxes {
-            bbox.transform();
-        }
-        for polyline in &mut transformed_objects.polylines {
-            polyline.transform();
-        }
-        for pointcloud in &mut transformed_objects.pointclouds {
-            pointcloud.transform();
-        }
-        for mesh in &mut transformed_objects.meshes {
-            mesh.transform();
-        }
-        for cylinder in &mut transformed_objects.cylinders {
-            cylinder.transform();
-        }
-        for arrow in &mut transformed_objects.arrows {
-            arrow.transform();
+        let mut objects = Objects {
+            guid: self.order.guid.clone(),
+            name: self.order.name.clone(),
+            ..Objects::new()
+        };
+        for geometry in placed {
+            Self::push_object(&mut objects, geometry);
         }
+        objects
+    }
+}
+
+/// Mutable access to a Session object, see `Session::get_object_mut`.
+///
+/// Keeps the cached bounding boxes in sync when dropped.
+pub struct GeometryMut<'a> {
+    session: &'a mut Session,
+    guid: String,
+}
+
+impl std::ops::Deref for GeometryMut<'_> {
+    type Target = Geometry;
+
+    fn deref(&self) -> &Geometry {
+        &self.session.lookup[&self.guid]
+    }
+}
+
+impl std::ops::DerefMut for GeometryMut<'_> {
+    fn deref_mut(&mut self) -> &mut Geometry {
+        Arc::make_mut(self.session.lookup.get_mut(&self.guid).unwrap())
+    }
+}
 
-        transformed_objects
+impl Drop for GeometryMut<'_> {
+    fn drop(&mut self) {
+        self.session.sync_object(&self.guid);
     }
 }
 
@@ -1100,7 +4429,7 @@ impl fmt::Display for Session {
             "Session({}, {}, points={}, vertices={}, edges={})",
             self.name,
             self.guid,
-            self.objects.points.len(),
+            self.objects_of::<Point>().len(),
             self.graph.vertex_count,
             self.graph.edge_count
         )
@@ -1,10 +1,15 @@
 #[cfg(test)]
 mod tests {
     use crate::encoders::{json_dump, json_load};
+    use crate::scalar::test_tolerance;
+    use crate::Scalar;
     use crate::{
-        Arrow, BoundingBox, Cylinder, Line, Mesh, Plane, Point, PointCloud, Polyline, Session,
-        TreeNode, Vector, BVH,
+        AnimationTrack, Arrow, AttrValue, BoundingBox, BroadPhase, CollisionOptions, Color,
+        Cylinder, Geometry, IdGenerator, Line, MergeStrategy, Mesh, Plane, Point, PointCloud,
+        Polyline, RayCastMode, RayCastOptions, RayHit, RemovalPolicy, SelectionMode, Session,
+        SessionError, SessionEvent, TreeNode, ValidationIssue, Vec3, Vector, Xform, BVH,
     };
+    use std::sync::Arc;
 
     #[test]
     fn test_session_serialization_with_all_geometry_types() {
@@ -63,15 +68,15 @@ mod tests {
         my_session.add_edge(&line.guid, &plane.guid, "line_to_plane");
 
         // Verify original session structure before serialization
-        assert_eq!(my_session.objects.points.len(), 1);
-        assert_eq!(my_session.objects.lines.len(), 1);
-        assert_eq!(my_session.objects.planes.len(), 1);
-        assert_eq!(my_session.objects.bboxes.len(), 1);
-        assert_eq!(my_session.objects.polylines.len(), 1);
-        assert_eq!(my_session.objects.pointclouds.len(), 1);
-        assert_eq!(my_session.objects.meshes.len(), 1);
-        assert_eq!(my_session.objects.cylinders.len(), 1);
-        assert_eq!(my_session.objects.arrows.len(), 1);
+        assert_eq!(my_session.objects_of::<Point>().len(), 1);
+        assert_eq!(my_session.objects_of::<Line>().len(), 1);
+        assert_eq!(my_session.objects_of::<Plane>().len(), 1);
+        assert_eq!(my_session.objects_of::<BoundingBox>().len(), 1);
+        assert_eq!(my_session.objects_of::<Polyline>().len(), 1);
+        assert_eq!(my_session.objects_of::<PointCloud>().len(), 1);
+        assert_eq!(my_session.objects_of::<Mesh>().len(), 1);
+        assert_eq!(my_session.objects_of::<Cylinder>().len(), 1);
+        assert_eq!(my_session.objects_of::<Arrow>().len(), 1);
         assert_eq!(my_session.lookup.len(), 9);
 
         // Graph structure before serialization
@@ -94,23 +99,41 @@ mod tests {
         assert_eq!(loaded.name, my_session.name);
 
         // Verify all geometry objects are preserved
-        assert_eq!(loaded.objects.arrows.len(), my_session.objects.arrows.len());
-        assert_eq!(loaded.objects.bboxes.len(), my_session.objects.bboxes.len());
         assert_eq!(
-            loaded.objects.cylinders.len(),
-            my_session.objects.cylinders.len()
+            loaded.objects_of::<Arrow>().len(),
+            my_session.objects_of::<Arrow>().len()
         );
-        assert_eq!(loaded.objects.lines.len(), my_session.objects.lines.len());
-        assert_eq!(loaded.objects.meshes.len(), my_session.objects.meshes.len());
-        assert_eq!(loaded.objects.planes.len(), my_session.objects.planes.len());
-        assert_eq!(loaded.objects.points.len(), my_session.objects.points.len());
         assert_eq!(
-            loaded.objects.pointclouds.len(),
-            my_session.objects.pointclouds.len()
+            loaded.objects_of::<BoundingBox>().len(),
+            my_session.objects_of::<BoundingBox>().len()
         );
         assert_eq!(
-            loaded.objects.polylines.len(),
-            my_session.objects.polylines.len()
+            loaded.objects_of::<Cylinder>().len(),
+            my_session.objects_of::<Cylinder>().len()
+        );
+        assert_eq!(
+            loaded.objects_of::<Line>().len(),
+            my_session.objects_of::<Line>().len()
+        );
+        assert_eq!(
+            loaded.objects_of::<Mesh>().len(),
+            my_session.objects_of::<Mesh>().len()
+        );
+        assert_eq!(
+            loaded.objects_of::<Plane>().len(),
+            my_session.objects_of::<Plane>().len()
+        );
+        assert_eq!(
+            loaded.objects_of::<Point>().len(),
+            my_session.objects_of::<Point>().len()
+        );
+        assert_eq!(
+            loaded.objects_of::<PointCloud>().len(),
+            my_session.objects_of::<PointCloud>().len()
+        );
+        assert_eq!(
+            loaded.objects_of::<Polyline>().len(),
+            my_session.objects_of::<Polyline>().len()
         );
 
         // Verify lookup table is preserved (rebuilt from objects during deserialization)
@@ -130,7 +153,7 @@ mod tests {
         // File I/O
         json_dump(&my_session, "test_session.json", true).unwrap();
         let from_file: Session = json_load("test_session.json").unwrap();
-        assert!(!from_file.objects.points.is_empty());
+        assert!(!from_file.objects_of::<Point>().is_empty());
     }
 
     #[test]
@@ -207,15 +230,15 @@ mod tests {
         let mut rng = StdRng::seed_from_u64(42);
 
         let object_count = 2000;
-        let world_size = 100.0f64;
+        let world_size: Scalar = 100.0;
 
         let mut scene = Session::new("perf_points_rs");
         let mut pure_boxes: Vec<BoundingBox> = Vec::with_capacity(object_count);
 
         for _ in 0..object_count {
-            let x = (rng.gen::<f64>() - 0.5) * world_size;
-            let y = (rng.gen::<f64>() - 0.5) * world_size;
-            let z = (rng.gen::<f64>() - 0.5) * world_size;
+            let x = (rng.gen::<Scalar>() - 0.5) * world_size;
+            let y = (rng.gen::<Scalar>() - 0.5) * world_size;
+            let z = (rng.gen::<Scalar>() - 0.5) * world_size;
             let pt = Point::new(x, y, z);
             scene.add_point(pt.clone());
             pure_boxes.push(BoundingBox::new(
@@ -333,19 +356,19 @@ mod tests {
         let mut rng = StdRng::seed_from_u64(123);
 
         let object_count = 3000usize;
-        let world_size = 200.0f64;
+        let world_size: Scalar = 200.0;
         let repeats = 50usize;
 
         let mut scene = Session::new("ray_cache_bench");
         // Add random points and some lines to populate BVH
         for _ in 0..object_count {
-            let x = (rng.gen::<f64>() - 0.5) * world_size;
-            let y = (rng.gen::<f64>() - 0.5) * world_size;
-            let z = (rng.gen::<f64>() - 0.5) * world_size;
+            let x = (rng.gen::<Scalar>() - 0.5) * world_size;
+            let y = (rng.gen::<Scalar>() - 0.5) * world_size;
+            let z = (rng.gen::<Scalar>() - 0.5) * world_size;
             scene.add_point(Point::new(x, y, z));
         }
         for i in 0..100 {
-            let x = -50.0 + i as f64 * 1.0;
+            let x = -50.0 + i as Scalar * 1.0;
             scene.add_line(Line::from_points(
                 &Point::new(x, -10.0, 0.0),
                 &Point::new(x, 10.0, 0.0),
@@ -377,4 +400,1924 @@ mod tests {
 
         assert!(t_first >= 0.0 && avg_cached >= 0.0);
     }
+
+    #[test]
+    fn test_ray_cast_cylinder_and_arrow_surface() {
+        let mut scene = Session::new("cylinder_arrow_surface");
+        let cylinder = Cylinder::new(Line::new(10.0, 0.0, -1.0, 10.0, 0.0, 1.0), 0.5);
+        let cylinder_guid = cylinder.guid.clone();
+        scene.add_cylinder(cylinder);
+
+        let hits = scene.ray_cast(
+            &Point::new(0.0, 0.0, 0.0),
+            &Vector::new(1.0, 0.0, 0.0),
+            1e-3,
+        );
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, cylinder_guid);
+        assert!(hits[0].point.distance(&Point::new(9.5, 0.0, 0.0)) < test_tolerance(1e-6));
+
+        let mut scene = Session::new("arrow_surface");
+        let arrow = Arrow::new(Line::new(0.0, 0.0, 10.0, 0.0, 0.0, 0.0), 0.1);
+        scene.add_arrow(arrow);
+        let hits = scene.ray_cast(
+            &Point::new(0.0, 0.0, 20.0),
+            &Vector::new(0.0, 0.0, -1.0),
+            1e-3,
+        );
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].point.distance(&Point::new(0.0, 0.0, 10.0)) < test_tolerance(1e-6));
+    }
+
+    #[test]
+    fn test_ray_cast_placed_objects_in_local_frame() {
+        let mut scene = Session::new("placed");
+        let mut cylinder = Cylinder::new(Line::new(-1.0, 0.0, 0.0, 1.0, 0.0, 0.0), 0.5);
+        cylinder.xform = &Xform::translation(10.0, 0.0, 0.0)
+            * &Xform::rotation_z(crate::scalar::consts::FRAC_PI_2);
+        let cylinder_guid = cylinder.guid.clone();
+        scene.add_cylinder(cylinder);
+        let mut point = Point::new(1.0, 0.0, 0.0);
+        point.xform = Xform::scale_xyz(5.0, 5.0, 5.0);
+        let point_guid = point.guid.clone();
+        scene.add_point(point);
+
+        let origin = Point::new(0.0, 0.0, 0.0);
+        let options = RayCastOptions {
+            mode: RayCastMode::All,
+            ..Default::default()
+        };
+        let hits = scene.ray_cast_with(&origin, &Vector::new(1.0, 0.0, 0.0), 1e-3, &options);
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].guid, point_guid);
+        assert!(hits[0].point.distance(&Point::new(5.0, 0.0, 0.0)) < test_tolerance(1e-6));
+        assert_eq!(hits[1].guid, cylinder_guid);
+        assert!(hits[1].point.distance(&Point::new(9.5, 0.0, 0.0)) < test_tolerance(1e-6));
+        assert!(hits[2].point.distance(&Point::new(10.5, 0.0, 0.0)) < test_tolerance(1e-6));
+    }
+
+    #[test]
+    fn test_ray_cast_pointcloud_splats() {
+        let mut scene = Session::new("pointcloud_splats");
+        let coords: Vec<Point> = (0..21)
+            .flat_map(|i| {
+                (0..21).map(move |j| {
+                    Point::new(10.0, -1.0 + i as Scalar * 0.1, -1.0 + j as Scalar * 0.1)
+                })
+            })
+            .collect();
+        let cloud = PointCloud::new(coords, vec![], vec![]);
+        let cloud_guid = cloud.guid.clone();
+        scene.add_pointcloud(cloud);
+
+        let origin = Point::new(0.0, 0.03, 0.02);
+        let hits = scene.ray_cast(&origin, &Vector::new(1.0, 0.0, 0.0), 0.05);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, cloud_guid);
+        assert!(hits[0].point.distance(&Point::new(10.0, 0.03, 0.02)) < 1e-6);
+
+        let gap = Point::new(0.0, 0.05, 0.05);
+        assert!(scene
+            .ray_cast(&gap, &Vector::new(1.0, 0.0, 0.0), 0.05)
+            .is_empty());
+
+        // A moved cloud is hit through its kd-tree in the local frame
+        scene.translate(&cloud_guid, &Vector::new(5.0, 0.0, 0.0), false);
+        let hits = scene.ray_cast(&origin, &Vector::new(1.0, 0.0, 0.0), 0.05);
+        assert!(hits[0].point.distance(&Point::new(15.0, 0.03, 0.02)) < 1e-6);
+        let Some(Geometry::PointCloud(cloud)) = scene.get_object(&cloud_guid) else {
+            panic!("point cloud expected");
+        };
+        assert!(cloud.kdtree.get().is_some());
+        assert!(cloud.points[0].x == 10.0);
+    }
+
+    #[test]
+    fn test_large_pointcloud_takes_a_leaf_per_chunk() {
+        let mut scene = Session::new("chunk_leaves");
+        // Three chunks along the x axis, the last one short
+        let count = 2 * PointCloud::CHUNK_SIZE + 100;
+        let coords: Vec<Vec3> = (0..count)
+            .map(|i| Vec3::new(i as Scalar * 0.001, 0.0, 0.0))
+            .collect();
+        let cloud = PointCloud::from_coords(coords, vec![], vec![]);
+        let cloud_guid = cloud.guid.clone();
+        scene.add_pointcloud(cloud);
+        let line = Line::new(10.0, -1.0, 0.0, 10.0, 1.0, 0.0);
+        let line_guid = line.guid.clone();
+        scene.add_line(line);
+        assert_eq!(scene.stats().cached_box_count, 4);
+
+        // Queries over several chunks list the cloud once
+        let region = BoundingBox::from_points(
+            &[Point::new(60.0, -1.0, -1.0), Point::new(70.0, 1.0, 1.0)],
+            0.0,
+        );
+        assert_eq!(scene.objects_in_box(&region), vec![cloud_guid.clone()]);
+        let closest = scene.closest_objects(&Point::new(10.0, 0.5, 0.0), 2);
+        assert_eq!(closest.len(), 2);
+        assert_ne!(closest[0].0, closest[1].0);
+
+        let down = Vector::new(0.0, 0.0, -1.0);
+        let hits = scene.ray_cast(&Point::new(100.0, 0.0, 10.0), &down, 4e-4);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, cloud_guid);
+        assert!(hits[0].point.distance(&Point::new(100.0, 0.0, 0.0)) < 1e-3);
+        let options = RayCastOptions {
+            mode: RayCastMode::All,
+            ..Default::default()
+        };
+        let along = Vector::new(1.0, 0.0, 0.0);
+        let hits = scene.ray_cast_with(&Point::new(-1.0, 0.0, 0.0), &along, 4e-4, &options);
+        let cloud_hits: Vec<&RayHit> = hits.iter().filter(|h| h.guid == cloud_guid).collect();
+        assert_eq!(cloud_hits.len(), 1);
+        assert!((cloud_hits[0].distance - 1.0).abs() < 1e-6);
+
+        // Chunks overlapping each other and the line give one pair
+        assert_eq!(scene.get_collisions().len(), 1);
+
+        // A window around the first chunk holds the line but only crosses the cloud
+        let slab = |point: [Scalar; 3], normal: [Scalar; 3]| {
+            Plane::from_point_normal(
+                Point::new(point[0], point[1], point[2]),
+                Vector::new(normal[0], normal[1], normal[2]),
+            )
+        };
+        let planes = [
+            slab([-1.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+            slab([70.0, 0.0, 0.0], [-1.0, 0.0, 0.0]),
+            slab([0.0, -2.0, 0.0], [0.0, 1.0, 0.0]),
+            slab([0.0, 2.0, 0.0], [0.0, -1.0, 0.0]),
+            slab([0.0, 0.0, -2.0], [0.0, 0.0, 1.0]),
+            slab([0.0, 0.0, 2.0], [0.0, 0.0, -1.0]),
+        ];
+        assert_eq!(
+            scene.select_frustum(&planes, SelectionMode::Window),
+            vec![line_guid.clone()]
+        );
+        assert_eq!(
+            scene.select_frustum(&planes, SelectionMode::Crossing).len(),
+            2
+        );
+
+        // Moving the cloud moves all its leaves
+        scene.translate(&cloud_guid, &Vector::new(0.0, 0.0, 5.0), false);
+        assert!(scene.update_collisions(std::slice::from_ref(&cloud_guid)).is_empty());
+        let hits = scene.ray_cast(&Point::new(100.0, 0.0, 10.0), &down, 4e-4);
+        assert!(hits[0].point.distance(&Point::new(100.0, 0.0, 5.0)) < 1e-3);
+
+        scene.remove_object(&cloud_guid);
+        assert_eq!(scene.stats().cached_box_count, 1);
+        assert!(scene
+            .ray_cast(&Point::new(100.0, 0.0, 10.0), &down, 4e-4)
+            .is_empty());
+        assert_eq!(scene.objects_in_box(&region), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_ray_cast_incremental_cache_updates() {
+        let mut scene = Session::new("incremental_cache");
+        let near = Line::from_points(&Point::new(10.0, -2.0, 0.0), &Point::new(10.0, 2.0, 0.0));
+        let near_guid = near.guid.clone();
+        scene.add_line(near);
+
+        let ray_origin = Point::new(0.0, 0.0, 0.0);
+        let ray_dir = Vector::new(1.0, 0.0, 0.0);
+        assert_eq!(scene.ray_cast(&ray_origin, &ray_dir, 1e-3).len(), 1);
+
+        // Objects added after the first cast are inserted into the cached BVH
+        let far = Line::from_points(&Point::new(20.0, -2.0, 0.0), &Point::new(20.0, 2.0, 0.0));
+        let far_guid = far.guid.clone();
+        scene.add_line(far);
+        let off = Line::from_points(&Point::new(5.0, 5.0, -1.0), &Point::new(5.0, 5.0, 1.0));
+        let off_guid = off.guid.clone();
+        scene.add_line(off);
+        assert!(!scene.bvh_cache_dirty);
+
+        let hits = scene.ray_cast(&ray_origin, &ray_dir, 1e-3);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, near_guid);
+
+        // Removing the closest object exposes the one behind it
+        scene.remove_object(&near_guid);
+        assert!(!scene.bvh_cache_dirty);
+        let hits = scene.ray_cast(&ray_origin, &ray_dir, 1e-3);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, far_guid);
+
+        // The last object took the freed slot and still moves in place
+        scene.translate(&off_guid, &Vector::new(0.0, -5.0, 0.0), false);
+        assert!(!scene.bvh_cache_dirty);
+        let hits = scene.ray_cast(&ray_origin, &ray_dir, 1e-3);
+        assert_eq!(hits[0].guid, off_guid);
+    }
+
+    #[test]
+    fn test_closest_objects() {
+        let mut scene = Session::new("closest_objects");
+        let near = Point::new(1.0, 0.0, 0.0);
+        let near_guid = near.guid.clone();
+        scene.add_point(near);
+        let far = Point::new(10.0, 0.0, 0.0);
+        let far_guid = far.guid.clone();
+        scene.add_point(far);
+
+        let closest = scene.closest_objects(&Point::new(0.0, 0.0, 0.0), 2);
+        assert_eq!(closest.len(), 2);
+        assert_eq!(closest[0].0, near_guid);
+        assert_eq!(closest[1].0, far_guid);
+        assert!(closest[0].1 <= closest[1].1);
+
+        let closest = scene.closest_objects(&Point::new(9.0, 0.0, 0.0), 1);
+        assert_eq!(closest[0].0, far_guid);
+    }
+
+    #[test]
+    fn test_objects_in_box() {
+        let mut scene = Session::new("objects_in_box");
+        let inside = Point::new(1.0, 1.0, 1.0);
+        let inside_guid = inside.guid.clone();
+        scene.add_point(inside);
+        scene.add_point(Point::new(10.0, 10.0, 10.0));
+
+        let region =
+            BoundingBox::from_points(&[Point::new(0.0, 0.0, 0.0), Point::new(2.0, 2.0, 2.0)], 0.0);
+        assert_eq!(scene.objects_in_box(&region), vec![inside_guid]);
+    }
+
+    #[test]
+    fn test_get_collisions_spatial_hash_matches_bvh() {
+        let mut scene = Session::new("broad_phase");
+        for i in 0..20 {
+            let x = i as Scalar * 0.75;
+            scene.add_line(Line::new(x, 0.0, 0.0, x + 1.0, 0.0, 0.0));
+        }
+
+        let mut bvh_pairs = scene.get_collisions();
+        scene.broad_phase = BroadPhase::SpatialHash;
+        let mut hash_pairs = scene.get_collisions();
+
+        let normalize = |pairs: &mut Vec<(String, String)>| {
+            for pair in pairs.iter_mut() {
+                if pair.0 > pair.1 {
+                    std::mem::swap(&mut pair.0, &mut pair.1);
+                }
+            }
+            pairs.sort();
+        };
+        normalize(&mut bvh_pairs);
+        normalize(&mut hash_pairs);
+        assert_eq!(bvh_pairs.len(), 19);
+        assert_eq!(hash_pairs, bvh_pairs);
+    }
+
+    #[test]
+    fn test_session_octree() {
+        let mut scene = Session::new("octree");
+        for i in 0..10 {
+            scene.add_point(Point::new(i as Scalar, 0.0, 0.0));
+        }
+        let octree = scene.octree(3, 2);
+        assert_eq!(octree.root().unwrap().count, 10);
+        assert_eq!(octree.object_guids.len(), 10);
+
+        let mut guids: Vec<String> = octree
+            .level_of_detail(2)
+            .iter()
+            .flat_map(|node| octree.objects_in(node))
+            .map(|id| octree.object_guids[id].clone())
+            .collect();
+        guids.sort();
+        let mut expected: Vec<String> = scene.lookup.keys().cloned().collect();
+        expected.sort();
+        assert_eq!(guids, expected);
+    }
+
+    #[test]
+    fn test_ray_cast_moved_mesh_keeps_triangle_bvh() {
+        let mut scene = Session::new("mesh_instance");
+        let tri = vec![
+            Point::new(30.0, -1.0, -1.0),
+            Point::new(30.0, 1.0, -1.0),
+            Point::new(30.0, 0.0, 1.0),
+        ];
+        let mesh = Mesh::from_polygons(vec![tri], None);
+        let mesh_guid = mesh.guid.clone();
+        scene.add_mesh(mesh);
+
+        let ray_origin = Point::new(0.0, 0.0, 0.0);
+        let ray_dir = Vector::new(1.0, 0.0, 0.0);
+        let hits = scene.ray_cast(&ray_origin, &ray_dir, 1e-3);
+        assert!((hits[0].distance - 30.0).abs() < 1e-9);
+
+        // Move the mesh up, the original ray misses and a raised one hits
+        assert!(scene.set_mesh_xform(&mesh_guid, Xform::translation(5.0, 0.0, 10.0)));
+        assert!(scene.ray_cast(&ray_origin, &ray_dir, 1e-3).is_empty());
+        let hits = scene.ray_cast(&Point::new(0.0, 0.0, 10.0), &ray_dir, 1e-3);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, mesh_guid);
+        assert!((hits[0].point.x() - 35.0).abs() < 1e-9);
+        assert!((hits[0].point.z() - 10.0).abs() < 1e-9);
+
+        // The scene BVH was refitted in place and the triangle BVH is still cached
+        assert!(!scene.bvh_cache_dirty);
+        match scene.get_object(&mesh_guid) {
+            Some(Geometry::Mesh(m)) => assert!(m.triangle_bvh.get().is_some()),
+            _ => panic!("mesh not found"),
+        }
+
+        let line = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let line_guid = line.guid.clone();
+        scene.add_line(line);
+        assert!(!scene.set_mesh_xform(&line_guid, Xform::identity()));
+    }
+
+    #[test]
+    fn test_transaction_undo_redo() {
+        let mut scene = Session::new("undo_redo");
+        let point = Point::new(10.0, 0.0, 0.0);
+        let point_guid = point.guid.clone();
+        let point_node = scene.add_point(point);
+        scene.add(&point_node, None);
+        assert!(!scene.can_undo());
+
+        assert!(scene.begin_transaction());
+        assert!(!scene.begin_transaction());
+        let line = Line::new(5.0, -1.0, 0.0, 5.0, 1.0, 0.0);
+        let line_guid = line.guid.clone();
+        let line_node = scene.add_line(line);
+        scene.add(&line_node, &point_node);
+        scene.add_edge(&point_guid, &line_guid, "attached");
+        assert!(!scene.undo());
+        assert!(scene.commit());
+        assert!(!scene.commit());
+
+        let origin = Point::new(0.0, 0.0, 0.0);
+        let x_axis = Vector::new(1.0, 0.0, 0.0);
+        assert_eq!(scene.ray_cast(&origin, &x_axis, 1e-3)[0].guid, line_guid);
+
+        assert!(scene.undo());
+        assert!(scene.get_object(&line_guid).is_none());
+        assert!(scene.objects_of::<Line>().is_empty());
+        assert!(scene.tree.get_node_by_name(&line_guid).is_none());
+        assert!(!scene.graph.has_node(&line_guid));
+        assert_eq!(scene.ray_cast(&origin, &x_axis, 1e-3)[0].guid, point_guid);
+        assert!(!scene.can_undo());
+        assert!(scene.can_redo());
+
+        assert!(scene.redo());
+        assert!(scene.get_object(&line_guid).is_some());
+        assert_eq!(scene.get_children(&point_node.guid()).len(), 1);
+        assert!(scene.graph.has_edge((&point_guid, &line_guid)));
+        assert_eq!(scene.ray_cast(&origin, &x_axis, 1e-3)[0].guid, line_guid);
+        assert!(!scene.can_redo());
+
+        // Snapshots are not affected by later edits of the live tree
+        assert!(scene.begin_transaction());
+        scene.remove_object(&point_guid);
+        scene.add(&TreeNode::new("folder"), None);
+        assert!(scene.get_object(&point_guid).is_none());
+        assert!(scene.rollback());
+        assert!(scene.get_object(&point_guid).is_some());
+        assert!(scene.tree.get_node_by_name("folder").is_none());
+        assert!(scene.tree.get_node_by_name(&line_guid).is_some());
+        assert!(scene.can_undo());
+    }
+
+    #[test]
+    fn test_undo_records_only_changes() {
+        let mut scene = Session::new("undo_changes");
+        let guids: Vec<String> = (0..4)
+            .map(|i| {
+                let point = Point::new(i as Scalar, 0.0, 0.0);
+                let guid = point.guid.clone();
+                let node = scene.add_point(point);
+                scene.add(&node, None);
+                guid
+            })
+            .collect();
+        let before = scene.jsondump().unwrap();
+
+        scene.begin_transaction();
+        scene.translate(&guids[0], &Vector::new(0.0, 0.0, 1.0), false);
+        scene.set_attribute(&guids[1], "layer", "walls".into());
+        scene.add_edge(&guids[2], &guids[3], "support");
+        scene.remove_object(&guids[1]);
+        scene.commit();
+        let after = scene.jsondump().unwrap();
+
+        // The step holds the changed entries, not copies of the whole Session
+        let step = &scene.undo_stack[0];
+        assert_eq!(step.objects.len(), 2);
+        assert!(step.attributes.is_empty());
+        assert_eq!(step.graph_nodes.len(), 3);
+        assert!(step.tree.is_some());
+        assert!(step.links.is_none());
+
+        assert!(scene.undo());
+        assert_eq!(scene.jsondump().unwrap(), before);
+        assert!(scene.get_attribute(&guids[1], "layer").is_none());
+        let order: Vec<String> = scene.objects().map(|g| g.guid().to_string()).collect();
+        assert_eq!(order, guids);
+        assert!(scene.redo());
+        assert_eq!(scene.jsondump().unwrap(), after);
+        assert!(scene.undo());
+        assert_eq!(
+            scene.ray_cast(
+                &Point::new(1.0, 0.0, -5.0),
+                &Vector::new(0.0, 0.0, 1.0),
+                1e-3
+            )[0]
+            .guid,
+            guids[1]
+        );
+    }
+
+    #[test]
+    fn test_update_object_keeps_collections_and_cache_in_sync() {
+        let mut scene = Session::new("update_object");
+        let line = Line::new(10.0, -1.0, 0.0, 10.0, 1.0, 0.0);
+        let guid = line.guid.clone();
+        scene.add_line(line);
+
+        let origin = Point::new(0.0, 0.0, 0.0);
+        let x_axis = Vector::new(1.0, 0.0, 0.0);
+        assert!((scene.ray_cast(&origin, &x_axis, 1e-3)[0].distance - 10.0).abs() < 1e-9);
+
+        // Edit in place through the guard
+        if let Some(mut geometry) = scene.get_object_mut(&guid) {
+            if let Geometry::Line(l) = &mut *geometry {
+                *l = Line::new(20.0, -1.0, 0.0, 20.0, 1.0, 0.0);
+                l.guid = guid.clone();
+            }
+        }
+        assert_eq!(scene.objects_of::<Line>().len(), 1);
+        assert!((scene.objects_of::<Line>()[0].start().x() - 20.0).abs() < 1e-12);
+        assert!(!scene.bvh_cache_dirty);
+        assert!((scene.ray_cast(&origin, &x_axis, 1e-3)[0].distance - 20.0).abs() < 1e-9);
+
+        // Replace with a different geometry type
+        let mut point = Point::new(5.0, 0.0, 0.0);
+        point.guid = guid.clone();
+        assert!(scene.update_object(&guid, Geometry::Point(point)));
+        assert!(scene.objects_of::<Line>().is_empty());
+        assert_eq!(scene.objects_of::<Point>().len(), 1);
+        let hits = scene.ray_cast(&origin, &x_axis, 1e-3);
+        assert!((hits[0].distance - 5.0).abs() < 1e-9);
+
+        assert!(!scene.update_object(&guid, Geometry::Point(Point::new(0.0, 0.0, 0.0))));
+        assert!(!scene.update_object("missing", Geometry::Point(Point::new(0.0, 0.0, 0.0))));
+        assert!(scene.get_object_mut("missing").is_none());
+    }
+
+    #[test]
+    fn test_edits_keep_object_order() {
+        let mut scene = Session::new("order");
+        let guids: Vec<String> = (0..3)
+            .map(|i| {
+                let line = Line::new(i as Scalar, 0.0, 0.0, i as Scalar, 1.0, 0.0);
+                let guid = line.guid.clone();
+                scene.add_line(line);
+                guid
+            })
+            .collect();
+        let line_guids = |scene: &Session| -> Vec<String> {
+            (scene.objects_of::<Line>().iter())
+                .map(|line| line.guid.clone())
+                .collect()
+        };
+
+        if let Some(mut geometry) = scene.get_object_mut(&guids[0]) {
+            geometry.xform_mut().m[12] = 5.0;
+        }
+        scene.translate(&guids[1], &Vector::new(0.0, 0.0, 1.0), false);
+        let mut line = scene.objects_of::<Line>()[0].clone();
+        line.name = "edited".to_string();
+        scene.update_object(&guids[0], Geometry::Line(line));
+        assert_eq!(line_guids(&scene), guids);
+
+        let loaded = Session::jsonload(&scene.jsondump().unwrap()).unwrap();
+        assert_eq!(line_guids(&loaded), guids);
+    }
+
+    #[test]
+    fn test_iter_objects_in_insertion_order() {
+        let mut scene = Session::new("iter");
+        let point = Point::new(0.0, 0.0, 0.0);
+        let point_guid = point.guid.clone();
+        scene.add_point(point);
+        let line = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let line_guid = line.guid.clone();
+        scene.add_line(line);
+        let second = Point::new(1.0, 0.0, 0.0);
+        let second_guid = second.guid.clone();
+        scene.add_point(second);
+
+        let pairs: Vec<(&str, &Geometry)> = scene.iter_objects().collect();
+        assert_eq!(pairs.len(), 3);
+        for (guid, geometry) in &pairs {
+            assert_eq!(*guid, geometry.guid());
+        }
+        let order: Vec<&str> = pairs.iter().map(|(guid, _)| *guid).collect();
+        assert_eq!(order, [point_guid.as_str(), &line_guid, &second_guid]);
+
+        scene.remove_object(&line_guid);
+        let order: Vec<&str> = scene.iter_objects().map(|(guid, _)| guid).collect();
+        assert_eq!(order, [point_guid.as_str(), &second_guid]);
+        assert_eq!(scene.objects().count(), 2);
+    }
+
+    #[test]
+    fn test_on_change_events() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut scene = Session::new("events");
+        let events: Rc<RefCell<Vec<SessionEvent>>> = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&events);
+        let id = scene.on_change(move |event| sink.borrow_mut().push(event.clone()));
+
+        let point = Point::new(0.0, 0.0, 0.0);
+        let point_guid = point.guid.clone();
+        scene.add_point(point);
+        let mesh = Mesh::from_polygons(
+            vec![vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ]],
+            None,
+        );
+        let mesh_guid = mesh.guid.clone();
+        scene.add_mesh(mesh);
+        scene.add_edge(&point_guid, &mesh_guid, "support");
+        scene.set_mesh_xform(&mesh_guid, Xform::translation(0.0, 0.0, 1.0));
+        scene.remove_object(&point_guid);
+
+        {
+            let events = events.borrow();
+            assert_eq!(events.len(), 5);
+            assert!(
+                matches!(&events[0], SessionEvent::ObjectAdded { guid } if *guid == point_guid)
+            );
+            assert!(matches!(&events[1], SessionEvent::ObjectAdded { guid } if *guid == mesh_guid));
+            assert!(matches!(
+                &events[2],
+                SessionEvent::EdgeAdded { from, to, attribute }
+                    if *from == point_guid && *to == mesh_guid && attribute == "support"
+            ));
+            assert!(matches!(
+                &events[3],
+                SessionEvent::ObjectTransformed { guid, xform }
+                    if *guid == mesh_guid && !xform.is_identity()
+            ));
+            assert!(
+                matches!(&events[4], SessionEvent::ObjectRemoved { guid } if *guid == point_guid)
+            );
+        }
+
+        // Clones do not inherit listeners and removed listeners are not called
+        let mut copy = scene.clone();
+        copy.add_point(Point::new(1.0, 0.0, 0.0));
+        assert!(scene.remove_listener(id));
+        assert!(!scene.remove_listener(id));
+        scene.add_point(Point::new(2.0, 0.0, 0.0));
+        assert_eq!(events.borrow().len(), 5);
+    }
+
+    #[test]
+    fn test_diff_and_merge() {
+        let mut base = Session::new("base");
+        let point = Point::new(0.0, 0.0, 0.0);
+        let point_guid = point.guid.clone();
+        let point_node = base.add_point(point);
+        base.add(&point_node, None);
+        let line = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let line_guid = line.guid.clone();
+        let line_node = base.add_line(line);
+        base.add(&line_node, None);
+        base.add_edge(&point_guid, &line_guid, "start");
+
+        let json = base.jsondump().unwrap();
+        let ours = Session::jsonload(&json).unwrap();
+        let mut theirs = Session::jsonload(&json).unwrap();
+        assert!(ours.diff(&theirs).is_empty());
+
+        // Their edits: move the line, drop the point, add a plane linked to the line
+        let mut moved = Line::new(0.0, 0.0, 1.0, 1.0, 0.0, 1.0);
+        moved.guid = line_guid.clone();
+        theirs.update_object(&line_guid, Geometry::Line(moved));
+        theirs.remove_object(&point_guid);
+        let plane = Plane::from_point_normal(Point::new(0.0, 0.0, 2.0), Vector::new(0.0, 0.0, 1.0));
+        let plane_guid = plane.guid.clone();
+        theirs.add_plane(plane);
+        theirs.add_edge(&line_guid, &plane_guid, "rests_on");
+
+        let delta = ours.diff(&theirs);
+        assert_eq!(delta.added.len(), 1);
+        assert_eq!(delta.added[0].guid(), plane_guid);
+        assert_eq!(delta.removed, vec![point_guid.clone()]);
+        assert_eq!(delta.modified.len(), 1);
+        assert_eq!(delta.modified[0].guid(), line_guid);
+        assert_eq!(delta.edges_added.len(), 1);
+        assert_eq!(delta.edges_removed.len(), 1);
+
+        let mut merged = Session::jsonload(&json).unwrap();
+        merged.merge(&delta, MergeStrategy::Theirs);
+        assert!(merged.diff(&theirs).is_empty());
+        assert!(merged.tree.get_node_by_name(&plane_guid).is_some());
+
+        let mut union = Session::jsonload(&json).unwrap();
+        union.merge(&delta, MergeStrategy::Ours);
+        assert!(union.get_object(&point_guid).is_some());
+        assert!(union.get_object(&plane_guid).is_some());
+        assert!(union.graph.has_edge((&point_guid, &line_guid)));
+        assert!(union.graph.has_edge((&plane_guid, &line_guid)));
+        match union.get_object(&line_guid) {
+            Some(Geometry::Line(l)) => assert!(l.start().z().abs() < 1e-12),
+            _ => panic!("line not found"),
+        }
+    }
+
+    #[test]
+    fn test_object_attributes_roundtrip() {
+        let mut scene = Session::new("attributes");
+        let beam = Line::new(0.0, 0.0, 0.0, 3.0, 0.0, 0.0);
+        let guid = beam.guid.clone();
+        scene.add_line(beam);
+
+        assert!(scene.set_attribute(&guid, "material", "oak".into()));
+        assert!(scene.set_attribute(&guid, "cost", 12.5.into()));
+        assert!(scene.set_attribute(&guid, "cnc", true.into()));
+        assert!(scene.set_attribute(
+            &guid,
+            "ids",
+            vec![AttrValue::from("A-1"), AttrValue::from(2.0)].into()
+        ));
+        assert!(!scene.set_attribute("missing", "material", "oak".into()));
+
+        let json = scene.jsondump().unwrap();
+        let loaded = Session::jsonload(&json).unwrap();
+        assert_eq!(loaded.get_attributes(&guid), scene.get_attributes(&guid));
+        assert_eq!(
+            loaded.get_attribute(&guid, "material"),
+            Some(&AttrValue::String("oak".to_string()))
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["attributes"][&guid]["cost"], serde_json::json!(12.5));
+
+        assert_eq!(
+            scene.remove_attribute(&guid, "cnc"),
+            Some(AttrValue::Bool(true))
+        );
+        assert!(scene.get_attribute(&guid, "cnc").is_none());
+        scene.remove_object(&guid);
+        assert!(scene.get_attributes(&guid).is_none());
+    }
+
+    #[test]
+    fn test_objects_in_sphere_and_nearest_object() {
+        let mut scene = Session::new("proximity");
+        assert!(scene.nearest_object(&Point::new(0.0, 0.0, 0.0)).is_none());
+
+        let mut guids = Vec::new();
+        for i in 1..=5 {
+            let point = Point::new(i as Scalar * 2.0, 0.0, 0.0);
+            guids.push(point.guid.clone());
+            scene.add_point(point);
+        }
+
+        let (nearest, distance) = scene.nearest_object(&Point::new(4.5, 0.0, 0.0)).unwrap();
+        assert_eq!(nearest, guids[1]);
+        assert!((distance - 0.5).abs() < 1e-2);
+
+        let inside = scene.objects_in_sphere(&Point::new(5.0, 0.0, 0.0), 3.5);
+        assert_eq!(inside.len(), 4);
+        assert!(guids[..4].iter().all(|guid| inside.contains(guid)));
+        assert!(scene
+            .objects_in_sphere(&Point::new(0.0, 5.0, 0.0), 1.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_select_rect_window_and_crossing() {
+        let mut scene = Session::new("selection");
+        let inside = Point::new(1.0, 1.0, 0.0);
+        let crossing = Line::new(-2.0, 0.0, 0.0, 2.0, 0.0, 0.0);
+        let aside = Point::new(8.0, 0.0, 0.0);
+        let behind = Point::new(0.0, 0.0, 20.0);
+        let guids = [&inside.guid, &crossing.guid, &aside.guid, &behind.guid].map(|g| g.clone());
+        scene.add_point(inside);
+        scene.add_line(crossing);
+        scene.add_point(aside);
+        scene.add_point(behind);
+
+        // Camera 10 units above the origin with a 90 degree field of view
+        let view = Xform::look_at_rh(
+            &Point::new(0.0, 0.0, 10.0),
+            &Point::new(0.0, 0.0, 0.0),
+            &Vector::new(0.0, 1.0, 0.0),
+        );
+        let (near, far) = (1.0, 100.0);
+        let mut projection = Xform::identity();
+        projection.m[10] = (far + near) / (near - far);
+        projection.m[11] = -1.0;
+        projection.m[14] = 2.0 * far * near / (near - far);
+        projection.m[15] = 0.0;
+
+        // Corners may come in drag order
+        let rect = [0.5, 0.5, -0.1, -0.1];
+        let window = scene.select_rect(&view, &projection, rect, SelectionMode::Window);
+        assert_eq!(window, [guids[0].clone()]);
+        let mut found = scene.select_rect(&view, &projection, rect, SelectionMode::Crossing);
+        found.sort();
+        let mut expected = guids[..2].to_vec();
+        expected.sort();
+        assert_eq!(found, expected);
+
+        let screen = [-1.0, -1.0, 1.0, 1.0];
+        let all = scene.select_rect(&view, &projection, screen, SelectionMode::Window);
+        assert_eq!(all.len(), 3);
+        assert!(!all.contains(&guids[3]));
+    }
+
+    #[test]
+    fn test_set_transform_translate_and_rotate() {
+        let mut scene = Session::new("transforms");
+        let parent = Point::new(0.0, 0.0, 0.0);
+        let child = Point::new(1.0, 0.0, 0.0);
+        let (parent_guid, child_guid) = (parent.guid.clone(), child.guid.clone());
+        let parent_node = scene.add_point(parent);
+        let child_node = scene.add_point(child);
+        scene.add(&parent_node, None);
+        scene.add(&child_node, &parent_node);
+        assert_eq!(
+            scene
+                .objects_in_sphere(&Point::new(1.0, 5.0, 0.0), 0.5)
+                .len(),
+            0
+        );
+
+        assert!(scene.translate(&parent_guid, &Vector::new(0.0, 5.0, 0.0), true));
+        let inside = scene.objects_in_sphere(&Point::new(0.5, 5.0, 0.0), 1.0);
+        assert_eq!(inside.len(), 2);
+        assert!(inside.contains(&parent_guid) && inside.contains(&child_guid));
+
+        assert!(scene.rotate(
+            &child_guid,
+            &Vector::new(0.0, 0.0, 1.0),
+            crate::scalar::consts::FRAC_PI_2,
+            false
+        ));
+        let (nearest, distance) = scene.nearest_object(&Point::new(-5.0, 1.0, 0.0)).unwrap();
+        assert_eq!(nearest, child_guid);
+        assert!(distance < 1e-2);
+        let (nearest, _) = scene.nearest_object(&Point::new(0.0, 5.0, 0.0)).unwrap();
+        assert_eq!(nearest, parent_guid);
+
+        assert!(scene.set_transform(&parent_guid, Xform::identity(), false));
+        let (nearest, _) = scene.nearest_object(&Point::new(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(nearest, parent_guid);
+        assert!(!scene.translate("missing", &Vector::new(1.0, 0.0, 0.0), false));
+    }
+
+    #[test]
+    fn test_get_collisions_with_narrow_phase_and_filter() {
+        let mut scene = Session::new("narrow");
+        let diagonal = Line::new(0.0, 0.0, 0.0, 10.0, 10.0, 0.0);
+        let parallel = Line::new(2.0, 0.0, 0.0, 12.0, 10.0, 0.0);
+        let mut crossing = Line::new(0.0, 10.0, 0.0, 10.0, 0.0, 0.0);
+        crossing.name = "crossing".to_string();
+        let (diagonal_guid, crossing_guid) = (diagonal.guid.clone(), crossing.guid.clone());
+        scene.add_line(diagonal);
+        scene.add_line(parallel);
+        scene.add_line(crossing);
+
+        assert_eq!(scene.get_collisions().len(), 3);
+
+        let mut options = CollisionOptions::narrow_phase();
+        let pairs = scene.get_collisions_with(&options);
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs
+            .iter()
+            .all(|(a, b)| a == &crossing_guid || b == &crossing_guid));
+        assert!(pairs
+            .iter()
+            .any(|(a, b)| a == &diagonal_guid || b == &diagonal_guid));
+
+        // A tolerance wider than the gap between the parallel lines reports them too
+        options.tolerance = 1.5;
+        assert_eq!(scene.get_collisions_with(&options).len(), 3);
+
+        options.filter = Some(|a, b| a.name() != "crossing" && b.name() != "crossing");
+        assert_eq!(scene.get_collisions_with(&options).len(), 1);
+    }
+
+    #[test]
+    fn test_collision_filters_exclude_objects() {
+        let mut scene = Session::new("filters");
+        let a = Point::new(0.0, 0.0, 0.0);
+        let b = Point::new(0.0, 0.0, 0.0);
+        let scaffold = Line::new(-1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let (a_guid, b_guid, scaffold_guid) =
+            (a.guid.clone(), b.guid.clone(), scaffold.guid.clone());
+        scene.add_point(a);
+        scene.add_point(b);
+        scene.add_line(scaffold);
+        assert_eq!(scene.get_collisions().len(), 3);
+
+        assert!(scene.set_collision_filter(&scaffold_guid, 0, u32::MAX));
+        assert!(!scene.set_collision_filter("missing", 0, 0));
+        let pairs = scene.get_collisions();
+        assert_eq!(pairs.len(), 1);
+        assert!(!pairs
+            .iter()
+            .any(|(x, y)| x == &scaffold_guid || y == &scaffold_guid));
+
+        // Group 2 ignores group 1 while group 1 still lists group 2 in its mask
+        scene.set_collision_filter(&b_guid, 2, !1);
+        assert!(scene.get_collisions().is_empty());
+
+        // Rays skip the scaffold in front of the points
+        let origin = Point::new(-5.0, 0.0, 0.0);
+        let hits = scene.ray_cast(&origin, &Vector::new(1.0, 0.0, 0.0), 1e-3);
+        assert!(hits.iter().all(|hit| hit.guid != scaffold_guid));
+        assert!(hits.iter().any(|hit| hit.guid == a_guid));
+        let hits = scene.ray_cast_masked(&origin, &Vector::new(1.0, 0.0, 0.0), 1e-3, 2);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, b_guid);
+
+        let loaded = Session::jsonload(&scene.jsondump().unwrap()).unwrap();
+        assert_eq!(loaded.collision_filter(&scaffold_guid).group, 0);
+        assert_eq!(
+            loaded.collision_filter(&a_guid),
+            scene.collision_filter(&a_guid)
+        );
+    }
+
+    #[test]
+    fn test_check_sweep_keeps_obstacles_shared_with_snapshots() {
+        let mut scene = Session::new("sweep");
+        let triangle = |points: [[Scalar; 3]; 3]| {
+            Mesh::from_polygons(
+                vec![points
+                    .iter()
+                    .map(|p| Point::new(p[0], p[1], p[2]))
+                    .collect()],
+                None,
+            )
+        };
+        let blade = triangle([[-1.0, 0.0, -1.0], [1.0, 0.0, -1.0], [0.0, 0.0, 1.0]]);
+        let wall = triangle([[5.0, -2.0, -2.0], [5.0, 2.0, -2.0], [5.0, 0.0, 2.0]]);
+        let (blade_guid, wall_guid) = (blade.guid.clone(), wall.guid.clone());
+        scene.add_mesh(blade);
+        scene.add_mesh(wall);
+
+        let state = scene.snapshot();
+        let path = [Xform::identity(), Xform::translation(10.0, 0.0, 0.0)];
+        let hits = scene.check_sweep(&blade_guid, &path);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, wall_guid);
+
+        // The wall was tested through its shared triangle BVH, not copied
+        assert!(Arc::ptr_eq(
+            &scene.lookup[&wall_guid],
+            &state.lookup[&wall_guid]
+        ));
+        match state.lookup[&wall_guid].as_ref() {
+            Geometry::Mesh(m) => assert!(m.triangle_bvh.get().is_some()),
+            _ => panic!("mesh expected"),
+        }
+    }
+
+    #[test]
+    fn test_check_sweep_finds_obstacles_between_keyframes() {
+        let mut scene = Session::new("sweep");
+        let gripper = BoundingBox::from_point(Point::new(0.0, 0.0, 0.0), 0.5);
+        let wall = Line::new(5.0, 0.0, -1.0, 5.0, 0.0, 1.0);
+        let bin = BoundingBox::from_point(Point::new(10.0, 5.0, 0.0), 0.5);
+        let far = BoundingBox::from_point(Point::new(20.0, 20.0, 0.0), 0.5);
+        let ghost = Point::new(7.0, 0.0, 0.0);
+        let guids =
+            [&gripper.guid, &wall.guid, &bin.guid, &far.guid, &ghost.guid].map(|g| g.clone());
+        scene.add_bbox(gripper);
+        scene.add_line(wall);
+        scene.add_bbox(bin);
+        scene.add_bbox(far);
+        scene.add_point(ghost);
+        scene.set_collision_filter(&guids[4], 0, 0);
+
+        // Neither keyframe touches the wall, the motion between them does
+        let path = [
+            Xform::identity(),
+            Xform::translation(10.0, 0.0, 0.0),
+            Xform::translation(10.0, 10.0, 0.0),
+        ];
+        let hits = scene.check_sweep(&guids[0], &path);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            (hits[0].guid.as_str(), hits[0].frame),
+            (guids[1].as_str(), 0)
+        );
+        assert!((hits[0].position - 0.45).abs() < 0.02);
+        assert_eq!(
+            (hits[1].guid.as_str(), hits[1].frame),
+            (guids[2].as_str(), 1)
+        );
+        assert!((hits[1].position - 1.4).abs() < 0.02);
+        // The object stays where it was
+        assert!(scene.lookup[&guids[0]].xform().is_identity());
+
+        // A rotating bar sweeps a point that both end poses miss
+        let bar = Line::new(-2.0, 0.0, 0.0, 2.0, 0.0, 0.0);
+        let bar_guid = bar.guid.clone();
+        scene.add_line(bar);
+        let post = Point::new(1.0, 1.0, 3.0);
+        let post_guid = post.guid.clone();
+        scene.add_point(post);
+        let lifted = |angle: Scalar| &Xform::translation(0.0, 0.0, 3.0) * &Xform::rotation_z(angle);
+        let turn = [lifted(0.0), lifted(crate::scalar::consts::FRAC_PI_2)];
+        let hits = scene.check_sweep(&bar_guid, &turn);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].guid, post_guid);
+        assert!((hits[0].position - 0.5).abs() < 0.1);
+        assert!(scene.check_sweep(&bar_guid, &turn[..1]).is_empty());
+        assert!(scene.check_sweep("missing", &turn).is_empty());
+    }
+
+    #[test]
+    fn test_update_collisions_after_moving_objects() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut scene = Session::new("incremental");
+        let points = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(10.0, 0.0, 0.0),
+        ];
+        let guids: Vec<String> = points.iter().map(|p| p.guid.clone()).collect();
+        for point in points {
+            scene.add_point(point);
+        }
+        assert_eq!(scene.get_collisions().len(), 1);
+        assert_eq!(scene.get_collisions().len(), 1);
+        assert_eq!(scene.graph.number_of_edges(), 1);
+        scene.add_edge(&guids[0], &guids[2], "support");
+
+        let removed = Rc::new(RefCell::new(0));
+        let counter = removed.clone();
+        scene.on_change(move |event| {
+            if matches!(event, SessionEvent::EdgeRemoved { .. }) {
+                *counter.borrow_mut() += 1;
+            }
+        });
+
+        scene.translate(&guids[2], &Vector::new(-10.0, 0.0, 0.0), false);
+        let moved = [guids[2].clone()];
+        let pairs = scene.update_collisions(&moved);
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs.iter().all(|(a, b)| a < b));
+        assert!(scene.graph.has_edge((&guids[1], &guids[2])));
+        assert_eq!(scene.graph.number_of_edges(), 3);
+
+        // Moving away removes collision edges but keeps the relationship edge
+        scene.translate(&guids[2], &Vector::new(0.0, 5.0, 0.0), false);
+        assert!(scene.update_collisions(&moved).is_empty());
+        assert!(!scene.graph.has_edge((&guids[1], &guids[2])));
+        assert!(scene.graph.has_edge((&guids[0], &guids[2])));
+        assert!(scene.graph.has_edge((&guids[0], &guids[1])));
+        assert_eq!(*removed.borrow(), 1);
+    }
+
+    #[test]
+    fn test_contact_clusters() {
+        let mut scene = Session::new("clusters");
+        let points = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(10.0, 0.0, 0.0),
+            Point::new(10.0, 0.0, 0.0),
+            Point::new(10.0, 0.0, 0.0),
+            Point::new(20.0, 0.0, 0.0),
+        ];
+        let guids: Vec<String> = points.iter().map(|p| p.guid.clone()).collect();
+        for point in points {
+            scene.add_point(point);
+        }
+        assert_eq!(scene.contact_clusters().len(), 6);
+
+        scene.get_collisions();
+        scene.add_edge(&guids[1], &guids[5], "support");
+        let clusters = scene.contact_clusters();
+        let sorted = |mut guids: Vec<String>| {
+            guids.sort();
+            guids
+        };
+        assert_eq!(
+            clusters,
+            vec![
+                sorted(guids[2..5].to_vec()),
+                sorted(guids[0..2].to_vec()),
+                vec![guids[5].clone()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_contacts_stores_contacts_on_edges() {
+        let mut scene = Session::new("contacts");
+        let a = BoundingBox::from_point(Point::new(0.0, 0.0, 0.0), 1.0);
+        let b = BoundingBox::from_point(Point::new(0.0, 0.0, 1.5), 1.0);
+        // The diagonal's box covers the third box, the line itself passes by
+        let c = BoundingBox::from_point(Point::new(8.0, 2.0, 0.0), 0.5);
+        let line = Line::new(3.0, 3.0, 0.0, 10.0, 10.0, 0.0);
+        let plane = Plane::from_point_normal(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+        let guids = [&a.guid, &b.guid, &c.guid, &line.guid, &plane.guid].map(|g| g.clone());
+        scene.add_bbox(a);
+        scene.add_bbox(b);
+        scene.add_bbox(c);
+        scene.add_line(line);
+        scene.add_plane(plane);
+
+        let contacts = scene.get_contacts(&CollisionOptions::default());
+        assert_eq!(contacts.len(), 1);
+        let (first, second, contact) = &contacts[0];
+        assert!((contact.depth - 0.5).abs() < 1e-6);
+        assert!(scene.contact(&guids[2], &guids[3]).is_none());
+        assert!(!scene.graph.has_edge((&guids[2], &guids[3])));
+
+        // Stored once, read from either side
+        let stored = scene.contact(first, second).unwrap();
+        assert!(stored.normal.distance(&contact.normal) < 1e-9);
+        let reverse = scene.contact(second, first).unwrap();
+        assert!(reverse.normal.distance(&-contact.normal) < 1e-9);
+        assert!((reverse.normal.z.abs() - 1.0).abs() < 1e-6);
+
+        // The plane is not convex and keeps its box overlap edges
+        let edge = scene.graph.edge_attribute(&guids[0], &guids[4], None);
+        assert_eq!(edge.as_deref(), Some("bvh_collision"));
+        assert!(scene.contact(&guids[0], &guids[4]).is_none());
+        assert_eq!(scene.contact_clusters()[0].len(), 3);
+
+        // User edges are never taken for contacts, whatever they hold
+        let json = serde_json::to_string(&stored).unwrap();
+        scene.add_edge(&guids[2], &guids[3], &json);
+        assert!(scene.contact(&guids[2], &guids[3]).is_none());
+        scene.update_collisions(&[guids[2].clone()]);
+        assert!(scene.graph.has_edge((&guids[2], &guids[3])));
+    }
+
+    #[test]
+    fn test_ray_cast_modes_report_occluded_objects() {
+        let mut scene = Session::new("picking");
+        let mut guids = Vec::new();
+        for i in 1..=3 {
+            let bbox = BoundingBox::from_point(Point::new(i as Scalar * 10.0, 0.0, 0.0), 1.0);
+            guids.push(bbox.guid.clone());
+            scene.add_bbox(bbox);
+        }
+        let marker = Point::new(5.0, 0.0, 0.0);
+        let marker_guid = marker.guid.clone();
+        scene.add_point(marker);
+
+        let origin = Point::new(0.0, 0.0, 0.0);
+        let direction = Vector::new(1.0, 0.0, 0.0);
+        let nearest = scene.ray_cast(&origin, &direction, 1e-3);
+        assert_eq!(nearest.len(), 1);
+        assert_eq!(nearest[0].guid, marker_guid);
+
+        let mut options = RayCastOptions {
+            mode: RayCastMode::FirstPerObject,
+            ..Default::default()
+        };
+        let first = scene.ray_cast_with(&origin, &direction, 1e-3, &options);
+        let order: Vec<&String> = first.iter().map(|hit| &hit.guid).collect();
+        assert_eq!(order, [&marker_guid, &guids[0], &guids[1], &guids[2]]);
+        assert!((first[1].distance - 9.0).abs() < 1e-6);
+
+        // Boxes are entered and left
+        options.mode = RayCastMode::All;
+        options.type_filter = Some(|geometry| matches!(geometry, Geometry::BoundingBox(_)));
+        let all = scene.ray_cast_with(&origin, &direction, 1e-3, &options);
+        assert_eq!(all.len(), 6);
+        assert!(all.windows(2).all(|w| w[0].distance <= w[1].distance));
+        assert!((all[1].distance - 11.0).abs() < 1e-6);
+
+        options.max_distance = 20.0;
+        let near = scene.ray_cast_with(&origin, &direction, 1e-3, &options);
+        assert_eq!(near.len(), 3);
+        assert!(near.iter().all(|hit| hit.distance <= 20.0));
+    }
+
+    #[test]
+    fn test_snapshot_shares_unchanged_objects() {
+        let mut scene = Session::new("shared");
+        let mesh = Mesh::from_polygons(
+            vec![vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ]],
+            None,
+        );
+        let mesh_guid = mesh.guid.clone();
+        scene.add_mesh(mesh);
+        let point = Point::new(1.0, 2.0, 3.0);
+        let point_guid = point.guid.clone();
+        scene.add_point(point);
+
+        let state = scene.snapshot();
+        assert!(Arc::ptr_eq(
+            &scene.lookup[&mesh_guid],
+            &state.lookup[&mesh_guid]
+        ));
+
+        // Only the edited object is copied, the snapshot keeps the old one
+        scene.translate(&point_guid, &Vector::new(1.0, 0.0, 0.0), false);
+        assert!(Arc::ptr_eq(
+            &scene.lookup[&mesh_guid],
+            &state.lookup[&mesh_guid]
+        ));
+        assert!(!Arc::ptr_eq(
+            &scene.lookup[&point_guid],
+            &state.lookup[&point_guid]
+        ));
+        assert!(state.lookup[&point_guid].xform().is_identity());
+
+        scene.restore(state);
+        assert!(scene.get_object(&point_guid).unwrap().xform().is_identity());
+    }
+
+    #[test]
+    fn test_ray_cast_keeps_objects_shared_with_snapshots() {
+        let mut scene = Session::new("shared");
+        let mesh = Mesh::from_polygons(
+            vec![vec![
+                Point::new(0.0, -1.0, -1.0),
+                Point::new(0.0, 1.0, -1.0),
+                Point::new(0.0, 0.0, 1.0),
+            ]],
+            None,
+        );
+        let mesh_guid = mesh.guid.clone();
+        scene.add_mesh(mesh);
+        let cloud = PointCloud::new(vec![Point::new(5.0, 0.0, 0.0)], vec![], vec![]);
+        let cloud_guid = cloud.guid.clone();
+        scene.add_pointcloud(cloud);
+
+        let state = scene.snapshot();
+        let options = RayCastOptions {
+            mode: RayCastMode::All,
+            ..Default::default()
+        };
+        let hits = scene.ray_cast_with(
+            &Point::new(-5.0, 0.0, 0.0),
+            &Vector::new(1.0, 0.0, 0.0),
+            0.01,
+            &options,
+        );
+        assert_eq!(hits.len(), 2);
+
+        // The caches were filled in the shared objects, none was copied
+        for guid in [&mesh_guid, &cloud_guid] {
+            assert!(Arc::ptr_eq(&scene.lookup[guid], &state.lookup[guid]));
+        }
+        match state.lookup[&mesh_guid].as_ref() {
+            Geometry::Mesh(m) => assert!(m.triangle_bvh.get().is_some()),
+            _ => panic!("mesh expected"),
+        }
+        match state.lookup[&cloud_guid].as_ref() {
+            Geometry::PointCloud(pc) => assert!(pc.kdtree.get().is_some()),
+            _ => panic!("point cloud expected"),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_restore_for_previews() {
+        let mut scene = Session::new("preview");
+        let point = Point::new(1.0, 2.0, 3.0);
+        let point_guid = point.guid.clone();
+        scene.add_point(point);
+        scene.add_line(Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+        scene.add_point(Point::new(4.0, 0.0, 0.0));
+        scene.set_attribute(&point_guid, "layer", "walls".into());
+        let before = serde_json::to_value(&scene).unwrap()["objects"].clone();
+        let state = scene.snapshot();
+
+        // Repeated drag preview frames restore the same checkpoint
+        for step in 1..=3 {
+            scene.restore(state.clone());
+            scene.translate(&point_guid, &Vector::new(step as Scalar, 0.0, 0.0), false);
+            scene.add_point(Point::new(0.0, 0.0, step as Scalar));
+            scene.remove_attribute(&point_guid, "layer");
+            assert_eq!(scene.lookup.len(), 4);
+        }
+
+        scene.restore(state);
+        assert_eq!(serde_json::to_value(&scene).unwrap()["objects"], before);
+        assert_eq!(scene.lookup.len(), 3);
+        assert!(scene.get_attribute(&point_guid, "layer").is_some());
+        assert!(scene.get_object(&point_guid).unwrap().xform().is_identity());
+        assert!(!scene.can_undo());
+        let (nearest, _) = scene.nearest_object(&Point::new(1.0, 2.0, 3.0)).unwrap();
+        assert_eq!(nearest, point_guid);
+    }
+
+    #[test]
+    fn test_operation_log_replay() {
+        let mut scene = Session::new("logged");
+        let path = std::env::temp_dir().join(format!("session_{}.ndjson", scene.guid));
+        let path = path.to_str().unwrap().to_string();
+        scene.open_log(&path).unwrap();
+
+        let a = Point::new(0.0, 0.0, 0.0);
+        let b = Point::new(1.0, 0.0, 0.0);
+        let line = Line::new(0.0, 0.0, 0.0, 0.0, 5.0, 0.0);
+        let (a_guid, b_guid, line_guid) = (a.guid.clone(), b.guid.clone(), line.guid.clone());
+        scene.add_point(a);
+        scene.add_point(b);
+        let checkpoint = scene.jsondump().unwrap();
+        scene.add_line(line);
+        scene.add_edge(&a_guid, &line_guid, "support");
+        scene.translate(&b_guid, &Vector::new(0.0, 0.0, 2.0), false);
+        if let Some(Geometry::Point(point)) = scene.get_object_mut(&a_guid).as_deref_mut() {
+            point.name = "renamed".to_string();
+        }
+        scene.remove_object(&line_guid);
+        scene.close_log().unwrap();
+
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(lines.lines().count(), 7);
+
+        // Replaying from an empty session or on top of a later checkpoint gives the same state
+        let mut replayed = Session::new("replayed");
+        assert_eq!(replayed.replay_log(&path).unwrap(), 7);
+        let mut resumed = Session::jsonload(&checkpoint).unwrap();
+        resumed.replay_log(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        for session in [&replayed, &resumed] {
+            assert_eq!(session.lookup.len(), 2);
+            assert!(session.get_object(&line_guid).is_none());
+            assert_eq!(session.get_object(&a_guid).unwrap().name(), "renamed");
+            let xform = session.get_object(&b_guid).unwrap().xform();
+            assert!((xform.transformed_point(&Point::new(0.0, 0.0, 0.0)).z() - 2.0).abs() < 1e-9);
+            assert_eq!(session.graph.number_of_edges(), 0);
+        }
+        assert!(Session::new("missing").replay_log(&path).is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_corrupted_contents() {
+        let mut scene = Session::new("valid");
+        let point = Point::new(1.0, 2.0, 3.0);
+        let point_guid = point.guid.clone();
+        let node = scene.add_point(point);
+        scene.add(&node, None);
+        let line = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let line_guid = line.guid.clone();
+        scene.add_line(line);
+        scene.add_edge(&point_guid, &line_guid, "support");
+        assert!(scene.validate().is_empty());
+
+        // Corruptions as they could come from direct edits of the public fields
+        let stray = Point::new(0.0, 0.0, 0.0);
+        let stray_guid = stray.guid.clone();
+        scene
+            .lookup
+            .insert(stray_guid.clone(), Arc::new(Geometry::Point(stray)));
+        scene.lookup.remove(&line_guid);
+        scene.graph.add_node("ghost", "");
+        let ghost_node = TreeNode::new(&uuid::Uuid::new_v4().to_string());
+        scene.add(&ghost_node, None);
+        let nan = Point::new(Scalar::NAN, 0.0, 0.0);
+        let nan_guid = nan.guid.clone();
+        scene.add_point(nan);
+        let short = Line::new(1.0, 1.0, 1.0, 1.0, 1.0, 1.0);
+        let short_guid = short.guid.clone();
+        scene.add_line(short);
+
+        let issues = scene.validate();
+        assert!(issues.contains(&ValidationIssue::MissingFromObjects { guid: stray_guid }));
+        assert!(issues.contains(&ValidationIssue::MissingFromLookup {
+            guid: line_guid.clone()
+        }));
+        assert!(issues.contains(&ValidationIssue::DanglingGraphNode {
+            key: line_guid.clone()
+        }));
+        assert!(issues.contains(&ValidationIssue::DanglingGraphNode {
+            key: "ghost".to_string()
+        }));
+        assert!(issues.contains(&ValidationIssue::OrphanTreeNode {
+            name: ghost_node.name()
+        }));
+        assert!(issues.contains(&ValidationIssue::NonFiniteCoordinates { guid: nan_guid }));
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            ValidationIssue::DegenerateGeometry { guid, .. } if *guid == short_guid
+        )));
+        assert_eq!(issues.len(), 7);
+        assert!(issues[0].to_string().contains(&line_guid));
+
+        // A GUID used twice in a file is rejected when loading
+        let mut clean = Session::new("duplicate");
+        clean.add_point(Point::new(1.0, 2.0, 3.0));
+        let mut json: serde_json::Value = serde_json::from_str(&clean.jsondump().unwrap()).unwrap();
+        let points = json["objects"]["points"].as_array_mut().unwrap();
+        points.push(points[0].clone());
+        let error = Session::jsonload(&json.to_string()).unwrap_err();
+        assert!(error.to_string().contains("Duplicate object GUID"));
+    }
+
+    #[test]
+    fn test_stats_counts_objects_and_memory() {
+        let mut scene = Session::new("stats");
+        let empty = scene.stats();
+        assert_eq!(empty.object_count, 0);
+        assert!(empty.memory_bytes > 0);
+
+        let quad = Mesh::from_polygons(
+            vec![vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ]],
+            None,
+        );
+        scene.add_mesh(quad);
+        scene.add_point(Point::new(0.0, 0.0, 0.0));
+        scene.add_point(Point::new(1.0, 0.0, 0.0));
+        scene.add_line(Line::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+        scene.add_pointcloud(PointCloud::new(
+            vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0)],
+            vec![],
+            vec![],
+        ));
+
+        let stats = scene.stats();
+        assert_eq!(stats.object_count, 5);
+        assert_eq!(stats.object_counts["Point"], 2);
+        assert_eq!(stats.object_counts["Mesh"], 1);
+        assert!(!stats.object_counts.contains_key("Cylinder"));
+        assert_eq!(stats.vertex_count, 4 + 2 + 2 + 2);
+        assert_eq!(stats.face_count, 1);
+        assert_eq!(stats.graph_vertex_count, 5);
+        assert_eq!(stats.ray_bvh_bytes, 0);
+        assert!(stats.memory_bytes > empty.memory_bytes);
+
+        // The ray cache is reported once a ray cast builds it
+        scene.ray_cast(
+            &Point::new(0.5, 0.5, 5.0),
+            &Vector::new(0.0, 0.0, -1.0),
+            1e-3,
+        );
+        let cached = scene.stats();
+        assert_eq!(cached.cached_box_count, 5);
+        assert!(cached.ray_bvh_bytes > 0);
+        assert!(cached.memory_bytes > stats.memory_bytes);
+    }
+
+    #[test]
+    fn test_bounding_box_covers_all_objects() {
+        let mut scene = Session::new("bounds");
+        assert!(scene.bounding_box().is_none());
+
+        scene.add_point(Point::new(-1.0, 0.0, 0.0));
+        scene.add_line(Line::new(0.0, 0.0, 0.0, 2.0, 3.0, 4.0));
+        let bbox = scene.bounding_box().unwrap();
+        let (min, max) = (bbox.min_point(), bbox.max_point());
+        assert!(min.x() <= -1.0 && min.y() <= 0.0 && min.z() <= 0.0);
+        assert!(max.x() >= 2.0 && max.y() >= 3.0 && max.z() >= 4.0);
+        assert!(max.x() < 2.1 && min.x() > -1.1);
+    }
+
+    #[test]
+    fn test_groups_move_hide_and_duplicate_members() {
+        let mut scene = Session::new("groups");
+        let a = Point::new(0.0, 0.0, 0.0);
+        let b = Point::new(1.0, 0.0, 0.0);
+        let (a_guid, b_guid) = (a.guid.clone(), b.guid.clone());
+        let node = scene.add_point(a);
+        scene.add(&node, None);
+        scene.add_point(b);
+        let outside = scene.add_point(Point::new(5.0, 0.0, 0.0)).name();
+        scene.add_edge(&a_guid, &b_guid, "bolted");
+        scene.add_edge(&a_guid, &outside, "touches");
+        scene.set_attribute(&a_guid, "material", AttrValue::from("steel"));
+
+        let members = vec![a_guid.clone(), b_guid.clone(), "missing".to_string()];
+        let block = scene.add_group("block", &members);
+        assert_eq!(
+            scene.get_group(&block).unwrap().members,
+            vec![a_guid.clone(), b_guid.clone()]
+        );
+        assert_eq!(
+            scene
+                .tree
+                .get_node_by_name(&a_guid)
+                .unwrap()
+                .parent()
+                .unwrap()
+                .name(),
+            block
+        );
+        assert!(scene.validate().is_empty());
+
+        // The group placement moves every member
+        assert!(scene.set_group_transform(&block, Xform::translation(0.0, 0.0, 2.0)));
+        assert!(scene.set_group_transform(&block, Xform::translation(0.0, 0.0, 3.0)));
+        let origin = Point::new(0.0, 0.0, 0.0);
+        for guid in [&a_guid, &b_guid] {
+            let xform = scene.get_object(guid).unwrap().xform();
+            assert!((xform.transformed_point(&origin).z() - 3.0).abs() < 1e-9);
+        }
+        let down = Vector::new(0.0, 0.0, -1.0);
+        assert_eq!(
+            scene
+                .ray_cast(&Point::new(0.0, 0.0, 10.0), &down, 1e-3)
+                .len(),
+            1
+        );
+
+        // Hidden members cannot be picked
+        assert!(scene.set_group_visible(&block, false));
+        assert!(!scene.is_visible(&a_guid));
+        assert!(scene.is_visible(&outside));
+        assert!(scene
+            .ray_cast(&Point::new(0.0, 0.0, 10.0), &down, 1e-3)
+            .is_empty());
+
+        let copy = scene.duplicate_group(&block).unwrap();
+        let group = scene.get_group(&copy).unwrap().clone();
+        assert_eq!(group.name, "block");
+        assert_eq!(group.xform.m, Xform::translation(0.0, 0.0, 3.0).m);
+        assert!(!group.visible);
+        assert_eq!(group.members.len(), 2);
+        assert!(!group.members.contains(&a_guid));
+        assert_eq!(scene.lookup.len(), 5);
+        let (copy_a, copy_b) = (&group.members[0], &group.members[1]);
+        assert_eq!(
+            scene.get_attribute(copy_a, "material"),
+            Some(&AttrValue::from("steel"))
+        );
+        // Only edges between members are copied
+        assert_eq!(scene.get_neighbours(copy_a), vec![copy_b.clone()]);
+        assert_eq!(scene.graph.number_of_edges(), 3);
+
+        assert!(scene.ungroup(&block));
+        assert!(scene.get_group(&block).is_none());
+        assert!(scene.is_visible(&a_guid));
+        assert!(scene
+            .tree
+            .get_node_by_name(&a_guid)
+            .unwrap()
+            .parent()
+            .unwrap()
+            .is_root());
+        assert!(scene.validate().is_empty());
+
+        let loaded = Session::jsonload(&scene.jsondump().unwrap()).unwrap();
+        assert_eq!(loaded.get_group(&copy), Some(&group));
+        assert!(scene.duplicate_group("missing").is_none());
+    }
+
+    #[test]
+    fn test_link_external_composites_geometry() {
+        let dir = std::env::temp_dir();
+        let context_path = dir.join(format!("context_{}.json", uuid::Uuid::new_v4()));
+        let context_path = context_path.to_str().unwrap().to_string();
+
+        let mut context = Session::new("context");
+        context.add_point(Point::new(1.0, 0.0, 0.0));
+        context.add_line(Line::new(0.0, 0.0, 0.0, 0.0, 1.0, 0.0));
+        // A link back to the working file is not followed again
+        context.link_external(&context_path, Xform::identity());
+        context.to_json(&context_path).unwrap();
+
+        let mut scene = Session::new("working");
+        scene.add_point(Point::new(0.0, 0.0, 0.0));
+        let link = scene.link_external(&context_path, Xform::translation(0.0, 0.0, 10.0));
+        scene.link_external("missing_context.json", Xform::identity());
+        assert_eq!(scene.lookup.len(), 1);
+
+        let geometry = scene.get_geometry();
+        assert_eq!(geometry.points.len(), 2);
+        assert_eq!(geometry.lines.len(), 1);
+        assert!((geometry.points[1].z() - 10.0).abs() < 1e-9);
+        assert!((geometry.lines[0].end().z() - 10.0).abs() < 1e-9);
+
+        // The file is only read again after reload_links
+        std::fs::remove_file(&context_path).unwrap();
+        assert_eq!(scene.get_geometry().points.len(), 2);
+        let loaded = Session::jsonload(&scene.jsondump().unwrap()).unwrap();
+        assert_eq!(loaded.links.len(), 2);
+        assert_eq!(loaded.links[0].path, context_path);
+        assert_eq!(loaded.get_geometry().points.len(), 1);
+        scene.reload_links();
+        assert_eq!(scene.get_geometry().points.len(), 1);
+
+        assert!(scene.unlink_external(&link));
+        assert!(!scene.unlink_external(&link));
+        assert_eq!(scene.links.len(), 1);
+    }
+
+    #[test]
+    fn test_with_id_generator_gives_identical_files() {
+        let build = |generator: IdGenerator| {
+            let mut scene = Session::with_id_generator("fixture", generator);
+            let a = scene.add_point(Point::new(0.0, 0.0, 0.0));
+            let b = scene.add_line(Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+            scene.add(&a, None);
+            scene.add(&b, Some(&a));
+            scene.add_edge(&a.name(), &b.name(), "touches");
+            scene.set_attribute(&a.name(), "material", AttrValue::from("steel"));
+            scene.add_group("pair", &[a.name(), b.name()]);
+            scene.duplicate_object(&b.name());
+            scene.link_external("other.json", Xform::identity());
+            scene.jsondump().unwrap()
+        };
+        for generator in [IdGenerator::Sequential, IdGenerator::Hashed] {
+            let first = build(generator);
+            assert_eq!(build(generator), first);
+            assert_ne!(build(IdGenerator::Random), first);
+        }
+
+        let sequential = Session::with_id_generator("fixture", IdGenerator::Sequential);
+        assert_eq!(sequential.guid, "00000000-0000-0000-0000-000000000001");
+        assert_eq!(sequential.id_generator(), IdGenerator::Sequential);
+        // The generator belongs to the session, others still get random GUIDs
+        assert_eq!(Session::new("random").id_generator(), IdGenerator::Random);
+        assert_ne!(Session::new("random").guid, Session::new("random").guid);
+    }
+
+    #[test]
+    fn test_hashed_guids_follow_the_geometry() {
+        let point = Point::new(1.0, 2.0, 3.0);
+        let mut first = Session::with_id_generator("first", IdGenerator::Hashed);
+        let a = first.add_point(point.clone()).name();
+        let mut second = Session::with_id_generator("second", IdGenerator::Hashed);
+        second.add_line(Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+        let b = second.add_point(Point::new(1.0, 2.0, 3.0)).name();
+        assert_eq!(a, b);
+        assert_ne!(a, point.guid);
+
+        // Equal geometry added twice gets distinct GUIDs
+        let c = first.add_point(point.clone()).name();
+        assert_ne!(a, c);
+        assert_eq!(first.lookup.len(), 2);
+        let moved = first.add_point(Point::new(1.0, 2.0, 4.0)).name();
+        assert_ne!(moved, a);
+
+        // Nested GUIDs are derived from the object GUID
+        let Some(Geometry::Point(stored)) = first.get_object(&a) else {
+            panic!("point should be stored");
+        };
+        let Some(Geometry::Point(same)) = second.get_object(&b) else {
+            panic!("point should be stored");
+        };
+        assert_eq!(stored.guid, a);
+        assert_eq!(stored.xform.guid, same.xform.guid);
+        assert_ne!(stored.xform.guid, point.xform.guid);
+        assert_eq!(first.graph.get_vertices().len(), 3);
+
+        // Adding an object that is already stored replaces it
+        let mut replacement = stored.clone();
+        replacement.name = "renamed".to_string();
+        assert_eq!(first.add_point(replacement).name(), a);
+        assert_eq!(first.lookup.len(), 3);
+    }
+
+    #[test]
+    fn test_display_state_round_trips() {
+        let mut scene = Session::new("display");
+        let a = scene.add_point(Point::new(0.0, 0.0, 0.0)).name();
+        let b = scene.add_point(Point::new(1.0, 0.0, 0.0)).name();
+        let c = scene.add_point(Point::new(2.0, 0.0, 0.0)).name();
+        assert_eq!(
+            scene.visible_objects(),
+            vec![a.clone(), b.clone(), c.clone()]
+        );
+        assert!(scene.display_state(&a).visible);
+
+        assert!(scene.set_visible(&b, false));
+        assert!(scene.set_selected(&c, true));
+        assert!(scene.set_highlight_color(&c, Some(Color::red())));
+        assert!(!scene.set_selected("missing", true));
+        assert_eq!(scene.visible_objects(), vec![a.clone(), c.clone()]);
+        assert_eq!(scene.selected_objects(), vec![c.clone()]);
+
+        // Hidden objects cannot be picked
+        let down = Vector::new(0.0, 0.0, -1.0);
+        assert!(scene
+            .ray_cast(&Point::new(1.0, 0.0, 5.0), &down, 1e-3)
+            .is_empty());
+
+        let group = scene.add_group("hidden", std::slice::from_ref(&c));
+        scene.set_group_visible(&group, false);
+        assert_eq!(scene.visible_objects(), vec![a.clone()]);
+
+        let loaded = Session::jsonload(&scene.jsondump().unwrap()).unwrap();
+        assert!(!loaded.display_state(&b).visible);
+        let state = loaded.display_state(&c);
+        assert!(state.selected && state.visible);
+        assert_eq!(state.highlight_color.unwrap().r, 255);
+        assert_eq!(loaded.visible_objects(), vec![a.clone()]);
+
+        // States back at the default are not stored
+        scene.set_visible(&b, true);
+        assert_eq!(scene.display.len(), 1);
+        scene.remove_object(&c);
+        assert!(scene.display.is_empty());
+    }
+
+    #[test]
+    fn test_animation_tracks_round_trip_and_replay() {
+        let mut scene = Session::new("motion");
+        let a = scene.add_point(Point::new(0.0, 0.0, 0.0)).name();
+        let b = scene.add_point(Point::new(0.0, 0.0, 0.0)).name();
+        let mut track = AnimationTrack::new();
+        track.add_keyframe(0.0, Xform::identity());
+        track.add_keyframe(1.0, Xform::translation(0.0, 0.0, 2.0));
+        assert!(scene.set_animation(&a, track.clone()));
+        assert!(!scene.set_animation("missing", track));
+        assert!(scene.animation(&b).is_none());
+
+        scene.apply_animations(0.5);
+        assert!((scene.lookup[&a].xform().m[14] - 1.0).abs() < 1e-5);
+        assert!(scene.lookup[&b].xform().is_identity());
+
+        let mut loaded = Session::jsonload(&scene.jsondump().unwrap()).unwrap();
+        assert_eq!(loaded.animation(&a), scene.animation(&a));
+        loaded.apply_animations(1.0);
+        assert_eq!(loaded.lookup[&a].xform().m[14], 2.0);
+
+        // Copies have no track and removed objects drop theirs
+        let copy = scene.duplicate_object(&a).unwrap();
+        assert!(scene.animation(&copy).is_none());
+        scene.remove_object(&a);
+        assert!(scene.animations.is_empty());
+        assert!(loaded.remove_animation(&a).is_some());
+    }
+
+    #[test]
+    fn test_duplicate_object_and_subtree_remap_guids() {
+        let mut scene = Session::new("duplicate");
+        let beam = scene.add_line(Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+        let bolt = scene.add_point(Point::new(0.5, 0.0, 0.0));
+        let nut = scene.add_point(Point::new(0.5, 0.0, 0.1));
+        let wall = scene.add_point(Point::new(5.0, 0.0, 0.0));
+        scene.add(&beam, None);
+        scene.add(&bolt, Some(&beam));
+        scene.add(&nut, Some(&bolt));
+        scene.add(&wall, None);
+        scene.add_edge(&bolt.name(), &nut.name(), "fastens");
+        scene.add_edge(&beam.name(), &wall.name(), "supported_by");
+        scene.set_attribute(&bolt.name(), "size", AttrValue::from("M12"));
+
+        let copy = scene.duplicate_object(&nut.name()).unwrap();
+        assert_ne!(copy, nut.name());
+        assert_eq!(scene.get_children(&bolt.guid()).len(), 2);
+        assert!(scene.get_neighbours(&copy).is_empty());
+        assert!(scene.duplicate_object("missing").is_none());
+
+        let copies = scene.duplicate_subtree(&beam);
+        assert_eq!(copies.len(), 4);
+        assert_eq!(scene.lookup.len(), 9);
+        let copied_beam = scene.tree.get_node_by_name(&copies[0]).unwrap();
+        assert!(copied_beam.parent().unwrap().is_root());
+        let copied_bolt = &copied_beam.children()[0];
+        assert_eq!(copied_bolt.name(), copies[1]);
+        assert_eq!(copied_bolt.children().len(), 2);
+        assert_eq!(
+            scene.get_attribute(&copies[1], "size"),
+            Some(&AttrValue::from("M12"))
+        );
+        // Edges within the subtree are remapped, edges leaving it are not copied
+        assert_eq!(scene.get_neighbours(&copies[1]), vec![copies[2].clone()]);
+        assert!(scene.get_neighbours(&copies[0]).is_empty());
+        assert!(scene.validate().is_empty());
+    }
+
+    #[test]
+    fn test_binary_round_trip_matches_json() {
+        let mut scene = Session::new("binary");
+        let mesh = Mesh::from_polygons(
+            vec![vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ]],
+            None,
+        );
+        let mesh_guid = scene.add_mesh(mesh).name();
+        let point = scene.add_point(Point::new(0.1, 0.2, 0.3));
+        scene.add(&point, None);
+        scene.add_edge(&mesh_guid, &point.name(), "supports");
+        scene.set_attribute(&mesh_guid, "weight", AttrValue::from(2.5));
+
+        let data = scene.binarydump().unwrap();
+        let json = scene.jsondump().unwrap();
+        assert!(data.len() < json.len());
+        let loaded = Session::binaryload(&data).unwrap();
+        assert_eq!(loaded.jsondump().unwrap(), json);
+
+        let path = std::env::temp_dir().join(format!("session_{}.msgpack", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        scene.to_binary(path).unwrap();
+        let loaded = Session::from_binary(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded.lookup.len(), 2);
+        assert!(Session::binaryload(b"not messagepack").is_err());
+    }
+
+    #[test]
+    fn test_jsonload_migrates_old_versions() {
+        let mut scene = Session::new("versioned");
+        let guid = scene.add_point(Point::new(1.0, 2.0, 3.0)).name();
+        let json: serde_json::Value = serde_json::from_str(&scene.jsondump().unwrap()).unwrap();
+        assert_eq!(json["version"], Session::SCHEMA_VERSION);
+
+        // Files from before versioning lack the version and the per-object sections
+        let mut old = json.clone();
+        let fields = old.as_object_mut().unwrap();
+        for key in [
+            "version",
+            "attributes",
+            "collision_filters",
+            "groups",
+            "links",
+            "display",
+            "animations",
+        ] {
+            fields.remove(key);
+        }
+        let loaded = Session::jsonload(&old.to_string()).unwrap();
+        assert!(loaded.get_object(&guid).is_some());
+        assert!(loaded.attributes.is_empty());
+
+        // Version 1 files have no animation tracks
+        let mut v1 = json.clone();
+        v1["version"] = serde_json::json!(1);
+        v1.as_object_mut().unwrap().remove("animations");
+        assert!(Session::jsonload(&v1.to_string())
+            .unwrap()
+            .animations
+            .is_empty());
+
+        let mut newer = json.clone();
+        newer["version"] = serde_json::json!(Session::SCHEMA_VERSION + 1);
+        let error = Session::jsonload(&newer.to_string()).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("newer than the supported version"));
+
+        let mut unknown = json.clone();
+        unknown["materials"] = serde_json::json!([]);
+        unknown["layers"] = serde_json::json!({});
+        let error = Session::jsonload(&unknown.to_string()).unwrap_err();
+        assert!(matches!(
+            error,
+            SessionError::SchemaMismatch(ref message)
+                if message == "Unknown fields in Session JSON: layers, materials"
+        ));
+        assert!(Session::jsonload("[]").is_err());
+    }
+
+    #[test]
+    fn test_get_geometry_applies_group_node_xforms() {
+        let mut scene = Session::new("groups");
+        let group = TreeNode::new("group");
+        group.set_xform(Some(Xform::translation(0.0, 0.0, 5.0)));
+        scene.add(&group, None);
+        let point = scene.add_point(Point::new(1.0, 0.0, 0.0));
+        scene.add(&point, &group);
+        let mut line = Line::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        line.xform = Xform::translation(1.0, 0.0, 0.0);
+        let line = scene.add_line(line);
+        scene.add(&line, &group);
+
+        let geometry = scene.get_geometry();
+        assert!((geometry.points[0].z() - 5.0).abs() < 1e-9);
+        assert!((geometry.lines[0].start().x() - 1.0).abs() < 1e-9);
+        assert!((geometry.lines[0].start().z() - 5.0).abs() < 1e-9);
+
+        // Moving the group moves its children, also after a round trip
+        group.set_xform(Some(Xform::translation(0.0, 3.0, 0.0)));
+        let loaded = Session::jsonload(&scene.jsondump().unwrap()).unwrap();
+        let geometry = loaded.get_geometry();
+        assert!((geometry.points[0].y() - 3.0).abs() < 1e-9);
+        assert!(geometry.points[0].z().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_geometry_nested_chains_and_detached_objects() {
+        let mut scene = Session::new("chains");
+        // Ten chains of ten points, each point moved one up from its parent
+        let mut expected = Vec::new();
+        for chain in 0..10 {
+            let mut parent: Option<TreeNode> = None;
+            for depth in 0..10 {
+                let mut point = Point::new(chain as Scalar, 0.0, 0.0);
+                point.xform = Xform::translation(0.0, 0.0, 1.0);
+                let node = scene.add_point(point);
+                scene.add(&node, parent.as_ref());
+                expected.push((chain as Scalar, (depth + 1) as Scalar));
+                parent = Some(node);
+            }
+        }
+        let mut detached = Point::new(0.0, 7.0, 0.0);
+        detached.xform = Xform::translation(0.0, 0.0, 2.0);
+        scene.add_point(detached);
+        let mut placed_line = Line::new(0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+        placed_line.xform = Xform::translation(1.0, 0.0, 0.0);
+        let line = scene.add_line(placed_line);
+        let group = TreeNode::new("group");
+        group.set_xform(Some(Xform::translation(0.0, 0.0, 10.0)));
+        scene.add(&group, None);
+        scene.add(&line, &group);
+
+        let geometry = scene.get_geometry();
+        assert_eq!(geometry.points.len(), 101);
+        for (point, (x, z)) in geometry.points.iter().zip(&expected) {
+            assert!((point.x() - x).abs() < 1e-9);
+            assert!((point.z() - z).abs() < 1e-9);
+        }
+        assert!((geometry.points[100].y() - 7.0).abs() < 1e-9);
+        assert!((geometry.points[100].z() - 2.0).abs() < 1e-9);
+        assert!((geometry.lines[0].start().x() - 1.0).abs() < 1e-9);
+        assert!((geometry.lines[0].start().z() - 10.0).abs() < 1e-9);
+        // The session objects are not modified
+        assert!(scene.objects_of::<Point>()[5].z().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_remove_object_policies() {
+        // root - a (xform z + 1) - b - c, root - d
+        let build = || {
+            let mut scene = Session::new("removal");
+            let mut a = Point::new(0.0, 0.0, 0.0);
+            a.xform = Xform::translation(0.0, 0.0, 1.0);
+            let points = [a, Point::new(1.0, 0.0, 0.0), Point::new(2.0, 0.0, 0.0)];
+            let guids: Vec<String> = points.iter().map(|point| point.guid.clone()).collect();
+            let nodes: Vec<TreeNode> = points
+                .into_iter()
+                .map(|point| scene.add_point(point))
+                .collect();
+            scene.add(&nodes[0], None);
+            scene.add(&nodes[1], &nodes[0]);
+            scene.add(&nodes[2], &nodes[1]);
+            let d = scene.add_point(Point::new(3.0, 0.0, 0.0));
+            scene.add(&d, None);
+            scene.add_edge(&guids[0], &guids[1], "supports");
+            (scene, guids)
+        };
+        let names = |scene: &Session| -> Vec<String> {
+            scene
+                .tree
+                .root()
+                .unwrap()
+                .children()
+                .iter()
+                .map(TreeNode::name)
+                .collect()
+        };
+
+        let (mut scene, g) = build();
+        let before = scene.get_geometry().points[1].z();
+        assert!((before - 1.0).abs() < 1e-9);
+        let report = scene
+            .remove_object_with(&g[0], RemovalPolicy::Reparent)
+            .unwrap();
+        assert_eq!(report.removed, [g[0].clone()]);
+        assert_eq!(report.reparented, [g[1].clone()]);
+        assert_eq!(names(&scene)[0], g[1]);
+        assert_eq!(names(&scene).len(), 2);
+        assert!(!scene.graph.has_node(&g[0]));
+        // The children stay where they were
+        let geometry = scene.get_geometry();
+        assert!((geometry.points[0].z() - 1.0).abs() < 1e-9);
+        assert!((geometry.points[1].z() - 1.0).abs() < 1e-9);
+
+        let (mut scene, g) = build();
+        let report = scene
+            .remove_object_with(&g[0], RemovalPolicy::DeleteSubtree)
+            .unwrap();
+        assert_eq!(report.removed, g);
+        assert_eq!(scene.lookup.len(), 1);
+        assert_eq!(scene.tree.nodes().len(), 2);
+
+        let (mut scene, g) = build();
+        scene.removal_policy = RemovalPolicy::Detach;
+        assert!(scene.remove_object(&g[0]));
+        assert!(!scene.remove_object(&g[0]));
+        assert!(scene.get_object(&g[1]).is_some());
+        assert!(scene.tree.get_node_by_name(&g[1]).is_none());
+        assert_eq!(scene.tree.nodes().len(), 2);
+        assert!(scene
+            .remove_object_with("missing", RemovalPolicy::Reparent)
+            .is_none());
+    }
 }
@@ -0,0 +1,188 @@
+use crate::BoundingBox;
+use crate::Scalar;
+use std::collections::HashMap;
+
+type Cell = (i64, i64, i64);
+
+/// Uniform grid broad phase that hashes axis-aligned boxes into cubic cells.
+///
+/// Objects are registered in every cell their box touches, so insertion and
+/// removal are local updates and no hierarchy has to be rebuilt. Works best
+/// when objects are of similar size and the cell size matches them.
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    pub cell_size: Scalar,
+    cells: HashMap<Cell, Vec<usize>>,
+    objects: HashMap<usize, ([Scalar; 3], [Scalar; 3])>,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl SpatialHash {
+    /// Creates an empty grid.
+    ///
+    /// # Arguments
+    /// * `cell_size` - Edge length of the cubic cells, must be positive
+    pub fn new(cell_size: Scalar) -> Self {
+        SpatialHash {
+            cell_size,
+            cells: HashMap::new(),
+            objects: HashMap::new(),
+        }
+    }
+
+    /// Creates a grid from boxes with object ids equal to their indices.
+    ///
+    /// The cell size is the mean of the largest box extents, which keeps most
+    /// objects within a few cells.
+    pub fn from_boxes(bounding_boxes: &[BoundingBox]) -> Self {
+        let extents: Vec<([Scalar; 3], [Scalar; 3])> =
+            bounding_boxes.iter().map(Self::min_max).collect();
+        let mean_extent = extents
+            .iter()
+            .map(|(min, max)| (0..3).map(|i| max[i] - min[i]).fold(0.0, Scalar::max))
+            .sum::<Scalar>()
+            / extents.len().max(1) as Scalar;
+        let cell_size = if mean_extent > 0.0 { mean_extent } else { 1.0 };
+
+        let mut hash = Self::new(cell_size);
+        for (object_id, (min, max)) in extents.into_iter().enumerate() {
+            hash.insert_min_max(object_id, min, max);
+        }
+        hash
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Inserts an object, replacing any previous entry with the same id.
+    pub fn insert(&mut self, bbox: &BoundingBox, object_id: usize) {
+        self.remove(object_id);
+        let (min, max) = Self::min_max(bbox);
+        self.insert_min_max(object_id, min, max);
+    }
+
+    /// Removes an object.
+    ///
+    /// # Returns
+    /// True if the object was present.
+    pub fn remove(&mut self, object_id: usize) -> bool {
+        let Some((min, max)) = self.objects.remove(&object_id) else {
+            return false;
+        };
+        for cell in self.cell_range(&min, &max) {
+            if let Some(ids) = self.cells.get_mut(&cell) {
+                ids.retain(|&id| id != object_id);
+                if ids.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+        true
+    }
+
+    /// Finds objects whose boxes overlap a query box.
+    ///
+    /// # Returns
+    /// Sorted object ids.
+    pub fn query_aabb(&self, bbox: &BoundingBox) -> Vec<usize> {
+        let (min, max) = Self::min_max(bbox);
+        let mut result: Vec<usize> = Vec::new();
+        for cell in self.cell_range(&min, &max) {
+            if let Some(ids) = self.cells.get(&cell) {
+                result.extend(
+                    ids.iter()
+                        .filter(|id| Self::overlaps((&min, &max), &self.objects[id])),
+                );
+            }
+        }
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    /// Finds all pairs of overlapping objects.
+    ///
+    /// # Returns
+    /// Sorted pairs (i, j) with i < j.
+    pub fn find_all_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (cell, ids) in &self.cells {
+            for (n, &a) in ids.iter().enumerate() {
+                let box_a = &self.objects[&a];
+                for &b in &ids[n + 1..] {
+                    let box_b = &self.objects[&b];
+                    if !Self::overlaps((&box_a.0, &box_a.1), box_b) {
+                        continue;
+                    }
+                    // Report each pair only from the cell holding the overlap's min corner
+                    let overlap_min = [
+                        box_a.0[0].max(box_b.0[0]),
+                        box_a.0[1].max(box_b.0[1]),
+                        box_a.0[2].max(box_b.0[2]),
+                    ];
+                    if self.cell_of(&overlap_min) == *cell {
+                        pairs.push((a.min(b), a.max(b)));
+                    }
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    fn insert_min_max(&mut self, object_id: usize, min: [Scalar; 3], max: [Scalar; 3]) {
+        for cell in self.cell_range(&min, &max) {
+            self.cells.entry(cell).or_default().push(object_id);
+        }
+        self.objects.insert(object_id, (min, max));
+    }
+
+    fn min_max(bbox: &BoundingBox) -> ([Scalar; 3], [Scalar; 3]) {
+        let aabb = if bbox.is_axis_aligned() {
+            bbox.clone()
+        } else {
+            BoundingBox::from_points(&bbox.corners(), 0.0)
+        };
+        let (c, h) = (&aabb.center, &aabb.half_size);
+        (
+            [c.x() - h.x(), c.y() - h.y(), c.z() - h.z()],
+            [c.x() + h.x(), c.y() + h.y(), c.z() + h.z()],
+        )
+    }
+
+    #[inline(always)]
+    fn overlaps(a: (&[Scalar; 3], &[Scalar; 3]), b: &([Scalar; 3], [Scalar; 3])) -> bool {
+        (0..3).all(|i| a.0[i] <= b.1[i] && a.1[i] >= b.0[i])
+    }
+
+    #[inline(always)]
+    fn cell_of(&self, p: &[Scalar; 3]) -> Cell {
+        (
+            (p[0] / self.cell_size).floor() as i64,
+            (p[1] / self.cell_size).floor() as i64,
+            (p[2] / self.cell_size).floor() as i64,
+        )
+    }
+
+    fn cell_range(&self, min: &[Scalar; 3], max: &[Scalar; 3]) -> impl Iterator<Item = Cell> {
+        let lo = self.cell_of(min);
+        let hi = self.cell_of(max);
+        (lo.0..=hi.0).flat_map(move |x| {
+            (lo.1..=hi.1).flat_map(move |y| (lo.2..=hi.2).map(move |z| (x, y, z)))
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "spatialhash_test.rs"]
+mod spatialhash_test;
@@ -0,0 +1,93 @@
+use super::*;
+use crate::{Point, Vector, BVH};
+use rand::{Rng, SeedableRng};
+
+fn random_boxes(count: usize) -> Vec<BoundingBox> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+    (0..count)
+        .map(|_| {
+            BoundingBox::new(
+                Point::new(
+                    rng.gen_range(-20.0..20.0),
+                    rng.gen_range(-20.0..20.0),
+                    rng.gen_range(-20.0..20.0),
+                ),
+                Vector::new(1.0, 0.0, 0.0),
+                Vector::new(0.0, 1.0, 0.0),
+                Vector::new(0.0, 0.0, 1.0),
+                Vector::new(
+                    rng.gen_range(0.5..3.0),
+                    rng.gen_range(0.5..3.0),
+                    rng.gen_range(0.5..3.0),
+                ),
+            )
+        })
+        .collect()
+}
+
+fn brute_force_pairs(boxes: &[BoundingBox]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for i in 0..boxes.len() {
+        for j in i + 1..boxes.len() {
+            if boxes[i].collides_with(&boxes[j]) {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+#[test]
+fn test_spatial_hash_pairs_match_bvh() {
+    let boxes = random_boxes(200);
+    let hash = SpatialHash::from_boxes(&boxes);
+    assert_eq!(hash.len(), 200);
+
+    let pairs = hash.find_all_pairs();
+    assert_eq!(pairs, brute_force_pairs(&boxes));
+
+    let bvh = BVH::from_boxes(&boxes, 100.0);
+    let (mut bvh_pairs, _, _) = bvh.check_all_collisions(&boxes);
+    bvh_pairs.sort_unstable();
+    assert_eq!(pairs, bvh_pairs);
+}
+
+#[test]
+fn test_spatial_hash_insert_remove() {
+    let boxes = random_boxes(50);
+    let mut hash = SpatialHash::new(4.0);
+    for (i, bbox) in boxes.iter().enumerate() {
+        hash.insert(bbox, i);
+    }
+    assert_eq!(hash.find_all_pairs(), brute_force_pairs(&boxes));
+
+    assert!(hash.remove(3));
+    assert!(!hash.remove(3));
+    assert_eq!(hash.len(), 49);
+    let expected: Vec<(usize, usize)> = brute_force_pairs(&boxes)
+        .into_iter()
+        .filter(|&(i, j)| i != 3 && j != 3)
+        .collect();
+    assert_eq!(hash.find_all_pairs(), expected);
+
+    // Re-inserting an id moves the object
+    let far = BoundingBox::from_point(Point::new(1000.0, 0.0, 0.0), 1.0);
+    hash.insert(&far, 0);
+    assert_eq!(hash.len(), 49);
+    assert_eq!(hash.query_aabb(&far), vec![0]);
+}
+
+#[test]
+fn test_spatial_hash_query_aabb() {
+    let boxes = random_boxes(100);
+    let hash = SpatialHash::from_boxes(&boxes);
+    let query = BoundingBox::from_points(
+        &[Point::new(-5.0, -5.0, -5.0), Point::new(5.0, 5.0, 5.0)],
+        0.0,
+    );
+
+    let expected: Vec<usize> = (0..boxes.len())
+        .filter(|&i| boxes[i].collides_with(&query))
+        .collect();
+    assert_eq!(hash.query_aabb(&query), expected);
+}
@@ -0,0 +1,126 @@
+//! Deterministic random data for tests and benchmarks.
+//!
+//! `Pcg32` is the PCG-XSH-RR generator of O'Neill's `pcg32_random_r`, so the
+//! Rust, Python and C++ benchmarks draw the same numbers from the same seed
+//! without relying on the platform `rand()`. The other implementations are:
+//!
+//! ```python
+//! class Pcg32:
+//!     MASK = (1 << 64) - 1
+//!
+//!     def __init__(self, seed, stream=54):
+//!         self.state, self.increment = 0, ((stream << 1) | 1) & self.MASK
+//!         self.next_u32()
+//!         self.state = (self.state + seed) & self.MASK
+//!         self.next_u32()
+//!
+//!     def next_u32(self):
+//!         old = self.state
+//!         self.state = (old * 6364136223846793005 + self.increment) & self.MASK
+//!         xorshifted = (((old >> 18) ^ old) >> 27) & 0xFFFFFFFF
+//!         rot = old >> 59
+//!         return ((xorshifted >> rot) | (xorshifted << ((-rot) & 31))) & 0xFFFFFFFF
+//!
+//!     def next_f64(self):
+//!         return self.next_u32() / 4294967296.0
+//! ```
+//!
+//! ```cpp
+//! struct Pcg32 {
+//!     uint64_t state = 0, increment;
+//!     explicit Pcg32(uint64_t seed, uint64_t stream = 54) : increment((stream << 1u) | 1u) {
+//!         next_u32();
+//!         state += seed;
+//!         next_u32();
+//!     }
+//!     uint32_t next_u32() {
+//!         uint64_t old = state;
+//!         state = old * 6364136223846793005ULL + increment;
+//!         uint32_t xorshifted = uint32_t(((old >> 18u) ^ old) >> 27u);
+//!         uint32_t rot = uint32_t(old >> 59u);
+//!         return (xorshifted >> rot) | (xorshifted << ((-rot) & 31));
+//!     }
+//!     double next_f64() { return next_u32() / 4294967296.0; }
+//! };
+//! ```
+
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{BoundingBox, Point, Scalar, Vector};
+
+/// Small seeded generator with identical Python and C++ versions, see the module docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Generator for a seed on the default stream 54, as in the PCG reference demo.
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, 54)
+    }
+
+    /// Generator for a seed on one of 2^63 independent streams.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Pcg32 {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// Uniform value in [0, 1), with 32 random bits.
+    pub fn next_f64(&mut self) -> f64 {
+        self.next_u32() as f64 / 4_294_967_296.0
+    }
+
+    /// Uniform value in [min, max).
+    pub fn range(&mut self, min: Scalar, max: Scalar) -> Scalar {
+        min + (self.next_f64() as Scalar) * (max - min)
+    }
+}
+
+/// Axis-aligned boxes scattered in a cube, the data set of the BVH benchmarks.
+///
+/// Each box draws its center x, y, z in `[-world_size / 2, world_size / 2)`,
+/// then its width, height and depth in `[world_size / 20, world_size / 10)`.
+///
+/// # Arguments
+/// * `seed` - Seed of the `Pcg32` generator
+/// * `count` - Number of boxes
+/// * `world_size` - Edge length of the cube centered at the origin
+pub fn random_boxes(seed: u64, count: usize, world_size: Scalar) -> Vec<BoundingBox> {
+    let mut rng = Pcg32::new(seed);
+    let (min_size, max_size) = (world_size / 20.0, world_size / 10.0);
+    (0..count)
+        .map(|_| {
+            let half_world = world_size * 0.5;
+            let [x, y, z] = [0; 3].map(|_| rng.range(-half_world, half_world));
+            let [w, h, d] = [0; 3].map(|_| rng.range(min_size, max_size));
+            BoundingBox::new(
+                Point::new(x, y, z),
+                Vector::new(1.0, 0.0, 0.0),
+                Vector::new(0.0, 1.0, 0.0),
+                Vector::new(0.0, 0.0, 1.0),
+                Vector::new(w * 0.5, h * 0.5, d * 0.5),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "testing_test.rs"]
+mod testing_test;
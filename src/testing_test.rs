@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn test_pcg32_matches_reference() {
+    // First outputs of the pcg32 reference demo for seed 42 on stream 54
+    let mut rng = Pcg32::new(42);
+    let outputs: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+    assert_eq!(
+        outputs,
+        [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+    );
+
+    let mut other = Pcg32::with_stream(42, 7);
+    assert_ne!(other.next_u32(), 0xa15c02b7);
+    let value = Pcg32::new(1).next_f64();
+    assert!((0.0..1.0).contains(&value));
+}
+
+#[test]
+fn test_random_boxes() {
+    let boxes = random_boxes(42, 100, 100.0);
+    assert_eq!(boxes.len(), 100);
+    for bbox in &boxes {
+        assert!(bbox.center.x().abs() <= 50.0);
+        assert!(bbox.center.z().abs() <= 50.0);
+        assert!((2.5..5.0).contains(&bbox.half_size.y()));
+    }
+    let again = random_boxes(42, 100, 100.0);
+    assert_eq!(again[99].center.x(), boxes[99].center.x());
+    assert_ne!(random_boxes(7, 1, 100.0)[0].center.x(), boxes[0].center.x());
+}
@@ -0,0 +1,252 @@
+use crate::error::SessionError;
+use crate::{AttrValue, Mesh, Session, Xform};
+use std::fmt::Write as _;
+use std::io::Write;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+/// Length unit of the coordinates in a 3MF model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Unit {
+    Micron,
+    #[default]
+    Millimeter,
+    Centimeter,
+    Inch,
+    Foot,
+    Meter,
+}
+
+impl Unit {
+    /// The name of the unit in the `unit` attribute of a 3MF model.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Micron => "micron",
+            Unit::Millimeter => "millimeter",
+            Unit::Centimeter => "centimeter",
+            Unit::Inch => "inch",
+            Unit::Foot => "foot",
+            Unit::Meter => "meter",
+        }
+    }
+}
+
+/// Write the meshes of a Session as a 3MF archive for 3D printing.
+///
+/// Every mesh becomes a model object with its faces fan-triangulated and a
+/// build item carrying its xform. Objects keep their name, GUID and the
+/// Session attributes as metadata, and vertex colors are written as a color
+/// group unless they are all white. The Session name is the model title.
+///
+/// # Arguments
+/// * `session` - The Session to export
+/// * `filepath` - The path of the 3MF file
+/// * `unit` - The unit of the Session coordinates
+///
+/// # Returns
+/// A Result indicating success or failure of the file write operation.
+pub fn write_3mf(session: &Session, filepath: &str, unit: Unit) -> Result<(), SessionError> {
+    let model = model_xml(session, unit);
+    let mut zip = ZipWriter::new(std::fs::File::create(filepath)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", RELATIONSHIPS),
+        ("3D/3dmodel.model", model.as_str()),
+    ] {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// The 3D model part of the archive.
+fn model_xml(session: &Session, unit: Unit) -> String {
+    let mut xml = String::new();
+    let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<model unit="{}" xml:lang="en-US" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" xmlns:m="http://schemas.microsoft.com/3dmanufacturing/material/2015/02" xmlns:session="urn:session_rust:metadata">"#,
+        unit.as_str()
+    );
+    let _ = writeln!(
+        xml,
+        r#"  <metadata name="Title">{}</metadata>"#,
+        escape(&session.name)
+    );
+    let _ = writeln!(
+        xml,
+        r#"  <metadata name="Application">session_rust</metadata>"#
+    );
+
+    let mut items = Vec::new();
+    let mut next_id = 1;
+    xml.push_str("  <resources>\n");
+    for mesh in session.objects_of::<Mesh>() {
+        let (vertices, faces) = mesh.to_vertices_and_faces();
+        let triangles: Vec<[usize; 3]> = faces
+            .iter()
+            .flat_map(|face| {
+                (1..face.len().saturating_sub(1)).map(|i| [face[0], face[i], face[i + 1]])
+            })
+            .collect();
+        if triangles.is_empty() {
+            continue;
+        }
+
+        let colors = vertex_colors(mesh, vertices.len()).map(|colors| {
+            let id = next_id;
+            next_id += 1;
+            let _ = writeln!(xml, r#"    <m:colorgroup id="{id}">"#);
+            for color in colors {
+                let _ = writeln!(xml, r#"      <m:color color="{color}"/>"#);
+            }
+            xml.push_str("    </m:colorgroup>\n");
+            id
+        });
+
+        let id = next_id;
+        next_id += 1;
+        let _ = writeln!(
+            xml,
+            r#"    <object id="{id}" type="model" name="{}">"#,
+            escape(&mesh.name)
+        );
+        xml.push_str("      <metadatagroup>\n");
+        for (name, value) in object_metadata(session, mesh) {
+            let _ = writeln!(
+                xml,
+                r#"        <metadata name="session:{}">{}</metadata>"#,
+                escape(&name),
+                escape(&value)
+            );
+        }
+        xml.push_str("      </metadatagroup>\n");
+        xml.push_str("      <mesh>\n        <vertices>\n");
+        for point in &vertices {
+            let _ = writeln!(
+                xml,
+                r#"          <vertex x="{}" y="{}" z="{}"/>"#,
+                point.x(),
+                point.y(),
+                point.z()
+            );
+        }
+        xml.push_str("        </vertices>\n        <triangles>\n");
+        for [a, b, c] in &triangles {
+            let _ = match colors {
+                Some(pid) => writeln!(
+                    xml,
+                    r#"          <triangle v1="{a}" v2="{b}" v3="{c}" pid="{pid}" p1="{a}" p2="{b}" p3="{c}"/>"#
+                ),
+                None => writeln!(xml, r#"          <triangle v1="{a}" v2="{b}" v3="{c}"/>"#),
+            };
+        }
+        xml.push_str("        </triangles>\n      </mesh>\n    </object>\n");
+        items.push((id, transform(&mesh.xform)));
+    }
+    xml.push_str("  </resources>\n  <build>\n");
+    for (id, transform) in items {
+        match transform {
+            Some(transform) => {
+                let _ = writeln!(
+                    xml,
+                    r#"    <item objectid="{id}" transform="{transform}"/>"#
+                );
+            }
+            None => {
+                let _ = writeln!(xml, r#"    <item objectid="{id}"/>"#);
+            }
+        }
+    }
+    xml.push_str("  </build>\n</model>\n");
+    xml
+}
+
+/// Name, GUID and the scalar Session attributes of a mesh, sorted by key.
+fn object_metadata(session: &Session, mesh: &Mesh) -> Vec<(String, String)> {
+    let mut metadata = vec![
+        ("name".to_string(), mesh.name.clone()),
+        ("guid".to_string(), mesh.guid.clone()),
+    ];
+    let mut attributes: Vec<(String, String)> = session
+        .get_attributes(&mesh.guid)
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                AttrValue::Bool(value) => value.to_string(),
+                AttrValue::Number(value) => value.to_string(),
+                AttrValue::String(value) => value.clone(),
+                AttrValue::Array(_) => return None,
+            };
+            Some((format!("attribute.{key}"), value))
+        })
+        .collect();
+    attributes.sort();
+    metadata.extend(attributes);
+    metadata
+}
+
+/// Vertex colors as `#RRGGBBAA`, when there is one per vertex and not all are white.
+fn vertex_colors(mesh: &Mesh, vertex_count: usize) -> Option<Vec<String>> {
+    let colors = &mesh.pointcolors;
+    let all_white = colors
+        .iter()
+        .all(|c| (c.r, c.g, c.b, c.a) == (255, 255, 255, 255));
+    if colors.len() != vertex_count || all_white {
+        return None;
+    }
+    Some(
+        colors
+            .iter()
+            .map(|c| format!("#{:02X}{:02X}{:02X}{:02X}", c.r, c.g, c.b, c.a))
+            .collect(),
+    )
+}
+
+/// The 3MF affine matrix `m00 m01 m02 m10 .. m32` of an xform, or None for the identity.
+///
+/// 3MF multiplies row vectors, so its rows are the columns of the xform.
+fn transform(xform: &Xform) -> Option<String> {
+    if xform.m == Xform::identity().m {
+        return None;
+    }
+    let m = &xform.m;
+    let values = [
+        m[0], m[1], m[2], m[4], m[5], m[6], m[8], m[9], m[10], m[12], m[13], m[14],
+    ];
+    Some(
+        values
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+#[path = "threemf_test.rs"]
+mod threemf_test;
@@ -0,0 +1,57 @@
+use crate::threemf::{write_3mf, Unit};
+use crate::{Color, Mesh, Point, Session, Xform};
+use std::io::Read;
+
+fn read_entry(path: &str, name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut content = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    content
+}
+
+#[test]
+fn test_write_3mf_model() {
+    let mut session = Session::new("bracket & plate");
+    let mut quad = Mesh::from_polygons(
+        vec![vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(10.0, 0.0, 0.0),
+            Point::new(10.0, 10.0, 0.0),
+            Point::new(0.0, 10.0, 0.0),
+        ]],
+        None,
+    );
+    quad.name = "plate".to_string();
+    quad.xform = Xform::translation(1.0, 2.0, 3.0);
+    quad.set_vertex_color(0, Color::new(255, 0, 0, 255));
+    let guid = session.add_mesh(quad).name();
+    session.set_attribute(&guid, "material", "PLA".into());
+    session.add_mesh(Mesh::new());
+
+    let path = std::env::temp_dir()
+        .join(format!("print_{}.3mf", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned();
+    write_3mf(&session, &path, Unit::Millimeter).unwrap();
+    let model = read_entry(&path, "3D/3dmodel.model");
+    assert!(read_entry(&path, "_rels/.rels").contains("/3D/3dmodel.model"));
+    assert!(read_entry(&path, "[Content_Types].xml").contains("3dmodel+xml"));
+    std::fs::remove_file(&path).ok();
+
+    assert!(model.contains(r#"unit="millimeter""#));
+    assert!(model.contains(r#"<metadata name="Title">bracket &amp; plate</metadata>"#));
+    assert!(model.contains(&format!(
+        r#"<metadata name="session:guid">{guid}</metadata>"#
+    )));
+    assert!(model.contains(r#"<metadata name="session:attribute.material">PLA</metadata>"#));
+    // The empty mesh is skipped, the quad is split into two triangles
+    assert_eq!(model.matches("<object ").count(), 1);
+    assert_eq!(model.matches("<vertex ").count(), 4);
+    assert_eq!(model.matches("<triangle ").count(), 2);
+    assert!(model.contains(r##"<m:color color="#FF0000FF"/>"##));
+    assert!(model.contains(r#"transform="1 0 0 0 1 0 0 0 1 1 2 3""#));
+}
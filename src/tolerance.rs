@@ -1,39 +1,48 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::scalar::consts::PI as STD_PI;
+use crate::Scalar;
+#[cfg(feature = "std")]
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::f64::consts::PI as STD_PI;
 
 /// Mathematical constants
-pub const PI: f64 = STD_PI;
-pub const TO_DEGREES: f64 = 180.0 / STD_PI;
-pub const TO_RADIANS: f64 = STD_PI / 180.0;
+pub const PI: Scalar = STD_PI;
+pub const TO_DEGREES: Scalar = 180.0 / STD_PI;
+pub const TO_RADIANS: Scalar = STD_PI / 180.0;
 
 /// Scale factor
-pub const SCALE: f64 = 1e6;
+pub const SCALE: Scalar = 1e6;
 
 /// Tolerance settings for geometric operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tolerance {
     pub unit: String,
-    absolute: Option<f64>,
-    relative: Option<f64>,
-    angular: Option<f64>,
-    approximation: Option<f64>,
+    absolute: Option<Scalar>,
+    relative: Option<Scalar>,
+    angular: Option<Scalar>,
+    approximation: Option<Scalar>,
     precision: Option<i32>,
-    lineardeflection: Option<f64>,
-    angulardeflection: Option<f64>,
+    lineardeflection: Option<Scalar>,
+    angulardeflection: Option<Scalar>,
 }
 
 impl Tolerance {
-    /// Default tolerance values (f64 only)
-    pub const ABSOLUTE: f64 = 1e-9;
-    pub const RELATIVE: f64 = 1e-6;
-    pub const ANGULAR: f64 = 1e-6;
-    pub const APPROXIMATION: f64 = 1e-3;
+    /// Default tolerance values
+    #[cfg(not(feature = "f32"))]
+    pub const ABSOLUTE: Scalar = 1e-9;
+    /// Single precision resolves about 1e-7 relative, so lengths of order one
+    /// are compared at 1e-5.
+    #[cfg(feature = "f32")]
+    pub const ABSOLUTE: Scalar = 1e-5;
+    pub const RELATIVE: Scalar = 1e-6;
+    pub const ANGULAR: Scalar = 1e-6;
+    pub const APPROXIMATION: Scalar = 1e-3;
     pub const PRECISION: i32 = 3;
-    pub const LINEARDEFLECTION: f64 = 1e-3;
-    pub const ANGULARDEFLECTION: f64 = 1e-1;
-    pub const ANGLE_TOLERANCE_DEGREES: f64 = 0.11;
-    pub const ZERO_TOLERANCE: f64 = 1e-12;
+    pub const LINEARDEFLECTION: Scalar = 1e-3;
+    pub const ANGULARDEFLECTION: Scalar = 1e-1;
+    pub const ANGLE_TOLERANCE_DEGREES: Scalar = 0.11;
+    pub const ZERO_TOLERANCE: Scalar = 1e-12;
 
     pub fn new(unit: &str) -> Self {
         Self {
@@ -58,35 +67,35 @@ impl Tolerance {
         self.angulardeflection = None;
     }
 
-    pub fn absolute(&self) -> f64 {
+    pub fn absolute(&self) -> Scalar {
         self.absolute.unwrap_or(Self::ABSOLUTE)
     }
 
-    pub fn set_absolute(&mut self, value: f64) {
+    pub fn set_absolute(&mut self, value: Scalar) {
         self.absolute = Some(value);
     }
 
-    pub fn relative(&self) -> f64 {
+    pub fn relative(&self) -> Scalar {
         self.relative.unwrap_or(Self::RELATIVE)
     }
 
-    pub fn set_relative(&mut self, value: f64) {
+    pub fn set_relative(&mut self, value: Scalar) {
         self.relative = Some(value);
     }
 
-    pub fn angular(&self) -> f64 {
+    pub fn angular(&self) -> Scalar {
         self.angular.unwrap_or(Self::ANGULAR)
     }
 
-    pub fn set_angular(&mut self, value: f64) {
+    pub fn set_angular(&mut self, value: Scalar) {
         self.angular = Some(value);
     }
 
-    pub fn approximation(&self) -> f64 {
+    pub fn approximation(&self) -> Scalar {
         self.approximation.unwrap_or(Self::APPROXIMATION)
     }
 
-    pub fn set_approximation(&mut self, value: f64) {
+    pub fn set_approximation(&mut self, value: Scalar) {
         self.approximation = Some(value);
     }
 
@@ -98,57 +107,75 @@ impl Tolerance {
         self.precision = Some(value);
     }
 
-    pub fn lineardeflection(&self) -> f64 {
+    pub fn lineardeflection(&self) -> Scalar {
         self.lineardeflection.unwrap_or(Self::LINEARDEFLECTION)
     }
 
-    pub fn set_lineardeflection(&mut self, value: f64) {
+    pub fn set_lineardeflection(&mut self, value: Scalar) {
         self.lineardeflection = Some(value);
     }
 
-    pub fn angulardeflection(&self) -> f64 {
+    pub fn angulardeflection(&self) -> Scalar {
         self.angulardeflection.unwrap_or(Self::ANGULARDEFLECTION)
     }
 
-    pub fn set_angulardeflection(&mut self, value: f64) {
+    pub fn set_angulardeflection(&mut self, value: Scalar) {
         self.angulardeflection = Some(value);
     }
 
-    pub fn tolerance(&self, truevalue: f64, rtol: f64, atol: f64) -> f64 {
+    pub fn tolerance(&self, truevalue: Scalar, rtol: Scalar, atol: Scalar) -> Scalar {
         rtol * truevalue.abs() + atol
     }
 
-    pub fn compare(&self, a: f64, b: f64, rtol: f64, atol: f64) -> bool {
+    pub fn compare(&self, a: Scalar, b: Scalar, rtol: Scalar, atol: Scalar) -> bool {
         (a - b).abs() <= self.tolerance(b, rtol, atol)
     }
 
-    pub fn is_zero(&self, a: f64, tol: Option<f64>) -> bool {
+    pub fn is_zero(&self, a: Scalar, tol: Option<Scalar>) -> bool {
         let tol = tol.unwrap_or(self.absolute());
         a.abs() <= tol
     }
 
-    pub fn is_positive(&self, a: f64, tol: Option<f64>) -> bool {
+    pub fn is_positive(&self, a: Scalar, tol: Option<Scalar>) -> bool {
         let tol = tol.unwrap_or(self.absolute());
         a > tol
     }
 
-    pub fn is_negative(&self, a: f64, tol: Option<f64>) -> bool {
+    pub fn is_negative(&self, a: Scalar, tol: Option<Scalar>) -> bool {
         let tol = tol.unwrap_or(self.absolute());
         a < -tol
     }
 
-    pub fn is_between(&self, value: f64, minval: f64, maxval: f64, atol: Option<f64>) -> bool {
+    pub fn is_between(
+        &self,
+        value: Scalar,
+        minval: Scalar,
+        maxval: Scalar,
+        atol: Option<Scalar>,
+    ) -> bool {
         let atol = atol.unwrap_or(self.absolute());
         minval - atol <= value && value <= maxval + atol
     }
 
-    pub fn is_close(&self, a: f64, b: f64, rtol: Option<f64>, atol: Option<f64>) -> bool {
+    pub fn is_close(
+        &self,
+        a: Scalar,
+        b: Scalar,
+        rtol: Option<Scalar>,
+        atol: Option<Scalar>,
+    ) -> bool {
         let rtol = rtol.unwrap_or(self.relative());
         let atol = atol.unwrap_or(self.absolute());
         self.compare(a, b, rtol, atol)
     }
 
-    pub fn is_allclose(&self, a: &[f64], b: &[f64], rtol: Option<f64>, atol: Option<f64>) -> bool {
+    pub fn is_allclose(
+        &self,
+        a: &[Scalar],
+        b: &[Scalar],
+        rtol: Option<Scalar>,
+        atol: Option<Scalar>,
+    ) -> bool {
         let rtol = rtol.unwrap_or(self.relative());
         let atol = atol.unwrap_or(self.absolute());
         a.iter()
@@ -156,17 +183,17 @@ impl Tolerance {
             .all(|(x, y)| self.compare(*x, *y, rtol, atol))
     }
 
-    pub fn is_angle_zero(&self, a: f64, tol: Option<f64>) -> bool {
+    pub fn is_angle_zero(&self, a: Scalar, tol: Option<Scalar>) -> bool {
         let tol = tol.unwrap_or(self.angular());
         a.abs() <= tol
     }
 
-    pub fn is_angles_close(&self, a: f64, b: f64, tol: Option<f64>) -> bool {
+    pub fn is_angles_close(&self, a: Scalar, b: Scalar, tol: Option<Scalar>) -> bool {
         let tol = tol.unwrap_or(self.angular());
         (a - b).abs() <= tol
     }
 
-    pub fn geometric_key(&self, xyz: [f64; 3], precision: Option<i32>) -> String {
+    pub fn geometric_key(&self, xyz: [Scalar; 3], precision: Option<i32>) -> String {
         let precision = precision.unwrap_or_else(|| self.precision());
         let [mut x, mut y, mut z] = xyz;
 
@@ -176,7 +203,7 @@ impl Tolerance {
 
         if precision < -1 {
             let p = (-precision - 1) as u32;
-            let factor = 10_f64.powi(p as i32);
+            let factor = (10 as Scalar).powi(p as i32);
             return format!(
                 "{},{},{}",
                 ((x / factor).round() * factor) as i64,
@@ -205,7 +232,7 @@ impl Tolerance {
         )
     }
 
-    pub fn geometric_key_xy(&self, xy: [f64; 2], precision: Option<i32>) -> String {
+    pub fn geometric_key_xy(&self, xy: [Scalar; 2], precision: Option<i32>) -> String {
         let precision = precision.unwrap_or_else(|| self.precision());
         let [mut x, mut y] = xy;
 
@@ -215,7 +242,7 @@ impl Tolerance {
 
         if precision < -1 {
             let p = (-precision - 1) as u32;
-            let factor = 10_f64.powi(p as i32);
+            let factor = (10 as Scalar).powi(p as i32);
             return format!(
                 "{},{}",
                 ((x / factor).round() * factor) as i64,
@@ -234,7 +261,7 @@ impl Tolerance {
         format!("{:.prec$},{:.prec$}", x, y, prec = precision as usize)
     }
 
-    pub fn format_number(&self, number: f64, precision: Option<i32>) -> String {
+    pub fn format_number(&self, number: Scalar, precision: Option<i32>) -> String {
         let precision = precision.unwrap_or_else(|| self.precision());
 
         if precision == -1 {
@@ -243,14 +270,14 @@ impl Tolerance {
 
         if precision < -1 {
             let p = (-precision - 1) as u32;
-            let factor = 10_f64.powi(p as i32);
+            let factor = (10 as Scalar).powi(p as i32);
             return format!("{}", ((number / factor).round() * factor) as i64);
         }
 
         format!("{:.prec$}", number, prec = precision as usize)
     }
 
-    pub fn precision_from_tolerance(&self, tol: Option<f64>) -> i32 {
+    pub fn precision_from_tolerance(&self, tol: Option<Scalar>) -> i32 {
         let tol = tol.unwrap_or_else(|| self.absolute());
         if tol < 1.0 {
             let s = format!("{tol:e}");
@@ -270,6 +297,7 @@ impl Default for Tolerance {
     }
 }
 
+#[cfg(feature = "std")]
 pub static TOL: Lazy<Tolerance> = Lazy::new(Tolerance::default);
 
 #[cfg(test)]
@@ -1,7 +1,8 @@
+use crate::error::SessionError;
 use crate::treenode::{TreeNode, TreeNodeSerde};
 use serde::{ser::Serialize as SerTrait, Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fmt;
-use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct Tree {
@@ -53,12 +54,24 @@ struct TreeSerde {
 impl Tree {
     pub fn new(name: &str) -> Self {
         Self {
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: name.to_string(),
             root_node: None,
         }
     }
 
+    /// Copy the tree with new node storage; `clone` shares nodes with the original.
+    pub fn deep_copy(&self) -> Self {
+        Tree {
+            guid: self.guid.clone(),
+            name: self.name.clone(),
+            root_node: self
+                .root_node
+                .as_ref()
+                .map(|root| TreeNode::from_serde(root.to_serde())),
+        }
+    }
+
     pub fn root(&self) -> Option<TreeNode> {
         self.root_node.clone()
     }
@@ -115,6 +128,94 @@ impl Tree {
         None
     }
 
+    /// Move a node with its subtree to the end of the children of a new parent.
+    ///
+    /// # Returns
+    /// `false`, leaving the tree unchanged, if either node is not in this tree,
+    /// the node is the root, or the new parent is the node or one of its descendants.
+    pub fn move_node(&mut self, node: &TreeNode, new_parent: &TreeNode) -> bool {
+        self.move_node_to(node, new_parent, usize::MAX)
+    }
+
+    /// Move a node with its subtree to a position among the children of a new parent.
+    ///
+    /// The index counts the children of the new parent without the node, and
+    /// an index past the last child appends. See `move_node` for the failures.
+    pub fn move_node_to(&mut self, node: &TreeNode, new_parent: &TreeNode, index: usize) -> bool {
+        if !self.contains(node) || !self.contains(new_parent) {
+            return false;
+        }
+        let Some(old_parent) = node.parent() else {
+            return false;
+        };
+        if new_parent == node || new_parent.ancestors().contains(node) {
+            return false;
+        }
+        old_parent.remove(node);
+        new_parent.insert(index, node);
+        true
+    }
+
+    /// Move a node to a position among its siblings.
+    ///
+    /// # Returns
+    /// `false` if the node is not in this tree or is the root.
+    pub fn set_sibling_index(&mut self, node: &TreeNode, index: usize) -> bool {
+        match node.parent() {
+            Some(parent) => self.move_node_to(node, &parent, index),
+            None => false,
+        }
+    }
+
+    /// Whether the node belongs to this tree.
+    pub fn contains(&self, node: &TreeNode) -> bool {
+        self.root_node.as_ref() == Some(&node.root())
+    }
+
+    /// Iterate the nodes depth first, each parent before its children.
+    pub fn iter_depth_first(&self) -> DepthFirstIter {
+        DepthFirstIter {
+            stack: self.root_node.iter().cloned().collect(),
+        }
+    }
+
+    /// Iterate the nodes breadth first, level by level.
+    pub fn iter_breadth_first(&self) -> BreadthFirstIter {
+        BreadthFirstIter {
+            queue: self.root_node.iter().cloned().collect(),
+        }
+    }
+
+    /// The nodes at a depth, the root at depth 0, in breadth first order.
+    pub fn nodes_at_depth(&self, depth: usize) -> Vec<TreeNode> {
+        self.iter_breadth_first()
+            .filter(|node| node.depth() == depth)
+            .collect()
+    }
+
+    /// The depth of the deepest node, None for an empty tree.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.iter_depth_first().map(|node| node.depth()).max()
+    }
+
+    /// The node with the GUID followed by its ancestors up to the root.
+    ///
+    /// # Returns
+    /// An empty vector if no node has the GUID.
+    pub fn path_to_root(&self, node_guid: &str) -> Vec<TreeNode> {
+        match self
+            .iter_depth_first()
+            .find(|node| node.guid() == node_guid)
+        {
+            Some(node) => {
+                let mut path = vec![node.clone()];
+                path.extend(node.ancestors());
+                path
+            }
+            None => vec![],
+        }
+    }
+
     pub fn leaves(&self) -> Vec<TreeNode> {
         self.nodes().into_iter().filter(|n| n.is_leaf()).collect()
     }
@@ -184,7 +285,7 @@ impl Tree {
         }
     }
 
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let serde_tree = TreeSerde {
             guid: self.guid.clone(),
             name: self.name.clone(),
@@ -197,7 +298,7 @@ impl Tree {
         Ok(String::from_utf8(buf)?)
     }
 
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         let serde_tree: TreeSerde = serde_json::from_str(json_data)?;
         let mut tree = Tree::new(&serde_tree.name);
         tree.guid = serde_tree.guid;
@@ -211,6 +312,36 @@ impl Tree {
     }
 }
 
+/// Depth first iterator over the nodes of a tree, see `Tree::iter_depth_first`.
+pub struct DepthFirstIter {
+    stack: Vec<TreeNode>,
+}
+
+impl Iterator for DepthFirstIter {
+    type Item = TreeNode;
+
+    fn next(&mut self) -> Option<TreeNode> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().into_iter().rev());
+        Some(node)
+    }
+}
+
+/// Breadth first iterator over the nodes of a tree, see `Tree::iter_breadth_first`.
+pub struct BreadthFirstIter {
+    queue: VecDeque<TreeNode>,
+}
+
+impl Iterator for BreadthFirstIter {
+    type Item = TreeNode;
+
+    fn next(&mut self) -> Option<TreeNode> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.children());
+        Some(node)
+    }
+}
+
 impl fmt::Display for Tree {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Tree({}, {})", self.name, self.guid)
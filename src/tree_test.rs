@@ -225,4 +225,96 @@ mod tests {
         let found = tree.get_node_by_name("root");
         assert_eq!(found.unwrap(), root);
     }
+
+    /// root -> (a -> (a1, a2), b -> (b1))
+    fn scene_tree() -> (Tree, [TreeNode; 6]) {
+        let mut tree = Tree::new("scene");
+        let nodes = ["root", "a", "b", "a1", "a2", "b1"].map(TreeNode::new);
+        let [root, a, b, a1, a2, b1] = &nodes;
+        tree.add(root, None);
+        tree.add(a, Some(root));
+        tree.add(b, Some(root));
+        tree.add(a1, Some(a));
+        tree.add(a2, Some(a));
+        tree.add(b1, Some(b));
+        (tree, nodes)
+    }
+
+    fn names(nodes: impl IntoIterator<Item = TreeNode>) -> Vec<String> {
+        nodes.into_iter().map(|node| node.name()).collect()
+    }
+
+    #[test]
+    fn test_tree_move_node() {
+        let (mut tree, [root, a, b, a1, _, b1]) = scene_tree();
+        assert!(tree.move_node(&a1, &b));
+        assert_eq!(names(b.children()), ["b1", "a1"]);
+        assert_eq!(names(a.children()), ["a2"]);
+        assert_eq!(a1.parent().unwrap(), b);
+
+        // Whole subtrees move
+        assert!(tree.move_node(&b, &a));
+        assert_eq!(
+            names(tree.iter_depth_first()),
+            ["root", "a", "a2", "b", "b1", "a1"]
+        );
+
+        // Cycles, the root and foreign nodes are refused
+        assert!(!tree.move_node(&a, &b1));
+        assert!(!tree.move_node(&a, &a));
+        assert!(!tree.move_node(&root, &a));
+        assert!(!tree.move_node(&TreeNode::new("other"), &a));
+        assert_eq!(
+            names(tree.iter_depth_first()),
+            ["root", "a", "a2", "b", "b1", "a1"]
+        );
+    }
+
+    #[test]
+    fn test_tree_sibling_order() {
+        let (mut tree, [root, a, b, a1, a2, _]) = scene_tree();
+        assert_eq!(a2.sibling_index(), Some(1));
+        assert_eq!(root.sibling_index(), None);
+
+        assert!(tree.set_sibling_index(&a2, 0));
+        assert_eq!(names(a.children()), ["a2", "a1"]);
+        assert!(tree.set_sibling_index(&a, 5));
+        assert_eq!(names(root.children()), ["b", "a"]);
+        assert!(!tree.set_sibling_index(&root, 0));
+
+        assert!(tree.move_node_to(&a1, &b, 0));
+        assert_eq!(names(b.children()), ["a1", "b1"]);
+    }
+
+    #[test]
+    fn test_tree_iterators_and_depth() {
+        let (tree, [root, _, _, a1, _, _]) = scene_tree();
+        assert_eq!(
+            names(tree.iter_depth_first()),
+            ["root", "a", "a1", "a2", "b", "b1"]
+        );
+        assert_eq!(
+            names(tree.iter_breadth_first()),
+            ["root", "a", "b", "a1", "a2", "b1"]
+        );
+        assert_eq!(tree.iter_depth_first().count(), tree.nodes().len());
+
+        assert_eq!(root.depth(), 0);
+        assert_eq!(a1.depth(), 2);
+        assert_eq!(names(tree.nodes_at_depth(1)), ["a", "b"]);
+        assert!(tree.nodes_at_depth(3).is_empty());
+        assert_eq!(tree.max_depth(), Some(2));
+
+        let empty = Tree::new("empty");
+        assert_eq!(empty.iter_depth_first().count(), 0);
+        assert_eq!(empty.iter_breadth_first().count(), 0);
+        assert_eq!(empty.max_depth(), None);
+    }
+
+    #[test]
+    fn test_tree_path_to_root() {
+        let (tree, [_, _, _, _, a2, _]) = scene_tree();
+        assert_eq!(names(tree.path_to_root(&a2.guid())), ["a2", "a", "root"]);
+        assert!(tree.path_to_root("missing").is_empty());
+    }
 }
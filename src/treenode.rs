@@ -1,9 +1,11 @@
+use crate::error::SessionError;
 use crate::tree::Tree;
+use crate::{AttrValue, Xform};
 use serde::{ser::Serialize as SerTrait, Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::rc::{Rc, Weak};
-use uuid::Uuid;
 
 // Internal type alias to hide complexity
 type NodeRef = Rc<RefCell<TreeNodeInner>>;
@@ -13,6 +15,8 @@ type WeakNodeRef = Weak<RefCell<TreeNodeInner>>;
 struct TreeNodeInner {
     pub guid: String,
     pub name: String,
+    xform: Option<Xform>,
+    attributes: BTreeMap<String, AttrValue>,
     children: Vec<NodeRef>,
     parent: Option<WeakNodeRef>,
     tree: Option<Weak<RefCell<Tree>>>,
@@ -29,6 +33,10 @@ struct TreeNodeInner {
 ///
 /// Note: TreeNode has its own guid (for identifying the node itself) and a name
 /// (which for geometry nodes, stores the geometry's GUID).
+///
+/// A node can also carry an xform, applied to its whole subtree by
+/// `Session::get_geometry`, and attributes, so group nodes without geometry
+/// can be moved and annotated as units.
 #[derive(Debug, Clone)]
 pub struct TreeNode {
     inner: NodeRef,
@@ -64,6 +72,10 @@ impl<'de> Deserialize<'de> for TreeNode {
 pub(crate) struct TreeNodeSerde {
     pub guid: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xform: Option<Xform>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, AttrValue>,
     pub children: Vec<TreeNodeSerde>,
 }
 
@@ -71,8 +83,10 @@ impl TreeNode {
     pub fn new(name: &str) -> Self {
         Self {
             inner: Rc::new(RefCell::new(TreeNodeInner {
-                guid: Uuid::new_v4().to_string(),
+                guid: crate::guid::new_guid(),
                 name: name.to_string(),
+                xform: None,
+                attributes: BTreeMap::new(),
                 children: Vec::new(),
                 parent: None,
                 tree: None,
@@ -88,12 +102,67 @@ impl TreeNode {
         self.inner.borrow().guid.clone()
     }
 
+    pub(crate) fn set_guid(&self, guid: String) {
+        self.inner.borrow_mut().guid = guid;
+    }
+
+    /// The transformation of the node and its subtree, None for the identity.
+    pub fn xform(&self) -> Option<Xform> {
+        self.inner.borrow().xform.clone()
+    }
+
+    /// Set or clear the transformation of the node and its subtree.
+    pub fn set_xform(&self, xform: Option<Xform>) {
+        self.inner.borrow_mut().xform = xform;
+    }
+
+    /// The xforms of the ancestors and the node combined, parents applied last.
+    pub fn world_xform(&self) -> Xform {
+        let local = self.xform().unwrap_or_else(Xform::identity);
+        match self.parent() {
+            Some(parent) => &parent.world_xform() * &local,
+            None => local,
+        }
+    }
+
+    /// Get an attribute of the node.
+    pub fn attribute(&self, key: &str) -> Option<AttrValue> {
+        self.inner.borrow().attributes.get(key).cloned()
+    }
+
+    /// Set an attribute of the node, returning the previous value.
+    pub fn set_attribute(&self, key: &str, value: AttrValue) -> Option<AttrValue> {
+        self.inner
+            .borrow_mut()
+            .attributes
+            .insert(key.to_string(), value)
+    }
+
+    /// Remove an attribute of the node, returning its value.
+    pub fn remove_attribute(&self, key: &str) -> Option<AttrValue> {
+        self.inner.borrow_mut().attributes.remove(key)
+    }
+
+    /// All attributes of the node, sorted by key.
+    pub fn attributes(&self) -> BTreeMap<String, AttrValue> {
+        self.inner.borrow().attributes.clone()
+    }
+
     pub fn add(&self, child: &TreeNode) {
         child.inner.borrow_mut().parent = Some(Rc::downgrade(&self.inner));
         child.inner.borrow_mut().tree = self.inner.borrow().tree.clone();
         self.inner.borrow_mut().children.push(child.inner.clone());
     }
 
+    /// Insert a child at a position among the children, at the end if the index is past it.
+    pub fn insert(&self, index: usize, child: &TreeNode) {
+        child.inner.borrow_mut().parent = Some(Rc::downgrade(&self.inner));
+        child.inner.borrow_mut().tree = self.inner.borrow().tree.clone();
+        let mut inner = self.inner.borrow_mut();
+        let index = index.min(inner.children.len());
+        inner.children.insert(index, child.inner.clone());
+    }
+
     pub fn remove(&self, child: &TreeNode) -> bool {
         let child_guid = child.guid();
         let mut inner = self.inner.borrow_mut();
@@ -130,6 +199,27 @@ impl TreeNode {
             .collect()
     }
 
+    /// The position of the node among the children of its parent, None for a root.
+    pub fn sibling_index(&self) -> Option<usize> {
+        self.parent()?
+            .inner
+            .borrow()
+            .children
+            .iter()
+            .position(|child| Rc::ptr_eq(child, &self.inner))
+    }
+
+    /// The number of ancestors of the node, 0 for a root.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut current = self.parent();
+        while let Some(node) = current {
+            depth += 1;
+            current = node.parent();
+        }
+        depth
+    }
+
     pub fn is_root(&self) -> bool {
         self.inner.borrow().parent.is_none()
     }
@@ -224,7 +314,7 @@ impl TreeNode {
         result
     }
 
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let serde_node = self.to_serde();
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
@@ -233,7 +323,7 @@ impl TreeNode {
         Ok(String::from_utf8(buf)?)
     }
 
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         let serde_node: TreeNodeSerde = serde_json::from_str(json_data)?;
         Ok(Self::from_serde(serde_node))
     }
@@ -243,6 +333,8 @@ impl TreeNode {
         TreeNodeSerde {
             guid: inner.guid.clone(),
             name: inner.name.clone(),
+            xform: inner.xform.clone(),
+            attributes: inner.attributes.clone(),
             children: inner
                 .children
                 .iter()
@@ -258,7 +350,12 @@ impl TreeNode {
 
     pub(crate) fn from_serde(serde_node: TreeNodeSerde) -> Self {
         let node = TreeNode::new(&serde_node.name);
-        node.inner.borrow_mut().guid = serde_node.guid;
+        {
+            let mut inner = node.inner.borrow_mut();
+            inner.guid = serde_node.guid;
+            inner.xform = serde_node.xform;
+            inner.attributes = serde_node.attributes;
+        }
 
         for child_serde in serde_node.children {
             let child = Self::from_serde(child_serde);
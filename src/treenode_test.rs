@@ -2,6 +2,7 @@
 mod tests {
     use crate::encoders::{json_dump, json_load};
     use crate::treenode::TreeNode;
+    use crate::{AttrValue, Xform};
 
     #[test]
     fn test_treenode_constructor() {
@@ -102,4 +103,43 @@ mod tests {
         assert_eq!(nodes.len(), 2);
         assert_eq!(nodes[0], root);
     }
+
+    #[test]
+    fn test_treenode_xform_and_attributes() {
+        let root = TreeNode::new("root");
+        let group = TreeNode::new("group");
+        root.add(&group);
+        assert!(group.xform().is_none());
+        assert_eq!(group.world_xform().m, Xform::identity().m);
+
+        root.set_xform(Some(Xform::translation(1.0, 0.0, 0.0)));
+        group.set_xform(Some(Xform::translation(0.0, 2.0, 0.0)));
+        assert_eq!(group.world_xform().m, Xform::translation(1.0, 2.0, 0.0).m);
+
+        assert!(group
+            .set_attribute("layer", AttrValue::from("walls"))
+            .is_none());
+        group.set_attribute("locked", AttrValue::Bool(true));
+        assert_eq!(group.attribute("layer"), Some(AttrValue::from("walls")));
+        assert_eq!(
+            group.attributes().keys().collect::<Vec<_>>(),
+            ["layer", "locked"]
+        );
+        assert_eq!(
+            group.remove_attribute("locked"),
+            Some(AttrValue::Bool(true))
+        );
+        assert!(group.attribute("locked").is_none());
+
+        // Round trip through JSON, plain nodes omit the new fields
+        let loaded = TreeNode::jsonload(&root.jsondump().unwrap()).unwrap();
+        let loaded_group = &loaded.children()[0];
+        assert_eq!(loaded_group.xform(), group.xform());
+        assert_eq!(loaded_group.attributes(), group.attributes());
+        assert_eq!(loaded_group.world_xform().m, group.world_xform().m);
+        let plain: serde_json::Value =
+            serde_json::from_str(&TreeNode::new("plain").jsondump().unwrap()).unwrap();
+        assert!(plain.get("xform").is_none());
+        assert!(plain.get("attributes").is_none());
+    }
 }
@@ -0,0 +1,226 @@
+//! Plain coordinate triple for bulk geometry.
+//!
+//! `Point` and `Vector` carry a GUID and a name, which is what Session objects
+//! need but costs two heap strings per coordinate. `Vec3` is the `Copy` value
+//! type used for the vertices of point clouds and for internal computations,
+//! converting to the rich types at the API boundary.
+
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{Point, Scalar, Vector, Xform};
+use core::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Three coordinates without identity, laid out as `[x, y, z]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct Vec3 {
+    pub x: Scalar,
+    pub y: Scalar,
+    pub z: Scalar,
+}
+
+/// Types whose coordinates can be read as a `Vec3`.
+///
+/// Lets the point set utilities, KD-tree and bounding boxes accept both
+/// `Point` and `Vec3` slices.
+pub trait AsVec3 {
+    fn as_vec3(&self) -> Vec3;
+}
+
+impl AsVec3 for Vec3 {
+    #[inline]
+    fn as_vec3(&self) -> Vec3 {
+        *self
+    }
+}
+
+impl<T: AsVec3> AsVec3 for &T {
+    #[inline]
+    fn as_vec3(&self) -> Vec3 {
+        (*self).as_vec3()
+    }
+}
+
+impl AsVec3 for Point {
+    #[inline]
+    fn as_vec3(&self) -> Vec3 {
+        Vec3::new(self.x(), self.y(), self.z())
+    }
+}
+
+impl AsVec3 for Vector {
+    #[inline]
+    fn as_vec3(&self) -> Vec3 {
+        Vec3::new(self.x(), self.y(), self.z())
+    }
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+
+    pub const fn new(x: Scalar, y: Scalar, z: Scalar) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn dot(&self, other: &Vec3) -> Scalar {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length_squared(&self) -> Scalar {
+        self.dot(self)
+    }
+
+    pub fn length(&self) -> Scalar {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(&self, other: &Vec3) -> Scalar {
+        (*self - *other).length()
+    }
+
+    /// The unit vector in the same direction, None for a zero length.
+    pub fn normalized(&self) -> Option<Vec3> {
+        let length = self.length();
+        (length > 0.0).then(|| *self * (1.0 / length))
+    }
+
+    /// Transform coordinates as points, dividing by w for projective xforms.
+    pub fn transform_points(xform: &Xform, points: &mut [Vec3]) {
+        let apply = |chunk: &mut [Vec3]| {
+            for point in chunk {
+                *point = xform.apply_to_point((*point).into()).into();
+            }
+        };
+        #[cfg(feature = "parallel")]
+        points.par_chunks_mut(4096).for_each(apply);
+        #[cfg(not(feature = "parallel"))]
+        apply(points);
+    }
+
+    /// Transform coordinates as directions, ignoring the translation.
+    pub fn transform_vectors(xform: &Xform, vectors: &mut [Vec3]) {
+        let m = &xform.m;
+        for v in vectors {
+            *v = Vec3::new(
+                m[0] * v.x + m[4] * v.y + m[8] * v.z,
+                m[1] * v.x + m[5] * v.y + m[9] * v.z,
+                m[2] * v.x + m[6] * v.y + m[10] * v.z,
+            );
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Conversions
+///////////////////////////////////////////////////////////////////////////////////////////
+
+impl From<[Scalar; 3]> for Vec3 {
+    fn from([x, y, z]: [Scalar; 3]) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl From<Vec3> for [Scalar; 3] {
+    fn from(v: Vec3) -> Self {
+        [v.x, v.y, v.z]
+    }
+}
+
+impl From<&Point> for Vec3 {
+    fn from(point: &Point) -> Self {
+        point.as_vec3()
+    }
+}
+
+impl From<Point> for Vec3 {
+    fn from(point: Point) -> Self {
+        point.as_vec3()
+    }
+}
+
+impl From<&Vector> for Vec3 {
+    fn from(vector: &Vector) -> Self {
+        vector.as_vec3()
+    }
+}
+
+impl From<Vector> for Vec3 {
+    fn from(vector: Vector) -> Self {
+        vector.as_vec3()
+    }
+}
+
+impl From<Vec3> for Point {
+    fn from(v: Vec3) -> Self {
+        Point::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vec3> for Vector {
+    fn from(v: Vec3) -> Self {
+        Vector::new(v.x, v.y, v.z)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////
+// Operators
+///////////////////////////////////////////////////////////////////////////////////////////
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<Scalar> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, factor: Scalar) -> Vec3 {
+        Vec3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Vec3) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, other: Vec3) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+#[path = "vec3_test.rs"]
+mod vec3_test;
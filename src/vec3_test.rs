@@ -0,0 +1,45 @@
+use crate::{AsVec3, Point, Vec3, Vector, Xform};
+
+#[test]
+fn test_vec3_arithmetic() {
+    let a = Vec3::new(1.0, 0.0, 0.0);
+    let b = Vec3::new(0.0, 2.0, 0.0);
+    assert_eq!(a + b, Vec3::new(1.0, 2.0, 0.0));
+    assert_eq!(a - b, Vec3::new(1.0, -2.0, 0.0));
+    assert_eq!(-(b * 0.5), Vec3::new(0.0, -1.0, 0.0));
+    assert_eq!(a.cross(&b), Vec3::new(0.0, 0.0, 2.0));
+    assert_eq!(a.dot(&b), 0.0);
+    assert_eq!((a - b).length_squared(), 5.0);
+    assert_eq!(b.distance(&Vec3::ZERO), 2.0);
+    assert_eq!(b.normalized(), Some(Vec3::new(0.0, 1.0, 0.0)));
+    assert_eq!(Vec3::ZERO.normalized(), None);
+}
+
+#[test]
+fn test_vec3_conversions() {
+    let point = Point::new(1.0, 2.0, 3.0);
+    let v = point.as_vec3();
+    assert_eq!(<[_; 3]>::from(v), [1.0, 2.0, 3.0]);
+    assert_eq!(Vec3::from([1.0, 2.0, 3.0]), v);
+    assert_eq!(Vec3::from(&Vector::new(1.0, 2.0, 3.0)), v);
+    let back = Point::from(v);
+    assert_eq!((back.x(), back.y(), back.z()), (1.0, 2.0, 3.0));
+    assert_eq!(Vector::from(v).z(), 3.0);
+    assert_eq!(
+        std::mem::size_of::<Vec3>(),
+        3 * std::mem::size_of::<crate::Scalar>()
+    );
+}
+
+#[test]
+fn test_vec3_transform() {
+    let xform =
+        Xform::translation(1.0, 0.0, 0.0) * Xform::rotation_z(crate::scalar::consts::FRAC_PI_2);
+    let mut points = [Vec3::new(1.0, 0.0, 0.0)];
+    Vec3::transform_points(&xform, &mut points);
+    assert!(points[0].distance(&Vec3::new(1.0, 1.0, 0.0)) < 1e-6);
+
+    let mut vectors = [Vec3::new(1.0, 0.0, 0.0)];
+    Vec3::transform_vectors(&xform, &mut vectors);
+    assert!(vectors[0].distance(&Vec3::new(0.0, 1.0, 0.0)) < 1e-6);
+}
@@ -1,10 +1,18 @@
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::tolerance::{Tolerance, SCALE, TO_DEGREES, TO_RADIANS};
-use serde::{ser::Serialize as SerTrait, Deserialize, Serialize};
-use std::fmt;
-use std::ops::{
+use crate::Scalar;
+use core::fmt;
+use core::ops::{
     Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
 };
-use uuid::Uuid;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use serde::ser::Serialize as SerTrait;
+use serde::{Deserialize, Serialize};
 
 /// A 3D vector with visual properties and JSON serialization support.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -13,25 +21,25 @@ pub struct Vector {
     pub guid: String,
     pub name: String,
     #[serde(rename = "x")]
-    _x: f64,
+    _x: Scalar,
     #[serde(rename = "y")]
-    _y: f64,
+    _y: Scalar,
     #[serde(rename = "z")]
-    _z: f64,
+    _z: Scalar,
     #[serde(skip)]
-    _length: f64,
+    _length: Scalar,
     #[serde(skip)]
     _has_length: bool,
 }
 
 impl Vector {
     /// Creates a new Vector with specified coordinates.
-    pub fn new(x: f64, y: f64, z: f64) -> Self {
+    pub fn new(x: Scalar, y: Scalar, z: Scalar) -> Self {
         Self {
             _x: x,
             _y: y,
             _z: z,
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_vector".to_string(),
             _length: 0.0,
             _has_length: false,
@@ -64,26 +72,26 @@ impl Vector {
     }
 
     /// Getters for coordinates
-    pub fn x(&self) -> f64 {
+    pub fn x(&self) -> Scalar {
         self._x
     }
-    pub fn y(&self) -> f64 {
+    pub fn y(&self) -> Scalar {
         self._y
     }
-    pub fn z(&self) -> f64 {
+    pub fn z(&self) -> Scalar {
         self._z
     }
 
     /// Setters for coordinates (invalidate cached length)
-    pub fn set_x(&mut self, v: f64) {
+    pub fn set_x(&mut self, v: Scalar) {
         self._x = v;
         self.invalidate_length_cache();
     }
-    pub fn set_y(&mut self, v: f64) {
+    pub fn set_y(&mut self, v: Scalar) {
         self._y = v;
         self.invalidate_length_cache();
     }
-    pub fn set_z(&mut self, v: f64) {
+    pub fn set_z(&mut self, v: Scalar) {
         self._z = v;
         self.invalidate_length_cache();
     }
@@ -128,9 +136,9 @@ impl Vector {
     ///
     /// Returns
     /// -------
-    /// f64
+    /// Scalar
     ///     The length of the vector.
-    pub fn compute_length(&self) -> f64 {
+    pub fn compute_length(&self) -> Scalar {
         (self._x * self._x + self._y * self._y + self._z * self._z).sqrt()
     }
 
@@ -138,9 +146,9 @@ impl Vector {
     ///
     /// Returns
     /// -------
-    /// f64
+    /// Scalar
     ///     The magnitude (length) of the vector.
-    pub fn magnitude(&mut self) -> f64 {
+    pub fn magnitude(&mut self) -> Scalar {
         if !self._has_length {
             self._length = self.compute_length();
             self._has_length = true;
@@ -149,7 +157,7 @@ impl Vector {
     }
 
     /// Computes the squared length of the vector (avoids sqrt for performance).
-    pub fn length_squared(&self) -> f64 {
+    pub fn length_squared(&self) -> Scalar {
         self._x * self._x + self._y * self._y + self._z * self._z
     }
 
@@ -164,6 +172,21 @@ impl Vector {
         }
     }
 
+    /// Normalize many vectors in place, see `normalize_self`.
+    ///
+    /// With the `parallel` feature the vectors are split in chunks over the rayon thread pool.
+    pub fn normalize_many(vectors: &mut [Vector]) {
+        let apply = |chunk: &mut [Vector]| {
+            for vector in chunk {
+                vector.normalize_self();
+            }
+        };
+        #[cfg(feature = "parallel")]
+        vectors.par_chunks_mut(4096).for_each(apply);
+        #[cfg(not(feature = "parallel"))]
+        apply(vectors);
+    }
+
     /// Returns a normalized copy of the vector.
     pub fn normalize(&self) -> Self {
         let mut result = self.clone();
@@ -180,7 +203,7 @@ impl Vector {
     }
 
     /// Scales the vector by a factor.
-    pub fn scale(&mut self, factor: f64) {
+    pub fn scale(&mut self, factor: Scalar) {
         self._x *= factor;
         self._y *= factor;
         self._z *= factor;
@@ -206,9 +229,9 @@ impl Vector {
     ///
     /// Returns
     /// -------
-    /// f64
+    /// Scalar
     ///     Dot product value.
-    pub fn dot(&self, other: &Vector) -> f64 {
+    pub fn dot(&self, other: &Vector) -> Scalar {
         self._x * other._x + self._y * other._y + self._z * other._z
     }
 
@@ -232,7 +255,7 @@ impl Vector {
     }
 
     /// Computes the angle between this vector and another in degrees.
-    pub fn angle(&self, other: &Vector, sign_by_cross_product: bool) -> f64 {
+    pub fn angle(&self, other: &Vector, sign_by_cross_product: bool) -> Scalar {
         let dotp = self.dot(other);
         let len_product = self.compute_length() * other.compute_length();
 
@@ -260,12 +283,16 @@ impl Vector {
     /// - projected length (scalar projection)
     /// - perpendicular projected vector (self - projection)
     /// - perpendicular projected vector length
-    pub fn projection(&self, onto: &Vector) -> (Vector, f64, Vector, f64) {
+    pub fn projection(&self, onto: &Vector) -> (Vector, Scalar, Vector, Scalar) {
         self.projection_with(onto, Tolerance::ZERO_TOLERANCE)
     }
 
     /// Same as `projection` but allows specifying a tolerance.
-    pub fn projection_with(&self, onto: &Vector, tolerance: f64) -> (Vector, f64, Vector, f64) {
+    pub fn projection_with(
+        &self,
+        onto: &Vector,
+        tolerance: Scalar,
+    ) -> (Vector, Scalar, Vector, Scalar) {
         let onto_len_sq = onto.length_squared();
 
         if onto_len_sq < tolerance {
@@ -318,7 +345,7 @@ impl Vector {
     }
 
     /// Gets a leveled vector (replicates statics bug with degrees passed to cos).
-    pub fn get_leveled_vector(&self, vertical_height: f64) -> Vector {
+    pub fn get_leveled_vector(&self, vertical_height: Scalar) -> Vector {
         let mut copy = self.clone();
         copy.normalize_self();
 
@@ -340,8 +367,8 @@ impl Vector {
         let i: usize;
         let j: usize;
         let k: usize;
-        let a: f64;
-        let b: f64;
+        let a: Scalar;
+        let b: Scalar;
 
         if v.y().abs() > v.x().abs() {
             if v.z().abs() > v.y().abs() {
@@ -408,11 +435,11 @@ impl Vector {
 
     /// Computes the cosine law for triangle edge length.
     pub fn cosine_law(
-        triangle_edge_length_a: f64,
-        triangle_edge_length_b: f64,
-        angle_in_degrees_between_edges: f64,
+        triangle_edge_length_a: Scalar,
+        triangle_edge_length_b: Scalar,
+        angle_in_degrees_between_edges: Scalar,
         degrees: bool,
-    ) -> f64 {
+    ) -> Scalar {
         let angle = if degrees {
             angle_in_degrees_between_edges * TO_RADIANS
         } else {
@@ -426,11 +453,11 @@ impl Vector {
 
     /// Computes the sine law for triangle angle.
     pub fn sine_law_angle(
-        triangle_edge_length_a: f64,
-        angle_in_degrees_in_front_of_a: f64,
-        triangle_edge_length_b: f64,
+        triangle_edge_length_a: Scalar,
+        angle_in_degrees_in_front_of_a: Scalar,
+        triangle_edge_length_b: Scalar,
         degrees: bool,
-    ) -> f64 {
+    ) -> Scalar {
         let angle_a = if degrees {
             angle_in_degrees_in_front_of_a * TO_RADIANS
         } else {
@@ -449,11 +476,11 @@ impl Vector {
 
     /// Computes the sine law for triangle edge length.
     pub fn sine_law_length(
-        triangle_edge_length_a: f64,
-        angle_in_degrees_in_front_of_a: f64,
-        angle_in_degrees_in_front_of_b: f64,
+        triangle_edge_length_a: Scalar,
+        angle_in_degrees_in_front_of_a: Scalar,
+        angle_in_degrees_in_front_of_b: Scalar,
         degrees: bool,
-    ) -> f64 {
+    ) -> Scalar {
         let angle_a = if degrees {
             angle_in_degrees_in_front_of_a * TO_RADIANS
         } else {
@@ -470,13 +497,13 @@ impl Vector {
     }
 
     /// Computes the angle between vector XY components in degrees.
-    pub fn angle_between_vector_xy_components(vector: &Vector) -> f64 {
+    pub fn angle_between_vector_xy_components(vector: &Vector) -> Scalar {
         vector._y.atan2(vector._x) * TO_DEGREES
     }
 
     /// Deprecated: use `angle_between_vector_xy_components`.
     #[allow(dead_code)]
-    pub fn angle_between_vector_xy_components_degrees(vector: &Vector) -> f64 {
+    pub fn angle_between_vector_xy_components_degrees(vector: &Vector) -> Scalar {
         Self::angle_between_vector_xy_components(vector)
     }
 
@@ -492,7 +519,7 @@ impl Vector {
     }
 
     /// Computes coordinate direction angles (alpha, beta, gamma) in degrees.
-    pub fn coordinate_direction_3angles(&self, degrees: bool) -> [f64; 3] {
+    pub fn coordinate_direction_3angles(&self, degrees: bool) -> [Scalar; 3] {
         let length = self.compute_length();
         if length < Tolerance::ZERO_TOLERANCE {
             return [0.0, 0.0, 0.0];
@@ -514,7 +541,7 @@ impl Vector {
     }
 
     /// Computes coordinate direction angles (phi, theta) in degrees.
-    pub fn coordinate_direction_2angles(&self, degrees: bool) -> [f64; 2] {
+    pub fn coordinate_direction_2angles(&self, degrees: bool) -> [Scalar; 2] {
         let length_xy = (self._x * self._x + self._y * self._y).sqrt();
         let length = self.compute_length();
 
@@ -537,7 +564,8 @@ impl Vector {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Serializes the Vector to a JSON string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
@@ -546,21 +574,24 @@ impl Vector {
     }
 
     /// Deserializes a Vector from a JSON string.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
     /// Serializes the Vector to a JSON file.
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
     }
 
     /// Deserializes a Vector from a JSON file.
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
     }
 }
 
@@ -572,7 +603,7 @@ impl Default for Vector {
 
 // Index trait for array-like access
 impl Index<usize> for Vector {
-    type Output = f64;
+    type Output = Scalar;
 
     fn index(&self, index: usize) -> &Self::Output {
         match index {
@@ -645,34 +676,34 @@ impl Sub for &Vector {
     }
 }
 
-impl Mul<f64> for Vector {
+impl Mul<Scalar> for Vector {
     type Output = Vector;
 
-    fn mul(self, scalar: f64) -> Vector {
+    fn mul(self, scalar: Scalar) -> Vector {
         Vector::new(self.x() * scalar, self.y() * scalar, self.z() * scalar)
     }
 }
 
-impl Mul<f64> for &Vector {
+impl Mul<Scalar> for &Vector {
     type Output = Vector;
 
-    fn mul(self, scalar: f64) -> Vector {
+    fn mul(self, scalar: Scalar) -> Vector {
         Vector::new(self.x() * scalar, self.y() * scalar, self.z() * scalar)
     }
 }
 
-impl Div<f64> for Vector {
+impl Div<Scalar> for Vector {
     type Output = Vector;
 
-    fn div(self, scalar: f64) -> Vector {
+    fn div(self, scalar: Scalar) -> Vector {
         Vector::new(self.x() / scalar, self.y() / scalar, self.z() / scalar)
     }
 }
 
-impl Div<f64> for &Vector {
+impl Div<Scalar> for &Vector {
     type Output = Vector;
 
-    fn div(self, scalar: f64) -> Vector {
+    fn div(self, scalar: Scalar) -> Vector {
         Vector::new(self.x() / scalar, self.y() / scalar, self.z() / scalar)
     }
 }
@@ -726,16 +757,16 @@ impl SubAssign<&Vector> for Vector {
     }
 }
 
-impl MulAssign<f64> for Vector {
-    fn mul_assign(&mut self, scalar: f64) {
+impl MulAssign<Scalar> for Vector {
+    fn mul_assign(&mut self, scalar: Scalar) {
         self.set_x(self.x() * scalar);
         self.set_y(self.y() * scalar);
         self.set_z(self.z() * scalar);
     }
 }
 
-impl DivAssign<f64> for Vector {
-    fn div_assign(&mut self, scalar: f64) {
+impl DivAssign<Scalar> for Vector {
+    fn div_assign(&mut self, scalar: Scalar) {
         self.set_x(self.x() / scalar);
         self.set_y(self.y() / scalar);
         self.set_z(self.z() / scalar);
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod vector_tests {
     use crate::encoders::{json_dump, json_load};
+    use crate::Scalar;
     use crate::Vector;
 
     #[test]
@@ -127,6 +128,7 @@ mod vector_tests {
     }
 
     #[test]
+    #[cfg_attr(feature = "f32", allow(clippy::excessive_precision))]
     fn test_vector_length() {
         let v = Vector::new(5.5697, -9.84, 1.587);
         let length = v.compute_length();
@@ -206,7 +208,10 @@ mod vector_tests {
     #[test]
     fn test_vector_get_leveled_vector() {
         let lev = Vector::new(1.0, 1.0, 1.0).get_leveled_vector(1.0);
-        assert!((lev.compute_length() - 4.168_432_5).abs() < 1e-4);
+        // The cosine of ~54.7 taken as radians amplifies rounding, so single
+        // precision only keeps three decimals
+        let tolerance = if cfg!(feature = "f32") { 1e-3 } else { 1e-4 };
+        assert!((lev.compute_length() - 4.168_432_5).abs() < tolerance);
     }
 
     #[test]
@@ -229,8 +234,8 @@ mod vector_tests {
 
     #[test]
     fn test_vector_angle_between_vector_xy_components() {
-        let v1 = Vector::new(3.0_f64.sqrt(), 1.0, 0.0);
-        let v2 = Vector::new(1.0, 3.0_f64.sqrt(), 0.0);
+        let v1 = Vector::new(Scalar::sqrt(3.0), 1.0, 0.0);
+        let v2 = Vector::new(1.0, Scalar::sqrt(3.0), 0.0);
         assert_eq!(
             (Vector::angle_between_vector_xy_components(&v1) * 100.0).round() / 100.0,
             30.0
@@ -259,8 +264,22 @@ mod vector_tests {
         assert!((abg[1] - 69.274_2).abs() < 1e-4);
         assert!((abg[2] - 30.032058).abs() < 1e-4);
 
-        let pt = Vector::new(1.0, 1.0, 2.0_f64.sqrt()).coordinate_direction_2angles(true);
+        let pt = Vector::new(1.0, 1.0, Scalar::sqrt(2.0)).coordinate_direction_2angles(true);
         assert!((pt[0] - 45.0).abs() < 1e-6);
         assert!((pt[1] - 45.0).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_vector_normalize_many() {
+        let mut vectors: Vec<Vector> = (0..10_000)
+            .map(|i| Vector::new(i as Scalar, 1.0, 2.0))
+            .collect();
+        vectors.push(Vector::new(0.0, 0.0, 0.0));
+        Vector::normalize_many(&mut vectors);
+        assert!(vectors[..10_000]
+            .iter()
+            .all(|v| (v.clone().magnitude() - 1.0).abs() < 1e-6));
+        // Zero vectors are left unchanged
+        assert_eq!(vectors[10_000].x(), 0.0);
+    }
 }
@@ -1,6 +1,6 @@
+use crate::error::SessionError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use uuid::Uuid;
 
 /// A graph vertex with a unique identifier and attribute string.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,7 +20,7 @@ impl Default for Vertex {
     fn default() -> Self {
         Self {
             name: "my_vertex".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             attribute: String::new(),
             index: -1,
         }
@@ -52,12 +52,12 @@ impl Vertex {
     ///////////////////////////////////////////////////////////////////////////////////////////
 
     /// Convert the Vertex to a JSON-serializable string.
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
 
     /// Create Vertex from JSON string data.
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 }
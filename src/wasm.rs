@@ -0,0 +1,160 @@
+use crate::scalar::{to_f32, to_f64};
+use crate::{BufferLayout, Geometry, Mesh, Point, Scalar, Session, Vector};
+use js_sys::{Float32Array, Uint32Array};
+use wasm_bindgen::prelude::*;
+
+/// A Session for JavaScript, exported as `Session`.
+#[wasm_bindgen(js_name = Session)]
+pub struct WasmSession {
+    inner: Session,
+}
+
+/// An object hit by `Session.rayCast`.
+#[wasm_bindgen(js_name = RayHit, getter_with_clone)]
+pub struct WasmRayHit {
+    pub guid: String,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub distance: f64,
+}
+
+/// Triangulated mesh arrays ready for a Three.js `BufferGeometry`.
+#[wasm_bindgen(js_name = MeshBuffers)]
+pub struct WasmMeshBuffers {
+    positions: Vec<f32>,
+    normals: Vec<f32>,
+    colors: Vec<f32>,
+    indices: Vec<u32>,
+    matrix: [f32; 16],
+}
+
+#[wasm_bindgen(js_class = MeshBuffers)]
+impl WasmMeshBuffers {
+    /// Vertex positions `x y z`, in the local coordinates of the mesh.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Float32Array {
+        Float32Array::from(&self.positions[..])
+    }
+
+    /// Vertex normals `x y z`, one per position.
+    #[wasm_bindgen(getter)]
+    pub fn normals(&self) -> Float32Array {
+        Float32Array::from(&self.normals[..])
+    }
+
+    /// Vertex colors `r g b a` in 0-1, one per position.
+    #[wasm_bindgen(getter)]
+    pub fn colors(&self) -> Float32Array {
+        Float32Array::from(&self.colors[..])
+    }
+
+    /// Three vertex indices per triangle.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> Uint32Array {
+        Uint32Array::from(&self.indices[..])
+    }
+
+    /// The mesh xform in column-major order, as read by `Matrix4.fromArray`.
+    #[wasm_bindgen(getter)]
+    pub fn matrix(&self) -> Float32Array {
+        Float32Array::from(&self.matrix[..])
+    }
+}
+
+#[wasm_bindgen(js_class = Session)]
+impl WasmSession {
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: &str) -> WasmSession {
+        WasmSession {
+            inner: Session::new(name),
+        }
+    }
+
+    /// Load a Session from a JSON string written by `jsondump`.
+    pub fn jsonload(json: &str) -> Result<WasmSession, JsError> {
+        Ok(WasmSession {
+            inner: Session::jsonload(json)?,
+        })
+    }
+
+    pub fn jsondump(&self) -> Result<String, JsError> {
+        Ok(self.inner.jsondump()?)
+    }
+
+    /// Load a Session from MessagePack bytes written by `binarydump`.
+    pub fn binaryload(data: &[u8]) -> Result<WasmSession, JsError> {
+        Ok(WasmSession {
+            inner: Session::binaryload(data)?,
+        })
+    }
+
+    pub fn binarydump(&self) -> Result<Vec<u8>, JsError> {
+        Ok(self.inner.binarydump()?)
+    }
+
+    /// The GUIDs of all meshes, in insertion order.
+    #[wasm_bindgen(js_name = meshGuids)]
+    pub fn mesh_guids(&self) -> Vec<String> {
+        self.inner
+            .objects_of::<Mesh>()
+            .into_iter()
+            .map(|mesh| mesh.guid.clone())
+            .collect()
+    }
+
+    /// Triangulated buffers of a mesh, or undefined for unknown GUIDs and other geometry.
+    #[wasm_bindgen(js_name = meshBuffers)]
+    pub fn mesh_buffers(&self, guid: &str) -> Option<WasmMeshBuffers> {
+        match self.inner.get_object(guid)? {
+            Geometry::Mesh(mesh) => Some(tessellate(mesh)),
+            _ => None,
+        }
+    }
+
+    /// Cast a ray and return the hits, closest first.
+    #[wasm_bindgen(js_name = rayCast)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn ray_cast(
+        &mut self,
+        ox: f64,
+        oy: f64,
+        oz: f64,
+        dx: f64,
+        dy: f64,
+        dz: f64,
+        tolerance: f64,
+    ) -> Vec<WasmRayHit> {
+        self.inner
+            .ray_cast(
+                &Point::new(ox as Scalar, oy as Scalar, oz as Scalar),
+                &Vector::new(dx as Scalar, dy as Scalar, dz as Scalar),
+                tolerance as Scalar,
+            )
+            .into_iter()
+            .map(|hit| WasmRayHit {
+                x: to_f64(hit.point.x()),
+                y: to_f64(hit.point.y()),
+                z: to_f64(hit.point.z()),
+                distance: to_f64(hit.distance),
+                guid: hit.guid,
+            })
+            .collect()
+    }
+}
+
+/// Fan-triangulate the faces of a mesh into flat vertex and index arrays.
+fn tessellate(mesh: &Mesh) -> WasmMeshBuffers {
+    let buffers = mesh.to_buffers(BufferLayout::Separate);
+    WasmMeshBuffers {
+        positions: buffers.positions,
+        normals: buffers.normals,
+        colors: buffers.colors,
+        indices: buffers.indices,
+        matrix: mesh.xform.m.map(to_f32),
+    }
+}
+
+#[cfg(test)]
+#[path = "wasm_test.rs"]
+mod wasm_test;
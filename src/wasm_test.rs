@@ -0,0 +1,40 @@
+use crate::wasm::{tessellate, WasmSession};
+use crate::{Mesh, Point, Session, Xform};
+
+fn quad() -> Mesh {
+    Mesh::from_polygons(
+        vec![vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        ]],
+        None,
+    )
+}
+
+#[test]
+fn test_tessellate_mesh() {
+    let mut mesh = quad();
+    mesh.xform = Xform::translation(0.0, 0.0, 2.0);
+    let buffers = tessellate(&mesh);
+    assert_eq!(buffers.positions.len(), 12);
+    assert_eq!(buffers.indices, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(&buffers.normals[..3], &[0.0, 0.0, 1.0]);
+    assert_eq!(buffers.matrix[14], 2.0);
+}
+
+#[test]
+fn test_wasm_session_json_and_ray_cast() {
+    let mut session = Session::new("viewer");
+    let guid = session.add_mesh(quad()).name();
+    let mut wasm = WasmSession::jsonload(&session.jsondump().unwrap()).unwrap();
+    assert_eq!(wasm.mesh_guids(), vec![guid.clone()]);
+    assert!(wasm.mesh_buffers(&guid).is_some());
+    assert!(wasm.mesh_buffers("missing").is_none());
+
+    let hits = wasm.ray_cast(0.5, 0.5, 5.0, 0.0, 0.0, -1.0, 1e-3);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].guid, guid);
+    assert!((hits[0].distance - 5.0).abs() < 1e-9);
+}
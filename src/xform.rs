@@ -1,8 +1,13 @@
-use crate::{Point, Vector};
-use serde::{ser::Serialize as SerTrait, Deserialize, Serialize};
-use std::fmt;
-use std::ops::{Index, IndexMut, Mul, MulAssign};
-use uuid::Uuid;
+#[cfg(feature = "std")]
+use crate::error::SessionError;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{Point, Quaternion, Scalar, Tolerance, Vector};
+use core::fmt;
+use core::ops::{Index, IndexMut, Mul, MulAssign};
+#[cfg(feature = "std")]
+use serde::ser::Serialize as SerTrait;
+use serde::{Deserialize, Serialize};
 
 /// A 4x4 column-major transformation matrix in 3D space
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -13,7 +18,7 @@ pub struct Xform {
     pub guid: String,
     pub name: String,
     /// The matrix elements stored in column-major order as a flattened array
-    pub m: [f64; 16],
+    pub m: [Scalar; 16],
 }
 
 impl Xform {
@@ -25,10 +30,10 @@ impl Xform {
         Self::identity()
     }
 
-    pub fn from_matrix(matrix: [f64; 16]) -> Self {
+    pub fn from_matrix(matrix: [Scalar; 16]) -> Self {
         Xform {
             typ: "Xform".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_xform".to_string(),
             m: matrix,
         }
@@ -37,7 +42,7 @@ impl Xform {
     pub fn identity() -> Self {
         let mut xform = Xform {
             typ: "Xform".to_string(),
-            guid: Uuid::new_v4().to_string(),
+            guid: crate::guid::new_guid(),
             name: "my_xform".to_string(),
             m: [0.0; 16],
         };
@@ -66,7 +71,7 @@ impl Xform {
     // Transformations
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    pub fn translation(x: f64, y: f64, z: f64) -> Self {
+    pub fn translation(x: Scalar, y: Scalar, z: Scalar) -> Self {
         let mut xform = Self::identity();
         xform.m[12] = x;
         xform.m[13] = y;
@@ -74,7 +79,7 @@ impl Xform {
         xform
     }
 
-    pub fn scaling(x: f64, y: f64, z: f64) -> Self {
+    pub fn scaling(x: Scalar, y: Scalar, z: Scalar) -> Self {
         let mut xform = Self::identity();
         xform.m[0] = x;
         xform.m[5] = y;
@@ -82,7 +87,7 @@ impl Xform {
         xform
     }
 
-    pub fn rotation_x(angle_radians: f64) -> Self {
+    pub fn rotation_x(angle_radians: Scalar) -> Self {
         let mut xform = Self::identity();
 
         let cos_angle = angle_radians.cos();
@@ -96,7 +101,7 @@ impl Xform {
         xform
     }
 
-    pub fn rotation_y(angle_radians: f64) -> Self {
+    pub fn rotation_y(angle_radians: Scalar) -> Self {
         let mut xform = Self::identity();
 
         let cos_angle = angle_radians.cos();
@@ -110,7 +115,7 @@ impl Xform {
         xform
     }
 
-    pub fn rotation_z(angle_radians: f64) -> Self {
+    pub fn rotation_z(angle_radians: Scalar) -> Self {
         let mut xform = Self::identity();
         let cos_angle = angle_radians.cos();
         let sin_angle = angle_radians.sin();
@@ -123,7 +128,7 @@ impl Xform {
         xform
     }
 
-    pub fn rotation(axis: &Vector, angle_radians: f64) -> Self {
+    pub fn rotation(axis: &Vector, angle_radians: Scalar) -> Self {
         let axis = axis.normalize();
 
         let mut xform = Self::identity();
@@ -284,16 +289,204 @@ impl Xform {
     }
 
     pub fn transform_point(&self, point: &mut Point) {
+        [point[0], point[1], point[2]] = self.apply_to_point([point[0], point[1], point[2]]);
+    }
+
+    /// Transform `[x, y, z]` coordinates as a point, dividing by w for projective xforms.
+    #[inline]
+    pub(crate) fn apply_to_point(&self, [x, y, z]: [Scalar; 3]) -> [Scalar; 3] {
         let m = &self.m;
-        let x = point[0];
-        let y = point[1];
-        let z = point[2];
         let w = m[3] * x + m[7] * y + m[11] * z + m[15];
         let w_inv = if w.abs() > 1e-10 { 1.0 / w } else { 1.0 };
 
-        point[0] = (m[0] * x + m[4] * y + m[8] * z + m[12]) * w_inv;
-        point[1] = (m[1] * x + m[5] * y + m[9] * z + m[13]) * w_inv;
-        point[2] = (m[2] * x + m[6] * y + m[10] * z + m[14]) * w_inv;
+        [
+            (m[0] * x + m[4] * y + m[8] * z + m[12]) * w_inv,
+            (m[1] * x + m[5] * y + m[9] * z + m[13]) * w_inv,
+            (m[2] * x + m[6] * y + m[10] * z + m[14]) * w_inv,
+        ]
+    }
+
+    /// True if the last row is (0, 0, 0, 1), so points need no division by w.
+    pub fn is_affine(&self) -> bool {
+        let m = &self.m;
+        m[3] == 0.0 && m[7] == 0.0 && m[11] == 0.0 && m[15] == 1.0
+    }
+
+    /// True for an affine xform that only rotates and translates.
+    ///
+    /// The axes must be orthonormal and right-handed within `Tolerance::RELATIVE`.
+    pub fn is_rigid(&self) -> bool {
+        if !self.is_affine() {
+            return false;
+        }
+        let m = &self.m;
+        let dot = |a: usize, b: usize| m[a] * m[b] + m[a + 1] * m[b + 1] + m[a + 2] * m[b + 2];
+        let orthonormal = [
+            (0, 0, 1.0),
+            (4, 4, 1.0),
+            (8, 8, 1.0),
+            (0, 4, 0.0),
+            (0, 8, 0.0),
+            (4, 8, 0.0),
+        ]
+        .iter()
+        .all(|&(a, b, expected)| (dot(a, b) - expected).abs() <= Tolerance::RELATIVE);
+        orthonormal && self.determinant() > 0.0
+    }
+
+    /// Determinant of the full 4x4 matrix.
+    pub fn determinant(&self) -> Scalar {
+        let mut rows = self.rows();
+        let mut det = 1.0;
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))
+                .unwrap_or(col);
+            if rows[pivot][col] == 0.0 {
+                return 0.0;
+            }
+            if pivot != col {
+                rows.swap(pivot, col);
+                det = -det;
+            }
+            det *= rows[col][col];
+            for row in col + 1..4 {
+                let factor = rows[row][col] / rows[col][col];
+                let pivot_row = rows[col];
+                for (value, pivot_value) in rows[row].iter_mut().zip(pivot_row).skip(col) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+        det
+    }
+
+    /// Inverse of the full 4x4 matrix, including projective xforms.
+    ///
+    /// `inverse` assumes the last row is (0, 0, 0, 1); use this for matrices
+    /// loaded from other software that may carry a perspective row.
+    ///
+    /// # Returns
+    /// None if the matrix is singular.
+    pub fn inverse_full(&self) -> Option<Xform> {
+        // Gauss-Jordan elimination with partial pivoting on [A | I]
+        let mut rows = self.rows();
+        let mut inverse = Xform::identity().rows();
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))
+                .unwrap_or(col);
+            if rows[pivot][col].abs() < Tolerance::ZERO_TOLERANCE {
+                return None;
+            }
+            rows.swap(pivot, col);
+            inverse.swap(pivot, col);
+            let scale = 1.0 / rows[col][col];
+            rows[col] = rows[col].map(|v| v * scale);
+            inverse[col] = inverse[col].map(|v| v * scale);
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = rows[row][col];
+                for k in 0..4 {
+                    rows[row][k] -= factor * rows[col][k];
+                    inverse[row][k] -= factor * inverse[col][k];
+                }
+            }
+        }
+        let mut result = Xform::identity();
+        for (row, values) in inverse.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                result[(row, col)] = *value;
+            }
+        }
+        Some(result)
+    }
+
+    /// Split an affine xform into translation, rotation, scale and shear.
+    ///
+    /// The xform is `T * R * S * H` with `S` the diagonal scale and `H` the
+    /// unit upper triangular shear with `(xy, xz, yz)` above the diagonal.
+    /// Mirroring shows up as a negative z scale.
+    ///
+    /// # Returns
+    /// `(translation, rotation, scale, shear)`, or None for projective or
+    /// singular xforms.
+    pub fn decompose(&self) -> Option<(Vector, Quaternion, Vector, Vector)> {
+        if !self.is_affine() {
+            return None;
+        }
+        let m = &self.m;
+        let column = |c: usize| [m[c], m[c + 1], m[c + 2]];
+        let dot = |a: [Scalar; 3], b: [Scalar; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let minus = |a: [Scalar; 3], b: [Scalar; 3], t: Scalar| [0, 1, 2].map(|i| a[i] - b[i] * t);
+        let unit = |a: [Scalar; 3]| {
+            let length = dot(a, a).sqrt();
+            (length > Tolerance::ZERO_TOLERANCE).then(|| (a.map(|v| v / length), length))
+        };
+
+        // Gram-Schmidt on the columns, the projections are the shear
+        let (c0, c1, c2) = (column(0), column(4), column(8));
+        let (r0, sx) = unit(c0)?;
+        let (r1, sy) = unit(minus(c1, r0, dot(c1, r0)))?;
+        let (mut r2, mut sz) = unit(minus(minus(c2, r0, dot(c2, r0)), r1, dot(c2, r1)))?;
+        let shear = Vector::new(dot(c1, r0) / sx, dot(c2, r0) / sx, dot(c2, r1) / sy);
+
+        let cross = [
+            r0[1] * r1[2] - r0[2] * r1[1],
+            r0[2] * r1[0] - r0[0] * r1[2],
+            r0[0] * r1[1] - r0[1] * r1[0],
+        ];
+        if dot(cross, r2) < 0.0 {
+            r2 = r2.map(|v| -v);
+            sz = -sz;
+        }
+        let rotation = Quaternion::from_matrix([0, 1, 2].map(|row| [r0[row], r1[row], r2[row]]));
+        Some((
+            Vector::new(m[12], m[13], m[14]),
+            rotation,
+            Vector::new(sx, sy, sz),
+            shear,
+        ))
+    }
+
+    /// Build an xform from the parts returned by `decompose`.
+    ///
+    /// # Arguments
+    /// * `translation` - Translation `T`
+    /// * `rotation` - Rotation `R`, normalized before use
+    /// * `scale` - Diagonal of `S`
+    /// * `shear` - `(xy, xz, yz)` above the diagonal of `H`
+    pub fn compose(
+        translation: &Vector,
+        rotation: &Quaternion,
+        scale: &Vector,
+        shear: &Vector,
+    ) -> Self {
+        let r = rotation.normalize().to_matrix();
+        let (sx, sy, sz) = (scale.x(), scale.y(), scale.z());
+        // Columns of S * H
+        let sh = [
+            [sx, 0.0, 0.0],
+            [sx * shear.x(), sy, 0.0],
+            [sx * shear.y(), sy * shear.z(), sz],
+        ];
+        let mut xform = Self::identity();
+        for (col, values) in sh.iter().enumerate() {
+            for (row, r_row) in r.iter().enumerate() {
+                xform.m[col * 4 + row] =
+                    r_row[0] * values[0] + r_row[1] * values[1] + r_row[2] * values[2];
+            }
+        }
+        xform.m[12] = translation.x();
+        xform.m[13] = translation.y();
+        xform.m[14] = translation.z();
+        xform
+    }
+
+    fn rows(&self) -> [[Scalar; 4]; 4] {
+        [0, 1, 2, 3].map(|row| [0, 1, 2, 3].map(|col| self[(row, col)]))
     }
 
     pub fn transform_vector(&self, vector: &mut Vector) {
@@ -329,7 +522,7 @@ impl Xform {
         true
     }
 
-    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::too_many_arguments, clippy::needless_range_loop)]
     pub fn change_basis_alt(
         origin_1: &Point,
         x_axis_1: &Vector,
@@ -572,7 +765,7 @@ impl Xform {
         &t * &f
     }
 
-    pub fn scale_xyz(scale_x: f64, scale_y: f64, scale_z: f64) -> Self {
+    pub fn scale_xyz(scale_x: Scalar, scale_y: Scalar, scale_z: Scalar) -> Self {
         let mut xform = Self::identity();
         xform.m[0] = scale_x;
         xform.m[5] = scale_y;
@@ -580,21 +773,26 @@ impl Xform {
         xform
     }
 
-    pub fn scale_uniform(origin: &Point, scale_value: f64) -> Self {
+    pub fn scale_uniform(origin: &Point, scale_value: Scalar) -> Self {
         let t0 = Self::translation(-origin.x(), -origin.y(), -origin.z());
         let t1 = Self::scaling(scale_value, scale_value, scale_value);
         let t2 = Self::translation(origin.x(), origin.y(), origin.z());
         &t2 * &(&t1 * &t0)
     }
 
-    pub fn scale_non_uniform(origin: &Point, scale_x: f64, scale_y: f64, scale_z: f64) -> Self {
+    pub fn scale_non_uniform(
+        origin: &Point,
+        scale_x: Scalar,
+        scale_y: Scalar,
+        scale_z: Scalar,
+    ) -> Self {
         let t0 = Self::translation(-origin.x(), -origin.y(), -origin.z());
         let t1 = Self::scale_xyz(scale_x, scale_y, scale_z);
         let t2 = Self::translation(origin.x(), origin.y(), origin.z());
         &t2 * &(&t1 * &t0)
     }
 
-    pub fn axis_rotation(angle: f64, axis: &Vector) -> Self {
+    pub fn axis_rotation(angle: Scalar, axis: &Vector) -> Self {
         let c = angle.cos();
         let s = angle.sin();
         let ux = axis.x();
@@ -618,11 +816,87 @@ impl Xform {
         xform
     }
 
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    // Rotation and Translation
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// Rigid xform that rotates and then translates.
+    pub fn from_quaternion_translation(rotation: &Quaternion, translation: &Vector) -> Self {
+        let r = rotation.normalize().to_matrix();
+        let mut xform = Self::identity();
+        for (row, values) in r.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                xform.m[col * 4 + row] = *value;
+            }
+        }
+        xform.m[12] = translation.x();
+        xform.m[13] = translation.y();
+        xform.m[14] = translation.z();
+        xform
+    }
+
+    /// Split an xform into its rotation and translation.
+    ///
+    /// Scale is removed by normalizing the axes, so the result is exact for
+    /// rotations combined with scaling but not for shear or mirroring.
+    /// Interpolate the parts with `Quaternion::slerp` to animate between poses.
+    pub fn to_quaternion_translation(&self) -> (Quaternion, Vector) {
+        let m = &self.m;
+        let translation = Vector::new(m[12], m[13], m[14]);
+        let axes = [0, 4, 8].map(|c| {
+            let length = (m[c] * m[c] + m[c + 1] * m[c + 1] + m[c + 2] * m[c + 2]).sqrt();
+            let inverse = if length > 0.0 { 1.0 / length } else { 0.0 };
+            [m[c] * inverse, m[c + 1] * inverse, m[c + 2] * inverse]
+        });
+        if axes.iter().any(|axis| axis == &[0.0; 3]) {
+            return (Quaternion::identity(), translation);
+        }
+        let rows = [0, 1, 2].map(|row| [axes[0][row], axes[1][row], axes[2][row]]);
+        (Quaternion::from_matrix(rows), translation)
+    }
+
+    /// Interpolate between two poses.
+    ///
+    /// Both xforms are decomposed, translation, scale and shear are
+    /// interpolated linearly and the rotation along the shorter arc, so a
+    /// rotating object keeps its shape. Xforms that cannot be decomposed,
+    /// such as projective ones, are interpolated element by element.
+    ///
+    /// # Arguments
+    /// * `a` - Pose at `t = 0`
+    /// * `b` - Pose at `t = 1`
+    /// * `t` - Interpolation parameter
+    pub fn lerp(a: &Xform, b: &Xform, t: Scalar) -> Xform {
+        let mix = |p: &Vector, q: &Vector| {
+            Vector::new(
+                p.x() + (q.x() - p.x()) * t,
+                p.y() + (q.y() - p.y()) * t,
+                p.z() + (q.z() - p.z()) * t,
+            )
+        };
+        match (a.decompose(), b.decompose()) {
+            (Some((ta, ra, sa, ha)), Some((tb, rb, sb, hb))) => Self::compose(
+                &mix(&ta, &tb),
+                &Quaternion::slerp(&ra, &rb, t),
+                &mix(&sa, &sb),
+                &mix(&ha, &hb),
+            ),
+            _ => {
+                let mut xform = Self::identity();
+                for (value, (p, q)) in xform.m.iter_mut().zip(a.m.iter().zip(&b.m)) {
+                    *value = p + (q - p) * t;
+                }
+                xform
+            }
+        }
+    }
+
     ///////////////////////////////////////////////////////////////////////////////////////////
     // JSON
     ///////////////////////////////////////////////////////////////////////////////////////////
 
-    pub fn jsondump(&self) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsondump(&self) -> Result<String, SessionError> {
         let mut buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
@@ -630,19 +904,22 @@ impl Xform {
         Ok(String::from_utf8(buf)?)
     }
 
-    pub fn jsonload(json_data: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn jsonload(json_data: &str) -> Result<Self, SessionError> {
         Ok(serde_json::from_str(json_data)?)
     }
 
-    pub fn to_json(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, filepath: &str) -> Result<(), SessionError> {
         let json = self.jsondump()?;
         std::fs::write(filepath, json)?;
         Ok(())
     }
 
-    pub fn from_json(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    #[cfg(feature = "std")]
+    pub fn from_json(filepath: &str) -> Result<Self, SessionError> {
         let json = std::fs::read_to_string(filepath)?;
-        Self::jsonload(&json)
+        Self::jsonload(&json).map_err(|e| e.with_path(filepath))
     }
 }
 
@@ -682,7 +959,7 @@ impl Default for Xform {
 
 // Implement Index trait for accessing matrix elements with [(row, col)] syntax
 impl Index<(usize, usize)> for Xform {
-    type Output = f64;
+    type Output = Scalar;
 
     fn index(&self, idx: (usize, usize)) -> &Self::Output {
         let (row, col) = idx;
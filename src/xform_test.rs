@@ -1,9 +1,10 @@
 #[cfg(test)]
 mod xform_tests {
     use crate::encoders::{json_dump, json_load};
-    use crate::{Point, Vector, Xform};
+    use crate::Scalar;
+    use crate::{Point, Quaternion, Vector, Xform};
 
-    fn approx_f32(a: f64, b: f64) -> bool {
+    fn approx_f32(a: Scalar, b: Scalar) -> bool {
         (a - b).abs() < 1e-5
     }
 
@@ -69,7 +70,7 @@ mod xform_tests {
 
     #[test]
     fn test_xform_rotation_z() {
-        let r = Xform::rotation_z(std::f64::consts::FRAC_PI_2);
+        let r = Xform::rotation_z(crate::scalar::consts::FRAC_PI_2);
         let p = Point::new(1.0, 0.0, 0.0);
         let rp = r.transformed_point(&p);
         assert!(approx_f32(rp.x(), 0.0));
@@ -80,8 +81,8 @@ mod xform_tests {
     #[test]
     fn test_xform_axis_rotation() {
         let axis = Vector::new(0.0, 0.0, 1.0);
-        let r1 = Xform::rotation_z(std::f64::consts::FRAC_PI_2);
-        let r2 = Xform::axis_rotation(std::f64::consts::FRAC_PI_2, &axis);
+        let r1 = Xform::rotation_z(crate::scalar::consts::FRAC_PI_2);
+        let r2 = Xform::axis_rotation(crate::scalar::consts::FRAC_PI_2, &axis);
         let p = Point::new(1.0, 0.0, 0.0);
         let p1 = r1.transformed_point(&p);
         let p2 = r2.transformed_point(&p);
@@ -184,7 +185,7 @@ mod xform_tests {
 
     #[test]
     fn test_xform_rotation_x() {
-        let r = Xform::rotation_x(std::f64::consts::FRAC_PI_2);
+        let r = Xform::rotation_x(crate::scalar::consts::FRAC_PI_2);
         let p = Point::new(0.0, 1.0, 0.0);
         let rp = r.transformed_point(&p);
         assert!(approx_f32(rp.x(), 0.0));
@@ -194,7 +195,7 @@ mod xform_tests {
 
     #[test]
     fn test_xform_rotation_y() {
-        let r = Xform::rotation_y(std::f64::consts::FRAC_PI_2);
+        let r = Xform::rotation_y(crate::scalar::consts::FRAC_PI_2);
         let p = Point::new(1.0, 0.0, 0.0);
         let rp = r.transformed_point(&p);
         assert!(approx_f32(rp.x(), 0.0));
@@ -205,7 +206,7 @@ mod xform_tests {
     #[test]
     fn test_xform_rotation() {
         let axis = Vector::new(0.0, 0.0, 1.0);
-        let r = Xform::rotation(&axis, std::f64::consts::FRAC_PI_2);
+        let r = Xform::rotation(&axis, crate::scalar::consts::FRAC_PI_2);
         let p = Point::new(1.0, 0.0, 0.0);
         let rp = r.transformed_point(&p);
         assert!(approx_f32(rp.x(), 0.0));
@@ -402,4 +403,124 @@ mod xform_tests {
         assert_eq!(x[(2, 0)], 3.0);
         assert_eq!(x[(3, 3)], 1.0);
     }
+
+    #[test]
+    fn test_xform_quaternion_translation() {
+        let rotation = Quaternion::from_axis_angle(Vector::new(1.0, 1.0, 0.0), 0.8);
+        let translation = Vector::new(1.0, -2.0, 3.0);
+        let xform = Xform::from_quaternion_translation(&rotation, &translation);
+        let expected = &Xform::translation(1.0, -2.0, 3.0)
+            * &Xform::rotation(&Vector::new(1.0, 1.0, 0.0), 0.8);
+        assert!(matrices_close(&xform, &expected));
+
+        // Scale is dropped from the rotation
+        let scaled = &xform * &Xform::scaling(2.0, 3.0, 4.0);
+        let (q, t) = scaled.to_quaternion_translation();
+        assert!(approx_f32(q.dot(&rotation).abs(), 1.0));
+        assert_eq!((t.x(), t.y(), t.z()), (1.0, -2.0, 3.0));
+
+        let (q, _) = Xform::scaling(0.0, 1.0, 1.0).to_quaternion_translation();
+        assert_eq!(q.s, 1.0);
+    }
+
+    #[test]
+    fn test_xform_determinant_and_inverse_full() {
+        assert!(approx_f32(Xform::identity().determinant(), 1.0));
+        assert!(approx_f32(
+            Xform::scaling(2.0, 3.0, 4.0).determinant(),
+            24.0
+        ));
+        assert!(approx_f32(
+            Xform::scaling(-1.0, 1.0, 1.0).determinant(),
+            -1.0
+        ));
+        assert_eq!(Xform::scaling(0.0, 1.0, 1.0).determinant(), 0.0);
+
+        // A perspective row that the affine inverse ignores
+        let mut projective = &Xform::translation(1.0, 2.0, 3.0) * &Xform::rotation_z(0.3);
+        projective.m[3] = 0.1;
+        projective.m[11] = -0.2;
+        let inverse = projective.inverse_full().unwrap();
+        assert!(matrices_close(
+            &(&projective * &inverse),
+            &Xform::identity()
+        ));
+        assert!(approx_f32(
+            projective.determinant() * inverse.determinant(),
+            1.0
+        ));
+        assert!(!matrices_close(
+            &(&projective * &projective.inverse().unwrap()),
+            &Xform::identity()
+        ));
+        assert!(Xform::scaling(1.0, 0.0, 1.0).inverse_full().is_none());
+    }
+
+    #[test]
+    fn test_xform_is_rigid() {
+        let rigid =
+            &Xform::translation(1.0, 2.0, 3.0) * &Xform::rotation(&Vector::new(1.0, 2.0, 3.0), 0.7);
+        assert!(rigid.is_rigid());
+        assert!(!Xform::scaling(1.0, 1.0, 2.0).is_rigid());
+        assert!(!Xform::scaling(1.0, 1.0, -1.0).is_rigid());
+        let mut projective = Xform::identity();
+        projective.m[3] = 0.5;
+        assert!(!projective.is_rigid());
+    }
+
+    #[test]
+    fn test_xform_decompose() {
+        let rotation = Quaternion::from_axis_angle(Vector::new(0.0, 1.0, 1.0), 0.9);
+        let mut shear = Xform::identity();
+        shear[(0, 1)] = 0.5;
+        shear[(0, 2)] = -0.25;
+        shear[(1, 2)] = 0.75;
+        let xform = &(&Xform::from_quaternion_translation(&rotation, &Vector::new(1.0, 2.0, 3.0))
+            * &Xform::scaling(2.0, 3.0, 4.0))
+            * &shear;
+
+        let (t, q, scale, h) = xform.decompose().unwrap();
+        assert_eq!((t.x(), t.y(), t.z()), (1.0, 2.0, 3.0));
+        assert!(approx_f32(q.dot(&rotation).abs(), 1.0));
+        assert!(
+            approx_f32(scale.x(), 2.0) && approx_f32(scale.y(), 3.0) && approx_f32(scale.z(), 4.0)
+        );
+        assert!(approx_f32(h.x(), 0.5) && approx_f32(h.y(), -0.25) && approx_f32(h.z(), 0.75));
+
+        // Mirroring becomes a negative z scale with a proper rotation
+        let mirrored = &Xform::rotation_z(0.4) * &Xform::scaling(1.0, -1.0, 1.0);
+        let (_, q, scale, _) = mirrored.decompose().unwrap();
+        assert!(approx_f32(scale.z(), -1.0));
+        let rebuilt = &Xform::from_quaternion_translation(&q, &Vector::new(0.0, 0.0, 0.0))
+            * &Xform::scaling(scale.x(), scale.y(), scale.z());
+        assert!(matrices_close(&rebuilt, &mirrored));
+
+        assert!(Xform::scaling(1.0, 0.0, 1.0).decompose().is_none());
+    }
+
+    #[test]
+    fn test_xform_compose_and_lerp() {
+        let rotation = Quaternion::from_axis_angle(Vector::new(1.0, 0.0, 1.0), 1.2);
+        let (t, s, h) = (
+            Vector::new(1.0, -2.0, 3.0),
+            Vector::new(2.0, 0.5, -1.0),
+            Vector::new(0.1, 0.2, 0.3),
+        );
+        let xform = Xform::compose(&t, &rotation, &s, &h);
+        let (t2, q2, s2, h2) = xform.decompose().unwrap();
+        assert!(matrices_close(&Xform::compose(&t2, &q2, &s2, &h2), &xform));
+
+        let a = Xform::translation(0.0, 0.0, 0.0);
+        let b = &Xform::translation(2.0, 0.0, 0.0) * &Xform::rotation_z(1.0);
+        assert!(matrices_close(&Xform::lerp(&a, &b, 0.0), &a));
+        assert!(matrices_close(&Xform::lerp(&a, &b, 1.0), &b));
+        let mid = &Xform::translation(1.0, 0.0, 0.0) * &Xform::rotation_z(0.5);
+        assert!(matrices_close(&Xform::lerp(&a, &b, 0.5), &mid));
+        // Interpolated rotations stay rigid instead of shrinking
+        assert!(Xform::lerp(&a, &b, 0.3).is_rigid());
+
+        let mut projective = Xform::identity();
+        projective.m[3] = 1.0;
+        assert!(approx_f32(Xform::lerp(&a, &projective, 0.5).m[3], 0.5));
+    }
 }